@@ -10,7 +10,6 @@ use halo2_proofs::{
 };
 use itertools::Itertools;
 use rand::Rng;
-use std::{env, fs::File};
 
 #[cfg(not(feature = "disable_proof_aggregation"))]
 use snark_verifier::loader::halo2::halo2_ecc::halo2_base;
@@ -34,7 +33,7 @@ use zkevm_circuits::util::Challenges;
 
 use crate::{
     batch::BatchHash,
-    constants::{ACC_LEN, BITS, DIGEST_LEN, LIMBS, MAX_AGG_SNARKS},
+    constants::{ACC_LEN, BITS, DIGEST_LEN, LIMBS},
     core::{assign_batch_hashes, extract_accumulators_and_proof},
     util::parse_hash_digest_cells,
     ConfigParams,
@@ -47,7 +46,8 @@ use super::AggregationConfig;
 pub struct AggregationCircuit {
     pub svk: KzgSuccinctVerifyingKey<G1Affine>,
     // the input snarks for the aggregation circuit
-    // it is padded already so it will have a fixed length of MAX_AGG_SNARKS
+    // it is padded already so it will have a fixed length of max_agg_snarks,
+    // which is the number of chunks of the batch with padding
     pub snarks_with_padding: Vec<SnarkWitness>,
     // the public instance for this circuit consists of
     // - an accumulator (12 elements)
@@ -61,6 +61,9 @@ pub struct AggregationCircuit {
 }
 
 impl AggregationCircuit {
+    /// Build an aggregation circuit for the snarks of a batch. The max number
+    /// of snarks of the circuit is the number of snarks with padding, which
+    /// must match the number of chunks of the batch with padding.
     pub fn new(
         params: &ParamsKZG<Bn256>,
         snarks_with_padding: &[Snark],
@@ -69,6 +72,12 @@ impl AggregationCircuit {
     ) -> Result<Self, snark_verifier::Error> {
         let timer = start_timer!(|| "generate aggregation circuit");
 
+        assert_eq!(
+            snarks_with_padding.len(),
+            batch_hash.max_agg_snarks(),
+            "number of snarks does not match the number of chunks"
+        );
+
         // sanity check: snarks's public input matches chunk_hashes
         for (chunk, snark) in batch_hash
             .chunks_with_padding
@@ -154,15 +163,7 @@ impl Circuit<Fr> for AggregationCircuit {
     }
 
     fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
-        let params = env::var("AGGREGATION_CONFIG").map_or_else(
-            |_| ConfigParams::aggregation_param(),
-            |path| {
-                serde_json::from_reader(
-                    File::open(path.as_str()).unwrap_or_else(|_| panic!("{path:?} does not exist")),
-                )
-                .unwrap()
-            },
-        );
+        let params = ConfigParams::aggregation_param_from_env("AGGREGATION_CONFIG");
 
         let challenges = Challenges::construct(meta);
         let config = AggregationConfig::configure(meta, &params, challenges);
@@ -259,7 +260,10 @@ impl Circuit<Fr> for AggregationCircuit {
                 },
             )?;
 
-            assert_eq!(snark_inputs.len(), MAX_AGG_SNARKS * DIGEST_LEN);
+            assert_eq!(
+                snark_inputs.len(),
+                self.batch_hash.max_agg_snarks() * DIGEST_LEN
+            );
             (accumulator_instances, snark_inputs)
         };
         end_timer!(timer);
@@ -280,12 +284,12 @@ impl Circuit<Fr> for AggregationCircuit {
             let timer = start_timer!(|| "extract hash");
             // orders:
             // - batch_public_input_hash
-            // - chunk\[i\].piHash for i in \[0, MAX_AGG_SNARKS)
+            // - chunk\[i\].piHash for i in \[0, max_agg_snarks)
             // - batch_data_hash_preimage
             let preimages = self.batch_hash.extract_hash_preimages();
             assert_eq!(
                 preimages.len(),
-                MAX_AGG_SNARKS + 2,
+                self.batch_hash.max_agg_snarks() + 2,
                 "error extracting preimages"
             );
             end_timer!(timer);
@@ -310,7 +314,7 @@ impl Circuit<Fr> for AggregationCircuit {
         };
         // digests
         let (batch_pi_hash_digest, chunk_pi_hash_digests, _potential_batch_data_hash_digest) =
-            parse_hash_digest_cells(&hash_digest_cells);
+            parse_hash_digest_cells(&hash_digest_cells, self.batch_hash.max_agg_snarks());
 
         // ==============================================
         // step 3: assert public inputs to the snarks are correct
@@ -342,7 +346,7 @@ impl Circuit<Fr> for AggregationCircuit {
                     return Ok(());
                 }
 
-                for i in 0..MAX_AGG_SNARKS {
+                for i in 0..self.batch_hash.max_agg_snarks() {
                     for j in 0..4 {
                         for k in 0..8 {
                             let mut t1 = Fr::default();
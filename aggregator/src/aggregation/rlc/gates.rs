@@ -16,19 +16,17 @@ impl RlcConfig {
         region.assign_fixed(|| "const zero", self.fixed, 0, || Value::known(Fr::zero()))?;
         region.assign_fixed(|| "const one", self.fixed, 1, || Value::known(Fr::one()))?;
         region.assign_fixed(|| "const two", self.fixed, 2, || Value::known(Fr::from(2)))?;
-        region.assign_fixed(|| "const five", self.fixed, 3, || Value::known(Fr::from(5)))?;
-        region.assign_fixed(|| "const nine", self.fixed, 4, || Value::known(Fr::from(9)))?;
-        region.assign_fixed(|| "const 32", self.fixed, 5, || Value::known(Fr::from(32)))?;
+        region.assign_fixed(|| "const 32", self.fixed, 3, || Value::known(Fr::from(32)))?;
         region.assign_fixed(
             || "const 136",
             self.fixed,
-            6,
+            4,
             || Value::known(Fr::from(136)),
         )?;
         region.assign_fixed(
             || "const 2^32",
             self.fixed,
-            7,
+            5,
             || Value::known(Fr::from(1 << 32)),
         )?;
         Ok(())
@@ -61,29 +59,11 @@ impl RlcConfig {
         }
     }
 
-    #[inline]
-    pub(crate) fn five_cell(&self, region_index: RegionIndex) -> Cell {
-        Cell {
-            region_index,
-            row_offset: 3,
-            column: self.fixed.into(),
-        }
-    }
-
-    #[inline]
-    pub(crate) fn nine_cell(&self, region_index: RegionIndex) -> Cell {
-        Cell {
-            region_index,
-            row_offset: 4,
-            column: self.fixed.into(),
-        }
-    }
-
     #[inline]
     pub(crate) fn thirty_two_cell(&self, region_index: RegionIndex) -> Cell {
         Cell {
             region_index,
-            row_offset: 5,
+            row_offset: 3,
             column: self.fixed.into(),
         }
     }
@@ -91,7 +71,7 @@ impl RlcConfig {
     pub(crate) fn one_hundred_and_thirty_six_cell(&self, region_index: RegionIndex) -> Cell {
         Cell {
             region_index,
-            row_offset: 6,
+            row_offset: 4,
             column: self.fixed.into(),
         }
     }
@@ -100,7 +80,7 @@ impl RlcConfig {
     pub(crate) fn two_to_thirty_two_cell(&self, region_index: RegionIndex) -> Cell {
         Cell {
             region_index,
-            row_offset: 7,
+            row_offset: 5,
             column: self.fixed.into(),
         }
     }
@@ -4,14 +4,12 @@
 use eth_types::{Field, H256};
 use ethers_core::utils::keccak256;
 
-use crate::constants::MAX_AGG_SNARKS;
-
 use super::chunk::ChunkHash;
 
 #[derive(Default, Debug, Clone)]
-/// A batch is a set of MAX_AGG_SNARKS num of continuous chunks
+/// A batch is a set of max_agg_snarks num of continuous chunks
 /// - the first k chunks are from real traces
-/// - the last (#MAX_AGG_SNARKS-k) chunks are from empty traces
+/// - the last (#max_agg_snarks-k) chunks are from empty traces
 /// A BatchHash consists of 2 hashes.
 /// - batch_pi_hash   := keccak(chain_id || chunk_0.prev_state_root || chunk_k-1.post_state_root ||
 ///   chunk_k-1.withdraw_root || batch_data_hash)
@@ -20,22 +18,21 @@ pub struct BatchHash {
     pub(crate) chain_id: u64,
     // chunks with padding.
     // - the first [0..number_of_valid_chunks) are real ones
-    // - the last [number_of_valid_chunks, MAX_AGG_SNARKS) are padding
-    pub(crate) chunks_with_padding: [ChunkHash; MAX_AGG_SNARKS],
+    // - the last [number_of_valid_chunks, max_agg_snarks) are padding
+    pub(crate) chunks_with_padding: Vec<ChunkHash>,
     pub(crate) data_hash: H256,
     pub(crate) public_input_hash: H256,
     pub(crate) number_of_valid_chunks: usize,
 }
 
 impl BatchHash {
-    /// Build Batch hash from an ordered list of #MAX_AGG_SNARKS of chunks.
+    /// Build Batch hash from an ordered list of chunks, padded to the max
+    /// number of chunks of the batch (see [`crate::MAX_AGG_SNARKS`] for the
+    /// default one).
     #[allow(dead_code)]
     pub fn construct(chunks_with_padding: &[ChunkHash]) -> Self {
-        assert_eq!(
-            chunks_with_padding.len(),
-            MAX_AGG_SNARKS,
-            "input chunk slice does not match MAX_AGG_SNARKS"
-        );
+        let max_agg_snarks = chunks_with_padding.len();
+        assert_ne!(max_agg_snarks, 0, "input chunk slice is empty");
 
         let number_of_valid_chunks = match chunks_with_padding
             .iter()
@@ -43,7 +40,7 @@ impl BatchHash {
             .find(|(_index, chunk)| chunk.is_padding)
         {
             Some((index, _)) => index,
-            None => MAX_AGG_SNARKS,
+            None => max_agg_snarks,
         };
 
         assert_ne!(
@@ -61,7 +58,7 @@ impl BatchHash {
         // sanity checks
         // ========================
         // todo: return errors instead
-        for i in 0..MAX_AGG_SNARKS - 1 {
+        for i in 0..max_agg_snarks - 1 {
             assert_eq!(
                 chunks_with_padding[i].chain_id,
                 chunks_with_padding[i + 1].chain_id,
@@ -111,10 +108,10 @@ impl BatchHash {
         let preimage = [
             chunks_with_padding[0].chain_id.to_be_bytes().as_ref(),
             chunks_with_padding[0].prev_state_root.as_bytes(),
-            chunks_with_padding[MAX_AGG_SNARKS - 1]
+            chunks_with_padding[max_agg_snarks - 1]
                 .post_state_root
                 .as_bytes(),
-            chunks_with_padding[MAX_AGG_SNARKS - 1]
+            chunks_with_padding[max_agg_snarks - 1]
                 .withdraw_root
                 .as_bytes(),
             data_hash.as_slice(),
@@ -124,45 +121,59 @@ impl BatchHash {
 
         Self {
             chain_id: chunks_with_padding[0].chain_id,
-            chunks_with_padding: chunks_with_padding.try_into().unwrap(), // safe unwrap
+            chunks_with_padding: chunks_with_padding.to_vec(),
             data_hash: data_hash.into(),
             public_input_hash: public_input_hash.into(),
             number_of_valid_chunks,
         }
     }
 
+    /// Max number of chunks of the batch, i.e. the number of chunks with
+    /// padding.
+    pub fn max_agg_snarks(&self) -> usize {
+        self.chunks_with_padding.len()
+    }
+
+    /// Prev state root of the batch, i.e. of its first chunk.
+    pub fn prev_state_root(&self) -> H256 {
+        self.chunks_with_padding[0].prev_state_root
+    }
+
+    /// Post state root of the batch, i.e. of its last chunk.
+    pub fn post_state_root(&self) -> H256 {
+        self.chunks_with_padding[self.max_agg_snarks() - 1].post_state_root
+    }
+
+    /// Withdraw root of the batch, i.e. of its last chunk.
+    pub fn withdraw_root(&self) -> H256 {
+        self.chunks_with_padding[self.max_agg_snarks() - 1].withdraw_root
+    }
+
+    /// Extract the input of the batch's public input hash
+    ///  chain_id || chunk\[0\].prev_state_root || chunk\[k-1\].post_state_root ||
+    ///  chunk\[k-1\].withdraw_root || batch_data_hash
+    pub(crate) fn public_input_hash_preimage(&self) -> Vec<u8> {
+        [
+            self.chain_id.to_be_bytes().as_ref(),
+            self.prev_state_root().as_bytes(),
+            self.post_state_root().as_bytes(),
+            self.withdraw_root().as_bytes(),
+            self.data_hash.as_bytes(),
+        ]
+        .concat()
+    }
+
     /// Extract all the hash inputs that will ever be used.
-    /// There are MAX_AGG_SNARKS + 2 hashes.
+    /// There are max_agg_snarks + 2 hashes.
     ///
     /// orders:
     /// - batch_public_input_hash
-    /// - chunk\[i\].piHash for i in \[0, MAX_AGG_SNARKS)
+    /// - chunk\[i\].piHash for i in \[0, max_agg_snarks)
     /// - batch_data_hash_preimage
     pub(crate) fn extract_hash_preimages(&self) -> Vec<Vec<u8>> {
-        let mut res = vec![];
-
-        // batchPiHash =
-        //  keccak(
-        //      chain_id ||
-        //      chunk[0].prev_state_root ||
-        //      chunk[k-1].post_state_root ||
-        //      chunk[k-1].withdraw_root ||
-        //      batch_data_hash )
-        let batch_public_input_hash_preimage = [
-            self.chain_id.to_be_bytes().as_ref(),
-            self.chunks_with_padding[0].prev_state_root.as_bytes(),
-            self.chunks_with_padding[MAX_AGG_SNARKS - 1]
-                .post_state_root
-                .as_bytes(),
-            self.chunks_with_padding[MAX_AGG_SNARKS - 1]
-                .withdraw_root
-                .as_bytes(),
-            self.data_hash.as_bytes(),
-        ]
-        .concat();
-        res.push(batch_public_input_hash_preimage);
+        let mut res = vec![self.public_input_hash_preimage()];
 
-        // compute piHash for each chunk for i in [0..MAX_AGG_SNARKS)
+        // compute piHash for each chunk for i in [0..max_agg_snarks)
         // chunk[i].piHash =
         // keccak(
         //        chain id ||
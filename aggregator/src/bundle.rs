@@ -0,0 +1,7 @@
+/// Circuit implementation of bundle circuit.
+mod circuit;
+/// Public input hash of a bundle of batches
+mod hash;
+
+pub use circuit::BundleCircuit;
+pub use hash::BundleHash;
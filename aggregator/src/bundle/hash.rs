@@ -0,0 +1,118 @@
+//! This module implements related functions that aggregates public inputs of many batches into a
+//! single one.
+
+use eth_types::{Field, H256};
+use ethers_core::utils::keccak256;
+
+use crate::batch::BatchHash;
+
+#[derive(Default, Debug, Clone)]
+/// A bundle is a list of n continuous batches, which are proven by a single
+/// bundle proof, so that only one proof is verified on chain for all of them.
+/// A BundleHash consists of 1 hash.
+/// - bundle_pi_hash := keccak(chain_id || batch_0.prev_state_root || batch_n-1.post_state_root ||
+///   batch_n-1.withdraw_root || batch_0.pi_hash || ... || batch_n-1.pi_hash)
+pub struct BundleHash {
+    pub(crate) chain_id: u64,
+    pub(crate) batch_hashes: Vec<BatchHash>,
+    pub(crate) public_input_hash: H256,
+}
+
+impl BundleHash {
+    /// Build Bundle hash from an ordered list of batches.
+    pub fn construct(batch_hashes: &[BatchHash]) -> Self {
+        let num_batches = batch_hashes.len();
+        assert_ne!(num_batches, 0, "input batch slice is empty");
+        log::trace!("build a Bundle with {num_batches} batches");
+
+        // ========================
+        // sanity checks
+        // ========================
+        // todo: return errors instead
+        for i in 0..num_batches - 1 {
+            assert_eq!(batch_hashes[i].chain_id, batch_hashes[i + 1].chain_id);
+            assert_eq!(
+                batch_hashes[i].post_state_root(),
+                batch_hashes[i + 1].prev_state_root(),
+            );
+        }
+
+        // public input hash is build as
+        //  keccak(
+        //      chain_id ||
+        //      batch[0].prev_state_root ||
+        //      batch[n-1].post_state_root ||
+        //      batch[n-1].withdraw_root ||
+        //      batch[0].pi_hash || ... || batch[n-1].pi_hash )
+        let public_input_hash = keccak256(Self::public_input_hash_preimage(batch_hashes));
+
+        Self {
+            chain_id: batch_hashes[0].chain_id,
+            batch_hashes: batch_hashes.to_vec(),
+            public_input_hash: public_input_hash.into(),
+        }
+    }
+
+    /// Number of batches of the bundle.
+    pub fn num_batches(&self) -> usize {
+        self.batch_hashes.len()
+    }
+
+    /// Public input hash of the bundle.
+    pub fn public_input_hash(&self) -> H256 {
+        self.public_input_hash
+    }
+
+    fn public_input_hash_preimage(batch_hashes: &[BatchHash]) -> Vec<u8> {
+        let first = &batch_hashes[0];
+        let last = &batch_hashes[batch_hashes.len() - 1];
+        [
+            first.chain_id.to_be_bytes().as_ref(),
+            first.prev_state_root().as_bytes(),
+            last.post_state_root().as_bytes(),
+            last.withdraw_root().as_bytes(),
+        ]
+        .into_iter()
+        .chain(
+            batch_hashes
+                .iter()
+                .map(|batch_hash| batch_hash.public_input_hash.as_bytes()),
+        )
+        .collect::<Vec<_>>()
+        .concat()
+    }
+
+    /// Extract all the hash inputs that will ever be used.
+    /// There are num_batches + 1 hashes.
+    ///
+    /// orders:
+    /// - batch\[i\].piHash for i in \[0, num_batches)
+    /// - bundle_public_input_hash
+    pub(crate) fn extract_hash_preimages(&self) -> Vec<Vec<u8>> {
+        // batch[i].piHash =
+        //  keccak(
+        //      chain_id ||
+        //      batch[i].prev_state_root ||
+        //      batch[i].post_state_root ||
+        //      batch[i].withdraw_root ||
+        //      batch[i].batch_data_hash )
+        self.batch_hashes
+            .iter()
+            .map(BatchHash::public_input_hash_preimage)
+            .chain(std::iter::once(Self::public_input_hash_preimage(
+                &self.batch_hashes,
+            )))
+            .collect()
+    }
+
+    /// Compute the public inputs for this circuit, excluding the accumulator.
+    /// Content: the public_input_hash
+    pub(crate) fn instances_exclude_acc<F: Field>(&self) -> Vec<Vec<F>> {
+        vec![self
+            .public_input_hash
+            .as_bytes()
+            .iter()
+            .map(|&x| F::from(x as u64))
+            .collect()]
+    }
+}
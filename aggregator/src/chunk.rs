@@ -72,6 +72,9 @@ impl ChunkHash {
                     .chain(b_ctx.base_fee.to_be_bytes())
                     .chain(b_ctx.gas_limit.to_be_bytes())
                     .chain(num_txs.to_be_bytes())
+                    .chain(b_ctx.excess_blob_gas.to_be_bytes())
+                    .chain(b_ctx.blob_base_fee.to_be_bytes())
+                    .chain([b_ctx.hardfork as u8])
             }))
             // Tx Hashes
             .chain(block.txs.iter().flat_map(|tx| tx.hash.to_fixed_bytes()))
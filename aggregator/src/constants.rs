@@ -32,6 +32,15 @@ pub(crate) const POST_STATE_ROOT_INDEX: usize = 40;
 pub(crate) const WITHDRAW_ROOT_INDEX: usize = 72;
 pub(crate) const CHUNK_DATA_HASH_INDEX: usize = 104;
 
+// the preimage of a bundle's public input hash is arranged as
+// - chain_id:          8 bytes
+// - prev_state_root    32 bytes
+// - post_state_root    32 bytes
+// - withdraw_root      32 bytes
+// - batch_pi_hash      32 bytes for each batch of the bundle
+
+pub(crate) const BATCH_PI_HASH_INDEX: usize = 104;
+
 // ================================
 // aggregator parameters
 // ================================
@@ -44,8 +53,8 @@ pub(crate) const LIMBS: usize = 3;
 /// number of bits in each limb in the ECC chip
 pub(crate) const BITS: usize = 88;
 
-/// Max number of snarks to be aggregated in a chunk.
-/// If the input size is less than this, dummy snarks
-/// will be padded.
-// TODO: update me(?)
+/// Default max number of snarks to be aggregated in a batch.
+/// If the input size is less than the max number, dummy snarks
+/// will be padded. The max number of an aggregation circuit is
+/// the number of snarks it is built with, padding included.
 pub const MAX_AGG_SNARKS: usize = 10;
@@ -32,10 +32,13 @@ use zkevm_circuits::{
 };
 
 use crate::{
-    constants::{CHAIN_ID_LEN, DIGEST_LEN, INPUT_LEN_PER_ROUND, LOG_DEGREE, MAX_AGG_SNARKS},
+    constants::{BATCH_PI_HASH_INDEX, CHAIN_ID_LEN, DIGEST_LEN, INPUT_LEN_PER_ROUND, LOG_DEGREE},
     util::{
-        assert_conditional_equal, assert_equal, assert_exist, get_indices, get_max_keccak_updates,
-        parse_hash_digest_cells, parse_hash_preimage_cells, parse_pi_hash_rlc_cells,
+        assert_conditional_equal, assert_equal, assert_exist, get_bundle_indices,
+        get_bundle_keccak_updates, get_bundle_pi_hash_keccak_updates, get_data_hash_keccak_updates,
+        get_indices, get_max_keccak_updates, parse_bundle_hash_digest_cells,
+        parse_bundle_hash_preimage_cells, parse_hash_digest_cells, parse_hash_preimage_cells,
+        parse_pi_hash_rlc_cells,
     },
     AggregationConfig, RlcConfig, CHUNK_DATA_HASH_INDEX, POST_STATE_ROOT_INDEX,
     PREV_STATE_ROOT_INDEX, WITHDRAW_ROOT_INDEX,
@@ -114,8 +117,8 @@ pub(crate) struct ExtractedHashCells {
     is_final_cells: Vec<AssignedCell<Fr, Fr>>,
 }
 
-/// Input the hash input bytes,
-/// assign the circuit for the hash function,
+/// Input the hash input bytes of a batch of chunks_are_valid.len() chunks
+/// (padding included), assign the circuit for the hash function,
 /// return
 /// - cells of the hash digests
 //
@@ -124,15 +127,15 @@ pub(crate) struct ExtractedHashCells {
 // 1. batch_data_hash digest is reused for public input hash
 // 2. batch_pi_hash used same roots as chunk_pi_hash
 // 2.1. batch_pi_hash and chunk[0] use a same prev_state_root
-// 2.2. batch_pi_hash and chunk[MAX_AGG_SNARKS-1] use a same post_state_root
-// 2.3. batch_pi_hash and chunk[MAX_AGG_SNARKS-1] use a same withdraw_root
+// 2.2. batch_pi_hash and chunk[max_agg_snarks-1] use a same post_state_root
+// 2.3. batch_pi_hash and chunk[max_agg_snarks-1] use a same withdraw_root
 // 3. batch_data_hash and chunk[i].pi_hash use a same chunk[i].data_hash when chunk[i] is not padded
 // 4. chunks are continuous: they are linked via the state roots
 // 5. batch and all its chunks use a same chain id
 // 6. chunk[i]'s chunk_pi_hash_rlc_cells == chunk[i-1].chunk_pi_hash_rlc_cells when chunk[i] is
 // padded
 // 7. the hash input length are correct
-// - first MAX_AGG_SNARKS + 1 hashes all have 136 bytes input
+// - first max_agg_snarks + 1 hashes all have 136 bytes input
 // - batch's data_hash length is 32 * number_of_valid_snarks
 // 8. batch data hash is correct w.r.t. its RLCs
 // 9. is_final_cells are set correctly
@@ -143,18 +146,54 @@ pub(crate) fn assign_batch_hashes(
     chunks_are_valid: &[bool],
     preimages: &[Vec<u8>],
 ) -> Result<Vec<AssignedCell<Fr, Fr>>, Error> {
+    let max_agg_snarks = chunks_are_valid.len();
+
+    // preimages consists of the following parts
+    // (1) batchPiHash preimage =
+    //      (chain_id ||
+    //      chunk[0].prev_state_root ||
+    //      chunk[k-1].post_state_root ||
+    //      chunk[k-1].withdraw_root ||
+    //      batch_data_hash)
+    // (2) chunk[i].piHash preimage =
+    //      (chain id ||
+    //      chunk[i].prevStateRoot || chunk[i].postStateRoot ||
+    //      chunk[i].withdrawRoot || chunk[i].datahash)
+    // (3) batchDataHash preimage =
+    //      (chunk[0].dataHash || ... || chunk[k-1].dataHash)
+    //
+    // extract the indices of the rows for which the preimage and the digest cells lie in
+    let (preimage_indices, digest_indices) = get_indices(preimages, max_agg_snarks);
+    let max_keccak_updates = get_max_keccak_updates(max_agg_snarks);
+    // sanity
+    assert_eq!(
+        preimage_indices.len(),
+        max_keccak_updates * INPUT_LEN_PER_ROUND
+    );
+    assert_eq!(
+        digest_indices.len(),
+        (max_agg_snarks + 1 + get_data_hash_keccak_updates(max_agg_snarks)) * DIGEST_LEN
+    );
+
     let extracted_hash_cells = extract_hash_cells(
         &config.keccak_circuit_config,
         layouter,
         challenges,
         preimages,
+        &preimage_indices,
+        &digest_indices,
+        max_keccak_updates,
     )?;
     // 2. batch_pi_hash used same roots as chunk_pi_hash
     // 2.1. batch_pi_hash and chunk[0] use a same prev_state_root
-    // 2.2. batch_pi_hash and chunk[MAX_AGG_SNARKS-1] use a same post_state_root
-    // 2.3. batch_pi_hash and chunk[MAX_AGG_SNARKS-1] use a same withdraw_root
+    // 2.2. batch_pi_hash and chunk[max_agg_snarks-1] use a same post_state_root
+    // 2.3. batch_pi_hash and chunk[max_agg_snarks-1] use a same withdraw_root
     // 5. batch and all its chunks use a same chain id
-    copy_constraints(layouter, &extracted_hash_cells.hash_input_cells)?;
+    copy_constraints(
+        layouter,
+        &extracted_hash_cells.hash_input_cells,
+        max_agg_snarks,
+    )?;
 
     // 1. batch_data_hash digest is reused for public input hash
     // 3. batch_data_hash and chunk[i].pi_hash use a same chunk[i].data_hash when chunk[i] is not
@@ -163,7 +202,7 @@ pub(crate) fn assign_batch_hashes(
     // 6. chunk[i]'s chunk_pi_hash_rlc_cells == chunk[i-1].chunk_pi_hash_rlc_cells when chunk[i] is
     // padded
     // 7. the hash input length are correct
-    // - first MAX_AGG_SNARKS + 1 hashes all have 136 bytes input
+    // - first max_agg_snarks + 1 hashes all have 136 bytes input
     // - batch's data_hash length is 32 * number_of_valid_snarks
     // 8. batch data hash is correct w.r.t. its RLCs
     // 9. is_final_cells are set correctly
@@ -178,39 +217,29 @@ pub(crate) fn assign_batch_hashes(
     Ok(extracted_hash_cells.hash_output_cells)
 }
 
+/// Assign the keccak circuit for the preimages, and extract
+/// - the cells at the given rows of the preimages and of the digests
+/// - the is_final, data rlc and input length cells at the start of each of the first
+///   max_keccak_updates + 1 keccak-f updates
 pub(crate) fn extract_hash_cells(
     keccak_config: &KeccakCircuitConfig<Fr>,
     layouter: &mut impl Layouter<Fr>,
     challenges: Challenges<Value<Fr>>,
     preimages: &[Vec<u8>],
+    preimage_indices: &[usize],
+    digest_indices: &[usize],
+    max_keccak_updates: usize,
 ) -> Result<ExtractedHashCells, Error> {
     let mut is_first_time = true;
     let keccak_capacity = KeccakCircuit::<Fr>::capacity_for_row(1 << LOG_DEGREE);
-    let max_keccak_updates = get_max_keccak_updates(MAX_AGG_SNARKS);
     let keccak_f_rows = keccak_packed_multi::get_num_rows_per_update();
 
     let timer = start_timer!(|| ("multi keccak").to_string());
-    // preimages consists of the following parts
-    // (1) batchPiHash preimage =
-    //      (chain_id ||
-    //      chunk[0].prev_state_root ||
-    //      chunk[k-1].post_state_root ||
-    //      chunk[k-1].withdraw_root ||
-    //      batch_data_hash)
-    // (2) chunk[i].piHash preimage =
-    //      (chain id ||
-    //      chunk[i].prevStateRoot || chunk[i].postStateRoot ||
-    //      chunk[i].withdrawRoot || chunk[i].datahash)
-    // (3) batchDataHash preimage =
-    //      (chunk[0].dataHash || ... || chunk[k-1].dataHash)
-    // each part of the preimage is mapped to image by Keccak256
+    // each preimage is mapped to image by Keccak256
     let witness = multi_keccak(preimages, challenges, keccak_capacity)
         .map_err(|e| Error::AssertionFailure(format!("multi keccak assignment failed: {e:?}")))?;
     end_timer!(timer);
 
-    // extract the indices of the rows for which the preimage and the digest cells lie in
-    let (preimage_indices, digest_indices) = get_indices(preimages);
-
     let extracted_hash_cells = layouter
         .assign_region(
             || "assign keccak rows",
@@ -271,11 +300,8 @@ pub(crate) fn extract_hash_cells(
                 }
 
                 // sanity
-                assert_eq!(
-                    hash_input_cells.len(),
-                    max_keccak_updates * INPUT_LEN_PER_ROUND
-                );
-                assert_eq!(hash_output_cells.len(), (MAX_AGG_SNARKS + 4) * DIGEST_LEN);
+                assert_eq!(hash_input_cells.len(), preimage_indices.len());
+                assert_eq!(hash_output_cells.len(), digest_indices.len());
 
                 keccak_config
                     .keccak_table
@@ -302,12 +328,13 @@ pub(crate) fn extract_hash_cells(
 // Assert the following constraints
 // 2. batch_pi_hash used same roots as chunk_pi_hash
 // 2.1. batch_pi_hash and chunk[0] use a same prev_state_root
-// 2.2. batch_pi_hash and chunk[MAX_AGG_SNARKS-1] use a same post_state_root
-// 2.3. batch_pi_hash and chunk[MAX_AGG_SNARKS-1] use a same withdraw_root
+// 2.2. batch_pi_hash and chunk[max_agg_snarks-1] use a same post_state_root
+// 2.3. batch_pi_hash and chunk[max_agg_snarks-1] use a same withdraw_root
 // 5. batch and all its chunks use a same chain id
 fn copy_constraints(
     layouter: &mut impl Layouter<Fr>,
     hash_input_cells: &[AssignedCell<Fr, Fr>],
+    max_agg_snarks: usize,
 ) -> Result<(), Error> {
     let mut is_first_time = true;
 
@@ -329,7 +356,7 @@ fn copy_constraints(
                     batch_pi_hash_preimage,
                     chunk_pi_hash_preimages,
                     _potential_batch_data_hash_preimage,
-                ) = parse_hash_preimage_cells(hash_input_cells);
+                ) = parse_hash_preimage_cells(hash_input_cells, max_agg_snarks);
 
                 // ====================================================
                 // Constraint the relations between hash preimages
@@ -378,35 +405,35 @@ fn copy_constraints(
                     // sanity check
                     assert_equal(
                         &batch_pi_hash_preimage[i + POST_STATE_ROOT_INDEX],
-                        &chunk_pi_hash_preimages[MAX_AGG_SNARKS - 1][i + POST_STATE_ROOT_INDEX],
+                        &chunk_pi_hash_preimages[max_agg_snarks - 1][i + POST_STATE_ROOT_INDEX],
                         format!(
                             "chunk and batch's post_state_root do not match: {:?} {:?}",
                             &batch_pi_hash_preimage[i + POST_STATE_ROOT_INDEX].value(),
-                            &chunk_pi_hash_preimages[MAX_AGG_SNARKS - 1][i + POST_STATE_ROOT_INDEX]
+                            &chunk_pi_hash_preimages[max_agg_snarks - 1][i + POST_STATE_ROOT_INDEX]
                                 .value(),
                         )
                         .as_str(),
                     );
                     region.constrain_equal(
                         batch_pi_hash_preimage[i + POST_STATE_ROOT_INDEX].cell(),
-                        chunk_pi_hash_preimages[MAX_AGG_SNARKS - 1][i + POST_STATE_ROOT_INDEX]
+                        chunk_pi_hash_preimages[max_agg_snarks - 1][i + POST_STATE_ROOT_INDEX]
                             .cell(),
                     )?;
                     // 2.3 chunk[k-1].withdraw_root
                     assert_equal(
                         &batch_pi_hash_preimage[i + WITHDRAW_ROOT_INDEX],
-                        &chunk_pi_hash_preimages[MAX_AGG_SNARKS - 1][i + WITHDRAW_ROOT_INDEX],
+                        &chunk_pi_hash_preimages[max_agg_snarks - 1][i + WITHDRAW_ROOT_INDEX],
                         format!(
                             "chunk and batch's withdraw_root do not match: {:?} {:?}",
                             &batch_pi_hash_preimage[i + WITHDRAW_ROOT_INDEX].value(),
-                            &chunk_pi_hash_preimages[MAX_AGG_SNARKS - 1][i + WITHDRAW_ROOT_INDEX]
+                            &chunk_pi_hash_preimages[max_agg_snarks - 1][i + WITHDRAW_ROOT_INDEX]
                                 .value(),
                         )
                         .as_str(),
                     );
                     region.constrain_equal(
                         batch_pi_hash_preimage[i + WITHDRAW_ROOT_INDEX].cell(),
-                        chunk_pi_hash_preimages[MAX_AGG_SNARKS - 1][i + WITHDRAW_ROOT_INDEX].cell(),
+                        chunk_pi_hash_preimages[max_agg_snarks - 1][i + WITHDRAW_ROOT_INDEX].cell(),
                     )?;
                 }
 
@@ -446,7 +473,7 @@ fn copy_constraints(
 // 6. chunk[i]'s chunk_pi_hash_rlc_cells == chunk[i-1].chunk_pi_hash_rlc_cells when chunk[i] is
 // padded
 // 7. the hash input length are correct
-// - first MAX_AGG_SNARKS + 1 hashes all have 136 bytes input
+// - first max_agg_snarks + 1 hashes all have 136 bytes input
 // - batch's data_hash length is 32 * number_of_valid_snarks
 // 8. batch data hash is correct w.r.t. its RLCs
 // 9. is_final_cells are set correctly
@@ -458,6 +485,8 @@ pub(crate) fn conditional_constraints(
     extracted_hash_cells: &ExtractedHashCells,
 ) -> Result<(), Error> {
     let mut first_pass = halo2_base::SKIP_FIRST_PASS;
+    let max_agg_snarks = chunks_are_valid.len();
+    let data_hash_rounds = get_data_hash_keccak_updates(max_agg_snarks);
     let ExtractedHashCells {
         hash_input_cells,
         hash_output_cells,
@@ -494,59 +523,73 @@ pub(crate) fn conditional_constraints(
                     num_valid_snarks(rlc_config, &mut region, &chunk_is_valid_cells, &mut offset)?;
 
                 log::trace!("number of valid chunks: {:?}", num_valid_snarks.value());
+
+                // the batch's data_hash length is 32 * number_of_valid_snarks
+                let const32 = rlc_config.load_private(&mut region, &Fr::from(32), &mut offset)?;
+                let const32_cell = rlc_config.thirty_two_cell(const32.cell().region_index);
+                region.constrain_equal(const32.cell(), const32_cell)?;
+                let data_hash_inputs_len =
+                    rlc_config.mul(&mut region, &num_valid_snarks, &const32, &mut offset)?;
+
                 //
-                // if the num_of_valid_snarks <= 4, which only needs 1 keccak-f round. Therefore
-                // the batch's data hash (input, len, data_rlc, output_rlc) are in the first 300
-                // keccak rows;
-                //
-                // else if the num_of_valid_snarks <= 8, which needs
-                // 2 keccak-f rounds. Therefore the batch's data hash (input, len, data_rlc,
-                // output_rlc) are in the 2nd 300 keccak rows;
-                //
-                // else the
-                // num_of_valid_snarks <= 12, which needs 3 keccak-f rounds. Therefore the batch's
-                // data hash (input, len, data_rlc, output_rlc) are in the 3rd 300 keccak rows;
+                // the batch's data hash of k valid snarks has 32 * k bytes of input, which
+                // needs 32 * k / 136 + 1 keccak-f rounds. Therefore the batch's data hash
+                // (input, len, data_rlc, output_rlc) are in the r-th 300 keccak rows of the
+                // data hash iff
+                //   136 * (r - 1) <= 32 * k < 136 * r
                 //
-                // the following flag is build to indicate which row the final data_rlc exists
+                // the following flags are build to indicate which row the final data_rlc
+                // exists, for instance with 10 snarks
                 //
                 // #valid snarks | offset of data hash | flags
                 // 1,2,3,4       | 0                   | 1, 0, 0
                 // 5,6,7,8       | 32                  | 0, 1, 0
                 // 9,10          | 64                  | 0, 0, 1
-
-                let five = {
-                    let five = rlc_config.load_private(&mut region, &Fr::from(5), &mut offset)?;
-                    let five_cell = rlc_config.five_cell(five.cell().region_index);
-                    region.constrain_equal(five_cell, five.cell())?;
-                    five
-                };
-                let nine = {
-                    let nine = rlc_config.load_private(&mut region, &Fr::from(9), &mut offset)?;
-                    let nine_cell = rlc_config.nine_cell(nine.cell().region_index);
-                    region.constrain_equal(nine_cell, nine.cell())?;
-                    nine
-                };
-                let flag1 = rlc_config.is_smaller_than(
-                    &mut region,
-                    &num_valid_snarks,
-                    &five,
-                    &mut offset,
-                )?;
-                let not_flag1 = rlc_config.not(&mut region, &flag1, &mut offset)?;
-                let not_flag3 = rlc_config.is_smaller_than(
-                    &mut region,
-                    &num_valid_snarks,
-                    &nine,
-                    &mut offset,
-                )?;
-                let flag3 = rlc_config.not(&mut region, &not_flag3, &mut offset)?;
-                let flag2 = rlc_config.mul(&mut region, &not_flag1, &not_flag3, &mut offset)?;
-                log::trace!(
-                    "flags: {:?} {:?} {:?}",
-                    flag1.value(),
-                    flag2.value(),
-                    flag3.value()
-                );
+                let const136 = rlc_config.load_private(&mut region, &Fr::from(136), &mut offset)?;
+                let const136_cell =
+                    rlc_config.one_hundred_and_thirty_six_cell(const136.cell().region_index);
+                region.constrain_equal(const136.cell(), const136_cell)?;
+                // is_smaller[r] := 32 * k < 136 * (r + 1), for r in [0, data_hash_rounds - 1)
+                let mut is_smaller = vec![];
+                let mut bound = const136.clone();
+                for r in 0..data_hash_rounds - 1 {
+                    if r > 0 {
+                        bound = rlc_config.add(&mut region, &bound, &const136, &mut offset)?;
+                    }
+                    is_smaller.push(rlc_config.is_smaller_than(
+                        &mut region,
+                        &data_hash_inputs_len,
+                        &bound,
+                        &mut offset,
+                    )?);
+                }
+                // flags[r] := !is_smaller[r - 1] && is_smaller[r]
+                let mut flags = vec![];
+                for r in 0..data_hash_rounds {
+                    let lower = if r > 0 {
+                        Some(rlc_config.not(&mut region, &is_smaller[r - 1], &mut offset)?)
+                    } else {
+                        None
+                    };
+                    let flag = match (lower, is_smaller.get(r)) {
+                        (Some(lower), Some(upper)) => {
+                            rlc_config.mul(&mut region, &lower, upper, &mut offset)?
+                        }
+                        (Some(lower), None) => lower,
+                        (None, Some(upper)) => upper.clone(),
+                        (None, None) => {
+                            let one =
+                                rlc_config.load_private(&mut region, &Fr::one(), &mut offset)?;
+                            let one_cell = rlc_config.one_cell(one.cell().region_index);
+                            region.constrain_equal(one.cell(), one_cell)?;
+                            one
+                        }
+                    };
+                    flags.push(flag);
+                }
+                for (r, flag) in flags.iter().enumerate() {
+                    log::trace!("{r}-th data hash round flag: {:?}", flag.value());
+                }
                 // ====================================================
                 // parse the hashes
                 // ====================================================
@@ -555,25 +598,20 @@ pub(crate) fn conditional_constraints(
                     batch_pi_hash_preimage,
                     chunk_pi_hash_preimages,
                     potential_batch_data_hash_preimage,
-                ) = parse_hash_preimage_cells(hash_input_cells);
+                ) = parse_hash_preimage_cells(hash_input_cells, max_agg_snarks);
 
                 // digests
                 let (
                     _batch_pi_hash_digest,
                     _chunk_pi_hash_digests,
                     potential_batch_data_hash_digest,
-                ) = parse_hash_digest_cells(hash_output_cells);
+                ) = parse_hash_digest_cells(hash_output_cells, max_agg_snarks);
                 // ====================================================
                 // start the actual statements
                 // ====================================================
                 //
                 // 1 batch_data_hash digest is reused for public input hash
                 //
-                // the following part of the code is hard coded for the case where
-                //   MAX_AGG_SNARKS <= 10
-                // in theory it may support up to 12 SNARKS (not tested)
-                // more SNARKs beyond 12 will require a revamp of the circuit
-                //
                 // public input hash is build as
                 //  keccak(
                 //      chain_id ||
@@ -584,46 +622,31 @@ pub(crate) fn conditional_constraints(
                 //
                 // batchDataHash = keccak(chunk[0].dataHash || ... || chunk[k-1].dataHash)
                 //
-                //
-                // #valid snarks | offset of data hash | flags
-                // 1,2,3,4       | 0                   | 1, 0, 0
-                // 5,6,7,8       | 32                  | 0, 1, 0
-                // 9,10          | 64                  | 0, 0, 1
+                // the digest of the r-th data hash round is at offset 32 * r
                 for i in 0..4 {
                     for j in 0..8 {
+                        let candidates = (0..data_hash_rounds)
+                            .map(|r| &potential_batch_data_hash_digest[(3 - i) * 8 + j + 32 * r])
+                            .collect::<Vec<_>>();
                         // sanity check
                         assert_exist(
                             &batch_pi_hash_preimage[i * 8 + j + CHUNK_DATA_HASH_INDEX],
-                            &potential_batch_data_hash_digest[(3 - i) * 8 + j],
-                            &potential_batch_data_hash_digest[(3 - i) * 8 + j + 32],
-                            &potential_batch_data_hash_digest[(3 - i) * 8 + j + 64],
+                            &candidates,
                         );
                         // assert
                         // batch_pi_hash_preimage[i * 8 + j + CHUNK_DATA_HASH_INDEX]
-                        // = flag1 * potential_batch_data_hash_digest[(3 - i) * 8 + j]
-                        // + flag2 * potential_batch_data_hash_digest[(3 - i) * 8 + j + 32]
-                        // + flag3 * potential_batch_data_hash_digest[(3 - i) * 8 + j + 64]
-
-                        let rhs = rlc_config.mul(
-                            &mut region,
-                            &flag1,
-                            &potential_batch_data_hash_digest[(3 - i) * 8 + j],
-                            &mut offset,
-                        )?;
-                        let rhs = rlc_config.mul_add(
-                            &mut region,
-                            &flag2,
-                            &potential_batch_data_hash_digest[(3 - i) * 8 + j + 32],
-                            &rhs,
-                            &mut offset,
-                        )?;
-                        let rhs = rlc_config.mul_add(
-                            &mut region,
-                            &flag3,
-                            &potential_batch_data_hash_digest[(3 - i) * 8 + j + 64],
-                            &rhs,
-                            &mut offset,
-                        )?;
+                        // = sum_r flags[r] * candidates[r]
+                        let mut rhs =
+                            rlc_config.mul(&mut region, &flags[0], candidates[0], &mut offset)?;
+                        for (flag, candidate) in flags.iter().zip(candidates.iter()).skip(1) {
+                            rhs = rlc_config.mul_add(
+                                &mut region,
+                                flag,
+                                candidate,
+                                &rhs,
+                                &mut offset,
+                            )?;
+                        }
 
                         region.constrain_equal(
                             batch_pi_hash_preimage[i * 8 + j + CHUNK_DATA_HASH_INDEX].cell(),
@@ -644,7 +667,7 @@ pub(crate) fn conditional_constraints(
                 //        chunk[i].postStateRoot ||
                 //        chunk[i].withdrawRoot  ||
                 //        chunk[i].datahash)
-                for i in 0..MAX_AGG_SNARKS {
+                for i in 0..max_agg_snarks {
                     for j in 0..DIGEST_LEN {
                         assert_conditional_equal(
                             &chunk_pi_hash_preimages[i][j + CHUNK_DATA_HASH_INDEX],
@@ -669,7 +692,7 @@ pub(crate) fn conditional_constraints(
                 }
 
                 // 4  __valid__ chunks are continuous: they are linked via the state roots
-                for i in 0..MAX_AGG_SNARKS - 1 {
+                for i in 0..max_agg_snarks - 1 {
                     for j in 0..DIGEST_LEN {
                         // sanity check
                         assert_conditional_equal(
@@ -701,9 +724,10 @@ pub(crate) fn conditional_constraints(
                     .map(|chunk_is_valid| rlc_config.not(&mut region, chunk_is_valid, &mut offset))
                     .collect::<Result<Vec<_>, halo2_proofs::plonk::Error>>()?;
 
-                let chunk_pi_hash_rlc_cells = parse_pi_hash_rlc_cells(data_rlc_cells);
+                let chunk_pi_hash_rlc_cells =
+                    parse_pi_hash_rlc_cells(data_rlc_cells, max_agg_snarks);
 
-                for i in 1..MAX_AGG_SNARKS {
+                for i in 1..max_agg_snarks {
                     rlc_config.conditional_enforce_equal(
                         &mut region,
                         chunk_pi_hash_rlc_cells[i - 1],
@@ -723,14 +747,14 @@ pub(crate) fn conditional_constraints(
                 }
 
                 // 7. the hash input length are correct
-                // - first MAX_AGG_SNARKS + 1 hashes all have 136 bytes input
+                // - first max_agg_snarks + 1 hashes all have 136 bytes input
                 // - batch's data_hash length is 32 * number_of_valid_snarks
 
-                // - first MAX_AGG_SNARKS + 1 hashes all have 136 bytes input
+                // - first max_agg_snarks + 1 hashes all have 136 bytes input
                 hash_input_len_cells
                     .iter()
                     .skip(1)
-                    .take((MAX_AGG_SNARKS + 1) * 2)
+                    .take((max_agg_snarks + 1) * 2)
                     .chunks(2)
                     .into_iter()
                     .try_for_each(|chunk| {
@@ -743,54 +767,30 @@ pub(crate) fn conditional_constraints(
                     })?;
 
                 // - batch's data_hash length is 32 * number_of_valid_snarks
-                let const32 = rlc_config.load_private(&mut region, &Fr::from(32), &mut offset)?;
-                let const32_cell = rlc_config.thirty_two_cell(const32.cell().region_index);
-                region.constrain_equal(const32.cell(), const32_cell)?;
-                let data_hash_inputs_len =
-                    rlc_config.mul(&mut region, &num_valid_snarks, &const32, &mut offset)?;
+                // the input length of the r-th data hash round is at offset r
+                let data_hash_len_cells = (0..data_hash_rounds)
+                    .map(|r| &hash_input_len_cells[max_agg_snarks * 2 + 3 + r])
+                    .collect::<Vec<_>>();
 
                 // sanity check
-                assert_exist(
-                    &data_hash_inputs_len,
-                    &hash_input_len_cells[MAX_AGG_SNARKS * 2 + 3],
-                    &hash_input_len_cells[MAX_AGG_SNARKS * 2 + 4],
-                    &hash_input_len_cells[MAX_AGG_SNARKS * 2 + 5],
-                );
+                assert_exist(&data_hash_inputs_len, &data_hash_len_cells);
 
                 log::trace!("data_hash_inputs: {:?}", data_hash_inputs_len.value());
-                log::trace!(
-                    "candidate 1: {:?}",
-                    hash_input_len_cells[MAX_AGG_SNARKS * 2 + 3].value()
-                );
-                log::trace!(
-                    "candidate 2: {:?}",
-                    hash_input_len_cells[MAX_AGG_SNARKS * 2 + 4].value()
-                );
-                log::trace!(
-                    "candidate 3: {:?}",
-                    hash_input_len_cells[MAX_AGG_SNARKS * 2 + 5].value()
-                );
+                for (r, cell) in data_hash_len_cells.iter().enumerate() {
+                    log::trace!("candidate {}: {:?}", r + 1, cell.value());
+                }
 
-                let mut data_hash_inputs_len_rec = rlc_config.mul(
-                    &mut region,
-                    &hash_input_len_cells[MAX_AGG_SNARKS * 2 + 3],
-                    &flag1,
-                    &mut offset,
-                )?;
-                data_hash_inputs_len_rec = rlc_config.mul_add(
-                    &mut region,
-                    &hash_input_len_cells[MAX_AGG_SNARKS * 2 + 4],
-                    &flag2,
-                    &data_hash_inputs_len_rec,
-                    &mut offset,
-                )?;
-                data_hash_inputs_len_rec = rlc_config.mul_add(
-                    &mut region,
-                    &hash_input_len_cells[MAX_AGG_SNARKS * 2 + 5],
-                    &flag3,
-                    &data_hash_inputs_len_rec,
-                    &mut offset,
-                )?;
+                let mut data_hash_inputs_len_rec =
+                    rlc_config.mul(&mut region, data_hash_len_cells[0], &flags[0], &mut offset)?;
+                for (cell, flag) in data_hash_len_cells.iter().zip(flags.iter()).skip(1) {
+                    data_hash_inputs_len_rec = rlc_config.mul_add(
+                        &mut region,
+                        cell,
+                        flag,
+                        &data_hash_inputs_len_rec,
+                        &mut offset,
+                    )?;
+                }
 
                 // sanity check
                 assert_equal(
@@ -813,7 +813,7 @@ pub(crate) fn conditional_constraints(
                 let challenge_cell =
                     rlc_config.read_challenge(&mut region, challenges, &mut offset)?;
 
-                let flags = chunk_is_valid_cells
+                let byte_flags = chunk_is_valid_cells
                     .iter()
                     .flat_map(|cell| vec![cell; 32])
                     .cloned()
@@ -821,54 +821,31 @@ pub(crate) fn conditional_constraints(
 
                 let rlc_cell = rlc_config.rlc_with_flag(
                     &mut region,
-                    potential_batch_data_hash_preimage[..DIGEST_LEN * MAX_AGG_SNARKS].as_ref(),
+                    potential_batch_data_hash_preimage[..DIGEST_LEN * max_agg_snarks].as_ref(),
                     &challenge_cell,
-                    &flags,
+                    &byte_flags,
                     &mut offset,
                 )?;
 
-                assert_exist(
-                    &rlc_cell,
-                    &data_rlc_cells[MAX_AGG_SNARKS * 2 + 3],
-                    &data_rlc_cells[MAX_AGG_SNARKS * 2 + 4],
-                    &data_rlc_cells[MAX_AGG_SNARKS * 2 + 5],
-                );
+                // the data rlc of the r-th data hash round is at offset r
+                let data_hash_rlc_cells = (0..data_hash_rounds)
+                    .map(|r| &data_rlc_cells[max_agg_snarks * 2 + 3 + r])
+                    .collect::<Vec<_>>();
+                assert_exist(&rlc_cell, &data_hash_rlc_cells);
                 log::trace!("rlc from chip {:?}", rlc_cell.value());
-                log::trace!(
-                    "rlc from table {:?}",
-                    data_rlc_cells[MAX_AGG_SNARKS * 2 + 3].value()
-                );
-                log::trace!(
-                    "rlc from table {:?}",
-                    data_rlc_cells[MAX_AGG_SNARKS * 2 + 4].value()
-                );
-                log::trace!(
-                    "rlc from table {:?}",
-                    data_rlc_cells[MAX_AGG_SNARKS * 2 + 5].value()
-                );
+                for cell in data_hash_rlc_cells.iter() {
+                    log::trace!("rlc from table {:?}", cell.value());
+                }
 
                 // assertion
-                let t1 = rlc_config.sub(
-                    &mut region,
-                    &rlc_cell,
-                    &data_rlc_cells[MAX_AGG_SNARKS * 2 + 3],
-                    &mut offset,
-                )?;
-                let t2 = rlc_config.sub(
-                    &mut region,
-                    &rlc_cell,
-                    &data_rlc_cells[MAX_AGG_SNARKS * 2 + 4],
-                    &mut offset,
-                )?;
-                let t3 = rlc_config.sub(
-                    &mut region,
-                    &rlc_cell,
-                    &data_rlc_cells[MAX_AGG_SNARKS * 2 + 5],
-                    &mut offset,
-                )?;
-                let t1t2 = rlc_config.mul(&mut region, &t1, &t2, &mut offset)?;
-                let t1t2t3 = rlc_config.mul(&mut region, &t1t2, &t3, &mut offset)?;
-                rlc_config.enforce_zero(&mut region, &t1t2t3)?;
+                // prod_r (rlc_cell - data_hash_rlc_cells[r]) == 0
+                let mut prod =
+                    rlc_config.sub(&mut region, &rlc_cell, data_hash_rlc_cells[0], &mut offset)?;
+                for cell in data_hash_rlc_cells.iter().skip(1) {
+                    let t = rlc_config.sub(&mut region, &rlc_cell, cell, &mut offset)?;
+                    prod = rlc_config.mul(&mut region, &prod, &t, &mut offset)?;
+                }
+                rlc_config.enforce_zero(&mut region, &prod)?;
 
                 // 9. is_final_cells are set correctly
                 // the is_final_cells are set as
@@ -882,24 +859,24 @@ pub(crate) fn conditional_constraints(
                 // 5                         | 0     | third keccak:
                 // 6                         | 1     |   chunk[1].pi_hash use 2 rounds
                 // ...
-                // 2*(MAX_AGG_SNARKS) + 1    | 0     | MAX_AGG_SNARKS+1's keccak
-                // 2*(MAX_AGG_SNARKS) + 2    | 1     |   chunk[MAX_AGG_SNARKS].pi_hash use 2 rounds
-                // 2*(MAX_AGG_SNARKS) + 3    | a     | MAX_AGG_SNARKS+2's keccak
-                // 2*(MAX_AGG_SNARKS) + 4    | b     |   batch_data_hash may use 1, 2
-                // 2*(MAX_AGG_SNARKS) + 5    | c     |   or 3 rounds
+                // 2*(max_agg_snarks) + 1    | 0     | max_agg_snarks+1's keccak
+                // 2*(max_agg_snarks) + 2    | 1     |   chunk[max_agg_snarks].pi_hash use 2 rounds
+                // 2*(max_agg_snarks) + 3    | a_0   | max_agg_snarks+2's keccak
+                // ...                       | ...   |   batch_data_hash may use 1, ...,
+                // 2*(max_agg_snarks) + 2 + R| a_R-1 |   or R := data_hash_rounds rounds
                 //
-                // so a,b,c are constrained as follows
+                // so a_r are constrained as follows, for instance with 10 snarks
                 //
-                // #valid snarks | flags     | a | b | c
-                // 1,2,3,4       | 1, 0, 0   | 1 | - | -
-                // 5,6,7,8       | 0, 1, 0   | 0 | 1 | -
-                // 9,10          | 0, 0, 1   | 0 | 0 | 1
+                // #valid snarks | flags     | a_0 | a_1 | a_2
+                // 1,2,3,4       | 1, 0, 0   | 1   | -   | -
+                // 5,6,7,8       | 0, 1, 0   | 0   | 1   | -
+                // 9,10          | 0, 0, 1   | 0   | 0   | 1
 
-                // first MAX_AGG_SNARKS + 1 keccak
+                // first max_agg_snarks + 1 keccak
                 for mut chunk in is_final_cells
                     .iter()
                     .skip(1)
-                    .take((MAX_AGG_SNARKS + 1) * 2)
+                    .take((max_agg_snarks + 1) * 2)
                     .into_iter()
                     .chunks(2)
                     .into_iter()
@@ -917,13 +894,19 @@ pub(crate) fn conditional_constraints(
                     )?;
                 }
                 // last keccak
-                // we constrain a * flag1 + b * flag2 + c * flag3 == 1
-                let a = &is_final_cells[2 * (MAX_AGG_SNARKS) + 3];
-                let b = &is_final_cells[2 * (MAX_AGG_SNARKS) + 4];
-                let c = &is_final_cells[2 * (MAX_AGG_SNARKS) + 5];
-                let mut left = rlc_config.mul(&mut region, a, &flag1, &mut offset)?;
-                left = rlc_config.mul_add(&mut region, b, &flag2, &left, &mut offset)?;
-                left = rlc_config.mul_add(&mut region, c, &flag3, &left, &mut offset)?;
+                // we constrain sum_r a_r * flags[r] == 1
+                let data_hash_is_final_cells = (0..data_hash_rounds)
+                    .map(|r| &is_final_cells[2 * max_agg_snarks + 3 + r])
+                    .collect::<Vec<_>>();
+                let mut left = rlc_config.mul(
+                    &mut region,
+                    data_hash_is_final_cells[0],
+                    &flags[0],
+                    &mut offset,
+                )?;
+                for (cell, flag) in data_hash_is_final_cells.iter().zip(flags.iter()).skip(1) {
+                    left = rlc_config.mul_add(&mut region, cell, flag, &left, &mut offset)?;
+                }
                 region
                     .constrain_equal(left.cell(), rlc_config.one_cell(left.cell().region_index))?;
 
@@ -949,3 +932,284 @@ fn num_valid_snarks(
     }
     Ok(res)
 }
+
+/// Input the hash input bytes of a bundle of batches, i.e. the public input
+/// hash preimages of preimages.len() - 1 batches followed by the bundle's one,
+/// assign the circuit for the hash function,
+/// return
+/// - cells of the hash digests
+//
+// This function asserts the following constraints on the hashes
+//
+// 1. bundle_pi_hash used same roots as batch_pi_hash
+// 1.1. bundle_pi_hash and batch[0] use a same prev_state_root
+// 1.2. bundle_pi_hash and batch[n-1] use a same post_state_root
+// 1.3. bundle_pi_hash and batch[n-1] use a same withdraw_root
+// 2. bundle_pi_hash uses the digests of batch[i].pi_hash
+// 3. batches are continuous: they are linked via the state roots
+// 4. bundle and all its batches use a same chain id
+// 5. the hash input length are correct
+// - first n hashes all have 136 bytes input
+// - bundle's pi_hash length is 104 + 32 * n
+// 6. is_final_cells are set correctly
+pub(crate) fn assign_bundle_hashes(
+    config: &AggregationConfig,
+    layouter: &mut impl Layouter<Fr>,
+    challenges: Challenges<Value<Fr>>,
+    preimages: &[Vec<u8>],
+) -> Result<Vec<AssignedCell<Fr, Fr>>, Error> {
+    let num_batches = preimages.len() - 1;
+
+    // preimages consists of the following parts
+    // (1) batch[i].piHash preimage =
+    //      (chain_id ||
+    //      batch[i].prev_state_root || batch[i].post_state_root ||
+    //      batch[i].withdraw_root || batch[i].batch_data_hash)
+    // (2) bundlePiHash preimage =
+    //      (chain_id ||
+    //      batch[0].prev_state_root ||
+    //      batch[n-1].post_state_root ||
+    //      batch[n-1].withdraw_root ||
+    //      batch[0].piHash || ... || batch[n-1].piHash)
+    //
+    // extract the indices of the rows for which the preimage and the digest cells lie in
+    let (preimage_indices, digest_indices) = get_bundle_indices(preimages);
+    let max_keccak_updates = get_bundle_keccak_updates(num_batches);
+    // sanity
+    assert_eq!(
+        preimage_indices.len(),
+        max_keccak_updates * INPUT_LEN_PER_ROUND
+    );
+    assert_eq!(digest_indices.len(), (num_batches + 1) * DIGEST_LEN);
+
+    let ExtractedHashCells {
+        hash_input_cells,
+        hash_output_cells,
+        hash_input_len_cells,
+        is_final_cells,
+        ..
+    } = extract_hash_cells(
+        &config.keccak_circuit_config,
+        layouter,
+        challenges,
+        preimages,
+        &preimage_indices,
+        &digest_indices,
+        max_keccak_updates,
+    )?;
+
+    let rlc_config = &config.rlc_config;
+    let bundle_pi_hash_rounds = get_bundle_pi_hash_keccak_updates(num_batches);
+    let mut first_pass = halo2_base::SKIP_FIRST_PASS;
+
+    layouter
+        .assign_region(
+            || "bundle constraints",
+            |mut region| -> Result<(), halo2_proofs::plonk::Error> {
+                if first_pass {
+                    first_pass = false;
+                    return Ok(());
+                }
+
+                rlc_config.init(&mut region)?;
+                let mut offset = 0;
+                // ====================================================
+                // parse the hashes
+                // ====================================================
+                let (batch_pi_hash_preimages, bundle_pi_hash_preimage) =
+                    parse_bundle_hash_preimage_cells(&hash_input_cells, num_batches);
+                let (batch_pi_hash_digests, _bundle_pi_hash_digest) =
+                    parse_bundle_hash_digest_cells(&hash_output_cells, num_batches);
+
+                // ====================================================
+                // start the actual statements
+                // ====================================================
+                //
+                // 1 bundle_pi_hash used same roots as batch_pi_hash
+                //
+                for (index, batch, name) in [
+                    // 1.1 batch[0].prev_state_root
+                    (PREV_STATE_ROOT_INDEX, 0, "prev_state_root"),
+                    // 1.2 batch[n-1].post_state_root
+                    (POST_STATE_ROOT_INDEX, num_batches - 1, "post_state_root"),
+                    // 1.3 batch[n-1].withdraw_root
+                    (WITHDRAW_ROOT_INDEX, num_batches - 1, "withdraw_root"),
+                ] {
+                    for i in 0..DIGEST_LEN {
+                        let lhs = &bundle_pi_hash_preimage[i + index];
+                        let rhs = &batch_pi_hash_preimages[batch][i + index];
+                        // sanity check
+                        assert_equal(
+                            lhs,
+                            rhs,
+                            format!(
+                                "batch and bundle's {name} do not match: {:?} {:?}",
+                                &lhs.value(),
+                                &rhs.value(),
+                            )
+                            .as_str(),
+                        );
+                        region.constrain_equal(lhs.cell(), rhs.cell())?;
+                    }
+                }
+
+                //
+                // 2 bundle_pi_hash uses the digests of batch[i].pi_hash
+                //
+                // bundle_pi_hash =
+                //   keccak(
+                //      chain_id ||
+                //      batch[0].prev_state_root ||
+                //      batch[n-1].post_state_root ||
+                //      batch[n-1].withdraw_root ||
+                //      batch[0].pi_hash || ... || batch[n-1].pi_hash)
+                //
+                for (i, batch_pi_hash_digest) in batch_pi_hash_digests.iter().enumerate() {
+                    for j in 0..4 {
+                        for k in 0..8 {
+                            let lhs = &bundle_pi_hash_preimage
+                                [BATCH_PI_HASH_INDEX + DIGEST_LEN * i + j * 8 + k];
+                            // in the keccak table, the input and output data have different
+                            // endianess
+                            let rhs = &batch_pi_hash_digest[(3 - j) * 8 + k];
+                            // sanity check
+                            assert_equal(
+                                lhs,
+                                rhs,
+                                format!(
+                                    "bundle's input and batch_{i}'s pi hash do not match: {:?} {:?}",
+                                    &lhs.value(),
+                                    &rhs.value(),
+                                )
+                                .as_str(),
+                            );
+                            region.constrain_equal(lhs.cell(), rhs.cell())?;
+                        }
+                    }
+                }
+
+                //
+                // 3 batches are continuous: batch[i].prev_state_root == batch[i-1].post_state_root
+                //
+                for i in 1..num_batches {
+                    for j in 0..DIGEST_LEN {
+                        let lhs = &batch_pi_hash_preimages[i][j + PREV_STATE_ROOT_INDEX];
+                        let rhs = &batch_pi_hash_preimages[i - 1][j + POST_STATE_ROOT_INDEX];
+                        // sanity check
+                        assert_equal(
+                            lhs,
+                            rhs,
+                            format!(
+                                "batch_{i} is not continuous with its previous batch: {:?} {:?}",
+                                &lhs.value(),
+                                &rhs.value(),
+                            )
+                            .as_str(),
+                        );
+                        region.constrain_equal(lhs.cell(), rhs.cell())?;
+                    }
+                }
+
+                //
+                // 4 assert hashes use a same chain id
+                //
+                for (i, batch_pi_hash_preimage) in batch_pi_hash_preimages.iter().enumerate() {
+                    for (lhs, rhs) in bundle_pi_hash_preimage
+                        .iter()
+                        .take(CHAIN_ID_LEN)
+                        .zip(batch_pi_hash_preimage.iter().take(CHAIN_ID_LEN))
+                    {
+                        // sanity check
+                        assert_equal(
+                            lhs,
+                            rhs,
+                            format!(
+                                "batch_{i} and bundle's chain id do not match: {:?} {:?}",
+                                &lhs.value(),
+                                &rhs.value(),
+                            )
+                            .as_str(),
+                        );
+                        region.constrain_equal(lhs.cell(), rhs.cell())?;
+                    }
+                }
+
+                //
+                // 5 the hash input length are correct
+                //
+                let const32 = rlc_config.load_private(&mut region, &Fr::from(32), &mut offset)?;
+                let const32_cell = rlc_config.thirty_two_cell(const32.cell().region_index);
+                region.constrain_equal(const32.cell(), const32_cell)?;
+                let const136 = rlc_config.load_private(&mut region, &Fr::from(136), &mut offset)?;
+                let const136_cell =
+                    rlc_config.one_hundred_and_thirty_six_cell(const136.cell().region_index);
+                region.constrain_equal(const136.cell(), const136_cell)?;
+
+                // - first n hashes all have 136 bytes input
+                // the input length of a hash is at the index of its last round
+                for i in 0..num_batches {
+                    region.constrain_equal(
+                        hash_input_len_cells[2 * i + 2].cell(),
+                        const136.cell(),
+                    )?;
+                }
+                // - bundle's pi_hash length is 104 + 32 * n = 136 + 32 * (n - 1)
+                let mut bundle_pi_hash_len = const136.clone();
+                for _ in 1..num_batches {
+                    bundle_pi_hash_len =
+                        rlc_config.add(&mut region, &bundle_pi_hash_len, &const32, &mut offset)?;
+                }
+                let bundle_pi_hash_len_cell =
+                    &hash_input_len_cells[2 * num_batches + bundle_pi_hash_rounds];
+                // sanity check
+                assert_equal(
+                    &bundle_pi_hash_len,
+                    bundle_pi_hash_len_cell,
+                    format!(
+                        "bundle_pi_hash_input_len do not match: {:?} {:?}",
+                        &bundle_pi_hash_len.value(),
+                        &bundle_pi_hash_len_cell.value(),
+                    )
+                    .as_str(),
+                );
+                region.constrain_equal(bundle_pi_hash_len.cell(), bundle_pi_hash_len_cell.cell())?;
+
+                //
+                // 6 is_final_cells are set correctly
+                //
+                // index                     | value | comments
+                // --------------------------|-------|------------
+                // 0                         | 0     | 0-th row is prefix pad
+                // 2*i + 1                   | 0     | i-th keccak:
+                // 2*i + 2                   | 1     |   batch[i].pi_hash use 2 rounds
+                // ...                       |       |
+                // 2*n + 1                   | 0     | last keccak:
+                // ...                       | 0     |   bundle_pi_hash uses
+                // 2*n + R                   | 1     |   R := bundle_pi_hash_rounds rounds
+                let zero = rlc_config.load_private(&mut region, &Fr::zero(), &mut offset)?;
+                let zero_cell = rlc_config.zero_cell(zero.cell().region_index);
+                region.constrain_equal(zero.cell(), zero_cell)?;
+                let one = rlc_config.load_private(&mut region, &Fr::one(), &mut offset)?;
+                let one_cell = rlc_config.one_cell(one.cell().region_index);
+                region.constrain_equal(one.cell(), one_cell)?;
+
+                for (index, cell) in is_final_cells
+                    .iter()
+                    .enumerate()
+                    .skip(1)
+                    .take(max_keccak_updates)
+                {
+                    let is_final = (index <= 2 * num_batches && index % 2 == 0)
+                        || index == max_keccak_updates;
+                    let expected = if is_final { &one } else { &zero };
+                    region.constrain_equal(cell.cell(), expected.cell())?;
+                }
+
+                log::trace!("rlc chip uses {} rows", offset);
+                Ok(())
+            },
+        )
+        .map_err(|e| Error::AssertionFailure(format!("bundle: {e}")))?;
+
+    Ok(hash_output_cells)
+}
@@ -0,0 +1,55 @@
+//! EVM verifiers of the aggregator circuits.
+//!
+//! The verifier of a circuit is generated as Yul code and compiled into its
+//! deployment code. A proof is verified by calling the deployed verifier with
+//! the calldata encoding the public inputs and the proof; the call reverts if
+//! the proof is invalid.
+
+use std::path::Path;
+
+use halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::VerifyingKey,
+    poly::kzg::commitment::ParamsKZG,
+};
+use snark_verifier::{
+    loader::evm,
+    pcs::kzg::{Bdfg21, Kzg},
+};
+use snark_verifier_sdk::CircuitExt;
+
+use crate::{AggregationCircuit, BundleCircuit, CompressionCircuit};
+
+/// EVM verifier of a circuit whose public inputs start with an accumulator,
+/// which is checked by the verifier with the final pairing.
+pub trait EvmVerifier: CircuitExt<Fr> + Sized {
+    /// Generate the deployment code of the verifier of the circuit with the
+    /// given verifying key. The Yul code of the verifier is written to `path`
+    /// if given.
+    fn gen_evm_verifier(
+        &self,
+        params: &ParamsKZG<Bn256>,
+        vk: &VerifyingKey<G1Affine>,
+        path: Option<&Path>,
+    ) -> Vec<u8> {
+        snark_verifier_sdk::gen_evm_verifier::<Self, Kzg<Bn256, Bdfg21>>(
+            params,
+            vk,
+            self.num_instance(),
+            path,
+        )
+    }
+
+    /// Encode the calldata of the verifier for a proof of the circuit: the
+    /// public inputs of the circuit, each as a 32 bytes big endian word,
+    /// followed by the proof.
+    fn encode_calldata(&self, proof: &[u8]) -> Vec<u8> {
+        evm::encode_calldata(&self.instances(), proof)
+    }
+}
+
+impl EvmVerifier for AggregationCircuit {}
+
+impl EvmVerifier for BundleCircuit {}
+
+impl EvmVerifier for CompressionCircuit {}
@@ -0,0 +1,55 @@
+//! Circuit digests of the aggregator circuits, so that their keys can be
+//! persisted in a [`KeyStore`](zkevm_circuits::util::key_store::KeyStore).
+//!
+//! Besides the config params, the keys of a circuit depend on the snarks it
+//! verifies: their protocols, which embed the verifying keys of the previous
+//! layer, and the succinct verifying key of the SRS.
+
+use snark_verifier_sdk::SnarkWitness;
+use zkevm_circuits::util::key_store::CircuitDigest;
+
+use crate::{AggregationCircuit, BundleCircuit, CompressionCircuit, ConfigParams};
+
+/// Bytes of the config params, the succinct verifying key and the protocols
+/// of the snarks of an aggregator circuit.
+fn aggregator_config_bytes<'a>(
+    params: &ConfigParams,
+    svk: &impl std::fmt::Debug,
+    snarks: impl IntoIterator<Item = &'a SnarkWitness>,
+) -> Vec<u8> {
+    let mut bytes = serde_json::to_vec(params).unwrap();
+    bytes.extend(format!("{svk:?}").as_bytes());
+    for snark in snarks {
+        bytes.extend(format!("{:?}", snark.protocol).as_bytes());
+    }
+    bytes
+}
+
+impl CircuitDigest for CompressionCircuit {
+    fn config_bytes(&self) -> Vec<u8> {
+        let mut bytes =
+            aggregator_config_bytes(&ConfigParams::compression_param(), &self.svk, [&self.snark]);
+        bytes.push(self.has_accumulator as u8);
+        bytes
+    }
+}
+
+impl CircuitDigest for AggregationCircuit {
+    fn config_bytes(&self) -> Vec<u8> {
+        aggregator_config_bytes(
+            &ConfigParams::aggregation_param_from_env("AGGREGATION_CONFIG"),
+            &self.svk,
+            &self.snarks_with_padding,
+        )
+    }
+}
+
+impl CircuitDigest for BundleCircuit {
+    fn config_bytes(&self) -> Vec<u8> {
+        aggregator_config_bytes(
+            &ConfigParams::aggregation_param_from_env("BUNDLE_CONFIG"),
+            &self.svk,
+            &self.batch_snarks,
+        )
+    }
+}
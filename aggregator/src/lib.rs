@@ -3,6 +3,9 @@ mod aggregation;
 /// This module implements `Batch` related data types.
 /// A batch is a list of chunk.
 mod batch;
+/// aggregation of batch proofs into a bundle proof.
+/// A bundle is a list of batches.
+mod bundle;
 // This module implements `Chunk` related data types.
 // A chunk is a list of blocks.
 mod chunk;
@@ -12,8 +15,14 @@ mod compression;
 mod constants;
 /// Core module for circuit assignment
 mod core;
+/// EVM verifiers of the circuits
+mod evm;
+/// Circuit digests for persisting the keys of the circuits
+mod key_store;
 /// Parameters for compression circuit
 mod param;
+/// Root circuit of the split proving of the super circuit
+mod split_root;
 /// utilities
 mod util;
 
@@ -22,8 +31,11 @@ mod tests;
 
 pub use aggregation::*;
 pub use batch::BatchHash;
+pub use bundle::*;
 pub use chunk::ChunkHash;
 pub use compression::*;
 pub use constants::MAX_AGG_SNARKS;
 pub(crate) use constants::*;
+pub use evm::EvmVerifier;
 pub use param::*;
+pub use split_root::*;
@@ -1,3 +1,5 @@
+use std::{env, fs::File};
+
 use snark_verifier::loader::halo2::halo2_ecc::fields::fp::FpStrategy;
 
 use crate::{BITS, LIMBS};
@@ -16,6 +18,33 @@ pub struct ConfigParams {
 }
 
 impl ConfigParams {
+    /// Parameters of the compression circuit, read from the file at
+    /// `COMPRESSION_CONFIG` (or the default wide config).
+    pub(crate) fn compression_param() -> Self {
+        // Too bad that configure function doesn't take additional input
+        // it would be nicer to load parameters from API rather than ENV
+        let path = env::var("COMPRESSION_CONFIG")
+            .unwrap_or_else(|_| "configs/compression_wide.config".to_owned());
+        serde_json::from_reader(
+            File::open(path.as_str()).unwrap_or_else(|_| panic!("{path:?} does not exist")),
+        )
+        .unwrap_or_else(|_| Self::default_compress_wide_param())
+    }
+
+    /// Parameters of an aggregation circuit, read from the file at the env
+    /// variable `var` if set.
+    pub(crate) fn aggregation_param_from_env(var: &str) -> Self {
+        env::var(var).map_or_else(
+            |_| Self::aggregation_param(),
+            |path| {
+                serde_json::from_reader(
+                    File::open(path.as_str()).unwrap_or_else(|_| panic!("{path:?} does not exist")),
+                )
+                .unwrap()
+            },
+        )
+    }
+
     pub(crate) fn aggregation_param() -> Self {
         Self {
             strategy: FpStrategy::Simple,
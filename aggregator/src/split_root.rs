@@ -0,0 +1,15 @@
+//! Input the proofs of a split super circuit and of its detached circuits, a split root circuit
+//! aggregates them into a single proof.
+//!
+//! The detached circuits prove the exponentiation and modexp circuits with their own degree.
+//! The split root circuit checks that they loaded the same tables as the split super circuit, by
+//! constraining the table fingerprints in their public inputs to be equal, and their challenges to
+//! be squeezed from the commitments of the tables in the proofs.
+//! It re-exposes the public inputs of the split super circuit, without the fingerprints.
+
+/// Circuit implementation of split root circuit.
+mod circuit;
+/// CircuitExt implementation of split root circuit.
+mod circuit_ext;
+
+pub use circuit::{table_challenges, SplitRootCircuit};
@@ -0,0 +1,405 @@
+//! Circuit implementation for split root circuit.
+
+use std::rc::Rc;
+
+use ark_std::{end_timer, start_timer};
+use halo2_proofs::{
+    circuit::{Cell, Layouter, SimpleFloorPlanner, Value},
+    halo2curves::{
+        bn256::{Fq, G1Affine},
+        pairing::Engine,
+    },
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use rand::Rng;
+use snark_verifier::{
+    loader::{
+        halo2::{
+            halo2_ecc::{
+                halo2_base,
+                halo2_base::{
+                    halo2_proofs::{
+                        halo2curves::bn256::{Bn256, Fr},
+                        poly::{commitment::ParamsProver, kzg::commitment::ParamsKZG},
+                    },
+                    Context, ContextParams,
+                },
+            },
+            Halo2Loader,
+        },
+        native::NativeLoader,
+    },
+    pcs::{
+        kzg::{Bdfg21, Kzg, KzgAccumulator, KzgAs, KzgSuccinctVerifyingKey},
+        AccumulationScheme,
+    },
+    util::{arithmetic::fe_to_limbs, transcript::Transcript},
+    verifier::PlonkVerifier,
+};
+use snark_verifier_sdk::{
+    flatten_accumulator,
+    types::{PoseidonTranscript, Shplonk, Svk, POSEIDON_SPEC},
+    Snark, SnarkWitness,
+};
+use zkevm_circuits::super_circuit::NUM_FINGERPRINT_CHALLENGES;
+
+use crate::{
+    core::extract_accumulators_and_proof, param::ConfigParams, CompressionConfig, BITS, LIMBS,
+};
+
+/// Input the proofs of a split super circuit and of its detached circuits, this split root circuit
+/// aggregates them and checks that they share the same table fingerprints, with challenges squeezed
+/// from the commitments of the tables.
+///
+/// It re-exposes the public inputs of the split super circuit, without the fingerprints.
+#[derive(Clone, Debug)]
+pub struct SplitRootCircuit {
+    pub(crate) svk: KzgSuccinctVerifyingKey<G1Affine>,
+    /// snark of the split super circuit, followed by the snarks of the detached circuits
+    pub(crate) snarks: Vec<SnarkWitness>,
+    /// for each snark, the indices of the commitments of its tables among its witness commitments
+    pub(crate) table_commitments: Vec<Vec<usize>>,
+    /// number of public inputs of the split super circuit, without the fingerprints
+    pub(crate) num_super_instances: usize,
+    /// instances, flattened.
+    /// - new accumulator
+    /// - public inputs of the split super circuit, without the fingerprints
+    pub(crate) flattened_instances: Vec<Fr>,
+    // accumulation scheme proof, private input
+    pub(crate) as_proof: Value<Vec<u8>>,
+}
+
+impl Circuit<Fr> for SplitRootCircuit {
+    type Config = CompressionConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            svk: self.svk,
+            snarks: self
+                .snarks
+                .iter()
+                .map(SnarkWitness::without_witnesses)
+                .collect(),
+            table_commitments: self.table_commitments.clone(),
+            num_super_instances: self.num_super_instances,
+            flattened_instances: vec![Fr::zero(); self.flattened_instances.len()],
+            as_proof: Value::unknown(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let params = ConfigParams::aggregation_param_from_env("SPLIT_ROOT_CONFIG");
+
+        log::info!(
+            "split root circuit configured with k = {} and {:?} advice columns",
+            params.degree,
+            params.num_advice
+        );
+
+        Self::Config::configure(meta, params)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let witness_time = start_timer!(|| "synthesize | split root circuit");
+        config
+            .range()
+            .load_lookup_table(&mut layouter)
+            .expect("load range lookup table");
+
+        let mut first_pass = halo2_base::SKIP_FIRST_PASS;
+
+        let (instances, fingerprint_checks) = layouter.assign_region(
+            || "split root circuit",
+            |region| -> Result<(Vec<Cell>, Vec<(Cell, Cell)>), Error> {
+                if first_pass {
+                    first_pass = false;
+                    return Ok((vec![], vec![]));
+                }
+                let ctx = Context::new(
+                    region,
+                    ContextParams {
+                        max_rows: config.gate().max_rows,
+                        num_context_ids: 1,
+                        fixed_columns: config.gate().constants.clone(),
+                    },
+                );
+
+                let ecc_chip = config.ecc_chip();
+                let loader = Halo2Loader::new(ecc_chip, ctx);
+
+                // same as `snark_verifier_sdk::aggregate`, keeping the loaded witness commitments
+                // of the snarks to squeeze the fingerprint challenges from their tables
+                let mut assigned_instances = Vec::with_capacity(self.snarks.len());
+                let mut challenge_transcript = PoseidonTranscript::<Rc<Halo2Loader<_, _>>, _>::new(
+                    &loader,
+                    Value::<&[u8]>::unknown(),
+                );
+                let mut accumulators = self
+                    .snarks
+                    .iter()
+                    .zip(self.table_commitments.iter())
+                    .flat_map(|(snark, table_commitments)| {
+                        let protocol = snark.protocol.loaded(&loader);
+                        let instances = snark
+                            .instances
+                            .iter()
+                            .map(|instances| {
+                                instances
+                                    .iter()
+                                    .map(|instance| loader.assign_scalar(*instance))
+                                    .collect::<Vec<_>>()
+                            })
+                            .collect::<Vec<_>>();
+                        let mut transcript = PoseidonTranscript::<Rc<Halo2Loader<_, _>>, _>::new(
+                            &loader,
+                            snark.proof(),
+                        );
+                        let proof =
+                            Shplonk::read_proof(&self.svk, &protocol, &instances, &mut transcript);
+                        for idx in table_commitments {
+                            challenge_transcript
+                                .common_ec_point(&proof.witnesses[*idx])
+                                .unwrap();
+                        }
+                        let accumulator =
+                            Shplonk::succinct_verify(&self.svk, &protocol, &instances, &proof);
+                        assigned_instances.push(
+                            instances
+                                .into_iter()
+                                .flatten()
+                                .map(|scalar| scalar.into_assigned())
+                                .collect::<Vec<_>>(),
+                        );
+                        accumulator
+                    })
+                    .collect::<Vec<_>>();
+                let acc = if accumulators.len() > 1 {
+                    let mut transcript = PoseidonTranscript::<Rc<Halo2Loader<_, _>>, _>::new(
+                        &loader,
+                        self.as_proof(),
+                    );
+                    let proof = KzgAs::<Kzg<Bn256, Bdfg21>>::read_proof(
+                        &Default::default(),
+                        &accumulators,
+                        &mut transcript,
+                    )
+                    .unwrap();
+                    KzgAs::<Kzg<Bn256, Bdfg21>>::verify(&Default::default(), &accumulators, &proof)
+                        .unwrap()
+                } else {
+                    accumulators.pop().unwrap()
+                };
+                let challenges = (0..NUM_FINGERPRINT_CHALLENGES)
+                    .map(|_| challenge_transcript.squeeze_challenge().into_assigned())
+                    .collect::<Vec<_>>();
+
+                // instance of the split root circuit is defined as
+                // - accumulators
+                // - re-export the public input from the split super circuit, without the
+                //   fingerprints
+                let (super_instances, super_fingerprints) =
+                    assigned_instances[0].split_at(self.num_super_instances);
+                let mut instances = flatten_accumulator(acc)
+                    .iter()
+                    .map(|assigned| assigned.cell())
+                    .collect::<Vec<_>>();
+                instances.extend(super_instances.iter().map(|x| x.cell()));
+
+                // the split super circuit uses the challenges squeezed from the tables, and the
+                // detached circuits share them, and the fingerprint of their table
+                let mut fingerprint_checks = super_fingerprints[..NUM_FINGERPRINT_CHALLENGES]
+                    .iter()
+                    .zip(challenges.iter())
+                    .map(|(a, b)| (a.cell(), b.cell()))
+                    .collect::<Vec<_>>();
+                for (i, detached_instances) in assigned_instances[1..].iter().enumerate() {
+                    let detached_fingerprints = &detached_instances
+                        [detached_instances.len() - NUM_FINGERPRINT_CHALLENGES - 1..];
+                    fingerprint_checks.extend(
+                        detached_fingerprints[..NUM_FINGERPRINT_CHALLENGES]
+                            .iter()
+                            .zip(challenges.iter())
+                            .chain([(
+                                &detached_fingerprints[NUM_FINGERPRINT_CHALLENGES],
+                                &super_fingerprints[NUM_FINGERPRINT_CHALLENGES + i],
+                            )])
+                            .map(|(a, b)| (a.cell(), b.cell())),
+                    );
+                }
+
+                config.range().finalize(&mut loader.ctx_mut());
+
+                loader.ctx_mut().print_stats(&["Range"]);
+                Ok((instances, fingerprint_checks))
+            },
+        )?;
+
+        let mut first_pass = halo2_base::SKIP_FIRST_PASS;
+        layouter.assign_region(
+            || "fingerprint checks",
+            |mut region| -> Result<(), Error> {
+                if first_pass {
+                    // this region only use copy constraints and do not affect the shape of the
+                    // layouter
+                    first_pass = false;
+                    return Ok(());
+                }
+                for (a, b) in fingerprint_checks.iter() {
+                    region.constrain_equal(*a, *b)?;
+                }
+                Ok(())
+            },
+        )?;
+
+        // Expose instances
+        for (i, cell) in instances.into_iter().enumerate() {
+            layouter.constrain_instance(cell, config.instance, i)?;
+        }
+
+        end_timer!(witness_time);
+        Ok(())
+    }
+}
+
+/// Return the challenges of the table fingerprints, squeezed from the commitments of the tables of
+/// the snarks. For each snark, `table_commitments` holds the indices of the commitments of its
+/// tables among its witness commitments.
+///
+/// The prover proves the circuits with placeholder challenges to get these commitments, then proves
+/// them again with these challenges and the same randomness, so that the commitments of the tables
+/// don't change.
+pub fn table_challenges(
+    params: &ParamsKZG<Bn256>,
+    snarks: &[Snark],
+    table_commitments: &[Vec<usize>],
+) -> [Fr; NUM_FINGERPRINT_CHALLENGES] {
+    let svk = params.get_g()[0].into();
+
+    let mut transcript_read =
+        PoseidonTranscript::<NativeLoader, &[u8]>::from_spec(&[], POSEIDON_SPEC.clone());
+    let mut challenge_transcript =
+        PoseidonTranscript::<NativeLoader, &[u8]>::from_spec(&[], POSEIDON_SPEC.clone());
+    for (snark, table_commitments) in snarks.iter().zip(table_commitments) {
+        transcript_read.new_stream(snark.proof.as_slice());
+        let proof = Shplonk::read_proof(
+            &svk,
+            &snark.protocol,
+            &snark.instances,
+            &mut transcript_read,
+        );
+        for idx in table_commitments {
+            challenge_transcript
+                .common_ec_point(&proof.witnesses[*idx])
+                .unwrap();
+        }
+    }
+    std::array::from_fn(|_| challenge_transcript.squeeze_challenge())
+}
+
+impl SplitRootCircuit {
+    /// Build a new circuit from the fresh snark of a split super circuit, and the fresh snarks of
+    /// its detached circuits in the order of the fingerprints of the split super circuit.
+    /// For each snark, `table_commitments` holds the indices of the commitments of its tables, see
+    /// [`table_challenges`].
+    pub fn new(
+        params: &ParamsKZG<Bn256>,
+        super_snark: Snark,
+        detached_snarks: Vec<Snark>,
+        table_commitments: Vec<Vec<usize>>,
+        rng: impl Rng + Send,
+    ) -> Result<Self, snark_verifier::Error> {
+        let svk = params.get_g()[0].into();
+
+        if table_commitments.len() != detached_snarks.len() + 1 {
+            return Err(snark_verifier::Error::InvalidInstances);
+        }
+        let snarks = [vec![super_snark], detached_snarks].concat();
+        let challenges = table_challenges(params, &snarks, &table_commitments);
+
+        // the fingerprints are the last instance column of each snark:
+        // the challenges followed by the fingerprint of each table
+        let super_fingerprints = snarks[0]
+            .instances
+            .last()
+            .ok_or_else(|| snark_verifier::Error::InvalidInstances)?;
+        if super_fingerprints.len() != NUM_FINGERPRINT_CHALLENGES + snarks.len() - 1 {
+            return Err(snark_verifier::Error::InvalidInstances);
+        }
+        if super_fingerprints[..NUM_FINGERPRINT_CHALLENGES] != challenges {
+            return Err(snark_verifier::Error::AssertionFailure(
+                "challenges of the split super snark aren't squeezed from the tables".to_string(),
+            ));
+        }
+        for (i, snark) in snarks[1..].iter().enumerate() {
+            let expected = challenges
+                .iter()
+                .chain([&super_fingerprints[NUM_FINGERPRINT_CHALLENGES + i]]);
+            if !snark
+                .instances
+                .last()
+                .map_or(false, |fingerprints| fingerprints.iter().eq(expected))
+            {
+                return Err(snark_verifier::Error::AssertionFailure(format!(
+                    "fingerprints of the {i}-th detached snark don't match the split super snark"
+                )));
+            }
+        }
+
+        let (accumulator, as_proof) =
+            extract_accumulators_and_proof(params, &snarks, rng, &params.g2(), &params.s_g2())?;
+
+        let KzgAccumulator::<G1Affine, NativeLoader> { lhs, rhs } = accumulator;
+
+        // sanity check on the accumulator
+        {
+            let left = Bn256::pairing(&lhs, &params.g2());
+            let right = Bn256::pairing(&rhs, &params.s_g2());
+            log::trace!("split root circuit acc check: left {:?}", left);
+            log::trace!("split root circuit acc check: right {:?}", right);
+
+            if left != right {
+                return Err(snark_verifier::Error::AssertionFailure(format!(
+                    "accumulator check failed {left:?} {right:?}",
+                )));
+            }
+        }
+
+        let acc_instances = [lhs.x, lhs.y, rhs.x, rhs.y]
+            .map(fe_to_limbs::<Fq, Fr, { LIMBS }, { BITS }>)
+            .concat();
+        let super_instances = &snarks[0].instances[..snarks[0].instances.len() - 1];
+        let num_super_instances = super_instances.iter().map(|x| x.len()).sum();
+
+        let flattened_instances = acc_instances
+            .iter()
+            .chain(super_instances.iter().flatten())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        Ok(Self {
+            svk,
+            snarks: snarks.into_iter().map(Into::into).collect(),
+            table_commitments,
+            num_super_instances,
+            flattened_instances,
+            as_proof: Value::known(as_proof),
+        })
+    }
+
+    pub fn succinct_verifying_key(&self) -> &Svk {
+        &self.svk
+    }
+
+    pub fn snarks(&self) -> &[SnarkWitness] {
+        &self.snarks
+    }
+
+    pub fn as_proof(&self) -> Value<&[u8]> {
+        self.as_proof.as_ref().map(Vec::as_slice)
+    }
+}
@@ -0,0 +1,32 @@
+//! CircuitExt implementation for split root circuit.
+
+use halo2_proofs::{halo2curves::bn256::Fr, plonk::Selector};
+use snark_verifier_sdk::CircuitExt;
+
+use crate::ACC_LEN;
+
+use super::circuit::SplitRootCircuit;
+
+impl CircuitExt<Fr> for SplitRootCircuit {
+    fn num_instance(&self) -> Vec<usize> {
+        // 12 elements from accumulator
+        // re-exposed public input of the split super circuit
+        vec![ACC_LEN + self.num_super_instances]
+    }
+
+    fn instances(&self) -> Vec<Vec<Fr>> {
+        vec![self.flattened_instances.clone()]
+    }
+
+    fn accumulator_indices() -> Option<Vec<(usize, usize)>> {
+        // the accumulator are the first 12 cells in the instance
+        Some((0..ACC_LEN).map(|idx| (0, idx)).collect())
+    }
+
+    fn selectors(config: &Self::Config) -> Vec<Selector> {
+        config.gate().basic_gates[0]
+            .iter()
+            .map(|gate| gate.q_enable)
+            .collect()
+    }
+}
@@ -1,7 +1,12 @@
 mod aggregation;
+mod bundle;
 mod compression;
+mod evm;
+mod key_store;
+mod mock_batch;
 mod mock_chunk;
 mod rlc;
+mod split_root;
 
 #[macro_export]
 macro_rules! layer_0 {
@@ -7,8 +7,13 @@ use snark_verifier::loader::halo2::halo2_ecc::halo2_base::utils::fs::gen_srs;
 use snark_verifier_sdk::{gen_pk, gen_snark_shplonk, verify_snark_shplonk, CircuitExt};
 
 use crate::{
-    aggregation::AggregationCircuit, batch::BatchHash, constants::MAX_AGG_SNARKS, layer_0,
-    tests::mock_chunk::MockChunkCircuit, ChunkHash,
+    aggregation::AggregationCircuit,
+    batch::BatchHash,
+    constants::MAX_AGG_SNARKS,
+    layer_0,
+    tests::mock_chunk::MockChunkCircuit,
+    util::{get_data_hash_keccak_updates, get_max_keccak_updates},
+    ChunkHash,
 };
 
 #[test]
@@ -18,12 +23,36 @@ fn test_aggregation_circuit() {
     let k = 20;
 
     // This set up requires one round of keccak for chunk's data hash
-    let circuit = build_new_aggregation_circuit(2);
+    let circuit = build_new_aggregation_circuit(2, MAX_AGG_SNARKS);
     let instance = circuit.instances();
     let mock_prover = MockProver::<Fr>::run(k, &circuit, instance).unwrap();
     mock_prover.assert_satisfied_par();
 }
 
+/// Test aggregation circuits with various max numbers of snarks, with the
+/// batch data hash in the first, a middle and the last keccak round.
+#[test]
+fn test_aggregation_circuit_various_max_snarks() {
+    let k = 20;
+
+    for (num_real_chunks, max_agg_snarks) in [(1, 1), (3, 3), (5, 17), (17, 17), (13, 20)] {
+        let circuit = build_new_aggregation_circuit(num_real_chunks, max_agg_snarks);
+        let instance = circuit.instances();
+        let mock_prover = MockProver::<Fr>::run(k, &circuit, instance).unwrap();
+        mock_prover.assert_satisfied_par();
+    }
+}
+
+#[test]
+fn test_keccak_updates() {
+    // a data hash of k * 136 bytes takes k + 1 rounds
+    for (num_snarks, data_hash_updates) in [(1, 1), (4, 1), (5, 2), (10, 3), (16, 4), (17, 5)] {
+        assert_eq!(get_data_hash_keccak_updates(num_snarks), data_hash_updates);
+    }
+    assert_eq!(get_max_keccak_updates(MAX_AGG_SNARKS), 2 + 2 * 10 + 3);
+    assert_eq!(get_max_keccak_updates(45), 2 + 2 * 45 + 11);
+}
+
 /// - Test aggregation proof generation and verification.
 /// - Test a same pk can be used for various number of chunk proofs.
 #[ignore = "it takes too much time"]
@@ -37,7 +66,7 @@ fn test_aggregation_circuit_full() {
     fs::create_dir(path).unwrap();
 
     // This set up requires one round of keccak for chunk's data hash
-    let circuit = build_new_aggregation_circuit(2);
+    let circuit = build_new_aggregation_circuit(2, MAX_AGG_SNARKS);
     let instance = circuit.instances();
     let mock_prover = MockProver::<Fr>::run(25, &circuit, instance).unwrap();
     mock_prover.assert_satisfied_par();
@@ -61,7 +90,7 @@ fn test_aggregation_circuit_full() {
     log::trace!("finished verification for circuit");
 
     // This set up requires two rounds of keccak for chunk's data hash
-    let circuit = build_new_aggregation_circuit(5);
+    let circuit = build_new_aggregation_circuit(5, MAX_AGG_SNARKS);
     let snark = gen_snark_shplonk(&param, &pk, circuit, &mut rng, None::<String>);
     log::trace!("finished snark generation for circuit");
 
@@ -73,7 +102,10 @@ fn test_aggregation_circuit_full() {
     log::trace!("finished verification for circuit");
 }
 
-fn build_new_aggregation_circuit(num_real_chunks: usize) -> AggregationCircuit {
+pub(crate) fn build_new_aggregation_circuit(
+    num_real_chunks: usize,
+    max_agg_snarks: usize,
+) -> AggregationCircuit {
     // inner circuit: Mock circuit
     let k0 = 8;
 
@@ -90,7 +122,7 @@ fn build_new_aggregation_circuit(num_real_chunks: usize) -> AggregationCircuit {
         ChunkHash::mock_padded_chunk_hash_for_testing(&chunks_without_padding[num_real_chunks - 1]);
     let chunks_with_padding = [
         chunks_without_padding,
-        vec![padded_chunk; MAX_AGG_SNARKS - num_real_chunks],
+        vec![padded_chunk; max_agg_snarks - num_real_chunks],
     ]
     .concat();
 
@@ -113,7 +145,7 @@ fn build_new_aggregation_circuit(num_real_chunks: usize) -> AggregationCircuit {
     // padded chunks
     // ==========================
     let padded_snarks =
-        { vec![real_snarks.last().unwrap().clone(); MAX_AGG_SNARKS - num_real_chunks] };
+        { vec![real_snarks.last().unwrap().clone(); max_agg_snarks - num_real_chunks] };
 
     // ==========================
     // batch
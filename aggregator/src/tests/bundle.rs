@@ -0,0 +1,85 @@
+use ark_std::{end_timer, start_timer, test_rng};
+use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr, poly::commitment::Params};
+use itertools::Itertools;
+use snark_verifier::loader::halo2::halo2_ecc::halo2_base::utils::fs::gen_srs;
+use snark_verifier_sdk::{gen_pk, gen_snark_shplonk, verify_snark_shplonk, CircuitExt};
+
+use crate::{
+    batch::BatchHash,
+    bundle::{BundleCircuit, BundleHash},
+    layer_0,
+    tests::mock_batch::MockBatchCircuit,
+    util::{get_bundle_keccak_updates, get_bundle_pi_hash_keccak_updates},
+    ChunkHash,
+};
+
+/// Test bundle circuits with various numbers of batches, with the bundle's
+/// public input hash taking 2, 3 and 4 keccak rounds.
+#[test]
+fn test_bundle_circuit() {
+    let k = 20;
+
+    for num_batches in [1, 6, 10] {
+        let circuit = build_new_bundle_circuit(num_batches);
+        let instance = circuit.instances();
+        let mock_prover = MockProver::<Fr>::run(k, &circuit, instance).unwrap();
+        mock_prover.assert_satisfied_par();
+    }
+}
+
+#[test]
+fn test_bundle_keccak_updates() {
+    // a bundle pi hash of 104 + 32 * n bytes takes (104 + 32 * n) / 136 + 1 rounds
+    for (num_batches, pi_hash_updates) in [(1, 2), (2, 2), (3, 2), (5, 2), (6, 3), (7, 3)] {
+        assert_eq!(
+            get_bundle_pi_hash_keccak_updates(num_batches),
+            pi_hash_updates
+        );
+    }
+    assert_eq!(get_bundle_keccak_updates(3), 2 * 3 + 2);
+}
+
+#[test]
+#[should_panic]
+fn test_bundle_hash_discontinuous_batches() {
+    let mut rng = test_rng();
+    let batches = (0..2)
+        .map(|_| BatchHash::construct(&[ChunkHash::mock_random_chunk_hash_for_testing(&mut rng)]))
+        .collect_vec();
+    BundleHash::construct(&batches);
+}
+
+pub(crate) fn build_new_bundle_circuit(num_batches: usize) -> BundleCircuit {
+    // inner circuit: Mock circuit
+    let k0 = 8;
+
+    let mut rng = test_rng();
+    let params = gen_srs(k0);
+
+    // each batch has 2 chunks, and all the chunks are continuous
+    let mut chunks = (0..2 * num_batches)
+        .map(|_| ChunkHash::mock_random_chunk_hash_for_testing(&mut rng))
+        .collect_vec();
+    for i in 0..2 * num_batches - 1 {
+        chunks[i + 1].prev_state_root = chunks[i].post_state_root;
+    }
+    let batch_hashes = chunks.chunks(2).map(BatchHash::construct).collect_vec();
+
+    // ==========================
+    // batches
+    // ==========================
+    let batch_snarks = batch_hashes
+        .iter()
+        .map(|batch_hash| {
+            let circuit = MockBatchCircuit::new(batch_hash);
+            layer_0!(circuit, MockBatchCircuit, params, k0, path)
+        })
+        .collect_vec();
+
+    // ==========================
+    // bundle
+    // ==========================
+    let bundle_hash = BundleHash::construct(&batch_hashes);
+
+    BundleCircuit::new(&params, &batch_snarks, rng, bundle_hash).unwrap()
+}
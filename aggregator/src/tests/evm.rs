@@ -0,0 +1,160 @@
+use ark_std::{end_timer, start_timer, test_rng};
+use eth_types::{
+    evm_types::{OpcodeId, MAX_CODE_SIZE},
+    Bytes, Word,
+};
+use ethers_core::utils::get_contract_address;
+use halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fr},
+    poly::{commitment::Params, kzg::commitment::ParamsKZG},
+};
+use mock::{eth, TestContext, MOCK_ACCOUNTS};
+use snark_verifier::loader::halo2::halo2_ecc::halo2_base::utils::fs::gen_srs;
+use snark_verifier_sdk::{
+    gen_evm_proof_shplonk, gen_pk, gen_snark_shplonk, verify_snark_shplonk, CircuitExt,
+};
+
+use crate::{
+    constants::MAX_AGG_SNARKS,
+    layer_0,
+    tests::{
+        aggregation::build_new_aggregation_circuit, bundle::build_new_bundle_circuit,
+        mock_chunk::MockChunkCircuit,
+    },
+    CompressionCircuit, EvmVerifier,
+};
+
+impl EvmVerifier for MockChunkCircuit {}
+
+/// Generate the EVM verifier of a mock chunk circuit, and execute it with the
+/// geth tracer for a valid and a tampered proof.
+#[test]
+fn test_mock_chunk_evm_verifier() {
+    let k = 8;
+
+    let mut rng = test_rng();
+    let params = gen_srs(k);
+
+    let circuit = MockChunkCircuit::random(&mut rng, false, false);
+    check_evm_verifier(&params, circuit);
+}
+
+/// Check the calldata of the aggregation and bundle verifiers, which are too
+/// large to be proven here.
+#[test]
+fn test_evm_verifier_calldata() {
+    let proof = (0..=u8::MAX).collect::<Vec<_>>();
+    check_calldata(&build_new_aggregation_circuit(2, MAX_AGG_SNARKS), &proof);
+    check_calldata(&build_new_bundle_circuit(1), &proof);
+}
+
+/// Generate the EVM verifier of a compression circuit, and execute it with
+/// the geth tracer for a valid and a tampered proof.
+#[ignore = "it takes too much time"]
+#[test]
+fn test_compression_evm_verifier() {
+    let k0 = 8;
+    let k1 = 22;
+
+    let mut rng = test_rng();
+    let params = gen_srs(k1);
+
+    // Proof for test circuit
+    let circuit = MockChunkCircuit::random(&mut rng, false, false);
+    let layer_0_snark = layer_0!(circuit, MockChunkCircuit, params, k0, path);
+
+    std::env::set_var("COMPRESSION_CONFIG", "./configs/compression_wide.config");
+    let compression_circuit =
+        CompressionCircuit::new(&params, layer_0_snark, true, &mut rng).unwrap();
+    check_evm_verifier(&params, compression_circuit);
+}
+
+/// Generate the EVM verifier of an aggregation circuit, and execute it with
+/// the geth tracer for a valid and a tampered proof.
+#[ignore = "it takes too much time"]
+#[test]
+fn test_aggregation_evm_verifier() {
+    let params = gen_srs(20);
+    check_evm_verifier(&params, build_new_aggregation_circuit(2, MAX_AGG_SNARKS));
+}
+
+/// Generate the EVM verifier of a bundle circuit, and execute it with the
+/// geth tracer for a valid and a tampered proof.
+#[ignore = "it takes too much time"]
+#[test]
+fn test_bundle_evm_verifier() {
+    let params = gen_srs(20);
+    check_evm_verifier(&params, build_new_bundle_circuit(2));
+}
+
+fn check_evm_verifier<C: EvmVerifier + Clone>(params: &ParamsKZG<Bn256>, circuit: C) {
+    let mut rng = test_rng();
+    let pk = gen_pk(params, &circuit, None);
+    let proof = gen_evm_proof_shplonk(params, &pk, circuit.clone(), circuit.instances(), &mut rng);
+
+    let deployment_code = circuit.gen_evm_verifier(params, pk.get_vk(), None);
+    let calldata = check_calldata(&circuit, &proof);
+    assert!(execute_evm_verifier(&deployment_code, &calldata));
+
+    // flip a bit of the last word of the proof
+    let mut tampered_calldata = calldata;
+    *tampered_calldata.last_mut().unwrap() ^= 1;
+    assert!(!execute_evm_verifier(&deployment_code, &tampered_calldata));
+}
+
+/// Check that the calldata is the public inputs, each as a 32 bytes big
+/// endian word, followed by the proof.
+fn check_calldata<C: EvmVerifier>(circuit: &C, proof: &[u8]) -> Vec<u8> {
+    let calldata = circuit.encode_calldata(proof);
+    let instances = circuit.instances().concat();
+    assert_eq!(calldata.len(), 32 * instances.len() + proof.len());
+    for (word, instance) in calldata.chunks(32).zip(instances.iter()) {
+        let mut bytes = instance.to_bytes();
+        bytes.reverse();
+        assert_eq!(word, bytes);
+    }
+    assert!(calldata.ends_with(proof));
+    calldata
+}
+
+/// Deploy the verifier and call it with the calldata, returning whether the
+/// call succeeded. The deployed verifier must fit the code size limit of
+/// EIP-170.
+fn execute_evm_verifier(deployment_code: &[u8], calldata: &[u8]) -> bool {
+    let verifier = get_contract_address(MOCK_ACCOUNTS[0], Word::zero());
+    let ctx = TestContext::<1, 2>::new(
+        None,
+        |accs| {
+            accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(10));
+        },
+        |mut txs, accs| {
+            txs[0]
+                .from(accs[0].address)
+                .gas(Word::from(20_000_000u64))
+                .input(Bytes::from(deployment_code.to_vec()));
+            txs[1]
+                .from(accs[0].address)
+                .to(verifier)
+                .gas(Word::from(10_000_000u64))
+                .input(Bytes::from(calldata.to_vec()));
+        },
+        |block, _txs| block,
+    )
+    .unwrap();
+
+    let deployment = &ctx.geth_traces[0];
+    assert!(!deployment.failed, "deployment of the verifier failed");
+    // The deployed code is returned by the last step of the constructor, with
+    // its size as the second item of the stack.
+    let code_size = deployment
+        .struct_logs
+        .last()
+        .filter(|step| step.op == OpcodeId::RETURN)
+        .and_then(|step| step.stack.nth_last(1).ok())
+        .unwrap();
+    assert!(
+        code_size <= Word::from(MAX_CODE_SIZE),
+        "verifier code size {code_size} exceeds the EIP-170 limit"
+    );
+    !ctx.geth_traces[1].failed
+}
@@ -0,0 +1,51 @@
+use std::{fs, process};
+
+use ark_std::test_rng;
+use snark_verifier::loader::halo2::halo2_ecc::halo2_base::utils::fs::gen_srs;
+use zkevm_circuits::util::key_store::{CircuitDigest, KeyStore, KeyStoreError};
+
+use crate::tests::mock_chunk::MockChunkCircuit;
+
+impl CircuitDigest for MockChunkCircuit {
+    fn config_bytes(&self) -> Vec<u8> {
+        vec![self.has_accumulator as u8]
+    }
+}
+
+#[test]
+fn test_key_store() {
+    let k = 8;
+    let params = gen_srs(k);
+    let mut rng = test_rng();
+    let circuit = MockChunkCircuit::random(&mut rng, false, false);
+
+    let dir = format!("data/key_store_{}", process::id());
+    let key_store = KeyStore::new(dir.as_str());
+
+    // keys are generated and written on the first call, then read
+    let pk = key_store
+        .read_or_gen_pk("mock_chunk", &params, &circuit)
+        .unwrap();
+    let read_pk = key_store
+        .read_or_gen_pk("mock_chunk", &params, &circuit)
+        .unwrap();
+    let read_vk = key_store.read_vk("mock_chunk", &circuit, k).unwrap();
+    assert_eq!(
+        read_pk.get_vk().transcript_repr(),
+        pk.get_vk().transcript_repr()
+    );
+    assert_eq!(read_vk.transcript_repr(), pk.get_vk().transcript_repr());
+
+    // keys of another configuration of the circuit are rejected
+    let circuit_with_accumulator = MockChunkCircuit::random(&mut rng, true, false);
+    assert!(matches!(
+        key_store.read_pk("mock_chunk", &circuit_with_accumulator, k),
+        Err(KeyStoreError::DigestMismatch { .. })
+    ));
+    assert!(matches!(
+        key_store.read_vk("mock_chunk", &circuit, k + 1),
+        Err(KeyStoreError::DigestMismatch { .. })
+    ));
+
+    fs::remove_dir_all(dir).unwrap();
+}
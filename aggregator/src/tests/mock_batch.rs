@@ -0,0 +1,107 @@
+use std::iter;
+
+use eth_types::H256;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner},
+    halo2curves::bn256::Fr,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use snark_verifier::loader::halo2::halo2_ecc::halo2_base::SKIP_FIRST_PASS;
+use snark_verifier_sdk::CircuitExt;
+use zkevm_circuits::util::Challenges;
+
+use crate::{
+    constants::{ACC_LEN, DIGEST_LEN},
+    tests::mock_chunk::MockConfig,
+    BatchHash, RlcConfig,
+};
+
+#[derive(Debug, Default, Clone, Copy)]
+/// A mock batch circuit
+///
+/// This mock batch circuit simulates an aggregation circuit of a batch.
+/// It's public inputs consists of 44 elements:
+/// - a (zero) accumulator
+/// - batch's public input hash
+pub(crate) struct MockBatchCircuit {
+    pub(crate) public_input_hash: H256,
+}
+
+impl MockBatchCircuit {
+    pub(crate) fn new(batch: &BatchHash) -> Self {
+        MockBatchCircuit {
+            public_input_hash: batch.public_input_hash,
+        }
+    }
+}
+
+impl Circuit<Fr> for MockBatchCircuit {
+    type Config = MockConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        meta.set_minimum_degree(4);
+
+        let challenges = Challenges::construct(meta);
+        let rlc_config = RlcConfig::configure(meta, challenges);
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        MockConfig {
+            rlc_config,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let mut first_pass = SKIP_FIRST_PASS;
+
+        let cells = layouter.assign_region(
+            || "mock circuit",
+            |mut region| -> Result<Vec<AssignedCell<Fr, Fr>>, Error> {
+                if first_pass {
+                    first_pass = false;
+                    return Ok(vec![]);
+                }
+                let mut index = 0;
+                self.instances()[0]
+                    .iter()
+                    .map(|value| {
+                        config
+                            .rlc_config
+                            .load_private(&mut region, value, &mut index)
+                    })
+                    .collect()
+            },
+        )?;
+
+        for (i, cell) in cells.into_iter().enumerate() {
+            layouter.constrain_instance(cell.cell(), config.instance, i)?;
+        }
+        Ok(())
+    }
+}
+
+impl CircuitExt<Fr> for MockBatchCircuit {
+    /// 12 elements from accumulator and 32 elements from digest
+    fn num_instance(&self) -> Vec<usize> {
+        vec![ACC_LEN + DIGEST_LEN]
+    }
+
+    /// return vec![acc | public input hash]
+    fn instances(&self) -> Vec<Vec<Fr>> {
+        vec![iter::repeat(0)
+            .take(ACC_LEN)
+            .chain(self.public_input_hash.as_bytes().iter().copied())
+            .map(|x| Fr::from(x as u64))
+            .collect()]
+    }
+}
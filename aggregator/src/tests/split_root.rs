@@ -0,0 +1,111 @@
+use ark_std::test_rng;
+use halo2_proofs::{
+    dev::MockProver,
+    halo2curves::bn256::{Bn256, Fr},
+    poly::kzg::commitment::ParamsKZG,
+};
+use itertools::Itertools;
+use rand::{rngs::StdRng, SeedableRng};
+use snark_verifier::loader::halo2::halo2_ecc::halo2_base::utils::fs::gen_srs;
+use snark_verifier_sdk::{gen_pk, gen_snark_shplonk, CircuitExt, Snark};
+use zkevm_circuits::{exp_circuit::ExpCircuit, super_circuit::DetachedCircuit};
+
+use crate::{table_challenges, SplitRootCircuit};
+
+type DetachedExpCircuit = DetachedCircuit<ExpCircuit<Fr>>;
+
+// Prove the detached exp circuits with the given rng seeds.
+fn gen_snarks(params: &ParamsKZG<Bn256>, circuits: &[DetachedExpCircuit]) -> Vec<Snark> {
+    let pk = gen_pk(params, &circuits[0], None);
+    circuits
+        .iter()
+        .enumerate()
+        .map(|(seed, circuit)| {
+            let mut rng = StdRng::seed_from_u64(seed as u64);
+            gen_snark_shplonk(params, &pk, circuit.clone(), &mut rng, None::<String>)
+        })
+        .collect()
+}
+
+// Prove the detached exp circuits with placeholder challenges, then again with the challenges
+// squeezed from their tables.
+fn gen_snarks_with_table_challenges(
+    params: &ParamsKZG<Bn256>,
+    circuits: Vec<DetachedExpCircuit>,
+    table_commitments: &[Vec<usize>],
+) -> (Vec<Snark>, Vec<Snark>) {
+    let placeholder_snarks = gen_snarks(params, &circuits);
+    let challenges = table_challenges(params, &placeholder_snarks, table_commitments);
+    let circuits = circuits
+        .into_iter()
+        .map(|mut circuit| {
+            circuit.challenges = challenges;
+            circuit
+        })
+        .collect_vec();
+    let snarks = gen_snarks(params, &circuits);
+    // the commitments of the tables don't change
+    assert_eq!(
+        table_challenges(params, &snarks, table_commitments),
+        challenges
+    );
+    (placeholder_snarks, snarks)
+}
+
+#[ignore = "it takes too much time"]
+#[test]
+fn test_split_root_table_challenges() {
+    // the u16 table of the exp circuit takes 2^16 rows
+    let params = gen_srs(17);
+    let mut rng = test_rng();
+    // a detached exp circuit stands for the split super circuit, with a single fingerprint
+    let table_commitments = vec![DetachedExpCircuit::table_commitment_indices(); 2];
+    let circuit = DetachedExpCircuit {
+        circuit: ExpCircuit::new(vec![], 100),
+        challenges: Default::default(),
+    };
+
+    // the snarks load the same table
+    let (placeholder_snarks, snarks) = gen_snarks_with_table_challenges(
+        &params,
+        vec![circuit.clone(), circuit.clone()],
+        &table_commitments,
+    );
+    let root_circuit = SplitRootCircuit::new(
+        &params,
+        snarks[0].clone(),
+        snarks[1..].to_vec(),
+        table_commitments.clone(),
+        &mut rng,
+    )
+    .unwrap();
+    let instances = root_circuit.instances();
+    let mock_prover = MockProver::<Fr>::run(19, &root_circuit, instances).unwrap();
+    mock_prover.assert_satisfied_par();
+
+    // the challenges must be squeezed from the tables
+    assert!(SplitRootCircuit::new(
+        &params,
+        placeholder_snarks[0].clone(),
+        placeholder_snarks[1..].to_vec(),
+        table_commitments.clone(),
+        &mut rng,
+    )
+    .is_err());
+
+    // the snarks load different tables, with the padding of a larger circuit
+    let other_circuit = DetachedExpCircuit {
+        circuit: ExpCircuit::new(vec![], 200),
+        challenges: Default::default(),
+    };
+    let (_, snarks) =
+        gen_snarks_with_table_challenges(&params, vec![circuit, other_circuit], &table_commitments);
+    assert!(SplitRootCircuit::new(
+        &params,
+        snarks[0].clone(),
+        snarks[1..].to_vec(),
+        table_commitments,
+        &mut rng,
+    )
+    .is_err());
+}
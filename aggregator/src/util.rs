@@ -1,6 +1,6 @@
 use crate::{
     aggregation::RlcConfig,
-    constants::{DIGEST_LEN, INPUT_LEN_PER_ROUND, MAX_AGG_SNARKS},
+    constants::{BATCH_PI_HASH_INDEX, DIGEST_LEN, INPUT_LEN_PER_ROUND},
 };
 use eth_types::Field;
 use halo2_proofs::{
@@ -33,21 +33,34 @@ pub(crate) fn get_max_keccak_updates(max_snarks: usize) -> usize {
 
     pi_rounds + chunk_hash_rounds + data_hash_rounds
 }
-pub(crate) fn get_data_hash_keccak_updates(max_snarks: usize) -> usize {
-    let data_hash_rounds = (32 * max_snarks) / INPUT_LEN_PER_ROUND;
-    let padding_round = if data_hash_rounds * INPUT_LEN_PER_ROUND < 32 * max_snarks {
-        1
-    } else {
-        0
-    };
+// Calculates the keccak updates needed for the batch data hash of the given
+// number of chunks. Keccak always pads at least one byte, so an input of
+// k * INPUT_LEN_PER_ROUND bytes takes k + 1 updates.
+pub(crate) fn get_data_hash_keccak_updates(num_snarks: usize) -> usize {
+    (DIGEST_LEN * num_snarks) / INPUT_LEN_PER_ROUND + 1
+}
+
+// Calculates the keccak updates needed for a bundle of the given number of
+// batches: 2 updates for the public input hash of each batch, followed by
+// the updates for the bundle's public input hash.
+pub(crate) fn get_bundle_keccak_updates(num_batches: usize) -> usize {
+    2 * num_batches + get_bundle_pi_hash_keccak_updates(num_batches)
+}
 
-    data_hash_rounds + padding_round
+// Calculates the keccak updates needed for the public input hash of a bundle
+// of the given number of batches, whose input has
+// BATCH_PI_HASH_INDEX + 32 * num_batches bytes.
+pub(crate) fn get_bundle_pi_hash_keccak_updates(num_batches: usize) -> usize {
+    (BATCH_PI_HASH_INDEX + DIGEST_LEN * num_batches) / INPUT_LEN_PER_ROUND + 1
 }
 
 /// Return
 /// - the indices of the rows that contain the input preimages
 /// - the indices of the rows that contain the output digest
-pub(crate) fn get_indices(preimages: &[Vec<u8>]) -> (Vec<usize>, Vec<usize>) {
+pub(crate) fn get_indices(
+    preimages: &[Vec<u8>],
+    max_agg_snarks: usize,
+) -> (Vec<usize>, Vec<usize>) {
     let mut preimage_indices = vec![];
     let mut digest_indices = vec![];
     let mut round_ctr = 0;
@@ -55,43 +68,16 @@ pub(crate) fn get_indices(preimages: &[Vec<u8>]) -> (Vec<usize>, Vec<usize>) {
     let keccak_f_rows = get_num_rows_per_update();
     let inner_round_rows = get_num_rows_per_round();
 
-    for preimage in preimages.iter().take(MAX_AGG_SNARKS + 1) {
-        //  136 = 17 * 8 is the size in bytes of each
-        //  input chunk that can be processed by Keccak circuit using absorb
-
-        //  For example, if num_rows_per_inner_round for Keccak is 12, then
-        //  each chunk of size 136 needs 300 Keccak circuit rows to prove
-        //  which consists of 12 Keccak rows for each of 24 + 1 Keccak circuit rounds
-        //  digest only happens at the end of the last input chunk with
-        //  4 Keccak circuit rounds, so 48 Keccak rows, and 300 - 48 = 252
-        let num_rounds = 1 + preimage.len() / INPUT_LEN_PER_ROUND;
-        let mut preimage_padded = preimage.clone();
-        preimage_padded.resize(INPUT_LEN_PER_ROUND * num_rounds, 0);
-        for (i, round) in preimage_padded.chunks(INPUT_LEN_PER_ROUND).enumerate() {
-            let f_round_offset = round_ctr * keccak_f_rows;
-            // indices for preimages
-            for (j, _chunk) in round.chunks(8).into_iter().enumerate() {
-                let inner_offset = f_round_offset + (j + 1) * inner_round_rows;
-                for k in 0..8 {
-                    preimage_indices.push(inner_offset + k);
-                }
-            }
-            // indices for digests
-            if i == num_rounds - 1 {
-                for j in 0..4 {
-                    let inner_offset = f_round_offset
-                        + j * inner_round_rows
-                        + (keccak_f_rows - inner_round_rows * (DIGEST_LEN / 8));
-                    for k in 0..8 {
-                        digest_indices.push(inner_offset + k);
-                    }
-                }
-            }
-            round_ctr += 1;
-        }
+    for preimage in preimages.iter().take(max_agg_snarks + 1) {
+        push_hash_indices(
+            preimage.len(),
+            &mut round_ctr,
+            &mut preimage_indices,
+            &mut digest_indices,
+        );
     }
     // last hash is for data_hash and has various length, so we output all the possible cells
-    for _i in 0..get_data_hash_keccak_updates(MAX_AGG_SNARKS) {
+    for _i in 0..get_data_hash_keccak_updates(max_agg_snarks) {
         for (j, _) in (0..INPUT_LEN_PER_ROUND)
             .into_iter()
             .chunks(8)
@@ -120,6 +106,76 @@ pub(crate) fn get_indices(preimages: &[Vec<u8>]) -> (Vec<usize>, Vec<usize>) {
     (preimage_indices, digest_indices)
 }
 
+/// Return
+/// - the indices of the rows that contain the input preimages
+/// - the indices of the rows that contain the output digest
+///
+/// for fixed length preimages, i.e. the ones of a bundle: the public input
+/// hash of each batch, followed by the bundle's one.
+pub(crate) fn get_bundle_indices(preimages: &[Vec<u8>]) -> (Vec<usize>, Vec<usize>) {
+    let mut preimage_indices = vec![];
+    let mut digest_indices = vec![];
+    let mut round_ctr = 0;
+
+    for preimage in preimages.iter() {
+        push_hash_indices(
+            preimage.len(),
+            &mut round_ctr,
+            &mut preimage_indices,
+            &mut digest_indices,
+        );
+    }
+
+    debug_assert!(is_ascending(&preimage_indices));
+    debug_assert!(is_ascending(&digest_indices));
+
+    (preimage_indices, digest_indices)
+}
+
+// Push the indices of the rows of the preimage (padding included) and of the
+// digest of a hash of the given length, which starts at the round_ctr-th
+// keccak-f update.
+fn push_hash_indices(
+    preimage_len: usize,
+    round_ctr: &mut usize,
+    preimage_indices: &mut Vec<usize>,
+    digest_indices: &mut Vec<usize>,
+) {
+    let keccak_f_rows = get_num_rows_per_update();
+    let inner_round_rows = get_num_rows_per_round();
+    //  136 = 17 * 8 is the size in bytes of each
+    //  input chunk that can be processed by Keccak circuit using absorb
+
+    //  For example, if num_rows_per_inner_round for Keccak is 12, then
+    //  each chunk of size 136 needs 300 Keccak circuit rows to prove
+    //  which consists of 12 Keccak rows for each of 24 + 1 Keccak circuit rounds
+    //  digest only happens at the end of the last input chunk with
+    //  4 Keccak circuit rounds, so 48 Keccak rows, and 300 - 48 = 252
+    let num_rounds = 1 + preimage_len / INPUT_LEN_PER_ROUND;
+    for i in 0..num_rounds {
+        let f_round_offset = *round_ctr * keccak_f_rows;
+        // indices for preimages
+        for j in 0..INPUT_LEN_PER_ROUND / 8 {
+            let inner_offset = f_round_offset + (j + 1) * inner_round_rows;
+            for k in 0..8 {
+                preimage_indices.push(inner_offset + k);
+            }
+        }
+        // indices for digests
+        if i == num_rounds - 1 {
+            for j in 0..4 {
+                let inner_offset = f_round_offset
+                    + j * inner_round_rows
+                    + (keccak_f_rows - inner_round_rows * (DIGEST_LEN / 8));
+                for k in 0..8 {
+                    digest_indices.push(inner_offset + k);
+                }
+            }
+        }
+        *round_ctr += 1;
+    }
+}
+
 #[inline]
 // assert two cells have same value
 // (NOT constraining equality in circuit)
@@ -156,24 +212,21 @@ pub(crate) fn assert_conditional_equal<F: Field>(
 }
 
 #[inline]
-// assert a \in (b1, b2, b3)
-pub(crate) fn assert_exist<F: Field>(
-    a: &AssignedCell<F, F>,
-    b1: &AssignedCell<F, F>,
-    b2: &AssignedCell<F, F>,
-    b3: &AssignedCell<F, F>,
-) {
-    let mut t1 = F::default();
-    let mut t2 = F::default();
-    let mut t3 = F::default();
-    let mut t4 = F::default();
-    a.value().map(|f| t1 = *f);
-    b1.value().map(|f| t2 = *f);
-    b2.value().map(|f| t3 = *f);
-    b3.value().map(|f| t4 = *f);
+// assert a \in bs
+pub(crate) fn assert_exist<F: Field>(a: &AssignedCell<F, F>, bs: &[&AssignedCell<F, F>]) {
+    let mut t = F::default();
+    a.value().map(|f| t = *f);
+    let candidates = bs
+        .iter()
+        .map(|b| {
+            let mut c = F::default();
+            b.value().map(|f| c = *f);
+            c
+        })
+        .collect::<Vec<_>>();
     assert!(
-        t1 == t2 || t1 == t3 || t1 == t4,
-        "t1: {t1:?}\nt2: {t2:?}\nt3: {t3:?}\nt4: {t4:?}\n",
+        candidates.contains(&t),
+        "a: {t:?}\ncandidates: {candidates:?}\n",
     )
 }
 
@@ -216,6 +269,7 @@ pub(crate) fn assigned_value_to_cell(
 #[allow(clippy::type_complexity)]
 pub(crate) fn parse_hash_preimage_cells(
     hash_input_cells: &[AssignedCell<Fr, Fr>],
+    max_agg_snarks: usize,
 ) -> (
     &[AssignedCell<Fr, Fr>],
     Vec<&[AssignedCell<Fr, Fr>]>,
@@ -226,13 +280,13 @@ pub(crate) fn parse_hash_preimage_cells(
     // we extract all those bytes
     let batch_pi_hash_preimage = &hash_input_cells[0..INPUT_LEN_PER_ROUND * 2];
     let mut chunk_pi_hash_preimages = vec![];
-    for i in 0..MAX_AGG_SNARKS {
+    for i in 0..max_agg_snarks {
         chunk_pi_hash_preimages.push(
             &hash_input_cells[INPUT_LEN_PER_ROUND * 2 * (i + 1)..INPUT_LEN_PER_ROUND * 2 * (i + 2)],
         );
     }
     let potential_batch_data_hash_preimage =
-        &hash_input_cells[INPUT_LEN_PER_ROUND * 2 * (MAX_AGG_SNARKS + 1)..];
+        &hash_input_cells[INPUT_LEN_PER_ROUND * 2 * (max_agg_snarks + 1)..];
 
     (
         batch_pi_hash_preimage,
@@ -245,6 +299,7 @@ pub(crate) fn parse_hash_preimage_cells(
 #[allow(clippy::type_complexity)]
 pub(crate) fn parse_hash_digest_cells(
     hash_output_cells: &[AssignedCell<Fr, Fr>],
+    max_agg_snarks: usize,
 ) -> (
     &[AssignedCell<Fr, Fr>],
     Vec<&[AssignedCell<Fr, Fr>]>,
@@ -252,10 +307,10 @@ pub(crate) fn parse_hash_digest_cells(
 ) {
     let batch_pi_hash_digest = &hash_output_cells[0..DIGEST_LEN];
     let mut chunk_pi_hash_digests = vec![];
-    for i in 0..MAX_AGG_SNARKS {
+    for i in 0..max_agg_snarks {
         chunk_pi_hash_digests.push(&hash_output_cells[DIGEST_LEN * (i + 1)..DIGEST_LEN * (i + 2)]);
     }
-    let potential_batch_data_hash_digest = &hash_output_cells[DIGEST_LEN * (MAX_AGG_SNARKS + 1)..];
+    let potential_batch_data_hash_digest = &hash_output_cells[DIGEST_LEN * (max_agg_snarks + 1)..];
     (
         batch_pi_hash_digest,
         chunk_pi_hash_digests,
@@ -267,17 +322,49 @@ pub(crate) fn parse_hash_digest_cells(
 #[allow(clippy::type_complexity)]
 pub(crate) fn parse_pi_hash_rlc_cells(
     data_rlc_cells: &[AssignedCell<Fr, Fr>],
+    max_agg_snarks: usize,
 ) -> Vec<&AssignedCell<Fr, Fr>> {
     data_rlc_cells
         .iter()
         .skip(3) // the first 3 rlc cells are pad (1) + batch pi hash (2)
-        .take(MAX_AGG_SNARKS * 2) // each chunk hash takes 2 rounds
+        .take(max_agg_snarks * 2) // each chunk hash takes 2 rounds
         .chunks(2)
         .into_iter()
         .map(|t| t.last().unwrap())
         .collect()
 }
 
+#[inline]
+#[allow(clippy::type_complexity)]
+pub(crate) fn parse_bundle_hash_preimage_cells(
+    hash_input_cells: &[AssignedCell<Fr, Fr>],
+    num_batches: usize,
+) -> (Vec<&[AssignedCell<Fr, Fr>]>, &[AssignedCell<Fr, Fr>]) {
+    // each batch pi hash has INPUT_LEN_PER_ROUND bytes as input, which keccak
+    // pads with another INPUT_LEN_PER_ROUND bytes
+    let batch_pi_hash_preimages = (0..num_batches)
+        .map(|i| &hash_input_cells[INPUT_LEN_PER_ROUND * 2 * i..INPUT_LEN_PER_ROUND * 2 * (i + 1)])
+        .collect();
+    let bundle_pi_hash_preimage = &hash_input_cells[INPUT_LEN_PER_ROUND * 2 * num_batches..];
+
+    (batch_pi_hash_preimages, bundle_pi_hash_preimage)
+}
+
+#[inline]
+#[allow(clippy::type_complexity)]
+pub(crate) fn parse_bundle_hash_digest_cells(
+    hash_output_cells: &[AssignedCell<Fr, Fr>],
+    num_batches: usize,
+) -> (Vec<&[AssignedCell<Fr, Fr>]>, &[AssignedCell<Fr, Fr>]) {
+    let batch_pi_hash_digests = (0..num_batches)
+        .map(|i| &hash_output_cells[DIGEST_LEN * i..DIGEST_LEN * (i + 1)])
+        .collect();
+    let bundle_pi_hash_digest =
+        &hash_output_cells[DIGEST_LEN * num_batches..DIGEST_LEN * (num_batches + 1)];
+
+    (batch_pi_hash_digests, bundle_pi_hash_digest)
+}
+
 #[allow(dead_code)]
 pub(crate) fn rlc(inputs: &[Fr], randomness: &Fr) -> Fr {
     assert!(!inputs.is_empty());
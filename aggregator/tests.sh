@@ -6,3 +6,6 @@ RUST_LOG=trace MODE=greeter cargo test --release --features=print-trace test_moc
 # RUST_LOG=trace MODE=greeter cargo test --release --features=print-trace test_aggregation_circuit -- --ignored --nocapture 2>&1 | tee aggregation.log
 # RUST_LOG=trace MODE=greeter cargo test --release --features=print-trace test_two_layer_proof_compression -- --ignored --nocapture 2>&1 | tee compression_2_layer.log
 # RUST_LOG=trace MODE=greeter cargo test --release --features=print-trace test_e2e -- --ignored --nocapture 2>&1 | tee aggregation_e2e.log
+# RUST_LOG=trace MODE=greeter cargo test --release --features=print-trace test_compression_evm_verifier -- --ignored --nocapture 2>&1 | tee evm_verifier.log
+# RUST_LOG=trace MODE=greeter cargo test --release --features=print-trace test_aggregation_evm_verifier -- --ignored --nocapture 2>&1 | tee aggregation_evm_verifier.log
+# RUST_LOG=trace MODE=greeter cargo test --release --features=print-trace test_bundle_evm_verifier -- --ignored --nocapture 2>&1 | tee bundle_evm_verifier.log
@@ -25,7 +25,7 @@ pub use call::{Call, CallContext, CallKind};
 use core::fmt::Debug;
 use eth_types::{
     self,
-    evm_types::OpcodeId,
+    evm_types::{delegation_code, ChainSpec, OpcodeId},
     geth_types,
     sign_types::{pk_bytes_le, pk_bytes_swap_endianness, SignData},
     Address, GethExecStep, GethExecTrace, ToBigEndian, ToWord, Word, H256, U256,
@@ -38,7 +38,8 @@ use ethers_providers::JsonRpcClient;
 pub use execution::{
     BigModExp, CopyBytes, CopyDataType, CopyEvent, CopyEventStepsBuilder, CopyStep, EcAddOp,
     EcMulOp, EcPairingOp, EcPairingPair, ExecState, ExecStep, ExpEvent, ExpStep, NumberOrHash,
-    PrecompileEvent, PrecompileEvents, N_BYTES_PER_PAIR, N_PAIRING_PER_OP,
+    PrecompileEvent, PrecompileEvents, BLAKE2F, N_BYTES_PER_PAIR, N_PAIRING_PER_OP, RIPEMD160,
+    SHA256,
 };
 use hex::decode_to_slice;
 
@@ -46,16 +47,18 @@ use ethers_core::utils::keccak256;
 pub use input_state_ref::CircuitInputStateRef;
 use itertools::Itertools;
 use log::warn;
+use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, HashMap},
     iter,
 };
 pub use transaction::{
-    Transaction, TransactionContext, TxL1Fee, TX_L1_COMMIT_EXTRA_COST, TX_L1_FEE_PRECISION,
+    AppliedAuthorization, Transaction, TransactionContext, TxL1Fee, TX_L1_COMMIT_EXTRA_COST,
+    TX_L1_FEE_PRECISION,
 };
 
 /// Setup parameters for ECC-related precompile calls.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct PrecompileEcParams {
     /// Maximum number of EcAdd ops supported in one block.
     pub ec_add: usize,
@@ -63,6 +66,9 @@ pub struct PrecompileEcParams {
     pub ec_mul: usize,
     /// Maximum number of EcPairing ops supported in one block.
     pub ec_pairing: usize,
+    /// Maximum number of Miller loop chunks of `N_PAIRING_PER_OP` (G1, G2) pairs, summed over
+    /// all EcPairing ops in one block.
+    pub ec_pairing_chunks: usize,
 }
 
 impl Default for PrecompileEcParams {
@@ -71,12 +77,13 @@ impl Default for PrecompileEcParams {
             ec_add: 50,
             ec_mul: 50,
             ec_pairing: 2,
+            ec_pairing_chunks: 2,
         }
     }
 }
 
 /// Circuit Setup Parameters
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct CircuitsParams {
     /// Maximum number of rw operations in the state circuit (RwTable length /
     /// number of rows). This must be at least the number of rw operations
@@ -114,6 +121,13 @@ pub struct CircuitsParams {
     pub max_keccak_rows: usize,
     /// Max number of ECC-related ops supported in the ECC circuit.
     pub max_ec_ops: PrecompileEcParams,
+    /// Max number of ECDSA signatures verified in the Sig circuit, shared by
+    /// the tx signatures, the EIP-7702 authorizations and the ecrecover calls.
+    /// One of them is reserved for the padding tx.
+    pub max_num_sig: usize,
+    /// Hard fork schedule of the chain, which selects the EVM rules of each
+    /// block.
+    pub chain_spec: ChainSpec,
 }
 
 impl Default for CircuitsParams {
@@ -134,6 +148,8 @@ impl Default for CircuitsParams {
             max_keccak_rows: 0,
             max_rlp_rows: 1000,
             max_ec_ops: PrecompileEcParams::default(),
+            max_num_sig: 128,
+            chain_spec: ChainSpec::default(),
         }
     }
 }
@@ -156,7 +172,7 @@ impl Default for CircuitsParams {
 /// [`OpcodeId`](crate::evm::OpcodeId)s used in each `ExecTrace` step so that
 /// the State Proof witnesses are already generated on a structured manner and
 /// ready to be added into the State circuit.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CircuitInputBuilder {
     /// StateDB key-value DB
     pub sdb: StateDB,
@@ -384,6 +400,14 @@ impl<'a> CircuitInputBuilder {
         );
         log::debug!("tx_receipt num: {}", self.block.container.tx_receipt.len());
         log::debug!("tx_log num: {}", self.block.container.tx_log.len());
+        log::debug!(
+            "transient_storage num: {}",
+            self.block.container.transient_storage.len()
+        );
+        log::debug!(
+            "tx_created_account num: {}",
+            self.block.container.tx_created_account.len()
+        );
         log::debug!("start num: {}", self.block.container.start.len());
     }
 
@@ -455,6 +479,26 @@ impl<'a> CircuitInputBuilder {
                 return Err(Error::InternalError("rws not enough"));
             };
         }
+        // The tx signatures, the EIP-7702 authorizations and the ecrecover calls
+        // share the capacity of the Sig circuit, where one signature is reserved
+        // for the padding tx.
+        let num_sig = state
+            .block
+            .txs
+            .iter()
+            .filter(|tx| !tx.tx_type.is_l1_msg())
+            .map(|tx| 1 + tx.authorization_list.len())
+            .sum::<usize>()
+            + state.block.precompile_events.get_ecrecover_events().len();
+        let max_num_sig = state.block.circuits_params.max_num_sig;
+        if num_sig + 1 > max_num_sig {
+            log::error!(
+                "num_sig + 1 > max_num_sig, num_sig={}, max_num_sig={}",
+                num_sig,
+                max_num_sig
+            );
+            return Err(Error::InternalError("signatures not enough"));
+        }
         push_op(&mut end_block_last, RWCounter(1), RW::READ, StartOp {});
         push_op(
             &mut end_block_last,
@@ -643,6 +687,23 @@ pub fn keccak_inputs(block: &Block, code_db: &CodeDB) -> Result<Vec<Vec<u8>>, Er
     keccak_inputs.extend_from_slice(&keccak_inputs_sign_verify(
         &block.precompile_events.get_ecrecover_events(),
     ));
+    // Authorizations of EIP-7702 txs
+    let authorization_sign_datas: Vec<SignData> = block
+        .txs
+        .iter()
+        .flat_map(|tx| tx.authorization_list.iter())
+        .filter_map(|auth| auth.sign_data().ok())
+        .collect();
+    keccak_inputs.extend_from_slice(&keccak_inputs_sign_verify(&authorization_sign_datas));
+    // The delegation codes that the authorizations may set
+    keccak_inputs.extend(
+        block
+            .txs
+            .iter()
+            .flat_map(|tx| tx.authorization_list.iter())
+            .filter(|auth| !auth.address.is_zero())
+            .map(|auth| delegation_code(auth.address)),
+    );
     log::debug!(
         "keccak total len after ecrecover: {}",
         keccak_inputs.iter().map(|i| i.len()).sum::<usize>()
@@ -9,11 +9,15 @@ use crate::{
     operation::{OperationContainer, RWCounter},
     Error,
 };
-use eth_types::{Address, Hash, ToWord, Word};
+use eth_types::{
+    evm_types::{block_utils::calculate_blob_base_fee, Hardfork},
+    geth_types::get_excess_blob_gas,
+    Address, Hash, ToWord, Word,
+};
 use std::collections::{BTreeMap, HashMap};
 
 /// Context of a [`Block`] which can mutate in a [`Transaction`].
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BlockContext {
     /// Used to track the global counter in every operation in the block.
     /// Contains the next available value.
@@ -88,6 +92,10 @@ pub struct BlockHead {
     pub difficulty: Word,
     /// base fee
     pub base_fee: Word,
+    /// excess blob gas
+    pub excess_blob_gas: u64,
+    /// blob base fee, derived from the excess blob gas
+    pub blob_base_fee: Word,
     /// start l1 queue index
     pub start_l1_queue_index: u64,
     /// Original block from geth
@@ -131,6 +139,8 @@ impl BlockHead {
                 eth_block.difficulty
             },
             base_fee: eth_block.base_fee_per_gas.unwrap_or_default(),
+            excess_blob_gas: get_excess_blob_gas(eth_block),
+            blob_base_fee: calculate_blob_base_fee(get_excess_blob_gas(eth_block)),
             eth_block: eth_block.clone(),
         })
     }
@@ -173,6 +183,8 @@ impl BlockHead {
                 eth_block.difficulty
             },
             base_fee: eth_block.base_fee_per_gas.unwrap_or_default(),
+            excess_blob_gas: get_excess_blob_gas(eth_block),
+            blob_base_fee: calculate_blob_base_fee(get_excess_blob_gas(eth_block)),
             eth_block: eth_block.clone(),
         })
     }
@@ -309,6 +321,14 @@ impl Block {
         self.chain_id
     }
 
+    /// Return the hard fork active in the block with the given number.
+    pub fn hardfork(&self, block_num: u64) -> Hardfork {
+        let header = &self.headers[&block_num];
+        self.circuits_params
+            .chain_spec
+            .hardfork_at(block_num, header.timestamp.as_u64())
+    }
+
     /// ..
     pub fn end_state_root(&self) -> Word {
         self.headers
@@ -10,7 +10,7 @@ use crate::{
     error::ExecError,
     exec_trace::OperationRef,
     operation::RWCounter,
-    precompile::{PrecompileAuxData, PrecompileCalls},
+    precompile::{PrecompileAuxData, PrecompileCalls, MODEXP_SIZE_LIMIT},
 };
 use eth_types::{
     evm_types::{memory::MemoryWordRange, Gas, GasCost, MemoryAddress, OpcodeId, ProgramCounter},
@@ -27,6 +27,7 @@ use halo2_proofs::{
     },
     plonk::Expression,
 };
+use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
 
 /// An execution step of the EVM.
@@ -190,7 +191,7 @@ impl ExecState {
 }
 
 /// Defines the various source/destination types for a copy event.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CopyDataType {
     /// When we need to pad the Copy rows of the circuit up to a certain maximum
     /// with rows that are not "useful".
@@ -349,7 +350,7 @@ pub struct CopyStep {
 }
 
 /// Defines an enum type that can hold either a number or a hash value.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum NumberOrHash {
     /// Variant to indicate a number value.
     Number(usize),
@@ -368,7 +369,7 @@ pub enum NumberOrHash {
 ///
 /// Additionally, when the destination is memory, `bytes_write_prev` holds the memory content
 /// *before* the write.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CopyBytes {
     /// Represents the list of (bytes, is_code, mask) copied during this copy event
     pub bytes: Vec<(u8, bool, bool)>,
@@ -398,7 +399,7 @@ impl CopyBytes {
 /// Defines a copy event associated with EVM opcodes such as CALLDATACOPY,
 /// CODECOPY, CREATE, etc. More information:
 /// <https://github.com/privacy-scaling-explorations/zkevm-specs/blob/master/specs/copy-proof.md>.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CopyEvent {
     /// Represents the start address at the source of the copy event.
     pub src_addr: u64,
@@ -448,6 +449,15 @@ impl CopyEvent {
         self.dst_type == CopyDataType::Memory || self.dst_type == CopyDataType::TxLog
     }
 
+    /// Whether the source and destination are the memory of the same call, as
+    /// in MCOPY. All source words are then read before any destination word
+    /// is written, so that overlapping ranges copy the original data.
+    pub fn is_memory_copy(&self) -> bool {
+        self.src_type == CopyDataType::Memory
+            && self.dst_type == CopyDataType::Memory
+            && self.src_id == self.dst_id
+    }
+
     /// The RW counter of the first RW lookup performed by this copy event.
     pub fn rw_counter_start(&self) -> u64 {
         usize::from(self.rw_counter_start) as u64
@@ -778,7 +788,7 @@ where
 }
 
 /// Intermediary multiplication step, representing `a * b == d (mod 2^256)`
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ExpStep {
     /// First multiplicand.
     pub a: Word,
@@ -799,7 +809,7 @@ impl From<(Word, Word, Word)> for ExpStep {
 }
 
 /// Event representating an exponentiation `a ^ b == d (mod 2^256)`.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ExpEvent {
     /// Identifier for the exponentiation trace.
     pub identifier: usize,
@@ -830,7 +840,7 @@ impl Default for ExpEvent {
 }
 
 /// I/Os from all precompiled contract calls in a block.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct PrecompileEvents {
     /// All events.
     pub events: Vec<PrecompileEvent>,
@@ -907,10 +917,52 @@ impl PrecompileEvents {
             .cloned()
             .collect()
     }
+    /// Get all SHA256 events.
+    pub fn get_sha256_events(&self) -> Vec<SHA256> {
+        self.events
+            .iter()
+            .filter_map(|e| {
+                if let PrecompileEvent::SHA256(op) = e {
+                    Some(op)
+                } else {
+                    None
+                }
+            })
+            .cloned()
+            .collect()
+    }
+    /// Get all RIPEMD160 events.
+    pub fn get_ripemd160_events(&self) -> Vec<RIPEMD160> {
+        self.events
+            .iter()
+            .filter_map(|e| {
+                if let PrecompileEvent::RIPEMD160(op) = e {
+                    Some(op)
+                } else {
+                    None
+                }
+            })
+            .cloned()
+            .collect()
+    }
+    /// Get all BLAKE2F events.
+    pub fn get_blake2f_events(&self) -> Vec<BLAKE2F> {
+        self.events
+            .iter()
+            .filter_map(|e| {
+                if let PrecompileEvent::BLAKE2F(op) = e {
+                    Some(op)
+                } else {
+                    None
+                }
+            })
+            .cloned()
+            .collect()
+    }
 }
 
 /// I/O from a precompiled contract call.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum PrecompileEvent {
     /// Represents the I/O from Ecrecover call.
     Ecrecover(SignData),
@@ -922,6 +974,12 @@ pub enum PrecompileEvent {
     EcPairing(Box<EcPairingOp>),
     /// Represents the I/O from Modexp call.
     ModExp(BigModExp),
+    /// Represents the I/O from SHA256 call.
+    SHA256(SHA256),
+    /// Represents the I/O from RIPEMD160 call.
+    RIPEMD160(RIPEMD160),
+    /// Represents the I/O from BLAKE2F call.
+    BLAKE2F(BLAKE2F),
 }
 
 impl Default for PrecompileEvent {
@@ -931,13 +989,16 @@ impl Default for PrecompileEvent {
 }
 
 /// EcAdd operation: P + Q = R
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EcAddOp {
     /// First EC point.
+    #[serde(with = "eth_types::serde_utils::group_encoding")]
     pub p: G1Affine,
     /// Second EC point.
+    #[serde(with = "eth_types::serde_utils::group_encoding")]
     pub q: G1Affine,
     /// Addition of the first and second EC points.
+    #[serde(with = "eth_types::serde_utils::group_encoding")]
     pub r: G1Affine,
 }
 
@@ -999,13 +1060,16 @@ impl EcAddOp {
 }
 
 /// EcMul operation: s.P = R
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EcMulOp {
     /// EC point.
+    #[serde(with = "eth_types::serde_utils::group_encoding")]
     pub p: G1Affine,
     /// Scalar.
+    #[serde(with = "eth_types::serde_utils::field")]
     pub s: Fr,
     /// Result for s.P = R.
+    #[serde(with = "eth_types::serde_utils::group_encoding")]
     pub r: G1Affine,
 }
 
@@ -1072,19 +1136,23 @@ impl EcMulOp {
     }
 }
 
-/// The number of pairing inputs per pairing operation. If the inputs provided to the precompile
-/// call are < 4, we append (G1::infinity, G2::generator) until we have the required no. of inputs.
+/// The number of pairing inputs per Miller loop chunk of a pairing operation. The inputs provided
+/// to the precompile call are split into chunks of 4 pairs, the last chunk being padded with
+/// (G1::infinity, G2::generator), and the Miller loop results of all chunks are accumulated before
+/// a single final exponentiation.
 pub const N_PAIRING_PER_OP: usize = 4;
 
 /// The number of bytes taken to represent a pair (G1, G2).
 pub const N_BYTES_PER_PAIR: usize = 192;
 
 /// Pair of (G1, G2).
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcPairingPair {
     /// G1 point.
+    #[serde(with = "eth_types::serde_utils::group_encoding")]
     pub g1_point: G1Affine,
     /// G2 point.
+    #[serde(with = "eth_types::serde_utils::group_encoding")]
     pub g2_point: G2Affine,
 }
 
@@ -1127,12 +1195,11 @@ impl EcPairingPair {
         Self { g1_point, g2_point }
     }
 
-    /// Padding pair for ECC circuit. The pairing check is done with a constant number
+    /// Padding pair for ECC circuit. The Miller loop is done with a constant number
     /// `N_PAIRING_PER_OP` of (G1, G2) pairs. The ECC circuit under the hood uses halo2-lib to
     /// compute the multi-miller loop, which allows `(G1::Infinity, G2::Generator)` pair to skip
-    /// the loop for that pair. So in case the EVM inputs are less than `N_PAIRING_PER_OP` we pad
-    /// the ECC Circuit inputs by this pair. Any EVM input of `(G1::Infinity, G2)` or
-    /// `(G1, G2::Infinity)` is also transformed into `(G1::Infinity, G2::Generator)`.
+    /// the loop for that pair. So in case the last chunk of EVM inputs has less than
+    /// `N_PAIRING_PER_OP` pairs we pad it by this pair.
     pub fn ecc_padding() -> Self {
         Self {
             g1_point: G1Affine::identity(),
@@ -1140,38 +1207,38 @@ impl EcPairingPair {
         }
     }
 
-    /// Padding pair for EVM circuit. The pairing check is done with a constant number
-    /// `N_PAIRING_PER_OP` of (G1, G2) pairs. In case EVM inputs are less in number, we pad them
-    /// with `(G1::Infinity, G2::Infinity)` for simplicity.
-    pub fn evm_padding() -> Self {
-        Self {
-            g1_point: G1Affine::identity(),
-            g2_point: G2Affine::identity(),
+    /// Whether the pair is skipped by the pairing check, i.e. one of its points is the identity.
+    pub fn is_identity(&self) -> bool {
+        self.g1_point.is_identity().into() || self.g2_point.is_identity().into()
+    }
+
+    /// The pair loaded in the Miller loop of the ECC circuit. Any EVM input of
+    /// `(G1::Infinity, G2)` or `(G1, G2::Infinity)` is transformed into
+    /// `(G1::Infinity, G2::Generator)`.
+    pub fn ecc_pair(&self) -> Self {
+        if self.is_identity() {
+            Self::ecc_padding()
+        } else {
+            *self
         }
     }
 }
 
 /// EcPairing operation
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcPairingOp {
-    /// tuples of G1 and G2 points supplied to the ECC circuit.
-    pub pairs: [EcPairingPair; N_PAIRING_PER_OP],
+    /// tuples of G1 and G2 points supplied to the precompile call.
+    pub pairs: Vec<EcPairingPair>,
     /// Result from the pairing check.
     pub output: Word,
 }
 
 impl Default for EcPairingOp {
+    /// The pairing check of an empty input, which is successful.
     fn default() -> Self {
-        let g1_point = G1Affine::generator();
-        let g2_point = G2Affine::generator();
         Self {
-            pairs: [
-                EcPairingPair { g1_point, g2_point },
-                EcPairingPair { g1_point, g2_point },
-                EcPairingPair { g1_point, g2_point },
-                EcPairingPair { g1_point, g2_point },
-            ],
-            output: Word::zero(),
+            pairs: vec![],
+            output: Word::one(),
         }
     }
 }
@@ -1185,6 +1252,17 @@ impl EcPairingOp {
             .collect::<Vec<u8>>()
     }
 
+    /// Returns the number of (G1, G2) pairs supplied to the pairing check.
+    pub fn n_pairs(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Returns the number of Miller loop chunks of `N_PAIRING_PER_OP` pairs the ECC circuit uses
+    /// for this op. An empty input doesn't use any chunk.
+    pub fn n_chunks(&self) -> usize {
+        (self.pairs.len() + N_PAIRING_PER_OP - 1) / N_PAIRING_PER_OP
+    }
+
     /// A check on the op to tell the ECC Circuit whether or not to skip the op.
     pub fn skip_by_ecc_circuit(&self) -> bool {
         false
@@ -1192,25 +1270,80 @@ impl EcPairingOp {
 }
 
 /// Event representating an exponentiation `a ^ b == d (mod m)` in precompile modexp.
-#[derive(Clone, Debug)]
+///
+/// The integers are in big-endian bytes, left-padded with zeroes to
+/// [`MODEXP_SIZE_LIMIT`] bytes. The default event has a zero modulus, so its
+/// result is zero.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BigModExp {
     /// Base `a` for the exponentiation.
-    pub base: Word,
+    #[serde(with = "eth_types::serde_utils::byte_array")]
+    pub base: [u8; MODEXP_SIZE_LIMIT],
     /// Exponent `b` for the exponentiation.
-    pub exponent: Word,
+    #[serde(with = "eth_types::serde_utils::byte_array")]
+    pub exponent: [u8; MODEXP_SIZE_LIMIT],
     /// Modulus `m`
-    pub modulus: Word,
+    #[serde(with = "eth_types::serde_utils::byte_array")]
+    pub modulus: [u8; MODEXP_SIZE_LIMIT],
     /// Mod exponentiation result.
-    pub result: Word,
+    #[serde(with = "eth_types::serde_utils::byte_array")]
+    pub result: [u8; MODEXP_SIZE_LIMIT],
 }
 
 impl Default for BigModExp {
     fn default() -> Self {
         Self {
-            modulus: 1.into(),
-            base: Default::default(),
-            exponent: Default::default(),
-            result: Default::default(),
+            base: [0u8; MODEXP_SIZE_LIMIT],
+            exponent: [0u8; MODEXP_SIZE_LIMIT],
+            modulus: [0u8; MODEXP_SIZE_LIMIT],
+            result: [0u8; MODEXP_SIZE_LIMIT],
         }
     }
 }
+
+impl BigModExp {
+    /// Create the event of `base ^ exponent (mod modulus)` from the big-endian
+    /// bytes of the integers, of at most [`MODEXP_SIZE_LIMIT`] bytes.
+    pub fn new(base: &[u8], exponent: &[u8], modulus: &[u8], result: &[u8]) -> Self {
+        fn left_pad(bytes: &[u8]) -> [u8; MODEXP_SIZE_LIMIT] {
+            assert!(bytes.len() <= MODEXP_SIZE_LIMIT, "modexp operand too large");
+            let mut padded = [0u8; MODEXP_SIZE_LIMIT];
+            padded[MODEXP_SIZE_LIMIT - bytes.len()..].copy_from_slice(bytes);
+            padded
+        }
+        Self {
+            base: left_pad(base),
+            exponent: left_pad(exponent),
+            modulus: left_pad(modulus),
+            result: left_pad(result),
+        }
+    }
+}
+
+/// Event representating a SHA256 hash in precompile sha256.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SHA256 {
+    /// input bytes
+    pub input: Vec<u8>,
+    /// digest
+    pub digest: [u8; 32],
+}
+
+/// Event representating a RIPEMD160 hash in precompile ripemd160.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RIPEMD160 {
+    /// input bytes
+    pub input: Vec<u8>,
+    /// digest
+    pub digest: [u8; 20],
+}
+
+/// Event representating a compression in precompile blake2f.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BLAKE2F {
+    /// input bytes, `rounds || h || m || t || f`
+    pub input: Vec<u8>,
+    /// output bytes, the new state vector `h`
+    #[serde(with = "eth_types::serde_utils::byte_array")]
+    pub output: [u8; 64],
+}
@@ -16,8 +16,8 @@ use crate::{
     exec_trace::OperationRef,
     operation::{
         AccountField, AccountOp, CallContextField, CallContextOp, MemoryOp, Op, OpEnum, Operation,
-        StackOp, Target, TxAccessListAccountOp, TxLogField, TxLogOp, TxReceiptField, TxReceiptOp,
-        RW,
+        StackOp, Target, TxAccessListAccountOp, TxCreatedAccountOp, TxLogField, TxLogOp,
+        TxReceiptField, TxReceiptOp, RW,
     },
     precompile::is_precompiled,
     state_db::{CodeDB, StateDB},
@@ -28,7 +28,8 @@ use eth_types::{
     evm_types::{
         gas_utils::memory_expansion_gas_cost,
         memory::{MemoryRange, MemoryWordRange},
-        Gas, GasCost, Memory, MemoryAddress, MemoryRef, OpcodeId, StackAddress, MAX_CODE_SIZE,
+        parse_delegation, Gas, GasCost, Memory, MemoryAddress, MemoryRef, OpcodeId, StackAddress,
+        MAX_CODE_SIZE,
     },
     Address, Bytecode, GethExecStep, ToAddress, ToBigEndian, ToWord, Word, H256, U256,
 };
@@ -567,6 +568,24 @@ impl<'a> CircuitInputStateRef<'a> {
         )
     }
 
+    /// Mark address as created in the current transaction.
+    pub fn tx_created_account_write(
+        &mut self,
+        step: &mut ExecStep,
+        address: Address,
+    ) -> Result<(), Error> {
+        let is_created = self.sdb.check_account_created(&address);
+        self.push_op_reversible(
+            step,
+            TxCreatedAccountOp {
+                tx_id: self.tx_ctx.id(),
+                address,
+                is_created: true,
+                is_created_prev: is_created,
+            },
+        )
+    }
+
     /// Push a write type [`TxAccessListAccountOp`] into the
     /// [`OperationContainer`](crate::operation::OperationContainer) with the
     /// next [`RWCounter`](crate::operation::RWCounter), and then
@@ -791,6 +810,19 @@ impl<'a> CircuitInputStateRef<'a> {
             .ok_or(Error::CodeNotFound(code_hash))
     }
 
+    /// Return the address the code of `address` is delegated to, if the account
+    /// carries an EIP-7702 delegation designator.
+    pub fn delegation(&self, address: &Address) -> Option<Address> {
+        let (found, account) = self.sdb.get_account(address);
+        if !found {
+            return None;
+        }
+        self.code_db
+            .0
+            .get(&account.code_hash)
+            .and_then(|code| parse_delegation(code))
+    }
+
     /// Reference to the caller's Call
     pub fn caller(&self) -> Result<&Call, Error> {
         self.tx_ctx
@@ -969,8 +1001,11 @@ impl<'a> CircuitInputStateRef<'a> {
                 if is_precompiled(&code_address) {
                     (CodeSource::Address(code_address), CodeDB::empty_code_hash())
                 } else {
-                    let (found, account) = self.sdb.get_account(&code_address);
-                    if !found {
+                    // A delegated account executes the code of its delegate, and
+                    // delegating to a precompile executes empty code.
+                    let delegate = self.delegation(&code_address);
+                    let (found, account) = self.sdb.get_account(&delegate.unwrap_or(code_address));
+                    if !found || delegate.map_or(false, |delegate| is_precompiled(&delegate)) {
                         (CodeSource::Address(code_address), CodeDB::empty_code_hash())
                     } else {
                         (CodeSource::Address(code_address), account.code_hash)
@@ -1067,6 +1102,22 @@ impl<'a> CircuitInputStateRef<'a> {
                     None
                 }
             }
+            OperationRef(Target::TransientStorage, idx) => {
+                let operation = &self.block.container.transient_storage[*idx];
+                if operation.rw().is_write() && operation.reversible() {
+                    Some(OpEnum::TransientStorage(operation.op().reverse()))
+                } else {
+                    None
+                }
+            }
+            OperationRef(Target::TxCreatedAccount, idx) => {
+                let operation = &self.block.container.tx_created_account[*idx];
+                if operation.rw().is_write() && operation.reversible() {
+                    Some(OpEnum::TxCreatedAccount(operation.op().reverse()))
+                } else {
+                    None
+                }
+            }
             _ => None,
         }
     }
@@ -1099,6 +1150,18 @@ impl<'a> CircuitInputStateRef<'a> {
             OpEnum::TxRefund(op) => {
                 self.sdb.set_refund(op.value);
             }
+            OpEnum::TransientStorage(op) => {
+                self.sdb
+                    .set_transient_storage(&op.address, &op.key, &op.value);
+            }
+            OpEnum::TxCreatedAccount(op) => {
+                if !op.is_created_prev && op.is_created {
+                    self.sdb.add_account_created(op.address);
+                }
+                if op.is_created_prev && !op.is_created {
+                    self.sdb.remove_account_created(&op.address);
+                }
+            }
             _ => unreachable!(),
         };
     }
@@ -1458,7 +1521,8 @@ impl<'a> CircuitInputStateRef<'a> {
         step: &GethExecStep,
         next_step: Option<&GethExecStep>,
     ) -> Result<Option<ExecError>, Error> {
-        if matches!(step.op, OpcodeId::INVALID(_)) {
+        // An opcode activated by a later hard fork is invalid in this block.
+        if !step.op.is_enabled(self.block.hardfork(self.tx.block_num)) {
             return Ok(Some(ExecError::InvalidOpcode));
         }
 
@@ -1511,6 +1575,7 @@ impl<'a> CircuitInputStateRef<'a> {
                     OpcodeId::RETURNDATACOPY => Some(ExecError::ReturnDataOutOfBounds),
                     // Break write protection (CALL with value will be handled below)
                     OpcodeId::SSTORE
+                    | OpcodeId::TSTORE
                     | OpcodeId::CREATE
                     | OpcodeId::CREATE2
                     | OpcodeId::SELFDESTRUCT
@@ -2032,6 +2097,62 @@ impl<'a> CircuitInputStateRef<'a> {
         Ok((read_steps, write_steps, prev_bytes))
     }
 
+    pub(crate) fn gen_copy_steps_for_memory_copy(
+        &mut self,
+        exec_step: &mut ExecStep,
+        src_addr: impl Into<MemoryAddress>,
+        dst_addr: impl Into<MemoryAddress>,
+        copy_length: impl Into<MemoryAddress>,
+    ) -> Result<(CopyEventSteps, CopyEventSteps, Vec<u8>), Error> {
+        let copy_length = copy_length.into().0;
+        if copy_length == 0 {
+            return Ok((vec![], vec![], vec![]));
+        }
+
+        let src_addr = src_addr.into().0;
+        let call_ctx = self.call_ctx_mut()?;
+        // The source range expands the memory as well.
+        call_ctx.memory.extend_at_least(src_addr + copy_length);
+        let memory = call_ctx.memory.clone();
+        let (src_range, dst_range, write_slot_bytes) = combine_copy_slot_bytes(
+            src_addr,
+            dst_addr.into().0,
+            copy_length,
+            &memory.0,
+            &mut call_ctx.memory,
+        );
+        let read_slot_bytes = memory.read_chunk(src_range);
+
+        let read_steps = CopyEventStepsBuilder::memory_range(src_range)
+            .source(read_slot_bytes.as_slice())
+            .build();
+        let write_steps = CopyEventStepsBuilder::memory_range(dst_range)
+            .source(write_slot_bytes.as_slice())
+            .build();
+
+        // Read all the source words before writing any destination word, as the
+        // ranges may overlap.
+        let mut src_chunk_index = src_range.start_slot().0;
+        for _ in read_slot_bytes.chunks(32) {
+            self.memory_read_word(exec_step, src_chunk_index.into())?;
+            src_chunk_index += 32;
+        }
+
+        let mut dst_chunk_index = dst_range.start_slot().0;
+        let mut prev_bytes: Vec<u8> = vec![];
+        for write_chunk in write_slot_bytes.chunks(32) {
+            self.write_chunk_for_copy_step(
+                exec_step,
+                write_chunk,
+                dst_chunk_index,
+                &mut prev_bytes,
+            )?;
+            dst_chunk_index += 32;
+        }
+
+        Ok((read_steps, write_steps, prev_bytes))
+    }
+
     pub(crate) fn gen_copy_steps_for_log(
         &mut self,
         exec_step: &mut ExecStep,
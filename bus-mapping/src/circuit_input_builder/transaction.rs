@@ -3,12 +3,16 @@
 use std::collections::BTreeMap;
 
 use eth_types::{
-    evm_types::{gas_utils::tx_data_gas_cost, Memory},
+    evm_types::{gas_utils::tx_data_gas_cost, Memory, DELEGATION_PREFIX},
     geth_types,
-    geth_types::{get_rlp_unsigned, TxType},
+    geth_types::{
+        get_authorization_list, get_blob_versioned_hashes, get_max_fee_per_blob_gas,
+        get_rlp_signed, get_rlp_unsigned, SetCodeAuthorization, TxType,
+    },
     AccessList, Address, GethExecTrace, Signature, Word, H256,
 };
 use ethers_core::utils::get_contract_address;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     l2_predeployed::l1_gas_price_oracle,
@@ -197,6 +201,33 @@ impl TransactionContext {
     }
 }
 
+/// An authorization of an EIP-7702 tx as applied by BeginTx, with the values
+/// of the rws it takes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppliedAuthorization {
+    /// Account recovered from the signature
+    pub authority: Address,
+    /// Whether the authority was in the access list before
+    pub is_warm_prev: bool,
+    /// Whether the authorization is applied, i.e. its signature, chain id and
+    /// nonce are valid and the code of the authority can be delegated
+    pub is_valid: bool,
+    /// Nonce of the authority before the authorization
+    pub nonce_prev: u64,
+    /// Code hash of the authority before and after the authorization
+    pub code_hash: (Word, Word),
+    /// Keccak code hash of the authority before and after the authorization
+    pub keccak_code_hash: (Word, Word),
+    /// Code size of the authority before and after the authorization
+    pub code_size: (Word, Word),
+    /// First bytes of the code of the authority before the authorization, as
+    /// long as the delegation prefix, zeros if the code doesn't have the size
+    /// of a delegation designator
+    pub code_prefix_prev: [u8; DELEGATION_PREFIX.len()],
+    /// Tx refund before and after the authorization
+    pub refund: (u64, u64),
+}
+
 #[derive(Debug, Clone)]
 /// Result of the parsing of an Ethereum Transaction.
 pub struct Transaction {
@@ -238,6 +269,20 @@ pub struct Transaction {
     pub l1_fee_committed: TxL1Fee,
     /// EIP2930
     pub access_list: Option<AccessList>,
+    /// The rw counter at which the access list is warmed up in BeginTx
+    pub access_list_rw_counter: usize,
+    /// Whether each address and storage key of the access list was warm
+    /// before BeginTx warms it up, in the order they are warmed up
+    pub access_list_is_warm_prev: Vec<bool>,
+    /// EIP4844 max fee per blob gas
+    pub max_fee_per_blob_gas: Word,
+    /// EIP4844
+    pub blob_versioned_hashes: Vec<H256>,
+    /// EIP7702
+    pub authorization_list: Vec<SetCodeAuthorization>,
+    /// The authorizations applied by BeginTx, in the order of the
+    /// authorization list
+    pub applied_authorizations: Vec<AppliedAuthorization>,
     /// Calls made in the transaction
     pub(crate) calls: Vec<Call>,
     /// Execution steps
@@ -263,6 +308,9 @@ impl From<&Transaction> for geth_types::Transaction {
             rlp_unsigned_bytes: tx.rlp_unsigned_bytes.clone(),
             rlp_bytes: tx.rlp_bytes.clone(),
             tx_type: tx.tx_type,
+            max_fee_per_blob_gas: tx.max_fee_per_blob_gas,
+            blob_versioned_hashes: tx.blob_versioned_hashes.clone(),
+            authorization_list: tx.authorization_list.clone(),
             ..Default::default()
         }
     }
@@ -297,6 +345,12 @@ impl Transaction {
             l1_fee: Default::default(),
             l1_fee_committed: Default::default(),
             access_list: None,
+            access_list_rw_counter: 0,
+            access_list_is_warm_prev: vec![],
+            max_fee_per_blob_gas: Word::zero(),
+            blob_versioned_hashes: vec![],
+            authorization_list: vec![],
+            applied_authorizations: vec![],
         }
     }
 
@@ -388,7 +442,7 @@ impl Transaction {
             block_num: eth_tx.block_number.unwrap().as_u64(),
             hash: eth_tx.hash,
             tx_type,
-            rlp_bytes: eth_tx.rlp().to_vec(),
+            rlp_bytes: get_rlp_signed(eth_tx),
             rlp_unsigned_bytes: get_rlp_unsigned(eth_tx),
             nonce: eth_tx.nonce.as_u64(),
             gas: eth_tx.gas.as_u64(),
@@ -410,6 +464,12 @@ impl Transaction {
             l1_fee,
             l1_fee_committed,
             access_list: eth_tx.access_list.clone(),
+            access_list_rw_counter: 0,
+            access_list_is_warm_prev: vec![],
+            max_fee_per_blob_gas: get_max_fee_per_blob_gas(eth_tx),
+            blob_versioned_hashes: get_blob_versioned_hashes(eth_tx),
+            authorization_list: get_authorization_list(eth_tx),
+            applied_authorizations: vec![],
         })
     }
 
@@ -467,7 +527,7 @@ impl Transaction {
 }
 
 /// Transaction L1 fee for L1GasPriceOracle contract
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct TxL1Fee {
     /// L1 base fee
     pub base_fee: u64,
@@ -1,7 +1,8 @@
 //! Definition of each opcode of the EVM.
 use crate::{
     circuit_input_builder::{
-        CircuitInputStateRef, CopyBytes, CopyDataType, CopyEvent, ExecStep, NumberOrHash,
+        AppliedAuthorization, CircuitInputStateRef, CopyBytes, CopyDataType, CopyEvent, ExecStep,
+        NumberOrHash,
     },
     error::{
         ContractAddressCollisionError, DepthError, ExecError, InsufficientBalanceError,
@@ -10,7 +11,7 @@ use crate::{
     evm::OpcodeId,
     l2_predeployed::l1_gas_price_oracle,
     operation::{
-        AccountField, AccountOp, CallContextField, StorageOp, TxAccessListAccountOp,
+        AccountField, AccountOp, CallContextField, StorageOp, TxAccessListAccountStorageOp,
         TxReceiptField, TxRefundOp, RW,
     },
     state_db::CodeDB,
@@ -18,8 +19,11 @@ use crate::{
 };
 use core::fmt::Debug;
 use eth_types::{
-    evm_types::{gas_utils::tx_data_gas_cost, GasCost, MAX_REFUND_QUOTIENT_OF_GAS_USED},
-    evm_unimplemented, Bytecode, GethExecStep, GethExecTrace, ToAddress, ToWord, Word,
+    evm_types::{
+        delegation_code, gas_utils::tx_data_gas_cost, parse_delegation, GasCost,
+        DELEGATION_CODE_SIZE, DELEGATION_PREFIX, MAX_REFUND_QUOTIENT_OF_GAS_USED,
+    },
+    evm_unimplemented, Bytecode, GethExecStep, GethExecTrace, ToWord, Word,
 };
 use ethers_core::utils::get_contract_address;
 
@@ -30,6 +34,7 @@ pub use self::sha3::sha3_tests::{gen_sha3_code, MemoryKind};
 
 mod address;
 mod balance;
+mod blobhash;
 mod blockhash;
 mod calldatacopy;
 mod calldataload;
@@ -48,6 +53,7 @@ mod extcodehash;
 mod extcodesize;
 mod gasprice;
 mod logs;
+mod mcopy;
 mod mload;
 mod mstore;
 mod number;
@@ -58,12 +64,15 @@ mod return_revert;
 mod returndatacopy;
 mod returndatasize;
 mod selfbalance;
+mod selfdestruct;
 mod sha3;
 mod sload;
 mod sstore;
 mod stackonlyop;
 mod stop;
 mod swap;
+mod tload;
+mod tstore;
 
 mod error_codestore;
 mod error_contract_address_collision;
@@ -73,6 +82,7 @@ mod error_oog_account_access;
 mod error_oog_call;
 mod error_oog_log;
 mod error_oog_memory_copy;
+mod error_oog_selfdestruct;
 mod error_oog_sload_sstore;
 mod error_precompile_failed;
 mod error_return_data_outofbound;
@@ -80,6 +90,8 @@ mod error_write_protection;
 
 #[cfg(test)]
 mod memory_expansion_test;
+#[cfg(test)]
+mod set_code_test;
 #[cfg(feature = "test")]
 pub use callop::tests::PrecompileCallArgs;
 
@@ -87,6 +99,7 @@ use self::sha3::Sha3;
 use crate::precompile::is_precompiled;
 use address::Address;
 use balance::Balance;
+use blobhash::Blobhash;
 use blockhash::Blockhash;
 use calldatacopy::Calldatacopy;
 use calldataload::Calldataload;
@@ -105,6 +118,7 @@ use error_oog_account_access::ErrorOOGAccountAccess;
 use error_oog_call::OOGCall;
 use error_oog_log::ErrorOOGLog;
 use error_oog_memory_copy::OOGMemoryCopy;
+use error_oog_selfdestruct::ErrorOOGSelfDestruct;
 use error_oog_sload_sstore::OOGSloadSstore;
 use error_precompile_failed::PrecompileFailed;
 use error_return_data_outofbound::ErrorReturnDataOutOfBound;
@@ -115,6 +129,7 @@ use extcodehash::Extcodehash;
 use extcodesize::Extcodesize;
 use gasprice::GasPrice;
 use logs::Log;
+use mcopy::MCopy;
 use mload::Mload;
 use mstore::Mstore;
 use origin::Origin;
@@ -123,11 +138,14 @@ use return_revert::ReturnRevert;
 use returndatacopy::Returndatacopy;
 use returndatasize::Returndatasize;
 use selfbalance::Selfbalance;
+use selfdestruct::SelfDestruct;
 use sload::Sload;
 use sstore::Sstore;
 use stackonlyop::StackOnlyOpcode;
 use stop::Stop;
 use swap::Swap;
+use tload::Tload;
+use tstore::Tstore;
 
 /// Generic opcode trait which defines the logic of the
 /// [`Operation`](crate::operation::Operation) that should be generated for one
@@ -220,12 +238,17 @@ fn fn_gen_associated_ops(opcode_id: &OpcodeId) -> FnGenAssociatedOps {
         OpcodeId::CHAINID => StackOnlyOpcode::<0, 1>::gen_associated_ops,
         OpcodeId::SELFBALANCE => Selfbalance::gen_associated_ops,
         OpcodeId::BASEFEE => StackOnlyOpcode::<0, 1>::gen_associated_ops,
+        OpcodeId::BLOBHASH => Blobhash::gen_associated_ops,
+        OpcodeId::BLOBBASEFEE => StackOnlyOpcode::<0, 1>::gen_associated_ops,
         OpcodeId::POP => StackOnlyOpcode::<1, 0>::gen_associated_ops,
         OpcodeId::MLOAD => Mload::gen_associated_ops,
         OpcodeId::MSTORE => Mstore::<false>::gen_associated_ops,
         OpcodeId::MSTORE8 => Mstore::<true>::gen_associated_ops,
         OpcodeId::SLOAD => Sload::gen_associated_ops,
         OpcodeId::SSTORE => Sstore::gen_associated_ops,
+        OpcodeId::TLOAD => Tload::gen_associated_ops,
+        OpcodeId::TSTORE => Tstore::gen_associated_ops,
+        OpcodeId::MCOPY => MCopy::gen_associated_ops,
         OpcodeId::JUMP => StackOnlyOpcode::<1, 0>::gen_associated_ops,
         OpcodeId::JUMPI => StackOnlyOpcode::<2, 0>::gen_associated_ops,
         OpcodeId::PC => StackOnlyOpcode::<0, 1>::gen_associated_ops,
@@ -275,10 +298,7 @@ fn fn_gen_associated_ops(opcode_id: &OpcodeId) -> FnGenAssociatedOps {
         OpcodeId::CREATE2 => Create::<true>::gen_associated_ops,
         OpcodeId::RETURN | OpcodeId::REVERT => ReturnRevert::gen_associated_ops,
         OpcodeId::INVALID(_) => Stop::gen_associated_ops,
-        OpcodeId::SELFDESTRUCT => {
-            log::debug!("Using dummy gen_selfdestruct_ops for opcode SELFDESTRUCT");
-            DummySelfDestruct::gen_associated_ops
-        }
+        OpcodeId::SELFDESTRUCT => SelfDestruct::gen_associated_ops,
         _ => {
             log::debug!("Using dummy gen_associated_ops for opcode {:?}", opcode_id);
             Dummy::gen_associated_ops
@@ -331,6 +351,9 @@ fn fn_gen_error_state_associated_ops(
         ExecError::OutOfGas(OogError::AccountAccess) => {
             Some(ErrorOOGAccountAccess::gen_associated_ops)
         }
+        ExecError::OutOfGas(OogError::SelfDestruct) => {
+            Some(ErrorOOGSelfDestruct::gen_associated_ops)
+        }
         // ExecError::
         ExecError::StackOverflow => Some(StackOnlyOpcode::<0, 0, true>::gen_associated_ops),
         ExecError::StackUnderflow => Some(StackOnlyOpcode::<0, 0, true>::gen_associated_ops),
@@ -475,7 +498,7 @@ pub fn gen_begin_tx_ops(
     geth_trace: &GethExecTrace,
 ) -> Result<(), Error> {
     let mut exec_step = state.new_begin_tx_step();
-    let call = state.call()?.clone();
+    let mut call = state.call()?.clone();
 
     let caller_address = call.caller_address;
 
@@ -586,19 +609,11 @@ pub fn gen_begin_tx_ops(
     }
 
     // Add caller, callee and coinbase (only for Shanghai) to access list.
-    #[cfg(feature = "shanghai")]
-    let accessed_addresses = [
-        call.caller_address,
-        call.address,
-        state
-            .block
-            .headers
-            .get(&state.tx.block_num)
-            .unwrap()
-            .coinbase,
-    ];
-    #[cfg(not(feature = "shanghai"))]
-    let accessed_addresses = [call.caller_address, call.address];
+    let hardfork = state.block.hardfork(state.tx.block_num);
+    let mut accessed_addresses = vec![call.caller_address, call.address];
+    if hardfork.is_coinbase_warm() {
+        accessed_addresses.push(state.block.headers[&state.tx.block_num].coinbase);
+    }
     for address in accessed_addresses {
         let is_warm_prev = !state.sdb.add_account_to_access_list(address);
         state.tx_accesslist_account_write(
@@ -610,15 +625,53 @@ pub fn gen_begin_tx_ops(
         )?;
     }
 
+    // Add the addresses and storage keys of the EIP-2930 access list, in the
+    // order they are encoded in the tx. The tx circuit looks them up from the
+    // rw counter of the first write.
+    state.tx.access_list_rw_counter = state.block_ctx.rwc.0;
+    let (mut access_list_addresses, mut access_list_storage_keys) = (0, 0);
+    for item in state.tx.access_list.clone().unwrap_or_default().0 {
+        let is_warm_prev = !state.sdb.add_account_to_access_list(item.address);
+        state.tx_accesslist_account_write(
+            &mut exec_step,
+            state.tx_ctx.id(),
+            item.address,
+            true,
+            is_warm_prev,
+        )?;
+        state.tx.access_list_is_warm_prev.push(is_warm_prev);
+        for key in item.storage_keys {
+            let key = key.to_word();
+            let is_warm_prev = !state
+                .sdb
+                .add_account_storage_to_access_list((item.address, key));
+            state.push_op(
+                &mut exec_step,
+                RW::WRITE,
+                TxAccessListAccountStorageOp {
+                    tx_id: state.tx_ctx.id(),
+                    address: item.address,
+                    key,
+                    is_warm: true,
+                    is_warm_prev,
+                },
+            );
+            state.tx.access_list_is_warm_prev.push(is_warm_prev);
+            access_list_storage_keys += 1;
+        }
+        access_list_addresses += 1;
+    }
+
+    // Apply the EIP-7702 authorizations, which are done before the callee's
+    // code is loaded.
+    gen_set_code_ops(state, &mut exec_step)?;
+
     // Calculate gas cost of init code only for EIP-3860 of Shanghai.
-    #[cfg(feature = "shanghai")]
     let init_code_gas_cost = if state.tx.is_create() {
-        (state.tx.input.len() as u64 + 31) / 32 * eth_types::evm_types::INIT_CODE_WORD_GAS
+        (state.tx.input.len() as u64 + 31) / 32 * hardfork.init_code_word_gas()
     } else {
         0
     };
-    #[cfg(not(feature = "shanghai"))]
-    let init_code_gas_cost = 0;
 
     // Calculate intrinsic gas cost
     let call_data_gas_cost = tx_data_gas_cost(&state.tx.input);
@@ -627,7 +680,10 @@ pub fn gen_begin_tx_ops(
     } else {
         GasCost::TX.as_u64()
     } + call_data_gas_cost
-        + init_code_gas_cost;
+        + init_code_gas_cost
+        + access_list_addresses * GasCost::ACCESS_LIST_ADDRESS.as_u64()
+        + access_list_storage_keys * GasCost::ACCESS_LIST_STORAGE_KEY.as_u64()
+        + state.tx.authorization_list.len() as u64 * GasCost::PER_EMPTY_ACCOUNT.as_u64();
     log::trace!("intrinsic_gas_cost {intrinsic_gas_cost}, call_data_gas_cost {call_data_gas_cost}, init_code_gas_cost {init_code_gas_cost}, exec_step.gas_cost {:?}", exec_step.gas_cost);
     exec_step.gas_cost = GasCost(intrinsic_gas_cost);
 
@@ -657,13 +713,6 @@ pub fn gen_begin_tx_ops(
     } else {
         Word::zero()
     };
-    // call_code is code being executed
-    let call_code_hash = call.code_hash.to_word();
-    if !state.tx.is_create() && !account_code_hash.is_zero() {
-        debug_assert_eq!(account_code_hash, call_code_hash);
-    }
-    let account_code_hash_is_empty_or_zero =
-        account_code_hash.is_zero() || account_code_hash == CodeDB::empty_code_hash().to_word();
 
     if !is_precompile {
         state.account_read(
@@ -674,6 +723,65 @@ pub fn gen_begin_tx_ops(
         );
     }
 
+    // A delegated callee executes the code of its delegate, which is warmed up
+    // before its code hash is read, right after the callee's one. The warm-up
+    // isn't reverted with the tx, as for the other entries of the access list.
+    let account_code_hash = match state.delegation(&call.address) {
+        Some(delegate) if !state.tx.is_create() && !is_precompile => {
+            let is_warm_prev = !state.sdb.add_account_to_access_list(delegate);
+            state.tx_accesslist_account_write(
+                &mut exec_step,
+                state.tx_ctx.id(),
+                delegate,
+                true,
+                is_warm_prev,
+            )?;
+            let delegate_account = state.sdb.get_account(&delegate).1.clone();
+            let delegate_code_hash = if delegate_account.is_empty() {
+                Word::zero()
+            } else {
+                delegate_account.code_hash.to_word()
+            };
+            state.account_read(
+                &mut exec_step,
+                delegate,
+                AccountField::CodeHash,
+                delegate_code_hash,
+            );
+            // the callee may have been delegated by the authorizations of this
+            // tx, so the root call has to be updated as well.
+            let code_hash = if is_precompiled(&delegate) || delegate_code_hash.is_zero() {
+                CodeDB::empty_code_hash()
+            } else {
+                delegate_account.code_hash
+            };
+            state.call_mut()?.code_hash = code_hash;
+            call.code_hash = code_hash;
+            delegate_code_hash
+        }
+        _ => {
+            // the authorizations of this tx may have cleared the delegation
+            // the root call was created with.
+            if !state.tx.is_create() && !is_precompile {
+                let code_hash = if account_code_hash.is_zero() {
+                    CodeDB::empty_code_hash()
+                } else {
+                    callee_account.code_hash
+                };
+                state.call_mut()?.code_hash = code_hash;
+                call.code_hash = code_hash;
+            }
+            account_code_hash
+        }
+    };
+    // call_code is code being executed
+    let call_code_hash = call.code_hash.to_word();
+    if !state.tx.is_create() && !account_code_hash.is_zero() {
+        debug_assert_eq!(account_code_hash, call_code_hash);
+    }
+    let account_code_hash_is_empty_or_zero =
+        account_code_hash.is_zero() || account_code_hash == CodeDB::empty_code_hash().to_word();
+
     // Transfer with fee
     let fee = state.tx.gas_price * state.tx.gas + state.tx_ctx.l1_fee;
     state.transfer_with_fee(
@@ -750,6 +858,7 @@ pub fn gen_begin_tx_ops(
                     value_prev: 0.into(),
                 },
             )?;
+            state.tx_created_account_write(&mut exec_step, call.address)?;
             for (field, value) in [
                 (CallContextField::Depth, call.depth.into()),
                 (
@@ -828,10 +937,7 @@ pub fn gen_begin_tx_ops(
             exec_step.gas_cost = real_gas_cost;
         }
     } else {
-        // EIP2930 not implemented
-        if state.tx.access_list.is_none() {
-            debug_assert_eq!(exec_step.gas_cost, real_gas_cost);
-        }
+        debug_assert_eq!(exec_step.gas_cost, real_gas_cost);
     }
 
     log::trace!("begin_tx_step: {:?}", exec_step);
@@ -849,6 +955,136 @@ pub fn gen_begin_tx_ops(
     Ok(())
 }
 
+/// Apply the authorization list of an EIP-7702 tx. Every authorization whose
+/// authority can be recovered takes the same rws, so that BeginTx can account for
+/// them by the number of recovered authorities. An invalid authorization writes
+/// back the current values, and one whose signature can't be recovered is skipped.
+fn gen_set_code_ops(
+    state: &mut CircuitInputStateRef,
+    exec_step: &mut ExecStep,
+) -> Result<(), Error> {
+    for auth in state.tx.authorization_list.clone() {
+        let Ok(authority) = auth.authority() else {
+            log::debug!("skip unrecoverable authorization {auth:?}");
+            continue;
+        };
+        let is_valid_sig = (auth.chain_id.is_zero()
+            || auth.chain_id == state.block.chain_id.into())
+            && auth.nonce.as_u64() < u64::MAX
+            && auth.has_low_s();
+
+        let is_warm_prev = state.sdb.check_account_in_access_list(&authority);
+        if is_valid_sig {
+            state.sdb.add_account_to_access_list(authority);
+        }
+        state.tx_accesslist_account_write(
+            exec_step,
+            state.tx_ctx.id(),
+            authority,
+            is_warm_prev || is_valid_sig,
+            is_warm_prev,
+        )?;
+
+        let account = state.sdb.get_account(&authority).1.clone();
+        let authority_exists = !account.is_empty();
+        let code = state.code(account.code_hash).unwrap_or_default();
+        let mut code_prefix_prev = [0; DELEGATION_PREFIX.len()];
+        if code.len() == DELEGATION_CODE_SIZE {
+            code_prefix_prev.copy_from_slice(&code[..DELEGATION_PREFIX.len()]);
+        }
+        let is_valid = is_valid_sig
+            && (code.is_empty() || parse_delegation(&code).is_some())
+            && account.nonce == auth.nonce.as_u64().into();
+
+        let code = if !is_valid {
+            code
+        } else if auth.address.is_zero() {
+            vec![]
+        } else {
+            delegation_code(auth.address)
+        };
+        let code_hash = state.code_db.insert(code.clone());
+        let keccak_code_hash = Word::from_big_endian(&ethers_core::utils::keccak256(&code));
+        // A non-existing authority stays non-existing if the authorization is invalid.
+        let (code_hash, keccak_code_hash) = if authority_exists {
+            (
+                (account.code_hash.to_word(), code_hash.to_word()),
+                (account.keccak_code_hash.to_word(), keccak_code_hash),
+            )
+        } else if is_valid {
+            (
+                (Word::zero(), code_hash.to_word()),
+                (Word::zero(), keccak_code_hash),
+            )
+        } else {
+            ((Word::zero(), Word::zero()), (Word::zero(), Word::zero()))
+        };
+        let code_size = (account.code_size, code.len().into());
+        state.account_write(
+            exec_step,
+            authority,
+            AccountField::CodeHash,
+            code_hash.1,
+            code_hash.0,
+        )?;
+        #[cfg(feature = "scroll")]
+        {
+            state.account_write(
+                exec_step,
+                authority,
+                AccountField::KeccakCodeHash,
+                keccak_code_hash.1,
+                keccak_code_hash.0,
+            )?;
+            state.account_write(
+                exec_step,
+                authority,
+                AccountField::CodeSize,
+                code_size.1,
+                code_size.0,
+            )?;
+        }
+        state.account_write(
+            exec_step,
+            authority,
+            AccountField::Nonce,
+            account.nonce + is_valid as u64,
+            account.nonce,
+        )?;
+
+        let refund = state.sdb.refund();
+        let refund_next = if is_valid && authority_exists {
+            refund + GasCost::PER_EMPTY_ACCOUNT.as_u64() - GasCost::PER_AUTH_BASE.as_u64()
+        } else {
+            refund
+        };
+        state.push_op(
+            exec_step,
+            RW::WRITE,
+            TxRefundOp {
+                tx_id: state.tx_ctx.id(),
+                value: refund_next,
+                value_prev: refund,
+            },
+        );
+        state.sdb.set_refund(refund_next);
+
+        state.tx.applied_authorizations.push(AppliedAuthorization {
+            authority,
+            is_warm_prev,
+            is_valid,
+            nonce_prev: account.nonce.as_u64(),
+            code_hash,
+            keccak_code_hash,
+            code_size,
+            code_prefix_prev,
+            refund: (refund, refund_next),
+        });
+    }
+
+    Ok(())
+}
+
 pub fn gen_end_tx_ops(state: &mut CircuitInputStateRef) -> Result<ExecStep, Error> {
     let mut exec_step = state.new_end_tx_step();
     let call = state.tx.calls()[0].clone();
@@ -1048,101 +1284,3 @@ fn gen_tx_l1_fee_ops(state: &mut CircuitInputStateRef, exec_step: &mut ExecStep)
         ),
     );
 }
-
-#[derive(Debug, Copy, Clone)]
-struct DummySelfDestruct;
-
-impl Opcode for DummySelfDestruct {
-    fn gen_associated_ops(
-        state: &mut CircuitInputStateRef,
-        geth_steps: &[GethExecStep],
-    ) -> Result<Vec<ExecStep>, Error> {
-        dummy_gen_selfdestruct_ops(state, geth_steps)
-    }
-}
-fn dummy_gen_selfdestruct_ops(
-    state: &mut CircuitInputStateRef,
-    geth_steps: &[GethExecStep],
-) -> Result<Vec<ExecStep>, Error> {
-    let geth_step = &geth_steps[0];
-    let mut exec_step = state.new_step(geth_step)?;
-    let sender = state.call()?.address;
-    let receiver = geth_step.stack.last()?.to_address();
-
-    let is_warm = state.sdb.check_account_in_access_list(&receiver);
-    state.push_op_reversible(
-        &mut exec_step,
-        TxAccessListAccountOp {
-            tx_id: state.tx_ctx.id(),
-            address: receiver,
-            is_warm: true,
-            is_warm_prev: is_warm,
-        },
-    )?;
-
-    let (found, receiver_account) = state.sdb.get_account(&receiver);
-    if !found {
-        return Err(Error::AccountNotFound(receiver));
-    }
-    let receiver_account = &receiver_account.clone();
-    let (found, sender_account) = state.sdb.get_account(&sender);
-    if !found {
-        return Err(Error::AccountNotFound(sender));
-    }
-    let sender_account = &sender_account.clone();
-    let value = sender_account.balance;
-    log::trace!(
-        "self destruct, sender {:?} receiver {:?} value {:?}",
-        sender,
-        receiver,
-        value
-    );
-    // NOTE: In this dummy implementation we assume that the receiver already
-    // exists.
-
-    state.push_op_reversible(
-        &mut exec_step,
-        AccountOp {
-            address: sender,
-            field: AccountField::Balance,
-            value: Word::zero(),
-            value_prev: value,
-        },
-    )?;
-    state.push_op_reversible(
-        &mut exec_step,
-        AccountOp {
-            address: sender,
-            field: AccountField::Nonce,
-            value: Word::zero(),
-            value_prev: sender_account.nonce,
-        },
-    )?;
-    state.push_op_reversible(
-        &mut exec_step,
-        AccountOp {
-            address: sender,
-            field: AccountField::CodeHash,
-            value: Word::zero(),
-            value_prev: sender_account.code_hash.to_word(),
-        },
-    )?;
-    if receiver != sender {
-        state.push_op_reversible(
-            &mut exec_step,
-            AccountOp {
-                address: receiver,
-                field: AccountField::Balance,
-                value: receiver_account.balance + value,
-                value_prev: receiver_account.balance,
-            },
-        )?;
-    }
-
-    if state.call()?.is_persistent {
-        state.sdb.destruct_account(sender);
-    }
-
-    state.handle_return(&mut exec_step, geth_steps, true)?;
-    Ok(vec![exec_step])
-}
@@ -0,0 +1,144 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    operation::CallContextField,
+    Error,
+};
+use eth_types::GethExecStep;
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the [`OpcodeId::BLOBHASH`](crate::evm::OpcodeId::BLOBHASH)
+/// `OpcodeId`.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Blobhash;
+
+impl Opcode for Blobhash {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+        let tx_id = state.tx_ctx.id();
+
+        // CallContext read of the TxId
+        state.call_context_read(
+            &mut exec_step,
+            state.call()?.call_id,
+            CallContextField::TxId,
+            tx_id.into(),
+        );
+
+        // Stack read of the blob index
+        let index = geth_step.stack.last()?;
+        state.stack_read(&mut exec_step, geth_step.stack.last_filled(), index)?;
+
+        // Stack write of the versioned hash, which is zero if the index is out
+        // of range of the tx blobs.
+        let blob_hash = geth_steps[1].stack.last()?;
+        state.stack_write(&mut exec_step, geth_steps[1].stack.last_filled(), blob_hash)?;
+
+        Ok(vec![exec_step])
+    }
+}
+
+#[cfg(all(test, feature = "cancun"))]
+mod blobhash_tests {
+    use super::*;
+    use crate::{
+        circuit_input_builder::ExecState,
+        mock::BlockData,
+        operation::{CallContextOp, StackOp, RW},
+    };
+    use eth_types::{
+        bytecode,
+        evm_types::{OpcodeId, StackAddress},
+        geth_types::GethData,
+        Hash, ToWord, Word,
+    };
+    use mock::{
+        test_ctx::{helpers::*, TestContext},
+        MOCK_WALLETS,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn test_ok(index: u64, blob_hashes: Vec<Hash>) {
+        let code = bytecode! {
+            PUSH8(index)
+            BLOBHASH
+            STOP
+        };
+        let expected = blob_hashes
+            .get(index as usize)
+            .map(ToWord::to_word)
+            .unwrap_or_default();
+
+        // Get the execution steps from the external tracer
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_wallet_0_no_code(code),
+            |mut txs, accs| {
+                txs[0]
+                    .from(MOCK_WALLETS[0].clone())
+                    .to(accs[0].address)
+                    .blob_versioned_hashes(blob_hashes.clone());
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let step = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::BLOBHASH))
+            .unwrap();
+
+        let call_id = builder.block.txs()[0].calls()[0].call_id;
+        assert_eq!(
+            {
+                let operation =
+                    &builder.block.container.call_context[step.bus_mapping_instance[0].as_usize()];
+                (operation.rw(), operation.op())
+            },
+            (
+                RW::READ,
+                &CallContextOp {
+                    call_id,
+                    field: CallContextField::TxId,
+                    value: Word::one(),
+                }
+            )
+        );
+
+        assert_eq!(
+            [1, 2]
+                .map(|idx| &builder.block.container.stack[step.bus_mapping_instance[idx].as_usize()])
+                .map(|operation| (operation.rw(), operation.op())),
+            [
+                (
+                    RW::READ,
+                    &StackOp::new(1, StackAddress::from(1023), Word::from(index))
+                ),
+                (RW::WRITE, &StackOp::new(1, StackAddress::from(1023), expected)),
+            ]
+        );
+    }
+
+    #[test]
+    fn blobhash_opcode_impl() {
+        let blob_hashes = vec![Hash::from_low_u64_be(0x01), Hash::from_low_u64_be(0x02)];
+        test_ok(1, blob_hashes.clone());
+        test_ok(2, blob_hashes);
+    }
+
+    #[test]
+    fn blobhash_opcode_impl_no_blobs() {
+        test_ok(0, vec![]);
+    }
+}
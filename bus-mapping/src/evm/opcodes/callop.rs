@@ -6,7 +6,7 @@ use crate::{
     },
     evm::opcodes::precompiles::gen_associated_ops as precompile_associated_ops,
     operation::{AccountField, CallContextField, TxAccessListAccountOp},
-    precompile::{execute_precompiled, is_precompiled, PrecompileCalls},
+    precompile::{execute_precompiled, is_precompiled, ModExpAuxData, PrecompileCalls},
     state_db::CodeDB,
     Error,
 };
@@ -101,18 +101,18 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
             (call.is_success as u64).into(),
         )?;
 
-        let callee_code_hash = call.code_hash;
         let callee_acc = state.sdb.get_account(&callee_address).1;
         let callee_exists = !callee_acc.is_empty();
 
-        let (callee_code_hash_word, is_empty_code_hash) = if callee_exists {
-            (
-                callee_code_hash.to_word(),
-                callee_code_hash == CodeDB::empty_code_hash(),
-            )
+        // The code hash of the callee account, which is a delegation designator
+        // if the callee is an EIP-7702 delegated account.
+        let callee_code_hash_word = if callee_exists {
+            callee_acc.code_hash.to_word()
         } else {
-            (Word::zero(), true)
+            Word::zero()
         };
+        // `call.code_hash` is the hash of the code being executed.
+        let is_empty_code_hash = call.code_hash == CodeDB::empty_code_hash();
         state.account_read(
             &mut exec_step,
             callee_address,
@@ -131,6 +131,36 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
             },
         )?;
 
+        // Warm up the delegate of a delegated callee and read its code hash.
+        let delegate = state.delegation(&callee_address);
+        let is_delegate_warm = if let Some(delegate) = delegate {
+            let is_delegate_warm = state.sdb.check_account_in_access_list(&delegate);
+            state.push_op_reversible(
+                &mut exec_step,
+                TxAccessListAccountOp {
+                    tx_id,
+                    address: delegate,
+                    is_warm: true,
+                    is_warm_prev: is_delegate_warm,
+                },
+            )?;
+            let delegate_acc = state.sdb.get_account(&delegate).1;
+            let delegate_code_hash_word = if delegate_acc.is_empty() {
+                Word::zero()
+            } else {
+                delegate_acc.code_hash.to_word()
+            };
+            state.account_read(
+                &mut exec_step,
+                delegate,
+                AccountField::CodeHash,
+                delegate_code_hash_word,
+            );
+            is_delegate_warm
+        } else {
+            true
+        };
+
         // Switch to callee's call context
         state.push_call(call.clone());
 
@@ -202,6 +232,10 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
             GasCost::WARM_ACCESS.as_u64()
         } else {
             GasCost::COLD_ACCOUNT_ACCESS.as_u64()
+        } + match (delegate, is_delegate_warm) {
+            (None, _) => 0,
+            (Some(_), true) => GasCost::WARM_ACCESS.as_u64(),
+            (Some(_), false) => GasCost::COLD_ACCOUNT_ACCESS.as_u64(),
         } + if has_value {
             GasCost::CALL_WITH_VALUE.as_u64()
                 + if call.kind == CallKind::Call && !callee_exists {
@@ -263,16 +297,14 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
 
                 // get the result of the precompile call.
                 // For failed call, it will cost all gas provided.
-                let (result, precompile_call_gas_cost) = execute_precompiled(
-                    &code_address,
-                    if args_length != 0 {
-                        let caller_memory = &state.caller_ctx()?.memory;
-                        &caller_memory.0[args_offset..args_offset + args_length]
-                    } else {
-                        &[]
-                    },
-                    callee_gas_left_with_stipend,
-                );
+                let call_data = if args_length != 0 {
+                    let caller_memory = &state.caller_ctx()?.memory;
+                    caller_memory.0[args_offset..args_offset + args_length].to_vec()
+                } else {
+                    vec![]
+                };
+                let (result, precompile_call_gas_cost) =
+                    execute_precompiled(&code_address, &call_data, callee_gas_left_with_stipend);
 
                 // mutate the callee memory by at least the precompile call's result that will be
                 // written from memory addr 0 to memory addr result.len()
@@ -351,10 +383,17 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
                 // rws also handle prev bytes internally.
                 let rw_counter_start = state.block_ctx.rwc;
                 let input_bytes = if call.call_data_length > 0 {
-                    let n_input_bytes = if let Some(input_len) = precompile_call.input_len() {
-                        min(input_len, call.call_data_length as usize)
-                    } else {
-                        call.call_data_length as usize
+                    let n_input_bytes = match precompile_call {
+                        // only the bytes up to the end of the modulus are copied.
+                        PrecompileCalls::Modexp => min(
+                            ModExpAuxData::input_len(&call_data),
+                            call.call_data_length as usize,
+                        ),
+                        _ => precompile_call
+                            .input_len()
+                            .map_or(call.call_data_length as usize, |input_len| {
+                                min(input_len, call.call_data_length as usize)
+                            }),
                     };
                     let copy_steps = state.gen_copy_steps_for_precompile_calldata(
                         &mut exec_step,
@@ -217,6 +217,7 @@ impl<const IS_CREATE2: bool> Opcode for Create<IS_CREATE2> {
                     value_prev: 0.into(),
                 },
             )?;
+            state.tx_created_account_write(&mut exec_step, callee.address)?;
         }
 
         // Per EIP-150, all but one 64th of the caller's gas is sent to the
@@ -90,6 +90,34 @@ impl Opcode for OOGCall {
             },
         );
 
+        // The access of the delegate of a delegated callee is charged as well,
+        // but the call fails before the delegate is warmed up.
+        if let Some(delegate) = state.delegation(&call_address) {
+            let is_warm = state.sdb.check_account_in_access_list(&delegate);
+            state.push_op(
+                &mut exec_step,
+                RW::READ,
+                TxAccessListAccountOp {
+                    tx_id,
+                    address: delegate,
+                    is_warm,
+                    is_warm_prev: is_warm,
+                },
+            );
+            let delegate_account = state.sdb.get_account(&delegate).1;
+            let delegate_code_hash_word = if delegate_account.is_empty() {
+                Word::zero()
+            } else {
+                delegate_account.code_hash.to_word()
+            };
+            state.account_read(
+                &mut exec_step,
+                delegate,
+                AccountField::CodeHash,
+                delegate_code_hash_word,
+            );
+        }
+
         state.handle_return(&mut exec_step, geth_steps, true)?;
         Ok(vec![exec_step])
     }
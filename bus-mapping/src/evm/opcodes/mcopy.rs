@@ -0,0 +1,222 @@
+use crate::{
+    circuit_input_builder::{
+        CircuitInputStateRef, CopyBytes, CopyDataType, CopyEvent, ExecStep, NumberOrHash,
+    },
+    evm::Opcode,
+    Error,
+};
+use eth_types::GethExecStep;
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct MCopy;
+
+impl Opcode for MCopy {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_steps = vec![gen_mcopy_step(state, geth_step)?];
+
+        let copy_event = gen_copy_event(state, geth_step, &mut exec_steps[0])?;
+        state.push_copy(&mut exec_steps[0], copy_event);
+        Ok(exec_steps)
+    }
+}
+
+fn gen_mcopy_step(
+    state: &mut CircuitInputStateRef,
+    geth_step: &GethExecStep,
+) -> Result<ExecStep, Error> {
+    let mut exec_step = state.new_step(geth_step)?;
+    let dst_offset = geth_step.stack.nth_last(0)?;
+    let src_offset = geth_step.stack.nth_last(1)?;
+    let length = geth_step.stack.nth_last(2)?;
+
+    state.stack_read(
+        &mut exec_step,
+        geth_step.stack.nth_last_filled(0),
+        dst_offset,
+    )?;
+    state.stack_read(
+        &mut exec_step,
+        geth_step.stack.nth_last_filled(1),
+        src_offset,
+    )?;
+    state.stack_read(&mut exec_step, geth_step.stack.nth_last_filled(2), length)?;
+
+    Ok(exec_step)
+}
+
+fn gen_copy_event(
+    state: &mut CircuitInputStateRef,
+    geth_step: &GethExecStep,
+    exec_step: &mut ExecStep,
+) -> Result<CopyEvent, Error> {
+    let rw_counter_start = state.block_ctx.rwc;
+
+    // Get low Uint64 of offset.
+    let dst_addr = geth_step.stack.nth_last(0)?;
+    let src_addr = geth_step.stack.nth_last(1)?;
+    let length = geth_step.stack.nth_last(2)?;
+
+    let (dst_addr, src_addr, length) = (dst_addr.low_u64(), src_addr.low_u64(), length.as_u64());
+    let src_addr_end = src_addr + length;
+
+    let (read_steps, write_steps, prev_bytes) =
+        state.gen_copy_steps_for_memory_copy(exec_step, src_addr, dst_addr, length)?;
+
+    let call_id = state.call()?.call_id;
+    Ok(CopyEvent {
+        src_type: CopyDataType::Memory,
+        src_id: NumberOrHash::Number(call_id),
+        src_addr,
+        src_addr_end,
+        dst_type: CopyDataType::Memory,
+        dst_id: NumberOrHash::Number(call_id),
+        dst_addr,
+        log_id: None,
+        rw_counter_start,
+        copy_bytes: CopyBytes::new(read_steps, Some(write_steps), Some(prev_bytes)),
+    })
+}
+
+#[cfg(all(test, feature = "cancun"))]
+mod mcopy_tests {
+    use crate::{
+        circuit_input_builder::{CopyDataType, ExecState, NumberOrHash},
+        mock::BlockData,
+        operation::{MemoryOp, StackOp, RW},
+    };
+    use eth_types::{
+        bytecode,
+        evm_types::{OpcodeId, StackAddress},
+        geth_types::GethData,
+        Word,
+    };
+    use mock::test_ctx::{helpers::*, TestContext};
+    use pretty_assertions::assert_eq;
+
+    fn test_ok(dst_offset: u64, src_offset: u64, length: u64) {
+        let code = bytecode! {
+            PUSH32(Word::from_big_endian(&[0xffu8; 32]))
+            PUSH1(0x00)
+            MSTORE
+            PUSH32(Word::from_big_endian(&(1..=32).collect::<Vec<u8>>()))
+            PUSH1(0x20)
+            MSTORE
+            PUSH32(length)
+            PUSH32(src_offset)
+            PUSH32(dst_offset)
+            MCOPY
+            STOP
+        };
+
+        // Get the execution steps from the external tracer
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let step = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::MCOPY))
+            .unwrap();
+
+        assert_eq!(
+            [0, 1, 2]
+                .map(|idx| &builder.block.container.stack[step.bus_mapping_instance[idx].as_usize()])
+                .map(|operation| (operation.rw(), operation.op())),
+            [
+                (
+                    RW::READ,
+                    &StackOp::new(1, StackAddress::from(1021), Word::from(dst_offset))
+                ),
+                (
+                    RW::READ,
+                    &StackOp::new(1, StackAddress::from(1022), Word::from(src_offset))
+                ),
+                (
+                    RW::READ,
+                    &StackOp::new(1, StackAddress::from(1023), Word::from(length))
+                ),
+            ]
+        );
+
+        // The memory before MCOPY and the expected memory after it, padded with
+        // zeros for the words beyond the memory size.
+        let (src, dst, len) = (src_offset as usize, dst_offset as usize, length as usize);
+        let mut memory = [[0xffu8; 32].to_vec(), (1..=32).collect::<Vec<u8>>()].concat();
+        memory.resize(memory.len() + src.max(dst) + len + 64, 0);
+        let mut expected = memory.clone();
+        expected.copy_within(src..src + len, dst);
+
+        let copy_events = builder.block.copy_events.clone();
+        assert_eq!(copy_events.len(), 1);
+        let copy_event = &copy_events[0];
+        assert!(copy_event.is_memory_copy());
+        assert_eq!(copy_event.src_type, CopyDataType::Memory);
+        assert_eq!(copy_event.dst_type, CopyDataType::Memory);
+        assert_eq!(copy_event.src_id, NumberOrHash::Number(1));
+        assert_eq!(copy_event.dst_id, NumberOrHash::Number(1));
+
+        // All the source words are read before the destination words are
+        // written.
+        let memory_ops: Vec<_> = step.bus_mapping_instance[3..]
+            .iter()
+            .map(|op_ref| &builder.block.container.memory[op_ref.as_usize()])
+            .collect();
+        let num_words = memory_ops.len() / 2;
+        assert_eq!(num_words * 2, memory_ops.len());
+        let src_slot = src / 32 * 32;
+        let dst_slot = dst / 32 * 32;
+        for (idx, operation) in memory_ops.iter().enumerate() {
+            let (rw, address, slot_memory) = if idx < num_words {
+                (RW::READ, src_slot + idx * 32, &memory)
+            } else {
+                (RW::WRITE, dst_slot + (idx - num_words) * 32, &expected)
+            };
+            assert_eq!(
+                (operation.rw(), operation.op()),
+                (
+                    rw,
+                    &MemoryOp::new(
+                        1,
+                        address.into(),
+                        Word::from_big_endian(&slot_memory[address..address + 32])
+                    )
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn mcopy_non_overlapping() {
+        test_ok(0x40, 0x00, 0x20);
+    }
+
+    #[test]
+    fn mcopy_overlapping_forward() {
+        test_ok(0x10, 0x00, 0x30);
+    }
+
+    #[test]
+    fn mcopy_overlapping_backward() {
+        test_ok(0x00, 0x11, 0x2f);
+    }
+
+    #[test]
+    fn mcopy_zero_length() {
+        test_ok(0x100, 0x00, 0x00);
+    }
+}
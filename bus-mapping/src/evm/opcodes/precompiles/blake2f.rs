@@ -0,0 +1,32 @@
+use crate::{
+    circuit_input_builder::{PrecompileEvent, BLAKE2F},
+    precompile::{BLAKE2FAuxData, PrecompileAuxData},
+};
+
+pub(crate) fn opt_data(
+    input_bytes: Option<Vec<u8>>,
+    output_bytes: Option<Vec<u8>>,
+) -> (Option<PrecompileEvent>, Option<PrecompileAuxData>) {
+    let input = input_bytes.unwrap_or_default();
+    let output = output_bytes.unwrap_or_default();
+    let aux_data = BLAKE2FAuxData {
+        input: input.clone(),
+        output: output.clone(),
+    };
+
+    // the call has failed (invalid input or out of gas) if there is no output.
+    if output.is_empty() {
+        return (None, Some(PrecompileAuxData::BLAKE2F(aux_data)));
+    }
+    assert_eq!(input.len(), 213);
+    assert_eq!(output.len(), 64);
+    let event = BLAKE2F {
+        input,
+        output: output.try_into().expect("output is 64 bytes"),
+    };
+
+    (
+        Some(PrecompileEvent::BLAKE2F(event)),
+        Some(PrecompileAuxData::BLAKE2F(aux_data)),
+    )
+}
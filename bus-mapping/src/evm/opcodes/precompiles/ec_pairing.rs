@@ -1,14 +1,11 @@
 use eth_types::{ToLittleEndian, U256};
 use halo2_proofs::halo2curves::{
-    bn256::{multi_miller_loop, Fq, Fq2, G1Affine, G2Affine, Gt},
-    group::cofactor::CofactorCurveAffine,
+    bn256::{multi_miller_loop, Fq, Fq2, G1Affine, G2Affine, G2Prepared, Gt},
     pairing::MillerLoopResult,
 };
 
 use crate::{
-    circuit_input_builder::{
-        EcPairingOp, EcPairingPair, PrecompileEvent, N_BYTES_PER_PAIR, N_PAIRING_PER_OP,
-    },
+    circuit_input_builder::{EcPairingOp, EcPairingPair, PrecompileEvent, N_BYTES_PER_PAIR},
     precompile::{EcPairingAuxData, PrecompileAuxData},
 };
 
@@ -29,16 +26,12 @@ pub(crate) fn opt_data(
         debug_assert_eq!(pairing_check, 1);
     }
 
-    let (op, aux_data) = if let Some(input) = input_bytes {
-        debug_assert!(
-            input.len() % N_BYTES_PER_PAIR == 0
-                && input.len() <= N_PAIRING_PER_OP * N_BYTES_PER_PAIR
-        );
-        // process input bytes.
-        let (mut ecc_pairs, mut evm_pairs): (Vec<EcPairingPair>, Vec<EcPairingPair>) = input
+    let pairs = input_bytes.map_or_else(Vec::new, |input| {
+        debug_assert!(input.len() % N_BYTES_PER_PAIR == 0);
+        // process input bytes, 192 bytes chunk at a time.
+        input
             .chunks_exact(N_BYTES_PER_PAIR)
             .map(|chunk| {
-                // process 192 bytes chunk at a time.
                 // process g1.
                 let g1_point = {
                     let g1_x =
@@ -74,59 +67,39 @@ pub(crate) fn opt_data(
                         },
                     }
                 };
-
-                let evm_circuit_pair = EcPairingPair { g1_point, g2_point };
-                let ecc_circuit_pair =
-                    if g1_point.is_identity().into() || g2_point.is_identity().into() {
-                        EcPairingPair::ecc_padding()
-                    } else {
-                        evm_circuit_pair
-                    };
-                (ecc_circuit_pair, evm_circuit_pair)
+                EcPairingPair { g1_point, g2_point }
             })
-            .unzip();
-        // pad the pairs to make them of fixed size: N_PAIRING_PER_OP.
-        ecc_pairs.resize(N_PAIRING_PER_OP, EcPairingPair::ecc_padding());
-        evm_pairs.resize(N_PAIRING_PER_OP, EcPairingPair::evm_padding());
-        (
-            EcPairingOp {
-                pairs: <[_; N_PAIRING_PER_OP]>::try_from(ecc_pairs).unwrap(),
-                output: pairing_check.into(),
-            },
-            EcPairingAuxData(EcPairingOp {
-                pairs: <[_; N_PAIRING_PER_OP]>::try_from(evm_pairs).unwrap(),
-                output: pairing_check.into(),
-            }),
-        )
-    } else {
-        // if no input bytes.
-        let ecc_pairs = [EcPairingPair::ecc_padding(); N_PAIRING_PER_OP];
-        let evm_pairs = [EcPairingPair::evm_padding(); N_PAIRING_PER_OP];
-        (
-            EcPairingOp {
-                pairs: ecc_pairs,
-                output: pairing_check.into(),
-            },
-            EcPairingAuxData(EcPairingOp {
-                pairs: evm_pairs,
-                output: pairing_check.into(),
-            }),
-        )
+            .collect::<Vec<_>>()
+    });
+    let op = EcPairingOp {
+        pairs,
+        output: pairing_check.into(),
     };
 
     debug_assert_eq!(
         {
-            let gt = multi_miller_loop(&[
-                (&op.pairs[0].g1_point, &op.pairs[0].g2_point.into()),
-                (&op.pairs[1].g1_point, &op.pairs[1].g2_point.into()),
-                (&op.pairs[2].g1_point, &op.pairs[2].g2_point.into()),
-                (&op.pairs[3].g1_point, &op.pairs[3].g2_point.into()),
-            ]);
+            // the ECC circuit accumulates the Miller loops of all chunks before the final
+            // exponentiation, which is the same as a single Miller loop over all pairs.
+            let ecc_pairs = op
+                .pairs
+                .iter()
+                .map(|pair| {
+                    let pair = pair.ecc_pair();
+                    (pair.g1_point, G2Prepared::from(pair.g2_point))
+                })
+                .collect::<Vec<_>>();
+            let gt = multi_miller_loop(
+                &ecc_pairs
+                    .iter()
+                    .map(|(g1, g2)| (g1, g2))
+                    .collect::<Vec<_>>(),
+            );
             let gt = gt.final_exponentiation();
             gt.eq(&Gt::identity()) as u8
         },
         pairing_check
     );
+    let aux_data = EcPairingAuxData(op.clone());
 
     (
         Some(PrecompileEvent::EcPairing(Box::new(op))),
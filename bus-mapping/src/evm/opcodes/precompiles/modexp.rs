@@ -3,8 +3,6 @@ use crate::{
     precompile::{ModExpAuxData, PrecompileAuxData},
 };
 
-use eth_types::Word;
-
 pub(crate) fn opt_data(
     input_bytes: Option<Vec<u8>>,
     output_bytes: Option<Vec<u8>>,
@@ -15,10 +13,10 @@ pub(crate) fn opt_data(
     );
     if aux_data.valid {
         let event = BigModExp {
-            base: Word::from_big_endian(&aux_data.inputs[0]),
-            exponent: Word::from_big_endian(&aux_data.inputs[1]),
-            modulus: Word::from_big_endian(&aux_data.inputs[2]),
-            result: Word::from_big_endian(&aux_data.output),
+            base: aux_data.inputs[0],
+            exponent: aux_data.inputs[1],
+            modulus: aux_data.inputs[2],
+            result: aux_data.output,
         };
         (
             Some(PrecompileEvent::ModExp(event)),
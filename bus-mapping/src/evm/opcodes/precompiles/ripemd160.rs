@@ -0,0 +1,33 @@
+use crate::{
+    circuit_input_builder::{PrecompileEvent, RIPEMD160},
+    precompile::{PrecompileAuxData, RIPEMD160AuxData},
+};
+
+pub(crate) fn opt_data(
+    input_bytes: Option<Vec<u8>>,
+    output_bytes: Option<Vec<u8>>,
+) -> (Option<PrecompileEvent>, Option<PrecompileAuxData>) {
+    let input = input_bytes.unwrap_or_default();
+    let output = output_bytes.unwrap_or_default();
+    let aux_data = RIPEMD160AuxData {
+        input: input.clone(),
+        output: output.clone(),
+    };
+
+    // the call has failed (out of gas) if there is no output.
+    if output.is_empty() {
+        return (None, Some(PrecompileAuxData::RIPEMD160(aux_data)));
+    }
+    // the 20 bytes digest is left-padded with zeroes to 32 bytes.
+    assert_eq!(output.len(), 32);
+    assert!(output[..12].iter().all(|&byte| byte == 0));
+    let event = RIPEMD160 {
+        input,
+        digest: output[12..].try_into().expect("digest is 20 bytes"),
+    };
+
+    (
+        Some(PrecompileEvent::RIPEMD160(event)),
+        Some(PrecompileAuxData::RIPEMD160(aux_data)),
+    )
+}
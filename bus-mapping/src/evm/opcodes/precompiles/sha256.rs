@@ -0,0 +1,31 @@
+use crate::{
+    circuit_input_builder::{PrecompileEvent, SHA256},
+    precompile::{PrecompileAuxData, SHA256AuxData},
+};
+
+pub(crate) fn opt_data(
+    input_bytes: Option<Vec<u8>>,
+    output_bytes: Option<Vec<u8>>,
+) -> (Option<PrecompileEvent>, Option<PrecompileAuxData>) {
+    let input = input_bytes.unwrap_or_default();
+    let output = output_bytes.unwrap_or_default();
+    let aux_data = SHA256AuxData {
+        input: input.clone(),
+        output: output.clone(),
+    };
+
+    // the call has failed (out of gas) if there is no output.
+    if output.is_empty() {
+        return (None, Some(PrecompileAuxData::SHA256(aux_data)));
+    }
+    assert_eq!(output.len(), 32);
+    let event = SHA256 {
+        input,
+        digest: output.try_into().expect("digest is 32 bytes"),
+    };
+
+    (
+        Some(PrecompileEvent::SHA256(event)),
+        Some(PrecompileAuxData::SHA256(aux_data)),
+    )
+}
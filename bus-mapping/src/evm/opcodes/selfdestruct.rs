@@ -0,0 +1,401 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    operation::{
+        AccountField, AccountOp, CallContextField, TxAccessListAccountOp, TxCreatedAccountOp, RW,
+    },
+    Error,
+};
+use eth_types::{GethExecStep, ToAddress, ToWord, Word, U256};
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the
+/// [`OpcodeId::SELFDESTRUCT`](crate::evm::OpcodeId::SELFDESTRUCT) `OpcodeId`.
+///
+/// The whole balance of the current account is transferred to the
+/// beneficiary. Per EIP-6780 the current account is only destroyed, by
+/// resetting its balance, nonce and code hash, when it was created in the same
+/// transaction. No gas refund is given since EIP-3529.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct SelfDestruct;
+
+impl Opcode for SelfDestruct {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+
+        let call = state.call()?.clone();
+        let sender = call.address;
+
+        // Read beneficiary address from stack.
+        let beneficiary_word = geth_step.stack.last()?;
+        let beneficiary = beneficiary_word.to_address();
+        state.stack_read(
+            &mut exec_step,
+            geth_step.stack.last_filled(),
+            beneficiary_word,
+        )?;
+
+        for (field, value) in [
+            (CallContextField::TxId, state.tx_ctx.id().into()),
+            (CallContextField::IsStatic, (call.is_static as u64).into()),
+            (
+                CallContextField::RwCounterEndOfReversion,
+                call.rw_counter_end_of_reversion.into(),
+            ),
+            (
+                CallContextField::IsPersistent,
+                (call.is_persistent as u64).into(),
+            ),
+            (CallContextField::CalleeAddress, sender.to_word()),
+        ] {
+            state.call_context_read(&mut exec_step, call.call_id, field, value);
+        }
+
+        // Update transaction access list for beneficiary address.
+        let is_warm = state.sdb.check_account_in_access_list(&beneficiary);
+        state.push_op_reversible(
+            &mut exec_step,
+            TxAccessListAccountOp {
+                tx_id: state.tx_ctx.id(),
+                address: beneficiary,
+                is_warm: true,
+                is_warm_prev: is_warm,
+            },
+        )?;
+
+        // Read beneficiary code hash, which is 0 for a non-existing account.
+        let beneficiary_account = state.sdb.get_account(&beneficiary).1;
+        let beneficiary_exists = !beneficiary_account.is_empty();
+        let beneficiary_code_hash = if beneficiary_exists {
+            beneficiary_account.code_hash.to_word()
+        } else {
+            Word::zero()
+        };
+        state.account_read(
+            &mut exec_step,
+            beneficiary,
+            AccountField::CodeHash,
+            beneficiary_code_hash,
+        );
+
+        // Read current balance, which is fully transferred to the beneficiary.
+        let (found, sender_account) = state.sdb.get_account(&sender);
+        if !found {
+            return Err(Error::AccountNotFound(sender));
+        }
+        let value = sender_account.balance;
+        state.account_read(&mut exec_step, sender, AccountField::Balance, value);
+
+        // Read whether the current account was created in this transaction.
+        let is_created = state.sdb.check_account_created(&sender);
+        state.push_op(
+            &mut exec_step,
+            RW::READ,
+            TxCreatedAccountOp {
+                tx_id: state.tx_ctx.id(),
+                address: sender,
+                is_created,
+                is_created_prev: is_created,
+            },
+        );
+        log::trace!(
+            "self destruct, sender {:?} beneficiary {:?} value {:?} is_created {:?}",
+            sender,
+            beneficiary,
+            value,
+            is_created
+        );
+
+        state.transfer(
+            &mut exec_step,
+            sender,
+            beneficiary,
+            beneficiary_exists,
+            false,
+            value,
+        )?;
+
+        // Destroy the current account only if it was created in this
+        // transaction. If the beneficiary is the current account itself, the
+        // transferred balance is burnt here, otherwise it is kept.
+        if is_created {
+            let sender_account = state.sdb.get_account(&sender).1.clone();
+            for (field, value_prev) in [
+                (AccountField::Balance, sender_account.balance),
+                (AccountField::Nonce, sender_account.nonce),
+                (AccountField::CodeHash, sender_account.code_hash.to_word()),
+            ] {
+                state.push_op_reversible(
+                    &mut exec_step,
+                    AccountOp {
+                        address: sender,
+                        field,
+                        value: Word::zero(),
+                        value_prev,
+                    },
+                )?;
+            }
+
+            if call.is_persistent {
+                state.sdb.destruct_account(sender);
+            }
+        }
+
+        state.call_context_read(
+            &mut exec_step,
+            call.call_id,
+            CallContextField::IsSuccess,
+            U256::one(),
+        );
+
+        state.handle_return(&mut exec_step, geth_steps, !call.is_root)?;
+
+        Ok(vec![exec_step])
+    }
+}
+
+#[cfg(test)]
+mod selfdestruct_tests {
+    use super::*;
+    use crate::{
+        circuit_input_builder::ExecState,
+        mock::BlockData,
+        operation::{AccountOp, CallContextOp, StackOp, Target, RW},
+        state_db::CodeDB,
+    };
+    use eth_types::{
+        address, bytecode,
+        evm_types::{OpcodeId, StackAddress},
+        geth_types::GethData,
+        Address, Word,
+    };
+    use ethers_core::utils::get_contract_address;
+    use mock::{TestContext, MOCK_ACCOUNTS};
+    use pretty_assertions::assert_eq;
+
+    /// `beneficiary` is the current account itself when it's `None`. The
+    /// current account is either deployed before the tx, or created by the tx
+    /// itself when `is_created` is true.
+    fn test_ok(beneficiary: Option<Address>, beneficiary_exists: bool, is_created: bool) {
+        let contract = if is_created {
+            get_contract_address(MOCK_ACCOUNTS[1], 0)
+        } else {
+            MOCK_ACCOUNTS[0]
+        };
+        let beneficiary = beneficiary.unwrap_or(contract);
+        let code = bytecode! {
+            PUSH20(beneficiary.to_word())
+            SELFDESTRUCT
+        };
+        let balance = Word::from(10u64.pow(18));
+
+        let block: GethData = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                if is_created {
+                    accs[0].address(MOCK_ACCOUNTS[0]).balance(Word::from(1u64));
+                } else {
+                    accs[0]
+                        .address(MOCK_ACCOUNTS[0])
+                        .balance(balance)
+                        .code(code.clone());
+                }
+                accs[1]
+                    .address(MOCK_ACCOUNTS[1])
+                    .balance(Word::from(10u64.pow(19)));
+                if beneficiary_exists && beneficiary != contract {
+                    accs[2].address(beneficiary).balance(Word::from(1u64));
+                } else {
+                    accs[2]
+                        .address(address!("0x0000000000000000000000000000000000000020"))
+                        .balance(Word::from(1u64));
+                }
+            },
+            |mut txs, accs| {
+                if is_created {
+                    txs[0]
+                        .from(accs[1].address)
+                        .value(balance)
+                        .input(code.clone().into());
+                } else {
+                    txs[0].from(accs[1].address).to(accs[0].address);
+                }
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let step = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::SELFDESTRUCT))
+            .unwrap();
+
+        let container = &builder.block.container;
+        let operation = &container.stack[step.bus_mapping_instance[0].as_usize()];
+        assert_eq!(
+            (operation.rw(), operation.op()),
+            (
+                RW::READ,
+                &StackOp::new(1, StackAddress::from(1023), beneficiary.to_word())
+            )
+        );
+
+        assert_eq!(
+            [1, 2, 3, 4, 5]
+                .map(|idx| &container.call_context[step.bus_mapping_instance[idx].as_usize()])
+                .map(|operation| (operation.rw(), operation.op())),
+            [
+                (
+                    RW::READ,
+                    &CallContextOp::new(1, CallContextField::TxId, Word::from(0x01)),
+                ),
+                (
+                    RW::READ,
+                    &CallContextOp::new(1, CallContextField::IsStatic, Word::from(0x00)),
+                ),
+                (
+                    RW::READ,
+                    &CallContextOp::new(
+                        1,
+                        CallContextField::RwCounterEndOfReversion,
+                        Word::from(0x00)
+                    ),
+                ),
+                (
+                    RW::READ,
+                    &CallContextOp::new(1, CallContextField::IsPersistent, Word::from(0x01)),
+                ),
+                (
+                    RW::READ,
+                    &CallContextOp::new(1, CallContextField::CalleeAddress, contract.to_word()),
+                ),
+            ]
+        );
+
+        let operation = &container.tx_access_list_account[step.bus_mapping_instance[6].as_usize()];
+        assert_eq!(
+            (operation.rw(), operation.op()),
+            (
+                RW::WRITE,
+                &TxAccessListAccountOp {
+                    tx_id: 1,
+                    address: beneficiary,
+                    is_warm: true,
+                    is_warm_prev: beneficiary == contract,
+                }
+            )
+        );
+
+        let operation = &container.account[step.bus_mapping_instance[8].as_usize()];
+        assert_eq!(
+            (operation.rw(), operation.op()),
+            (
+                RW::READ,
+                &AccountOp {
+                    address: contract,
+                    field: AccountField::Balance,
+                    value: balance,
+                    value_prev: balance,
+                }
+            )
+        );
+
+        let operation = &container.tx_created_account[step.bus_mapping_instance[9].as_usize()];
+        assert_eq!(
+            (operation.rw(), operation.op()),
+            (
+                RW::READ,
+                &TxCreatedAccountOp {
+                    tx_id: 1,
+                    address: contract,
+                    is_created,
+                    is_created_prev: is_created,
+                }
+            )
+        );
+
+        let account_ops = step
+            .bus_mapping_instance
+            .iter()
+            .filter(|op_ref| op_ref.target() == Target::Account)
+            .map(|op_ref| &container.account[op_ref.as_usize()])
+            .collect::<Vec<_>>();
+        let (found, account) = builder.sdb.get_account(&contract);
+        if is_created {
+            // The last three account writes destroy the current account.
+            let destruct_ops = &account_ops[account_ops.len() - 3..];
+            assert!(destruct_ops
+                .iter()
+                .all(|operation| operation.rw() == RW::WRITE
+                    && operation.op().address == contract
+                    && operation.op().value.is_zero()));
+            let expected_balance_prev = if beneficiary == contract {
+                balance
+            } else {
+                Word::zero()
+            };
+            assert_eq!(destruct_ops[0].op().value_prev, expected_balance_prev);
+
+            // The current account is reset in state db after a successful tx.
+            assert!(account.is_empty());
+        } else {
+            // Only the balance is moved, the nonce and code hash are untouched.
+            assert!(account_ops
+                .iter()
+                .all(|operation| operation.op().address != contract
+                    || operation.op().field == AccountField::Balance));
+
+            // The current account survives the tx, and keeps its balance only
+            // when it's the beneficiary.
+            let expected_balance = if beneficiary == contract {
+                balance
+            } else {
+                Word::zero()
+            };
+            assert!(found);
+            assert_eq!(account.balance, expected_balance);
+            assert_eq!(account.code_hash, CodeDB::hash(&code.to_vec()));
+        }
+    }
+
+    #[test]
+    fn selfdestruct_opcode_impl_existing_beneficiary() {
+        test_ok(Some(MOCK_ACCOUNTS[2]), true, false);
+    }
+
+    #[test]
+    fn selfdestruct_opcode_impl_non_existing_beneficiary() {
+        test_ok(Some(MOCK_ACCOUNTS[3]), false, false);
+    }
+
+    #[test]
+    fn selfdestruct_opcode_impl_self_beneficiary() {
+        test_ok(None, true, false);
+    }
+
+    #[test]
+    fn selfdestruct_opcode_impl_created_existing_beneficiary() {
+        test_ok(Some(MOCK_ACCOUNTS[2]), true, true);
+    }
+
+    #[test]
+    fn selfdestruct_opcode_impl_created_non_existing_beneficiary() {
+        test_ok(Some(MOCK_ACCOUNTS[3]), false, true);
+    }
+
+    #[test]
+    fn selfdestruct_opcode_impl_created_self_beneficiary() {
+        test_ok(None, true, true);
+    }
+}
@@ -0,0 +1,74 @@
+use crate::mock::BlockData;
+use eth_types::{
+    bytecode,
+    evm_types::GasCost,
+    geth_types::{GethData, SetCodeAuthorization, EIP7702_TX_TYPE},
+    U64,
+};
+use ethers_signers::Signer;
+use mock::{
+    test_ctx::{helpers::account_0_code_wallet_0_no_code, TestContext},
+    MOCK_ACCOUNTS, MOCK_CHAIN_ID, MOCK_WALLETS,
+};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn set_code_skips_unrecoverable_authority() {
+    let valid_auth = SetCodeAuthorization::new_signed(
+        (*MOCK_CHAIN_ID).into(),
+        MOCK_ACCOUNTS[2],
+        U64::zero(),
+        &MOCK_WALLETS[1],
+    );
+    // a recovery id other than 0 or 1 can't be recovered
+    let bad_auth = SetCodeAuthorization {
+        y_parity: U64::from(2),
+        ..SetCodeAuthorization::new_signed(
+            (*MOCK_CHAIN_ID).into(),
+            MOCK_ACCOUNTS[3],
+            U64::zero(),
+            &MOCK_WALLETS[2],
+        )
+    };
+    assert!(bad_auth.authority().is_err());
+    let authorization_list = vec![bad_auth, valid_auth];
+
+    let mut block: GethData = TestContext::<2, 1>::new(
+        None,
+        account_0_code_wallet_0_no_code(bytecode! { STOP }),
+        |mut txs, accs| {
+            txs[0]
+                .transaction_type(EIP7702_TX_TYPE as u64)
+                .from(MOCK_WALLETS[0].clone())
+                .to(accs[0].address)
+                .authorization_list(authorization_list.clone());
+        },
+        |block, _tx| block.number(0xcafeu64),
+    )
+    .unwrap()
+    .into();
+    // The geth tracer doesn't charge the intrinsic gas of the authorizations.
+    if cfg!(not(feature = "rust-tracer")) {
+        let auth_gas = authorization_list.len() as u64 * GasCost::PER_EMPTY_ACCOUNT.as_u64();
+        block.geth_traces[0].gas.0 += auth_gas;
+        for step in block.geth_traces[0].struct_logs.iter_mut() {
+            step.gas.0 -= auth_gas;
+        }
+    }
+
+    let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+
+    // only the recovered authority is applied
+    let tx = &builder.block.txs()[0];
+    assert_eq!(tx.authorization_list.len(), 2);
+    assert_eq!(
+        tx.applied_authorizations
+            .iter()
+            .map(|auth| (auth.authority, auth.is_valid))
+            .collect::<Vec<_>>(),
+        vec![(MOCK_WALLETS[1].address(), true)]
+    );
+}
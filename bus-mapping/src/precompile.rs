@@ -2,6 +2,7 @@
 
 use eth_types::{evm_types::GasCost, Address, ToBigEndian, Word};
 use revm_precompile::{Precompile, Precompiles};
+use serde::{Deserialize, Serialize};
 use strum::EnumIter;
 
 use crate::circuit_input_builder::{EcMulOp, EcPairingOp};
@@ -132,14 +133,13 @@ impl PrecompileCalls {
         match self {
             Self::Ecrecover | Self::Bn128Add => Some(128),
             Self::Bn128Mul => Some(96),
-            Self::Modexp => Some(MODEXP_INPUT_LIMIT),
             _ => None,
         }
     }
 }
 
 /// Auxiliary data for Ecrecover
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcrecoverAuxData {
     /// Keccak hash of the message being signed.
     pub msg_hash: Word,
@@ -185,22 +185,24 @@ impl EcrecoverAuxData {
 }
 
 /// size limit of modexp
-pub const MODEXP_SIZE_LIMIT: usize = 32;
+pub const MODEXP_SIZE_LIMIT: usize = 512;
 /// size of input limit
-pub const MODEXP_INPUT_LIMIT: usize = 192;
+pub const MODEXP_INPUT_LIMIT: usize = 96 + 3 * MODEXP_SIZE_LIMIT;
 
 /// Auxiliary data for Modexp
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ModExpAuxData {
     /// The specified len of inputs: [base, exp, modulus]
     pub input_lens: [Word; 3],
     /// Input value [base, exp, modulus], limited to SIZE_LIMIT
+    #[serde(with = "eth_types::serde_utils::byte_arrays")]
     pub inputs: [[u8; MODEXP_SIZE_LIMIT]; 3],
     /// Input valid.
     pub valid: bool,
     /// len of output, limited to lens of moduls, but can be 0
     pub output_len: usize,
     /// output of modexp.
+    #[serde(with = "eth_types::serde_utils::byte_array")]
     pub output: [u8; MODEXP_SIZE_LIMIT],
     /// backup of input memory
     pub input_memory: Vec<u8>,
@@ -208,6 +210,20 @@ pub struct ModExpAuxData {
     pub output_memory: Vec<u8>,
 }
 
+impl Default for ModExpAuxData {
+    fn default() -> Self {
+        Self {
+            input_lens: Default::default(),
+            inputs: [[0u8; MODEXP_SIZE_LIMIT]; 3],
+            valid: false,
+            output_len: 0,
+            output: [0u8; MODEXP_SIZE_LIMIT],
+            input_memory: Vec::new(),
+            output_memory: Vec::new(),
+        }
+    }
+}
+
 impl ModExpAuxData {
     fn parse_memory_to_value(mem: &[u8]) -> [u8; MODEXP_SIZE_LIMIT] {
         let mut value_bytes = [0u8; MODEXP_SIZE_LIMIT];
@@ -219,10 +235,13 @@ impl ModExpAuxData {
 
     /// check input
     pub fn check_input(input: &[u8]) -> (bool, [Word; 3]) {
-        let mut i = input.chunks(32);
-        let base_len = Word::from_big_endian(i.next().unwrap_or(&[]));
-        let exp_len = Word::from_big_endian(i.next().unwrap_or(&[]));
-        let modulus_len = Word::from_big_endian(i.next().unwrap_or(&[]));
+        // the header is right-padded with zeroes if the input is shorter
+        let mut header = [0u8; 96];
+        let header_len = input.len().min(96);
+        header[..header_len].copy_from_slice(&input[..header_len]);
+        let base_len = Word::from_big_endian(&header[0..32]);
+        let exp_len = Word::from_big_endian(&header[32..64]);
+        let modulus_len = Word::from_big_endian(&header[64..96]);
 
         let limit = Word::from(MODEXP_SIZE_LIMIT);
 
@@ -231,6 +250,18 @@ impl ModExpAuxData {
         (input_valid, [base_len, exp_len, modulus_len])
     }
 
+    /// Length of the input expected from its header, i.e. the header and the
+    /// base, exponent and modulus if the input is valid, or only the header.
+    /// Bytes after it are ignored by the precompile call.
+    pub fn input_len(input: &[u8]) -> usize {
+        let (input_valid, lens) = Self::check_input(input);
+        96 + if input_valid {
+            lens.iter().map(Word::as_usize).sum::<usize>()
+        } else {
+            0
+        }
+    }
+
     /// Create a new instance of modexp auxiliary data.
     pub fn new(mut mem_input: Vec<u8>, output: Vec<u8>) -> Self {
         let input_memory = mem_input.clone();
@@ -270,7 +301,7 @@ impl ModExpAuxData {
 }
 
 /// Auxiliary data for EcAdd, i.e. P + Q = R
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcAddAuxData {
     /// x co-ordinate of the first point.
     pub p_x: Word,
@@ -303,7 +334,7 @@ impl EcAddAuxData {
 }
 
 /// Auxiliary data for EcMul, i.e. s * P = R
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcMulAuxData {
     /// x co-ordinate of the point.
     pub p_x: Word,
@@ -338,11 +369,38 @@ impl EcMulAuxData {
 }
 
 /// Auxiliary data for EcPairing.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EcPairingAuxData(pub EcPairingOp);
 
+/// Auxiliary data for SHA256
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SHA256AuxData {
+    /// input bytes
+    pub input: Vec<u8>,
+    /// output bytes, empty if the call failed
+    pub output: Vec<u8>,
+}
+
+/// Auxiliary data for RIPEMD160
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RIPEMD160AuxData {
+    /// input bytes
+    pub input: Vec<u8>,
+    /// output bytes, empty if the call failed
+    pub output: Vec<u8>,
+}
+
+/// Auxiliary data for BLAKE2F
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BLAKE2FAuxData {
+    /// input bytes
+    pub input: Vec<u8>,
+    /// output bytes, empty if the call failed
+    pub output: Vec<u8>,
+}
+
 /// Auxiliary data attached to an internal state for precompile verification.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PrecompileAuxData {
     /// Ecrecover.
     Ecrecover(EcrecoverAuxData),
@@ -354,6 +412,12 @@ pub enum PrecompileAuxData {
     EcMul(EcMulAuxData),
     /// EcPairing.
     EcPairing(Box<EcPairingAuxData>),
+    /// SHA256.
+    SHA256(SHA256AuxData),
+    /// RIPEMD160.
+    RIPEMD160(RIPEMD160AuxData),
+    /// BLAKE2F.
+    BLAKE2F(BLAKE2FAuxData),
 }
 
 impl Default for PrecompileAuxData {
@@ -120,6 +120,11 @@ pub struct StateDB {
     // Fields with transaction lifespan, will be clear in `clear_access_list_and_refund`.
     access_list_account: HashSet<Address>,
     access_list_account_storage: HashSet<(Address, U256)>,
+    // `transient_storage` is defined by EIP-1153, it's discarded at the end of each transaction.
+    transient_storage: HashMap<(Address, Word), Word>,
+    // Accounts created in the current transaction, which are the only ones `SELFDESTRUCT` can
+    // destroy since EIP-6780.
+    created_account: HashSet<Address>,
     // `dirty_storage` contains writes during current transaction.
     // When current transaction finishes, `dirty_storage` will be committed into `state`.
     // The reason why we need this is that EVM needs committed state, namely
@@ -270,6 +275,36 @@ impl StateDB {
         debug_assert!(exist);
     }
 
+    /// Get a transient storage value, zero if it was not set in the current
+    /// transaction.
+    pub fn get_transient_storage(&self, addr: &Address, key: &Word) -> Word {
+        self.transient_storage
+            .get(&(*addr, *key))
+            .copied()
+            .unwrap_or_else(Word::zero)
+    }
+
+    /// Set a transient storage value.
+    pub fn set_transient_storage(&mut self, addr: &Address, key: &Word, value: &Word) {
+        self.transient_storage.insert((*addr, *key), *value);
+    }
+
+    /// Check whether `addr` was created in the current transaction.
+    pub fn check_account_created(&self, addr: &Address) -> bool {
+        self.created_account.contains(addr)
+    }
+
+    /// Mark `addr` as created in the current transaction.
+    pub fn add_account_created(&mut self, addr: Address) -> bool {
+        self.created_account.insert(addr)
+    }
+
+    /// Remove `addr` from the accounts created in the current transaction.
+    pub fn remove_account_created(&mut self, addr: &Address) {
+        let exist = self.created_account.remove(addr);
+        debug_assert!(exist);
+    }
+
     /// Set account as self destructed.
     pub fn destruct_account(&mut self, addr: Address) {
         self.state.insert(addr, Account::zero());
@@ -286,12 +321,15 @@ impl StateDB {
         self.refund = value;
     }
 
-    /// Clear access list and refund, and commit dirty storage.
+    /// Clear access list, transient storage, created accounts and refund, and
+    /// commit dirty storage.
     /// It should be invoked before processing
     /// with new transaction with the same [`StateDB`].
     pub fn commit_tx(&mut self) {
         self.access_list_account = HashSet::new();
         self.access_list_account_storage = HashSet::new();
+        self.transient_storage = HashMap::new();
+        self.created_account = HashSet::new();
         for ((addr, key), value) in self.dirty_storage.clone() {
             let (_, ptr) = self.get_storage_mut(&addr, &key);
             *ptr = value;
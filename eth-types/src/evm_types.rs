@@ -1,15 +1,18 @@
 //! Evm types needed for parsing instruction sets as well
 
+use crate::Address;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
 pub mod block_utils;
+pub mod chain_spec;
 pub mod gas_utils;
 pub mod memory;
 pub mod opcode_ids;
 pub mod stack;
 pub mod storage;
 
+pub use chain_spec::{ChainSpec, ForkCondition, Hardfork};
 pub use memory::{Memory, MemoryAddress, MemoryRef};
 pub use opcode_ids::OpcodeId;
 pub use stack::{Stack, StackAddress};
@@ -71,39 +74,39 @@ pub const MAX_EXPANDED_MEMORY_ADDRESS: u64 = 0x1FFFFFFFE0;
 pub const MAX_REFUND_QUOTIENT_OF_GAS_USED: usize = 5;
 /// Gas stipend when CALL or CALLCODE is attached with value.
 pub const GAS_STIPEND_CALL_WITH_VALUE: u64 = 2300;
-
-#[cfg(feature = "shanghai")]
-mod gas_create {
-    // For EIP-3860, there are 2 special gas cost constraints in geth
-    // [gasCreate2Eip3860](https://github.com/ethereum/go-ethereum/blob/eb83e7c54021573eaceb14236af3a7a8c64f6027/core/vm/gas_table.go#L321)
-    // (similar for CREATE).
-    // 1. size <= 49152 (MaxInitCodeSize)
-    // 2. gasCost = memoryGasCost + (2 + 6) * ((size + 31) / 32) should not
-    //    overflow for Uint64.
-    // No need to constrain the second condition, since the maximum gas cost
-    // cannot overflow for Uint64 (36028809887100925 calculated by
-    // `memorySize = 0x1FFFFFFFE0` and `size = 49152`) if the first condition is
-    // satisfied.
-
-    /// Maximum init code size to permit in a creation transaction and create instructions.
-    pub const MAX_INIT_CODE_SIZE: u64 = 2 * super::MAX_CODE_SIZE;
-    /// Once per word of the init code when creating a contract.
-    pub const INIT_CODE_WORD_GAS: u64 = 2;
-    /// Gas per code word for CREATE.
-    pub const CREATE_GAS_PER_CODE_WORD: u64 = INIT_CODE_WORD_GAS;
-    /// Gas per code word for CREATE2.
-    pub const CREATE2_GAS_PER_CODE_WORD: u64 = INIT_CODE_WORD_GAS + super::GasCost::COPY_SHA3.0;
+/// Prefix of the code an EIP-7702 authority is delegated with.
+pub const DELEGATION_PREFIX: [u8; 3] = [0xef, 0x01, 0x00];
+/// Size of an EIP-7702 delegation designator, `0xef0100 || address`.
+pub const DELEGATION_CODE_SIZE: usize = DELEGATION_PREFIX.len() + 20;
+
+/// Return the EIP-7702 delegation designator pointing to `address`.
+pub fn delegation_code(address: Address) -> Vec<u8> {
+    [DELEGATION_PREFIX.as_slice(), address.as_bytes()].concat()
 }
-#[cfg(not(feature = "shanghai"))]
-mod gas_create {
-    /// Maximum init code size (0x1FFFFFFFE0) if not EIP-3860.
-    pub use super::MAX_EXPANDED_MEMORY_ADDRESS as MAX_INIT_CODE_SIZE;
-    /// Gas per code word for CREATE if not EIP-3860.
-    pub const CREATE_GAS_PER_CODE_WORD: u64 = 0;
-    /// Gas per code word for CREATE2 if not EIP-3860.
-    pub const CREATE2_GAS_PER_CODE_WORD: u64 = super::GasCost::COPY_SHA3.0;
+
+/// Return the delegate address if `code` is an EIP-7702 delegation designator.
+pub fn parse_delegation(code: &[u8]) -> Option<Address> {
+    (code.len() == DELEGATION_CODE_SIZE && code.starts_with(&DELEGATION_PREFIX))
+        .then(|| Address::from_slice(&code[DELEGATION_PREFIX.len()..]))
 }
-pub use gas_create::*;
+
+// For EIP-3860, there are 2 special gas cost constraints in geth
+// [gasCreate2Eip3860](https://github.com/ethereum/go-ethereum/blob/eb83e7c54021573eaceb14236af3a7a8c64f6027/core/vm/gas_table.go#L321)
+// (similar for CREATE).
+// 1. size <= 49152 (MaxInitCodeSize)
+// 2. gasCost = memoryGasCost + (2 + 6) * ((size + 31) / 32) should not
+//    overflow for Uint64.
+// No need to constrain the second condition, since the maximum gas cost
+// cannot overflow for Uint64 (36028809887100925 calculated by
+// `memorySize = 0x1FFFFFFFE0` and `size = 49152`) if the first condition is
+// satisfied.
+// Both only apply since Shanghai (see `Hardfork::max_init_code_size`).
+
+/// Maximum init code size to permit in a creation transaction and create
+/// instructions (EIP-3860).
+pub const MAX_INIT_CODE_SIZE: u64 = 2 * MAX_CODE_SIZE;
+/// Once per word of the init code when creating a contract (EIP-3860).
+pub const INIT_CODE_WORD_GAS: u64 = 2;
 
 /// Defines the gas consumption.
 #[derive(Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -162,10 +165,18 @@ impl GasCost {
     pub const TX: Self = Self(21000);
     /// Constant cost for a creation transaction
     pub const CREATION_TX: Self = Self(53000);
+    /// Constant cost for every address in the access list of a transaction
+    pub const ACCESS_LIST_ADDRESS: Self = Self(2400);
+    /// Constant cost for every storage key in the access list of a transaction
+    pub const ACCESS_LIST_STORAGE_KEY: Self = Self(1900);
     /// Constant cost for calling with non-zero value
     pub const CALL_WITH_VALUE: Self = Self(9000);
     /// Constant cost for turning empty account into non-empty account
     pub const NEW_ACCOUNT: Self = Self(25000);
+    /// Intrinsic cost for every authorization tuple of an EIP-7702 transaction
+    pub const PER_EMPTY_ACCOUNT: Self = Self(25000);
+    /// Part of `PER_EMPTY_ACCOUNT` refunded when the authority already exists
+    pub const PER_AUTH_BASE: Self = Self(12500);
     /// Cost per byte of deploying a new contract
     pub const CODE_DEPOSIT_BYTE_COST: Self = Self(200);
     /// Denominator of quadratic part of memory expansion gas cost
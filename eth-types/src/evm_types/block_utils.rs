@@ -6,6 +6,18 @@ use ethers_core::utils::keccak256;
 /// Maximum range of previous blocks allowed inside BLOCKHASH opcode
 pub const NUM_PREV_BLOCK_ALLOWED: u64 = 256;
 
+/// Blob gas consumed by each blob (EIP-4844)
+pub const GAS_PER_BLOB: u64 = 1 << 17;
+/// Maximum blob gas consumed by all the blobs of a block (EIP-4844)
+pub const MAX_BLOB_GAS_PER_BLOCK: u64 = 786432;
+/// Maximum number of blobs in a block, which also bounds the number of
+/// versioned hashes carried by a single transaction.
+pub const MAX_BLOBS_PER_BLOCK: usize = (MAX_BLOB_GAS_PER_BLOCK / GAS_PER_BLOB) as usize;
+/// Minimum base fee per blob gas (EIP-4844)
+pub const MIN_BLOB_BASE_FEE: u64 = 1;
+/// Denominator of the exponential update of the blob base fee (EIP-4844)
+pub const BLOB_BASE_FEE_UPDATE_FRACTION: u64 = 3338477;
+
 /// Calculate block hash by chain ID and block number (only for scroll).
 /// Return a pair of input and output.
 pub fn calculate_block_hash(chain_id: u64, block_number: U256) -> (Vec<u8>, U256) {
@@ -27,3 +39,45 @@ pub fn is_valid_block_number(block_number: U256, current_block_number: U256) ->
                 .checked_sub(NUM_PREV_BLOCK_ALLOWED.into())
                 .unwrap_or_default()
 }
+
+/// Calculate the base fee per blob gas from the excess blob gas of the block
+/// header, as returned by the BLOBBASEFEE opcode (EIP-4844).
+pub fn calculate_blob_base_fee(excess_blob_gas: u64) -> U256 {
+    fake_exponential(
+        MIN_BLOB_BASE_FEE.into(),
+        excess_blob_gas.into(),
+        BLOB_BASE_FEE_UPDATE_FRACTION.into(),
+    )
+}
+
+/// Approximate `factor * e ** (numerator / denominator)` using Taylor
+/// expansion, as specified by EIP-4844.
+fn fake_exponential(factor: U256, numerator: U256, denominator: U256) -> U256 {
+    let mut output = U256::zero();
+    let mut numerator_accum = factor * denominator;
+    let mut i = U256::one();
+    while !numerator_accum.is_zero() {
+        output += numerator_accum;
+        numerator_accum = numerator_accum * numerator / (denominator * i);
+        i += U256::one();
+    }
+    output / denominator
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blob_base_fee() {
+        // test vectors of `fake_exponential` from the EIP-4844 test suite
+        assert_eq!(calculate_blob_base_fee(0), 1.into());
+        assert_eq!(calculate_blob_base_fee(2314057), 1.into());
+        assert_eq!(calculate_blob_base_fee(2314058), 2.into());
+        assert_eq!(calculate_blob_base_fee(10 * 1024 * 1024), 23.into());
+        assert_eq!(
+            fake_exponential(2.into(), 5.into(), 2.into()),
+            U256::from(23)
+        );
+    }
+}
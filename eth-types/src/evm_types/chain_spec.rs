@@ -0,0 +1,190 @@
+//! Hard fork schedule of a chain, selecting the EVM rules of each block at
+//! runtime.
+
+use super::{GasCost, INIT_CODE_WORD_GAS, MAX_EXPANDED_MEMORY_ADDRESS, MAX_INIT_CODE_SIZE};
+use serde::{Deserialize, Serialize};
+
+/// Hard forks supported on top of London, in activation order. The
+/// discriminant is the value of the fork in the block table.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum Hardfork {
+    /// London, which is always active.
+    #[default]
+    London = 0,
+    /// Shanghai: PUSH0 (EIP-3855), warm COINBASE (EIP-3651) and the init code
+    /// limit and metering (EIP-3860).
+    Shanghai = 1,
+    /// Cancun: transient storage (EIP-1153), MCOPY (EIP-5656) and the blob
+    /// opcodes (EIP-4844, EIP-7516).
+    Cancun = 2,
+}
+
+impl Hardfork {
+    /// Maximum init code size of a creation tx or a CREATE/CREATE2. Before
+    /// EIP-3860 it is only bounded by the maximum value of offset + size
+    /// (0x1FFFFFFFE0).
+    pub fn max_init_code_size(&self) -> u64 {
+        if *self >= Self::Shanghai {
+            MAX_INIT_CODE_SIZE
+        } else {
+            MAX_EXPANDED_MEMORY_ADDRESS
+        }
+    }
+
+    /// Gas per word of the init code of a creation tx or a CREATE (EIP-3860).
+    pub fn init_code_word_gas(&self) -> u64 {
+        if *self >= Self::Shanghai {
+            INIT_CODE_WORD_GAS
+        } else {
+            0
+        }
+    }
+
+    /// Gas per word of the init code of a CREATE2, which also pays for hashing
+    /// the init code.
+    pub fn create2_gas_per_code_word(&self) -> u64 {
+        self.init_code_word_gas() + GasCost::COPY_SHA3.0
+    }
+
+    /// Whether EIP-3651 (warm COINBASE) is active.
+    pub fn is_coinbase_warm(&self) -> bool {
+        *self >= Self::Shanghai
+    }
+}
+
+/// Condition for the activation of a hard fork.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ForkCondition {
+    /// Activated at the block with the given number.
+    Block(u64),
+    /// Activated at the first block with a timestamp not less than the given
+    /// one.
+    Timestamp(u64),
+    /// Never activated.
+    #[default]
+    Never,
+}
+
+impl ForkCondition {
+    /// Whether the fork is active in the block with the given number and
+    /// timestamp.
+    pub fn is_active(&self, number: u64, timestamp: u64) -> bool {
+        match *self {
+            Self::Block(block) => number >= block,
+            Self::Timestamp(time) => timestamp >= time,
+            Self::Never => false,
+        }
+    }
+}
+
+/// Hard fork schedule of a chain. London is always active, while each later
+/// fork is activated by its [`ForkCondition`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainSpec {
+    /// Activation of Shanghai
+    pub shanghai: ForkCondition,
+    /// Activation of Cancun, which must not precede Shanghai
+    pub cancun: ForkCondition,
+}
+
+impl Default for ChainSpec {
+    /// Activate the forks enabled by the `shanghai` and `cancun` features from
+    /// genesis, as the chain config of the mock tracer does.
+    fn default() -> Self {
+        if cfg!(feature = "cancun") {
+            Self::cancun()
+        } else if cfg!(feature = "shanghai") {
+            Self::shanghai()
+        } else {
+            Self::london()
+        }
+    }
+}
+
+impl ChainSpec {
+    /// Chain spec with only London active.
+    pub const fn london() -> Self {
+        Self {
+            shanghai: ForkCondition::Never,
+            cancun: ForkCondition::Never,
+        }
+    }
+
+    /// Chain spec with Shanghai active from genesis.
+    pub const fn shanghai() -> Self {
+        Self {
+            shanghai: ForkCondition::Block(0),
+            cancun: ForkCondition::Never,
+        }
+    }
+
+    /// Chain spec with Cancun active from genesis.
+    pub const fn cancun() -> Self {
+        Self {
+            shanghai: ForkCondition::Block(0),
+            cancun: ForkCondition::Block(0),
+        }
+    }
+
+    /// Chain spec of Ethereum mainnet.
+    pub const fn mainnet() -> Self {
+        Self {
+            shanghai: ForkCondition::Timestamp(1681338455),
+            cancun: ForkCondition::Timestamp(1710338135),
+        }
+    }
+
+    /// Return the activation condition of a hard fork.
+    pub fn condition(&self, fork: Hardfork) -> ForkCondition {
+        match fork {
+            Hardfork::London => ForkCondition::Block(0),
+            Hardfork::Shanghai => self.shanghai,
+            Hardfork::Cancun => self.cancun,
+        }
+    }
+
+    /// Return the latest hard fork active in the block with the given number
+    /// and timestamp.
+    pub fn hardfork_at(&self, number: u64, timestamp: u64) -> Hardfork {
+        [Hardfork::Cancun, Hardfork::Shanghai]
+            .into_iter()
+            .find(|fork| self.condition(*fork).is_active(number, timestamp))
+            .unwrap_or(Hardfork::London)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hardfork_at() {
+        let spec = ChainSpec {
+            shanghai: ForkCondition::Block(10),
+            cancun: ForkCondition::Timestamp(1000),
+        };
+        assert_eq!(spec.hardfork_at(9, 999), Hardfork::London);
+        assert_eq!(spec.hardfork_at(10, 999), Hardfork::Shanghai);
+        assert_eq!(spec.hardfork_at(10, 1000), Hardfork::Cancun);
+        assert_eq!(
+            ChainSpec::london().hardfork_at(u64::MAX, u64::MAX),
+            Hardfork::London
+        );
+        assert_eq!(ChainSpec::cancun().hardfork_at(0, 0), Hardfork::Cancun);
+    }
+
+    #[test]
+    fn init_code_rules() {
+        assert_eq!(
+            Hardfork::London.max_init_code_size(),
+            MAX_EXPANDED_MEMORY_ADDRESS
+        );
+        assert_eq!(Hardfork::Shanghai.max_init_code_size(), 49152);
+        assert_eq!(Hardfork::London.init_code_word_gas(), 0);
+        assert_eq!(Hardfork::Cancun.init_code_word_gas(), 2);
+        assert_eq!(Hardfork::London.create2_gas_per_code_word(), 6);
+        assert_eq!(Hardfork::Shanghai.create2_gas_per_code_word(), 8);
+    }
+}
@@ -1,5 +1,8 @@
 //! Doc this
-use crate::{error::Error, evm_types::GasCost};
+use crate::{
+    error::Error,
+    evm_types::{GasCost, Hardfork},
+};
 use core::fmt::Debug;
 use lazy_static::lazy_static;
 use regex::Regex;
@@ -93,6 +96,12 @@ pub enum OpcodeId {
     MSIZE,
     /// `JUMPDEST`
     JUMPDEST,
+    /// `TLOAD`
+    TLOAD,
+    /// `TSTORE`
+    TSTORE,
+    /// `MCOPY`
+    MCOPY,
 
     // PUSHn
     /// `PUSH0`
@@ -281,6 +290,10 @@ pub enum OpcodeId {
     SELFBALANCE,
     /// `BASEFEE`
     BASEFEE,
+    /// `BLOBHASH`
+    BLOBHASH,
+    /// `BLOBBASEFEE`
+    BLOBBASEFEE,
     /// `SLOAD`
     SLOAD,
     /// `SSTORE`
@@ -317,15 +330,26 @@ pub enum OpcodeId {
 }
 
 impl OpcodeId {
-    #[cfg(feature = "shanghai")]
     /// Returns `true` if the `OpcodeId` is a `PUSHn` (including `PUSH0`).
     pub fn is_push(&self) -> bool {
         self.as_u8() >= Self::PUSH0.as_u8() && self.as_u8() <= Self::PUSH32.as_u8()
     }
-    #[cfg(not(feature = "shanghai"))]
-    /// Returns `true` if the `OpcodeId` is a `PUSHn`.
-    pub fn is_push(&self) -> bool {
-        self.as_u8() >= Self::PUSH1.as_u8() && self.as_u8() <= Self::PUSH32.as_u8()
+
+    /// Returns the hard fork which activates the `OpcodeId`, which is London
+    /// for the opcodes defined before.
+    pub fn activation_fork(&self) -> Hardfork {
+        match self {
+            Self::PUSH0 => Hardfork::Shanghai,
+            Self::TLOAD | Self::TSTORE | Self::MCOPY | Self::BLOBHASH | Self::BLOBBASEFEE => {
+                Hardfork::Cancun
+            }
+            _ => Hardfork::London,
+        }
+    }
+
+    /// Returns `true` if the `OpcodeId` is defined in the given hard fork.
+    pub fn is_enabled(&self, fork: Hardfork) -> bool {
+        !matches!(self, Self::INVALID(_)) && self.activation_fork() <= fork
     }
 
     /// Returns `true` if the `OpcodeId` is a `PUSH1` .. `PUSH32` (excluding `PUSH0`).
@@ -425,6 +449,9 @@ impl OpcodeId {
             OpcodeId::PC => 0x58u8,
             OpcodeId::MSIZE => 0x59u8,
             OpcodeId::JUMPDEST => 0x5bu8,
+            OpcodeId::TLOAD => 0x5cu8,
+            OpcodeId::TSTORE => 0x5du8,
+            OpcodeId::MCOPY => 0x5eu8,
             OpcodeId::PUSH0 => 0x5fu8,
             OpcodeId::PUSH1 => 0x60u8,
             OpcodeId::PUSH2 => 0x61u8,
@@ -514,6 +541,8 @@ impl OpcodeId {
             OpcodeId::CHAINID => 0x46u8,
             OpcodeId::SELFBALANCE => 0x47u8,
             OpcodeId::BASEFEE => 0x48u8,
+            OpcodeId::BLOBHASH => 0x49u8,
+            OpcodeId::BLOBBASEFEE => 0x4au8,
             OpcodeId::SLOAD => 0x54u8,
             OpcodeId::SSTORE => 0x55u8,
             OpcodeId::GAS => 0x5au8,
@@ -592,6 +621,8 @@ impl OpcodeId {
             OpcodeId::CHAINID => GasCost::QUICK,
             OpcodeId::SELFBALANCE => GasCost::FAST,
             OpcodeId::BASEFEE => GasCost::QUICK,
+            OpcodeId::BLOBHASH => GasCost::FASTEST,
+            OpcodeId::BLOBBASEFEE => GasCost::QUICK,
             OpcodeId::POP => GasCost::QUICK,
             OpcodeId::MLOAD => GasCost::FASTEST,
             OpcodeId::MSTORE => GasCost::FASTEST,
@@ -604,6 +635,9 @@ impl OpcodeId {
             OpcodeId::MSIZE => GasCost::QUICK,
             OpcodeId::GAS => GasCost::QUICK,
             OpcodeId::JUMPDEST => GasCost::ONE,
+            OpcodeId::TLOAD => GasCost::WARM_ACCESS,
+            OpcodeId::TSTORE => GasCost::WARM_ACCESS,
+            OpcodeId::MCOPY => GasCost::FASTEST,
             OpcodeId::PUSH0 => GasCost::QUICK,
             OpcodeId::PUSH1 => GasCost::FASTEST,
             OpcodeId::PUSH2 => GasCost::FASTEST,
@@ -687,9 +721,10 @@ impl OpcodeId {
         }
     }
 
-    /// Returns invalid stack pointers of `OpcodeId`
-    pub fn invalid_stack_ptrs(&self) -> Vec<u32> {
-        let (min_stack_ptr, max_stack_ptr): (u32, u32) = match self {
+    /// Returns the range `(min_stack_ptr, max_stack_ptr)` of stack pointers
+    /// with which `OpcodeId` runs without stack overflow or underflow.
+    pub fn valid_stack_ptr_range(&self) -> (u32, u32) {
+        match self {
             // `min_stack_pointer` 0 means stack overflow never happen, for example, `OpcodeId::ADD`
             // can only encounter underflow error, but never encounter overflow error.
             // `max_stack_pointer` means max stack poniter for op code normally run. for example,
@@ -747,6 +782,8 @@ impl OpcodeId {
             OpcodeId::CHAINID => (1, 1024),
             OpcodeId::SELFBALANCE => (1, 1024),
             OpcodeId::BASEFEE => (1, 1024),
+            OpcodeId::BLOBHASH => (0, 1023),
+            OpcodeId::BLOBBASEFEE => (1, 1024),
             OpcodeId::POP => (0, 1023),
             OpcodeId::MLOAD => (0, 1023),
             OpcodeId::MSTORE => (0, 1022),
@@ -759,6 +796,9 @@ impl OpcodeId {
             OpcodeId::MSIZE => (1, 1024),
             OpcodeId::GAS => (1, 1024),
             OpcodeId::JUMPDEST => (0, 1024),
+            OpcodeId::TLOAD => (0, 1023),
+            OpcodeId::TSTORE => (0, 1022),
+            OpcodeId::MCOPY => (0, 1021),
             OpcodeId::PUSH0 => (1, 1024),
             OpcodeId::PUSH1 => (1, 1024),
             OpcodeId::PUSH2 => (1, 1024),
@@ -840,7 +880,12 @@ impl OpcodeId {
             OpcodeId::REVERT => (0, 1022),
             OpcodeId::SELFDESTRUCT => (0, 1023),
             _ => (0, 0),
-        };
+        }
+    }
+
+    /// Returns invalid stack pointers of `OpcodeId`
+    pub fn invalid_stack_ptrs(&self) -> Vec<u32> {
+        let (min_stack_ptr, max_stack_ptr) = self.valid_stack_ptr_range();
 
         debug_assert!(max_stack_ptr <= 1024);
 
@@ -861,6 +906,7 @@ impl OpcodeId {
                 | OpcodeId::RETURNDATACOPY
                 | OpcodeId::CODECOPY
                 | OpcodeId::EXTCODECOPY
+                | OpcodeId::MCOPY
         )
     }
 
@@ -910,7 +956,16 @@ impl OpcodeId {
         })
     }
 
-    /// Returns the all invalid opcodes.
+    /// Returns the opcodes activated by a hard fork after London, which are
+    /// invalid before the fork.
+    pub fn hardfork_opcodes() -> Vec<Self> {
+        Self::valid_opcodes()
+            .into_iter()
+            .filter(|op| op.activation_fork() > Hardfork::London)
+            .collect()
+    }
+
+    /// Returns the all invalid opcodes, regardless of the hard fork.
     pub fn invalid_opcodes() -> Vec<Self> {
         (u8::MIN..=u8::MAX).fold(vec![], |mut acc, val| {
             if matches!(val.into(), Self::INVALID(_)) {
@@ -964,7 +1019,9 @@ impl From<u8> for OpcodeId {
             0x58u8 => OpcodeId::PC,
             0x59u8 => OpcodeId::MSIZE,
             0x5bu8 => OpcodeId::JUMPDEST,
-            #[cfg(feature = "shanghai")]
+            0x5cu8 => OpcodeId::TLOAD,
+            0x5du8 => OpcodeId::TSTORE,
+            0x5eu8 => OpcodeId::MCOPY,
             0x5fu8 => OpcodeId::PUSH0,
             0x60u8 => OpcodeId::PUSH1,
             0x61u8 => OpcodeId::PUSH2,
@@ -1055,6 +1112,8 @@ impl From<u8> for OpcodeId {
             0x47u8 => OpcodeId::SELFBALANCE,
             #[cfg(not(feature = "scroll"))]
             0x48u8 => OpcodeId::BASEFEE,
+            0x49u8 => OpcodeId::BLOBHASH,
+            0x4au8 => OpcodeId::BLOBBASEFEE,
             0x54u8 => OpcodeId::SLOAD,
             0x55u8 => OpcodeId::SSTORE,
             0x5au8 => OpcodeId::GAS,
@@ -1121,10 +1180,10 @@ impl FromStr for OpcodeId {
             "PC" => OpcodeId::PC,
             "MSIZE" => OpcodeId::MSIZE,
             "JUMPDEST" => OpcodeId::JUMPDEST,
-            #[cfg(feature = "shanghai")]
+            "TLOAD" => OpcodeId::TLOAD,
+            "TSTORE" => OpcodeId::TSTORE,
+            "MCOPY" => OpcodeId::MCOPY,
             "PUSH0" => OpcodeId::PUSH0,
-            #[cfg(not(feature = "shanghai"))]
-            "PUSH0" => OpcodeId::INVALID(0x5f),
             "PUSH1" => OpcodeId::PUSH1,
             "PUSH2" => OpcodeId::PUSH2,
             "PUSH3" => OpcodeId::PUSH3,
@@ -1234,8 +1293,8 @@ impl FromStr for OpcodeId {
             "BASEFEE" => OpcodeId::BASEFEE,
             #[cfg(feature = "scroll")]
             "BASEFEE" => OpcodeId::INVALID(0x48),
-            "TLOAD" => OpcodeId::INVALID(0xb3),
-            "TSTORE" => OpcodeId::INVALID(0xb4),
+            "BLOBHASH" => OpcodeId::BLOBHASH,
+            "BLOBBASEFEE" => OpcodeId::BLOBBASEFEE,
             _ => {
                 // Parse an invalid opcode value as reported by geth
                 lazy_static! {
@@ -1277,13 +1336,7 @@ mod opcode_ids_tests {
 
     #[test]
     fn push_n() {
-        #[cfg(feature = "shanghai")]
         assert!(matches!(OpcodeId::push_n(0), Ok(OpcodeId::PUSH0)));
-        #[cfg(not(feature = "shanghai"))]
-        assert!(matches!(
-            OpcodeId::push_n(0),
-            Err(Error::InvalidOpConversion)
-        ));
         assert!(matches!(OpcodeId::push_n(1), Ok(OpcodeId::PUSH1)));
         assert!(matches!(OpcodeId::push_n(10), Ok(OpcodeId::PUSH10)));
         assert!(matches!(
@@ -1294,10 +1347,7 @@ mod opcode_ids_tests {
 
     #[test]
     fn postfix() {
-        #[cfg(feature = "shanghai")]
         assert_eq!(OpcodeId::PUSH0.postfix(), Some(0));
-        #[cfg(not(feature = "shanghai"))]
-        assert_eq!(OpcodeId::PUSH0.postfix(), None);
         assert_eq!(OpcodeId::PUSH1.postfix(), Some(1));
         assert_eq!(OpcodeId::PUSH10.postfix(), Some(10));
         assert_eq!(OpcodeId::LOG2.postfix(), Some(2));
@@ -1312,4 +1362,51 @@ mod opcode_ids_tests {
         assert_eq!(OpcodeId::LOG2.data_len(), 0);
         assert_eq!(OpcodeId::CALLCODE.data_len(), 0);
     }
+
+    #[test]
+    fn transient_storage() {
+        assert_eq!(OpcodeId::from(0x5c), OpcodeId::TLOAD);
+        assert_eq!(OpcodeId::from(0x5d), OpcodeId::TSTORE);
+        assert_eq!(OpcodeId::from_str("TLOAD").unwrap(), OpcodeId::TLOAD);
+        assert_eq!(OpcodeId::TLOAD.as_u8(), 0x5c);
+        assert_eq!(OpcodeId::TSTORE.as_u8(), 0x5d);
+    }
+
+    #[test]
+    fn mcopy() {
+        assert_eq!(OpcodeId::from(0x5e), OpcodeId::MCOPY);
+        assert_eq!(OpcodeId::from_str("MCOPY").unwrap(), OpcodeId::MCOPY);
+        assert_eq!(OpcodeId::MCOPY.as_u8(), 0x5e);
+        assert!(OpcodeId::MCOPY.has_memory_access());
+    }
+
+    #[test]
+    fn blob_opcodes() {
+        assert_eq!(OpcodeId::from(0x49), OpcodeId::BLOBHASH);
+        assert_eq!(OpcodeId::from(0x4a), OpcodeId::BLOBBASEFEE);
+        assert_eq!(OpcodeId::from_str("BLOBHASH").unwrap(), OpcodeId::BLOBHASH);
+        assert_eq!(OpcodeId::BLOBHASH.as_u8(), 0x49);
+        assert_eq!(OpcodeId::BLOBBASEFEE.as_u8(), 0x4a);
+    }
+
+    #[test]
+    fn activation_fork() {
+        assert!(OpcodeId::PUSH0.is_enabled(Hardfork::Shanghai));
+        assert!(!OpcodeId::PUSH0.is_enabled(Hardfork::London));
+        assert!(OpcodeId::TLOAD.is_enabled(Hardfork::Cancun));
+        assert!(!OpcodeId::BLOBBASEFEE.is_enabled(Hardfork::Shanghai));
+        assert!(OpcodeId::SELFBALANCE.is_enabled(Hardfork::London));
+        assert!(!OpcodeId::INVALID(0xfe).is_enabled(Hardfork::Cancun));
+        assert_eq!(
+            OpcodeId::hardfork_opcodes(),
+            vec![
+                OpcodeId::BLOBHASH,
+                OpcodeId::BLOBBASEFEE,
+                OpcodeId::TLOAD,
+                OpcodeId::TSTORE,
+                OpcodeId::MCOPY,
+                OpcodeId::PUSH0,
+            ]
+        );
+    }
 }
@@ -5,21 +5,36 @@ use crate::{
     AccessList, Address, Block, Bytes, Error, GethExecTrace, Hash, ToBigEndian, ToLittleEndian,
     Word, U64,
 };
-use ethers_core::types::{
-    Eip1559TransactionRequest, Eip2930TransactionRequest, NameOrAddress, TransactionRequest, H256,
+use ethers_core::{
+    types::{
+        Eip1559TransactionRequest, Eip2930TransactionRequest, NameOrAddress, TransactionRequest,
+        H256,
+    },
+    utils::rlp::{Encodable, RlpStream},
 };
 use ethers_signers::{LocalWallet, Signer};
 use halo2_proofs::halo2curves::{group::ff::PrimeField, secp256k1};
 use num::Integer;
 use num_bigint::BigUint;
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Serialize, Serializer};
 use serde_with::serde_as;
 use sha3::{Digest, Keccak256};
 use std::collections::HashMap;
 use strum_macros::EnumIter;
 
+/// EIP-2718 type byte of the EIP-2930 tx
+pub const EIP2930_TX_TYPE: u8 = 0x01;
+/// EIP-2718 type byte of the EIP-1559 tx
+pub const EIP1559_TX_TYPE: u8 = 0x02;
+/// EIP-2718 type byte of the EIP-4844 tx
+pub const EIP4844_TX_TYPE: u8 = 0x03;
+/// EIP-2718 type byte of the EIP-7702 tx
+pub const EIP7702_TX_TYPE: u8 = 0x04;
+/// Magic byte prefixed to the RLP of an EIP-7702 authorization before hashing
+pub const EIP7702_AUTH_MAGIC: u8 = 0x05;
+
 /// Tx type
-#[derive(Default, Debug, Copy, Clone, EnumIter, Serialize, PartialEq, Eq)]
+#[derive(Default, Debug, Copy, Clone, EnumIter, Serialize, Deserialize, PartialEq, Eq)]
 pub enum TxType {
     /// EIP 155 tx
     #[default]
@@ -32,6 +47,10 @@ pub enum TxType {
     Eip2930,
     /// L1 Message tx
     L1Msg,
+    /// EIP 4844 tx
+    Eip4844,
+    /// EIP 7702 tx
+    Eip7702,
 }
 
 impl From<TxType> for usize {
@@ -57,11 +76,33 @@ impl TxType {
         matches!(*self, TxType::Eip155)
     }
 
+    /// If this type is Eip1559 or not
+    pub fn is_eip1559_tx(&self) -> bool {
+        matches!(*self, TxType::Eip1559)
+    }
+
+    /// If this type is Eip2930 or not
+    pub fn is_eip2930_tx(&self) -> bool {
+        matches!(*self, TxType::Eip2930)
+    }
+
+    /// If this type is Eip4844 or not
+    pub fn is_eip4844_tx(&self) -> bool {
+        matches!(*self, TxType::Eip4844)
+    }
+
+    /// If this type is Eip7702 or not
+    pub fn is_eip7702_tx(&self) -> bool {
+        matches!(*self, TxType::Eip7702)
+    }
+
     /// Get the type of transaction
     pub fn get_tx_type(tx: &crate::Transaction) -> Self {
         match tx.transaction_type {
             Some(x) if x == U64::from(1) => Self::Eip2930,
-            Some(x) if x == U64::from(2) => Self::Eip2930,
+            Some(x) if x == U64::from(2) => Self::Eip1559,
+            Some(x) if x == U64::from(3) => Self::Eip4844,
+            Some(x) if x == U64::from(4) => Self::Eip7702,
             Some(x) if x == U64::from(0x7e) => Self::L1Msg,
             _ => {
                 if cfg!(feature = "scroll") {
@@ -99,6 +140,14 @@ impl TxType {
                 assert!(v <= 1);
                 v
             }
+            TxType::Eip4844 => {
+                assert!(v <= 1);
+                v
+            }
+            TxType::Eip7702 => {
+                assert!(v <= 1);
+                v
+            }
             TxType::L1Msg => {
                 unreachable!("L1 msg does not have signature")
             }
@@ -119,19 +168,217 @@ pub fn get_rlp_unsigned(tx: &crate::Transaction) -> Vec<u8> {
             let tx: TransactionRequest = tx.into();
             tx.rlp_unsigned().to_vec()
         }
+        // the typed txs are signed with their type byte
         TxType::Eip1559 => {
             let tx: Eip1559TransactionRequest = tx.into();
-            tx.rlp().to_vec()
+            [vec![EIP1559_TX_TYPE], tx.rlp().to_vec()].concat()
         }
         TxType::Eip2930 => {
             let tx: Eip2930TransactionRequest = tx.into();
-            tx.rlp().to_vec()
+            [vec![EIP2930_TX_TYPE], tx.rlp().to_vec()].concat()
         }
         TxType::L1Msg => {
             // L1 msg does not have signature
             vec![]
         }
+        TxType::Eip4844 => get_eip4844_rlp(tx, false),
+        TxType::Eip7702 => get_eip7702_rlp(tx, false),
+    }
+}
+
+/// Get the RLP bytes of the signed tx, which is the preimage of the tx hash
+pub fn get_rlp_signed(tx: &crate::Transaction) -> Vec<u8> {
+    match TxType::get_tx_type(tx) {
+        // ethers doesn't know about blob txs
+        TxType::Eip4844 => get_eip4844_rlp(tx, true),
+        TxType::Eip7702 => get_eip7702_rlp(tx, true),
+        _ => tx.rlp().to_vec(),
+    }
+}
+
+/// EIP-4844 tx encoding: 0x03 || rlp([chain_id, nonce, max_priority_fee_per_gas,
+/// max_fee_per_gas, gas_limit, to, value, data, access_list, max_fee_per_blob_gas,
+/// blob_versioned_hashes]), followed by [y_parity, r, s] in the list if signed.
+fn get_eip4844_rlp(tx: &crate::Transaction, signed: bool) -> Vec<u8> {
+    let mut rlp = RlpStream::new();
+    rlp.begin_unbounded_list();
+    rlp.append(&tx.chain_id.unwrap_or_default());
+    rlp.append(&tx.nonce);
+    rlp.append(&tx.max_priority_fee_per_gas.unwrap_or_default());
+    rlp.append(&tx.max_fee_per_gas.unwrap_or_default());
+    rlp.append(&tx.gas);
+    // blob txs can not create contracts
+    rlp.append(&tx.to.unwrap_or_default());
+    rlp.append(&tx.value);
+    rlp.append(&tx.input.to_vec());
+    rlp.append(&tx.access_list.clone().unwrap_or_default());
+    rlp.append(&get_max_fee_per_blob_gas(tx));
+    rlp.append_list::<H256, H256>(&get_blob_versioned_hashes(tx));
+    if signed {
+        rlp.append(&tx.v);
+        rlp.append(&tx.r);
+        rlp.append(&tx.s);
     }
+    rlp.finalize_unbounded_list();
+
+    [vec![EIP4844_TX_TYPE], rlp.out().to_vec()].concat()
+}
+
+/// EIP-7702 tx encoding: 0x04 || rlp([chain_id, nonce, max_priority_fee_per_gas,
+/// max_fee_per_gas, gas_limit, to, value, data, access_list, authorization_list]),
+/// followed by [y_parity, r, s] in the list if signed.
+fn get_eip7702_rlp(tx: &crate::Transaction, signed: bool) -> Vec<u8> {
+    let mut rlp = RlpStream::new();
+    rlp.begin_unbounded_list();
+    rlp.append(&tx.chain_id.unwrap_or_default());
+    rlp.append(&tx.nonce);
+    rlp.append(&tx.max_priority_fee_per_gas.unwrap_or_default());
+    rlp.append(&tx.max_fee_per_gas.unwrap_or_default());
+    rlp.append(&tx.gas);
+    // set-code txs can not create contracts
+    rlp.append(&tx.to.unwrap_or_default());
+    rlp.append(&tx.value);
+    rlp.append(&tx.input.to_vec());
+    rlp.append(&tx.access_list.clone().unwrap_or_default());
+    rlp.append_list::<SetCodeAuthorization, SetCodeAuthorization>(&get_authorization_list(tx));
+    if signed {
+        rlp.append(&tx.v);
+        rlp.append(&tx.r);
+        rlp.append(&tx.s);
+    }
+    rlp.finalize_unbounded_list();
+
+    [vec![EIP7702_TX_TYPE], rlp.out().to_vec()].concat()
+}
+
+/// Get the max fee per blob gas of the transaction, which is zero unless it's
+/// an EIP-4844 transaction.
+pub fn get_max_fee_per_blob_gas(tx: &crate::Transaction) -> Word {
+    tx.other
+        .get_deserialized::<Word>("maxFeePerBlobGas")
+        .and_then(Result::ok)
+        .unwrap_or_default()
+}
+
+/// Get the authorization list of the transaction, which is empty unless it's
+/// an EIP-7702 transaction.
+pub fn get_authorization_list(tx: &crate::Transaction) -> Vec<SetCodeAuthorization> {
+    tx.other
+        .get_deserialized::<Vec<SetCodeAuthorization>>("authorizationList")
+        .and_then(Result::ok)
+        .unwrap_or_default()
+}
+
+/// EIP-7702 authorization tuple, which delegates the code of the signing
+/// account (the authority) to `address`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetCodeAuthorization {
+    /// Chain id the authorization is valid on, zero for any chain
+    pub chain_id: Word,
+    /// Address the authority delegates its code to
+    pub address: Address,
+    /// Nonce of the authority
+    pub nonce: U64,
+    /// Recovery id of the signature
+    pub y_parity: U64,
+    /// "r" value of the signature
+    pub r: Word,
+    /// "s" value of the signature
+    pub s: Word,
+}
+
+impl Encodable for SetCodeAuthorization {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(6);
+        s.append(&self.chain_id);
+        s.append(&self.address);
+        s.append(&self.nonce);
+        s.append(&self.y_parity);
+        s.append(&self.r);
+        s.append(&self.s);
+    }
+}
+
+impl SetCodeAuthorization {
+    /// Create an authorization signed by `wallet`.
+    pub fn new_signed(chain_id: Word, address: Address, nonce: U64, wallet: &LocalWallet) -> Self {
+        let mut auth = Self {
+            chain_id,
+            address,
+            nonce,
+            ..Default::default()
+        };
+        let sig = wallet.sign_hash(H256::from_slice(&Keccak256::digest(auth.sig_msg())));
+        auth.y_parity = U64::from(sig.v - 27);
+        auth.r = sig.r;
+        auth.s = sig.s;
+        auth
+    }
+
+    /// Message signed by the authority: 0x05 || rlp([chain_id, address, nonce]).
+    pub fn sig_msg(&self) -> Vec<u8> {
+        let mut rlp = RlpStream::new_list(3);
+        rlp.append(&self.chain_id);
+        rlp.append(&self.address);
+        rlp.append(&self.nonce);
+        [vec![EIP7702_AUTH_MAGIC], rlp.out().to_vec()].concat()
+    }
+
+    /// Return the SignData associated with this authorization.
+    pub fn sign_data(&self) -> Result<SignData, Error> {
+        if self.y_parity > U64::one() {
+            return Err(Error::Signature(libsecp256k1::Error::InvalidRecoveryId));
+        }
+        sign_data(
+            self.y_parity.as_u64() as u8,
+            &self.r,
+            &self.s,
+            self.sig_msg().into(),
+        )
+    }
+
+    /// Whether the signature is in the lower half of the curve order, as
+    /// required for authorizations.
+    pub fn has_low_s(&self) -> bool {
+        BigUint::from_bytes_be(&self.s.to_be_bytes()) * 2u32 < *SECP256K1_Q
+    }
+
+    /// Recover the authority, i.e. the account whose code is delegated.
+    pub fn authority(&self) -> Result<Address, Error> {
+        Ok(self.sign_data()?.get_addr())
+    }
+}
+
+fn sign_data(v: u8, r: &Word, s: &Word, msg: Bytes) -> Result<SignData, Error> {
+    let sig_r = ct_option_ok_or(
+        secp256k1::Fq::from_repr(r.to_le_bytes()),
+        Error::Signature(libsecp256k1::Error::InvalidSignature),
+    )?;
+    let sig_s = ct_option_ok_or(
+        secp256k1::Fq::from_repr(s.to_le_bytes()),
+        Error::Signature(libsecp256k1::Error::InvalidSignature),
+    )?;
+    let msg_hash: [u8; 32] = Keccak256::digest(&msg)
+        .as_slice()
+        .to_vec()
+        .try_into()
+        .expect("hash length isn't 32 bytes");
+    let pk = recover_pk(v, r, s, &msg_hash)?;
+    // msg_hash = msg_hash % q
+    let msg_hash = BigUint::from_bytes_be(msg_hash.as_slice());
+    let msg_hash = msg_hash.mod_floor(&*SECP256K1_Q);
+    let msg_hash_le = biguint_to_32bytes_le(msg_hash);
+    let msg_hash = ct_option_ok_or(
+        secp256k1::Fq::from_repr(msg_hash_le),
+        libsecp256k1::Error::InvalidMessage,
+    )?;
+    Ok(SignData {
+        signature: (sig_r, sig_s, v),
+        pk,
+        msg,
+        msg_hash,
+    })
 }
 
 /// Definition of all of the data related to an account.
@@ -188,6 +435,8 @@ pub struct BlockConstants {
     pub gas_limit: Word,
     /// base fee
     pub base_fee: Word,
+    /// excess blob gas
+    pub excess_blob_gas: U64,
 }
 
 impl<TX> TryFrom<&Block<TX>> for BlockConstants {
@@ -201,6 +450,7 @@ impl<TX> TryFrom<&Block<TX>> for BlockConstants {
             difficulty: block.difficulty,
             gas_limit: block.gas_limit,
             base_fee: block.base_fee_per_gas.ok_or(Error::IncompleteBlock)?,
+            excess_blob_gas: get_excess_blob_gas(block).into(),
         })
     }
 }
@@ -222,10 +472,30 @@ impl BlockConstants {
             difficulty,
             gas_limit,
             base_fee,
+            excess_blob_gas: U64::zero(),
         }
     }
 }
 
+/// Get the excess blob gas of the block header, which is zero before Cancun.
+pub fn get_excess_blob_gas<TX>(block: &Block<TX>) -> u64 {
+    block
+        .other
+        .get_deserialized::<U64>("excessBlobGas")
+        .and_then(Result::ok)
+        .unwrap_or_default()
+        .as_u64()
+}
+
+/// Get the versioned hashes of the blobs carried by the transaction, which is
+/// empty unless it's an EIP-4844 transaction.
+pub fn get_blob_versioned_hashes(tx: &crate::Transaction) -> Vec<H256> {
+    tx.other
+        .get_deserialized::<Vec<H256>>("blobVersionedHashes")
+        .and_then(Result::ok)
+        .unwrap_or_default()
+}
+
 /// Definition of all of the constants related to an Ethereum transaction.
 #[derive(Debug, Default, Clone, Serialize)]
 pub struct Transaction {
@@ -253,6 +523,12 @@ pub struct Transaction {
     pub call_data: Bytes,
     /// Access list
     pub access_list: Option<AccessList>,
+    /// Max fee per blob gas (EIP-4844)
+    pub max_fee_per_blob_gas: Word,
+    /// Versioned hashes of the blobs carried by the transaction (EIP-4844)
+    pub blob_versioned_hashes: Vec<H256>,
+    /// Authorizations of the set-code transaction (EIP-7702)
+    pub authorization_list: Vec<SetCodeAuthorization>,
 
     /// "v" value of the transaction signature
     pub v: u64,
@@ -306,10 +582,13 @@ impl From<&crate::Transaction> for Transaction {
             gas_tip_cap: tx.max_fee_per_gas.unwrap_or_default(),
             call_data: tx.input.clone(),
             access_list: tx.access_list.clone(),
+            max_fee_per_blob_gas: get_max_fee_per_blob_gas(tx),
+            blob_versioned_hashes: get_blob_versioned_hashes(tx),
+            authorization_list: get_authorization_list(tx),
             v: tx.v.as_u64(),
             r: tx.r,
             s: tx.s,
-            rlp_bytes: tx.rlp().to_vec(),
+            rlp_bytes: get_rlp_signed(tx),
             rlp_unsigned_bytes: get_rlp_unsigned(tx),
             hash: tx.hash,
         }
@@ -334,38 +613,8 @@ impl From<&Transaction> for TransactionRequest {
 impl Transaction {
     /// Return the SignData associated with this Transaction.
     pub fn sign_data(&self) -> Result<SignData, Error> {
-        let sig_r_le = self.r.to_le_bytes();
-        let sig_s_le = self.s.to_le_bytes();
-        let sig_r = ct_option_ok_or(
-            secp256k1::Fq::from_repr(sig_r_le),
-            Error::Signature(libsecp256k1::Error::InvalidSignature),
-        )?;
-        let sig_s = ct_option_ok_or(
-            secp256k1::Fq::from_repr(sig_s_le),
-            Error::Signature(libsecp256k1::Error::InvalidSignature),
-        )?;
-        let msg = self.rlp_unsigned_bytes.clone().into();
-        let msg_hash: [u8; 32] = Keccak256::digest(&msg)
-            .as_slice()
-            .to_vec()
-            .try_into()
-            .expect("hash length isn't 32 bytes");
         let v = self.tx_type.get_recovery_id(self.v);
-        let pk = recover_pk(v, &self.r, &self.s, &msg_hash)?;
-        // msg_hash = msg_hash % q
-        let msg_hash = BigUint::from_bytes_be(msg_hash.as_slice());
-        let msg_hash = msg_hash.mod_floor(&*SECP256K1_Q);
-        let msg_hash_le = biguint_to_32bytes_le(msg_hash);
-        let msg_hash = ct_option_ok_or(
-            secp256k1::Fq::from_repr(msg_hash_le),
-            libsecp256k1::Error::InvalidMessage,
-        )?;
-        Ok(SignData {
-            signature: (sig_r, sig_s, v),
-            pk,
-            msg,
-            msg_hash,
-        })
+        sign_data(v, &self.r, &self.s, self.rlp_unsigned_bytes.clone().into())
     }
 }
 
@@ -392,14 +641,23 @@ impl GethData {
             let wallet = wallets.get(&tx.from).unwrap();
             assert_eq!(wallet.chain_id(), self.chain_id);
             let geth_tx: Transaction = (&*tx).into();
-            let req: TransactionRequest = (&geth_tx).into();
-            let sig = wallet.sign_transaction_sync(&req.chain_id(self.chain_id).into());
+            let sig = if geth_tx.tx_type.is_eip4844_tx() || geth_tx.tx_type.is_eip7702_tx() {
+                // blob and set-code txs sign the hash of their unsigned encoding and carry
+                // the recovery id as v
+                let sighash = Keccak256::digest(&geth_tx.rlp_unsigned_bytes);
+                let mut sig = wallet.sign_hash(H256::from_slice(&sighash));
+                sig.v -= 27;
+                sig
+            } else {
+                let req: TransactionRequest = (&geth_tx).into();
+                wallet.sign_transaction_sync(&req.chain_id(self.chain_id).into())
+            };
             tx.v = U64::from(sig.v);
             tx.r = sig.r;
             tx.s = sig.s;
             // The previous tx.hash is calculated without signature.
             // Therefore we need to update tx.hash.
-            tx.hash = tx.hash();
+            tx.hash = H256::from_slice(&Keccak256::digest(get_rlp_signed(tx)));
         }
     }
 }
@@ -20,6 +20,7 @@ pub mod error;
 pub mod bytecode;
 pub mod evm_types;
 pub mod geth_types;
+pub mod serde_utils;
 pub mod sign_types;
 
 pub use bytecode::Bytecode;
@@ -0,0 +1,194 @@
+//! Serde helpers for the types which serde can't (de)serialize as they are, to
+//! be used with `#[serde(with = "...")]`.
+
+use serde::{de, Deserializer};
+use std::fmt;
+
+struct BytesVisitor;
+
+impl<'de> de::Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("bytes")
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+        Ok(bytes.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, bytes: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(bytes)
+    }
+
+    // Formats without bytes, like JSON, serialize them as a sequence.
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+struct ByteBuf(Vec<u8>);
+
+impl<'de> serde::Deserialize<'de> for ByteBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_bytes(BytesVisitor).map(ByteBuf)
+    }
+}
+
+fn deserialize_bytes<'de, D, R>(deserializer: D) -> Result<R, D::Error>
+where
+    D: Deserializer<'de>,
+    R: Default + AsMut<[u8]>,
+{
+    let bytes = deserializer.deserialize_bytes(BytesVisitor)?;
+    let mut repr = R::default();
+    if repr.as_mut().len() != bytes.len() {
+        return Err(de::Error::invalid_length(
+            bytes.len(),
+            &format!("{} bytes", repr.as_mut().len()).as_str(),
+        ));
+    }
+    repr.as_mut().copy_from_slice(&bytes);
+    Ok(repr)
+}
+
+/// Byte arrays of any length, as serde only supports arrays of up to 32
+/// elements.
+pub mod byte_array {
+    use serde::{de, Deserializer, Serializer};
+
+    /// Serialize the array as bytes.
+    pub fn serialize<S: Serializer, const N: usize>(
+        bytes: &[u8; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(bytes)
+    }
+
+    /// Deserialize the array from bytes of the same length.
+    pub fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<[u8; N], D::Error> {
+        let bytes = deserializer.deserialize_bytes(super::BytesVisitor)?;
+        let len = bytes.len();
+        bytes
+            .try_into()
+            .map_err(|_| de::Error::invalid_length(len, &format!("{N} bytes").as_str()))
+    }
+}
+
+/// Arrays of byte arrays of any length.
+pub mod byte_arrays {
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    struct Bytes<'a>(&'a [u8]);
+
+    impl Serialize for Bytes<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(self.0)
+        }
+    }
+
+    /// Serialize the arrays as a sequence of bytes.
+    pub fn serialize<S: Serializer, const N: usize, const M: usize>(
+        arrays: &[[u8; N]; M],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(arrays.iter().map(|bytes| Bytes(bytes)))
+    }
+
+    /// Deserialize the arrays from a sequence of bytes of the same lengths.
+    pub fn deserialize<'de, D: Deserializer<'de>, const N: usize, const M: usize>(
+        deserializer: D,
+    ) -> Result<[[u8; N]; M], D::Error> {
+        let vecs = Vec::<super::ByteBuf>::deserialize(deserializer)?;
+        if vecs.len() != M {
+            return Err(de::Error::invalid_length(
+                vecs.len(),
+                &format!("{M} arrays").as_str(),
+            ));
+        }
+        let mut arrays = [[0u8; N]; M];
+        for (array, super::ByteBuf(bytes)) in arrays.iter_mut().zip(vecs) {
+            if bytes.len() != N {
+                return Err(de::Error::invalid_length(
+                    bytes.len(),
+                    &format!("{N} bytes").as_str(),
+                ));
+            }
+            array.copy_from_slice(&bytes);
+        }
+        Ok(arrays)
+    }
+}
+
+/// Prime field elements, by their canonical representation.
+pub mod field {
+    use halo2_proofs::halo2curves::group::ff::PrimeField;
+    use serde::{de, Deserializer, Serializer};
+
+    /// Serialize the field element as the bytes of its representation.
+    pub fn serialize<S: Serializer, F: PrimeField>(
+        value: &F,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(value.to_repr().as_ref())
+    }
+
+    /// Deserialize the field element from the bytes of its representation.
+    pub fn deserialize<'de, D: Deserializer<'de>, F: PrimeField>(
+        deserializer: D,
+    ) -> Result<F, D::Error> {
+        let repr = super::deserialize_bytes::<D, F::Repr>(deserializer)?;
+        Option::from(F::from_repr(repr)).ok_or_else(|| de::Error::custom("invalid field element"))
+    }
+}
+
+/// Curve points, by their compressed encoding.
+pub mod group_encoding {
+    use halo2_proofs::halo2curves::group::GroupEncoding;
+    use serde::{de, Deserializer, Serializer};
+
+    /// Serialize the point as the bytes of its encoding.
+    pub fn serialize<S: Serializer, C: GroupEncoding>(
+        point: &C,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(point.to_bytes().as_ref())
+    }
+
+    /// Deserialize the point from the bytes of its encoding.
+    pub fn deserialize<'de, D: Deserializer<'de>, C: GroupEncoding>(
+        deserializer: D,
+    ) -> Result<C, D::Error> {
+        let repr = super::deserialize_bytes::<D, C::Repr>(deserializer)?;
+        Option::from(C::from_bytes(&repr)).ok_or_else(|| de::Error::custom("invalid curve point"))
+    }
+}
+
+/// Optional opcodes, by their byte. The serde implementation of
+/// [`OpcodeId`](crate::evm_types::OpcodeId) is meant for the geth traces in
+/// JSON, and doesn't round trip through binary formats.
+pub mod option_opcode {
+    use crate::evm_types::OpcodeId;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serialize the opcode as its byte.
+    pub fn serialize<S: Serializer>(
+        opcode: &Option<OpcodeId>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        opcode.map(|opcode| opcode.as_u8()).serialize(serializer)
+    }
+
+    /// Deserialize the opcode from its byte.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<OpcodeId>, D::Error> {
+        Ok(Option::<u8>::deserialize(deserializer)?.map(OpcodeId::from))
+    }
+}
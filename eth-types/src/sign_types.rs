@@ -18,6 +18,7 @@ use halo2_proofs::{
 };
 use lazy_static::lazy_static;
 use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
 use sha3::{Digest, Keccak256};
 use subtle::CtOption;
 
@@ -48,19 +49,45 @@ pub fn sign(
 
 /// Signature data required by the SignVerify Chip as input to verify a
 /// signature.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SignData {
     /// Secp256k1 signature point (r, s, v)
     /// v must be 0 or 1
+    #[serde(with = "serde_signature")]
     pub signature: (secp256k1::Fq, secp256k1::Fq, u8),
     /// Secp256k1 public key
+    #[serde(with = "crate::serde_utils::group_encoding")]
     pub pk: Secp256k1Affine,
     /// Message being hashed before signing.
     pub msg: Bytes,
     /// Hash of the message that is being signed
+    #[serde(with = "crate::serde_utils::field")]
     pub msg_hash: secp256k1::Fq,
 }
 
+mod serde_signature {
+    use super::secp256k1::Fq;
+    use halo2_proofs::halo2curves::group::ff::PrimeField;
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        signature: &(Fq, Fq, u8),
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        (signature.0.to_repr(), signature.1.to_repr(), signature.2).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<(Fq, Fq, u8), D::Error> {
+        let (r, s, v) = <([u8; 32], [u8; 32], u8)>::deserialize(deserializer)?;
+        let from_repr = |repr| {
+            Option::from(Fq::from_repr(repr)).ok_or_else(|| de::Error::custom("invalid signature"))
+        };
+        Ok((from_repr(r)?, from_repr(s)?, v))
+    }
+}
+
 impl SignData {
     /// Recover address of the signature
     pub fn get_addr(&self) -> Address {
@@ -0,0 +1,319 @@
+//! Differential tracing, which traces the config with both the geth tracer
+//! and the Rust tracer and fails if their results differ.
+
+use crate::{geth_trace, rust_tracer, TraceConfig};
+use eth_types::{evm_types::parse_delegation, Error, GethExecTrace};
+
+/// Returns if the config uses EIP-7702, with authorizations or delegated
+/// accounts, which the geth tracer doesn't support.
+fn uses_set_code(config: &TraceConfig) -> bool {
+    config
+        .transactions
+        .iter()
+        .any(|tx| !tx.authorization_list.is_empty())
+        || config
+            .accounts
+            .values()
+            .any(|account| parse_delegation(&account.code).is_some())
+}
+
+/// Returns a description of the first difference between the traces of the
+/// geth tracer and the Rust tracer, if any.
+fn first_mismatch(geth_traces: &[GethExecTrace], rust_traces: &[GethExecTrace]) -> Option<String> {
+    if geth_traces.len() != rust_traces.len() {
+        return Some(format!(
+            "number of traces: geth {} rust {}",
+            geth_traces.len(),
+            rust_traces.len()
+        ));
+    }
+
+    for (index, (geth, rust)) in geth_traces.iter().zip(rust_traces).enumerate() {
+        if geth.struct_logs.len() != rust.struct_logs.len() {
+            return Some(format!(
+                "config.Transactions[{index}]: number of steps: geth {} rust {}",
+                geth.struct_logs.len(),
+                rust.struct_logs.len()
+            ));
+        }
+        for (step_index, (geth_step, rust_step)) in
+            geth.struct_logs.iter().zip(&rust.struct_logs).enumerate()
+        {
+            if geth_step != rust_step {
+                return Some(format!(
+                    "config.Transactions[{index}]: step {step_index}: geth {geth_step:?} rust \
+                     {rust_step:?}"
+                ));
+            }
+        }
+        if geth != rust {
+            return Some(format!(
+                "config.Transactions[{index}]: result: geth (gas: {}, failed: {}, return value: \
+                 {}) rust (gas: {}, failed: {}, return value: {})",
+                geth.gas.0,
+                geth.failed,
+                geth.return_value,
+                rust.gas.0,
+                rust.failed,
+                rust.return_value
+            ));
+        }
+    }
+
+    None
+}
+
+/// Creates a trace for the specified config with both tracers, returning an
+/// error describing the first difference between them. A config using EIP-7702
+/// is only traced with the Rust tracer.
+pub fn trace(config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error> {
+    if uses_set_code(config) {
+        log::debug!("trace the EIP-7702 config with the rust tracer only");
+        return rust_tracer::trace(config);
+    }
+
+    let mismatch = match (geth_trace(config), rust_tracer::trace(config)) {
+        (Ok(geth_traces), Ok(rust_traces)) => match first_mismatch(&geth_traces, &rust_traces) {
+            None => return Ok(geth_traces),
+            Some(mismatch) => mismatch,
+        },
+        (Err(Error::TracingError(geth_err)), Err(Error::TracingError(rust_err)))
+            if geth_err == rust_err =>
+        {
+            return Err(Error::TracingError(geth_err))
+        }
+        (geth_result, rust_result) => format!(
+            "result: geth {:?} rust {:?}",
+            geth_result.map(|_| ()),
+            rust_result.map(|_| ())
+        ),
+    };
+
+    log::error!("tracers mismatch: {}", mismatch);
+    Err(Error::TracingError(format!("tracers mismatch: {mismatch}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ChainConfig, LoggerConfig};
+    use eth_types::{
+        address, bytecode,
+        geth_types::{Account, BlockConstants, Transaction},
+        Bytecode, Word,
+    };
+    use std::collections::HashMap;
+
+    fn check_bytecode(code: Bytecode, chain_config: Option<ChainConfig>) {
+        let sender = address!("0x00000000000000000000000000000000000000fe");
+        let contract = address!("0x00000000000000000000000000000000000000ff");
+        let config = TraceConfig {
+            chain_id: 1,
+            history_hashes: (0..3).map(Word::from).collect(),
+            block_constants: BlockConstants {
+                coinbase: address!("0x00000000000000000000000000000000000000cb"),
+                timestamp: Word::from(0x10),
+                number: 3.into(),
+                difficulty: Word::from(0x20000),
+                gas_limit: Word::from(10_000_000),
+                base_fee: Word::from(7),
+                excess_blob_gas: 0.into(),
+            },
+            accounts: HashMap::from([
+                (
+                    sender,
+                    Account {
+                        address: sender,
+                        balance: Word::from(10).pow(18.into()),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    contract,
+                    Account {
+                        address: contract,
+                        balance: Word::from(100),
+                        code: code.code().into(),
+                        storage: HashMap::from([(Word::one(), Word::from(3))]),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+            transactions: vec![
+                Transaction {
+                    from: sender,
+                    to: Some(contract),
+                    gas_limit: Word::from(1_000_000),
+                    gas_price: Word::from(10),
+                    value: Word::from(5),
+                    ..Default::default()
+                },
+                Transaction {
+                    from: sender,
+                    to: None,
+                    nonce: Word::one(),
+                    gas_limit: Word::from(1_000_000),
+                    gas_price: Word::from(10),
+                    call_data: code.code().into(),
+                    ..Default::default()
+                },
+            ],
+            logger_config: LoggerConfig::enable_memory(),
+            chain_config,
+        };
+
+        if let Err(err) = trace(&config) {
+            panic!("{err:?}");
+        }
+    }
+
+    #[test]
+    fn differential_arithmetic_and_memory() {
+        check_bytecode(
+            bytecode! {
+                PUSH32(Word::MAX)
+                PUSH1(0x07)
+                SDIV
+                PUSH1(0x03)
+                PUSH32(Word::MAX)
+                SMOD
+                PUSH1(0x05)
+                PUSH1(0x01)
+                SIGNEXTEND
+                PUSH1(0x04)
+                PUSH32(Word::MAX)
+                SAR
+                PUSH1(0x40)
+                MSTORE
+                PUSH2(0x0100)
+                PUSH1(0x20)
+                SHA3
+                CALLDATASIZE
+                PUSH1(0x00)
+                PUSH1(0x80)
+                CALLDATACOPY
+                PUSH1(0x01)
+                BLOCKHASH
+                PUSH1(0x03)
+                BLOCKHASH
+                MSIZE
+                GAS
+                STOP
+            },
+            None,
+        );
+    }
+
+    #[test]
+    fn differential_storage_and_logs() {
+        check_bytecode(
+            bytecode! {
+                PUSH1(0x01)
+                SLOAD
+                PUSH1(0x00)
+                SSTORE
+                PUSH1(0x00)
+                PUSH1(0x01)
+                SSTORE
+                PUSH1(0x03)
+                PUSH1(0x01)
+                SSTORE
+                PUSH1(0x20)
+                PUSH1(0x00)
+                LOG0
+                PUSH1(0x00)
+                SLOAD
+                STOP
+            },
+            None,
+        );
+    }
+
+    #[test]
+    fn differential_calls_and_creates() {
+        check_bytecode(
+            bytecode! {
+                // Call the identity precompile.
+                PUSH1(0x20)
+                PUSH1(0x00)
+                PUSH1(0x20)
+                PUSH1(0x00)
+                PUSH1(0x00)
+                PUSH1(0x04)
+                PUSH2(0xffff)
+                CALL
+                // Call a non-existing account with value.
+                PUSH1(0x00)
+                PUSH1(0x00)
+                PUSH1(0x00)
+                PUSH1(0x00)
+                PUSH1(0x01)
+                PUSH1(0xaa)
+                GAS
+                CALL
+                // Create a contract reverting with its return data.
+                PUSH32(Word::from_big_endian(&[0x60, 0x01, 0x60, 0x00, 0xfd]))
+                PUSH1(0x00)
+                MSTORE
+                PUSH1(0x05)
+                PUSH1(0x1b)
+                PUSH1(0x00)
+                CREATE
+                RETURNDATASIZE
+                PUSH1(0x00)
+                PUSH1(0x05)
+                PUSH1(0x1b)
+                PUSH1(0x00)
+                CREATE2
+                STOP
+            },
+            None,
+        );
+    }
+
+    #[test]
+    fn differential_errors() {
+        check_bytecode(
+            bytecode! {
+                PUSH1(0x01)
+                PUSH1(0x00)
+                RETURNDATACOPY
+            },
+            None,
+        );
+        check_bytecode(
+            bytecode! {
+                PUSH32(Word::MAX)
+                MLOAD
+            },
+            None,
+        );
+        check_bytecode(
+            bytecode! {
+                PUSH1(0x03)
+                JUMP
+            },
+            None,
+        );
+    }
+
+    #[test]
+    fn differential_forks() {
+        let code = bytecode! {
+            PUSH0
+            PUSH1(0x01)
+            PUSH1(0x02)
+            TSTORE
+            PUSH1(0x02)
+            TLOAD
+            PUSH1(0x20)
+            PUSH1(0x00)
+            PUSH1(0x10)
+            MCOPY
+            STOP
+        };
+        check_bytecode(code.clone(), None);
+        check_bytecode(code.clone(), Some(ChainConfig::shanghai()));
+        check_bytecode(code, Some(ChainConfig::cancun()));
+    }
+}
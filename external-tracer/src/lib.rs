@@ -1,4 +1,27 @@
-//! This module generates traces by connecting to an external tracer
+//! This module generates traces by connecting to an external tracer.
+//!
+//! The tracer backend is selected by cargo features:
+//! - `geth-tracer` (default): the geth tracer of `geth-utils`, called via CGO.
+//! - `rust-tracer`: the tracer implemented in Rust, which doesn't need Go.
+//! - `differential-tracer`: both tracers, failing if their traces differ.
+
+#[cfg(not(any(feature = "geth-tracer", feature = "rust-tracer")))]
+compile_error!("either the `geth-tracer` or the `rust-tracer` feature must be enabled");
+
+#[cfg(feature = "differential-tracer")]
+mod differential;
+#[cfg(feature = "rust-tracer")]
+mod rust_tracer;
+
+#[cfg(feature = "rust-tracer")]
+pub use rust_tracer::trace as rust_trace;
+
+#[cfg(feature = "differential-tracer")]
+use differential::trace as backend_trace;
+#[cfg(all(feature = "geth-tracer", not(feature = "rust-tracer")))]
+use geth_trace as backend_trace;
+#[cfg(all(feature = "rust-tracer", not(feature = "differential-tracer")))]
+use rust_tracer::trace as backend_trace;
 
 use eth_types::{
     geth_types::{Account, BlockConstants, Transaction},
@@ -67,6 +90,8 @@ impl LoggerConfig {
 pub struct ChainConfig {
     /// Shanghai switch time (nil = no fork, 0 = already on shanghai)
     pub shanghai_time: Option<u64>,
+    /// Cancun switch time (nil = no fork, 0 = already on cancun)
+    pub cancun_time: Option<u64>,
     /// TerminalTotalDifficulty is the amount of total difficulty reached by
     /// the network that triggers the consensus upgrade.
     pub terminal_total_difficulty: Option<u64>,
@@ -81,14 +106,29 @@ impl ChainConfig {
     pub fn shanghai() -> Self {
         Self {
             shanghai_time: Some(0),
+            cancun_time: None,
             terminal_total_difficulty: Some(0),
             terminal_total_difficulty_passed: true,
         }
     }
+
+    /// Create a chain config for Cancun fork.
+    pub fn cancun() -> Self {
+        Self {
+            cancun_time: Some(0),
+            ..Self::shanghai()
+        }
+    }
 }
 
-/// Creates a trace for the specified config
+/// Creates a trace for the specified config with the enabled tracer backend.
 pub fn trace(config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error> {
+    backend_trace(config)
+}
+
+/// Creates a trace for the specified config with the geth tracer
+#[cfg(feature = "geth-tracer")]
+pub fn geth_trace(config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error> {
     // Get the trace
     let trace_string = geth_utils::trace(&serde_json::to_string(&config).unwrap()).map_err(
         |error| match error {
@@ -0,0 +1,566 @@
+//! Tracer implemented in Rust, which executes the transactions of a
+//! [`TraceConfig`] and produces the same [`GethExecTrace`]s as the geth tracer
+//! of `geth-utils`, without going through the Go library. Unlike the geth
+//! tracer, it also supports the authorizations and delegations of EIP-7702.
+
+mod gas;
+mod interpreter;
+mod logger;
+mod state;
+
+use crate::{ChainConfig, TraceConfig};
+use eth_types::{
+    evm_types::{
+        block_utils::calculate_blob_base_fee, delegation_code, gas_utils::tx_data_gas_cost,
+        parse_delegation, Gas, GasCost,
+    },
+    geth_types::{SetCodeAuthorization, Transaction},
+    Address, Error, GethExecTrace, Word,
+};
+use ethers_core::utils::to_checksum;
+use gas::{
+    to_word_size, INIT_CODE_WORD_GAS, MAX_INIT_CODE_SIZE, TX_ACCESS_LIST_ADDRESS_GAS,
+    TX_ACCESS_LIST_STORAGE_KEY_GAS,
+};
+use interpreter::Evm;
+use logger::StructLogger;
+use state::StateDb;
+
+/// Number of precompiled contracts active since Berlin.
+const NUM_PRECOMPILED_CONTRACTS: u64 = 9;
+
+/// Forks enabled on top of London, which is always active.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Rules {
+    pub(crate) is_shanghai: bool,
+    pub(crate) is_cancun: bool,
+}
+
+impl Rules {
+    fn new(chain_config: Option<&ChainConfig>, timestamp: u64) -> Self {
+        let is_active = |fork_time: Option<u64>| fork_time.map_or(false, |time| time <= timestamp);
+        Self {
+            is_shanghai: chain_config.map_or(false, |config| is_active(config.shanghai_time)),
+            is_cancun: chain_config.map_or(false, |config| is_active(config.cancun_time)),
+        }
+    }
+}
+
+/// Block context of the executed transactions.
+#[derive(Debug)]
+pub(crate) struct BlockContext {
+    pub(crate) coinbase: Address,
+    pub(crate) number: u64,
+    pub(crate) timestamp: u64,
+    pub(crate) difficulty: Word,
+    pub(crate) gas_limit: u64,
+    pub(crate) base_fee: Word,
+    pub(crate) blob_base_fee: Word,
+    pub(crate) chain_id: u64,
+    history_hashes: Vec<Word>,
+}
+
+impl BlockContext {
+    fn new(config: &TraceConfig) -> Self {
+        let block = &config.block_constants;
+        Self {
+            coinbase: block.coinbase,
+            number: block.number.low_u64(),
+            timestamp: block.timestamp.low_u64(),
+            difficulty: block.difficulty,
+            gas_limit: block.gas_limit.low_u64(),
+            base_fee: block.base_fee,
+            blob_base_fee: calculate_blob_base_fee(block.excess_blob_gas.as_u64()),
+            chain_id: config.chain_id,
+            history_hashes: config.history_hashes.clone(),
+        }
+    }
+
+    /// Returns the hash of one of the 256 most recent blocks.
+    pub(crate) fn block_hash(&self, number: u64) -> Word {
+        (self.history_hashes.len() as u64)
+            .checked_sub(self.number - number)
+            .map_or(Word::zero(), |index| self.history_hashes[index as usize])
+    }
+}
+
+/// Transaction context of the executed transaction.
+#[derive(Clone, Debug)]
+pub(crate) struct TxContext {
+    pub(crate) origin: Address,
+    pub(crate) gas_price: Word,
+    pub(crate) blob_hashes: Vec<Word>,
+}
+
+/// Returns the intrinsic gas of the transaction.
+fn intrinsic_gas(tx: &Transaction, rules: &Rules) -> u64 {
+    let mut gas = if tx.to.is_none() {
+        GasCost::CREATION_TX.0
+    } else {
+        GasCost::TX.0
+    };
+    gas += tx_data_gas_cost(&tx.call_data);
+    if tx.to.is_none() && rules.is_shanghai {
+        gas += to_word_size(tx.call_data.len() as u64) * INIT_CODE_WORD_GAS;
+    }
+    if let Some(access_list) = &tx.access_list {
+        gas += access_list.0.len() as u64 * TX_ACCESS_LIST_ADDRESS_GAS;
+        gas += access_list
+            .0
+            .iter()
+            .map(|item| item.storage_keys.len() as u64 * TX_ACCESS_LIST_STORAGE_KEY_GAS)
+            .sum::<u64>();
+    }
+    gas += tx.authorization_list.len() as u64 * GasCost::PER_EMPTY_ACCOUNT.0;
+    gas
+}
+
+/// Apply an authorization of an EIP-7702 transaction, following
+/// `stateTransition.applyAuthorization` in geth. Invalid authorizations are
+/// skipped.
+fn apply_authorization(state: &mut StateDb, chain_id: u64, auth: &SetCodeAuthorization) {
+    if !auth.chain_id.is_zero() && auth.chain_id != chain_id.into() {
+        return;
+    }
+    if auth.nonce.as_u64() == u64::MAX || !auth.has_low_s() {
+        return;
+    }
+    let Ok(authority) = auth.authority() else {
+        return;
+    };
+    state.add_address_to_access_list(&authority);
+    let code = state.code(&authority);
+    if !code.is_empty() && parse_delegation(code).is_none() {
+        return;
+    }
+    if state.nonce(&authority) != auth.nonce.as_u64() {
+        return;
+    }
+
+    if state.exist(&authority) {
+        state.add_refund(GasCost::PER_EMPTY_ACCOUNT.0 - GasCost::PER_AUTH_BASE.0);
+    }
+    state.set_nonce(&authority, auth.nonce.as_u64() + 1);
+    let code = if auth.address.is_zero() {
+        vec![]
+    } else {
+        delegation_code(auth.address)
+    };
+    state.set_code(&authority, code);
+}
+
+/// Validate and apply the transaction to the state, following
+/// `core.ApplyMessage` in geth.
+fn apply_transaction(
+    state: &mut StateDb,
+    block: &BlockContext,
+    rules: Rules,
+    config: &TraceConfig,
+    tx: &Transaction,
+) -> Result<GethExecTrace, String> {
+    let from = tx.from;
+    let nonce = tx.nonce.low_u64();
+    let gas_limit = tx.gas_limit.low_u64();
+    let gas_price = tx.gas_price;
+
+    let state_nonce = state.nonce(&from);
+    if nonce < state_nonce {
+        return Err(format!(
+            "nonce too low: address {}, tx: {nonce} state: {state_nonce}",
+            to_checksum(&from, None)
+        ));
+    }
+    if nonce > state_nonce {
+        return Err(format!(
+            "nonce too high: address {}, tx: {nonce} state: {state_nonce}",
+            to_checksum(&from, None)
+        ));
+    }
+    if state_nonce == u64::MAX {
+        return Err(format!(
+            "nonce has max value: address {}, nonce: {state_nonce}",
+            to_checksum(&from, None)
+        ));
+    }
+    // A delegated sender is still an EOA (EIP-7702).
+    let code = state.code(&from);
+    if !code.is_empty() && parse_delegation(code).is_none() {
+        return Err(format!(
+            "sender not an eoa: address {}, codehash: {:?}",
+            to_checksum(&from, None),
+            state.code_hash(&from)
+        ));
+    }
+    // The fee cap and the tip cap are both set to the gas price.
+    if !gas_price.is_zero() && gas_price < block.base_fee {
+        return Err(format!(
+            "max fee per gas less than block base fee: address {}, maxFeePerGas: {gas_price} \
+             baseFee: {}",
+            to_checksum(&from, None),
+            block.base_fee
+        ));
+    }
+    let gas_cost = Word::from(gas_limit) * gas_price;
+    let balance = state.balance(&from);
+    if balance < gas_cost + tx.value {
+        return Err(format!(
+            "insufficient funds for gas * price + value: address {} have {balance} want {}",
+            to_checksum(&from, None),
+            gas_cost + tx.value
+        ));
+    }
+    state.sub_balance(&from, gas_cost);
+
+    let intrinsic_gas = intrinsic_gas(tx, &rules);
+    if gas_limit < intrinsic_gas {
+        return Err(format!(
+            "intrinsic gas too low: have {gas_limit}, want {intrinsic_gas}"
+        ));
+    }
+    let gas = gas_limit - intrinsic_gas;
+    if !tx.value.is_zero() && state.balance(&from) < tx.value {
+        return Err(format!(
+            "insufficient funds for transfer: address {}",
+            to_checksum(&from, None)
+        ));
+    }
+    if tx.to.is_none() && rules.is_shanghai && tx.call_data.len() as u64 > MAX_INIT_CODE_SIZE {
+        return Err(format!(
+            "max initcode size exceeded: code size {} limit {MAX_INIT_CODE_SIZE}",
+            tx.call_data.len()
+        ));
+    }
+
+    let mut warm_accounts: Vec<_> = (1..=NUM_PRECOMPILED_CONTRACTS)
+        .map(Address::from_low_u64_be)
+        .chain([from])
+        .chain(tx.to)
+        .collect();
+    if rules.is_shanghai {
+        warm_accounts.push(block.coinbase);
+    }
+    let access_list = tx.access_list.iter().flat_map(|access_list| &access_list.0);
+    warm_accounts.extend(access_list.clone().map(|item| item.address));
+    state.prepare(
+        warm_accounts,
+        access_list.flat_map(|item| {
+            item.storage_keys
+                .iter()
+                .map(|key| (item.address, Word::from_big_endian(key.as_bytes())))
+        }),
+    );
+
+    let tx_context = TxContext {
+        origin: from,
+        gas_price,
+        blob_hashes: tx
+            .blob_versioned_hashes
+            .iter()
+            .map(|hash| Word::from_big_endian(hash.as_bytes()))
+            .collect(),
+    };
+    let logger = StructLogger::new(config.logger_config.clone());
+    let mut evm = Evm::new(state, block, tx_context, rules, logger);
+    let (ret, gas_left, err) = match tx.to {
+        None => {
+            let (ret, _, gas_left, err) = evm.create(from, tx.call_data.to_vec(), gas, tx.value);
+            (ret, gas_left, err)
+        }
+        Some(to) => {
+            evm.state.set_nonce(&from, nonce + 1);
+            for auth in &tx.authorization_list {
+                apply_authorization(evm.state, block.chain_id, auth);
+            }
+            // Warm up the delegate of the callee once all the authorizations
+            // are applied.
+            if let Some(delegate) = parse_delegation(evm.state.code(&to)) {
+                evm.state.add_address_to_access_list(&delegate);
+            }
+            evm.call(from, to, tx.call_data.to_vec(), gas, tx.value)
+        }
+    };
+    let struct_logs = evm.into_logger().into_logs();
+
+    // Refund at most a fifth of the used gas (EIP-3529).
+    let gas_used = gas_limit - gas_left;
+    let refund = (gas_used / 5).min(state.refund());
+    let gas_left = gas_left + refund;
+    state.add_balance(&from, Word::from(gas_left) * gas_price);
+    let gas_used = gas_limit - gas_left;
+    if !gas_price.is_zero() {
+        state.add_balance(
+            &block.coinbase,
+            Word::from(gas_used) * (gas_price - block.base_fee),
+        );
+    }
+
+    Ok(GethExecTrace {
+        l1_fee: 0,
+        gas: Gas(gas_used),
+        failed: err.is_some(),
+        return_value: hex::encode(ret),
+        struct_logs,
+    })
+}
+
+/// Creates a trace for the specified config with the Rust tracer.
+pub fn trace(config: &TraceConfig) -> Result<Vec<GethExecTrace>, Error> {
+    if cfg!(feature = "scroll") {
+        return Err(Error::TracingError(
+            "rust tracer doesn't support the scroll feature".to_string(),
+        ));
+    }
+
+    let block = BlockContext::new(config);
+    let txs_gas_limit = config
+        .transactions
+        .iter()
+        .fold(0u64, |acc, tx| acc.wrapping_add(tx.gas_limit.low_u64()));
+    if txs_gas_limit > block.gas_limit {
+        return Err(Error::TracingError(format!(
+            "txs total gas: {txs_gas_limit} Exceeds block gas limit: {}",
+            block.gas_limit
+        )));
+    }
+
+    let rules = Rules::new(config.chain_config.as_ref(), block.timestamp);
+    let mut state = StateDb::new(&config.accounts);
+    config
+        .transactions
+        .iter()
+        .enumerate()
+        .map(|(index, tx)| {
+            let trace =
+                apply_transaction(&mut state, &block, rules, config, tx).map_err(|err| {
+                    Error::TracingError(format!(
+                        "Failed to apply config.Transactions[{index}]: {err}"
+                    ))
+                })?;
+            state.finalise();
+            Ok(trace)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LoggerConfig;
+    use eth_types::{
+        address, bytecode,
+        evm_types::{OpcodeId, Stack},
+        geth_types::{Account, BlockConstants},
+        Bytecode, ToWord,
+    };
+    use std::collections::HashMap;
+
+    fn config_with_code(code: Bytecode, gas_price: Word) -> TraceConfig {
+        let sender = address!("0x00000000000000000000000000000000000000fe");
+        let contract = address!("0x00000000000000000000000000000000000000ff");
+        TraceConfig {
+            chain_id: 1,
+            history_hashes: vec![Word::from(0xbeef)],
+            block_constants: BlockConstants {
+                coinbase: address!("0x00000000000000000000000000000000000000cb"),
+                timestamp: Word::from(1),
+                number: 1.into(),
+                difficulty: Word::from(0x20000),
+                gas_limit: Word::from(10_000_000),
+                base_fee: Word::from(1),
+                excess_blob_gas: 0.into(),
+            },
+            accounts: HashMap::from([
+                (
+                    sender,
+                    Account {
+                        address: sender,
+                        balance: Word::from(10).pow(18.into()),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    contract,
+                    Account {
+                        address: contract,
+                        code: code.code().into(),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+            transactions: vec![Transaction {
+                from: sender,
+                to: Some(contract),
+                gas_limit: Word::from(1_000_000),
+                gas_price,
+                ..Default::default()
+            }],
+            logger_config: LoggerConfig::enable_memory(),
+            chain_config: None,
+        }
+    }
+
+    #[test]
+    fn rust_tracer_simple_steps() {
+        let code = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            STOP
+        };
+        let traces = trace(&config_with_code(code, Word::from(2))).unwrap();
+        let trace = &traces[0];
+
+        assert!(!trace.failed);
+        assert_eq!(trace.gas.0, 21000 + 3 + 3 + 3);
+        let ops: Vec<_> = trace.struct_logs.iter().map(|step| step.op).collect();
+        assert_eq!(
+            ops,
+            vec![
+                OpcodeId::PUSH1,
+                OpcodeId::PUSH1,
+                OpcodeId::ADD,
+                OpcodeId::STOP
+            ]
+        );
+        assert_eq!(trace.struct_logs[0].gas.0, 1_000_000 - 21000);
+        assert_eq!(
+            trace.struct_logs[2].stack,
+            Stack::from_slice(&[1.into(), 2.into()])
+        );
+        assert_eq!(trace.struct_logs[3].stack, Stack::from_slice(&[3.into()]));
+    }
+
+    #[test]
+    fn rust_tracer_storage_and_refund() {
+        let code = bytecode! {
+            PUSH1(0x2a)
+            PUSH1(0x00)
+            SSTORE
+            PUSH1(0x00)
+            PUSH1(0x00)
+            SSTORE
+            PUSH1(0x00)
+            SLOAD
+            STOP
+        };
+        let traces = trace(&config_with_code(code, Word::from(2))).unwrap();
+        let steps = &traces[0].struct_logs;
+
+        let sstore = &steps[2];
+        assert_eq!(sstore.op, OpcodeId::SSTORE);
+        assert_eq!(sstore.gas_cost.0, 22100);
+        assert_eq!(
+            sstore.storage.get_or_err(&Word::zero()).unwrap(),
+            0x2a.into()
+        );
+        // Resetting the slot to its original value refunds the SSTORE_SET.
+        let sstore = &steps[5];
+        assert_eq!(sstore.refund.0, 19900);
+        let sload = &steps[7];
+        assert_eq!(sload.op, OpcodeId::SLOAD);
+        assert_eq!(
+            sload.storage.get_or_err(&Word::zero()).unwrap(),
+            Word::zero()
+        );
+    }
+
+    #[test]
+    fn rust_tracer_revert() {
+        let code = bytecode! {
+            PUSH32(Word::from(0xdead))
+            PUSH1(0x00)
+            MSTORE
+            PUSH1(0x02)
+            PUSH1(0x1e)
+            REVERT
+        };
+        let traces = trace(&config_with_code(code, Word::from(2))).unwrap();
+
+        assert!(traces[0].failed);
+        assert_eq!(traces[0].return_value, "dead");
+        assert_eq!(traces[0].struct_logs[2].memory.0.len(), 0);
+        assert_eq!(traces[0].struct_logs[3].memory.0.len(), 32);
+    }
+
+    #[test]
+    fn rust_tracer_step_error() {
+        let code = bytecode! {
+            ADD
+        };
+        let traces = trace(&config_with_code(code, Word::from(2))).unwrap();
+
+        assert!(traces[0].failed);
+        assert_eq!(traces[0].gas.0, 1_000_000);
+        assert_eq!(
+            traces[0].struct_logs[0].error.as_deref(),
+            Some("stack underflow (0 <=> 2)")
+        );
+    }
+
+    #[test]
+    fn rust_tracer_delegated_call() {
+        let authority = address!("0x00000000000000000000000000000000000000aa");
+        let delegate = address!("0x00000000000000000000000000000000000000bb");
+        let code = bytecode! {
+            PUSH1(0x00)
+            PUSH1(0x00)
+            PUSH1(0x00)
+            PUSH1(0x00)
+            PUSH1(0x00)
+            PUSH20(authority.to_word())
+            GAS
+            CALL
+            STOP
+        };
+        let mut config = config_with_code(code, Word::from(2));
+        for (address, code) in [
+            (authority, delegation_code(delegate)),
+            (delegate, bytecode! { ADDRESS POP STOP }.code()),
+        ] {
+            config.accounts.insert(
+                address,
+                Account {
+                    address,
+                    code: code.into(),
+                    ..Default::default()
+                },
+            );
+        }
+        let traces = trace(&config).unwrap();
+        let steps = &traces[0].struct_logs;
+
+        // The cold accesses of the callee and of its delegate are charged
+        // before forwarding all but one 64th of the gas left.
+        let call = &steps[7];
+        assert_eq!(call.op, OpcodeId::CALL);
+        let gas_left = call.gas.0 - 2 * GasCost::COLD_ACCOUNT_ACCESS.0;
+        let callee_gas = gas_left - gas_left / 64;
+        assert_eq!(steps[8].gas.0, callee_gas);
+        // The callee executes the code of the delegate in its own context.
+        let callee_ops: Vec<_> = steps[8..11]
+            .iter()
+            .map(|step| (step.op, step.depth))
+            .collect();
+        assert_eq!(
+            callee_ops,
+            vec![
+                (OpcodeId::ADDRESS, 2),
+                (OpcodeId::POP, 2),
+                (OpcodeId::STOP, 2)
+            ]
+        );
+        assert_eq!(steps[9].stack, Stack::from_slice(&[authority.to_word()]));
+    }
+
+    #[test]
+    fn rust_tracer_invalid_tx() {
+        let mut config = config_with_code(Bytecode::default(), Word::from(2));
+        config.transactions[0].nonce = Word::one();
+
+        let err = trace(&config).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::TracingError(err) if err.starts_with("Failed to apply config.Transactions[0]: nonce too high")
+        ));
+    }
+}
@@ -0,0 +1,360 @@
+//! Memory size and dynamic gas functions of the opcodes, following
+//! `core/vm/memory_table.go` and `core/vm/gas_table.go` in geth.
+
+use super::interpreter::{Contract, Evm, ExecError};
+use eth_types::{
+    evm_types::{parse_delegation, GasCost, OpcodeId, MAX_CODE_SIZE, MAX_EXPANDED_MEMORY_ADDRESS},
+    ToAddress, Word,
+};
+
+/// Gas per address in the access list of a transaction.
+pub(crate) const TX_ACCESS_LIST_ADDRESS_GAS: u64 = 2400;
+/// Gas per storage key in the access list of a transaction.
+pub(crate) const TX_ACCESS_LIST_STORAGE_KEY_GAS: u64 = 1900;
+/// Gas per word of the init code (EIP-3860).
+pub(crate) const INIT_CODE_WORD_GAS: u64 = 2;
+/// Maximum init code size (EIP-3860).
+pub(crate) const MAX_INIT_CODE_SIZE: u64 = 2 * MAX_CODE_SIZE;
+/// Gas per byte of the data of a LOG.
+const LOG_DATA_GAS: u64 = 8;
+
+/// Returns the number of 32 bytes words needed to hold `size` bytes.
+pub(crate) fn to_word_size(size: u64) -> u64 {
+    if size > u64::MAX - 31 {
+        u64::MAX / 32 + 1
+    } else {
+        (size + 31) / 32
+    }
+}
+
+/// Returns the low 64 bits of `value`, and whether it doesn't fit in 64 bits.
+pub(crate) fn u64_with_overflow(value: Word) -> (u64, bool) {
+    (value.low_u64(), value.bits() > 64)
+}
+
+fn calc_mem_size(offset: Word, length: Word) -> Option<u64> {
+    let (length, overflow) = u64_with_overflow(length);
+    if overflow {
+        return None;
+    }
+    calc_mem_size_with_length(offset, length)
+}
+
+fn calc_mem_size_with_length(offset: Word, length: u64) -> Option<u64> {
+    // If length is zero, the memory size is always zero regardless of offset.
+    if length == 0 {
+        return Some(0);
+    }
+    let (offset, overflow) = u64_with_overflow(offset);
+    if overflow {
+        return None;
+    }
+    offset.checked_add(length)
+}
+
+/// Returns the memory size accessed by the opcode with the given stack, or
+/// `None` if it overflows.
+pub(crate) fn memory_size(op: OpcodeId, stack: &[Word]) -> Option<u64> {
+    let back = |n: usize| stack[stack.len() - 1 - n];
+    match op {
+        OpcodeId::SHA3
+        | OpcodeId::RETURN
+        | OpcodeId::REVERT
+        | OpcodeId::LOG0
+        | OpcodeId::LOG1
+        | OpcodeId::LOG2
+        | OpcodeId::LOG3
+        | OpcodeId::LOG4 => calc_mem_size(back(0), back(1)),
+        OpcodeId::CALLDATACOPY | OpcodeId::CODECOPY | OpcodeId::RETURNDATACOPY => {
+            calc_mem_size(back(0), back(2))
+        }
+        OpcodeId::EXTCODECOPY => calc_mem_size(back(1), back(3)),
+        OpcodeId::MLOAD | OpcodeId::MSTORE => calc_mem_size_with_length(back(0), 32),
+        OpcodeId::MSTORE8 => calc_mem_size_with_length(back(0), 1),
+        OpcodeId::MCOPY => calc_mem_size(back(0).max(back(1)), back(2)),
+        OpcodeId::CREATE | OpcodeId::CREATE2 => calc_mem_size(back(1), back(2)),
+        OpcodeId::CALL | OpcodeId::CALLCODE => {
+            let ret = calc_mem_size(back(5), back(6))?;
+            let args = calc_mem_size(back(3), back(4))?;
+            Some(ret.max(args))
+        }
+        OpcodeId::DELEGATECALL | OpcodeId::STATICCALL => {
+            let ret = calc_mem_size(back(4), back(5))?;
+            let args = calc_mem_size(back(2), back(3))?;
+            Some(ret.max(args))
+        }
+        _ => Some(0),
+    }
+}
+
+/// Returns the gas cost of expanding the memory of `memory_len` bytes to
+/// `new_size` bytes.
+fn memory_gas_cost(memory_len: usize, new_size: u64) -> Result<u64, ExecError> {
+    if new_size == 0 {
+        return Ok(0);
+    }
+    if new_size > MAX_EXPANDED_MEMORY_ADDRESS {
+        return Err(ExecError::GasUintOverflow);
+    }
+    let curr_words = memory_len as u64 / 32;
+    let next_words = to_word_size(new_size);
+    if next_words > curr_words {
+        Ok(eth_types::evm_types::gas_utils::memory_expansion_gas_cost(
+            curr_words, next_words,
+        ))
+    } else {
+        Ok(0)
+    }
+}
+
+/// Returns the gas of the memory expansion plus `per_word_gas` for each word
+/// of `size` bytes.
+fn memory_word_gas(
+    memory_len: usize,
+    memory_size: u64,
+    size: Word,
+    per_word_gas: u64,
+) -> Result<u64, ExecError> {
+    let gas = memory_gas_cost(memory_len, memory_size)?;
+    let (size, overflow) = u64_with_overflow(size);
+    if overflow {
+        return Err(ExecError::GasUintOverflow);
+    }
+    to_word_size(size)
+        .checked_mul(per_word_gas)
+        .and_then(|word_gas| gas.checked_add(word_gas))
+        .ok_or(ExecError::GasUintOverflow)
+}
+
+/// Returns the gas forwarded to the callee by the 63/64 rule of EIP-150.
+fn call_gas(available_gas: u64, base: u64, requested: Word) -> u64 {
+    let available_gas = available_gas.wrapping_sub(base);
+    let gas = available_gas - available_gas / 64;
+    let (requested, overflow) = u64_with_overflow(requested);
+    if overflow || gas < requested {
+        gas
+    } else {
+        requested
+    }
+}
+
+impl<'a> Evm<'a> {
+    /// Returns the dynamic gas cost of the opcode, updating the access list
+    /// and the refund counter like geth does.
+    pub(crate) fn dynamic_gas(
+        &mut self,
+        op: OpcodeId,
+        contract: &mut Contract,
+        stack: &[Word],
+        memory_len: usize,
+        memory_size: u64,
+    ) -> Result<u64, ExecError> {
+        let back = |n: usize| stack[stack.len() - 1 - n];
+        match op {
+            OpcodeId::EXP => Ok(((back(1).bits() as u64 + 7) / 8) * GasCost::EXP_BYTE_TIMES.0),
+            OpcodeId::SHA3 => {
+                memory_word_gas(memory_len, memory_size, back(1), GasCost::COPY_SHA3.0)
+            }
+            OpcodeId::CALLDATACOPY
+            | OpcodeId::CODECOPY
+            | OpcodeId::RETURNDATACOPY
+            | OpcodeId::MCOPY => memory_word_gas(memory_len, memory_size, back(2), GasCost::COPY.0),
+            OpcodeId::EXTCODECOPY => {
+                let gas = memory_word_gas(memory_len, memory_size, back(3), GasCost::COPY.0)?;
+                gas.checked_add(self.account_access_gas(back(0)))
+                    .ok_or(ExecError::GasUintOverflow)
+            }
+            OpcodeId::BALANCE | OpcodeId::EXTCODESIZE | OpcodeId::EXTCODEHASH => {
+                Ok(self.account_access_gas(back(0)))
+            }
+            OpcodeId::SLOAD => {
+                let key = back(0);
+                if self.state.slot_in_access_list(&contract.address, &key) {
+                    Ok(GasCost::WARM_ACCESS.0)
+                } else {
+                    self.state.add_slot_to_access_list(&contract.address, key);
+                    Ok(GasCost::COLD_SLOAD.0)
+                }
+            }
+            OpcodeId::SSTORE => self.sstore_gas(contract, back(0), back(1)),
+            OpcodeId::MLOAD
+            | OpcodeId::MSTORE
+            | OpcodeId::MSTORE8
+            | OpcodeId::RETURN
+            | OpcodeId::REVERT => memory_gas_cost(memory_len, memory_size),
+            OpcodeId::LOG0 | OpcodeId::LOG1 | OpcodeId::LOG2 | OpcodeId::LOG3 | OpcodeId::LOG4 => {
+                let num_topics = op.postfix().expect("LOG has postfix") as u64;
+                let (size, overflow) = u64_with_overflow(back(1));
+                if overflow {
+                    return Err(ExecError::GasUintOverflow);
+                }
+                let gas = memory_gas_cost(memory_len, memory_size)?;
+                gas.checked_add(GasCost::LOG.0 * (1 + num_topics))
+                    .and_then(|gas| {
+                        size.checked_mul(LOG_DATA_GAS)
+                            .and_then(|data_gas| gas.checked_add(data_gas))
+                    })
+                    .ok_or(ExecError::GasUintOverflow)
+            }
+            OpcodeId::CREATE | OpcodeId::CREATE2 => {
+                let per_word_gas = match (op, self.rules.is_shanghai) {
+                    (OpcodeId::CREATE, false) => return memory_gas_cost(memory_len, memory_size),
+                    (OpcodeId::CREATE, true) => INIT_CODE_WORD_GAS,
+                    (_, false) => GasCost::COPY_SHA3.0,
+                    (_, true) => GasCost::COPY_SHA3.0 + INIT_CODE_WORD_GAS,
+                };
+                if self.rules.is_shanghai {
+                    let (size, overflow) = u64_with_overflow(back(2));
+                    if overflow || size > MAX_INIT_CODE_SIZE {
+                        return Err(ExecError::GasUintOverflow);
+                    }
+                }
+                memory_word_gas(memory_len, memory_size, back(2), per_word_gas)
+            }
+            OpcodeId::CALL | OpcodeId::CALLCODE | OpcodeId::DELEGATECALL | OpcodeId::STATICCALL => {
+                self.call_op_gas(op, contract, stack, memory_len, memory_size)
+            }
+            OpcodeId::SELFDESTRUCT => {
+                let beneficiary = back(0).to_address();
+                let mut gas = 0;
+                if !self.state.address_in_access_list(&beneficiary) {
+                    self.state.add_address_to_access_list(&beneficiary);
+                    gas = GasCost::COLD_ACCOUNT_ACCESS.0;
+                }
+                if self.state.empty(&beneficiary)
+                    && !self.state.balance(&contract.address).is_zero()
+                {
+                    gas += GasCost::NEW_ACCOUNT.0;
+                }
+                Ok(gas)
+            }
+            _ => Ok(0),
+        }
+    }
+
+    /// Returns the extra gas of accessing a cold account (EIP-2929), since
+    /// the warm access is charged as constant gas.
+    fn account_access_gas(&mut self, address: Word) -> u64 {
+        let address = address.to_address();
+        if self.state.address_in_access_list(&address) {
+            0
+        } else {
+            self.state.add_address_to_access_list(&address);
+            GasCost::COLD_ACCOUNT_ACCESS.0 - GasCost::WARM_ACCESS.0
+        }
+    }
+
+    /// SSTORE gas of EIP-2200 with the changes of EIP-2929 and EIP-3529.
+    fn sstore_gas(
+        &mut self,
+        contract: &Contract,
+        key: Word,
+        value: Word,
+    ) -> Result<u64, ExecError> {
+        if contract.gas <= GasCost::SSTORE_SENTRY.0 {
+            return Err(ExecError::OutOfGas);
+        }
+        let address = contract.address;
+        let current = self.state.storage(&address, &key);
+        let mut cost = 0;
+        if !self.state.slot_in_access_list(&address, &key) {
+            cost = GasCost::COLD_SLOAD.0;
+            self.state.add_slot_to_access_list(&address, key);
+        }
+
+        if current == value {
+            return Ok(cost + GasCost::WARM_ACCESS.0);
+        }
+        let original = self.state.committed_storage(&address, &key);
+        if original == current {
+            if original.is_zero() {
+                return Ok(cost + GasCost::SSTORE_SET.0);
+            }
+            if value.is_zero() {
+                self.state.add_refund(GasCost::SSTORE_CLEARS_SCHEDULE.0);
+            }
+            return Ok(cost + GasCost::SSTORE_RESET.0);
+        }
+        if !original.is_zero() {
+            if current.is_zero() {
+                self.state.sub_refund(GasCost::SSTORE_CLEARS_SCHEDULE.0);
+            } else if value.is_zero() {
+                self.state.add_refund(GasCost::SSTORE_CLEARS_SCHEDULE.0);
+            }
+        }
+        if original == value {
+            if original.is_zero() {
+                self.state
+                    .add_refund(GasCost::SSTORE_SET.0 - GasCost::WARM_ACCESS.0);
+            } else {
+                self.state
+                    .add_refund(GasCost::SSTORE_RESET.0 - GasCost::WARM_ACCESS.0);
+            }
+        }
+        Ok(cost + GasCost::WARM_ACCESS.0)
+    }
+
+    /// Gas of the call opcodes, including the gas forwarded to the callee
+    /// which is kept in `call_gas_temp`.
+    fn call_op_gas(
+        &mut self,
+        op: OpcodeId,
+        contract: &mut Contract,
+        stack: &[Word],
+        memory_len: usize,
+        memory_size: u64,
+    ) -> Result<u64, ExecError> {
+        let back = |n: usize| stack[stack.len() - 1 - n];
+        let address = back(1).to_address();
+        let warm_access = self.state.address_in_access_list(&address);
+        let cold_cost = GasCost::COLD_ACCOUNT_ACCESS.0 - GasCost::WARM_ACCESS.0;
+        if !warm_access {
+            self.state.add_address_to_access_list(&address);
+            // Charge the cold access before computing the gas available to
+            // the callee.
+            if contract.gas < cold_cost {
+                return Err(ExecError::OutOfGas);
+            }
+            contract.gas -= cold_cost;
+        }
+        // Charge the access of the delegate of a delegated callee (EIP-7702)
+        // before computing the gas available to the callee as well.
+        let delegate_cost = match parse_delegation(self.state.code(&address)) {
+            Some(delegate) if self.state.address_in_access_list(&delegate) => {
+                GasCost::WARM_ACCESS.0
+            }
+            Some(delegate) => {
+                self.state.add_address_to_access_list(&delegate);
+                GasCost::COLD_ACCOUNT_ACCESS.0
+            }
+            None => 0,
+        };
+        if contract.gas < delegate_cost {
+            return Err(ExecError::OutOfGas);
+        }
+        contract.gas -= delegate_cost;
+
+        let mut gas = 0;
+        let transfers_value =
+            matches!(op, OpcodeId::CALL | OpcodeId::CALLCODE) && !back(2).is_zero();
+        if op == OpcodeId::CALL && transfers_value && self.state.empty(&address) {
+            gas += GasCost::NEW_ACCOUNT.0;
+        }
+        if transfers_value {
+            gas += GasCost::CALL_WITH_VALUE.0;
+        }
+        let gas = gas
+            .checked_add(memory_gas_cost(memory_len, memory_size)?)
+            .ok_or(ExecError::GasUintOverflow)?;
+        self.call_gas_temp = call_gas(contract.gas, gas, back(0));
+        let gas = gas
+            .checked_add(self.call_gas_temp)
+            .ok_or(ExecError::GasUintOverflow)?;
+
+        // Add the cold access and the delegate access back, so they're
+        // charged and reported as part of the dynamic gas.
+        let access_cost = if warm_access { 0 } else { cold_cost } + delegate_cost;
+        contract.gas += access_cost;
+        Ok(gas.wrapping_add(access_cost))
+    }
+}
@@ -0,0 +1,1016 @@
+//! EVM interpreter following the semantics of `core/vm` in geth, capturing
+//! the struct logs of the executed steps.
+
+use super::{
+    gas::{memory_size, to_word_size, u64_with_overflow},
+    logger::{StepContext, StructLogger},
+    state::{empty_code_hash, StateDb},
+    BlockContext, Rules, TxContext,
+};
+use eth_types::{
+    evm_types::{GasCost, OpcodeId, GAS_STIPEND_CALL_WITH_VALUE, MAX_CODE_SIZE},
+    Address, ToAddress, ToBigEndian, ToWord, Word, H256, U512,
+};
+use ethers_core::utils::{get_contract_address, get_create2_address, keccak256};
+use revm_precompile::{Precompile, Precompiles};
+use std::fmt;
+
+/// Maximum depth of nested calls and creations.
+const MAX_CALL_DEPTH: usize = 1024;
+
+/// Errors of the execution of a call frame. The messages of the errors which
+/// can be captured in the struct logs match the ones of geth.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum ExecError {
+    StackUnderflow { len: usize, required: usize },
+    StackOverflow { len: usize, limit: usize },
+    OutOfGas,
+    GasUintOverflow,
+    CodeStoreOutOfGas,
+    Depth,
+    InsufficientBalance,
+    ContractAddressCollision,
+    ExecutionReverted,
+    MaxCodeSizeExceeded,
+    InvalidJump,
+    WriteProtection,
+    ReturnDataOutOfBounds,
+    NonceUintOverflow,
+    InvalidCode,
+    InvalidOpCode(u8),
+    PrecompileFailed,
+}
+
+impl fmt::Display for ExecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::StackUnderflow { len, required } => {
+                write!(f, "stack underflow ({len} <=> {required})")
+            }
+            Self::StackOverflow { len, limit } => write!(f, "stack limit reached {len} ({limit})"),
+            Self::OutOfGas => write!(f, "out of gas"),
+            Self::GasUintOverflow => write!(f, "gas uint64 overflow"),
+            Self::CodeStoreOutOfGas => write!(f, "contract creation code storage out of gas"),
+            Self::Depth => write!(f, "max call depth exceeded"),
+            Self::InsufficientBalance => write!(f, "insufficient balance for transfer"),
+            Self::ContractAddressCollision => write!(f, "contract address collision"),
+            Self::ExecutionReverted => write!(f, "execution reverted"),
+            Self::MaxCodeSizeExceeded => write!(f, "max code size exceeded"),
+            Self::InvalidJump => write!(f, "invalid jump destination"),
+            Self::WriteProtection => write!(f, "write protection"),
+            Self::ReturnDataOutOfBounds => write!(f, "return data out of bounds"),
+            Self::NonceUintOverflow => write!(f, "nonce uint64 overflow"),
+            Self::InvalidCode => write!(f, "invalid code: must not begin with 0xef"),
+            Self::InvalidOpCode(opcode) => {
+                write!(f, "invalid opcode: opcode {opcode:#x} not defined")
+            }
+            Self::PrecompileFailed => write!(f, "precompiled contract failed"),
+        }
+    }
+}
+
+/// Returns the opcode of `byte` if it's defined in the active fork.
+fn opcode(byte: u8, rules: &Rules) -> Option<OpcodeId> {
+    match byte {
+        0x49 => rules.is_cancun.then_some(OpcodeId::BLOBHASH),
+        0x4a => rules.is_cancun.then_some(OpcodeId::BLOBBASEFEE),
+        0x5c => rules.is_cancun.then_some(OpcodeId::TLOAD),
+        0x5d => rules.is_cancun.then_some(OpcodeId::TSTORE),
+        0x5e => rules.is_cancun.then_some(OpcodeId::MCOPY),
+        0x5f => rules.is_shanghai.then_some(OpcodeId::PUSH0),
+        _ => match OpcodeId::from(byte) {
+            OpcodeId::INVALID(_) => None,
+            op => Some(op),
+        },
+    }
+}
+
+/// Returns if the address is a precompiled contract.
+fn is_precompiled(address: &Address) -> bool {
+    Precompiles::berlin()
+        .get(address.as_fixed_bytes())
+        .is_some()
+}
+
+/// Run the precompiled contract, returning the output, the gas left and the
+/// error if any.
+fn run_precompiled(address: &Address, input: &[u8], gas: u64) -> (Vec<u8>, u64, Option<ExecError>) {
+    let Some(Precompile::Standard(precompile_fn)) = Precompiles::berlin()
+        .get(address.as_fixed_bytes()) else {
+        panic!("calling non-exist precompiled contract address")
+    };
+
+    match precompile_fn(input, gas) {
+        Ok((gas_cost, output)) => (output, gas - gas_cost, None),
+        Err(_) => (vec![], 0, Some(ExecError::PrecompileFailed)),
+    }
+}
+
+/// Returns `size` bytes of `data` from `start`, padded with zeros.
+fn get_data(data: &[u8], start: u64, size: u64) -> Vec<u8> {
+    let len = data.len() as u64;
+    let start = start.min(len);
+    let end = start.saturating_add(size).min(len);
+    let mut bytes = data[start as usize..end as usize].to_vec();
+    bytes.resize(size as usize, 0);
+    bytes
+}
+
+/// Returns the memory slice at `offset` of `size` bytes, which has already
+/// been expanded.
+fn memory_slice(memory: &[u8], offset: Word, size: Word) -> &[u8] {
+    if size.is_zero() {
+        return &[];
+    }
+    let offset = offset.low_u64() as usize;
+    &memory[offset..offset + size.low_u64() as usize]
+}
+
+/// Copy at most `size` bytes of `value` to the memory at `offset`.
+fn memory_set(memory: &mut [u8], offset: u64, size: u64, value: &[u8]) {
+    if size > 0 {
+        let len = value.len().min(size as usize);
+        let offset = offset as usize;
+        memory[offset..offset + len].copy_from_slice(&value[..len]);
+    }
+}
+
+fn is_negative(value: Word) -> bool {
+    value.bit(255)
+}
+
+fn negate(value: Word) -> Word {
+    value.overflowing_neg().0
+}
+
+fn abs(value: Word) -> Word {
+    if is_negative(value) {
+        negate(value)
+    } else {
+        value
+    }
+}
+
+fn signed_lt(a: Word, b: Word) -> bool {
+    match (is_negative(a), is_negative(b)) {
+        (true, false) => true,
+        (false, true) => false,
+        _ => a < b,
+    }
+}
+
+fn u512_to_word(value: U512) -> Word {
+    Word::try_from(value).expect("value should fit in 256 bits")
+}
+
+/// Execution context of a call frame.
+#[derive(Debug)]
+pub(crate) struct Contract {
+    pub(crate) caller: Address,
+    /// Address of the account whose storage and balance are used.
+    pub(crate) address: Address,
+    pub(crate) value: Word,
+    pub(crate) input: Vec<u8>,
+    pub(crate) code: Vec<u8>,
+    jump_dests: Vec<bool>,
+    pub(crate) gas: u64,
+}
+
+impl Contract {
+    fn new(
+        caller: Address,
+        address: Address,
+        value: Word,
+        input: Vec<u8>,
+        code: Vec<u8>,
+        gas: u64,
+    ) -> Self {
+        let mut jump_dests = vec![false; code.len()];
+        let mut pc = 0;
+        while pc < code.len() {
+            let op = OpcodeId::from(code[pc]);
+            if op == OpcodeId::JUMPDEST {
+                jump_dests[pc] = true;
+            }
+            pc += 1 + op.data_len();
+        }
+        Self {
+            caller,
+            address,
+            value,
+            input,
+            code,
+            jump_dests,
+            gas,
+        }
+    }
+
+    fn is_valid_jump_dest(&self, dest: Word) -> bool {
+        let (dest, overflow) = u64_with_overflow(dest);
+        !overflow && *self.jump_dests.get(dest as usize).unwrap_or(&false)
+    }
+}
+
+/// Control flow after the execution of an opcode.
+enum Control {
+    Continue,
+    Jump(usize),
+    Stop(Vec<u8>),
+    Revert(Vec<u8>),
+}
+
+pub(crate) struct Evm<'a> {
+    pub(crate) state: &'a mut StateDb,
+    block: &'a BlockContext,
+    tx: TxContext,
+    pub(crate) rules: Rules,
+    logger: StructLogger,
+    depth: usize,
+    read_only: bool,
+    /// Gas forwarded to the callee, computed by the dynamic gas of the call
+    /// opcodes.
+    pub(crate) call_gas_temp: u64,
+}
+
+impl<'a> Evm<'a> {
+    pub(crate) fn new(
+        state: &'a mut StateDb,
+        block: &'a BlockContext,
+        tx: TxContext,
+        rules: Rules,
+        logger: StructLogger,
+    ) -> Self {
+        Self {
+            state,
+            block,
+            tx,
+            rules,
+            logger,
+            depth: 0,
+            read_only: false,
+            call_gas_temp: 0,
+        }
+    }
+
+    pub(crate) fn into_logger(self) -> StructLogger {
+        self.logger
+    }
+
+    /// Revert the state to the snapshot on error, and consume all the gas
+    /// unless the execution has been reverted.
+    fn finish_call(
+        &mut self,
+        snapshot: usize,
+        ret: Vec<u8>,
+        mut gas: u64,
+        err: Option<ExecError>,
+    ) -> (Vec<u8>, u64, Option<ExecError>) {
+        if let Some(err) = &err {
+            self.state.revert_to_snapshot(snapshot);
+            if *err != ExecError::ExecutionReverted {
+                gas = 0;
+            }
+        }
+        (ret, gas, err)
+    }
+
+    /// Execute the code of the callee.
+    fn run_contract(
+        &mut self,
+        mut contract: Contract,
+        read_only: bool,
+    ) -> (Vec<u8>, u64, Option<ExecError>) {
+        let (ret, err) = self.run(&mut contract, read_only);
+        (ret, contract.gas, err)
+    }
+
+    pub(crate) fn call(
+        &mut self,
+        caller: Address,
+        address: Address,
+        input: Vec<u8>,
+        gas: u64,
+        value: Word,
+    ) -> (Vec<u8>, u64, Option<ExecError>) {
+        if self.depth > MAX_CALL_DEPTH {
+            return (vec![], gas, Some(ExecError::Depth));
+        }
+        if !value.is_zero() && self.state.balance(&caller) < value {
+            return (vec![], gas, Some(ExecError::InsufficientBalance));
+        }
+        let snapshot = self.state.snapshot();
+        let is_precompile = is_precompiled(&address);
+        if !self.state.exist(&address) {
+            if !is_precompile && value.is_zero() {
+                // Calling a non existing account does nothing.
+                return (vec![], gas, None);
+            }
+            self.state.create_account(&address);
+        }
+        self.state.transfer(&caller, &address, value);
+
+        let (ret, gas, err) = if is_precompile {
+            run_precompiled(&address, &input, gas)
+        } else {
+            let code = self.state.resolved_code(&address).to_vec();
+            if code.is_empty() {
+                (vec![], gas, None)
+            } else {
+                self.run_contract(
+                    Contract::new(caller, address, value, input, code, gas),
+                    false,
+                )
+            }
+        };
+        self.finish_call(snapshot, ret, gas, err)
+    }
+
+    fn call_code(
+        &mut self,
+        caller: Address,
+        address: Address,
+        input: Vec<u8>,
+        gas: u64,
+        value: Word,
+    ) -> (Vec<u8>, u64, Option<ExecError>) {
+        if self.depth > MAX_CALL_DEPTH {
+            return (vec![], gas, Some(ExecError::Depth));
+        }
+        if self.state.balance(&caller) < value {
+            return (vec![], gas, Some(ExecError::InsufficientBalance));
+        }
+        let snapshot = self.state.snapshot();
+        let (ret, gas, err) = if is_precompiled(&address) {
+            run_precompiled(&address, &input, gas)
+        } else {
+            let code = self.state.resolved_code(&address).to_vec();
+            self.run_contract(
+                Contract::new(caller, caller, value, input, code, gas),
+                false,
+            )
+        };
+        self.finish_call(snapshot, ret, gas, err)
+    }
+
+    fn delegate_call(
+        &mut self,
+        parent: &Contract,
+        address: Address,
+        input: Vec<u8>,
+        gas: u64,
+    ) -> (Vec<u8>, u64, Option<ExecError>) {
+        if self.depth > MAX_CALL_DEPTH {
+            return (vec![], gas, Some(ExecError::Depth));
+        }
+        let snapshot = self.state.snapshot();
+        let (ret, gas, err) = if is_precompiled(&address) {
+            run_precompiled(&address, &input, gas)
+        } else {
+            let code = self.state.resolved_code(&address).to_vec();
+            self.run_contract(
+                Contract::new(
+                    parent.caller,
+                    parent.address,
+                    parent.value,
+                    input,
+                    code,
+                    gas,
+                ),
+                false,
+            )
+        };
+        self.finish_call(snapshot, ret, gas, err)
+    }
+
+    fn static_call(
+        &mut self,
+        caller: Address,
+        address: Address,
+        input: Vec<u8>,
+        gas: u64,
+    ) -> (Vec<u8>, u64, Option<ExecError>) {
+        if self.depth > MAX_CALL_DEPTH {
+            return (vec![], gas, Some(ExecError::Depth));
+        }
+        let snapshot = self.state.snapshot();
+        // Touch the callee, like geth does by adding a zero balance.
+        self.state.add_balance(&address, Word::zero());
+
+        let (ret, gas, err) = if is_precompiled(&address) {
+            run_precompiled(&address, &input, gas)
+        } else {
+            let code = self.state.resolved_code(&address).to_vec();
+            self.run_contract(
+                Contract::new(caller, address, Word::zero(), input, code, gas),
+                true,
+            )
+        };
+        self.finish_call(snapshot, ret, gas, err)
+    }
+
+    pub(crate) fn create(
+        &mut self,
+        caller: Address,
+        code: Vec<u8>,
+        gas: u64,
+        value: Word,
+    ) -> (Vec<u8>, Address, u64, Option<ExecError>) {
+        let address = get_contract_address(caller, self.state.nonce(&caller));
+        self.create_at(caller, code, gas, value, address)
+    }
+
+    fn create2(
+        &mut self,
+        caller: Address,
+        code: Vec<u8>,
+        gas: u64,
+        value: Word,
+        salt: Word,
+    ) -> (Vec<u8>, Address, u64, Option<ExecError>) {
+        let address = get_create2_address(caller, salt.to_be_bytes().to_vec(), code.clone());
+        self.create_at(caller, code, gas, value, address)
+    }
+
+    fn create_at(
+        &mut self,
+        caller: Address,
+        code: Vec<u8>,
+        gas: u64,
+        value: Word,
+        address: Address,
+    ) -> (Vec<u8>, Address, u64, Option<ExecError>) {
+        if self.depth > MAX_CALL_DEPTH {
+            return (vec![], Address::zero(), gas, Some(ExecError::Depth));
+        }
+        if self.state.balance(&caller) < value {
+            return (
+                vec![],
+                Address::zero(),
+                gas,
+                Some(ExecError::InsufficientBalance),
+            );
+        }
+        let nonce = self.state.nonce(&caller);
+        if nonce == u64::MAX {
+            return (
+                vec![],
+                Address::zero(),
+                gas,
+                Some(ExecError::NonceUintOverflow),
+            );
+        }
+        self.state.set_nonce(&caller, nonce + 1);
+        // The access list change is not reverted even if the creation fails.
+        self.state.add_address_to_access_list(&address);
+
+        let code_hash = self.state.code_hash(&address);
+        if self.state.nonce(&address) != 0
+            || (code_hash != H256::zero() && code_hash != empty_code_hash())
+        {
+            return (
+                vec![],
+                Address::zero(),
+                0,
+                Some(ExecError::ContractAddressCollision),
+            );
+        }
+
+        let snapshot = self.state.snapshot();
+        self.state.create_account(&address);
+        self.state.set_nonce(&address, 1);
+        self.state.transfer(&caller, &address, value);
+
+        let mut contract = Contract::new(caller, address, value, vec![], code, gas);
+        let (ret, mut err) = self.run(&mut contract, false);
+
+        if err.is_none() && ret.len() as u64 > MAX_CODE_SIZE {
+            err = Some(ExecError::MaxCodeSizeExceeded);
+        }
+        if err.is_none() && ret.first() == Some(&0xef) {
+            err = Some(ExecError::InvalidCode);
+        }
+        if err.is_none() {
+            let code_deposit_gas = ret.len() as u64 * GasCost::CODE_DEPOSIT_BYTE_COST.0;
+            if contract.gas >= code_deposit_gas {
+                contract.gas -= code_deposit_gas;
+                self.state.set_code(&address, ret.clone());
+            } else {
+                err = Some(ExecError::CodeStoreOutOfGas);
+            }
+        }
+        let (ret, gas, err) = self.finish_call(snapshot, ret, contract.gas, err);
+        (ret, address, gas, err)
+    }
+
+    /// Run the code of the contract, returning the output and the error if
+    /// any.
+    fn run(&mut self, contract: &mut Contract, read_only: bool) -> (Vec<u8>, Option<ExecError>) {
+        self.depth += 1;
+        let prev_read_only = self.read_only;
+        self.read_only |= read_only;
+
+        let result = if contract.code.is_empty() {
+            (vec![], None)
+        } else {
+            self.run_steps(contract)
+        };
+
+        self.read_only = prev_read_only;
+        self.depth -= 1;
+        result
+    }
+
+    fn run_steps(&mut self, contract: &mut Contract) -> (Vec<u8>, Option<ExecError>) {
+        let mut stack = Vec::new();
+        let mut memory = Vec::new();
+        let mut return_data = Vec::new();
+        let mut pc = 0;
+
+        loop {
+            let gas = contract.gas;
+            let byte = contract.code.get(pc).copied().unwrap_or_default();
+            let op = opcode(byte, &self.rules);
+
+            let mut gas_cost = op.map_or(0, |op| op.constant_gas_cost().0);
+            let result = match op {
+                Some(op) => self.consume_gas(op, contract, &stack, memory.len(), &mut gas_cost),
+                None => Ok(0),
+            };
+
+            let address = contract.address;
+            let state = &self.state;
+            self.logger.capture_state(
+                StepContext {
+                    pc,
+                    opcode: byte,
+                    gas,
+                    gas_cost,
+                    refund: state.refund(),
+                    depth: self.depth,
+                    error: result.as_ref().err().map(ToString::to_string),
+                    address,
+                    stack: &stack,
+                    memory: &memory,
+                },
+                |key| state.storage(&address, &key),
+            );
+
+            let memory_size = match result {
+                Ok(memory_size) => memory_size as usize,
+                Err(err) => return (vec![], Some(err)),
+            };
+            if memory_size > memory.len() {
+                memory.resize(memory_size, 0);
+            }
+            let Some(op) = op else {
+                return (vec![], Some(ExecError::InvalidOpCode(byte)));
+            };
+
+            match self.execute(op, pc, contract, &mut stack, &mut memory, &mut return_data) {
+                Ok(Control::Continue) => pc += 1 + op.data_len(),
+                Ok(Control::Jump(dest)) => pc = dest,
+                Ok(Control::Stop(ret)) => return (ret, None),
+                Ok(Control::Revert(ret)) => return (ret, Some(ExecError::ExecutionReverted)),
+                Err(err) => return (vec![], Some(err)),
+            }
+        }
+    }
+
+    /// Validate the stack and consume the constant and dynamic gas of the
+    /// opcode, returning the memory size after the execution.
+    fn consume_gas(
+        &mut self,
+        op: OpcodeId,
+        contract: &mut Contract,
+        stack: &[Word],
+        memory_len: usize,
+        gas_cost: &mut u64,
+    ) -> Result<u64, ExecError> {
+        let (min_stack_ptr, max_stack_ptr) = op.valid_stack_ptr_range();
+        let stack_ptr = (MAX_CALL_DEPTH - stack.len()) as u32;
+        if stack_ptr > max_stack_ptr {
+            return Err(ExecError::StackUnderflow {
+                len: stack.len(),
+                required: (1024 - max_stack_ptr) as usize,
+            });
+        }
+        if stack_ptr < min_stack_ptr {
+            return Err(ExecError::StackOverflow {
+                len: stack.len(),
+                limit: (1024 - min_stack_ptr) as usize,
+            });
+        }
+
+        if contract.gas < *gas_cost {
+            return Err(ExecError::OutOfGas);
+        }
+        contract.gas -= *gas_cost;
+
+        let memory_size = memory_size(op, stack)
+            .and_then(|size| to_word_size(size).checked_mul(32))
+            .ok_or(ExecError::GasUintOverflow)?;
+        let dynamic_gas = self.dynamic_gas(op, contract, stack, memory_len, memory_size)?;
+        *gas_cost += dynamic_gas;
+        if contract.gas < dynamic_gas {
+            return Err(ExecError::OutOfGas);
+        }
+        contract.gas -= dynamic_gas;
+
+        Ok(memory_size)
+    }
+
+    fn execute(
+        &mut self,
+        op: OpcodeId,
+        pc: usize,
+        contract: &mut Contract,
+        stack: &mut Vec<Word>,
+        memory: &mut [u8],
+        return_data: &mut Vec<u8>,
+    ) -> Result<Control, ExecError> {
+        macro_rules! pop {
+            () => {
+                stack.pop().expect("stack has been validated")
+            };
+        }
+
+        let value = match op {
+            OpcodeId::STOP => return Ok(Control::Stop(vec![])),
+            OpcodeId::ADD => pop!().overflowing_add(pop!()).0,
+            OpcodeId::MUL => pop!().overflowing_mul(pop!()).0,
+            OpcodeId::SUB => pop!().overflowing_sub(pop!()).0,
+            OpcodeId::DIV => {
+                let (a, b) = (pop!(), pop!());
+                a.checked_div(b).unwrap_or_default()
+            }
+            OpcodeId::SDIV => {
+                let (a, b) = (pop!(), pop!());
+                if b.is_zero() {
+                    Word::zero()
+                } else {
+                    let quotient = abs(a) / abs(b);
+                    if is_negative(a) != is_negative(b) {
+                        negate(quotient)
+                    } else {
+                        quotient
+                    }
+                }
+            }
+            OpcodeId::MOD => {
+                let (a, b) = (pop!(), pop!());
+                a.checked_rem(b).unwrap_or_default()
+            }
+            OpcodeId::SMOD => {
+                let (a, b) = (pop!(), pop!());
+                if b.is_zero() {
+                    Word::zero()
+                } else {
+                    let remainder = abs(a) % abs(b);
+                    if is_negative(a) {
+                        negate(remainder)
+                    } else {
+                        remainder
+                    }
+                }
+            }
+            OpcodeId::ADDMOD => {
+                let (a, b, n) = (pop!(), pop!(), pop!());
+                if n.is_zero() {
+                    Word::zero()
+                } else {
+                    u512_to_word((U512::from(a) + U512::from(b)) % U512::from(n))
+                }
+            }
+            OpcodeId::MULMOD => {
+                let (a, b, n) = (pop!(), pop!(), pop!());
+                if n.is_zero() {
+                    Word::zero()
+                } else {
+                    u512_to_word(a.full_mul(b) % U512::from(n))
+                }
+            }
+            OpcodeId::EXP => {
+                let (base, exponent) = (pop!(), pop!());
+                base.overflowing_pow(exponent).0
+            }
+            OpcodeId::SIGNEXTEND => {
+                let (byte, value) = (pop!(), pop!());
+                if byte < Word::from(31) {
+                    let bit = byte.low_u64() as usize * 8 + 7;
+                    let mask = (Word::one() << bit) - 1;
+                    if value.bit(bit) {
+                        value | !mask
+                    } else {
+                        value & mask
+                    }
+                } else {
+                    value
+                }
+            }
+            OpcodeId::LT => (pop!() < pop!()).to_word(),
+            OpcodeId::GT => (pop!() > pop!()).to_word(),
+            OpcodeId::SLT => signed_lt(pop!(), pop!()).to_word(),
+            OpcodeId::SGT => {
+                let (a, b) = (pop!(), pop!());
+                signed_lt(b, a).to_word()
+            }
+            OpcodeId::EQ => (pop!() == pop!()).to_word(),
+            OpcodeId::ISZERO => pop!().is_zero().to_word(),
+            OpcodeId::AND => pop!() & pop!(),
+            OpcodeId::OR => pop!() | pop!(),
+            OpcodeId::XOR => pop!() ^ pop!(),
+            OpcodeId::NOT => !pop!(),
+            OpcodeId::BYTE => {
+                let (index, value) = (pop!(), pop!());
+                if index < Word::from(32) {
+                    Word::from(value.byte(31 - index.low_u64() as usize))
+                } else {
+                    Word::zero()
+                }
+            }
+            OpcodeId::SHL | OpcodeId::SHR | OpcodeId::SAR => {
+                let (shift, value) = (pop!(), pop!());
+                let shift = (shift < Word::from(256)).then(|| shift.low_u64() as usize);
+                match (op, shift) {
+                    (OpcodeId::SHL, Some(shift)) => value << shift,
+                    (OpcodeId::SHR, Some(shift)) => value >> shift,
+                    (OpcodeId::SAR, Some(shift)) if is_negative(value) => !(!value >> shift),
+                    (OpcodeId::SAR, Some(shift)) => value >> shift,
+                    (OpcodeId::SAR, None) if is_negative(value) => Word::MAX,
+                    _ => Word::zero(),
+                }
+            }
+            OpcodeId::SHA3 => {
+                let (offset, size) = (pop!(), pop!());
+                Word::from_big_endian(&keccak256(memory_slice(memory, offset, size)))
+            }
+            OpcodeId::ADDRESS => contract.address.to_word(),
+            OpcodeId::BALANCE => self.state.balance(&pop!().to_address()),
+            OpcodeId::ORIGIN => self.tx.origin.to_word(),
+            OpcodeId::CALLER => contract.caller.to_word(),
+            OpcodeId::CALLVALUE => contract.value,
+            OpcodeId::CALLDATALOAD => {
+                let (offset, overflow) = u64_with_overflow(pop!());
+                if overflow {
+                    Word::zero()
+                } else {
+                    Word::from_big_endian(&get_data(&contract.input, offset, 32))
+                }
+            }
+            OpcodeId::CALLDATASIZE => contract.input.len().into(),
+            OpcodeId::CALLDATACOPY | OpcodeId::CODECOPY | OpcodeId::EXTCODECOPY => {
+                let code;
+                let data = match op {
+                    OpcodeId::CALLDATACOPY => &contract.input,
+                    OpcodeId::CODECOPY => &contract.code,
+                    _ => {
+                        code = self.state.code(&pop!().to_address()).to_vec();
+                        &code
+                    }
+                };
+                let (memory_offset, data_offset, length) = (pop!(), pop!(), pop!());
+                let (data_offset, overflow) = u64_with_overflow(data_offset);
+                let data_offset = if overflow { u64::MAX } else { data_offset };
+                memory_set(
+                    memory,
+                    memory_offset.low_u64(),
+                    length.low_u64(),
+                    &get_data(data, data_offset, length.low_u64()),
+                );
+                return Ok(Control::Continue);
+            }
+            OpcodeId::CODESIZE => contract.code.len().into(),
+            OpcodeId::GASPRICE => self.tx.gas_price,
+            OpcodeId::EXTCODESIZE => self.state.code(&pop!().to_address()).len().into(),
+            OpcodeId::RETURNDATASIZE => return_data.len().into(),
+            OpcodeId::RETURNDATACOPY => {
+                let (memory_offset, data_offset, length) = (pop!(), pop!(), pop!());
+                let (offset, overflow) = u64_with_overflow(data_offset);
+                if overflow {
+                    return Err(ExecError::ReturnDataOutOfBounds);
+                }
+                let (end, overflow) = u64_with_overflow(data_offset.overflowing_add(length).0);
+                if overflow || (return_data.len() as u64) < end {
+                    return Err(ExecError::ReturnDataOutOfBounds);
+                }
+                memory_set(
+                    memory,
+                    memory_offset.low_u64(),
+                    length.low_u64(),
+                    &return_data[offset as usize..end as usize],
+                );
+                return Ok(Control::Continue);
+            }
+            OpcodeId::EXTCODEHASH => {
+                let address = pop!().to_address();
+                if self.state.empty(&address) {
+                    Word::zero()
+                } else {
+                    self.state.code_hash(&address).to_word()
+                }
+            }
+            OpcodeId::BLOCKHASH => {
+                let (number, overflow) = u64_with_overflow(pop!());
+                let upper = self.block.number;
+                let lower = upper.saturating_sub(256);
+                if !overflow && number >= lower && number < upper {
+                    self.block.block_hash(number)
+                } else {
+                    Word::zero()
+                }
+            }
+            OpcodeId::COINBASE => self.block.coinbase.to_word(),
+            OpcodeId::TIMESTAMP => self.block.timestamp.into(),
+            OpcodeId::NUMBER => self.block.number.into(),
+            // PREVRANDAO, which is the difficulty in the tracer.
+            OpcodeId::DIFFICULTY => self.block.difficulty,
+            OpcodeId::GASLIMIT => self.block.gas_limit.into(),
+            OpcodeId::CHAINID => self.block.chain_id.into(),
+            OpcodeId::SELFBALANCE => self.state.balance(&contract.address),
+            OpcodeId::BASEFEE => self.block.base_fee,
+            OpcodeId::BLOBHASH => {
+                let (index, overflow) = u64_with_overflow(pop!());
+                match self.tx.blob_hashes.get(index as usize) {
+                    Some(hash) if !overflow => *hash,
+                    _ => Word::zero(),
+                }
+            }
+            OpcodeId::BLOBBASEFEE => self.block.blob_base_fee,
+            OpcodeId::POP => {
+                pop!();
+                return Ok(Control::Continue);
+            }
+            OpcodeId::MLOAD => {
+                let offset = pop!().low_u64() as usize;
+                Word::from_big_endian(&memory[offset..offset + 32])
+            }
+            OpcodeId::MSTORE => {
+                let (offset, value) = (pop!().low_u64() as usize, pop!());
+                memory[offset..offset + 32].copy_from_slice(&value.to_be_bytes());
+                return Ok(Control::Continue);
+            }
+            OpcodeId::MSTORE8 => {
+                let (offset, value) = (pop!().low_u64() as usize, pop!());
+                memory[offset] = value.low_u64() as u8;
+                return Ok(Control::Continue);
+            }
+            OpcodeId::SLOAD => self.state.storage(&contract.address, &pop!()),
+            OpcodeId::SSTORE | OpcodeId::TSTORE => {
+                if self.read_only {
+                    return Err(ExecError::WriteProtection);
+                }
+                let (key, value) = (pop!(), pop!());
+                if op == OpcodeId::SSTORE {
+                    self.state.set_storage(&contract.address, key, value);
+                } else {
+                    self.state
+                        .set_transient_storage(&contract.address, key, value);
+                }
+                return Ok(Control::Continue);
+            }
+            OpcodeId::JUMP | OpcodeId::JUMPI => {
+                let dest = pop!();
+                if op == OpcodeId::JUMPI && pop!().is_zero() {
+                    return Ok(Control::Continue);
+                }
+                if !contract.is_valid_jump_dest(dest) {
+                    return Err(ExecError::InvalidJump);
+                }
+                return Ok(Control::Jump(dest.low_u64() as usize));
+            }
+            OpcodeId::PC => pc.into(),
+            OpcodeId::MSIZE => memory.len().into(),
+            OpcodeId::GAS => contract.gas.into(),
+            OpcodeId::JUMPDEST => return Ok(Control::Continue),
+            OpcodeId::TLOAD => self.state.transient_storage(&contract.address, &pop!()),
+            OpcodeId::MCOPY => {
+                let (dst_offset, src_offset, length) = (pop!(), pop!(), pop!());
+                if !length.is_zero() {
+                    let src_offset = src_offset.low_u64() as usize;
+                    let length = length.low_u64() as usize;
+                    memory.copy_within(
+                        src_offset..src_offset + length,
+                        dst_offset.low_u64() as usize,
+                    );
+                }
+                return Ok(Control::Continue);
+            }
+            OpcodeId::PUSH0 => Word::zero(),
+            op if op.is_push_with_data() => {
+                let start = (pc + 1).min(contract.code.len());
+                let end = (start + op.data_len()).min(contract.code.len());
+                let mut bytes = contract.code[start..end].to_vec();
+                bytes.resize(op.data_len(), 0);
+                Word::from_big_endian(&bytes)
+            }
+            op if op.is_dup() => stack[stack.len() - op.postfix().unwrap() as usize],
+            op if op.is_swap() => {
+                let top = stack.len() - 1;
+                stack.swap(top, top - op.postfix().unwrap() as usize);
+                return Ok(Control::Continue);
+            }
+            op if op.is_log() => {
+                if self.read_only {
+                    return Err(ExecError::WriteProtection);
+                }
+                for _ in 0..2 + op.postfix().unwrap() {
+                    pop!();
+                }
+                return Ok(Control::Continue);
+            }
+            OpcodeId::CREATE | OpcodeId::CREATE2 => {
+                if self.read_only {
+                    return Err(ExecError::WriteProtection);
+                }
+                let (value, offset, size) = (pop!(), pop!(), pop!());
+                let salt = (op == OpcodeId::CREATE2).then(|| pop!());
+                let code = memory_slice(memory, offset, size).to_vec();
+                let gas = contract.gas - contract.gas / 64;
+                contract.gas -= gas;
+
+                let (ret, address, return_gas, err) = match salt {
+                    None => self.create(contract.address, code, gas, value),
+                    Some(salt) => self.create2(contract.address, code, gas, value, salt),
+                };
+                stack.push(if err.is_none() {
+                    address.to_word()
+                } else {
+                    Word::zero()
+                });
+                contract.gas += return_gas;
+                *return_data = if err == Some(ExecError::ExecutionReverted) {
+                    ret
+                } else {
+                    vec![]
+                };
+                return Ok(Control::Continue);
+            }
+            OpcodeId::CALL | OpcodeId::CALLCODE | OpcodeId::DELEGATECALL | OpcodeId::STATICCALL => {
+                // The requested gas is replaced by the gas computed with the
+                // dynamic gas of the opcode.
+                pop!();
+                let mut gas = self.call_gas_temp;
+                let address = pop!().to_address();
+                let value = if matches!(op, OpcodeId::CALL | OpcodeId::CALLCODE) {
+                    pop!()
+                } else {
+                    Word::zero()
+                };
+                let (args_offset, args_length, ret_offset, ret_length) =
+                    (pop!(), pop!(), pop!(), pop!());
+                let input = memory_slice(memory, args_offset, args_length).to_vec();
+
+                if op == OpcodeId::CALL && self.read_only && !value.is_zero() {
+                    return Err(ExecError::WriteProtection);
+                }
+                if !value.is_zero() {
+                    gas += GAS_STIPEND_CALL_WITH_VALUE;
+                }
+
+                let (ret, return_gas, err) = match op {
+                    OpcodeId::CALL => self.call(contract.address, address, input, gas, value),
+                    OpcodeId::CALLCODE => {
+                        self.call_code(contract.address, address, input, gas, value)
+                    }
+                    OpcodeId::DELEGATECALL => self.delegate_call(contract, address, input, gas),
+                    _ => self.static_call(contract.address, address, input, gas),
+                };
+
+                stack.push(err.is_none().to_word());
+                if matches!(err, None | Some(ExecError::ExecutionReverted)) {
+                    memory_set(memory, ret_offset.low_u64(), ret_length.low_u64(), &ret);
+                }
+                contract.gas += return_gas;
+                *return_data = ret;
+                return Ok(Control::Continue);
+            }
+            OpcodeId::RETURN | OpcodeId::REVERT => {
+                let (offset, size) = (pop!(), pop!());
+                let ret = memory_slice(memory, offset, size).to_vec();
+                return Ok(if op == OpcodeId::RETURN {
+                    Control::Stop(ret)
+                } else {
+                    Control::Revert(ret)
+                });
+            }
+            OpcodeId::SELFDESTRUCT => {
+                if self.read_only {
+                    return Err(ExecError::WriteProtection);
+                }
+                let beneficiary = pop!().to_address();
+                let balance = self.state.balance(&contract.address);
+                self.state.sub_balance(&contract.address, balance);
+                self.state.add_balance(&beneficiary, balance);
+                // Since EIP-6780 only an account created in the same transaction
+                // is destroyed.
+                self.state.selfdestruct_6780(&contract.address);
+                return Ok(Control::Stop(vec![]));
+            }
+            OpcodeId::INVALID(_) => unreachable!("undefined opcodes are not executed"),
+            _ => unreachable!("unexpected opcode {:?}", op),
+        };
+
+        stack.push(value);
+        Ok(Control::Continue)
+    }
+}
@@ -0,0 +1,92 @@
+//! Struct logger collecting the [`GethExecStep`]s of a transaction, following
+//! the semantics of `eth/tracers/logger.StructLogger` in geth.
+
+use crate::LoggerConfig;
+use eth_types::{
+    evm_types::{Gas, GasCost, Memory, OpcodeId, ProgramCounter, Stack, Storage},
+    Address, GethExecStep, Word,
+};
+use std::collections::HashMap;
+
+/// Snapshot of the interpreter state before an opcode is executed.
+pub(crate) struct StepContext<'a> {
+    pub(crate) pc: usize,
+    pub(crate) opcode: u8,
+    pub(crate) gas: u64,
+    pub(crate) gas_cost: u64,
+    pub(crate) refund: u64,
+    pub(crate) depth: usize,
+    pub(crate) error: Option<String>,
+    pub(crate) address: Address,
+    pub(crate) stack: &'a [Word],
+    pub(crate) memory: &'a [u8],
+}
+
+#[derive(Debug)]
+pub(crate) struct StructLogger {
+    config: LoggerConfig,
+    /// Storage slots accessed by SLOAD and SSTORE so far, per contract.
+    storage: HashMap<Address, HashMap<Word, Word>>,
+    logs: Vec<GethExecStep>,
+}
+
+impl StructLogger {
+    pub(crate) fn new(config: LoggerConfig) -> Self {
+        Self {
+            config,
+            storage: HashMap::new(),
+            logs: vec![],
+        }
+    }
+
+    /// Capture a step. `sload_value` is the current value of the slot read
+    /// by SLOAD.
+    pub(crate) fn capture_state(
+        &mut self,
+        ctx: StepContext,
+        sload_value: impl FnOnce(Word) -> Word,
+    ) {
+        let op = OpcodeId::from(ctx.opcode);
+        let stack_len = ctx.stack.len();
+
+        let mut storage = Storage::empty();
+        if !self.config.disable_storage && matches!(op, OpcodeId::SLOAD | OpcodeId::SSTORE) {
+            let contract_storage = self.storage.entry(ctx.address).or_default();
+            if op == OpcodeId::SLOAD && stack_len >= 1 {
+                let key = ctx.stack[stack_len - 1];
+                contract_storage.insert(key, sload_value(key));
+                storage = Storage::new(contract_storage.clone());
+            } else if op == OpcodeId::SSTORE && stack_len >= 2 {
+                let key = ctx.stack[stack_len - 1];
+                let value = ctx.stack[stack_len - 2];
+                contract_storage.insert(key, value);
+                storage = Storage::new(contract_storage.clone());
+            }
+        }
+
+        self.logs.push(GethExecStep {
+            pc: ProgramCounter(ctx.pc),
+            op,
+            gas: Gas(ctx.gas),
+            gas_cost: GasCost(ctx.gas_cost),
+            refund: Gas(ctx.refund),
+            depth: ctx.depth as u16,
+            error: ctx.error,
+            stack: if self.config.disable_stack {
+                Stack::new()
+            } else {
+                Stack::from_slice(ctx.stack)
+            },
+            memory: if self.config.enable_memory {
+                Memory::from(ctx.memory.to_vec())
+            } else {
+                Memory::new()
+            },
+            storage,
+        });
+    }
+
+    pub(crate) fn into_logs(self) -> Vec<GethExecStep> {
+        self.logs
+    }
+}
@@ -0,0 +1,478 @@
+//! In-memory state database with a journal of reversible changes, following
+//! the semantics of `core/state.StateDB` in geth.
+
+use eth_types::{
+    evm_types::parse_delegation, geth_types::Account as GethAccount, Address, Word, H256,
+};
+use ethers_core::utils::keccak256;
+use std::collections::{HashMap, HashSet};
+
+/// Returns the hash of the empty code.
+pub(crate) fn empty_code_hash() -> H256 {
+    H256(keccak256([0u8; 0]))
+}
+
+/// State of a single account.
+#[derive(Clone, Debug)]
+pub(crate) struct Account {
+    pub(crate) nonce: u64,
+    pub(crate) balance: Word,
+    pub(crate) code: Vec<u8>,
+    pub(crate) code_hash: H256,
+    pub(crate) storage: HashMap<Word, Word>,
+    /// The account has been self-destructed in the current transaction.
+    pub(crate) selfdestructed: bool,
+    /// The account has been (re-)created in the current transaction, so its
+    /// committed storage is empty.
+    pub(crate) created: bool,
+}
+
+impl Default for Account {
+    fn default() -> Self {
+        Self {
+            nonce: 0,
+            balance: Word::zero(),
+            code: vec![],
+            code_hash: empty_code_hash(),
+            storage: HashMap::new(),
+            selfdestructed: false,
+            created: false,
+        }
+    }
+}
+
+impl Account {
+    /// Return if the account is empty as defined by EIP-161.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.nonce == 0 && self.balance.is_zero() && self.code.is_empty()
+    }
+}
+
+/// Change to the state that can be reverted.
+#[derive(Debug)]
+enum JournalEntry {
+    CreateAccount {
+        address: Address,
+        prev: Option<Account>,
+    },
+    BalanceChange {
+        address: Address,
+        prev: Word,
+    },
+    NonceChange {
+        address: Address,
+        prev: u64,
+    },
+    CodeChange {
+        address: Address,
+        prev_code: Vec<u8>,
+        prev_hash: H256,
+    },
+    StorageChange {
+        address: Address,
+        key: Word,
+        prev: Word,
+    },
+    TransientStorageChange {
+        address: Address,
+        key: Word,
+        prev: Word,
+    },
+    Selfdestruct {
+        address: Address,
+        prev: bool,
+        prev_balance: Word,
+    },
+    RefundChange {
+        prev: u64,
+    },
+    AccessListAddAccount {
+        address: Address,
+    },
+    AccessListAddSlot {
+        address: Address,
+        key: Word,
+    },
+}
+
+/// Identifier of a journal position which the state can be reverted to.
+pub(crate) type Snapshot = usize;
+
+/// State database used by the Rust tracer.
+#[derive(Debug, Default)]
+pub(crate) struct StateDb {
+    accounts: HashMap<Address, Account>,
+    /// Values of the storage slots before their first write in the current
+    /// transaction.
+    original_storage: HashMap<(Address, Word), Word>,
+    transient_storage: HashMap<(Address, Word), Word>,
+    access_list_accounts: HashSet<Address>,
+    access_list_slots: HashSet<(Address, Word)>,
+    refund: u64,
+    journal: Vec<JournalEntry>,
+}
+
+impl StateDb {
+    /// Create the state from the accounts of a trace config. Empty accounts
+    /// are dropped, like the state setup of `geth-utils` does when finalising
+    /// the state.
+    pub(crate) fn new(accounts: &HashMap<Address, GethAccount>) -> Self {
+        let mut state = Self::default();
+        for (address, account) in accounts {
+            state.accounts.insert(
+                *address,
+                Account {
+                    nonce: account.nonce.low_u64(),
+                    balance: account.balance,
+                    code: account.code.to_vec(),
+                    code_hash: H256(keccak256(&account.code)),
+                    storage: account.storage.clone(),
+                    ..Default::default()
+                },
+            );
+        }
+        state.finalise();
+        state
+    }
+
+    /// Prepare the state for a new transaction: reset the access list with
+    /// the warm accounts and slots, and clear the transient storage.
+    pub(crate) fn prepare(
+        &mut self,
+        warm_accounts: impl IntoIterator<Item = Address>,
+        warm_slots: impl IntoIterator<Item = (Address, Word)>,
+    ) {
+        self.access_list_accounts = warm_accounts.into_iter().collect();
+        self.access_list_slots = HashSet::new();
+        for (address, key) in warm_slots {
+            self.access_list_accounts.insert(address);
+            self.access_list_slots.insert((address, key));
+        }
+        self.transient_storage = HashMap::new();
+    }
+
+    /// Finalise the current transaction: remove the self-destructed and empty
+    /// accounts, and clear the journal and the refund counter.
+    pub(crate) fn finalise(&mut self) {
+        self.accounts
+            .retain(|_, account| !account.selfdestructed && !account.is_empty());
+        for account in self.accounts.values_mut() {
+            account.created = false;
+        }
+        self.original_storage.clear();
+        self.journal.clear();
+        self.refund = 0;
+    }
+
+    pub(crate) fn snapshot(&self) -> Snapshot {
+        self.journal.len()
+    }
+
+    pub(crate) fn revert_to_snapshot(&mut self, snapshot: Snapshot) {
+        while self.journal.len() > snapshot {
+            match self.journal.pop().unwrap() {
+                JournalEntry::CreateAccount { address, prev } => match prev {
+                    Some(prev) => {
+                        self.accounts.insert(address, prev);
+                    }
+                    None => {
+                        self.accounts.remove(&address);
+                    }
+                },
+                JournalEntry::BalanceChange { address, prev } => {
+                    self.account_mut(&address).balance = prev;
+                }
+                JournalEntry::NonceChange { address, prev } => {
+                    self.account_mut(&address).nonce = prev;
+                }
+                JournalEntry::CodeChange {
+                    address,
+                    prev_code,
+                    prev_hash,
+                } => {
+                    let account = self.account_mut(&address);
+                    account.code = prev_code;
+                    account.code_hash = prev_hash;
+                }
+                JournalEntry::StorageChange { address, key, prev } => {
+                    self.account_mut(&address).storage.insert(key, prev);
+                }
+                JournalEntry::TransientStorageChange { address, key, prev } => {
+                    self.transient_storage.insert((address, key), prev);
+                }
+                JournalEntry::Selfdestruct {
+                    address,
+                    prev,
+                    prev_balance,
+                } => {
+                    let account = self.account_mut(&address);
+                    account.selfdestructed = prev;
+                    account.balance = prev_balance;
+                }
+                JournalEntry::RefundChange { prev } => self.refund = prev,
+                JournalEntry::AccessListAddAccount { address } => {
+                    self.access_list_accounts.remove(&address);
+                }
+                JournalEntry::AccessListAddSlot { address, key } => {
+                    self.access_list_slots.remove(&(address, key));
+                }
+            }
+        }
+    }
+
+    fn account(&self, address: &Address) -> Option<&Account> {
+        self.accounts.get(address)
+    }
+
+    /// Returns the account which must exist, since it has a journal entry.
+    fn account_mut(&mut self, address: &Address) -> &mut Account {
+        self.accounts
+            .get_mut(address)
+            .expect("journaled account should exist")
+    }
+
+    /// Returns the account, creating it when it doesn't exist.
+    fn get_or_create(&mut self, address: &Address) -> &mut Account {
+        if !self.accounts.contains_key(address) {
+            self.journal.push(JournalEntry::CreateAccount {
+                address: *address,
+                prev: None,
+            });
+            self.accounts.insert(*address, Account::default());
+        }
+        self.account_mut(address)
+    }
+
+    /// Create a new account, keeping the balance of the account that may
+    /// already exist at the address.
+    pub(crate) fn create_account(&mut self, address: &Address) {
+        let prev = self.accounts.get(address).cloned();
+        let balance = prev
+            .as_ref()
+            .map(|account| account.balance)
+            .unwrap_or_default();
+        self.journal.push(JournalEntry::CreateAccount {
+            address: *address,
+            prev,
+        });
+        self.accounts.insert(
+            *address,
+            Account {
+                balance,
+                created: true,
+                ..Default::default()
+            },
+        );
+    }
+
+    pub(crate) fn exist(&self, address: &Address) -> bool {
+        self.accounts.contains_key(address)
+    }
+
+    /// Returns if the account doesn't exist or is empty as defined by
+    /// EIP-161.
+    pub(crate) fn empty(&self, address: &Address) -> bool {
+        self.account(address).map_or(true, Account::is_empty)
+    }
+
+    pub(crate) fn balance(&self, address: &Address) -> Word {
+        self.account(address)
+            .map(|account| account.balance)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn add_balance(&mut self, address: &Address, amount: Word) {
+        let account = self.get_or_create(address);
+        let prev = account.balance;
+        account.balance = prev.overflowing_add(amount).0;
+        self.journal.push(JournalEntry::BalanceChange {
+            address: *address,
+            prev,
+        });
+    }
+
+    pub(crate) fn sub_balance(&mut self, address: &Address, amount: Word) {
+        let account = self.get_or_create(address);
+        let prev = account.balance;
+        account.balance = prev.overflowing_sub(amount).0;
+        self.journal.push(JournalEntry::BalanceChange {
+            address: *address,
+            prev,
+        });
+    }
+
+    /// Move `value` from `from` to `to`.
+    pub(crate) fn transfer(&mut self, from: &Address, to: &Address, value: Word) {
+        self.sub_balance(from, value);
+        self.add_balance(to, value);
+    }
+
+    pub(crate) fn nonce(&self, address: &Address) -> u64 {
+        self.account(address)
+            .map(|account| account.nonce)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn set_nonce(&mut self, address: &Address, nonce: u64) {
+        let account = self.get_or_create(address);
+        let prev = account.nonce;
+        account.nonce = nonce;
+        self.journal.push(JournalEntry::NonceChange {
+            address: *address,
+            prev,
+        });
+    }
+
+    pub(crate) fn code(&self, address: &Address) -> &[u8] {
+        self.account(address)
+            .map(|account| account.code.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Returns the code executed when calling the account, which is the code
+    /// of its delegate if the account is delegated (EIP-7702). Only one level
+    /// of delegation is followed.
+    pub(crate) fn resolved_code(&self, address: &Address) -> &[u8] {
+        let code = self.code(address);
+        match parse_delegation(code) {
+            Some(delegate) => self.code(&delegate),
+            None => code,
+        }
+    }
+
+    /// Returns the code hash, or zero if the account doesn't exist.
+    pub(crate) fn code_hash(&self, address: &Address) -> H256 {
+        self.account(address)
+            .map(|account| account.code_hash)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn set_code(&mut self, address: &Address, code: Vec<u8>) {
+        let code_hash = H256(keccak256(&code));
+        let account = self.get_or_create(address);
+        let prev_code = std::mem::replace(&mut account.code, code);
+        let prev_hash = std::mem::replace(&mut account.code_hash, code_hash);
+        self.journal.push(JournalEntry::CodeChange {
+            address: *address,
+            prev_code,
+            prev_hash,
+        });
+    }
+
+    pub(crate) fn storage(&self, address: &Address, key: &Word) -> Word {
+        self.account(address)
+            .and_then(|account| account.storage.get(key).copied())
+            .unwrap_or_default()
+    }
+
+    /// Returns the value of the storage slot at the start of the current
+    /// transaction.
+    pub(crate) fn committed_storage(&self, address: &Address, key: &Word) -> Word {
+        match self.account(address) {
+            Some(account) if account.created => Word::zero(),
+            _ => self
+                .original_storage
+                .get(&(*address, *key))
+                .copied()
+                .unwrap_or_else(|| self.storage(address, key)),
+        }
+    }
+
+    pub(crate) fn set_storage(&mut self, address: &Address, key: Word, value: Word) {
+        let account = self.get_or_create(address);
+        let prev = account.storage.insert(key, value).unwrap_or_default();
+        self.original_storage.entry((*address, key)).or_insert(prev);
+        self.journal.push(JournalEntry::StorageChange {
+            address: *address,
+            key,
+            prev,
+        });
+    }
+
+    pub(crate) fn transient_storage(&self, address: &Address, key: &Word) -> Word {
+        self.transient_storage
+            .get(&(*address, *key))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn set_transient_storage(&mut self, address: &Address, key: Word, value: Word) {
+        let prev = self
+            .transient_storage
+            .insert((*address, key), value)
+            .unwrap_or_default();
+        self.journal.push(JournalEntry::TransientStorageChange {
+            address: *address,
+            key,
+            prev,
+        });
+    }
+
+    /// Mark the account as self-destructed and clear its balance.
+    pub(crate) fn selfdestruct(&mut self, address: &Address) {
+        if let Some(account) = self.accounts.get_mut(address) {
+            let prev = std::mem::replace(&mut account.selfdestructed, true);
+            let prev_balance = std::mem::take(&mut account.balance);
+            self.journal.push(JournalEntry::Selfdestruct {
+                address: *address,
+                prev,
+                prev_balance,
+            });
+        }
+    }
+
+    /// Self-destruct the account only if it was created in the current
+    /// transaction, as defined by EIP-6780.
+    pub(crate) fn selfdestruct_6780(&mut self, address: &Address) {
+        if self
+            .account(address)
+            .map_or(false, |account| account.created)
+        {
+            self.selfdestruct(address);
+        }
+    }
+
+    pub(crate) fn refund(&self) -> u64 {
+        self.refund
+    }
+
+    pub(crate) fn add_refund(&mut self, gas: u64) {
+        self.journal
+            .push(JournalEntry::RefundChange { prev: self.refund });
+        self.refund += gas;
+    }
+
+    pub(crate) fn sub_refund(&mut self, gas: u64) {
+        self.journal
+            .push(JournalEntry::RefundChange { prev: self.refund });
+        self.refund = self
+            .refund
+            .checked_sub(gas)
+            .expect("refund counter below zero");
+    }
+
+    pub(crate) fn address_in_access_list(&self, address: &Address) -> bool {
+        self.access_list_accounts.contains(address)
+    }
+
+    pub(crate) fn slot_in_access_list(&self, address: &Address, key: &Word) -> bool {
+        self.access_list_slots.contains(&(*address, *key))
+    }
+
+    pub(crate) fn add_address_to_access_list(&mut self, address: &Address) {
+        if self.access_list_accounts.insert(*address) {
+            self.journal
+                .push(JournalEntry::AccessListAddAccount { address: *address });
+        }
+    }
+
+    pub(crate) fn add_slot_to_access_list(&mut self, address: &Address, key: Word) {
+        self.add_address_to_access_list(address);
+        if self.access_list_slots.insert((*address, key)) {
+            self.journal.push(JournalEntry::AccessListAddSlot {
+                address: *address,
+                key,
+            });
+        }
+    }
+}
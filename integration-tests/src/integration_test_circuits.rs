@@ -5,7 +5,7 @@ use bus_mapping::{
     },
     mock::BlockData,
 };
-use eth_types::geth_types::GethData;
+use eth_types::{evm_types::ChainSpec, geth_types::GethData};
 use halo2_proofs::{
     dev::{CellValue, MockProver},
     halo2curves::bn256::{Bn256, Fr, G1Affine},
@@ -71,6 +71,10 @@ const MAX_EC_ADD: usize = 10;
 const MAX_EC_MUL: usize = 10;
 /// Max number of EcPairing ops.
 const MAX_EC_PAIRING: usize = 4;
+/// Max number of Miller loop chunks of EcPairing ops.
+const MAX_EC_PAIRING_CHUNKS: usize = 4;
+/// Max number of ECDSA signatures.
+const MAX_NUM_SIG: usize = 128;
 
 const CIRCUITS_PARAMS: CircuitsParams = CircuitsParams {
     max_rws: MAX_RWS,
@@ -88,7 +92,10 @@ const CIRCUITS_PARAMS: CircuitsParams = CircuitsParams {
         ec_add: MAX_EC_ADD,
         ec_mul: MAX_EC_MUL,
         ec_pairing: MAX_EC_PAIRING,
+        ec_pairing_chunks: MAX_EC_PAIRING_CHUNKS,
     },
+    max_num_sig: MAX_NUM_SIG,
+    chain_spec: ChainSpec::shanghai(),
 };
 
 const EVM_CIRCUIT_DEGREE: u32 = 18;
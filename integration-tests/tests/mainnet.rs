@@ -2,6 +2,7 @@ use bus_mapping::{
     circuit_input_builder::{keccak_inputs, BuilderClient, CircuitsParams, PrecompileEcParams},
     Error::JSONRpcError,
 };
+use eth_types::evm_types::ChainSpec;
 use halo2_proofs::{
     circuit::Value,
     dev::{MockProver, VerifyFailure},
@@ -38,7 +39,10 @@ const CIRCUITS_PARAMS: CircuitsParams = CircuitsParams {
         ec_add: 10,
         ec_mul: 10,
         ec_pairing: 4,
+        ec_pairing_chunks: 4,
     },
+    max_num_sig: 128,
+    chain_spec: ChainSpec::mainnet(),
 };
 
 #[tokio::test]
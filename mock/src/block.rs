@@ -35,6 +35,7 @@ pub struct MockBlock {
     // Also, the field is stored in the block_table since we don't have a chain_config
     // structure/table.
     pub(crate) chain_id: u64,
+    excess_blob_gas: U64,
 }
 
 impl Default for MockBlock {
@@ -63,6 +64,7 @@ impl Default for MockBlock {
             mix_hash: Hash::zero(),
             nonce: H64::zero(),
             chain_id: *MOCK_CHAIN_ID,
+            excess_blob_gas: U64::zero(),
         }
     }
 }
@@ -102,7 +104,7 @@ impl From<MockBlock> for Block<Transaction> {
             mix_hash: Some(mock.mix_hash),
             nonce: Some(mock.nonce),
             base_fee_per_gas: Some(mock.base_fee_per_gas),
-            other: OtherFields::default(),
+            other: mock.other_fields(),
         }
     }
 }
@@ -132,12 +134,21 @@ impl From<MockBlock> for Block<()> {
             mix_hash: Some(mock.mix_hash),
             nonce: Some(mock.nonce),
             base_fee_per_gas: Some(mock.base_fee_per_gas),
-            other: OtherFields::default(),
+            other: mock.other_fields(),
         }
     }
 }
 
 impl MockBlock {
+    /// The header fields which are not part of the ethers Block, encoded the
+    /// same way as in the JSON-RPC response.
+    fn other_fields(&self) -> OtherFields {
+        serde_json::from_value(serde_json::json!({
+            "excessBlobGas": self.excess_blob_gas,
+        }))
+        .expect("valid other fields")
+    }
+
     /// TODO: This should be computed based on the fields of the block by
     /// default unless `Some(hash)` is specified on build process.
     pub fn hash(&mut self, hash: Hash) -> &mut Self {
@@ -280,6 +291,12 @@ impl MockBlock {
         self
     }
 
+    /// Set excess_blob_gas field for the MockBlock.
+    pub fn excess_blob_gas(&mut self, excess_blob_gas: u64) -> &mut Self {
+        self.excess_blob_gas = U64::from(excess_blob_gas);
+        self
+    }
+
     /// Finalizes the current MockBlock under construction returning a new
     /// instance to it.
     pub fn build(&mut self) -> Self {
@@ -254,7 +254,9 @@ pub fn gen_geth_traces(
             .map(eth_types::geth_types::Transaction::from)
             .collect(),
         logger_config,
-        #[cfg(feature = "shanghai")]
+        #[cfg(feature = "cancun")]
+        chain_config: Some(external_tracer::ChainConfig::cancun()),
+        #[cfg(all(feature = "shanghai", not(feature = "cancun")))]
         chain_config: Some(external_tracer::ChainConfig::shanghai()),
         #[cfg(not(feature = "shanghai"))]
         chain_config: None,
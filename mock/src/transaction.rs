@@ -2,12 +2,16 @@
 
 use super::{MOCK_ACCOUNTS, MOCK_CHAIN_ID, MOCK_GASPRICE};
 use eth_types::{
-    geth_types::Transaction as GethTransaction, word, AccessList, Address, Bytes, Hash,
-    Transaction, Word, U64,
+    geth_types::{
+        get_rlp_signed, get_rlp_unsigned, SetCodeAuthorization, Transaction as GethTransaction,
+        EIP1559_TX_TYPE, EIP2930_TX_TYPE, EIP4844_TX_TYPE, EIP7702_TX_TYPE,
+    },
+    word, AccessList, Address, Bytes, Hash, Transaction, Word, U64,
 };
 use ethers_core::{
     rand::{CryptoRng, RngCore},
-    types::{OtherFields, TransactionRequest},
+    types::TransactionRequest,
+    utils::keccak256,
 };
 use ethers_signers::{LocalWallet, Signer};
 use lazy_static::lazy_static;
@@ -161,6 +165,9 @@ pub struct MockTransaction {
     pub max_priority_fee_per_gas: Word,
     pub max_fee_per_gas: Word,
     pub chain_id: u64,
+    pub max_fee_per_blob_gas: Word,
+    pub blob_versioned_hashes: Vec<Hash>,
+    pub authorization_list: Vec<SetCodeAuthorization>,
 }
 
 impl Default for MockTransaction {
@@ -186,6 +193,9 @@ impl Default for MockTransaction {
             max_priority_fee_per_gas: Word::zero(),
             max_fee_per_gas: Word::zero(),
             chain_id: *MOCK_CHAIN_ID,
+            max_fee_per_blob_gas: Word::zero(),
+            blob_versioned_hashes: Vec::new(),
+            authorization_list: Vec::new(),
         }
     }
 }
@@ -212,7 +222,12 @@ impl From<MockTransaction> for Transaction {
             max_priority_fee_per_gas: Some(mock.max_priority_fee_per_gas),
             max_fee_per_gas: Some(mock.max_fee_per_gas),
             chain_id: Some(mock.chain_id.into()),
-            other: OtherFields::default(),
+            other: serde_json::from_value(serde_json::json!({
+                "maxFeePerBlobGas": mock.max_fee_per_blob_gas,
+                "blobVersionedHashes": mock.blob_versioned_hashes,
+                "authorizationList": mock.authorization_list,
+            }))
+            .expect("valid other fields"),
         }
     }
 }
@@ -311,6 +326,27 @@ impl MockTransaction {
         self
     }
 
+    /// Set max_fee_per_blob_gas field for the MockTransaction.
+    pub fn max_fee_per_blob_gas(&mut self, max_fee_per_blob_gas: Word) -> &mut Self {
+        self.max_fee_per_blob_gas = max_fee_per_blob_gas;
+        self
+    }
+
+    /// Set blob_versioned_hashes field for the MockTransaction.
+    pub fn blob_versioned_hashes(&mut self, blob_versioned_hashes: Vec<Hash>) -> &mut Self {
+        self.blob_versioned_hashes = blob_versioned_hashes;
+        self
+    }
+
+    /// Set authorization_list field for the MockTransaction.
+    pub fn authorization_list(
+        &mut self,
+        authorization_list: Vec<SetCodeAuthorization>,
+    ) -> &mut Self {
+        self.authorization_list = authorization_list;
+        self
+    }
+
     /// Set max_priority_fee_per_gas field for the MockTransaction.
     pub fn max_priority_fee_per_gas(&mut self, max_priority_fee_per_gas: Word) -> &mut Self {
         self.max_priority_fee_per_gas = max_priority_fee_per_gas;
@@ -346,11 +382,26 @@ impl MockTransaction {
             (None, None, None) => {
                 // Compute sig params and set them in case we have a wallet as `from` attr.
                 if self.from.is_wallet() && self.hash.is_none() {
-                    let sig = self
-                        .from
-                        .as_wallet()
-                        .with_chain_id(self.chain_id)
-                        .sign_transaction_sync(&tx.into());
+                    let wallet = self.from.as_wallet().with_chain_id(self.chain_id);
+                    let sig = if [
+                        EIP2930_TX_TYPE,
+                        EIP1559_TX_TYPE,
+                        EIP4844_TX_TYPE,
+                        EIP7702_TX_TYPE,
+                    ]
+                    .map(U64::from)
+                    .contains(&self.transaction_type)
+                    {
+                        // typed txs are signed over the hash of their unsigned encoding
+                        // with v being the recovery id
+                        let tmp_tx = Transaction::from(self.to_owned());
+                        let sighash = keccak256(get_rlp_unsigned(&tmp_tx));
+                        let mut sig = wallet.sign_hash(sighash.into());
+                        sig.v -= 27;
+                        sig
+                    } else {
+                        wallet.sign_transaction_sync(&tx.into())
+                    };
                     // Set sig parameters
                     self.sig_data((sig.v, sig.r, sig.s));
                 }
@@ -364,7 +415,7 @@ impl MockTransaction {
             let tmp_tx = Transaction::from(self.to_owned());
             // FIXME: Note that tmp_tx does not have sigs if self.from.is_wallet() = false.
             //  This means tmp_tx.hash() is not correct.
-            self.hash(tmp_tx.hash());
+            self.hash(keccak256(get_rlp_signed(&tmp_tx)).into());
         }
 
         self.to_owned()
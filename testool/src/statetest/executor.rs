@@ -4,7 +4,9 @@ use bus_mapping::{
     circuit_input_builder::{CircuitInputBuilder, CircuitsParams, PrecompileEcParams},
     mock::BlockData,
 };
-use eth_types::{geth_types, geth_types::TxType, Address, Bytes, GethExecTrace, U256, U64};
+use eth_types::{
+    evm_types::ChainSpec, geth_types, geth_types::TxType, Address, Bytes, GethExecTrace, U256, U64,
+};
 use ethers_core::{
     k256::ecdsa::SigningKey,
     types::{transaction::eip2718::TypedTransaction, TransactionRequest},
@@ -175,6 +177,7 @@ fn into_traceconfig(st: StateTest) -> (String, TraceConfig, StateTestResult) {
                 difficulty: st.env.current_difficulty,
                 gas_limit: U256::from(st.env.current_gas_limit),
                 base_fee: st.env.current_base_fee,
+                excess_blob_gas: U64::zero(),
             },
 
             transactions: vec![geth_types::Transaction {
@@ -189,6 +192,7 @@ fn into_traceconfig(st: StateTest) -> (String, TraceConfig, StateTestResult) {
                 gas_tip_cap: U256::zero(),
                 call_data: st.data,
                 access_list: None,
+                blob_versioned_hashes: vec![],
                 v: sig.v,
                 r: sig.r,
                 s: sig.s,
@@ -201,7 +205,9 @@ fn into_traceconfig(st: StateTest) -> (String, TraceConfig, StateTestResult) {
                 enable_memory: *bus_mapping::util::CHECK_MEM_STRICT,
                 ..Default::default()
             },
-            #[cfg(feature = "shanghai")]
+            #[cfg(feature = "cancun")]
+            chain_config: Some(external_tracer::ChainConfig::cancun()),
+            #[cfg(all(feature = "shanghai", not(feature = "cancun")))]
             chain_config: Some(external_tracer::ChainConfig::shanghai()),
             #[cfg(not(feature = "shanghai"))]
             chain_config: None,
@@ -336,7 +342,10 @@ pub fn run_test(
                 ec_add: 50,
                 ec_mul: 50,
                 ec_pairing: 2,
+                ec_pairing_chunks: 2,
             },
+            max_num_sig: 128,
+            chain_spec: ChainSpec::default(),
         };
         let block_data = BlockData::new_from_geth_data_with_params(geth_data, circuits_params);
 
@@ -371,7 +380,10 @@ pub fn run_test(
                 ec_add: 50,
                 ec_mul: 50,
                 ec_pairing: 2,
+                ec_pairing_chunks: 2,
             },
+            max_num_sig: 128,
+            chain_spec: ChainSpec::default(),
         };
         let (k, circuit, instance, _builder) =
             SuperCircuit::<Fr, MAX_TXS, MAX_CALLDATA, 64, 0x100>::build(geth_data, circuits_params)
@@ -8,6 +8,7 @@ use std::process::{Command, Stdio};
 
 #[derive(Debug, Eq, PartialEq, PartialOrd)]
 pub enum MainnetFork {
+    Cancun = 16,
     Shanghai = 15,
     Merge = 14,
     GrayGlacier = 13,
@@ -25,7 +26,9 @@ pub enum MainnetFork {
     Frontier = 1,
 }
 
-#[cfg(feature = "shanghai")]
+#[cfg(feature = "cancun")]
+pub const TEST_FORK: MainnetFork = MainnetFork::Cancun;
+#[cfg(all(feature = "shanghai", not(feature = "cancun")))]
 pub const TEST_FORK: MainnetFork = MainnetFork::Shanghai;
 #[cfg(not(feature = "shanghai"))]
 pub const TEST_FORK: MainnetFork = MainnetFork::Merge;
@@ -35,6 +38,7 @@ impl FromStr for MainnetFork {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(match s {
+            "Cancun" => Self::Cancun,
             "Shanghai" => Self::Shanghai,
             "Merge" => Self::Merge,
             "Gray Glacier" => Self::GrayGlacier,
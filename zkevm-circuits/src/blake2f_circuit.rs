@@ -0,0 +1,788 @@
+//! The BLAKE2 compression function F circuit implementation, see EIP-152.
+//!
+//! Each call takes `rounds + 2` units of `NUM_ROWS_PER_UNIT` rows:
+//! - 1 unit loading the input, one word per row, and initializing the state
+//! - 1 unit per round, where each row does one step of the mixing function G
+//! - 1 unit computing the output words `h[i] ^ v[i] ^ v[i + 8]`
+//!
+//! All words are decomposed into bits, so no lookup tables are needed.
+/// BLAKE2F witness generation
+pub mod blake2f_bit;
+mod param;
+mod util;
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+mod dev;
+#[cfg(any(feature = "test", test))]
+mod test;
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+pub use dev::Blake2fCircuit as TestBlake2fCircuit;
+
+use std::marker::PhantomData;
+
+use self::{
+    blake2f_bit::{get_num_units, multi_blake2f, Blake2fRow},
+    param::*,
+    util::*,
+};
+use crate::{
+    evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    table::{Blake2fTable, LookupTable},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use eth_types::Field;
+use gadgets::util::{not, sum, Expr};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, VirtualCells},
+    poly::Rotation,
+};
+
+/// Blake2fCircuitConfig
+#[derive(Clone, Debug)]
+pub struct Blake2fCircuitConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    q_rows: [Column<Fixed>; NUM_ROWS_PER_UNIT],
+    is_init: Column<Advice>,
+    is_round: Column<Advice>,
+    is_fin: Column<Advice>,
+    round_flags: [Column<Advice>; NUM_SIGMAS],
+    rounds_left: Column<Advice>,
+    word_sum: [Column<Advice>; NUM_BITS_PER_WORD_EXT],
+    word_r: [Column<Advice>; NUM_BITS_PER_WORD],
+    r_new: Column<Advice>,
+    v: [Column<Advice>; NUM_STATE_WORDS],
+    h: [Column<Advice>; NUM_HASH_WORDS],
+    m: [Column<Advice>; NUM_MESSAGE_WORDS],
+    /// The columns for other circuits to lookup BLAKE2F results
+    pub blake2f_table: Blake2fTable,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+#[derive(Debug, Clone)]
+pub struct Blake2fCircuitConfigArgs<F: Field> {
+    /// Blake2fTable
+    pub blake2f_table: Blake2fTable,
+    /// Challenges randomness
+    pub challenges: Challenges<Expression<F>>,
+}
+
+fn query_bits<F: Field>(
+    meta: &mut VirtualCells<'_, F>,
+    columns: &[Column<Advice>],
+    rot: i32,
+) -> Vec<Expression<F>> {
+    columns
+        .iter()
+        .map(|column| meta.query_advice(*column, Rotation(rot)))
+        .collect()
+}
+
+/// Returns the sum of the row selectors of the rows matching `predicate`
+fn select_rows<F: Field>(
+    q_rows: &[Expression<F>],
+    predicate: impl Fn(usize) -> bool,
+) -> Expression<F> {
+    sum::expr(
+        q_rows
+            .iter()
+            .enumerate()
+            .filter(|(row, _)| predicate(*row))
+            .map(|(_, q_row)| q_row.expr()),
+    )
+}
+
+/// Accumulates the RLC of `bytes` on top of `acc`
+fn rlc_acc<F: Field>(
+    acc: Expression<F>,
+    bytes: &[Expression<F>],
+    randomness: Expression<F>,
+) -> Expression<F> {
+    bytes
+        .iter()
+        .fold(acc, |acc, byte| acc * randomness.expr() + byte.expr())
+}
+
+impl<F: Field> SubCircuitConfig<F> for Blake2fCircuitConfig<F> {
+    type ConfigArgs = Blake2fCircuitConfigArgs<F>;
+
+    /// Return a new Blake2fCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            blake2f_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = blake2f_table.q_enable;
+        let q_first = meta.fixed_column();
+        let q_rows = array_init::array_init(|_| meta.fixed_column());
+
+        let is_final = blake2f_table.is_final;
+        let input_rlc = blake2f_table.input_rlc;
+        let rounds = blake2f_table.rounds;
+        let output_rlc = blake2f_table.output_rlc;
+
+        let is_init = meta.advice_column();
+        let is_round = meta.advice_column();
+        let is_fin = meta.advice_column();
+        let round_flags = array_init::array_init(|_| meta.advice_column());
+        let rounds_left = meta.advice_column();
+        let word_sum = array_init::array_init(|_| meta.advice_column());
+        let word_r = array_init::array_init(|_| meta.advice_column());
+        let r_new = meta.advice_column();
+        let v = array_init::array_init(|_| meta.advice_column());
+        let h = array_init::array_init(|_| meta.advice_column());
+        let m = array_init::array_init(|_| meta.advice_column());
+
+        let unit_columns = [is_init, is_round, is_fin, rounds_left]
+            .into_iter()
+            .chain(round_flags)
+            .collect::<Vec<_>>();
+        let call_columns = [rounds].into_iter().chain(h).chain(m).collect::<Vec<_>>();
+
+        meta.create_gate("boolean checks", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in word_sum
+                .iter()
+                .chain(word_r.iter())
+                .chain(round_flags.iter())
+                .chain([is_init, is_round, is_fin].iter())
+            {
+                cb.require_boolean(
+                    "bit is boolean",
+                    meta.query_advice(*column, Rotation::cur()),
+                );
+            }
+            cb.require_boolean(
+                "a row is in at most one kind of unit",
+                sum::expr(
+                    [is_init, is_round, is_fin]
+                        .map(|column| meta.query_advice(column, Rotation::cur())),
+                ),
+            );
+            cb.require_equal(
+                "is_final is only set on the last row of the output unit",
+                meta.query_advice(is_final, Rotation::cur()),
+                meta.query_advice(is_fin, Rotation::cur())
+                    * meta.query_fixed(q_rows[ROW_LAST], Rotation::cur()),
+            );
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("carry over", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_rows = q_rows.map(|column| meta.query_fixed(column, Rotation::cur()));
+            let [is_init, is_round, is_fin] = [is_init, is_round, is_fin]
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            cb.condition(not::expr(q_rows[0].expr()), |cb| {
+                for column in unit_columns.iter() {
+                    cb.require_equal(
+                        "value is the same on all rows of a unit",
+                        meta.query_advice(*column, Rotation::cur()),
+                        meta.query_advice(*column, Rotation::prev()),
+                    );
+                }
+            });
+            cb.condition(
+                is_round.expr() + is_fin.expr() + is_init.expr() * not::expr(q_rows[0].expr()),
+                |cb| {
+                    for column in call_columns.iter() {
+                        cb.require_equal(
+                            "value is the same on all rows of a call",
+                            meta.query_advice(*column, Rotation::cur()),
+                            meta.query_advice(*column, Rotation::prev()),
+                        );
+                    }
+                },
+            );
+            cb.condition(
+                is_round.expr() + is_fin + is_init * sum::expr(&q_rows[ROW_F + 1..]),
+                |cb| {
+                    cb.require_equal(
+                        "input_rlc is the same after the input",
+                        meta.query_advice(input_rlc, Rotation::cur()),
+                        meta.query_advice(input_rlc, Rotation::prev()),
+                    );
+                },
+            );
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("unit transition", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_first = meta.query_fixed(q_first, Rotation::cur());
+            let not_first = not::expr(q_first.expr());
+            let [is_round, is_fin] =
+                [is_round, is_fin].map(|column| meta.query_advice(column, Rotation::cur()));
+            let [prev_is_init, prev_is_round] =
+                [is_init, is_round].map(|column| meta.query_advice(column, Rotation::prev()));
+            let prev_is_call = prev_is_init.expr() + prev_is_round.expr();
+            let prev_rounds_left = meta.query_advice(rounds_left, Rotation::prev());
+            let prev_round_flags =
+                round_flags.map(|column| meta.query_advice(column, Rotation::prev()));
+            let round_flags = round_flags.map(|column| meta.query_advice(column, Rotation::cur()));
+
+            cb.condition(q_first, |cb| {
+                cb.require_zero(
+                    "the first unit is not a round or an output unit",
+                    is_round.expr() + is_fin.expr(),
+                );
+            });
+            cb.require_zero(
+                "a call continues until its output unit",
+                not_first.expr() * prev_is_call.expr() * not::expr(is_round.expr() + is_fin.expr()),
+            );
+            cb.require_zero(
+                "a round or an output unit continues a call",
+                not_first.expr() * (is_round.expr() + is_fin.expr()) * not::expr(prev_is_call),
+            );
+            cb.condition(not_first.expr() * is_round.expr(), |cb| {
+                cb.require_equal(
+                    "rounds_left is decreased by each round",
+                    meta.query_advice(rounds_left, Rotation::cur()),
+                    prev_rounds_left.expr() - 1.expr(),
+                );
+            });
+            cb.condition(not_first.expr() * is_round.expr() * prev_is_init, |cb| {
+                for (idx, round_flag) in round_flags.iter().enumerate() {
+                    cb.require_equal(
+                        "the first round uses the first permutation",
+                        round_flag.expr(),
+                        (idx == 0).expr(),
+                    );
+                }
+            });
+            cb.condition(not_first.expr() * is_round * prev_is_round, |cb| {
+                for (idx, round_flag) in round_flags.iter().enumerate() {
+                    cb.require_equal(
+                        "each round uses the next permutation",
+                        round_flag.expr(),
+                        prev_round_flags[(idx + NUM_SIGMAS - 1) % NUM_SIGMAS].expr(),
+                    );
+                }
+            });
+            cb.condition(not_first * is_fin, |cb| {
+                cb.require_zero("all rounds are done before the output", prev_rounds_left);
+            });
+            cb.gate(meta.query_fixed(q_rows[0], Rotation::cur()))
+        });
+
+        meta.create_gate("input", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_rows = q_rows.map(|column| meta.query_fixed(column, Rotation::cur()));
+            let word_bits = query_bits(meta, &word_sum, 0);
+            let word = decode::expr(&word_bits);
+            let bytes = to_le_bytes(&word_bits);
+            let prev_input_rlc = meta.query_advice(input_rlc, Rotation::prev());
+            let input_rlc = meta.query_advice(input_rlc, Rotation::cur());
+            // v[12], v[13] and v[14] are computed on the rows of t and f
+            let initial_words = (ROW_T..=ROW_F)
+                .map(|row| meta.query_advice(r_new, Rotation(row as i32 - ROW_LAST as i32)))
+                .collect::<Vec<_>>();
+            let r_new = meta.query_advice(r_new, Rotation::cur());
+            cb.require_zero(
+                "input words have no carry",
+                decode::expr(&word_bits[NUM_BITS_PER_WORD..]),
+            );
+
+            cb.condition(q_rows[ROW_ROUNDS].expr(), |cb| {
+                cb.require_zero(
+                    "rounds has 4 bytes",
+                    decode::expr(&word_bits[NUM_BYTES_ROUNDS * NUM_BITS_PER_BYTE..]),
+                );
+                cb.require_equal(
+                    "rounds is the first input word",
+                    meta.query_advice(rounds, Rotation::cur()),
+                    word.expr(),
+                );
+                cb.require_equal(
+                    "rounds_left starts at rounds",
+                    meta.query_advice(rounds_left, Rotation::cur()),
+                    word.expr(),
+                );
+                cb.require_equal(
+                    "input_rlc starts with rounds in big-endian",
+                    input_rlc.expr(),
+                    rlc_acc(
+                        0.expr(),
+                        &bytes[..NUM_BYTES_ROUNDS]
+                            .iter()
+                            .rev()
+                            .cloned()
+                            .collect::<Vec<_>>(),
+                        challenges.keccak_input(),
+                    ),
+                );
+            });
+            for (idx, column) in h.iter().chain(m.iter()).enumerate() {
+                cb.condition(q_rows[ROW_H + idx].expr(), |cb| {
+                    cb.require_equal(
+                        "h and m are loaded from the input words",
+                        meta.query_advice(*column, Rotation::cur()),
+                        word.expr(),
+                    );
+                });
+            }
+            cb.condition(sum::expr(&q_rows[ROW_H..ROW_F]), |cb| {
+                cb.require_equal(
+                    "input_rlc is accumulated over the little-endian word bytes",
+                    input_rlc.expr(),
+                    rlc_acc(prev_input_rlc.expr(), &bytes, challenges.keccak_input()),
+                );
+            });
+            for (idx, iv) in IV[4..6].iter().enumerate() {
+                cb.condition(q_rows[ROW_T + idx].expr(), |cb| {
+                    cb.require_equal(
+                        "v[12 + i] = IV[4 + i] ^ t[i]",
+                        r_new.expr(),
+                        decode::expr(&xor::expr_const(&word_bits, *iv)),
+                    );
+                });
+            }
+            cb.condition(q_rows[ROW_F].expr(), |cb| {
+                cb.require_zero(
+                    "the final block flag is 0 or 1",
+                    decode::expr(&word_bits[1..]),
+                );
+                cb.require_equal(
+                    "v[14] = f ? !IV[6] : IV[6]",
+                    r_new.expr(),
+                    IV[6].expr() + word.expr() * (u64::MAX - 2 * IV[6]).expr(),
+                );
+                cb.require_equal(
+                    "input_rlc is accumulated over the final block flag",
+                    input_rlc.expr(),
+                    prev_input_rlc * challenges.keccak_input() + word.expr(),
+                );
+            });
+
+            cb.condition(q_rows[ROW_LAST].expr(), |cb| {
+                let mut initial_state = h
+                    .iter()
+                    .map(|column| meta.query_advice(*column, Rotation::cur()))
+                    .collect::<Vec<_>>();
+                initial_state.extend(IV[..4].iter().map(|iv| iv.expr()));
+                initial_state.extend(initial_words);
+                initial_state.push(IV[7].expr());
+                for (column, state) in v.iter().zip(initial_state) {
+                    cb.require_equal(
+                        "state is initialized from h, IV, t and f",
+                        meta.query_advice(*column, Rotation::cur()),
+                        state,
+                    );
+                }
+            });
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    * meta.query_advice(is_init, Rotation::cur()),
+            )
+        });
+
+        meta.create_gate("round", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_rows = q_rows.map(|column| meta.query_fixed(column, Rotation::cur()));
+            let sum_bits = query_bits(meta, &word_sum, 0);
+            let r_bits = query_bits(meta, &word_r, 0);
+            let r_new = meta.query_advice(r_new, Rotation::cur());
+            let prev_v = v.map(|column| meta.query_advice(column, Rotation::prev()));
+            let m = m.map(|column| meta.query_advice(column, Rotation::cur()));
+            let round_flags = round_flags.map(|column| meta.query_advice(column, Rotation::cur()));
+
+            // The selectors of the state words used on the row
+            let is_p = (0..NUM_STATE_WORDS)
+                .map(|word| select_rows(&q_rows, |row| step(row).0 == word))
+                .collect::<Vec<_>>();
+            let is_q = (0..NUM_STATE_WORDS)
+                .map(|word| select_rows(&q_rows, |row| step(row).1 == word))
+                .collect::<Vec<_>>();
+            let is_r = (0..NUM_STATE_WORDS)
+                .map(|word| select_rows(&q_rows, |row| step(row).2 == word))
+                .collect::<Vec<_>>();
+            let select_word = |is_word: &[Expression<F>]| {
+                sum::expr(
+                    is_word
+                        .iter()
+                        .zip(prev_v.iter())
+                        .map(|(is_word, v)| is_word.expr() * v.expr()),
+                )
+            };
+
+            // The message word of the slot, permuted by the sigma of the round
+            let message = sum::expr(round_flags.iter().zip(SIGMA.iter()).map(
+                |(round_flag, sigma)| {
+                    round_flag.expr()
+                        * sum::expr(m.iter().enumerate().map(|(word, m)| {
+                            select_rows(&q_rows, |row| {
+                                step(row).3.map(|slot| sigma[slot]) == Some(word)
+                            }) * m.expr()
+                        }))
+                },
+            ));
+            cb.require_equal(
+                "sum = p + q + m",
+                decode::expr(&sum_bits),
+                select_word(&is_p) + select_word(&is_q) + message,
+            );
+            cb.require_equal(
+                "r is the state word",
+                decode::expr(&r_bits),
+                select_word(&is_r),
+            );
+
+            let xor_bits = xor::expr(&sum_bits, &r_bits);
+            cb.require_equal(
+                "r_new = (r ^ p) >>> k",
+                r_new.expr(),
+                sum::expr(ROTATIONS.iter().map(|rotation| {
+                    select_rows(&q_rows, |row| step(row).4 == *rotation)
+                        * decode::expr(&rotate::expr(&xor_bits, *rotation))
+                })),
+            );
+
+            let p_new = decode::expr(&sum_bits[..NUM_BITS_PER_WORD]);
+            for (idx, column) in v.iter().enumerate() {
+                cb.require_equal(
+                    "p and r are updated in the state",
+                    meta.query_advice(*column, Rotation::cur()),
+                    prev_v[idx].expr()
+                        + is_p[idx].expr() * (p_new.expr() - prev_v[idx].expr())
+                        + is_r[idx].expr() * (r_new.expr() - prev_v[idx].expr()),
+                );
+            }
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    * meta.query_advice(is_round, Rotation::cur()),
+            )
+        });
+
+        meta.create_gate("output", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_rows = q_rows.map(|column| meta.query_fixed(column, Rotation::cur()));
+            let sum_bits = query_bits(meta, &word_sum, 0);
+            let r_bits = query_bits(meta, &word_r, 0);
+            let prev_r_new = meta.query_advice(r_new, Rotation::prev());
+            let r_new = meta.query_advice(r_new, Rotation::cur());
+            let prev_output_rlc = meta.query_advice(output_rlc, Rotation::prev());
+            let output_rlc = meta.query_advice(output_rlc, Rotation::cur());
+            let xor_bits = xor::expr(&sum_bits, &r_bits);
+
+            for column in v {
+                cb.require_equal(
+                    "state is the same on all rows of the output unit",
+                    meta.query_advice(column, Rotation::cur()),
+                    meta.query_advice(column, Rotation::prev()),
+                );
+            }
+            for idx in 0..NUM_HASH_WORDS {
+                cb.condition(q_rows[2 * idx].expr(), |cb| {
+                    cb.require_equal(
+                        "the first operand is h[i]",
+                        decode::expr(&sum_bits),
+                        meta.query_advice(h[idx], Rotation::cur()),
+                    );
+                    cb.require_equal(
+                        "the second operand is v[i]",
+                        decode::expr(&r_bits),
+                        meta.query_advice(v[idx], Rotation::cur()),
+                    );
+                });
+                cb.condition(q_rows[2 * idx + 1].expr(), |cb| {
+                    cb.require_equal(
+                        "the first operand is h[i] ^ v[i]",
+                        decode::expr(&sum_bits),
+                        prev_r_new.expr(),
+                    );
+                    cb.require_equal(
+                        "the second operand is v[i + 8]",
+                        decode::expr(&r_bits),
+                        meta.query_advice(v[idx + NUM_HASH_WORDS], Rotation::cur()),
+                    );
+                    cb.require_equal(
+                        "output_rlc is accumulated over the little-endian output bytes",
+                        output_rlc.expr(),
+                        rlc_acc(
+                            prev_output_rlc.expr(),
+                            &to_le_bytes(&xor_bits),
+                            challenges.keccak_input(),
+                        ),
+                    );
+                });
+            }
+            cb.condition(sum::expr(&q_rows[..2 * NUM_HASH_WORDS]), |cb| {
+                cb.require_equal(
+                    "r_new is the xor of the operands",
+                    r_new,
+                    decode::expr(&xor_bits),
+                );
+            });
+            cb.condition(q_rows[0].expr(), |cb| {
+                cb.require_zero("output_rlc starts at zero", output_rlc.expr());
+            });
+            cb.condition(
+                select_rows(&q_rows, |row| {
+                    row > 0 && (row % 2 == 0 || row >= 2 * NUM_HASH_WORDS)
+                }),
+                |cb| {
+                    cb.require_equal(
+                        "output_rlc is carried over",
+                        output_rlc.expr(),
+                        prev_output_rlc.expr(),
+                    );
+                },
+            );
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    * meta.query_advice(is_fin, Rotation::cur()),
+            )
+        });
+
+        Blake2fCircuitConfig {
+            q_enable,
+            q_first,
+            q_rows,
+            is_init,
+            is_round,
+            is_fin,
+            round_flags,
+            rounds_left,
+            word_sum,
+            word_r,
+            r_new,
+            v,
+            h,
+            m,
+            blake2f_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> Blake2fCircuitConfig<F> {
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        witness: &[Blake2fRow<F>],
+    ) -> Result<(), Error> {
+        let mut is_first_time = true;
+        layouter.assign_region(
+            || "assign blake2f rows",
+            |mut region| {
+                if is_first_time {
+                    is_first_time = false;
+                    if let Some(row) = witness.last() {
+                        self.set_row(&mut region, witness.len() - 1, row)?;
+                    }
+                    return Ok(());
+                }
+                for (offset, blake2f_row) in witness.iter().enumerate() {
+                    self.set_row(&mut region, offset, blake2f_row)?;
+                }
+                self.blake2f_table.annotate_columns_in_region(&mut region);
+                self.annotate_circuit(&mut region);
+                Ok(())
+            },
+        )
+    }
+
+    /// Set the cells for a blake2f row; return the cells that are assigned.
+    pub fn set_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &Blake2fRow<F>,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let idx = offset % NUM_ROWS_PER_UNIT;
+
+        // Fixed selectors
+        for (name, column, value) in &[
+            ("q_enable", self.q_enable, true),
+            ("q_first", self.q_first, offset == 0),
+        ] {
+            region.assign_fixed(
+                || format!("assign {name} {offset}"),
+                *column,
+                offset,
+                || Value::known(F::from(*value)),
+            )?;
+        }
+        for (row_idx, column) in self.q_rows.iter().enumerate() {
+            region.assign_fixed(
+                || format!("assign q_row {row_idx} {offset}"),
+                *column,
+                offset,
+                || Value::known(F::from(row_idx == idx)),
+            )?;
+        }
+
+        // table values
+        let mut res = self.blake2f_table.assign_row(
+            region,
+            offset,
+            [
+                Value::known(F::from(row.is_final)),
+                row.input_rlc,
+                Value::known(F::from(row.rounds)),
+                row.output_rlc,
+            ],
+        )?;
+
+        // Unit flags and words
+        for (name, column, value) in [
+            ("is_init", self.is_init, row.is_init as u64),
+            ("is_round", self.is_round, row.is_round as u64),
+            ("is_fin", self.is_fin, row.is_fin as u64),
+            ("rounds_left", self.rounds_left, row.rounds_left),
+            ("r_new", self.r_new, row.r_new),
+        ]
+        .into_iter()
+        .chain(
+            self.round_flags
+                .iter()
+                .zip(row.round_flags.iter())
+                .map(|(column, flag)| ("round_flag", *column, *flag as u64)),
+        )
+        .chain(
+            self.v
+                .iter()
+                .zip(row.v.iter())
+                .map(|(column, word)| ("v", *column, *word)),
+        )
+        .chain(
+            self.h
+                .iter()
+                .zip(row.h.iter())
+                .map(|(column, word)| ("h", *column, *word)),
+        )
+        .chain(
+            self.m
+                .iter()
+                .zip(row.m.iter())
+                .map(|(column, word)| ("m", *column, *word)),
+        ) {
+            res.push(region.assign_advice(
+                || format!("assign {name} {offset}"),
+                column,
+                offset,
+                || Value::known(F::from(value)),
+            )?);
+        }
+
+        // Bits of the words
+        for (name, columns, bits) in [
+            ("sum", self.word_sum.as_slice(), row.sum.as_slice()),
+            ("r", self.word_r.as_slice(), row.r.as_slice()),
+        ] {
+            for (idx, (column, bit)) in columns.iter().zip(bits.iter()).enumerate() {
+                res.push(region.assign_advice(
+                    || format!("assign {name} bit {idx} {offset}"),
+                    *column,
+                    offset,
+                    || Value::known(F::from(*bit as u64)),
+                )?);
+            }
+        }
+
+        Ok(res)
+    }
+
+    /// Annotate the circuit
+    pub fn annotate_circuit(&self, region: &mut Region<F>) {
+        region.name_column(|| "BLAKE2F_q_first", self.q_first);
+        region.name_column(|| "BLAKE2F_is_init", self.is_init);
+        region.name_column(|| "BLAKE2F_is_round", self.is_round);
+        region.name_column(|| "BLAKE2F_is_fin", self.is_fin);
+        region.name_column(|| "BLAKE2F_rounds_left", self.rounds_left);
+        region.name_column(|| "BLAKE2F_r_new", self.r_new);
+    }
+}
+
+/// Blake2fCircuit
+#[derive(Default, Clone, Debug)]
+pub struct Blake2fCircuit<F: Field> {
+    // The 213 bytes input of each call
+    inputs: Vec<Vec<u8>>,
+    // The maximum number of rows, for example, 2^20
+    // This needs to be large enough for the circuit.
+    num_rows: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> SubCircuit<F> for Blake2fCircuit<F> {
+    type Config = Blake2fCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // Column r_new is queried at 5 distinct rotations, so returns 8
+        // unusable rows.
+        8
+    }
+
+    /// The blake2f circuit shares the row budget of the keccak circuit, see
+    /// `block.circuits_params.max_keccak_rows`.
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self::new(
+            block.circuits_params.max_keccak_rows,
+            block
+                .get_blake2f()
+                .into_iter()
+                .map(|event| event.input)
+                .collect(),
+        )
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        let rows = block
+            .get_blake2f()
+            .iter()
+            .map(|event| get_num_units(&event.input) * NUM_ROWS_PER_UNIT)
+            .sum::<usize>();
+        (rows, rows.max(block.circuits_params.max_keccak_rows))
+    }
+
+    /// Make the assignments to the Blake2fCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let witness = self.generate_witness(*challenges);
+        config.assign(layouter, witness.as_slice())
+    }
+}
+
+impl<F: Field> Blake2fCircuit<F> {
+    /// Creates a new circuit instance
+    pub fn new(num_rows: usize, inputs: Vec<Vec<u8>>) -> Self {
+        Blake2fCircuit {
+            inputs,
+            num_rows,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The number of row units that can be used in this circuit
+    pub fn capacity(&self) -> Option<usize> {
+        Self::capacity_for_row(self.num_rows)
+    }
+
+    /// The number of row units that can be used for a particular row number
+    pub fn capacity_for_row(num_rows: usize) -> Option<usize> {
+        if num_rows > 0 {
+            Some(num_rows.saturating_sub(Self::unusable_rows()) / NUM_ROWS_PER_UNIT)
+        } else {
+            None
+        }
+    }
+
+    /// Sets the witness using the inputs of the calls
+    pub(crate) fn generate_witness(&self, challenges: Challenges<Value<F>>) -> Vec<Blake2fRow<F>> {
+        multi_blake2f(self.inputs.as_slice(), challenges, self.capacity())
+            .expect("Too many inputs for given capacity")
+    }
+}
@@ -0,0 +1,269 @@
+use super::{param::*, util::*};
+use crate::{evm_circuit::util::rlc, util::Challenges};
+use eth_types::Field;
+use halo2_proofs::{circuit::Value, plonk::Error};
+use log::debug;
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+
+/// Blake2fRow
+#[derive(Clone, Debug)]
+pub struct Blake2fRow<F> {
+    pub(crate) is_init: bool,
+    pub(crate) is_round: bool,
+    pub(crate) is_fin: bool,
+    pub(crate) round_flags: [bool; NUM_SIGMAS],
+    pub(crate) rounds_left: u64,
+    pub(crate) sum: [u8; NUM_BITS_PER_WORD_EXT],
+    pub(crate) r: [u8; NUM_BITS_PER_WORD],
+    pub(crate) r_new: u64,
+    pub(crate) v: [u64; NUM_STATE_WORDS],
+    pub(crate) h: [u64; NUM_HASH_WORDS],
+    pub(crate) m: [u64; NUM_MESSAGE_WORDS],
+    /// The number of rounds of the call
+    pub rounds: u64,
+    /// if the row is the last row of the call
+    pub is_final: bool,
+    pub(crate) input_rlc: Value<F>,
+    pub(crate) output_rlc: Value<F>,
+}
+
+impl<F: Field> Blake2fRow<F> {
+    fn padding() -> Self {
+        Self {
+            is_init: false,
+            is_round: false,
+            is_fin: false,
+            round_flags: [false; NUM_SIGMAS],
+            rounds_left: 0,
+            sum: [0; NUM_BITS_PER_WORD_EXT],
+            r: [0; NUM_BITS_PER_WORD],
+            r_new: 0,
+            v: [0; NUM_STATE_WORDS],
+            h: [0; NUM_HASH_WORDS],
+            m: [0; NUM_MESSAGE_WORDS],
+            rounds: 0,
+            is_final: false,
+            input_rlc: Value::known(F::zero()),
+            output_rlc: Value::known(F::zero()),
+        }
+    }
+}
+
+struct Input {
+    rounds: u64,
+    h: [u64; NUM_HASH_WORDS],
+    m: [u64; NUM_MESSAGE_WORDS],
+    t: [u64; 2],
+    f: bool,
+}
+
+fn read_word(bytes: &[u8], idx: usize) -> u64 {
+    u64::from_le_bytes(
+        bytes[idx * NUM_BYTES_PER_WORD..(idx + 1) * NUM_BYTES_PER_WORD]
+            .try_into()
+            .unwrap(),
+    )
+}
+
+impl Input {
+    fn new(input: &[u8]) -> Self {
+        assert_eq!(input.len(), INPUT_LENGTH, "blake2f input has 213 bytes");
+        assert!(input[INPUT_LENGTH - 1] <= 1, "blake2f final flag is 0 or 1");
+        let words = &input[NUM_BYTES_ROUNDS..INPUT_LENGTH - 1];
+        Self {
+            rounds: u32::from_be_bytes(input[..NUM_BYTES_ROUNDS].try_into().unwrap()) as u64,
+            h: array_init::array_init(|idx| read_word(words, idx)),
+            m: array_init::array_init(|idx| read_word(words, NUM_HASH_WORDS + idx)),
+            t: array_init::array_init(|idx| {
+                read_word(words, NUM_HASH_WORDS + NUM_MESSAGE_WORDS + idx)
+            }),
+            f: input[INPUT_LENGTH - 1] == 1,
+        }
+    }
+
+    /// The words on the rows of the input unit
+    fn words(&self) -> [u64; NUM_ROWS_PER_UNIT] {
+        let mut words = [0; NUM_ROWS_PER_UNIT];
+        words[ROW_ROUNDS] = self.rounds;
+        words[ROW_H..ROW_M].copy_from_slice(&self.h);
+        words[ROW_M..ROW_T].copy_from_slice(&self.m);
+        words[ROW_T..ROW_F].copy_from_slice(&self.t);
+        words[ROW_F] = self.f as u64;
+        words
+    }
+
+    fn state(&self) -> [u64; NUM_STATE_WORDS] {
+        let mut v = [0; NUM_STATE_WORDS];
+        v[..NUM_HASH_WORDS].copy_from_slice(&self.h);
+        v[NUM_HASH_WORDS..].copy_from_slice(&IV);
+        v[12] ^= self.t[0];
+        v[13] ^= self.t[1];
+        if self.f {
+            v[14] = !v[14];
+        }
+        v
+    }
+}
+
+/// Applies the step of `row` to the state, returns `(p + q + m, r, r_new)`
+fn apply_step(
+    v: &mut [u64; NUM_STATE_WORDS],
+    m: &[u64; NUM_MESSAGE_WORDS],
+    sigma: &[usize; NUM_MESSAGE_WORDS],
+    row: usize,
+) -> (u128, u64, u64) {
+    let (p, q, r, slot, rotation) = step(row);
+    let sum = v[p] as u128 + v[q] as u128 + slot.map(|slot| m[sigma[slot]]).unwrap_or(0) as u128;
+    let old_r = v[r];
+    v[p] = sum as u64;
+    v[r] = (old_r ^ v[p]).rotate_right(rotation as u32);
+    (sum, old_r, v[r])
+}
+
+/// The number of rows units needed for an input
+pub(crate) fn get_num_units(input: &[u8]) -> usize {
+    Input::new(input).rounds as usize + NUM_EXTRA_UNITS
+}
+
+/// Computes the BLAKE2 compression function F on an EIP-152 input
+pub fn compress(input: &[u8]) -> [u8; NUM_HASH_WORDS * NUM_BYTES_PER_WORD] {
+    let input = Input::new(input);
+    let mut v = input.state();
+    for round in 0..input.rounds as usize {
+        for row in 0..NUM_ROWS_PER_UNIT {
+            apply_step(&mut v, &input.m, &SIGMA[round % NUM_SIGMAS], row);
+        }
+    }
+    let mut output = [0; NUM_HASH_WORDS * NUM_BYTES_PER_WORD];
+    for (idx, chunk) in output.chunks_mut(NUM_BYTES_PER_WORD).enumerate() {
+        chunk.copy_from_slice(&(input.h[idx] ^ v[idx] ^ v[idx + NUM_HASH_WORDS]).to_le_bytes());
+    }
+    output
+}
+
+fn blake2f<F: Field>(
+    rows: &mut Vec<Blake2fRow<F>>,
+    bytes: &[u8],
+    challenges: Challenges<Value<F>>,
+) {
+    let input = Input::new(bytes);
+    let call_row = Blake2fRow {
+        h: input.h,
+        m: input.m,
+        rounds: input.rounds,
+        ..Blake2fRow::padding()
+    };
+
+    // Input unit, the input RLC is accumulated over the bytes of each row
+    let mut v = input.state();
+    let mut input_len = 0;
+    let mut input_rlc = Value::known(F::zero());
+    for (row_idx, word) in input.words().into_iter().enumerate() {
+        input_len += match row_idx {
+            ROW_ROUNDS => NUM_BYTES_ROUNDS,
+            ROW_F => 1,
+            _ if row_idx < ROW_F => NUM_BYTES_PER_WORD,
+            _ => 0,
+        };
+        input_rlc = challenges
+            .keccak_input()
+            .map(|r| rlc::value(bytes[..input_len].iter().rev(), r));
+        let r_new = match row_idx {
+            ROW_T..=ROW_F => v[12 + row_idx - ROW_T],
+            _ => 0,
+        };
+        rows.push(Blake2fRow {
+            is_init: true,
+            rounds_left: input.rounds,
+            sum: into_bits(word as u128),
+            r_new,
+            v,
+            input_rlc,
+            ..call_row.clone()
+        });
+    }
+
+    // Round units
+    for round in 0..input.rounds {
+        let sigma = &SIGMA[round as usize % NUM_SIGMAS];
+        for row_idx in 0..NUM_ROWS_PER_UNIT {
+            let (sum, r, r_new) = apply_step(&mut v, &input.m, sigma, row_idx);
+            rows.push(Blake2fRow {
+                is_round: true,
+                round_flags: array_init::array_init(|idx| idx == round as usize % NUM_SIGMAS),
+                rounds_left: input.rounds - round - 1,
+                sum: into_bits(sum),
+                r: into_bits(r as u128),
+                r_new,
+                v,
+                input_rlc,
+                ..call_row.clone()
+            });
+        }
+    }
+
+    // Output unit, each output word is `h[i] ^ v[i] ^ v[i + 8]` using 2 rows
+    let mut output = vec![];
+    let mut output_rlc = Value::known(F::zero());
+    for row_idx in 0..NUM_ROWS_PER_UNIT {
+        let word_idx = row_idx / 2;
+        let (a, b) = match row_idx {
+            _ if row_idx >= 2 * NUM_HASH_WORDS => (0, 0),
+            _ if row_idx % 2 == 0 => (input.h[word_idx], v[word_idx]),
+            _ => (rows.last().unwrap().r_new, v[word_idx + NUM_HASH_WORDS]),
+        };
+        if row_idx < 2 * NUM_HASH_WORDS && row_idx % 2 == 1 {
+            output.extend((a ^ b).to_le_bytes());
+            output_rlc = challenges
+                .keccak_input()
+                .map(|r| rlc::value(output.iter().rev(), r));
+        }
+        rows.push(Blake2fRow {
+            is_fin: true,
+            sum: into_bits(a as u128),
+            r: into_bits(b as u128),
+            r_new: a ^ b,
+            v,
+            is_final: row_idx == ROW_LAST,
+            input_rlc,
+            output_rlc,
+            ..call_row.clone()
+        });
+    }
+    debug!("blake2f output: {:x?}", output);
+}
+
+/// Witness generation for multiple blake2f calls, `capacity` is the number of
+/// row units.
+pub fn multi_blake2f<F: Field>(
+    inputs: &[Vec<u8>],
+    challenges: Challenges<Value<F>>,
+    capacity: Option<usize>,
+) -> Result<Vec<Blake2fRow<F>>, Error> {
+    log::info!("multi_blake2f assign with capacity: {:?}", capacity);
+    let mut rows: Vec<Blake2fRow<F>> = Vec::new();
+    if let Some(capacity) = capacity {
+        rows.reserve(capacity * NUM_ROWS_PER_UNIT);
+    }
+
+    let real_rows: Vec<_> = inputs
+        .par_iter()
+        .flat_map_iter(|input| {
+            let mut rows = Vec::new();
+            blake2f(&mut rows, input, challenges);
+            rows
+        })
+        .collect();
+    rows.extend(real_rows.into_iter());
+    debug!("blake2f rows len without padding: {}", rows.len());
+    if let Some(capacity) = capacity {
+        // Check that we are not over capacity
+        if rows.len() > capacity * NUM_ROWS_PER_UNIT {
+            return Err(Error::BoundsFailure);
+        }
+        // Pad with disabled units to the expected capacity
+        rows.resize(capacity * NUM_ROWS_PER_UNIT, Blake2fRow::padding());
+    }
+    debug!("blake2f witgen done");
+    Ok(rows)
+}
@@ -0,0 +1,71 @@
+pub(crate) const MAX_DEGREE: usize = 5;
+
+pub(crate) const NUM_BITS_PER_BYTE: usize = 8;
+pub(crate) const NUM_BYTES_PER_WORD: usize = 8;
+pub(crate) const NUM_BITS_PER_WORD: usize = NUM_BYTES_PER_WORD * NUM_BITS_PER_BYTE;
+// `p + q + m` is the sum of 3 words, so it needs 2 carry bits.
+pub(crate) const NUM_BITS_PER_WORD_EXT: usize = NUM_BITS_PER_WORD + 2;
+pub(crate) const NUM_HASH_WORDS: usize = 8;
+pub(crate) const NUM_MESSAGE_WORDS: usize = 16;
+pub(crate) const NUM_STATE_WORDS: usize = 16;
+// The mixing function G is applied 8 times per round, and does 4 steps
+// `p = p + q + m; r = (r ^ p) >>> k` each, so a round takes 32 rows.
+pub(crate) const NUM_MIXES: usize = 8;
+pub(crate) const NUM_STEPS_PER_MIX: usize = 4;
+pub(crate) const NUM_ROWS_PER_UNIT: usize = NUM_MIXES * NUM_STEPS_PER_MIX;
+// A call takes one unit to load the input, one unit per round and one unit to
+// compute the output.
+pub(crate) const NUM_EXTRA_UNITS: usize = 2;
+pub(crate) const NUM_SIGMAS: usize = 10;
+
+/// The input is `rounds || h || m || t || f`, see EIP-152.
+pub(crate) const INPUT_LENGTH: usize = 213;
+pub(crate) const NUM_BYTES_ROUNDS: usize = 4;
+/// Rows of the input unit
+pub(crate) const ROW_ROUNDS: usize = 0;
+pub(crate) const ROW_H: usize = ROW_ROUNDS + 1;
+pub(crate) const ROW_M: usize = ROW_H + NUM_HASH_WORDS;
+pub(crate) const ROW_T: usize = ROW_M + NUM_MESSAGE_WORDS;
+pub(crate) const ROW_F: usize = ROW_T + 2;
+pub(crate) const ROW_LAST: usize = NUM_ROWS_PER_UNIT - 1;
+
+/// Initialization vector
+pub(crate) const IV: [u64; NUM_HASH_WORDS] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+/// Message word permutations, round `i` uses `SIGMA[i % 10]`
+pub(crate) const SIGMA: [[usize; NUM_MESSAGE_WORDS]; NUM_SIGMAS] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// The state words `[a, b, c, d]` mixed by each G
+pub(crate) const MIX_INDICES: [[usize; 4]; NUM_MIXES] = [
+    [0, 4, 8, 12],
+    [1, 5, 9, 13],
+    [2, 6, 10, 14],
+    [3, 7, 11, 15],
+    [0, 5, 10, 15],
+    [1, 6, 11, 12],
+    [2, 7, 8, 13],
+    [3, 4, 9, 14],
+];
+
+/// The right rotation of each step of G
+pub(crate) const ROTATIONS: [usize; NUM_STEPS_PER_MIX] = [32, 24, 16, 63];
@@ -0,0 +1,141 @@
+use super::{blake2f_bit::compress, *};
+use crate::{evm_circuit::util::rlc, util::unusable_rows};
+use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
+use log::error;
+
+const K: u32 = 12;
+
+/// The input of EIP-152 test vector 5, hashing "abc" with `rounds` rounds
+fn input(rounds: u32, f: bool) -> Vec<u8> {
+    let mut input = rounds.to_be_bytes().to_vec();
+    input.extend(
+        hex::decode(
+            "48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5\
+             d182e6ad7f520e511f6c3e2b8c68059b6bbd41fbabd9831f79217e1319cde05b",
+        )
+        .unwrap(),
+    );
+    let mut m = [0u8; 128];
+    m[..3].copy_from_slice(b"abc");
+    input.extend(m);
+    input.extend(3u64.to_le_bytes());
+    input.extend(0u64.to_le_bytes());
+    input.push(f as u8);
+    input
+}
+
+#[test]
+fn blake2f_circuit_unusable_rows() {
+    assert_eq!(
+        Blake2fCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, Blake2fCircuit::<Fr>>(),
+    )
+}
+
+fn verify<F: Field>(k: u32, inputs: Vec<Vec<u8>>, success: bool) {
+    let circuit = Blake2fCircuit::new(2usize.pow(k), inputs);
+
+    let prover = MockProver::<F>::run(k, &circuit, vec![]).unwrap();
+    let verify_result = prover.verify();
+    if verify_result.is_ok() != success {
+        if let Some(errors) = verify_result.err() {
+            for error in errors.iter() {
+                error!("{}", error);
+            }
+        }
+        panic!();
+    }
+}
+
+#[test]
+fn blake2f_circuit_simple() {
+    let inputs = vec![
+        input(0, true),
+        input(1, false),
+        input(12, true),
+        input(12, false),
+    ];
+    verify::<Fr>(K, inputs, true);
+}
+
+fn mock_challenges() -> Challenges<Value<Fr>> {
+    Challenges::mock(
+        Value::known(Fr::from(0x100)),
+        Value::known(Fr::from(0x100)),
+        Value::known(Fr::from(0x100)),
+    )
+}
+
+#[test]
+fn blake2f_circuit_capacity() {
+    let challenges = mock_challenges();
+    // 12 rounds need 14 units
+    let inputs = vec![input(12, true)];
+    assert!(multi_blake2f::<Fr>(&inputs, challenges, Some(13)).is_err());
+    assert_eq!(
+        multi_blake2f::<Fr>(&inputs, challenges, Some(16))
+            .unwrap()
+            .len(),
+        16 * NUM_ROWS_PER_UNIT
+    );
+}
+
+#[test]
+fn blake2f_compress() {
+    // EIP-152 test vectors 4 to 7
+    for (rounds, f, output) in [
+        (
+            0,
+            true,
+            "08c9bcf367e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5\
+             d282e6ad7f520e511f6c3e2b8c68059b9442be0454267ce079217e1319cde05b",
+        ),
+        (
+            12,
+            true,
+            "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+             7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923",
+        ),
+        (
+            12,
+            false,
+            "75ab69d3190a562c51aef8d88f1c2775876944407270c42c9844252c26d28752\
+             98743e7f6d5ea2f2d3e8d226039cd31b4e426ac4f2d3d666a610c2116fde4735",
+        ),
+        (
+            1,
+            true,
+            "b63a380cb2897d521994a85234ee2c181b5f844d2c624c002677e9703449d2fb\
+             a551b3a8333bcdf5f2f7e08993d53923de3d64fcc68c034e717b9293fed7a421",
+        ),
+    ] {
+        assert_eq!(
+            compress(&input(rounds, f)).to_vec(),
+            hex::decode(output).unwrap()
+        );
+    }
+}
+
+#[test]
+fn blake2f_witness_output() {
+    let challenges = mock_challenges();
+    for (rounds, f) in [(0, true), (1, false), (12, true)] {
+        let input = input(rounds, f);
+        let rows = multi_blake2f::<Fr>(&[input.clone()], challenges, None).unwrap();
+        assert_eq!(rows.len(), get_num_units(&input) * NUM_ROWS_PER_UNIT);
+
+        let last_row = rows.last().unwrap();
+        assert!(last_row.is_final);
+        assert_eq!(last_row.rounds, rounds as u64);
+
+        let output = compress(&input);
+        last_row
+            .output_rlc
+            .zip(last_row.input_rlc)
+            .zip(challenges.keccak_input())
+            .assert_if_known(|((output_rlc, input_rlc), r)| {
+                *output_rlc == rlc::value(output.iter().rev(), *r)
+                    && *input_rlc == rlc::value(input.iter().rev(), *r)
+            });
+    }
+}
@@ -0,0 +1,90 @@
+use super::param::*;
+use crate::util::Expr;
+use eth_types::Field;
+use halo2_proofs::plonk::Expression;
+
+/// Decodes little-endian bits into a value
+pub(crate) mod decode {
+    use crate::util::Expr;
+    use eth_types::Field;
+    use halo2_proofs::plonk::Expression;
+
+    pub(crate) fn expr<F: Field>(bits: &[Expression<F>]) -> Expression<F> {
+        let mut value = 0.expr();
+        let mut multiplier = F::one();
+        for bit in bits.iter() {
+            value = value + bit.expr() * multiplier;
+            multiplier *= F::from(2);
+        }
+        value
+    }
+}
+
+/// Rotates the bits of a word to the right
+pub(crate) mod rotate {
+    use super::NUM_BITS_PER_WORD;
+    use eth_types::Field;
+    use halo2_proofs::plonk::Expression;
+
+    pub(crate) fn expr<F: Field>(bits: &[Expression<F>], count: usize) -> Vec<Expression<F>> {
+        (0..NUM_BITS_PER_WORD)
+            .map(|idx| bits[(idx + count) % NUM_BITS_PER_WORD].clone())
+            .collect()
+    }
+}
+
+/// Xors the bits of two words
+pub(crate) mod xor {
+    use super::NUM_BITS_PER_WORD;
+    use crate::util::Expr;
+    use eth_types::Field;
+    use halo2_proofs::plonk::Expression;
+
+    pub(crate) fn expr<F: Field>(a: &[Expression<F>], b: &[Expression<F>]) -> Vec<Expression<F>> {
+        a[..NUM_BITS_PER_WORD]
+            .iter()
+            .zip(b.iter())
+            .map(|(a, b)| a.expr() + b.expr() - 2.expr() * a.expr() * b.expr())
+            .collect()
+    }
+
+    /// Xors the bits of a word with a constant, the result is linear in the
+    /// bits.
+    pub(crate) fn expr_const<F: Field>(a: &[Expression<F>], b: u64) -> Vec<Expression<F>> {
+        a[..NUM_BITS_PER_WORD]
+            .iter()
+            .enumerate()
+            .map(|(idx, a)| {
+                if (b >> idx) & 1 == 1 {
+                    1.expr() - a.expr()
+                } else {
+                    a.expr()
+                }
+            })
+            .collect()
+    }
+}
+
+/// The state words and the message slot used by a row of a round, the step
+/// `p = p + q + m; r = (r ^ p) >>> k` returns `(p, q, r, slot, k)`.
+pub(crate) fn step(row: usize) -> (usize, usize, usize, Option<usize>, usize) {
+    let mix = row / NUM_STEPS_PER_MIX;
+    let step = row % NUM_STEPS_PER_MIX;
+    let [a, b, c, d] = MIX_INDICES[mix];
+    let (p, q, r) = if step % 2 == 0 { (a, b, d) } else { (c, d, b) };
+    let slot = (step % 2 == 0).then_some(2 * mix + step / 2);
+    (p, q, r, slot, ROTATIONS[step])
+}
+
+/// Returns the little-endian bytes of a word given its little-endian bits
+pub(crate) fn to_le_bytes<F: Field>(bits: &[Expression<F>]) -> Vec<Expression<F>> {
+    bits[..NUM_BITS_PER_WORD]
+        .chunks(NUM_BITS_PER_BYTE)
+        .map(decode::expr)
+        .collect()
+}
+
+/// Returns the little-endian bits of a value
+pub(crate) fn into_bits<const N: usize>(value: u128) -> [u8; N] {
+    array_init::array_init(|idx| ((value >> idx) & 1) as u8)
+}
@@ -17,6 +17,7 @@ use eth_types::{Field, Word};
 use gadgets::{
     binary_number::BinaryNumberChip,
     is_equal::{IsEqualChip, IsEqualConfig, IsEqualInstruction},
+    is_zero::{IsZeroChip, IsZeroConfig, IsZeroInstruction},
     util::{not, Expr},
 };
 use halo2_proofs::{
@@ -43,11 +44,15 @@ use crate::{
     witness::{Bytecode, RwMap, Transaction},
 };
 
-use self::copy_gadgets::{
-    constrain_address, constrain_bytes_left, constrain_event_rlc_acc, constrain_first_last,
-    constrain_forward_parameters, constrain_is_pad, constrain_mask, constrain_masked_value,
-    constrain_must_terminate, constrain_non_pad_non_mask, constrain_rw_counter, constrain_tag,
-    constrain_value_rlc, constrain_word_index, constrain_word_rlc,
+use self::{
+    copy_gadgets::{
+        constrain_address, constrain_bytes_left, constrain_event_rlc_acc, constrain_first_last,
+        constrain_forward_parameters, constrain_is_memory_copy, constrain_is_pad, constrain_mask,
+        constrain_masked_value, constrain_must_terminate, constrain_non_pad_non_mask,
+        constrain_rw_counter, constrain_tag, constrain_value_rlc, constrain_word_index,
+        constrain_word_rlc,
+    },
+    util::number_or_hash_to_field,
 };
 
 /// The current row.
@@ -103,6 +108,9 @@ pub struct CopyCircuitConfig<F> {
     pub is_memory: Column<Advice>,
     /// Booleans to indicate what copy data type exists at the current row.
     pub is_tx_log: Column<Advice>,
+    /// Whether the event copies within the memory of a single call (MCOPY). All source words are
+    /// then read before the destination words are written.
+    pub is_memory_copy: Column<Advice>,
     /// Whether the row is enabled or not.
     pub q_enable: Column<Fixed>,
     /// The Copy Table contains the columns that are exposed via the lookup
@@ -112,6 +120,8 @@ pub struct CopyCircuitConfig<F> {
     pub is_src_end: IsEqualConfig<F>,
     /// Whether this is the end of a word (last byte).
     pub is_word_end: IsEqualConfig<F>,
+    /// Detect when the reader and the writer have the same id.
+    pub is_same_id: IsZeroConfig<F>,
     /// non pad and non mask witness to reduce the degree of lookups.
     pub non_pad_non_mask: Column<Advice>,
     // External tables
@@ -173,6 +183,7 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
             meta.advice_column(),
             meta.advice_column(),
         );
+        let is_memory_copy = meta.advice_column();
         let is_pad = meta.advice_column();
         let is_first = copy_table.is_first;
         let id = copy_table.id;
@@ -208,6 +219,15 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
             |_meta| 31.expr(),
         );
 
+        // The id may be a hash RLC, so its inverse is in the second phase.
+        let id_diff_inv = meta.advice_column_in(SecondPhase);
+        let is_same_id = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(q_step),
+            |meta| meta.query_advice(id, CURRENT) - meta.query_advice(id, NEXT_ROW),
+            id_diff_inv,
+        );
+
         let non_pad_non_mask = meta.advice_column();
 
         constrain_tag(
@@ -344,13 +364,24 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
             {
                 let is_rw_type = meta.query_advice(is_memory, CURRENT) + is_tx_log.expr();
 
+                let is_memory_copy = constrain_is_memory_copy(
+                    cb,
+                    meta,
+                    is_reader.expr(),
+                    is_memory,
+                    is_same_id.expr(),
+                    is_memory_copy,
+                );
+
                 constrain_rw_counter(
                     cb,
                     meta,
+                    is_first.expr(),
                     is_last.expr(),
                     is_last_step.expr(),
                     is_rw_type.expr(),
                     is_word_end.expr(),
+                    is_memory_copy,
                     rw_counter,
                     rwc_inc_left,
                 );
@@ -469,9 +500,11 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
             is_bytecode,
             is_memory,
             is_tx_log,
+            is_memory_copy,
             q_enable,
             is_src_end,
             is_word_end,
+            is_same_id,
             non_pad_non_mask,
             copy_table,
             tx_table,
@@ -491,9 +524,13 @@ impl<F: Field> CopyCircuitConfig<F> {
         tag_chip: &BinaryNumberChip<F, CopyDataType, 4>,
         is_src_end_chip: &IsEqualChip<F>,
         lt_word_end_chip: &IsEqualChip<F>,
+        is_same_id_chip: &IsZeroChip<F>,
         challenges: Challenges<Value<F>>,
         copy_event: &CopyEvent,
     ) -> Result<(), Error> {
+        let is_memory_copy = copy_event.is_memory_copy();
+        let id_diff = number_or_hash_to_field(&copy_event.src_id, challenges.evm_word())
+            - number_or_hash_to_field(&copy_event.dst_id, challenges.evm_word());
         for (step_idx, (tag, table_row, circuit_row)) in
             CopyTable::assignments(copy_event, challenges)
                 .iter()
@@ -564,6 +601,7 @@ impl<F: Field> CopyCircuitConfig<F> {
                     addr,
                     Value::known(F::from(copy_event.src_addr_end)),
                 )?;
+                is_same_id_chip.assign(region, *offset, id_diff)?;
             }
 
             lt_word_end_chip.assign(
@@ -615,6 +653,12 @@ impl<F: Field> CopyCircuitConfig<F> {
                 *offset,
                 || Value::known(F::from(tag.eq(&CopyDataType::TxLog))),
             )?;
+            region.assign_advice(
+                || format!("is_memory_copy at row: {}", *offset),
+                self.is_memory_copy,
+                *offset,
+                || Value::known(F::from(is_memory_copy)),
+            )?;
 
             *offset += 1;
         }
@@ -649,6 +693,7 @@ impl<F: Field> CopyCircuitConfig<F> {
         let tag_chip = BinaryNumberChip::construct(self.copy_table.tag);
         let is_src_end_chip = IsEqualChip::construct(self.is_src_end.clone());
         let lt_word_end_chip = IsEqualChip::construct(self.is_word_end.clone());
+        let is_same_id_chip = IsZeroChip::construct(self.is_same_id.clone());
 
         layouter.assign_region(
             || "assign copy table",
@@ -664,6 +709,7 @@ impl<F: Field> CopyCircuitConfig<F> {
                 region.name_column(|| "is_code", self.is_code);
                 region.name_column(|| "is_pad", self.is_pad);
                 region.name_column(|| "non_pad_non_mask", self.non_pad_non_mask);
+                region.name_column(|| "is_memory_copy", self.is_memory_copy);
 
                 let mut offset = 0;
                 for (ev_idx, copy_event) in copy_events.iter().enumerate() {
@@ -684,6 +730,7 @@ impl<F: Field> CopyCircuitConfig<F> {
                         &tag_chip,
                         &is_src_end_chip,
                         &lt_word_end_chip,
+                        &is_same_id_chip,
                         challenges,
                         copy_event,
                     )?;
@@ -698,6 +745,7 @@ impl<F: Field> CopyCircuitConfig<F> {
                         &tag_chip,
                         &is_src_end_chip,
                         &lt_word_end_chip,
+                        &is_same_id_chip,
                     )?;
                 }
                 assert_eq!(offset % 2, 0, "enabled rows must come in pairs");
@@ -710,6 +758,7 @@ impl<F: Field> CopyCircuitConfig<F> {
                         &tag_chip,
                         &is_src_end_chip,
                         &lt_word_end_chip,
+                        &is_same_id_chip,
                     )?;
                 }
 
@@ -727,6 +776,7 @@ impl<F: Field> CopyCircuitConfig<F> {
         tag_chip: &BinaryNumberChip<F, CopyDataType, 4>,
         is_src_end_chip: &IsEqualChip<F>,
         lt_word_end_chip: &IsEqualChip<F>,
+        is_same_id_chip: &IsZeroChip<F>,
     ) -> Result<(), Error> {
         // q_enable
         region.assign_fixed(
@@ -890,6 +940,7 @@ impl<F: Field> CopyCircuitConfig<F> {
             Value::known(F::zero()),
             Value::known(F::from(31u64)),
         )?;
+        is_same_id_chip.assign(region, *offset, Value::known(F::zero()))?;
         region.assign_advice(
             || format!("non_pad_non_mask at row: {offset}"),
             self.non_pad_non_mask,
@@ -903,6 +954,7 @@ impl<F: Field> CopyCircuitConfig<F> {
             self.is_bytecode,
             self.is_memory,
             self.is_tx_log,
+            self.is_memory_copy,
         ] {
             region.assign_advice(
                 || format!("assigning padding row: {}", *offset),
@@ -514,32 +514,92 @@ pub fn constrain_address<F: Field>(
     });
 }
 
+/// Detect a copy within the memory of a single call, as done by MCOPY. Returns the flag of the
+/// current row, which is set on the reader row and copied to the writer row.
+pub fn constrain_is_memory_copy<F: Field>(
+    cb: &mut BaseConstraintBuilder<F>,
+    meta: &mut VirtualCells<'_, F>,
+    is_reader: Expression<F>,
+    is_memory: Column<Advice>,
+    is_same_id: Expression<F>,
+    is_memory_copy: Column<Advice>,
+) -> Expression<F> {
+    let is_memory_copy_expr = meta.query_advice(is_memory_copy, CURRENT);
+    cb.condition(is_reader, |cb| {
+        cb.require_equal(
+            "is_memory_copy == is_memory[0] * is_memory[1] * (id[0] == id[1])",
+            is_memory_copy_expr.expr(),
+            and::expr([
+                meta.query_advice(is_memory, CURRENT),
+                meta.query_advice(is_memory, NEXT_ROW),
+                is_same_id,
+            ]),
+        );
+        cb.require_equal(
+            "is_memory_copy[1] == is_memory_copy[0]",
+            meta.query_advice(is_memory_copy, NEXT_ROW),
+            is_memory_copy_expr.expr(),
+        );
+    });
+    is_memory_copy_expr
+}
+
 /// Update the RW counter and verify that all RWs requested by the event are consumed.
 #[allow(clippy::too_many_arguments)]
 pub fn constrain_rw_counter<F: Field>(
     cb: &mut BaseConstraintBuilder<F>,
     meta: &mut VirtualCells<'_, F>,
+    is_first: Expression<F>,
     is_last: Expression<F>,      // The last row.
     is_last_step: Expression<F>, // Both the last reader and writer rows.
     is_rw_type: Expression<F>,
     is_word_end: Expression<F>,
+    is_memory_copy: Expression<F>,
     rw_counter: Column<Advice>,
     rwc_inc_left: Column<Advice>,
 ) {
     // Decrement rwc_inc_left for the next row, when an RW operation happens.
     let rwc_diff = is_rw_type.expr() * is_word_end.expr();
     let new_value = meta.query_advice(rwc_inc_left, CURRENT) - rwc_diff;
-    // At the end, it must reach 0.
-    let update_or_finish = select::expr(
-        not::expr(is_last.expr()),
-        meta.query_advice(rwc_inc_left, NEXT_ROW),
-        0.expr(),
-    );
-    cb.require_equal(
-        "rwc_inc_left[2] == rwc_inc_left[0] - rwc_diff, or 0 at the end",
-        new_value,
-        update_or_finish,
-    );
+    cb.condition(not::expr(is_memory_copy.expr()), |cb| {
+        // At the end, it must reach 0.
+        let update_or_finish = select::expr(
+            not::expr(is_last.expr()),
+            meta.query_advice(rwc_inc_left, NEXT_ROW),
+            0.expr(),
+        );
+        cb.require_equal(
+            "rwc_inc_left[2] == rwc_inc_left[0] - rwc_diff, or 0 at the end",
+            new_value.expr(),
+            update_or_finish,
+        );
+    });
+
+    // A memory copy reads all the source words before writing the destination words, so that
+    // overlapping ranges are copied correctly. The reader counts the RW operations of both
+    // threads and the writer those of the writes only, each decremented at its own word ends.
+    cb.condition(is_memory_copy.expr(), |cb| {
+        cb.condition(is_first.expr(), |cb| {
+            cb.require_equal(
+                "rwc_inc_left[0] == 2 * rwc_inc_left[1] for a memory copy",
+                meta.query_advice(rwc_inc_left, CURRENT),
+                2.expr() * meta.query_advice(rwc_inc_left, NEXT_ROW),
+            );
+        });
+        cb.condition(not::expr(is_last_step.expr()), |cb| {
+            cb.require_equal(
+                "rwc_inc_left[2] == rwc_inc_left[0] - rwc_diff for a memory copy",
+                new_value.expr(),
+                meta.query_advice(rwc_inc_left, NEXT_STEP),
+            );
+        });
+        cb.condition(is_last.expr(), |cb| {
+            cb.require_zero(
+                "rwc_inc_left reaches 0 at the end of a memory copy",
+                new_value.expr(),
+            );
+        });
+    });
 
     // Maintain rw_counter based on rwc_inc_left. Their sum remains constant in all cases.
     cb.condition(not::expr(is_last.expr()), |cb| {
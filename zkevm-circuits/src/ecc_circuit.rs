@@ -4,14 +4,16 @@
 use std::{iter, marker::PhantomData};
 
 use bus_mapping::{
-    circuit_input_builder::{EcAddOp, EcMulOp, EcPairingOp, N_BYTES_PER_PAIR, N_PAIRING_PER_OP},
+    circuit_input_builder::{
+        EcAddOp, EcMulOp, EcPairingOp, EcPairingPair, N_BYTES_PER_PAIR, N_PAIRING_PER_OP,
+    },
     precompile::PrecompileCalls,
 };
 use eth_types::{Field, ToScalar};
 use halo2_base::{
     gates::{GateInstructions, RangeInstructions},
     utils::modulus,
-    Context, QuantumCell, SKIP_FIRST_PASS,
+    AssignedValue, Context, QuantumCell, SKIP_FIRST_PASS,
 };
 use halo2_ecc::{
     bigint::CRTInteger,
@@ -20,7 +22,7 @@ use halo2_ecc::{
     fields::{
         fp::{FpConfig, FpStrategy},
         fp12::Fp12Chip,
-        FieldChip,
+        FieldChip, FieldExtPoint, Selectable,
     },
 };
 use halo2_proofs::{
@@ -46,8 +48,8 @@ mod util;
 
 use util::{
     EcAddAssigned, EcAddDecomposed, EcMulAssigned, EcMulDecomposed, EcOpsAssigned,
-    EcPairingAssigned, EcPairingDecomposed, G1Assigned, G1Decomposed, G2Decomposed, ScalarAssigned,
-    LOG_TOTAL_NUM_ROWS,
+    EcPairingAssigned, EcPairingChunkDecomposed, EcPairingDecomposed, G1Assigned, G1Decomposed,
+    ScalarAssigned, LOG_TOTAL_NUM_ROWS,
 };
 
 macro_rules! log_context_cursor {
@@ -135,6 +137,10 @@ impl<F: Field> SubCircuitConfig<F> for EccCircuitConfig<F> {
 /// We follow a strategy to pre-allocate maximum number of cells for each of the above ECC
 /// operations, which means a witness that exceeds the pre-allocated number of cells for any of the
 /// operations will be invalid.
+///
+/// The pairs of a pairing operation are split into Miller loop chunks of `N_PAIRING_PER_OP` pairs.
+/// The Miller loop results of the chunks of an operation are accumulated, and a single final
+/// exponentiation is done on the accumulated result of its last chunk.
 #[derive(Clone, Debug, Default)]
 pub struct EccCircuit<F: Field, const XI_0: i64> {
     /// Maximum number of EcAdd operations supported in one instance of the ECC Circuit.
@@ -144,6 +150,9 @@ pub struct EccCircuit<F: Field, const XI_0: i64> {
     pub max_mul_ops: usize,
     /// Maximum number of pairing operations supported in one instance of the ECC Circuit.
     pub max_pairing_ops: usize,
+    /// Maximum number of Miller loop chunks of `N_PAIRING_PER_OP` pairs, over all pairing
+    /// operations, supported in one instance of the ECC Circuit.
+    pub max_pairing_chunks: usize,
 
     /// EcAdd operations provided as witness data to the ECC circuit.
     pub add_ops: Vec<EcAddOp>,
@@ -163,27 +172,35 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
         config: &<Self as SubCircuit<F>>::Config,
         challenges: &Challenges<Value<F>>,
     ) -> Result<(), Error> {
+        let pairing_chunks = self
+            .pairing_ops
+            .iter()
+            .map(|op| op.n_chunks())
+            .sum::<usize>();
         if self.add_ops.len() > self.max_add_ops
             || self.mul_ops.len() > self.max_mul_ops
             || self.pairing_ops.len() > self.max_pairing_ops
+            || pairing_chunks > self.max_pairing_chunks
         {
             error!(
-                "add ops = {}, mul ops = {}, pairing ops = {} > max add ops = {}, max mul ops = {}, max pairing ops = {}",
+                "add ops = {}, mul ops = {}, pairing ops = {}, pairing chunks = {} > max add ops = {}, max mul ops = {}, max pairing ops = {}, max pairing chunks = {}",
                 self.add_ops.len(),
                 self.mul_ops.len(),
                 self.pairing_ops.len(),
+                pairing_chunks,
                 self.max_add_ops,
                 self.max_mul_ops,
                 self.max_pairing_ops,
+                self.max_pairing_chunks,
             );
             return Err(Error::Synthesis);
         }
 
-        // keccak powers of randomness.
+        // keccak powers of randomness, up to the power to shift the RLC by one (G1, G2) pair.
         let keccak_powers = std::iter::successors(Some(Value::known(F::one())), |coeff| {
             Some(challenges.keccak_input() * coeff)
         })
-        .take(N_PAIRING_PER_OP * N_BYTES_PER_PAIR)
+        .take(N_BYTES_PER_PAIR + 1)
         .map(|x| QuantumCell::Witness(x))
         .collect_vec();
 
@@ -256,12 +273,71 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
                     decompose_ec_op!(EcMulOp, self.mul_ops, self.max_mul_ops, decompose_ec_mul_op);
 
                 // e(G1 . G2) * ... * e(G1 . G2) -> Gt
-                let ec_pairings_decomposed = decompose_ec_op!(
-                    EcPairingOp,
-                    self.pairing_ops,
-                    self.max_pairing_ops,
-                    decompose_ec_pairing_op
-                );
+                //
+                // The pairs of each op are split into Miller loop chunks, the op being checked
+                // against the accumulated Miller loop result of its last chunk.
+                let pairing_ops = self
+                    .pairing_ops
+                    .iter()
+                    .filter(|op| !op.skip_by_ecc_circuit())
+                    .chain(std::iter::repeat(&EcPairingOp::default()))
+                    .take(self.max_pairing_ops)
+                    .collect_vec();
+                let mut chunks = vec![];
+                let mut last_chunks = vec![];
+                for op in pairing_ops.iter() {
+                    chunks.extend(
+                        op.pairs
+                            .chunks(N_PAIRING_PER_OP)
+                            .enumerate()
+                            .map(|(i, pairs)| (i == 0, pairs)),
+                    );
+                    // an empty input selects the extra indicator past the chunks.
+                    last_chunks.push(if op.n_chunks() == 0 {
+                        self.max_pairing_chunks
+                    } else {
+                        chunks.len() - 1
+                    });
+                }
+                let fq12_one = fp12_chip.load_constant(&mut ctx, Fq12::one());
+                let mut ec_pairing_chunks_decomposed: Vec<EcPairingChunkDecomposed<F>> =
+                    Vec::with_capacity(self.max_pairing_chunks);
+                for &(is_first, pairs) in chunks
+                    .iter()
+                    .chain(std::iter::repeat(&(true, &[][..])))
+                    .take(self.max_pairing_chunks)
+                {
+                    let prev_acc = ec_pairing_chunks_decomposed
+                        .last()
+                        .map_or(&fq12_one, |chunk| &chunk.acc);
+                    let chunk_decomposed = self.decompose_ec_pairing_chunk(
+                        &mut ctx,
+                        &ecc_chip,
+                        &pairing_chip,
+                        &fp12_chip,
+                        &powers_of_256,
+                        is_first,
+                        pairs,
+                        prev_acc,
+                    );
+                    ec_pairing_chunks_decomposed.push(chunk_decomposed);
+                }
+                let ec_pairings_decomposed = pairing_ops
+                    .iter()
+                    .zip_eq(last_chunks.iter())
+                    .map(|(op, &last_chunk)| {
+                        self.decompose_ec_pairing_op(
+                            &mut ctx,
+                            &ecc_chip,
+                            &pairing_chip,
+                            &fp12_chip,
+                            &ec_pairing_chunks_decomposed,
+                            &fq12_one,
+                            last_chunk,
+                            op,
+                        )
+                    })
+                    .collect_vec();
 
                 #[cfg(not(feature = "onephase"))]
                 {
@@ -272,7 +348,29 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
 
                 let ec_adds_assigned = assign_ec_op!(ec_adds_decomposed, assign_ec_add);
                 let ec_muls_assigned = assign_ec_op!(ec_muls_decomposed, assign_ec_mul);
-                let ec_pairings_assigned = assign_ec_op!(ec_pairings_decomposed, assign_ec_pairing);
+                let mut ec_pairing_chunk_rlcs: Vec<AssignedValue<F>> =
+                    Vec::with_capacity(self.max_pairing_chunks);
+                for chunk_decomposed in ec_pairing_chunks_decomposed.iter() {
+                    let chunk_rlc = self.assign_ec_pairing_chunk(
+                        &mut ctx,
+                        chunk_decomposed,
+                        ec_pairing_chunk_rlcs.last().copied(),
+                        &ecc_chip,
+                        &keccak_powers,
+                    );
+                    ec_pairing_chunk_rlcs.push(chunk_rlc);
+                }
+                let ec_pairings_assigned = ec_pairings_decomposed
+                    .iter()
+                    .map(|decomposed_op| {
+                        self.assign_ec_pairing(
+                            &mut ctx,
+                            decomposed_op,
+                            &ec_pairing_chunk_rlcs,
+                            &ecc_chip,
+                        )
+                    })
+                    .collect_vec();
 
                 // Finalize the Fp config always at the end of assignment.
                 let lookup_cells = config.fp_config.finalize(&mut ctx);
@@ -565,130 +663,220 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
         }
     }
 
-    /// Decomposes an EcPairing operation and returns cells that represent the LE-bytes of all
-    /// (G1, G2) pairs. In phase2 they will be RLC'd with the keccak randomness.
+    /// Decomposes a Miller loop chunk of an EcPairing operation and returns cells that represent
+    /// the BE-bytes of its EVM input (G1, G2) pairs. In phase2 they will be RLC'd with the keccak
+    /// randomness.
+    ///
+    /// The pairs not in the EVM input, and the pairs with an identity point, are replaced by the
+    /// padding pair `(G1::Infinity, G2::Generator)` in the Miller loop.
     #[allow(clippy::too_many_arguments)]
-    fn decompose_ec_pairing_op(
+    fn decompose_ec_pairing_chunk(
         &self,
         ctx: &mut Context<F>,
         ecc_chip: &EccChip<F, FpConfig<F, Fq>>,
-        _fr_chip: &FpConfig<F, Fr>,
         pairing_chip: &PairingChip<F>,
         fp12_chip: &Fp12Chip<F, FpConfig<F, Fq>, Fq12, XI_0>,
         powers_of_256: &[QuantumCell<F>],
-        op: &EcPairingOp,
-    ) -> EcPairingDecomposed<F> {
-        log::trace!("[ECC] ==> EcPairing Assignment START:");
+        is_first: bool,
+        pairs: &[EcPairingPair],
+        prev_acc: &FieldExtPoint<CRTInteger<F>>,
+    ) -> EcPairingChunkDecomposed<F> {
+        log::trace!("[ECC] ==> EcPairing Chunk Assignment START:");
         log_context_cursor!(ctx);
 
-        let g1s = op
-            .pairs
-            .iter()
-            .map(|pair| {
-                let ec_point = pairing_chip.load_private_g1(ctx, Value::known(pair.g1_point));
-                let (x_cells, y_cells) = self.decompose_g1(pair.g1_point);
-                self.assert_crt_repr(ctx, ecc_chip, &ec_point.x, &x_cells, powers_of_256);
-                self.assert_crt_repr(ctx, ecc_chip, &ec_point.y, &y_cells, powers_of_256);
-                G1Decomposed {
-                    ec_point,
-                    x_cells,
-                    y_cells,
-                }
-            })
-            .collect_vec();
-
-        log::trace!("[ECC] EcPairing g1s Assigned:");
-        log_context_cursor!(ctx);
+        debug_assert!(pairs.len() <= N_PAIRING_PER_OP);
+        let gate = ecc_chip.field_chip().range().gate();
+        let padding_bytes = EcPairingPair::ecc_padding().to_bytes_be();
+
+        let is_first = gate.load_witness(ctx, Value::known(F::from(is_first as u64)));
+        self.assert_bit(ctx, ecc_chip, is_first);
+
+        let mut is_input = Vec::with_capacity(N_PAIRING_PER_OP);
+        let mut input_cells = Vec::with_capacity(N_PAIRING_PER_OP);
+        let mut g1s = Vec::with_capacity(N_PAIRING_PER_OP);
+        let mut g2s = Vec::with_capacity(N_PAIRING_PER_OP);
+        for i in 0..N_PAIRING_PER_OP {
+            let pair_is_input =
+                gate.load_witness(ctx, Value::known(F::from((i < pairs.len()) as u64)));
+            self.assert_bit(ctx, ecc_chip, pair_is_input);
+
+            // the padding pair stands for the EVM input bytes of a pair not in the input.
+            let evm_pair = pairs
+                .get(i)
+                .copied()
+                .unwrap_or_else(EcPairingPair::ecc_padding);
+            // EVM input bytes of the pair in BE format, in the order [g1.x, g1.y, g2.x.c1,
+            // g2.x.c0, g2.y.c1, g2.y.c0].
+            let evm_cells = evm_pair
+                .to_bytes_be()
+                .iter()
+                .map(|&byte| gate.load_witness(ctx, Value::known(F::from(u64::from(byte)))))
+                .collect_vec();
 
-        let g2s = op
-            .pairs
-            .iter()
-            .map(|pair| {
-                let ec_point = pairing_chip.load_private_g2(ctx, Value::known(pair.g2_point));
-                let [x_c0_cells, x_c1_cells, y_c0_cells, y_c1_cells] =
-                    self.decompose_g2(pair.g2_point);
-                self.assert_crt_repr(
+            // the pair is replaced by the padding pair if it's not in the input or if one of its
+            // points is the identity, i.e. all its bytes are zero.
+            let [is_g1_identity, is_g2_identity] = [0..64, 64..N_BYTES_PER_PAIR].map(|range| {
+                let sum = gate.sum(
                     ctx,
-                    ecc_chip,
-                    &ec_point.x.coeffs[0],
-                    &x_c0_cells,
-                    powers_of_256,
+                    evm_cells[range]
+                        .iter()
+                        .map(|&cell| QuantumCell::Existing(cell)),
                 );
-                self.assert_crt_repr(
+                gate.is_zero(ctx, &sum)
+            });
+            let is_padding = {
+                let not_input = gate.not(ctx, QuantumCell::Existing(pair_is_input));
+                let is_identity = gate.or(
                     ctx,
-                    ecc_chip,
-                    &ec_point.x.coeffs[1],
-                    &x_c1_cells,
-                    powers_of_256,
+                    QuantumCell::Existing(is_g1_identity),
+                    QuantumCell::Existing(is_g2_identity),
                 );
-                self.assert_crt_repr(
+                gate.or(
                     ctx,
-                    ecc_chip,
-                    &ec_point.y.coeffs[0],
-                    &y_c0_cells,
-                    powers_of_256,
-                );
-                self.assert_crt_repr(
-                    ctx,
-                    ecc_chip,
-                    &ec_point.y.coeffs[1],
-                    &y_c1_cells,
-                    powers_of_256,
-                );
-                G2Decomposed {
-                    ec_point,
-                    x_c0_cells,
-                    x_c1_cells,
-                    y_c0_cells,
-                    y_c1_cells,
-                }
-            })
-            .collect_vec();
+                    QuantumCell::Existing(not_input),
+                    QuantumCell::Existing(is_identity),
+                )
+            };
+            let ecc_cells = evm_cells
+                .iter()
+                .zip_eq(padding_bytes.iter())
+                .map(|(&evm_cell, &padding_byte)| {
+                    gate.select(
+                        ctx,
+                        QuantumCell::Constant(F::from(u64::from(padding_byte))),
+                        QuantumCell::Existing(evm_cell),
+                        QuantumCell::Existing(is_padding),
+                    )
+                })
+                .collect_vec();
+            // cells of the k-th co-ordinate of the pair loaded in the Miller loop, in LE format.
+            let le_cells = |k: usize| {
+                ecc_cells[32 * k..32 * (k + 1)]
+                    .iter()
+                    .rev()
+                    .map(|&cell| QuantumCell::Existing(cell))
+                    .collect_vec()
+            };
 
-        log::trace!("[ECC] EcPairing g2s Assigned:");
+            let ecc_pair = evm_pair.ecc_pair();
+            let g1 = pairing_chip.load_private_g1(ctx, Value::known(ecc_pair.g1_point));
+            let g2 = pairing_chip.load_private_g2(ctx, Value::known(ecc_pair.g2_point));
+            for (crt_int, k) in [
+                (&g1.x, 0),
+                (&g1.y, 1),
+                (&g2.x.coeffs[1], 2),
+                (&g2.x.coeffs[0], 3),
+                (&g2.y.coeffs[1], 4),
+                (&g2.y.coeffs[0], 5),
+            ] {
+                self.assert_crt_repr(ctx, ecc_chip, crt_int, &le_cells(k), powers_of_256);
+            }
+
+            input_cells.push(
+                evm_cells
+                    .iter()
+                    .map(|&cell| QuantumCell::Existing(cell))
+                    .collect_vec(),
+            );
+            is_input.push(pair_is_input);
+            g1s.push(g1);
+            g2s.push(g2);
+        }
+
+        log::trace!("[ECC] EcPairing Chunk Inputs Assigned:");
         log_context_cursor!(ctx);
 
-        // RLC over the entire input bytes.
-        let input_cells = g1s
-            .iter()
-            .zip_eq(g2s.iter())
-            .flat_map(|(g1, g2)| {
-                std::iter::empty()
-                    .chain(g1.x_cells.iter().rev())
-                    .chain(g1.y_cells.iter().rev())
-                    .chain(g2.x_c1_cells.iter().rev())
-                    .chain(g2.x_c0_cells.iter().rev())
-                    .chain(g2.y_c1_cells.iter().rev())
-                    .chain(g2.y_c0_cells.iter().rev())
-                    .cloned()
-                    .collect::<Vec<QuantumCell<F>>>()
-            })
-            .collect::<Vec<QuantumCell<F>>>();
+        // acc = f if the chunk is the first chunk of the op, else prev_acc * f.
+        let acc = {
+            let f =
+                pairing_chip.multi_miller_loop(ctx, g1s.iter().zip_eq(g2s.iter()).collect_vec());
+            let acc = fp12_chip.mul(ctx, prev_acc, &f);
+            FieldExtPoint::construct(
+                f.coeffs
+                    .iter()
+                    .zip_eq(acc.coeffs.iter())
+                    .map(|(f, acc)| ecc_chip.field_chip().select(ctx, f, acc, &is_first))
+                    .collect_vec(),
+            )
+        };
 
-        log::trace!("[ECC] EcPairing Inputs RLC Assigned:");
+        log::trace!("[ECC] EcPairing Chunk Assignment END:");
         log_context_cursor!(ctx);
 
-        let pairs = g1s
-            .iter()
-            .zip(g2s.iter())
-            .map(|(g1, g2)| (&g1.ec_point, &g2.ec_point))
+        EcPairingChunkDecomposed {
+            is_first,
+            is_input,
+            input_cells,
+            acc,
+        }
+    }
+
+    /// Decomposes an EcPairing operation by selecting the accumulated Miller loop result of its
+    /// last chunk, and checks the final exponentiation of this result against the op output.
+    #[allow(clippy::too_many_arguments)]
+    fn decompose_ec_pairing_op(
+        &self,
+        ctx: &mut Context<F>,
+        ecc_chip: &EccChip<F, FpConfig<F, Fq>>,
+        pairing_chip: &PairingChip<F>,
+        fp12_chip: &Fp12Chip<F, FpConfig<F, Fq>, Fq12, XI_0>,
+        chunks: &[EcPairingChunkDecomposed<F>],
+        fq12_one: &FieldExtPoint<CRTInteger<F>>,
+        last_chunk: usize,
+        op: &EcPairingOp,
+    ) -> EcPairingDecomposed<F> {
+        log::trace!("[ECC] ==> EcPairing Assignment START:");
+        log_context_cursor!(ctx);
+
+        let gate = ecc_chip.field_chip().range().gate();
+
+        let chunk_selectors = (0..=chunks.len())
+            .map(|i| {
+                let selector =
+                    gate.load_witness(ctx, Value::known(F::from((i == last_chunk) as u64)));
+                self.assert_bit(ctx, ecc_chip, selector);
+                selector
+            })
             .collect_vec();
+        let n_selected = gate.sum(
+            ctx,
+            chunk_selectors
+                .iter()
+                .map(|&selector| QuantumCell::Existing(selector)),
+        );
+        gate.assert_equal(
+            ctx,
+            QuantumCell::Existing(n_selected),
+            QuantumCell::Constant(F::one()),
+        );
 
         let success = {
-            let gt = {
-                let gt = pairing_chip.multi_miller_loop(ctx, pairs);
-                pairing_chip.final_exp(ctx, &gt)
-            };
+            // Miller loop result of the op, one for an empty input.
+            let accs = chunks
+                .iter()
+                .map(|chunk| &chunk.acc)
+                .chain(iter::once(fq12_one))
+                .collect_vec();
+            let gt = FieldExtPoint::construct(
+                (0..fq12_one.coeffs.len())
+                    .map(|k| {
+                        let coeffs = accs.iter().map(|acc| acc.coeffs[k].clone()).collect_vec();
+                        ecc_chip
+                            .field_chip()
+                            .select_by_indicator(ctx, &coeffs, &chunk_selectors)
+                    })
+                    .collect_vec(),
+            );
+            let gt = pairing_chip.final_exp(ctx, &gt);
             // whether pairing check was successful.
-            let one = fp12_chip.load_constant(ctx, Fq12::one());
-            fp12_chip.is_equal(ctx, &gt, &one)
+            fp12_chip.is_equal(ctx, &gt, fq12_one)
         };
 
-        let op_output = ecc_chip.field_chip().range().gate().load_witness(
+        let op_output = gate.load_witness(
             ctx,
             Value::known(op.output.to_scalar().expect("EcPairing output = {0, 1}")),
         );
-        ecc_chip.field_chip().range().gate().assert_equal(
+        gate.assert_equal(
             ctx,
             QuantumCell::Existing(success),
             QuantumCell::Existing(op_output),
@@ -698,7 +886,7 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
         log_context_cursor!(ctx);
 
         EcPairingDecomposed {
-            input_cells,
+            chunk_selectors,
             success,
         }
     }
@@ -790,19 +978,71 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
         }
     }
 
+    /// Handles Phase2 for a Miller loop chunk of EcPairing operation and returns the RLC of the
+    /// EVM input bytes of its op, up to this chunk.
+    fn assign_ec_pairing_chunk(
+        &self,
+        ctx: &mut Context<F>,
+        ec_pairing_chunk_decomposed: &EcPairingChunkDecomposed<F>,
+        prev_rlc: Option<AssignedValue<F>>,
+        ecc_chip: &EccChip<F, FpConfig<F, Fq>>,
+        keccak_powers: &[QuantumCell<F>],
+    ) -> AssignedValue<F> {
+        let gate = ecc_chip.field_chip().range().gate();
+
+        // the RLC restarts from zero at the first chunk of an op.
+        let mut rlc = gate.select(
+            ctx,
+            QuantumCell::Constant(F::zero()),
+            prev_rlc.map_or(QuantumCell::Constant(F::zero()), QuantumCell::Existing),
+            QuantumCell::Existing(ec_pairing_chunk_decomposed.is_first),
+        );
+        for (&is_input, input_cells) in ec_pairing_chunk_decomposed
+            .is_input
+            .iter()
+            .zip_eq(ec_pairing_chunk_decomposed.input_cells.iter())
+        {
+            let pair_rlc = gate.inner_product(
+                ctx,
+                input_cells.iter().rev().cloned(),
+                keccak_powers[..N_BYTES_PER_PAIR].iter().cloned(),
+            );
+            let next_rlc = gate.mul_add(
+                ctx,
+                QuantumCell::Existing(rlc),
+                keccak_powers[N_BYTES_PER_PAIR].clone(),
+                QuantumCell::Existing(pair_rlc),
+            );
+            rlc = gate.select(
+                ctx,
+                QuantumCell::Existing(next_rlc),
+                QuantumCell::Existing(rlc),
+                QuantumCell::Existing(is_input),
+            );
+        }
+        rlc
+    }
+
     /// Handles Phase2 for EcPairing operation and returns the RLC'd input bytes.
     fn assign_ec_pairing(
         &self,
         ctx: &mut Context<F>,
         ec_pairing_decomposed: &EcPairingDecomposed<F>,
+        ec_pairing_chunk_rlcs: &[AssignedValue<F>],
         ecc_chip: &EccChip<F, FpConfig<F, Fq>>,
-        keccak_powers: &[QuantumCell<F>],
     ) -> EcPairingAssigned<F> {
         EcPairingAssigned {
+            // the RLC of an empty input is zero.
             input_rlc: ecc_chip.field_chip().range().gate().inner_product(
                 ctx,
-                ec_pairing_decomposed.input_cells.clone().into_iter().rev(),
-                keccak_powers.iter().cloned(),
+                ec_pairing_chunk_rlcs
+                    .iter()
+                    .map(|&rlc| QuantumCell::Existing(rlc))
+                    .chain(iter::once(QuantumCell::Constant(F::zero()))),
+                ec_pairing_decomposed
+                    .chunk_selectors
+                    .iter()
+                    .map(|&selector| QuantumCell::Existing(selector)),
             ),
             success: ec_pairing_decomposed.success,
         }
@@ -861,6 +1101,22 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
         }
     }
 
+    /// Constrain an assigned value to be boolean.
+    fn assert_bit(
+        &self,
+        ctx: &mut Context<F>,
+        ecc_chip: &EccChip<F, FpConfig<F, Fq>>,
+        x: AssignedValue<F>,
+    ) {
+        let gate = ecc_chip.field_chip().range().gate();
+        let x_squared = gate.mul(ctx, QuantumCell::Existing(x), QuantumCell::Existing(x));
+        gate.assert_equal(
+            ctx,
+            QuantumCell::Existing(x),
+            QuantumCell::Existing(x_squared),
+        );
+    }
+
     /// Decompose G1 element into cells representing its x and y co-ordinates.
     fn decompose_g1(&self, g1: G1Affine) -> (Vec<QuantumCell<F>>, Vec<QuantumCell<F>>) {
         (
@@ -875,32 +1131,6 @@ impl<F: Field, const XI_0: i64> EccCircuit<F, XI_0> {
         )
     }
 
-    /// Decompose G2 element into cells representing all coefficients of its x and y co-ordinates.
-    fn decompose_g2(&self, g2: G2Affine) -> [Vec<QuantumCell<F>>; 4] {
-        [
-            g2.x.c0
-                .to_bytes()
-                .iter()
-                .map(|&x| QuantumCell::Witness(Value::known(F::from(u64::from(x)))))
-                .collect_vec(),
-            g2.x.c1
-                .to_bytes()
-                .iter()
-                .map(|&x| QuantumCell::Witness(Value::known(F::from(u64::from(x)))))
-                .collect_vec(),
-            g2.y.c0
-                .to_bytes()
-                .iter()
-                .map(|&y| QuantumCell::Witness(Value::known(F::from(u64::from(y)))))
-                .collect_vec(),
-            g2.y.c1
-                .to_bytes()
-                .iter()
-                .map(|&y| QuantumCell::Witness(Value::known(F::from(u64::from(y)))))
-                .collect_vec(),
-        ]
-    }
-
     /// Assign a scalar field element and return its assigned state.
     fn assign_fr(
         &self,
@@ -921,6 +1151,7 @@ impl<F: Field, const XI_0: i64> SubCircuit<F> for EccCircuit<F, XI_0> {
             max_add_ops: block.circuits_params.max_ec_ops.ec_add,
             max_mul_ops: block.circuits_params.max_ec_ops.ec_mul,
             max_pairing_ops: block.circuits_params.max_ec_ops.ec_pairing,
+            max_pairing_chunks: block.circuits_params.max_ec_ops.ec_pairing_chunks,
             add_ops: block.get_ec_add_ops(),
             mul_ops: block.get_ec_mul_ops(),
             pairing_ops: block.get_ec_pairing_ops(),
@@ -966,6 +1197,11 @@ impl<F: Field, const XI_0: i64> SubCircuit<F> for EccCircuit<F, XI_0> {
         let ec_adds = block.get_ec_add_ops().len();
         let ec_muls = block.get_ec_mul_ops().len();
         let ec_pairings = block.get_ec_pairing_ops().len();
+        let ec_pairing_chunks = block
+            .get_ec_pairing_ops()
+            .iter()
+            .map(|op| op.n_chunks())
+            .sum::<usize>();
 
         // Instead of showing actual minimum row usage,
         // halo2-lib based circuits use min_row_num to represent a percentage of total-used capacity
@@ -974,6 +1210,7 @@ impl<F: Field, const XI_0: i64> SubCircuit<F> for EccCircuit<F, XI_0> {
             (row_num / block.circuits_params.max_ec_ops.ec_add) * ec_adds,
             (row_num / block.circuits_params.max_ec_ops.ec_mul) * ec_muls,
             (row_num / block.circuits_params.max_ec_ops.ec_pairing) * ec_pairings,
+            (row_num / block.circuits_params.max_ec_ops.ec_pairing_chunks) * ec_pairing_chunks,
         ]
         .into_iter()
         .max()
@@ -27,6 +27,7 @@ fn run<F: Field, const MUST_FAIL: bool>(
         max_add_ops: max_ec_ops.ec_add,
         max_mul_ops: max_ec_ops.ec_mul,
         max_pairing_ops: max_ec_ops.ec_pairing,
+        max_pairing_chunks: max_ec_ops.ec_pairing_chunks,
         add_ops,
         mul_ops,
         pairing_ops,
@@ -75,30 +76,30 @@ impl GenRand for EcMulOp {
     }
 }
 
+/// Generate `n_pairs` (G1, G2) pairs such that the pairing check is successful, i.e.
+/// e(-P, Q) * e(alpha.beta.G1, G2) == 1 for each couple of pairs.
+fn gen_pairing_pairs<R: RngCore + CryptoRng>(mut r: &mut R, n_pairs: usize) -> Vec<EcPairingPair> {
+    debug_assert_eq!(n_pairs % 2, 0);
+    (0..n_pairs / 2)
+        .flat_map(|_| {
+            let alpha = Fr::random(&mut r);
+            let beta = Fr::random(&mut r);
+            let point_p = G1Affine::from(G1Affine::generator() * alpha);
+            let point_p_negated = point_p.neg();
+            let point_q = G2Affine::from(G2Affine::generator() * beta);
+            let point_s = G1Affine::from(G1Affine::generator() * alpha * beta);
+            let point_t = G2Affine::generator();
+            [
+                EcPairingPair::new(point_p_negated, point_q),
+                EcPairingPair::new(point_s, point_t),
+            ]
+        })
+        .collect()
+}
+
 impl GenRand for EcPairingOp {
     fn gen_rand<R: RngCore + CryptoRng>(mut r: &mut R, is_neg: bool) -> Self {
-        let alpha = Fr::random(&mut r);
-        let beta = Fr::random(&mut r);
-        let point_p = G1Affine::from(G1Affine::generator() * alpha);
-        let point_p_negated = point_p.neg();
-        let point_q = G2Affine::from(G2Affine::generator() * beta);
-        let point_s = G1Affine::from(G1Affine::generator() * alpha * beta);
-        let point_t = G2Affine::generator();
-
-        let alpha = Fr::random(&mut r);
-        let beta = Fr::random(&mut r);
-        let point_a = G1Affine::from(G1Affine::generator() * alpha);
-        let point_a_negated = point_a.neg();
-        let point_b = G2Affine::from(G2Affine::generator() * beta);
-        let point_c = G1Affine::from(G1Affine::generator() * alpha * beta);
-        let point_d = G2Affine::generator();
-
-        let mut pairs = [
-            EcPairingPair::new(point_p_negated, point_q),
-            EcPairingPair::new(point_s, point_t),
-            EcPairingPair::new(point_a_negated, point_b),
-            EcPairingPair::new(point_c, point_d),
-        ];
+        let mut pairs = gen_pairing_pairs(&mut r, 4);
         let output = eth_types::U256::one();
 
         if is_neg {
@@ -159,6 +160,45 @@ fn test_ecc_circuit_negative() {
     )
 }
 
+#[test]
+fn test_ecc_circuit_pairing_chunks() {
+    use crate::ecc_circuit::util::LOG_TOTAL_NUM_ROWS;
+    use halo2_proofs::halo2curves::bn256::Fr;
+
+    let mut rng = rand::thread_rng();
+
+    // 6 pairs are split into 2 Miller loop chunks, and the empty input doesn't use any chunk.
+    let pairing_ops = vec![
+        EcPairingOp {
+            pairs: gen_pairing_pairs(&mut rng, 6),
+            output: eth_types::U256::one(),
+        },
+        EcPairingOp::default(),
+    ];
+
+    run::<Fr, false>(
+        LOG_TOTAL_NUM_ROWS,
+        PrecompileEcParams::default(),
+        vec![],
+        vec![],
+        pairing_ops.clone(),
+    );
+
+    // the pairing check of the first op fails once one of its points is changed.
+    let mut pairing_ops = pairing_ops;
+    pairing_ops[0].pairs[5].g1_point = pairing_ops[0].pairs[5]
+        .g1_point
+        .add(&G1Affine::generator())
+        .into();
+    run::<Fr, true>(
+        LOG_TOTAL_NUM_ROWS,
+        PrecompileEcParams::default(),
+        vec![],
+        vec![],
+        pairing_ops,
+    );
+}
+
 #[test]
 fn variadic_size_check() {
     use crate::ecc_circuit::util::LOG_TOTAL_NUM_ROWS;
@@ -172,6 +212,7 @@ fn variadic_size_check() {
         max_add_ops: default_params.ec_add,
         max_mul_ops: default_params.ec_mul,
         max_pairing_ops: default_params.ec_pairing,
+        max_pairing_chunks: default_params.ec_pairing_chunks,
         add_ops: gen(&mut rng, 25, false),
         mul_ops: gen(&mut rng, 20, false),
         pairing_ops: gen(&mut rng, 2, false),
@@ -183,6 +224,7 @@ fn variadic_size_check() {
         max_add_ops: default_params.ec_add,
         max_mul_ops: default_params.ec_mul,
         max_pairing_ops: default_params.ec_pairing,
+        max_pairing_chunks: default_params.ec_pairing_chunks,
         add_ops: gen(&mut rng, 20, false),
         mul_ops: gen(&mut rng, 15, false),
         pairing_ops: gen(&mut rng, 1, false),
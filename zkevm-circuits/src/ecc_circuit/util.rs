@@ -5,7 +5,7 @@ use halo2_ecc::{bigint::CRTInteger, ecc::EcPoint, fields::FieldExtPoint};
 // Total number of rows allowable for ECC circuit
 pub const LOG_TOTAL_NUM_ROWS: u32 = 20;
 
-// Cell usage accounting for EcAdd, EcMul and EcPairing
+// Cell usage accounting for EcAdd, EcMul and EcPairing (with a single Miller loop chunk)
 // Roud up to nearest 100
 pub(super) const EC_ADD_CELLS: usize = 6_900; // actual: 6_851
 pub(super) const EC_MUL_CELLS: usize = 405_500; // actual: 405_476
@@ -37,20 +37,6 @@ pub(super) struct ScalarAssigned<F: Field> {
     pub scalar: CRTInteger<F>,
 }
 
-/// Decomposed state of a G2 curve point.
-pub(super) struct G2Decomposed<F: Field> {
-    /// The assigned EcPoint.
-    pub ec_point: EcPoint<F, FieldExtPoint<CRTInteger<F>>>,
-    /// Cells for the coeff 0 of x-coordinate of the G2 curve point in LE format.
-    pub x_c0_cells: Vec<QuantumCell<F>>,
-    /// Cells for the coeff 1 of x-coordinate of the G2 curve point in LE format.
-    pub x_c1_cells: Vec<QuantumCell<F>>,
-    /// Cells for the coeff 0 of y-coordinate of the G2 curve point in LE format.
-    pub y_c0_cells: Vec<QuantumCell<F>>,
-    /// Cells for the coeff 1 of y-coordinate of the G2 curve point in LE format.
-    pub y_c1_cells: Vec<QuantumCell<F>>,
-}
-
 /// State of EcAdd operation post first phase.
 pub(super) struct EcAddDecomposed<F: Field> {
     pub point_p: G1Decomposed<F>,
@@ -79,9 +65,23 @@ pub(super) struct EcMulAssigned<F: Field> {
     pub point_r: G1Assigned<F>,
 }
 
+/// State of a Miller loop chunk of EcPairing operations post first phase.
+pub(super) struct EcPairingChunkDecomposed<F: Field> {
+    /// Whether the chunk is the first chunk of an EcPairing operation.
+    pub is_first: AssignedValue<F>,
+    /// Whether each pair of the chunk is part of the EVM input.
+    pub is_input: Vec<AssignedValue<F>>,
+    /// Cells for the EVM input bytes of each pair in BE format.
+    pub input_cells: Vec<Vec<QuantumCell<F>>>,
+    /// Miller loop result accumulated over the chunks of the EcPairing operation.
+    pub acc: FieldExtPoint<CRTInteger<F>>,
+}
+
 /// State of EcPairing operation post first phase.
 pub(super) struct EcPairingDecomposed<F: Field> {
-    pub input_cells: Vec<QuantumCell<F>>,
+    /// Indicator of the last chunk of the operation, the extra last indicator being for an empty
+    /// input.
+    pub chunk_selectors: Vec<AssignedValue<F>>,
     pub success: AssignedValue<F>,
 }
 
@@ -21,8 +21,9 @@ pub use crate::witness;
 use crate::{
     evm_circuit::param::{MAX_STEP_HEIGHT, STEP_STATE_HEIGHT},
     table::{
-        BlockTable, BytecodeTable, CopyTable, EccTable, ExpTable, KeccakTable, LookupTable,
-        ModExpTable, PowOfRandTable, RwTable, SigTable, TxTable,
+        Blake2fTable, BlockTable, BytecodeTable, CopyTable, EccTable, ExpTable, KeccakTable,
+        LookupTable, ModExpTable, PowOfRandTable, Ripemd160Table, RwTable, Sha256Table, SigTable,
+        TxTable,
     },
     util::{SubCircuit, SubCircuitConfig},
 };
@@ -51,6 +52,9 @@ pub struct EvmCircuitConfig<F> {
     sig_table: SigTable,
     modexp_table: ModExpTable,
     ecc_table: EccTable,
+    sha256_table: Sha256Table,
+    ripemd160_table: Ripemd160Table,
+    blake2f_table: Blake2fTable,
     pow_of_rand_table: PowOfRandTable,
 }
 
@@ -78,6 +82,12 @@ pub struct EvmCircuitConfigArgs<F: Field> {
     pub modexp_table: ModExpTable,
     /// Ecc Table.
     pub ecc_table: EccTable,
+    /// Sha256Table
+    pub sha256_table: Sha256Table,
+    /// Ripemd160Table
+    pub ripemd160_table: Ripemd160Table,
+    /// Blake2fTable
+    pub blake2f_table: Blake2fTable,
     // Power of Randomness Table.
     pub pow_of_rand_table: PowOfRandTable,
 }
@@ -108,6 +118,9 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             sig_table,
             modexp_table,
             ecc_table,
+            sha256_table,
+            ripemd160_table,
+            blake2f_table,
             pow_of_rand_table,
         }: Self::ConfigArgs,
     ) -> Self {
@@ -128,6 +141,9 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             &sig_table,
             &modexp_table,
             &ecc_table,
+            &sha256_table,
+            &ripemd160_table,
+            &blake2f_table,
             &pow_of_rand_table,
         ));
 
@@ -145,6 +161,9 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
         sig_table.annotate_columns(meta);
         modexp_table.annotate_columns(meta);
         ecc_table.annotate_columns(meta);
+        sha256_table.annotate_columns(meta);
+        ripemd160_table.annotate_columns(meta);
+        blake2f_table.annotate_columns(meta);
         pow_of_rand_table.annotate_columns(meta);
 
         Self {
@@ -161,6 +180,9 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             sig_table,
             modexp_table,
             ecc_table,
+            sha256_table,
+            ripemd160_table,
+            blake2f_table,
             pow_of_rand_table,
         }
     }
@@ -440,8 +462,11 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
         let keccak_table = KeccakTable::construct(meta);
         let exp_table = ExpTable::construct(meta);
         let sig_table = SigTable::construct(meta);
-        let modexp_table = ModExpTable::construct(meta);
+        let modexp_table = ModExpTable::construct(meta, &challenges_expr);
         let ecc_table = EccTable::construct(meta);
+        let sha256_table = Sha256Table::construct(meta);
+        let ripemd160_table = Ripemd160Table::construct(meta);
+        let blake2f_table = Blake2fTable::construct(meta);
         let pow_of_rand_table = PowOfRandTable::construct(meta, &challenges_expr);
         (
             EvmCircuitConfig::new(
@@ -458,6 +483,9 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
                     sig_table,
                     modexp_table,
                     ecc_table,
+                    sha256_table,
+                    ripemd160_table,
+                    blake2f_table,
                     pow_of_rand_table,
                 },
             ),
@@ -508,7 +536,7 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
             .dev_load(&mut layouter, block, &challenges)?;
         config
             .modexp_table
-            .dev_load(&mut layouter, &block.get_big_modexp())?;
+            .dev_load(&mut layouter, &block.get_big_modexp(), &challenges)?;
         config.ecc_table.dev_load(
             &mut layouter,
             block.circuits_params.max_ec_ops,
@@ -517,6 +545,21 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
             &block.get_ec_pairing_ops(),
             &challenges,
         )?;
+        config.sha256_table.dev_load(
+            &mut layouter,
+            block.get_sha256().iter().map(|event| &event.input),
+            &challenges,
+        )?;
+        config.ripemd160_table.dev_load(
+            &mut layouter,
+            block.get_ripemd160().iter().map(|event| &event.input),
+            &challenges,
+        )?;
+        config.blake2f_table.dev_load(
+            &mut layouter,
+            block.get_blake2f().iter().map(|event| &event.input),
+            &challenges,
+        )?;
 
         self.synthesize_sub(&config, &challenges, &mut layouter)
     }
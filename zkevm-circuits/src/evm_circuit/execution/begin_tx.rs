@@ -1,12 +1,15 @@
 use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
-        param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS, N_BYTES_U64, N_BYTES_WORD},
+        param::{
+            N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS, N_BYTES_U64, N_BYTES_WORD, SET_CODE_RW_DELTA,
+        },
         step::ExecutionState,
         util::{
             and,
             common_gadget::{
-                TransferGadgetInfo, TransferWithGasFeeGadget, TxL1FeeGadget, TxL1MsgGadget,
+                DelegateAccess, DelegationGadget, HardforkGadget, TransferGadgetInfo,
+                TransferWithGasFeeGadget, TxL1FeeGadget, TxL1MsgGadget,
             },
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
@@ -27,17 +30,11 @@ use crate::{
     },
 };
 use bus_mapping::circuit_input_builder::CopyDataType;
-use eth_types::{Address, Field, ToLittleEndian, ToScalar, U256};
+use eth_types::{evm_types::Hardfork, Address, Field, ToLittleEndian, ToScalar, U256};
 use ethers_core::utils::{get_contract_address, keccak256, rlp::RlpStream};
 use gadgets::util::{expr_from_bytes, not, or, select, Expr};
 use halo2_proofs::{circuit::Value, plonk::Error};
 
-// For Shanghai, EIP-3651 (Warm COINBASE) adds 1 write op for coinbase.
-#[cfg(feature = "shanghai")]
-const SHANGHAI_RW_DELTA: u8 = 1;
-#[cfg(not(feature = "shanghai"))]
-const SHANGHAI_RW_DELTA: u8 = 0;
-
 const PRECOMPILE_COUNT: usize = 9;
 
 #[derive(Clone, Debug)]
@@ -82,12 +79,25 @@ pub(crate) struct BeginTxGadget<F> {
     /// RLP gadget for CREATE address.
     create: ContractCreateGadget<F, false>,
     is_caller_callee_equal: Cell<F>,
+    // Hard fork of the block, which selects EIP-3651 and EIP-3860 of Shanghai
+    hardfork: HardforkGadget<F>,
     // EIP-3651 (Warm COINBASE) for Shanghai
     coinbase: Cell<F>,
     // Caller, callee and a list addresses are added to the access list before
     // coinbase, and may be duplicate.
     // <https://github.com/ethereum/go-ethereum/blob/604e215d1bb070dff98fb76aa965064c74e3633f/core/state/statedb.go#LL1119C9-L1119C9>
     is_coinbase_warm: Cell<F>,
+    // EIP-2930 access list, warmed up by the tx circuit lookups into the rw
+    // table right after the coinbase.
+    access_list_addresses_len: Cell<F>,
+    access_list_storage_keys_len: Cell<F>,
+    access_list_rw_counter: Cell<F>,
+    // EIP-7702 authorization list, applied right after the access list by the
+    // tx circuit lookups into the rw table.
+    authorization_list_len: Cell<F>,
+    authorization_list_authorities_len: Cell<F>,
+    // EIP-7702 delegation of the callee.
+    delegation: DelegationGadget<F>,
     tx_l1_fee: TxL1FeeGadget<F>,
     tx_l1_msg: TxL1MsgGadget<F>,
 }
@@ -116,6 +126,21 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 TxContextFieldTag::TxDataGasCost,
             ]
             .map(|field_tag| cb.tx_context(tx_id.expr(), field_tag, None));
+        let [access_list_addresses_len, access_list_storage_keys_len, access_list_rw_counter] = [
+            TxContextFieldTag::AccessListAddressesLen,
+            TxContextFieldTag::AccessListStorageKeysLen,
+            TxContextFieldTag::AccessListRwCounter,
+        ]
+        .map(|field_tag| cb.tx_context(tx_id.expr(), field_tag, None));
+        let access_list_len =
+            access_list_addresses_len.expr() + access_list_storage_keys_len.expr();
+        let [authorization_list_len, authorization_list_authorities_len] = [
+            TxContextFieldTag::AuthorizationListLen,
+            TxContextFieldTag::AuthorizationListAuthoritiesLen,
+        ]
+        .map(|field_tag| cb.tx_context(tx_id.expr(), field_tag, None));
+        let set_code_rw_delta =
+            authorization_list_authorities_len.expr() * SET_CODE_RW_DELTA.expr();
 
         let is_call_data_empty = IsZeroGadget::construct(cb, tx_call_data_length.expr());
 
@@ -226,22 +251,20 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         let tx_call_data_word_length =
             ConstantDivisionGadget::construct(cb, tx_call_data_length.expr() + 31.expr(), 32);
 
-        // TODO1: Take gas cost of access list (EIP 2930) into consideration.
+        let hardfork = HardforkGadget::construct(cb);
+        let is_shanghai = hardfork.is_active(Hardfork::Shanghai);
+
         // Use intrinsic gas
-        // TODO2: contrain calling precompile directly
+        // TODO: contrain calling precompile directly
 
         let intrinsic_gas_cost = cb.query_cell();
         cb.condition(not::expr(is_precompile.expr()), |cb| {
             // Calculate gas cost of init code only for EIP-3860 of Shanghai.
-            #[cfg(feature = "shanghai")]
             let init_code_gas_cost = select::expr(
                 tx_is_create.expr(),
-                tx_call_data_word_length.quotient().expr()
-                    * eth_types::evm_types::INIT_CODE_WORD_GAS.expr(),
+                tx_call_data_word_length.quotient().expr() * hardfork.init_code_word_gas(),
                 0.expr(),
             );
-            #[cfg(not(feature = "shanghai"))]
-            let init_code_gas_cost = 0.expr();
 
             cb.require_equal(
                 "calculate intrinsic gas cost",
@@ -251,7 +274,13 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     eth_types::evm_types::GasCost::CREATION_TX.expr(),
                     eth_types::evm_types::GasCost::TX.expr(),
                 ) + tx_call_data_gas_cost.expr()
-                    + init_code_gas_cost,
+                    + init_code_gas_cost
+                    + access_list_addresses_len.expr()
+                        * eth_types::evm_types::GasCost::ACCESS_LIST_ADDRESS.expr()
+                    + access_list_storage_keys_len.expr()
+                        * eth_types::evm_types::GasCost::ACCESS_LIST_STORAGE_KEY.expr()
+                    + authorization_list_len.expr()
+                        * eth_types::evm_types::GasCost::PER_EMPTY_ACCOUNT.expr(),
             )
         });
         // Check gas_left is sufficient
@@ -294,14 +323,30 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             coinbase.expr(),
         );
 
-        #[cfg(feature = "shanghai")]
-        cb.account_access_list_write(
-            tx_id.expr(),
-            coinbase.expr(),
-            1.expr(),
-            is_coinbase_warm.expr(),
-            None,
-        ); // rwc_delta += 1
+        cb.condition(is_shanghai.expr(), |cb| {
+            cb.account_access_list_write(
+                tx_id.expr(),
+                coinbase.expr(),
+                1.expr(),
+                is_coinbase_warm.expr(),
+                None,
+            );
+        }); // rwc_delta += is_shanghai
+
+        // Warm up the addresses and storage keys of the access list. The writes
+        // are looked up by the tx circuit starting at `access_list_rw_counter`,
+        // so only their rw counters are reserved here.
+        cb.require_equal(
+            "access list rw counter",
+            access_list_rw_counter.expr(),
+            cb.curr.state.rw_counter.expr() + cb.rw_counter_offset(),
+        );
+        cb.reserve_rw_counters(access_list_len.clone()); // rwc_delta += access_list_len
+
+        // Apply the authorizations of an EIP-7702 tx whose authority is recovered.
+        // The tx circuit looks up their writes right after the access list, so
+        // only their rw counters are reserved here.
+        cb.reserve_rw_counters(set_code_rw_delta.clone()); // rwc_delta += set_code_rw_delta
 
         let account_code_hash = cb.query_cell_phase2();
         let account_code_hash_is_empty =
@@ -326,6 +371,17 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 account_code_hash.expr(),
             ); // rwc_delta += 1
         });
+        // A delegated callee executes the code of its delegate, which is warmed up
+        // like the other entries of the access list. The code hash of a
+        // precompile or a created account is zero, so it's never delegated.
+        let delegation = DelegationGadget::construct(
+            cb,
+            account_code_hash.expr(),
+            Some(DelegateAccess::WarmUp(tx_id.expr(), None)),
+        );
+        let code_hash = delegation.executed_code_hash(account_code_hash.expr());
+        let code_hash_is_empty_or_zero =
+            delegation.executed_no_code(account_code_hash_is_empty_or_zero.expr());
 
         // Transfer value from caller to callee, creating account if necessary.
         let transfer_with_gas_fee = TransferWithGasFeeGadget::construct(
@@ -421,6 +477,13 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 0.expr(),
                 Some(&mut reversion_info),
             );
+            cb.account_created_write(
+                tx_id.expr(),
+                call_callee_address.expr(),
+                1.expr(),
+                0.expr(),
+                Some(&mut reversion_info),
+            );
             for (field_tag, value) in [
                 (CallContextFieldTag::Depth, 1.expr()),
                 (CallContextFieldTag::CallerAddress, tx_caller_address.expr()),
@@ -446,7 +509,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             }
 
             cb.require_step_state_transition(StepStateTransition {
-                // 21 + a reads and writes:
+                // 23 + a reads and writes:
                 //   - a TxL1FeeGadget
                 //   - Write CallContext TxId
                 //   - Write CallContext RwCounterEndOfReversion
@@ -457,9 +520,12 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 //   - Write TxAccessListAccount (Caller)
                 //   - Write TxAccessListAccount (Callee)
                 //   - Write TxAccessListAccount (Coinbase) only for Shanghai
+                //   - Write TxAccessListAccount (Storage) x access list entries
+                //   - SET_CODE_RW_DELTA x recovered authorizations
                 //   - Read Account CodeHash
                 //   - a TransferWithGasFeeGadget
                 //   - Write Account (Callee) Nonce (Reversible)
+                //   - Write TxCreatedAccount (Callee) (Reversible)
                 //   - Write CallContext Depth
                 //   - Write CallContext CallerAddress
                 //   - Write CallContext CalleeAddress
@@ -474,21 +540,24 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 //   - Write CallContext IsCreate
                 //   - Write CallContext CodeHash
                 rw_counter: Delta(
-                    22.expr()
+                    23.expr()
                         + l1_rw_delta.expr()
                         + transfer_with_gas_fee.rw_delta()
-                        + SHANGHAI_RW_DELTA.expr()
-                        + PRECOMPILE_COUNT.expr(),
+                        + is_shanghai.expr()
+                        + PRECOMPILE_COUNT.expr()
+                        + access_list_len.clone()
+                        + set_code_rw_delta.clone(),
                 ),
                 call_id: To(call_id.expr()),
                 is_root: To(true.expr()),
                 is_create: To(tx_is_create.expr()),
                 code_hash: To(cb.curr.state.code_hash.expr()),
                 gas_left: To(gas_left.clone()),
-                // There are a + 1 reversible writes:
+                // There are a + 2 reversible writes:
                 //  - a TransferWithGasFeeGadget
                 //  - Callee Account Nonce
-                reversible_write_counter: To(transfer_with_gas_fee.reversible_w_delta() + 1.expr()),
+                //  - Callee TxCreatedAccount
+                reversible_write_counter: To(transfer_with_gas_fee.reversible_w_delta() + 2.expr()),
                 log_id: To(0.expr()),
                 ..StepStateTransition::new_context()
             });
@@ -527,14 +596,18 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 //   - Write TxAccessListAccount (Caller)
                 //   - Write TxAccessListAccount (Callee)
                 //   - Write TxAccessListAccount (Coinbase) only for Shanghai
+                //   - Write TxAccessListAccount (Storage) x access list entries
+                //   - SET_CODE_RW_DELTA x recovered authorizations
                 //   - a TxL1FeeGadget
                 //   - a TransferWithGasFeeGadget
                 rw_counter: Delta(
                     7.expr()
                         + l1_rw_delta.expr()
                         + transfer_with_gas_fee.rw_delta()
-                        + SHANGHAI_RW_DELTA.expr()
+                        + is_shanghai.expr()
                         + PRECOMPILE_COUNT.expr()
+                        + access_list_len.clone()
+                        + set_code_rw_delta.clone()
                         // TRICKY:
                         // Process the reversion only for Precompile in begin TX. Since no
                         // associated opcodes could process reversion afterwards
@@ -553,7 +626,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         cb.condition(
             and::expr([
                 not::expr(tx_is_create.expr()),
-                account_code_hash_is_empty_or_zero.expr(),
+                code_hash_is_empty_or_zero.expr(),
                 not::expr(is_precompile.expr()),
             ]),
             |cb| {
@@ -579,15 +652,21 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write TxAccessListAccount (Caller)
                     //   - Write TxAccessListAccount (Callee)
                     //   - Write TxAccessListAccount (Coinbase) only for Shanghai
+                    //   - Write TxAccessListAccount (Storage) x access list entries
+                    //   - SET_CODE_RW_DELTA x recovered authorizations
                     //   - Read Account CodeHash
+                    //   - Read Account CodeHash of the delegate if delegated
                     //   - a TxL1FeeGadget
                     //   - a TransferWithGasFeeGadget
                     rw_counter: Delta(
                         8.expr()
                             + l1_rw_delta.expr()
                             + transfer_with_gas_fee.rw_delta()
-                            + SHANGHAI_RW_DELTA.expr()
-                            + PRECOMPILE_COUNT.expr(),
+                            + is_shanghai.expr()
+                            + PRECOMPILE_COUNT.expr()
+                            + access_list_len.clone()
+                            + set_code_rw_delta.clone()
+                            + delegation.rw_delta(),
                     ),
                     call_id: To(call_id.expr()),
                     ..StepStateTransition::any()
@@ -599,7 +678,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         cb.condition(
             and::expr([
                 not::expr(tx_is_create.expr()),
-                not::expr(account_code_hash_is_empty_or_zero),
+                not::expr(code_hash_is_empty_or_zero),
             ]),
             |cb| {
                 // Setup first call's context.
@@ -622,7 +701,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     (CallContextFieldTag::LastCalleeReturnDataLength, 0.expr()),
                     (CallContextFieldTag::IsRoot, 1.expr()),
                     (CallContextFieldTag::IsCreate, tx_is_create.expr()),
-                    (CallContextFieldTag::CodeHash, account_code_hash.expr()),
+                    (CallContextFieldTag::CodeHash, code_hash.expr()),
                 ] {
                     cb.call_context_lookup(true.expr(), Some(call_id.expr()), field_tag, value);
                 }
@@ -639,7 +718,10 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write TxAccessListAccount (Caller)
                     //   - Write TxAccessListAccount (Callee)
                     //   - Write TxAccessListAccount (Coinbase) only for Shanghai
+                    //   - Write TxAccessListAccount (Storage) x access list entries
+                    //   - SET_CODE_RW_DELTA x recovered authorizations
                     //   - Read Account CodeHash
+                    //   - Read Account CodeHash of the delegate if delegated
                     //   - a TransferWithGasFeeGadget
                     //   - Write CallContext Depth
                     //   - Write CallContext CallerAddress
@@ -658,13 +740,16 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                         21.expr()
                             + l1_rw_delta.expr()
                             + transfer_with_gas_fee.rw_delta()
-                            + SHANGHAI_RW_DELTA.expr()
-                            + PRECOMPILE_COUNT.expr(),
+                            + is_shanghai.expr()
+                            + PRECOMPILE_COUNT.expr()
+                            + access_list_len
+                            + set_code_rw_delta
+                            + delegation.rw_delta(),
                     ),
                     call_id: To(call_id.expr()),
                     is_root: To(true.expr()),
                     is_create: To(tx_is_create.expr()),
-                    code_hash: To(account_code_hash.expr()),
+                    code_hash: To(code_hash),
                     gas_left: To(gas_left),
                     reversible_write_counter: To(transfer_with_gas_fee.reversible_w_delta()),
                     log_id: To(0.expr()),
@@ -711,8 +796,15 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             keccak_code_hash,
             create,
             is_caller_callee_equal,
+            hardfork,
             coinbase,
             is_coinbase_warm,
+            access_list_addresses_len,
+            access_list_storage_keys_len,
+            access_list_rw_counter,
+            authorization_list_len,
+            authorization_list_authorities_len,
+            delegation,
             tx_l1_fee,
             tx_l1_msg,
         }
@@ -801,10 +893,13 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
 
         rws.offset_add(PRECOMPILE_COUNT + 2);
 
-        #[cfg(feature = "shanghai")]
-        let is_coinbase_warm = rws.next().tx_access_list_value_pair().1;
-        #[cfg(not(feature = "shanghai"))]
-        let is_coinbase_warm = false;
+        let hardfork = block.context.ctxs[&tx.block_number].hardfork;
+        let is_coinbase_warm =
+            hardfork.is_coinbase_warm() && rws.next().tx_access_list_value_pair().1;
+
+        let (access_list_addresses_len, access_list_storage_keys_len) = tx.access_list_len();
+        rws.offset_add(access_list_addresses_len + access_list_storage_keys_len);
+        rws.offset_add(tx.applied_authorizations.len() * SET_CODE_RW_DELTA);
 
         let is_precompile = is_precompiled(&tx.callee_address.unwrap_or_default());
         let account_code_hash = if !is_precompile {
@@ -812,6 +907,8 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         } else {
             zero
         };
+        self.delegation
+            .assign_from_rws(region, offset, block, account_code_hash, &mut rws)?;
         let transfer_assign_result = self.transfer_with_gas_fee.assign_from_rws(
             region,
             offset,
@@ -1027,6 +1124,22 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         )?;
         self.is_coinbase_warm
             .assign(region, offset, Value::known(F::from(is_coinbase_warm)))?;
+        self.hardfork.assign(region, offset, hardfork)?;
+        for (cell, value) in [
+            (&self.access_list_addresses_len, access_list_addresses_len),
+            (
+                &self.access_list_storage_keys_len,
+                access_list_storage_keys_len,
+            ),
+            (&self.access_list_rw_counter, tx.access_list_rw_counter),
+            (&self.authorization_list_len, tx.authorization_list.len()),
+            (
+                &self.authorization_list_authorities_len,
+                tx.applied_authorizations.len(),
+            ),
+        ] {
+            cell.assign(region, offset, Value::known(F::from(value as u64)))?;
+        }
 
         let tx_l1_fee = if tx.tx_type.is_l1_msg() {
             log::trace!("tx is l1msg and l1 fee is 0");
@@ -1387,6 +1500,127 @@ mod test {
         CircuitTestBuilder::new_from_test_ctx(ctx).run();
     }
 
+    /// BeginTx of an EIP-2930 tx whose access list contains a duplicate
+    /// address and storage key, as well as the caller, the callee and a
+    /// precompile, which are already warm.
+    #[test]
+    fn begin_tx_access_list() {
+        use eth_types::{geth_types::EIP2930_TX_TYPE, ToWord};
+        use ethers_core::types::transaction::eip2930::{AccessList, AccessListItem};
+        use ethers_signers::Signer;
+        use mock::MOCK_WALLETS;
+
+        let caller = MOCK_WALLETS[0].address();
+        let precompile = address!("0x0000000000000000000000000000000000000004");
+        let access_list = AccessList(vec![
+            AccessListItem {
+                address: MOCK_ACCOUNTS[1],
+                storage_keys: vec![Hash::from_low_u64_be(2)],
+            },
+            AccessListItem {
+                address: MOCK_ACCOUNTS[1],
+                storage_keys: vec![Hash::from_low_u64_be(2), Hash::from_low_u64_be(3)],
+            },
+            AccessListItem {
+                address: caller,
+                storage_keys: vec![],
+            },
+            AccessListItem {
+                address: MOCK_ACCOUNTS[0],
+                storage_keys: vec![Hash::from_low_u64_be(1)],
+            },
+            AccessListItem {
+                address: precompile,
+                storage_keys: vec![],
+            },
+        ]);
+
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(eth(10))
+                    .code(bytecode! {
+                        PUSH1(1)
+                        SLOAD
+                        POP
+                        PUSH20(MOCK_ACCOUNTS[1].to_word())
+                        BALANCE
+                        POP
+                        STOP
+                    });
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+                accs[2].address(caller).balance(eth(10));
+            },
+            |mut txs, _accs| {
+                txs[0]
+                    .transaction_type(EIP2930_TX_TYPE as u64)
+                    .from(MOCK_WALLETS[0].clone())
+                    .to(MOCK_ACCOUNTS[0])
+                    .access_list(access_list);
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    /// BeginTx of an EIP-7702 tx whose callee is delegated by one of its
+    /// authorizations. The geth tracer doesn't support EIP-7702, so it's
+    /// traced with the Rust tracer.
+    #[cfg(feature = "rust-tracer")]
+    #[test]
+    fn begin_tx_authorizations() {
+        use eth_types::geth_types::{SetCodeAuthorization, EIP7702_TX_TYPE};
+        use ethers_signers::Signer;
+        use mock::{MOCK_CHAIN_ID, MOCK_WALLETS};
+
+        let (new_authority, authority) = (&MOCK_WALLETS[1], &MOCK_WALLETS[2]);
+        let sign = |wallet, chain_id: u64, address| {
+            SetCodeAuthorization::new_signed(chain_id.into(), address, 0.into(), wallet)
+        };
+        let authorization_list = vec![
+            // signed for another chain
+            sign(new_authority, *MOCK_CHAIN_ID + 1, MOCK_ACCOUNTS[1]),
+            // delegates a new account to the code of MOCK_ACCOUNTS[0]
+            sign(new_authority, *MOCK_CHAIN_ID, MOCK_ACCOUNTS[0]),
+            // delegates an existing account on any chain, which is refunded
+            sign(authority, 0, MOCK_ACCOUNTS[0]),
+            // its nonce has been increased by the previous authorization
+            sign(authority, *MOCK_CHAIN_ID, MOCK_ACCOUNTS[1]),
+        ];
+
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(eth(10))
+                    .code(bytecode! {
+                        ADDRESS
+                        SLOAD
+                        POP
+                        STOP
+                    });
+                accs[1].address(MOCK_WALLETS[0].address()).balance(eth(10));
+                accs[2].address(authority.address()).balance(eth(10));
+            },
+            |mut txs, _accs| {
+                txs[0]
+                    .transaction_type(EIP7702_TX_TYPE as u64)
+                    .from(MOCK_WALLETS[0].clone())
+                    .to(new_authority.address())
+                    .authorization_list(authorization_list);
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
     /// testool case EmptyTransaction3_d0_g0_v0
     #[test]
     fn begin_tx_create_empty_tx() {
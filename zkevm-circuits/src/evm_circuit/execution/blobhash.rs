@@ -0,0 +1,156 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            common_gadget::{HardforkGadget, SameContextGadget, WordByteCapGadget},
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
+                Transition::Delta,
+            },
+            CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{CallContextFieldTag, TxContextFieldTag},
+    util::Expr,
+};
+use bus_mapping::evm::OpcodeId;
+use eth_types::{evm_types::block_utils::MAX_BLOBS_PER_BLOCK, Field};
+use gadgets::util::not;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+#[derive(Clone, Debug)]
+pub(crate) struct BlobHashGadget<F> {
+    same_context: SameContextGadget<F>,
+    hardfork: HardforkGadget<F>,
+    tx_id: Cell<F>,
+    index: WordByteCapGadget<F, 1>,
+    blob_hash: Cell<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for BlobHashGadget<F> {
+    const NAME: &'static str = "BLOBHASH";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::BLOBHASH;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // BLOBHASH is activated in Cancun (EIP-4844).
+        let hardfork = HardforkGadget::construct(cb);
+        hardfork.require_enabled(cb, OpcodeId::BLOBHASH);
+
+        // Lookup in call_ctx the TxId
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+
+        let index = WordByteCapGadget::construct(cb, MAX_BLOBS_PER_BLOCK.expr());
+        cb.stack_pop(index.original_word());
+
+        // The tx table has a BlobVersionedHash row for every blob slot, so
+        // any index below the cap can be looked up.
+        let blob_hash = cb.query_cell_phase2();
+        cb.condition(index.lt_cap(), |cb| {
+            cb.tx_context_lookup(
+                tx_id.expr(),
+                TxContextFieldTag::BlobVersionedHash,
+                Some(index.valid_value()),
+                blob_hash.expr(),
+            );
+        });
+        cb.condition(not::expr(index.lt_cap()), |cb| {
+            cb.require_zero("Out of range blob index", blob_hash.expr());
+        });
+
+        cb.stack_push(blob_hash.expr());
+
+        let step_state_transition = StepStateTransition {
+            rw_counter: Delta(3.expr()),
+            program_counter: Delta(1.expr()),
+            gas_left: Delta(-OpcodeId::BLOBHASH.constant_gas_cost().expr()),
+            ..Default::default()
+        };
+        let opcode = cb.query_cell();
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+
+        Self {
+            same_context,
+            hardfork,
+            tx_id,
+            index,
+            blob_hash,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)?;
+        self.hardfork.assign(
+            region,
+            offset,
+            block.context.ctxs[&tx.block_number].hardfork,
+        )?;
+
+        let index = block.rws[step.rw_indices[1]].stack_value();
+        let blob_hash = block.rws[step.rw_indices[2]].stack_value();
+
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id as u64)))?;
+        self.index
+            .assign(region, offset, index, F::from(MAX_BLOBS_PER_BLOCK as u64))?;
+        self.blob_hash
+            .assign(region, offset, region.word_rlc(blob_hash))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "cancun"))]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{bytecode, Hash, Word};
+    use mock::test_ctx::{helpers::*, TestContext};
+
+    fn test_ok(index: Word, blob_hashes: Vec<Hash>) {
+        let bytecode = bytecode! {
+            PUSH32(index)
+            BLOBHASH
+            STOP
+        };
+
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode),
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .blob_versioned_hashes(blob_hashes);
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn blobhash_gadget_in_range() {
+        let blob_hashes = vec![Hash::from_low_u64_be(0x11), Hash::from_low_u64_be(0x22)];
+        test_ok(Word::zero(), blob_hashes.clone());
+        test_ok(Word::one(), blob_hashes);
+    }
+
+    #[test]
+    fn blobhash_gadget_out_of_range() {
+        let blob_hashes = vec![Hash::from_low_u64_be(0x11)];
+        test_ok(Word::one(), blob_hashes.clone());
+        test_ok(Word::from(6), blob_hashes.clone());
+        test_ok(Word::MAX, blob_hashes);
+    }
+}
@@ -5,7 +5,10 @@ use crate::{
         step::ExecutionState,
         util::{
             and,
-            common_gadget::{CommonCallGadget, TransferGadget, TransferGadgetInfo},
+            common_gadget::{
+                CommonCallGadget, DelegateAccess, DelegationGadget, TransferGadget,
+                TransferGadgetInfo,
+            },
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
                 Transition::{Delta, To},
@@ -26,7 +29,7 @@ use crate::{
 use bus_mapping::{
     circuit_input_builder::CopyDataType,
     evm::OpcodeId,
-    precompile::{is_precompiled, PrecompileCalls},
+    precompile::{is_precompiled, ModExpAuxData, PrecompileCalls},
 };
 use eth_types::{
     evm_types::{memory::MemoryWordRange, GAS_STIPEND_CALL_WITH_VALUE},
@@ -57,6 +60,8 @@ pub(crate) struct CallOpGadget<F> {
     current_value: Word<F>,
     is_warm: Cell<F>,
     is_warm_prev: Cell<F>,
+    // EIP-7702 delegation of the callee.
+    delegation: DelegationGadget<F>,
     callee_reversion_info: ReversionInfo<F>,
     transfer: TransferGadget<F>,
     code_hash_previous: Cell<F>,
@@ -163,10 +168,23 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
         );
         // rwc_delta = 7 + is_delegatecall * 2 + call_gadget.rw_delta()
 
+        // A delegated callee executes the code of its delegate, which is added
+        // to the access list as well.
+        let delegation = DelegationGadget::construct(
+            cb,
+            call_gadget.phase2_callee_code_hash.expr(),
+            Some(DelegateAccess::WarmUp(
+                tx_id.expr(),
+                Some(&mut reversion_info),
+            )),
+        );
+        // rwc_delta = 7 + is_delegatecall * 2 + call_gadget.rw_delta() +
+        // delegation.rw_delta()
+
         // Propagate rw_counter_end_of_reversion and is_persistent
         let mut callee_reversion_info = cb.reversion_info_write(Some(callee_call_id.expr()));
         // rwc_delta = 7 + is_delegatecall * 2 + call_gadget.rw_delta() +
-        // callee_reversion_info.rw_delta()
+        // delegation.rw_delta() + callee_reversion_info.rw_delta()
         cb.require_equal(
             "callee_is_persistent == is_persistent ⋅ is_success",
             callee_reversion_info.is_persistent(),
@@ -275,13 +293,15 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
 
         // no_callee_code is true when the account exists and has empty
         // code hash, or when the account doesn't exist (which we encode with
-        // code_hash = 0).
-        let no_callee_code =
-            call_gadget.is_empty_code_hash.expr() + call_gadget.callee_not_exists.expr();
+        // code_hash = 0). For a delegated callee, it's the delegate's code.
+        let no_callee_code = delegation.executed_no_code(
+            call_gadget.is_empty_code_hash.expr() + call_gadget.callee_not_exists.expr(),
+        );
 
         // Sum up and verify gas cost.
         // Only CALL opcode could invoke transfer to make empty account into non-empty.
-        let gas_cost = call_gadget.gas_cost_expr(is_warm_prev.expr(), is_call.expr());
+        let gas_cost =
+            call_gadget.gas_cost_expr(is_warm_prev.expr(), is_call.expr()) + delegation.gas_cost();
         // Apply EIP 150
         let gas_available = cb.curr.state.gas_left.expr() - gas_cost.clone();
         let one_64th_gas = cb.annotation("one_64th_gas", |cb| {
@@ -304,9 +324,11 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
         let rw_counter_delta = 8.expr()
             + is_delegatecall.expr() * 2.expr()
             + call_gadget.rw_delta()
+            + delegation.rw_delta()
             + callee_reversion_info.rw_delta()
             + transfer_rwc_delta.expr();
-        let caller_reversible_rwc_delta = 1.expr(); // AccessList
+        // AccessList of the callee and its delegate
+        let caller_reversible_rwc_delta = 1.expr() + delegation.is_delegated();
         let callee_reversible_rwc_delta = is_call.expr() * transfer.reversible_w_delta();
 
         // 1. handle precompile calls.
@@ -386,7 +408,8 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
                     ),
                     (
                         CallContextFieldTag::ReversibleWriteCounter,
-                        cb.curr.state.reversible_write_counter.expr() + 1.expr(),
+                        cb.curr.state.reversible_write_counter.expr()
+                            + caller_reversible_rwc_delta.expr(),
                     ),
                     (CallContextFieldTag::LastCalleeId, callee_call_id.expr()),
                     (CallContextFieldTag::LastCalleeReturnDataOffset, 0.expr()),
@@ -510,6 +533,7 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
                     call_gadget.rd_address.offset(),
                     call_gadget.rd_address.length(),
                     precompile_return_length.expr(),
+                    precompile_input_len.expr(),
                     precompile_input_bytes_rlc.expr(),
                     precompile_output_bytes_rlc.expr(),
                     precompile_return_bytes_rlc.expr(),
@@ -633,7 +657,8 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
                     ),
                     (
                         CallContextFieldTag::ReversibleWriteCounter,
-                        cb.curr.state.reversible_write_counter.expr() + 1.expr(),
+                        cb.curr.state.reversible_write_counter.expr()
+                            + caller_reversible_rwc_delta.expr(),
                     ),
                 ] {
                     cb.call_context_lookup(true.expr(), None, field_tag, value);
@@ -676,7 +701,7 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
                     (CallContextFieldTag::IsCreate, 0.expr()),
                     (
                         CallContextFieldTag::CodeHash,
-                        call_gadget.phase2_callee_code_hash.expr(),
+                        delegation.executed_code_hash(call_gadget.phase2_callee_code_hash.expr()),
                     ),
                 ] {
                     cb.call_context_lookup(
@@ -708,7 +733,9 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
                     call_id: To(callee_call_id.expr()),
                     is_root: To(false.expr()),
                     is_create: To(false.expr()),
-                    code_hash: To(call_gadget.phase2_callee_code_hash.expr()),
+                    code_hash: To(
+                        delegation.executed_code_hash(call_gadget.phase2_callee_code_hash.expr())
+                    ),
                     gas_left: To(callee_gas_left),
                     reversible_write_counter: To(callee_reversible_rwc_delta.expr()),
                     ..StepStateTransition::new_context()
@@ -732,6 +759,7 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             call: call_gadget,
             is_warm,
             is_warm_prev,
+            delegation,
             callee_reversion_info,
             transfer,
             code_hash_previous,
@@ -807,6 +835,9 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
         let callee_exists = !callee_code_hash.is_zero();
 
         let (is_warm, is_warm_prev) = rws.next().tx_access_list_value_pair();
+        let delegate_gas_cost =
+            self.delegation
+                .assign_from_rws(region, offset, block, callee_code_hash, &mut rws)?;
 
         let [callee_rw_counter_end_of_reversion, callee_is_persistent] =
             [(); 2].map(|_| rws.next().call_context_value());
@@ -938,7 +969,7 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             is_call,
             has_value,
             !callee_exists,
-        )?;
+        )? + delegate_gas_cost;
         let gas_available = step.gas_left - gas_cost;
         self.one_64th_gas
             .assign(region, offset, gas_available.into())?;
@@ -1002,6 +1033,22 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             let precompile_call: PrecompileCalls = precompile_addr.0[19].into();
             let input_len = if let Some(input_len) = precompile_call.input_len() {
                 min(input_len, cd_length.as_usize())
+            } else if precompile_call == PrecompileCalls::Modexp && !cd_length.is_zero() {
+                // the input length of modexp is given by its header, peek at the memory words
+                // read by the input copy without consuming them.
+                let rws_offset = rws.offset();
+                let header_len = min(96, cd_length.as_usize());
+                let range = MemoryWordRange::align_range(cd_offset.as_usize(), header_len);
+                let words = (0..range.word_count())
+                    .map(|_| rws.next().memory_word_pair().0)
+                    .flat_map(|word| word.to_be_bytes())
+                    .collect::<Vec<_>>();
+                rws.offset_set(rws_offset);
+                let begin = cd_offset.as_usize() - range.start_slot().0;
+                min(
+                    ModExpAuxData::input_len(&words[begin..begin + header_len]),
+                    cd_length.as_usize(),
+                )
             } else {
                 cd_length.as_usize()
             };
@@ -1543,6 +1590,79 @@ mod test {
         );
     }
 
+    #[cfg(feature = "rust-tracer")]
+    #[test]
+    fn callop_delegated_callee() {
+        // The delegate is cold at the first call and warm at the second one,
+        // or always warm as a precompile, which executes no code.
+        let delegates = [Address::repeat_byte(0xdd), Address::from_low_u64_be(4)];
+        let stack = Stack {
+            gas: 100000,
+            ..Default::default()
+        };
+
+        for (opcode, delegate) in TEST_CALL_OPCODES.iter().cartesian_product(delegates) {
+            test_delegated(caller(opcode, stack, true), delegate);
+        }
+    }
+
+    /// Call the callee delegated to `delegate` (EIP-7702). The geth tracer
+    /// doesn't support delegations, so it's traced with the Rust tracer.
+    #[cfg(feature = "rust-tracer")]
+    fn test_delegated(caller: Account, delegate: Address) {
+        let callee = Account {
+            address: Address::repeat_byte(0xff),
+            code: eth_types::evm_types::delegation_code(delegate).into(),
+            nonce: 1.into(),
+            ..Default::default()
+        };
+        let delegate_code = bytecode! {
+            ADDRESS
+            CALLER
+            POP
+            POP
+            PUSH1(0)
+            PUSH1(0)
+            RETURN
+        };
+        let ctx = TestContext::<4, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x000000000000000000000000000000000000cafe"))
+                    .balance(Word::from(10u64.pow(19)));
+                accs[1]
+                    .address(caller.address)
+                    .code(caller.code)
+                    .balance(caller.balance);
+                accs[2]
+                    .address(callee.address)
+                    .code(callee.code)
+                    .nonce(callee.nonce);
+                accs[3]
+                    .address(Address::repeat_byte(0xdd))
+                    .code(delegate_code)
+                    .nonce(Word::one());
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(200000.into())
+                    .value(1000.into());
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                max_rws: 500,
+                ..Default::default()
+            })
+            .run();
+    }
+
     #[test]
     fn call_non_exist_with_value() {
         let callee_code = bytecode! {
@@ -7,7 +7,7 @@ use crate::{
         },
         step::ExecutionState,
         util::{
-            common_gadget::{get_copy_bytes, TransferGadget},
+            common_gadget::{get_copy_bytes, HardforkGadget, TransferGadget},
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
                 Transition::{Delta, To},
@@ -27,10 +27,7 @@ use crate::{
     util::Expr,
 };
 use bus_mapping::{circuit_input_builder::CopyDataType, evm::OpcodeId, state_db::CodeDB};
-use eth_types::{
-    evm_types::{GasCost, CREATE2_GAS_PER_CODE_WORD, CREATE_GAS_PER_CODE_WORD, MAX_INIT_CODE_SIZE},
-    Field, ToBigEndian, ToLittleEndian, ToScalar, ToWord, U256,
-};
+use eth_types::{evm_types::GasCost, Field, ToBigEndian, ToLittleEndian, ToScalar, ToWord, U256};
 use ethers_core::utils::keccak256;
 use gadgets::util::{and, expr_from_bytes};
 use halo2_proofs::{circuit::Value, plonk::Error};
@@ -51,6 +48,7 @@ pub(crate) struct CreateGadget<F, const IS_CREATE2: bool, const S: ExecutionStat
     transfer: TransferGadget<F>,
     init_code: MemoryAddressGadget<F>,
     init_code_word_size: ConstantDivisionGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    hardfork: HardforkGadget<F>,
     // Init code size must be less than or equal to 49152
     // (maximum init code size) if Shanghai, otherwise should be less than or
     // equal to 0x1FFFFFFFE0 (maximum value of offset + size).
@@ -113,8 +111,12 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
         let init_code_length = cb.query_word_rlc();
         let init_code =
             MemoryAddressGadget::construct(cb, init_code_memory_offset, init_code_length);
-        let init_code_size_not_overflow =
-            LtGadget::construct(cb, init_code.length(), MAX_INIT_CODE_SIZE.expr() + 1.expr());
+        let hardfork = HardforkGadget::construct(cb);
+        let init_code_size_not_overflow = LtGadget::construct(
+            cb,
+            init_code.length(),
+            hardfork.max_init_code_size() + 1.expr(),
+        );
 
         // Init code size overflow is checked before ErrDepth, ErrInsufficientBalance,
         // ErrNonceUintOverflow and ErrContractAddressCollision.
@@ -328,6 +330,13 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
                     0.expr(),
                     Some(&mut callee_reversion_info),
                 );
+                cb.account_created_write(
+                    tx_id.expr(),
+                    new_address.clone(),
+                    1.expr(),
+                    0.expr(),
+                    Some(&mut callee_reversion_info),
+                );
 
                 tansfer_gadget
             },
@@ -340,13 +349,14 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             init_code.length() + (N_BYTES_WORD - 1).expr(),
             N_BYTES_WORD as u64,
         );
+        // CREATE2 also pays for hashing the init code.
         let keccak_gas_cost = init_code_word_size.quotient()
-            * if IS_CREATE2 {
-                CREATE2_GAS_PER_CODE_WORD
-            } else {
-                CREATE_GAS_PER_CODE_WORD
-            }
-            .expr();
+            * (hardfork.init_code_word_gas()
+                + if IS_CREATE2 {
+                    GasCost::COPY_SHA3.expr()
+                } else {
+                    0.expr()
+                });
 
         let gas_cost = GasCost::CREATE.expr() + memory_expansion.gas_cost() + keccak_gas_cost;
         let gas_remaining = cb.curr.state.gas_left.expr() - gas_cost.clone();
@@ -442,7 +452,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
                         is_create: To(true.expr()),
                         code_hash: To(create.code_hash_word_rlc()),
                         gas_left: To(callee_gas_left),
-                        reversible_write_counter: To(1.expr() + transfer.reversible_w_delta()),
+                        reversible_write_counter: To(2.expr() + transfer.reversible_w_delta()),
                         ..StepStateTransition::new_context()
                     })
                 },
@@ -464,7 +474,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
                         program_counter: Delta(1.expr()),
                         stack_pointer: Delta(2.expr() + IS_CREATE2.expr()),
                         gas_left: Delta(-gas_cost.expr()),
-                        reversible_write_counter: Delta(3.expr() + transfer.reversible_w_delta()),
+                        reversible_write_counter: Delta(4.expr() + transfer.reversible_w_delta()),
                         ..Default::default()
                     })
                 },
@@ -506,6 +516,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             gas_left,
             callee_is_success,
             init_code_word_size,
+            hardfork,
             init_code_size_not_overflow,
             create,
             caller_balance,
@@ -574,11 +585,13 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             offset,
             region.keccak_rlc(&values.iter().rev().cloned().collect::<Vec<u8>>()),
         )?;
+        let hardfork = block.context.ctxs[&tx.block_number].hardfork;
+        self.hardfork.assign(region, offset, hardfork)?;
         self.init_code_size_not_overflow.assign(
             region,
             offset,
             F::from(init_code_length.as_u64()),
-            F::from(MAX_INIT_CODE_SIZE + 1),
+            F::from(hardfork.max_init_code_size() + 1),
         )?;
 
         self.tx_id
@@ -691,15 +704,16 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
 
         let keccak_gas_cost = u64::try_from(init_code_word_size).unwrap()
             * if IS_CREATE2 {
-                CREATE2_GAS_PER_CODE_WORD
+                hardfork.create2_gas_per_code_word()
             } else {
-                CREATE_GAS_PER_CODE_WORD
+                hardfork.init_code_word_gas()
             };
         let gas_left =
             step.gas_left - GasCost::CREATE.as_u64() - memory_expansion_gas_cost - keccak_gas_cost;
         self.gas_left.assign(region, offset, gas_left.into())?;
 
-        rws.offset_add(7); // skip unused call context
+        // skip callee nonce, callee TxCreatedAccount and unused call context
+        rws.offset_add(8);
         self.callee_is_success.assign(
             region,
             offset,
@@ -3,20 +3,27 @@ use crate::evm_circuit::{
     step::ExecutionState,
     table::{FixedTableTag, Lookup},
     util::{
-        common_gadget::CommonErrorGadget, constraint_builder::EVMConstraintBuilder, CachedRegion,
-        Cell,
+        common_gadget::{CommonErrorGadget, HardforkGadget},
+        constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+        math_gadget::{IsZeroGadget, LtGadget},
+        not, CachedRegion, Cell,
     },
     witness::{Block, Call, ExecStep, Transaction},
 };
-use eth_types::Field;
+use eth_types::{evm_types::OpcodeId, Field};
 use gadgets::util::Expr;
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 /// Gadget for invalid opcodes. It verifies by a fixed lookup for
-/// ResponsibleOpcode.
+/// ResponsibleOpcode, whose aux value is the hard fork activating the opcode,
+/// or 0 if the opcode is invalid in every hard fork.
 #[derive(Clone, Debug)]
 pub(crate) struct ErrorInvalidOpcodeGadget<F> {
     opcode: Cell<F>,
+    activation: Cell<F>,
+    activation_is_zero: IsZeroGadget<F>,
+    hardfork: HardforkGadget<F>,
+    is_before_activation: LtGadget<F, 1>,
     common_error_gadget: CommonErrorGadget<F>,
 }
 
@@ -27,6 +34,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidOpcodeGadget<F> {
 
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
         let opcode = cb.query_cell();
+        let activation = cb.query_cell();
         cb.add_lookup(
             "Responsible opcode lookup",
             Lookup::Fixed {
@@ -34,15 +42,31 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidOpcodeGadget<F> {
                 values: [
                     Self::EXECUTION_STATE.as_u64().expr(),
                     opcode.expr(),
-                    0.expr(),
+                    activation.expr(),
                 ],
             },
         );
 
+        // An opcode activated by a later hard fork is invalid before it.
+        let activation_is_zero = IsZeroGadget::construct(cb, activation.expr());
+        let hardfork = HardforkGadget::construct(cb);
+        let is_before_activation = LtGadget::construct(cb, hardfork.hardfork(), activation.expr());
+        cb.condition(not::expr(activation_is_zero.expr()), |cb| {
+            cb.require_equal(
+                "hard fork of the block is before the activation of opcode",
+                is_before_activation.expr(),
+                1.expr(),
+            );
+        });
+
         let common_error_gadget = CommonErrorGadget::construct(cb, opcode.expr(), 2.expr());
 
         Self {
             opcode,
+            activation,
+            activation_is_zero,
+            hardfork,
+            is_before_activation,
             common_error_gadget,
         }
     }
@@ -52,7 +76,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidOpcodeGadget<F> {
         region: &mut CachedRegion<'_, '_, F>,
         offset: usize,
         block: &Block<F>,
-        _: &Transaction,
+        tx: &Transaction,
         call: &Call,
         step: &ExecStep,
     ) -> Result<(), Error> {
@@ -60,6 +84,21 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidOpcodeGadget<F> {
         self.opcode
             .assign(region, offset, Value::known(F::from(opcode)))?;
 
+        // Always-invalid opcodes are "activated" in London, i.e. aux value 0.
+        let activation = OpcodeId::from(opcode as u8).activation_fork() as u64;
+        let hardfork = block.context.ctxs[&tx.block_number].hardfork;
+        self.activation
+            .assign(region, offset, Value::known(F::from(activation)))?;
+        self.activation_is_zero
+            .assign(region, offset, F::from(activation))?;
+        self.hardfork.assign(region, offset, hardfork)?;
+        self.is_before_activation.assign(
+            region,
+            offset,
+            F::from(hardfork as u64),
+            F::from(activation),
+        )?;
+
         log::debug!("ErrorInvalidOpcode - opcode = {}", opcode);
 
         self.common_error_gadget
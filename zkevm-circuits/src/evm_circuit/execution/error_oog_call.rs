@@ -4,7 +4,9 @@ use crate::{
         param::N_BYTES_GAS,
         step::ExecutionState,
         util::{
-            common_gadget::{CommonCallGadget, CommonErrorGadget},
+            common_gadget::{
+                CommonCallGadget, CommonErrorGadget, DelegateAccess, DelegationGadget,
+            },
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
             math_gadget::{IsZeroGadget, LtGadget},
             memory_gadget::MemoryExpandedAddressGadget,
@@ -33,6 +35,7 @@ pub(crate) struct ErrorOOGCallGadget<F> {
     is_static: Cell<F>,
     is_warm: Cell<F>,
     call: CommonCallGadget<F, MemoryExpandedAddressGadget<F>, false>,
+    delegation: DelegationGadget<F>,
     insufficient_gas: LtGadget<F, N_BYTES_GAS>,
     common_error_gadget: CommonErrorGadget<F>,
 }
@@ -72,6 +75,14 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCallGadget<F> {
             is_warm.expr(),
         );
 
+        // The access of the delegate of a delegated callee is charged as well,
+        // but the call fails before the delegate is warmed up.
+        let delegation = DelegationGadget::construct(
+            cb,
+            call_gadget.phase2_callee_code_hash.expr(),
+            Some(DelegateAccess::Read(tx_id.expr())),
+        );
+
         cb.condition(is_call.expr() * call_gadget.has_value.expr(), |cb| {
             cb.require_zero(
                 "CALL with value must not be in static call stack",
@@ -80,7 +91,8 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCallGadget<F> {
         });
 
         // Verify gas cost
-        let gas_cost = call_gadget.gas_cost_expr(is_warm.expr(), is_call.expr());
+        let gas_cost =
+            call_gadget.gas_cost_expr(is_warm.expr(), is_call.expr()) + delegation.gas_cost();
 
         // Check if the amount of gas available is less than the amount of gas required
         let insufficient_gas = LtGadget::construct(cb, cb.curr.state.gas_left.expr(), gas_cost);
@@ -100,7 +112,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCallGadget<F> {
         let common_error_gadget = CommonErrorGadget::construct(
             cb,
             opcode.expr(),
-            13.expr() + is_call.expr() + is_callcode.expr(),
+            13.expr() + is_call.expr() + is_callcode.expr() + delegation.rw_delta(),
         );
 
         Self {
@@ -113,6 +125,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCallGadget<F> {
             is_static,
             is_warm,
             call: call_gadget,
+            delegation,
             insufficient_gas,
             common_error_gadget,
         }
@@ -151,6 +164,9 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCallGadget<F> {
         let callee_code_hash = rws.next().account_value_pair().0;
         let callee_exists = !callee_code_hash.is_zero();
         let (is_warm, is_warm_prev) = rws.next().tx_access_list_value_pair();
+        let delegate_gas_cost =
+            self.delegation
+                .assign_from_rws(region, offset, block, callee_code_hash, &mut rws)?;
 
         let memory_expansion_gas_cost = self.call.assign(
             region,
@@ -207,7 +223,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCallGadget<F> {
             is_call,
             has_value,
             !callee_exists,
-        )?;
+        )? + delegate_gas_cost;
 
         self.insufficient_gas.assign_value(
             region,
@@ -216,16 +232,10 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCallGadget<F> {
             Value::known(F::from(gas_cost)),
         )?;
 
-        // Both CALL and CALLCODE opcodes have an extra stack pop `value` relative to
-        // DELEGATECALL and STATICCALL.
-        self.common_error_gadget.assign(
-            region,
-            offset,
-            block,
-            call,
-            step,
-            13 + if is_call || is_callcode { 1 } else { 0 },
-        )?;
+        // The rws of the step are followed by the 2 call context reads of the
+        // error gadget.
+        self.common_error_gadget
+            .assign(region, offset, block, call, step, rws.offset() + 2)?;
         Ok(())
     }
 }
@@ -384,6 +394,25 @@ mod test {
         }
     }
 
+    /// The access of the delegate of a callee delegated by EIP-7702 runs the
+    /// call out of gas, while the gas left covers the cold access of the
+    /// callee. The geth tracer doesn't support delegations, so it's traced
+    /// with the Rust tracer.
+    #[cfg(feature = "rust-tracer")]
+    #[test]
+    fn test_oog_call_delegated_callee() {
+        let callee = Account {
+            address: Address::repeat_byte(0xff),
+            code: eth_types::evm_types::delegation_code(Address::repeat_byte(0xdd)).into(),
+            nonce: 1.into(),
+            ..Default::default()
+        };
+        for opcode in TEST_CALL_OPCODES {
+            // the tx gas of an internal call leaves about 4000 gas to the call
+            test_oog(&caller(*opcode, Stack::default()), &callee, false);
+        }
+    }
+
     #[test]
     fn test_oog_call_with_overflow_gas() {
         let stack = Stack {
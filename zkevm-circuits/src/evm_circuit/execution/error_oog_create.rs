@@ -4,7 +4,7 @@ use crate::{
         param::{N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS, N_BYTES_MEMORY_WORD_SIZE},
         step::ExecutionState,
         util::{
-            common_gadget::CommonErrorGadget,
+            common_gadget::{CommonErrorGadget, HardforkGadget},
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
             math_gadget::{LtGadget, PairSelectGadget},
             memory_gadget::{
@@ -17,9 +17,7 @@ use crate::{
     witness::{Block, Call, ExecStep, Transaction},
 };
 use eth_types::{
-    evm_types::{
-        GasCost, OpcodeId, CREATE2_GAS_PER_CODE_WORD, CREATE_GAS_PER_CODE_WORD, MAX_INIT_CODE_SIZE,
-    },
+    evm_types::{GasCost, OpcodeId},
     Field, ToLittleEndian, U256,
 };
 use gadgets::util::Expr;
@@ -34,6 +32,7 @@ pub(crate) struct ErrorOOGCreateGadget<F> {
     minimum_word_size: MemoryWordSizeGadget<F>,
     memory_address: MemoryExpandedAddressGadget<F>,
     memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
+    hardfork: HardforkGadget<F>,
     // Init code size is overflow when it is greater than 49152
     // (maximum init code size) if Shanghai, otherwise when it is greater than
     // 0x1FFFFFFFE0 (maximum value of offset + size).
@@ -68,18 +67,16 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCreateGadget<F> {
         cb.stack_pop(memory_address.length_rlc());
         cb.condition(is_create2.expr().0, |cb| cb.stack_pop(salt.expr()));
 
+        let hardfork = HardforkGadget::construct(cb);
         let init_code_size_overflow =
-            LtGadget::construct(cb, MAX_INIT_CODE_SIZE.expr(), memory_address.length());
+            LtGadget::construct(cb, hardfork.max_init_code_size(), memory_address.length());
 
         let minimum_word_size = MemoryWordSizeGadget::construct(cb, memory_address.length());
         let memory_expansion = MemoryExpansionGadget::construct(cb, [memory_address.end_offset()]);
 
+        // CREATE2 also pays for hashing the init code.
         let keccak_gas_cost = minimum_word_size.expr()
-            * select::expr(
-                is_create2.expr().0,
-                CREATE2_GAS_PER_CODE_WORD.expr(),
-                CREATE_GAS_PER_CODE_WORD.expr(),
-            );
+            * (hardfork.init_code_word_gas() + is_create2.expr().0 * GasCost::COPY_SHA3.expr());
         let gas_cost = GasCost::CREATE.expr() + memory_expansion.gas_cost() + keccak_gas_cost;
         let insufficient_gas = LtGadget::construct(cb, cb.curr.state.gas_left.expr(), gas_cost);
 
@@ -107,6 +104,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCreateGadget<F> {
             minimum_word_size,
             memory_address,
             memory_expansion,
+            hardfork,
             init_code_size_overflow,
             insufficient_gas,
             common_error_gadget,
@@ -118,7 +116,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCreateGadget<F> {
         region: &mut CachedRegion<'_, '_, F>,
         offset: usize,
         block: &Block<F>,
-        _: &Transaction,
+        tx: &Transaction,
         call: &Call,
         step: &ExecStep,
     ) -> Result<(), Error> {
@@ -166,18 +164,20 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCreateGadget<F> {
             .assign(region, offset, step.memory_word_size(), [memory_address])?
             .1;
 
+        let hardfork = block.context.ctxs[&tx.block_number].hardfork;
+        self.hardfork.assign(region, offset, hardfork)?;
         self.init_code_size_overflow.assign(
             region,
             offset,
-            F::from(MAX_INIT_CODE_SIZE),
+            F::from(hardfork.max_init_code_size()),
             F::from(init_code_size),
         )?;
 
         let keccak_gas_cost = minimum_word_size
             * if is_create2 {
-                CREATE2_GAS_PER_CODE_WORD
+                hardfork.create2_gas_per_code_word()
             } else {
-                CREATE_GAS_PER_CODE_WORD
+                hardfork.init_code_word_gas()
             };
         self.insufficient_gas.assign(
             region,
@@ -203,7 +203,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCreateGadget<F> {
 mod tests {
     use super::*;
     use crate::test_util::CircuitTestBuilder;
-    use eth_types::{bytecode, word, Bytecode, ToWord};
+    use eth_types::{bytecode, evm_types::ChainSpec, word, Bytecode, ToWord};
     use mock::{
         eth,
         test_ctx::{helpers::account_0_code_account_1_no_code, LoggerConfig},
@@ -301,15 +301,16 @@ mod tests {
     #[test]
     fn test_oog_create_max_init_code_size() {
         for is_create2 in [true, false] {
-            // For Shanghai, MAX_INIT_CODE_SIZE is 49152, it is constrained by
-            // `init_code_size_overflow`.
-            // For not Shanghai, MAX_INIT_CODE_SIZE is 0x1FFFFFFFE0, it is
-            // constrained by `memory_address.overflow()`
+            // For Shanghai, the maximum init code size is 49152, it is
+            // constrained by `init_code_size_overflow`.
+            // For not Shanghai, the maximum init code size is 0x1FFFFFFFE0, it
+            // is constrained by `memory_address.overflow()`
             // (and `init_code_size_overflow`).
+            let max_init_code_size = ChainSpec::default().hardfork_at(0, 0).max_init_code_size();
             let case = TestCase::new(
                 is_create2,
                 U256::zero(),
-                (MAX_INIT_CODE_SIZE + 1).into(),
+                (max_init_code_size + 1).into(),
                 MOCK_BLOCK_GAS_LIMIT,
             );
 
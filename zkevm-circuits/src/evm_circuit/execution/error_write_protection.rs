@@ -44,11 +44,12 @@ impl<F: Field> ExecutionGadget<F> for ErrorWriteProtectionGadget<F> {
         // max_degree. otherwise need to do fixed lookup for these opcodes
         // checking.
         cb.require_in_set(
-            "ErrorWriteProtection only happens in [CALL, SSTORE, CREATE, CREATE2, SELFDESTRUCT, LOG0..4 ]",
+            "ErrorWriteProtection only happens in [CALL, SSTORE, TSTORE, CREATE, CREATE2, SELFDESTRUCT, LOG0..4 ]",
             opcode.expr(),
             vec![
                 OpcodeId::CALL.expr(),
                 OpcodeId::SSTORE.expr(),
+                OpcodeId::TSTORE.expr(),
                 OpcodeId::CREATE.expr(),
                 OpcodeId::CREATE2.expr(),
                 OpcodeId::SELFDESTRUCT.expr(),
@@ -225,6 +226,38 @@ mod test {
         test_internal_write_protection(true);
     }
 
+    #[cfg(feature = "cancun")]
+    #[test]
+    fn test_write_protection_tstore() {
+        let callee_bytecode = bytecode! {
+            PUSH1(42)
+            PUSH1(0x02)
+            // this TSTORE got error: ErrorWriteProtection
+            TSTORE
+            STOP
+        };
+        test_ok(static_caller(), callee(callee_bytecode));
+    }
+
+    fn static_caller() -> Account {
+        let caller_bytecode = bytecode! {
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH32(Address::repeat_byte(0xff).to_word())
+            PUSH2(40000) // gas
+            STATICCALL
+            STOP
+        };
+        Account {
+            address: Address::repeat_byte(0xfe),
+            balance: Word::from(10).pow(20.into()),
+            code: caller_bytecode.into(),
+            ..Default::default()
+        }
+    }
+
     // ErrorWriteProtection error happen in internal call
     fn test_internal_write_protection(is_call: bool) {
         let mut caller_bytecode = bytecode! {
@@ -0,0 +1,244 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_MEMORY_WORD_SIZE,
+        step::ExecutionState,
+        util::{
+            common_gadget::{HardforkGadget, SameContextGadget},
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
+                Transition::{Delta, To},
+            },
+            memory_gadget::{
+                CommonMemoryAddressGadget, MemoryAddressGadget, MemoryCopierGasGadget,
+                MemoryExpansionGadget,
+            },
+            CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    util::Expr,
+};
+use bus_mapping::{circuit_input_builder::CopyDataType, evm::OpcodeId};
+use eth_types::{evm_types::GasCost, Field, ToScalar};
+use gadgets::util::not;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+#[derive(Clone, Debug)]
+pub(crate) struct MCopyGadget<F> {
+    same_context: SameContextGadget<F>,
+    hardfork: HardforkGadget<F>,
+    /// The memory address in the destination to which we copy.
+    dst_memory_addr: MemoryAddressGadget<F>,
+    /// The memory address in the source from which we copy, with the same
+    /// length as the destination.
+    src_memory_addr: MemoryAddressGadget<F>,
+    /// Opcode MCOPY has a dynamic gas cost:
+    /// gas_code = static_gas * minimum_word_size + memory_expansion_cost
+    /// where the memory expansion covers both the source and the destination.
+    memory_expansion: MemoryExpansionGadget<F, 2, N_BYTES_MEMORY_WORD_SIZE>,
+    /// Opcode MCOPY needs to copy data into memory. We account for the copying
+    /// costs using the memory copier gas gadget.
+    memory_copier_gas: MemoryCopierGasGadget<F, { GasCost::COPY }>,
+    /// RW inverse counter from the copy table at the start of related copy
+    /// steps.
+    copy_rwc_inc: Cell<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for MCopyGadget<F> {
+    const NAME: &'static str = "MCOPY";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::MCOPY;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // MCOPY is activated in Cancun (EIP-5656).
+        let hardfork = HardforkGadget::construct(cb);
+        hardfork.require_enabled(cb, OpcodeId::MCOPY);
+
+        let opcode = cb.query_cell();
+
+        let dst_memory_addr = MemoryAddressGadget::construct_self(cb);
+        let src_memory_addr = MemoryAddressGadget::construct_self(cb);
+        cb.require_equal(
+            "source and destination have the same length",
+            src_memory_addr.length_rlc(),
+            dst_memory_addr.length_rlc(),
+        );
+
+        // Pop dest_offset, offset, length from stack
+        cb.stack_pop(dst_memory_addr.offset_rlc());
+        cb.stack_pop(src_memory_addr.offset_rlc());
+        cb.stack_pop(dst_memory_addr.length_rlc());
+
+        // Calculate the next memory size and the gas cost for this memory
+        // access. Both the source and the destination may expand the memory.
+        let memory_expansion = MemoryExpansionGadget::construct(
+            cb,
+            [dst_memory_addr.end_offset(), src_memory_addr.end_offset()],
+        );
+        let memory_copier_gas = MemoryCopierGasGadget::construct(
+            cb,
+            dst_memory_addr.length(),
+            memory_expansion.gas_cost(),
+        );
+
+        // The copy circuit reads all the source words before writing the
+        // destination words, so that overlapping ranges are copied correctly.
+        let copy_rwc_inc = cb.query_cell();
+        cb.condition(dst_memory_addr.has_length(), |cb| {
+            cb.copy_table_lookup(
+                cb.curr.state.call_id.expr(),
+                CopyDataType::Memory.expr(),
+                cb.curr.state.call_id.expr(),
+                CopyDataType::Memory.expr(),
+                src_memory_addr.offset(),
+                src_memory_addr.end_offset(),
+                dst_memory_addr.offset(),
+                dst_memory_addr.length(),
+                0.expr(), // for MCOPY rlc_acc is 0
+                copy_rwc_inc.expr(),
+            );
+        });
+        cb.condition(not::expr(dst_memory_addr.has_length()), |cb| {
+            cb.require_zero(
+                "if no bytes to copy, copy table rwc inc == 0",
+                copy_rwc_inc.expr(),
+            );
+        });
+
+        // State transition
+        let step_state_transition = StepStateTransition {
+            rw_counter: Delta(cb.rw_counter_offset()),
+            program_counter: Delta(1.expr()),
+            stack_pointer: Delta(3.expr()),
+            gas_left: Delta(
+                -(OpcodeId::MCOPY.constant_gas_cost().expr() + memory_copier_gas.gas_cost()),
+            ),
+            memory_word_size: To(memory_expansion.next_memory_word_size()),
+            ..Default::default()
+        };
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+
+        Self {
+            same_context,
+            hardfork,
+            dst_memory_addr,
+            src_memory_addr,
+            memory_expansion,
+            memory_copier_gas,
+            copy_rwc_inc,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        _call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)?;
+        self.hardfork.assign(
+            region,
+            offset,
+            block.context.ctxs[&tx.block_number].hardfork,
+        )?;
+
+        let [dest_offset, src_offset, length] =
+            [0, 1, 2].map(|i| block.rws[step.rw_indices[i]].stack_value());
+
+        let dst_memory_addr = self
+            .dst_memory_addr
+            .assign(region, offset, dest_offset, length)?;
+        let src_memory_addr = self
+            .src_memory_addr
+            .assign(region, offset, src_offset, length)?;
+
+        // assign to gadgets handling memory expansion cost and copying cost.
+        let (_, memory_expansion_cost) = self.memory_expansion.assign(
+            region,
+            offset,
+            step.memory_word_size(),
+            [dst_memory_addr, src_memory_addr],
+        )?;
+        self.memory_copier_gas
+            .assign(region, offset, length.as_u64(), memory_expansion_cost)?;
+
+        self.copy_rwc_inc.assign(
+            region,
+            offset,
+            Value::known(
+                step.copy_rw_counter_delta
+                    .to_scalar()
+                    .expect("unexpected U256 -> Scalar conversion failure"),
+            ),
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "cancun"))]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::circuit_input_builder::CircuitsParams;
+    use eth_types::{bytecode, Word};
+    use mock::TestContext;
+
+    fn test_ok(dst_offset: u64, src_offset: u64, length: u64) {
+        let code = bytecode! {
+            PUSH32(Word::from_big_endian(&(0..32).collect::<Vec<u8>>()))
+            PUSH1(0x00)
+            MSTORE
+            PUSH32(Word::from_big_endian(&(32..64).collect::<Vec<u8>>()))
+            PUSH1(0x20)
+            MSTORE
+            PUSH32(length)
+            PUSH32(src_offset)
+            PUSH32(dst_offset)
+            MCOPY
+            STOP
+        };
+
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap(),
+        )
+        .params(CircuitsParams {
+            max_copy_rows: 1750,
+            ..Default::default()
+        })
+        .run();
+    }
+
+    #[test]
+    fn mcopy_gadget_simple() {
+        test_ok(0x40, 0x00, 0x20);
+    }
+
+    #[test]
+    fn mcopy_gadget_overlapping_forward() {
+        test_ok(0x10, 0x00, 0x30);
+    }
+
+    #[test]
+    fn mcopy_gadget_overlapping_backward() {
+        test_ok(0x00, 0x11, 0x2f);
+    }
+
+    #[test]
+    fn mcopy_gadget_same_offset() {
+        test_ok(0x20, 0x20, 0x20);
+    }
+
+    #[test]
+    fn mcopy_gadget_expand_by_source() {
+        test_ok(0x00, 0x60, 0x40);
+    }
+
+    #[test]
+    fn mcopy_gadget_zero_length() {
+        test_ok(0x1000, 0x2000, 0x00);
+    }
+}
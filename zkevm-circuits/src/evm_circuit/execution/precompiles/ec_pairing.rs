@@ -1,13 +1,10 @@
 use bus_mapping::{
-    circuit_input_builder::{EcPairingPair, N_BYTES_PER_PAIR, N_PAIRING_PER_OP},
+    circuit_input_builder::N_BYTES_PER_PAIR,
     precompile::{PrecompileAuxData, PrecompileCalls},
 };
 use eth_types::{evm_types::GasCost, Field, ToScalar};
-use gadgets::util::{or, select, Expr};
-use halo2_proofs::{
-    circuit::Value,
-    plonk::{Error, Expression},
-};
+use gadgets::util::Expr;
+use halo2_proofs::{circuit::Value, plonk::Error};
 
 use crate::{
     evm_circuit::{
@@ -16,7 +13,6 @@ use crate::{
         util::{
             common_gadget::RestoreContextGadget,
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
-            math_gadget::{BinaryNumberGadget, IsZeroGadget},
             rlc, CachedRegion, Cell,
         },
     },
@@ -24,10 +20,10 @@ use crate::{
     witness::{Block, Call, ExecStep, Transaction},
 };
 
-/// Note: input_len ∈ { 0, 192, 384, 576, 768 } if valid.
+/// Note: input_len is a multiple of 192 if valid.
 ///
-/// Note: input bytes are padded to 768 bytes within our zkEVM implementation to standardise a
-/// pairing operation, such that each pairing op has 4 pairs: [(G1, G2); 4].
+/// Note: the ECC circuit splits the input pairs in Miller loop chunks of 4 pairs, replaces the
+/// pairs with an identity point by padding pairs, and exposes the RLC of the EVM input bytes.
 #[derive(Clone, Debug)]
 pub struct EcPairingGadget<F> {
     // Random linear combination of input bytes to the precompile ecPairing call.
@@ -38,17 +34,8 @@ pub struct EcPairingGadget<F> {
     /// Gas cost for the precompile call.
     gas_cost: Cell<F>,
 
-    /// Number of pairs provided through EVM input. Since a maximum of 4 pairs can be supplied from
-    /// EVM, we need 3 binary bits for a max value of [1, 0, 0].
+    /// Number of pairs provided through EVM input.
     n_pairs: Cell<F>,
-    n_pairs_cmp: BinaryNumberGadget<F, 3>,
-    /// keccak_rand ^ 64.
-    rand_pow_64: Cell<F>,
-
-    evm_input_g1_rlc: [Cell<F>; N_PAIRING_PER_OP],
-    evm_input_g2_rlc: [Cell<F>; N_PAIRING_PER_OP],
-    is_g1_identity: [IsZeroGadget<F>; N_PAIRING_PER_OP],
-    is_g2_identity: [IsZeroGadget<F>; N_PAIRING_PER_OP],
 
     is_success: Cell<F>,
     callee_address: Cell<F>,
@@ -69,17 +56,6 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
         let (evm_input_rlc, output) = (cb.query_cell_phase2(), cb.query_bool());
         let gas_cost = cb.query_cell();
         let n_pairs = cb.query_cell();
-        let n_pairs_cmp = BinaryNumberGadget::construct(cb, n_pairs.expr());
-        let rand_pow_64 = cb.query_cell_phase2();
-        let (rand_pow_128, rand_pow_192, rand_pow_384, rand_pow_576) = {
-            let rand_pow_128 = rand_pow_64.expr() * rand_pow_64.expr();
-            let rand_pow_192 = rand_pow_128.expr() * rand_pow_64.expr();
-            let rand_pow_384 = rand_pow_192.expr() * rand_pow_192.expr();
-            let rand_pow_576 = rand_pow_384.expr() * rand_pow_192.expr();
-            (rand_pow_128, rand_pow_192, rand_pow_384, rand_pow_576)
-        };
-        cb.pow_of_rand_lookup(64.expr(), rand_pow_64.expr());
-
         cb.require_equal(
             "gas cost",
             gas_cost.expr(),
@@ -105,97 +81,6 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
             cb.execution_state().precompile_base_gas_cost().expr(),
         );
 
-        let evm_input_g1_rlc = array_init::array_init(|_| cb.query_cell_phase2());
-        let evm_input_g2_rlc = array_init::array_init(|_| cb.query_cell_phase2());
-        let is_g1_identity = evm_input_g1_rlc.clone().map(|g1_rlc| {
-            cb.annotation("is G1 zero", |cb| {
-                IsZeroGadget::construct(cb, g1_rlc.expr())
-            })
-        });
-        let is_g2_identity = evm_input_g2_rlc.clone().map(|g2_rlc| {
-            cb.annotation("is G2 zero", |cb| {
-                IsZeroGadget::construct(cb, g2_rlc.expr())
-            })
-        });
-
-        let padding_g1_g2_rlc = rlc::expr(
-            &EcPairingPair::ecc_padding()
-                .to_bytes_be()
-                .iter()
-                .rev()
-                .map(|i| i.expr())
-                .collect::<Vec<Expression<F>>>(),
-            cb.challenges().keccak_input(),
-        );
-        let ecc_circuit_input_rlcs = evm_input_g1_rlc
-            .clone()
-            .zip(is_g1_identity.clone())
-            .zip(evm_input_g2_rlc.clone().zip(is_g2_identity.clone()))
-            .map(|((g1_rlc, is_g1_identity), (g2_rlc, is_g2_identity))| {
-                select::expr(
-                    or::expr([is_g1_identity.expr(), is_g2_identity.expr()]),
-                    // rlc([G1::identity, G2::generator])
-                    padding_g1_g2_rlc.expr(),
-                    // rlc([g1, g2])
-                    g1_rlc.expr() * rand_pow_128.expr() + g2_rlc.expr(),
-                )
-            });
-        let ecc_circuit_input_rlc = ecc_circuit_input_rlcs[0].expr() * rand_pow_576.expr()
-            + ecc_circuit_input_rlcs[1].expr() * rand_pow_384.expr()
-            + ecc_circuit_input_rlcs[2].expr() * rand_pow_192.expr()
-            + ecc_circuit_input_rlcs[3].expr();
-
-        // Equality checks for EVM input bytes to ecPairing call.
-        cb.condition(n_pairs_cmp.value_equals(0usize), |cb| {
-            cb.require_zero("ecPairing: evm_input_rlc == 0", evm_input_rlc.expr());
-        });
-        cb.condition(n_pairs_cmp.value_equals(1usize), |cb| {
-            cb.require_equal(
-                "ecPairing: evm_input_rlc for 1 pair",
-                evm_input_rlc.expr(),
-                evm_input_g1_rlc[0].expr() * rand_pow_128.expr() + evm_input_g2_rlc[0].expr(),
-            );
-        });
-        cb.condition(n_pairs_cmp.value_equals(2usize), |cb| {
-            cb.require_equal(
-                "ecPairing: evm_input_rlc for 2 pairs",
-                evm_input_rlc.expr(),
-                (evm_input_g1_rlc[0].expr() * rand_pow_128.expr() + evm_input_g2_rlc[0].expr())
-                    * rand_pow_192.expr()
-                    + evm_input_g1_rlc[1].expr() * rand_pow_128.expr()
-                    + evm_input_g2_rlc[1].expr(),
-            );
-        });
-        cb.condition(n_pairs_cmp.value_equals(3usize), |cb| {
-            cb.require_equal(
-                "ecPairing: evm_input_rlc for 3 pairs",
-                evm_input_rlc.expr(),
-                (evm_input_g1_rlc[0].expr() * rand_pow_128.expr() + evm_input_g2_rlc[0].expr())
-                    * rand_pow_384.expr()
-                    + (evm_input_g1_rlc[1].expr() * rand_pow_128.expr()
-                        + evm_input_g2_rlc[1].expr())
-                        * rand_pow_192.expr()
-                    + evm_input_g1_rlc[2].expr() * rand_pow_128.expr()
-                    + evm_input_g2_rlc[2].expr(),
-            );
-        });
-        cb.condition(n_pairs_cmp.value_equals(4usize), |cb| {
-            cb.require_equal(
-                "ecPairing: evm_input_rlc for 4 pairs",
-                evm_input_rlc.expr(),
-                (evm_input_g1_rlc[0].expr() * rand_pow_128.expr() + evm_input_g2_rlc[0].expr())
-                    * rand_pow_576.expr()
-                    + (evm_input_g1_rlc[1].expr() * rand_pow_128.expr()
-                        + evm_input_g2_rlc[1].expr())
-                        * rand_pow_384.expr()
-                    + (evm_input_g1_rlc[2].expr() * rand_pow_128.expr()
-                        + evm_input_g2_rlc[2].expr())
-                        * rand_pow_192.expr()
-                    + evm_input_g1_rlc[3].expr() * rand_pow_128.expr()
-                    + evm_input_g2_rlc[3].expr(),
-            );
-        });
-
         // validate successful call to the precompile ecPairing.
         cb.condition(is_success.expr(), |cb| {
             // Covers the following cases:
@@ -208,7 +93,7 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
                 0.expr(),
                 0.expr(),
                 0.expr(),
-                ecc_circuit_input_rlc.expr(),
+                evm_input_rlc.expr(),
                 output.expr(),
                 0.expr(),
             );
@@ -217,11 +102,6 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
                 n_pairs.expr() * N_BYTES_PER_PAIR.expr(),
                 call_data_length.expr(),
             );
-            cb.require_in_set(
-                "ecPairing: input_len ∈ { 0, 192, 384, 576, 768 }",
-                call_data_length.expr(),
-                vec![0.expr(), 192.expr(), 384.expr(), 576.expr(), 768.expr()],
-            );
         });
 
         let restore_context = RestoreContextGadget::construct2(
@@ -241,13 +121,6 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
             gas_cost,
 
             n_pairs,
-            n_pairs_cmp,
-            rand_pow_64,
-
-            evm_input_g1_rlc,
-            evm_input_g2_rlc,
-            is_g1_identity,
-            is_g2_identity,
 
             is_success,
             callee_address,
@@ -273,21 +146,10 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
             let n_pairs = (call.call_data_length as usize) / N_BYTES_PER_PAIR;
             let keccak_rand = region.challenges().keccak_input();
 
-            // Consider only call_data_length bytes for EVM input.
             self.evm_input_rlc.assign(
                 region,
                 offset,
-                keccak_rand.map(|r| {
-                    rlc::value(
-                        aux_data
-                            .0
-                            .to_bytes_be()
-                            .iter()
-                            .take(call.call_data_length as usize)
-                            .rev(),
-                        r,
-                    )
-                }),
+                keccak_rand.map(|r| rlc::value(aux_data.0.to_bytes_be().iter().rev(), r)),
             )?;
             // Pairing check output from ecPairing call.
             self.output.assign(
@@ -304,21 +166,6 @@ impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
             // Number of pairs provided in the EVM call.
             self.n_pairs
                 .assign(region, offset, Value::known(F::from(n_pairs as u64)))?;
-            self.n_pairs_cmp.assign(region, offset, n_pairs)?;
-            // keccak_rand ^ 64.
-            self.rand_pow_64
-                .assign(region, offset, keccak_rand.map(|r| r.pow(&[64, 0, 0, 0])))?;
-            // G1, G2 points from EVM.
-            for i in 0..N_PAIRING_PER_OP {
-                let g1_bytes = aux_data.0.pairs[i].g1_bytes_be();
-                let g2_bytes = aux_data.0.pairs[i].g2_bytes_be();
-                let g1_rlc = keccak_rand.map(|r| rlc::value(g1_bytes.iter().rev(), r));
-                let g2_rlc = keccak_rand.map(|r| rlc::value(g2_bytes.iter().rev(), r));
-                self.evm_input_g1_rlc[i].assign(region, offset, g1_rlc)?;
-                self.is_g1_identity[i].assign_value(region, offset, g1_rlc)?;
-                self.evm_input_g2_rlc[i].assign(region, offset, g2_rlc)?;
-                self.is_g2_identity[i].assign_value(region, offset, g2_rlc)?;
-            }
             self.gas_cost.assign(
                 region,
                 offset,
@@ -629,6 +476,41 @@ mod test {
                     address: PrecompileCalls::Bn128Pairing.address().to_word(),
                     ..Default::default()
                 },
+                PrecompileCallArgs {
+                    name: "ecPairing (pairing true): 6 pairs over 2 Miller loop chunks",
+                    setup_code: {
+                        let two_pairs = [
+                            // G1_x1, G1_y1, G2_x11, G2_x12, G2_y11, G2_y12
+                            word!("0x2cf44499d5d27bb186308b7af7af02ac5bc9eeb6a3d147c186b21fb1b76e18da"),
+                            word!("0x2c0f001f52110ccfe69108924926e45f0b0c868df0e7bde1fe16d3242dc715f6"),
+                            word!("0x1fb19bb476f6b9e44e2a32234da8212f61cd63919354bc06aef31e3cfaff3ebc"),
+                            word!("0x22606845ff186793914e03e21df544c34ffe2f2f3504de8a79d9159eca2d98d9"),
+                            word!("0x2bd368e28381e8eccb5fa81fc26cf3f048eea9abfdd85d7ed3ab3698d63e4f90"),
+                            word!("0x2fe02e47887507adf0ff1743cbac6ba291e66f59be6bd763950bb16041a0a85e"),
+                            // G1_x2, G1_y2, G2_x21, G2_x22, G2_y21, G2_y22
+                            word!("0x0000000000000000000000000000000000000000000000000000000000000001"),
+                            word!("0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd45"),
+                            word!("0x1971ff0471b09fa93caaf13cbf443c1aede09cc4328f5a62aad45f40ec133eb4"),
+                            word!("0x091058a3141822985733cbdddfed0fd8d6c104e9e9eff40bf5abfef9ab163bc7"),
+                            word!("0x2a23af9a5ce2ba2796c1f4e453a370eb0af8c212d9dc9acd8fc02c2e907baea2"),
+                            word!("0x23a8eb0b0996252cb548a4487da97b02422ebc0e834613f954de6c7e0afdc1fc"),
+                        ];
+                        // e(P1, Q1) * e(P2, Q2) == 1, repeated 3 times.
+                        let mut setup_code = bytecode! {};
+                        for (i, word) in two_pairs.iter().cycle().take(36).enumerate() {
+                            setup_code.push(32, *word);
+                            setup_code.push(2, 0x20 * i);
+                            setup_code.write_op(OpcodeId::MSTORE);
+                        }
+                        setup_code
+                    },
+                    call_data_offset: 0x00.into(),
+                    call_data_length: 0x480.into(),
+                    ret_offset: 0x480.into(),
+                    ret_size: 0x20.into(),
+                    address: PrecompileCalls::Bn128Pairing.address().to_word(),
+                    ..Default::default()
+                },
             ]
         };
     }
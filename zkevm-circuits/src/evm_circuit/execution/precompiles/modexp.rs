@@ -1,6 +1,6 @@
-use bus_mapping::precompile::{PrecompileAuxData, MODEXP_INPUT_LIMIT, MODEXP_SIZE_LIMIT};
+use bus_mapping::precompile::{PrecompileAuxData, MODEXP_SIZE_LIMIT};
 use eth_types::{evm_types::GasCost, Field, ToScalar, U256};
-use gadgets::util::{self, not, select, Expr};
+use gadgets::util::{self, not, Expr};
 use halo2_proofs::{
     circuit::Value,
     plonk::{Error, Expression},
@@ -9,19 +9,18 @@ use halo2_proofs::{
 use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
-        param::{N_BITS_U8, N_BYTES_U64, N_BYTES_WORD},
+        param::N_BYTES_U64,
         step::ExecutionState,
         util::{
             common_gadget::RestoreContextGadget,
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
             math_gadget::{
-                BinaryNumberGadget, BitLengthGadget, ByteOrWord, ByteSizeGadget,
-                ConstantDivisionGadget, IsZeroGadget, LtGadget, MinMaxGadget,
+                BinaryNumberGadget, ConstantDivisionGadget, IsZeroGadget, LtGadget, MinMaxGadget,
             },
             rlc, CachedRegion, Cell,
         },
     },
-    table::CallContextFieldTag,
+    table::{CallContextFieldTag, ModExpTable},
     witness::{Block, Call, ExecStep, Transaction},
 };
 
@@ -114,11 +113,11 @@ impl<F: Field, const BIT_LIMIT: usize> RandPowRepresent<F, BIT_LIMIT> {
 }
 
 const SIZE_LIMIT: usize = MODEXP_SIZE_LIMIT;
-const SIZE_REPRESENT_BITS: usize = 6;
+// bits to represent a size, up to SIZE_LIMIT
+const SIZE_REPRESENT_BITS: usize = 10;
 const SIZE_REPRESENT_BYTES: usize = SIZE_LIMIT / 256 + 1;
-const INPUT_LIMIT: usize = 32 * 6;
-const INPUT_REPRESENT_BYTES: usize = MODEXP_INPUT_LIMIT / 256 + 1;
-const INPUT_REPRESENT_BITS: usize = 8;
+// bits to represent the right-padding of the input, up to MODEXP_INPUT_LIMIT
+const INPUT_REPRESENT_BITS: usize = 11;
 
 type Word<F> = [Cell<F>; 32];
 
@@ -247,23 +246,27 @@ type RandPow<F> = RandPowRepresent<F, SIZE_REPRESENT_BITS>;
 type InputParsedResult = (bool, [U256; 3], [[u8; SIZE_LIMIT]; 3]);
 type OutputParsedResult = (usize, [u8; SIZE_LIMIT]);
 
+// rlc of the big-endian bytes of an integer, which ignores its leading zeroes
+fn rlc_value<F: Field>(bytes: &[u8; SIZE_LIMIT], randomness: Value<F>) -> Value<F> {
+    randomness.map(|randomness| rlc::value(bytes.iter().rev(), randomness))
+}
+
 #[derive(Clone, Debug)]
 struct ModExpInputs<F> {
     base_len: SizeRepresent<F>,
     modulus_len: SizeRepresent<F>,
     exp_len: SizeRepresent<F>,
     base_pow: RandPow<F>,
-    base: Word<F>,
+    base_rlc: Cell<F>,
     modulus_pow: RandPow<F>,
-    modulus: Word<F>,
+    modulus_rlc: Cell<F>,
     exp_pow: RandPow<F>,
-    exp: Word<F>,
+    exp_rlc: Cell<F>,
+    exp_bit_len: Cell<F>,
     input_valid: Cell<F>,
     input_bytes_rlc: Expression<F>,
+    lens_expected: [Expression<F>; 3],
     input_len_expected: Expression<F>,
-    pub base_limbs: Limbs<F>,
-    pub exp_limbs: Limbs<F>,
-    pub modulus_limbs: Limbs<F>,
 }
 
 impl<F: Field> ModExpInputs<F> {
@@ -276,13 +279,12 @@ impl<F: Field> ModExpInputs<F> {
             RandPowRepresent::<_, 6>::base_pows_expr(cb.challenges().keccak_input())[5].clone(); //r**32
         let r_pow_64 = r_pow_32.clone().square();
 
-        let base = cb.query_bytes();
-        let modulus = cb.query_bytes();
-        let exp = cb.query_bytes();
-
-        let base_limbs = Limbs::configure(cb, &base);
-        let exp_limbs = Limbs::configure(cb, &exp);
-        let modulus_limbs = Limbs::configure(cb, &modulus);
+        // rlc of the integers, which are looked up in the modexp table with the bit length of
+        // the exponent
+        let base_rlc = cb.query_cell_phase2();
+        let modulus_rlc = cb.query_cell_phase2();
+        let exp_rlc = cb.query_cell_phase2();
+        let exp_bit_len = cb.query_cell();
 
         let input_valid = cb.query_bool();
         cb.require_equal(
@@ -294,6 +296,16 @@ impl<F: Field> ModExpInputs<F> {
                 modulus_len.is_valid(),
             ]),
         );
+        cb.condition(not::expr(input_valid.expr()), |cb| {
+            for (name, value) in [
+                ("base rlc is zero for invalid input", &base_rlc),
+                ("exp rlc is zero for invalid input", &exp_rlc),
+                ("modulus rlc is zero for invalid input", &modulus_rlc),
+                ("exp bit len is zero for invalid input", &exp_bit_len),
+            ] {
+                cb.require_zero(name, value.expr());
+            }
+        });
 
         let base_len_expected = util::select::expr(input_valid.expr(), base_len.value(), 0.expr());
 
@@ -307,13 +319,12 @@ impl<F: Field> ModExpInputs<F> {
             + exp_len_expected.clone()
             + modulus_len_expected.clone();
 
-        // we put correct size in each input word if input is valid
-        // else we just put as most as possible bytes (32) into it
-        // so we finally handle the memory in limited sized (32*3)
+        // the integers take their specified sizes in the input if it is valid, else the input
+        // is only the lens
         let modulus_pow = RandPow::configure(
             cb,
             cb.challenges().keccak_input(),
-            modulus_len_expected,
+            modulus_len_expected.clone(),
             None,
         );
 
@@ -321,7 +332,7 @@ impl<F: Field> ModExpInputs<F> {
         let exp_pow = RandPow::configure(
             cb,
             cb.challenges().keccak_input(),
-            exp_len_expected,
+            exp_len_expected.clone(),
             Some(modulus_pow.expr()),
         );
 
@@ -329,37 +340,32 @@ impl<F: Field> ModExpInputs<F> {
         let base_pow = RandPow::configure(
             cb,
             cb.challenges().keccak_input(),
-            base_len_expected,
+            base_len_expected.clone(),
             Some(exp_pow.expr()),
         );
 
-        let input_bytes_rlc = rlc_word_rev(&modulus, cb.challenges().keccak_input()) //rlc of base
-            + modulus_pow.expr() * rlc_word_rev(&exp, cb.challenges().keccak_input()) //rlc of exp plus r**base_len
-            + exp_pow.expr() * rlc_word_rev(&base, cb.challenges().keccak_input()) //rlc of exp plus r**(base_len + exp_len)
+        let input_bytes_rlc = modulus_rlc.expr() //rlc of modulus
+            + modulus_pow.expr() * exp_rlc.expr() //rlc of exp plus r**modulus_len
+            + exp_pow.expr() * base_rlc.expr() //rlc of base plus r**(modulus_len + exp_len)
             + base_pow.expr() * modulus_len.memory_rlc()
             + base_pow.expr() * r_pow_32 * exp_len.memory_rlc()
             + base_pow.expr() * r_pow_64 * base_len.memory_rlc();
 
-        // println!("phase 2 cell used {}",
-        //     padding_pow.phase2_cell_cost() + [&modulus_pow, &exp_pow,
-        // &base_pow].iter().map(|pw|pw.phase2_cell_cost()).sum::<usize>() );
-
         Self {
             base_len,
             modulus_len,
             exp_len,
             base_pow,
-            base,
+            base_rlc,
             modulus_pow,
-            modulus,
+            modulus_rlc,
             exp_pow,
-            exp,
+            exp_rlc,
+            exp_bit_len,
             input_valid,
             input_bytes_rlc,
+            lens_expected: [base_len_expected, exp_len_expected, modulus_len_expected],
             input_len_expected,
-            base_limbs,
-            exp_limbs,
-            modulus_limbs,
         }
     }
 
@@ -411,17 +417,26 @@ impl<F: Field> ModExpInputs<F> {
             linked_v = Some(assigned);
         }
 
-        for (val_r, input_limbs) in
+        for (value, rlc_cell) in
             values
                 .iter()
-                .zip([&self.base_limbs, &self.exp_limbs, &self.modulus_limbs])
+                .zip([&self.base_rlc, &self.exp_rlc, &self.modulus_rlc])
         {
-            input_limbs.assign(region, offset, val_r)?;
+            let rlc = if input_valid {
+                rlc_value(value, region.challenges().keccak_input())
+            } else {
+                Value::known(F::zero())
+            };
+            rlc_cell.assign(region, offset, rlc)?;
         }
 
-        for (val, input_bytes) in values.zip([&self.base, &self.exp, &self.modulus]) {
-            assign_word(region, offset, input_bytes, val)?;
-        }
+        let exp_bit_len = if input_valid {
+            ModExpTable::exp_bit_lens(&values[1])[SIZE_LIMIT - 1]
+        } else {
+            0
+        };
+        self.exp_bit_len
+            .assign(region, offset, Value::known(F::from(exp_bit_len)))?;
 
         Ok(())
     }
@@ -429,10 +444,9 @@ impl<F: Field> ModExpInputs<F> {
 
 #[derive(Clone, Debug)]
 struct ModExpOutputs<F> {
-    result: Word<F>,
+    result_rlc: Cell<F>,
     is_result_zero: IsZeroGadget<F>,
     output_bytes_rlc: Expression<F>,
-    pub result_limbs: Limbs<F>,
 }
 
 impl<F: Field> ModExpOutputs<F> {
@@ -446,20 +460,16 @@ impl<F: Field> ModExpOutputs<F> {
             IsZeroGadget::construct(cb, output_len)
         });
 
-        let result = cb.query_bytes();
-        let result_limbs = Limbs::configure(cb, &result);
+        // the result is left-padded to the modulus len, so its rlc is the rlc of the output
+        let result_rlc = cb.query_cell_phase2();
 
-        let output_bytes_rlc = util::select::expr(
-            is_result_zero.expr(),
-            0.expr(),
-            rlc_word_rev(&result, cb.challenges().keccak_input()),
-        );
+        let output_bytes_rlc =
+            util::select::expr(is_result_zero.expr(), 0.expr(), result_rlc.expr());
 
         Self {
-            result,
+            result_rlc,
             is_result_zero,
             output_bytes_rlc,
-            result_limbs,
         }
     }
 
@@ -479,83 +489,26 @@ impl<F: Field> ModExpOutputs<F> {
     ) -> Result<(), Error> {
         self.is_result_zero
             .assign(region, offset, F::from(output_len as u64))?;
-        self.result_limbs.assign(region, offset, &data)?;
-        assign_word(region, offset, &self.result, data)?;
-        Ok(())
-    }
-}
-
-#[derive(Clone, Debug)]
-pub(crate) struct Limbs<F> {
-    byte14_split_lo: Cell<F>,
-    byte14_split_hi: Cell<F>,
-    limbs: [Expression<F>; 3],
-}
-
-impl<F: Field> Limbs<F> {
-    pub fn configure(cb: &mut EVMConstraintBuilder<F>, word: &Word<F>) -> Self {
-        let byte14_split_lo = cb.query_byte();
-        let byte14_split_hi = cb.query_byte();
-
-        cb.require_equal(
-            "split 14th byte in word into half",
-            word[MODEXP_SIZE_LIMIT - 14].expr(),
-            byte14_split_lo.expr() + byte14_split_hi.expr(),
-        );
-
-        let inv_16 = Expression::Constant(F::from(16u64).invert().unwrap());
-
-        let limbs = [
-            expr_from_bytes(
-                &std::iter::once(&byte14_split_lo)
-                    .chain(&word[MODEXP_SIZE_LIMIT - 13..])
-                    .collect::<Vec<_>>(),
-            ),
-            expr_from_bytes(
-                &word[MODEXP_SIZE_LIMIT - 27..MODEXP_SIZE_LIMIT - 14]
-                    .iter()
-                    .chain(std::iter::once(&byte14_split_hi))
-                    .collect::<Vec<_>>(),
-            ) * inv_16,
-            expr_from_bytes(&word[..MODEXP_SIZE_LIMIT - 27]),
-        ];
-
-        Self {
-            byte14_split_hi,
-            byte14_split_lo,
-            limbs,
-        }
-    }
-
-    pub fn limbs(&self) -> [Expression<F>; 3] {
-        self.limbs.clone()
-    }
-
-    pub fn assign(
-        &self,
-        region: &mut CachedRegion<'_, '_, F>,
-        offset: usize,
-        big_int: &[u8; MODEXP_SIZE_LIMIT],
-    ) -> Result<(), Error> {
-        let byte14_lo = big_int[MODEXP_SIZE_LIMIT - 14] & 0xf;
-        let byte14_hi = big_int[MODEXP_SIZE_LIMIT - 14] & 0xf0;
-
-        self.byte14_split_lo
-            .assign(region, offset, Value::known(F::from(byte14_lo as u64)))?;
-        self.byte14_split_hi
-            .assign(region, offset, Value::known(F::from(byte14_hi as u64)))?;
+        self.result_rlc.assign(
+            region,
+            offset,
+            rlc_value(&data, region.challenges().keccak_input()),
+        )?;
         Ok(())
     }
 }
 
+/// Gas cost of modexp following EIP-2565, with
+/// `iteration_count = max(exp_bit_len + 255, 8 * Esize, 257) - 256`, which is
+/// `max(exp_bit_len - 1, 1)` if `Esize <= 32`, else
+/// `max(8 * (Esize - 32) + bit_len(exp_head) - 1, 8 * (Esize - 32))` where
+/// `exp_head` is the first 32 bytes of the exponent.
 #[derive(Clone, Debug)]
 pub(crate) struct ModExpGasCost<F> {
-    max_length: MinMaxGadget<F, 1>,
+    max_length: MinMaxGadget<F, 2>,
     words: ConstantDivisionGadget<F, 1>,
-    exp_is_zero: IsZeroGadget<F>,
-    exp_byte_size: ByteSizeGadget<F>,
-    exp_msb_bit_length: BitLengthGadget<F>,
-    exp_msb: BinaryNumberGadget<F, N_BITS_U8>,
+    bit_len_or_size: MinMaxGadget<F, 2>,
+    iteration_count: MinMaxGadget<F, 2>,
     calc_gas: ConstantDivisionGadget<F, N_BYTES_U64>,
     dynamic_gas: MinMaxGadget<F, N_BYTES_U64>,
 }
@@ -563,53 +516,22 @@ pub(crate) struct ModExpGasCost<F> {
 impl<F: Field> ModExpGasCost<F> {
     fn construct(
         cb: &mut EVMConstraintBuilder<F>,
-        b_size: &SizeRepresent<F>,
-        exp: &[Cell<F>; N_BYTES_WORD],
-        m_size: &SizeRepresent<F>,
+        [b_size, e_size, m_size]: [Expression<F>; 3],
+        exp_bit_len: Expression<F>,
     ) -> Self {
-        let max_length = MinMaxGadget::construct(cb, b_size.value(), m_size.value());
+        let max_length = MinMaxGadget::construct(cb, b_size, m_size);
         let words = ConstantDivisionGadget::construct(cb, max_length.max() + 7.expr(), 8);
         let multiplication_complexity = words.quotient() * words.quotient();
-        let exp_is_zero = IsZeroGadget::construct(cb, expr_from_bytes(exp));
-
-        let (exp_byte_size, exp_msb, exp_msb_bit_length) =
-            cb.condition(not::expr(exp_is_zero.expr()), |cb| {
-                let exp_byte_size = ByteSizeGadget::construct(
-                    cb,
-                    exp.iter()
-                        .rev()
-                        .map(Expr::expr)
-                        .collect::<Vec<Expression<F>>>()
-                        .try_into()
-                        .unwrap(),
-                );
-                let exp_msb =
-                    BinaryNumberGadget::construct(cb, exp_byte_size.most_significant_value.expr());
-                let exp_msb_bit_length = BitLengthGadget::construct(
-                    cb,
-                    exp_msb
-                        .bits
-                        .iter()
-                        .rev()
-                        .map(Expr::expr)
-                        .collect::<Vec<Expression<F>>>()
-                        .try_into()
-                        .unwrap(),
-                );
-                (exp_byte_size, exp_msb, exp_msb_bit_length)
-            });
-        let exp_bit_length =
-            (exp_byte_size.size() - 1.expr()) * N_BITS_U8.expr() + exp_msb_bit_length.size();
-
-        // We already restrict Esize <= 32. So we can completely ignore the branch concerning
-        // Esize > 32. We only care about whether or not exponent is zero.
-        let iteration_count = select::expr(
-            exp_is_zero.expr(),
-            0.expr(),
-            exp_bit_length.expr() - 1.expr(),
+
+        let bit_len_or_size =
+            MinMaxGadget::construct(cb, exp_bit_len + 255.expr(), e_size * 8.expr());
+        let iteration_count = MinMaxGadget::construct(cb, bit_len_or_size.max(), 257.expr());
+
+        let calc_gas = ConstantDivisionGadget::construct(
+            cb,
+            multiplication_complexity * (iteration_count.max() - 256.expr()),
+            3,
         );
-        let calc_gas =
-            ConstantDivisionGadget::construct(cb, multiplication_complexity * iteration_count, 3);
         let dynamic_gas = MinMaxGadget::construct(
             cb,
             GasCost::PRECOMPILE_MODEXP_MIN.expr(),
@@ -619,10 +541,8 @@ impl<F: Field> ModExpGasCost<F> {
         Self {
             max_length,
             words,
-            exp_is_zero,
-            exp_byte_size,
-            exp_msb,
-            exp_msb_bit_length,
+            bit_len_or_size,
+            iteration_count,
             calc_gas,
             dynamic_gas,
         }
@@ -632,55 +552,37 @@ impl<F: Field> ModExpGasCost<F> {
         &self,
         region: &mut CachedRegion<'_, '_, F>,
         offset: usize,
-        b_size: &U256,
-        m_size: &U256,
-        exponent: &[u8; MODEXP_SIZE_LIMIT],
+        [b_size, e_size, m_size]: [u64; 3],
+        exp_bit_len: u64,
     ) -> Result<u64, Error> {
-        self.max_length.assign(
-            region,
-            offset,
-            b_size.to_scalar().expect("Bsize is within scalar field"),
-            m_size.to_scalar().expect("Msize is within scalar field"),
-        )?;
+        self.max_length
+            .assign(region, offset, F::from(b_size), F::from(m_size))?;
+        let max_length = b_size.max(m_size);
         self.words
-            .assign(region, offset, b_size.max(m_size).as_u128() + 7u128)?;
-        let exp_word = U256::from_big_endian(exponent);
-        self.exp_is_zero.assign(
+            .assign(region, offset, (max_length + 7) as u128)?;
+        let words = (max_length + 7) / 8;
+        let multiplication_complexity = words * words;
+
+        self.bit_len_or_size.assign(
             region,
             offset,
-            exp_word
-                .to_scalar()
-                .expect("exponent is within scalar field"),
+            F::from(exp_bit_len + 255),
+            F::from(e_size * 8),
         )?;
-        self.exp_byte_size
-            .assign(region, offset, ByteOrWord::Word(exp_word))?;
-        let exp_byte_size = (exp_word.bits() + 7) / 8;
-        let exp_msb = if exp_byte_size > 0 {
-            exponent[N_BYTES_WORD - exp_byte_size]
-        } else {
-            0
-        };
-        self.exp_msb.assign(region, offset, exp_msb)?;
-        self.exp_msb_bit_length
-            .assign(region, offset, ByteOrWord::Byte(exp_msb))?;
-        let exp_bit_length = exp_word.bits();
-        let max_length = b_size.max(m_size);
-        let words = (max_length + 7) / 8;
-        let multiplication_complexity = words * words;
-        let iteration_count = if exp_word.is_zero() {
-            0
-        } else {
-            exp_bit_length - 1
-        };
+        let bit_len_or_size = (exp_bit_len + 255).max(e_size * 8);
+        self.iteration_count
+            .assign(region, offset, F::from(bit_len_or_size), F::from(257))?;
+        let iteration_count = bit_len_or_size.max(257) - 256;
+
         let numerator = multiplication_complexity * iteration_count;
-        self.calc_gas.assign(region, offset, numerator.as_u128())?;
+        self.calc_gas.assign(region, offset, numerator as u128)?;
         self.dynamic_gas.assign(
             region,
             offset,
             F::from(GasCost::PRECOMPILE_MODEXP_MIN.0),
-            F::from((numerator / 3).as_u64()),
+            F::from(numerator / 3),
         )?;
-        let gas_cost = std::cmp::max(GasCost::PRECOMPILE_MODEXP_MIN.0, (numerator / 3).as_u64());
+        let gas_cost = std::cmp::max(GasCost::PRECOMPILE_MODEXP_MIN.0, numerator / 3);
 
         Ok(gas_cost)
     }
@@ -698,14 +600,15 @@ pub struct ModExpGadget<F> {
     restore_context_gadget: RestoreContextGadget<F>,
 
     input: ModExpInputs<F>,
+    input_len: MinMaxGadget<F, N_BYTES_U64>,
     padding_zero: RandPowRepresent<F, INPUT_REPRESENT_BITS>,
     output: ModExpOutputs<F>,
 
     input_bytes_acc: Cell<F>,
     output_bytes_acc: Cell<F>,
     gas_cost: Cell<F>,
+    input_len_copied: Cell<F>,
     gas_cost_gadget: ModExpGasCost<F>,
-    garbage_bytes_holder: [Cell<F>; INPUT_LIMIT - 96],
 }
 
 impl<F: Field> ExecutionGadget<F> for ModExpGadget<F> {
@@ -718,6 +621,7 @@ impl<F: Field> ExecutionGadget<F> for ModExpGadget<F> {
         let input_bytes_acc = cb.query_cell_phase2();
         let output_bytes_acc = cb.query_cell_phase2();
         let gas_cost = cb.query_cell();
+        let input_len_copied = cb.query_cell();
 
         let [is_success, callee_address, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
             [
@@ -738,10 +642,19 @@ impl<F: Field> ExecutionGadget<F> for ModExpGadget<F> {
         );
 
         let input = ModExpInputs::configure(cb);
+
+        // only the input bytes up to the expected len are copied to the precompile call, and
+        // the missing ones are right-padded with zeroes
+        let input_len = MinMaxGadget::construct(cb, call_data_length.expr(), input.len_expected());
+        cb.require_equal(
+            "input len copied is min(call data length, expected input len)",
+            input_len_copied.expr(),
+            input_len.min(),
+        );
         let padding_zero = RandPowRepresent::configure(
             cb,
             cb.challenges().keccak_input(),
-            MODEXP_INPUT_LIMIT.expr() - input.len_expected(),
+            input.len_expected() - input_len.min(),
             None,
         );
 
@@ -765,22 +678,20 @@ impl<F: Field> ExecutionGadget<F> for ModExpGadget<F> {
             input.modulus_len(),
         );
 
-        cb.condition(util::not::expr(output.is_nil()), |cb| {
+        cb.condition(input.is_valid(), |cb| {
             cb.modexp_table_lookup(
-                input.base_limbs.limbs(),
-                input.exp_limbs.limbs(),
-                input.modulus_limbs.limbs(),
-                output.result_limbs.limbs(),
+                input.base_rlc.expr(),
+                input.exp_rlc.expr(),
+                input.modulus_rlc.expr(),
+                output.result_rlc.expr(),
+                input.exp_bit_len.expr(),
             );
         });
 
-        let garbage_bytes_holder = cb.query_bytes();
-
         cb.require_equal(
             "input acc bytes with padding must equal",
-            input_bytes_acc.expr(),
-            padding_zero.expr() * input.bytes_rlc()
-                + rlc_rev(&garbage_bytes_holder, cb.challenges().keccak_input()),
+            input_bytes_acc.expr() * padding_zero.expr(),
+            input.bytes_rlc(),
         );
 
         cb.require_equal(
@@ -790,7 +701,7 @@ impl<F: Field> ExecutionGadget<F> for ModExpGadget<F> {
         );
 
         let gas_cost_gadget =
-            ModExpGasCost::construct(cb, &input.base_len, &input.exp, &input.modulus_len);
+            ModExpGasCost::construct(cb, input.lens_expected.clone(), input.exp_bit_len.expr());
         cb.require_equal(
             "modexp: gas cost",
             gas_cost.expr(),
@@ -818,13 +729,14 @@ impl<F: Field> ExecutionGadget<F> for ModExpGadget<F> {
             return_data_length,
             restore_context_gadget,
             input,
+            input_len,
             padding_zero,
             output,
             input_bytes_acc,
             output_bytes_acc,
             gas_cost,
+            input_len_copied,
             gas_cost_gadget,
-            garbage_bytes_holder,
         }
     }
 
@@ -841,29 +753,26 @@ impl<F: Field> ExecutionGadget<F> for ModExpGadget<F> {
             self.input
                 .assign(region, offset, (data.valid, data.input_lens, data.inputs))?;
 
-            let input_expected_len = 96
-                + if data.valid {
-                    data.input_lens.iter().map(U256::as_usize).sum::<usize>()
-                } else {
-                    0
-                };
-
-            let garbage_bytes = if call.call_data_length as usize > input_expected_len {
-                let mut bts = Vec::new();
-                bts.resize(input_expected_len - 96, 0); //front prefix zero
-                bts.append(&mut Vec::from(&data.input_memory[input_expected_len..]));
-                bts.resize(96, 0); //padding zero
-                bts
-            } else {
-                Vec::from([0u8; 96])
-            };
+            let lens_expected = data
+                .input_lens
+                .map(|len| if data.valid { len.as_u64() } else { 0 });
+            let input_expected_len = 96 + lens_expected.iter().sum::<u64>();
+            let input_len = call.call_data_length.min(input_expected_len);
 
-            self.padding_zero
-                .assign(region, offset, INPUT_LIMIT - input_expected_len, None)?;
-
-            for (cell, bt) in self.garbage_bytes_holder.iter().zip(garbage_bytes) {
-                cell.assign(region, offset, Value::known(F::from(bt as u64)))?;
-            }
+            self.input_len.assign(
+                region,
+                offset,
+                F::from(call.call_data_length),
+                F::from(input_expected_len),
+            )?;
+            self.input_len_copied
+                .assign(region, offset, Value::known(F::from(input_len)))?;
+            self.padding_zero.assign(
+                region,
+                offset,
+                (input_expected_len - input_len) as usize,
+                None,
+            )?;
 
             self.output
                 .assign(region, offset, (data.output_len, data.output))?;
@@ -873,27 +782,22 @@ impl<F: Field> ExecutionGadget<F> for ModExpGadget<F> {
                 .keccak_input()
                 .map(|randomness| rlc::value(data.input_memory.iter().rev(), randomness));
 
-            let n_padded_zeroes_pow = region
-                .challenges()
-                .keccak_input()
-                .map(|r| r.pow(&[INPUT_LIMIT as u64 - call.call_data_length, 0, 0, 0]));
-
             let output_rlc = region
                 .challenges()
                 .keccak_input()
                 .map(|randomness| rlc::value(data.output_memory.iter().rev(), randomness));
 
-            self.input_bytes_acc
-                .assign(region, offset, n_padded_zeroes_pow * input_rlc)?;
+            self.input_bytes_acc.assign(region, offset, input_rlc)?;
             self.output_bytes_acc.assign(region, offset, output_rlc)?;
 
-            let gas_cost = self.gas_cost_gadget.assign(
-                region,
-                offset,
-                &data.input_lens[0],
-                &data.input_lens[2],
-                &data.inputs[1],
-            )?;
+            let exp_bit_len = if data.valid {
+                ModExpTable::exp_bit_lens(&data.inputs[1])[SIZE_LIMIT - 1]
+            } else {
+                0
+            };
+            let gas_cost =
+                self.gas_cost_gadget
+                    .assign(region, offset, lens_expected, exp_bit_len)?;
             self.gas_cost
                 .assign(region, offset, Value::known(F::from(gas_cost)))?;
         } else {
@@ -942,7 +846,6 @@ impl<F: Field> ExecutionGadget<F> for ModExpGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use super::*;
     use bus_mapping::{
         evm::{OpcodeId, PrecompileCallArgs},
         precompile::PrecompileCalls,
@@ -953,64 +856,6 @@ mod test {
 
     use crate::test_util::CircuitTestBuilder;
 
-    #[test]
-    fn test_limbs() {
-        use crate::table::ModExpTable;
-        use halo2_proofs::{arithmetic::FieldExt, halo2curves::bn256::Fr};
-        use misc_precompiled_circuit::circuits::modexp::Number;
-        use num_bigint::BigUint;
-
-        // simply take an hash for test
-        let bi = BigUint::parse_bytes(
-            b"fcb51a0695d8f838b1ee009b3fbf66bda078cd64590202a864a8f3e8c4315c47",
-            16,
-        )
-        .unwrap();
-        let n = Number::<Fr>::from_bn(&bi);
-        let w = word!("0xfcb51a0695d8f838b1ee009b3fbf66bda078cd64590202a864a8f3e8c4315c47");
-        let mut bytes = [0u8; 32];
-        w.to_big_endian(&mut bytes);
-        assert_eq!(BigUint::from_bytes_be(&bytes), bi);
-
-        let byte14_lo = bytes[MODEXP_SIZE_LIMIT - 14] & 0xf;
-        let byte14_hi = bytes[MODEXP_SIZE_LIMIT - 14] & 0xf0;
-
-        let limb0: Fr = U256::from_big_endian(
-            &(std::iter::once(byte14_lo))
-                .chain(bytes[MODEXP_SIZE_LIMIT - 13..].iter().copied())
-                .collect::<Vec<_>>(),
-        )
-        .to_scalar()
-        .unwrap();
-
-        let limb1: Fr = U256::from_big_endian(
-            &bytes[MODEXP_SIZE_LIMIT - 27..MODEXP_SIZE_LIMIT - 14]
-                .iter()
-                .copied()
-                .chain(std::iter::once(byte14_hi))
-                .collect::<Vec<_>>(),
-        )
-        .to_scalar()
-        .unwrap();
-
-        let limb2: Fr = U256::from_big_endian(&bytes[..MODEXP_SIZE_LIMIT - 27])
-            .to_scalar()
-            .unwrap();
-
-        assert_eq!(limb0, n.limbs[0].value);
-        assert_eq!(limb1, n.limbs[1].value * Fr::from(16_u64));
-        assert_eq!(limb2, n.limbs[2].value);
-
-        let nt: Fr = ModExpTable::native_u256(&w);
-
-        let table_split = ModExpTable::split_u256_108bit_limbs(&w);
-        assert_eq!(Fr::from_u128(table_split[0]), n.limbs[0].value);
-        assert_eq!(Fr::from_u128(table_split[1]), n.limbs[1].value);
-        assert_eq!(Fr::from_u128(table_split[2]), n.limbs[2].value);
-        assert_eq!(nt, n.limbs[3].value);
-        //Limb::new(None, value)
-    }
-
     lazy_static::lazy_static! {
         static ref TEST_VECTOR: Vec<PrecompileCallArgs> = {
             vec![
@@ -1244,6 +1089,96 @@ mod test {
             ]
         };
 
+        static ref TEST_LARGE_VECTOR: Vec<PrecompileCallArgs> = {
+            vec![
+                PrecompileCallArgs {
+                    name: "modexp length above 32 bytes",
+                    setup_code: bytecode! {
+                        // Base size
+                        PUSH1(0x40)
+                        PUSH1(0x00)
+                        MSTORE
+                        // Esize
+                        PUSH1(0x21)
+                        PUSH1(0x20)
+                        MSTORE
+                        // Msize
+                        PUSH1(0x40)
+                        PUSH1(0x40)
+                        MSTORE
+                        // B
+                        PUSH32(word!("0x0000000000000000000000000000000000000000000000000000000000000008"))
+                        PUSH1(0x60)
+                        MSTORE
+                        PUSH32(word!("0xfcb51a0695d8f838b1ee009b3fbf66bda078cd64590202a864a8f3e8c4315c47"))
+                        PUSH1(0x80)
+                        MSTORE
+                        // E
+                        PUSH32(word!("0x0100000000000000000000000000000000000000000000000000000000000000"))
+                        PUSH1(0xA0)
+                        MSTORE
+                        PUSH1(0x09)
+                        PUSH1(0xC0)
+                        MSTORE8
+                        // M
+                        PUSH32(word!("0xfcb51a0695d8f838b1ee009b3fbf66bda078cd64590202a864a8f3e8c4315c47"))
+                        PUSH1(0xC1)
+                        MSTORE
+                        PUSH32(word!("0x1000000000000000000000000000000000000000000000000000000000000009"))
+                        PUSH1(0xE1)
+                        MSTORE
+                    },
+                    call_data_offset: 0x0.into(),
+                    call_data_length: 0x101.into(),
+                    ret_offset: 0x120.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Modexp.address().to_word(),
+                    ..Default::default()
+                },
+                PrecompileCallArgs {
+                    name: "modexp length above 32 bytes with padding 0",
+                    setup_code: bytecode! {
+                        // Base size
+                        PUSH1(0x40)
+                        PUSH1(0x00)
+                        MSTORE
+                        // Esize
+                        PUSH1(0x21)
+                        PUSH1(0x20)
+                        MSTORE
+                        // Msize
+                        PUSH1(0x40)
+                        PUSH1(0x40)
+                        MSTORE
+                        // B
+                        PUSH32(word!("0x0000000000000000000000000000000000000000000000000000000000000008"))
+                        PUSH1(0x60)
+                        MSTORE
+                        PUSH32(word!("0xfcb51a0695d8f838b1ee009b3fbf66bda078cd64590202a864a8f3e8c4315c47"))
+                        PUSH1(0x80)
+                        MSTORE
+                        // E
+                        PUSH32(word!("0x0100000000000000000000000000000000000000000000000000000000000000"))
+                        PUSH1(0xA0)
+                        MSTORE
+                        PUSH1(0x09)
+                        PUSH1(0xC0)
+                        MSTORE8
+                        // M, without its last 16 bytes
+                        PUSH32(word!("0xfcb51a0695d8f838b1ee009b3fbf66bda078cd64590202a864a8f3e8c4315c47"))
+                        PUSH1(0xC1)
+                        MSTORE
+                    },
+                    call_data_offset: 0x0.into(),
+                    call_data_length: 0xF1.into(),
+                    ret_offset: 0x120.into(),
+                    ret_size: 0x40.into(),
+                    address: PrecompileCalls::Modexp.address().to_word(),
+                    ..Default::default()
+                },
+            ]
+        };
+
         static ref TEST_INVALID_VECTOR: Vec<PrecompileCallArgs> = {
             vec![
                 PrecompileCallArgs {
@@ -1258,7 +1193,7 @@ mod test {
                         PUSH1(0x20)
                         MSTORE
                         // Msize
-                        PUSH1(0x21)
+                        PUSH2(0x0201)
                         PUSH1(0x40)
                         MSTORE
                         // B, E and M
@@ -1327,6 +1262,25 @@ mod test {
         }
     }
 
+    #[test]
+    fn precompile_modexp_test_large() {
+        let call_kinds = vec![
+            OpcodeId::CALL,
+            OpcodeId::STATICCALL,
+            OpcodeId::DELEGATECALL,
+            OpcodeId::CALLCODE,
+        ];
+
+        for (test_vector, &call_kind) in TEST_LARGE_VECTOR.iter().cartesian_product(&call_kinds) {
+            let bytecode = test_vector.with_call_op(call_kind);
+
+            CircuitTestBuilder::new_from_test_ctx(
+                TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+            )
+            .run();
+        }
+    }
+
     // notice, "invalid" test would not actuall work until bus-mapping put calling fail case being
     // handle in normal CallOp, i.e. return None in
     // bus_mapping::circuit_input_builder::input_state_ref::CircuitInputStateRef::get_step_err
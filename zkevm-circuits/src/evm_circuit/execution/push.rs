@@ -5,7 +5,7 @@ use crate::{
         step::ExecutionState,
         util::{
             and,
-            common_gadget::SameContextGadget,
+            common_gadget::{HardforkGadget, SameContextGadget},
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
                 Transition::Delta,
@@ -25,6 +25,7 @@ use halo2_proofs::{circuit::Value, plonk::Error};
 pub(crate) struct PushGadget<F> {
     same_context: SameContextGadget<F>,
     is_push0: IsZeroGadget<F>,
+    hardfork: HardforkGadget<F>,
     value: Word<F>,
     is_pushed: [Cell<F>; 32],
     is_padding: [Cell<F>; 32],
@@ -41,6 +42,11 @@ impl<F: Field> ExecutionGadget<F> for PushGadget<F> {
         let opcode = cb.query_cell();
 
         let is_push0 = IsZeroGadget::construct(cb, opcode.expr() - OpcodeId::PUSH0.expr());
+        // PUSH0 is activated in Shanghai (EIP-3855).
+        let hardfork = HardforkGadget::construct(cb);
+        cb.condition(is_push0.expr(), |cb| {
+            hardfork.require_enabled(cb, OpcodeId::PUSH0);
+        });
 
         let value = cb.query_word_rlc();
         cb.stack_push(value.expr());
@@ -145,6 +151,7 @@ impl<F: Field> ExecutionGadget<F> for PushGadget<F> {
         Self {
             same_context,
             is_push0,
+            hardfork,
             value,
             is_pushed,
             is_padding,
@@ -158,7 +165,7 @@ impl<F: Field> ExecutionGadget<F> for PushGadget<F> {
         region: &mut CachedRegion<'_, '_, F>,
         offset: usize,
         block: &Block<F>,
-        _: &Transaction,
+        tx: &Transaction,
         call: &Call,
         step: &ExecStep,
     ) -> Result<(), Error> {
@@ -170,6 +177,11 @@ impl<F: Field> ExecutionGadget<F> for PushGadget<F> {
             offset,
             F::from(opcode.as_u64() - OpcodeId::PUSH0.as_u64()),
         )?;
+        self.hardfork.assign(
+            region,
+            offset,
+            block.context.ctxs[&tx.block_number].hardfork,
+        )?;
 
         let bytecode = block
             .bytecodes
@@ -0,0 +1,559 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_ACCOUNT_ADDRESS,
+        step::ExecutionState,
+        util::{
+            common_gadget::{RestoreContextGadget, TransferGadget, TransferGadgetInfo},
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
+                Transition::{Delta, Same},
+            },
+            from_bytes,
+            math_gadget::{IsEqualGadget, IsZeroGadget},
+            not, select, CachedRegion, Cell, StepRws, Word,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{AccountFieldTag, CallContextFieldTag},
+    util::Expr,
+};
+use eth_types::{
+    evm_types::{GasCost, OpcodeId},
+    Field, ToLittleEndian, ToScalar,
+};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget for SELFDESTRUCT. The whole balance of the current account is
+/// transferred to the beneficiary. Per EIP-6780 the balance, nonce and code
+/// hash of the current account are then reset to zero only if it was created
+/// in the same transaction.
+#[derive(Clone, Debug)]
+pub(crate) struct SelfDestructGadget<F> {
+    opcode: Cell<F>,
+    beneficiary_word: Word<F>,
+    tx_id: Cell<F>,
+    is_static: Cell<F>,
+    reversion_info: ReversionInfo<F>,
+    callee_address: Cell<F>,
+    is_warm: Cell<F>,
+    beneficiary_code_hash: Cell<F>,
+    beneficiary_not_exists: IsZeroGadget<F>,
+    is_self_beneficiary: IsEqualGadget<F>,
+    balance: Word<F>,
+    is_created: Cell<F>,
+    transfer: TransferGadget<F>,
+    code_hash_previous: Cell<F>,
+    #[cfg(feature = "scroll")]
+    keccak_code_hash_previous: Cell<F>,
+    nonce: Cell<F>,
+    code_hash: Cell<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for SelfDestructGadget<F> {
+    const NAME: &'static str = "SELFDESTRUCT";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::SELFDESTRUCT;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.opcode_lookup(opcode.expr(), 1.expr());
+        cb.require_equal(
+            "Opcode should be SELFDESTRUCT",
+            opcode.expr(),
+            OpcodeId::SELFDESTRUCT.expr(),
+        );
+
+        let beneficiary_word = cb.query_word_rlc();
+        let beneficiary = from_bytes::expr(&beneficiary_word.cells[..N_BYTES_ACCOUNT_ADDRESS]);
+        cb.stack_pop(beneficiary_word.expr());
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+
+        // constrain not in static call
+        let is_static = cb.call_context(None, CallContextFieldTag::IsStatic);
+        cb.require_zero("is_static is false", is_static.expr());
+
+        let mut reversion_info = cb.reversion_info_read(None);
+        let callee_address = cb.call_context(None, CallContextFieldTag::CalleeAddress);
+
+        let is_warm = cb.query_bool();
+        cb.account_access_list_write(
+            tx_id.expr(),
+            beneficiary.expr(),
+            1.expr(),
+            is_warm.expr(),
+            Some(&mut reversion_info),
+        );
+
+        // For non-existing accounts the code_hash must be 0 in the rw_table.
+        let beneficiary_code_hash = cb.query_cell_phase2();
+        cb.account_read(
+            beneficiary.expr(),
+            AccountFieldTag::CodeHash,
+            beneficiary_code_hash.expr(),
+        );
+        let beneficiary_not_exists = IsZeroGadget::construct(cb, beneficiary_code_hash.expr());
+        let is_self_beneficiary =
+            IsEqualGadget::construct(cb, beneficiary.expr(), callee_address.expr());
+
+        let balance = cb.query_word_rlc();
+        cb.account_read(
+            callee_address.expr(),
+            AccountFieldTag::Balance,
+            balance.expr(),
+        );
+
+        let is_created = cb.query_bool();
+        cb.account_created_read(tx_id.expr(), callee_address.expr(), is_created.expr());
+
+        // Transfer the whole balance to the beneficiary, which is created if it
+        // doesn't exist and the balance is not zero.
+        let code_hash_previous = cb.query_cell();
+        #[cfg(feature = "scroll")]
+        let keccak_code_hash_previous = cb.query_cell_phase2();
+        let transfer = TransferGadget::construct(
+            cb,
+            callee_address.expr(),
+            beneficiary.expr(),
+            not::expr(beneficiary_not_exists.expr()),
+            0.expr(),
+            code_hash_previous.expr(),
+            #[cfg(feature = "scroll")]
+            keccak_code_hash_previous.expr(),
+            balance.clone(),
+            &mut reversion_info,
+        );
+
+        // Destroy the current account only if it was created in this tx. The
+        // balance after transfer is only non-zero when the beneficiary is the
+        // current account itself, in which case the balance is burnt.
+        let nonce = cb.query_cell();
+        let code_hash = cb.query_cell_phase2();
+        cb.condition(is_created.expr(), |cb| {
+            for (field_tag, value_prev) in [
+                (
+                    AccountFieldTag::Balance,
+                    is_self_beneficiary.expr() * balance.expr(),
+                ),
+                (AccountFieldTag::Nonce, nonce.expr()),
+                (AccountFieldTag::CodeHash, code_hash.expr()),
+            ] {
+                cb.account_write(
+                    callee_address.expr(),
+                    field_tag,
+                    0.expr(),
+                    value_prev,
+                    Some(&mut reversion_info),
+                );
+            }
+        });
+
+        // Call ends with SELFDESTRUCT must be successful
+        cb.call_context_lookup(false.expr(), None, CallContextFieldTag::IsSuccess, 1.expr());
+
+        // No gas refund for SELFDESTRUCT since EIP-3529.
+        let gas_cost = GasCost::SELFDESTRUCT.expr()
+            + select::expr(
+                is_warm.expr(),
+                0.expr(),
+                GasCost::COLD_ACCOUNT_ACCESS.expr(),
+            )
+            + beneficiary_not_exists.expr()
+                * not::expr(transfer.value_is_zero())
+                * GasCost::NEW_ACCOUNT.expr();
+
+        let is_to_end_tx = cb.next.execution_state_selector([ExecutionState::EndTx]);
+        cb.require_equal(
+            "Go to EndTx only when is_root",
+            cb.curr.state.is_root.expr(),
+            is_to_end_tx,
+        );
+
+        // When it's a root call
+        cb.condition(cb.curr.state.is_root.expr(), |cb| {
+            cb.require_step_state_transition(StepStateTransition {
+                call_id: Same,
+                rw_counter: Delta(cb.rw_counter_offset()),
+                gas_left: Delta(-gas_cost.clone()),
+                ..StepStateTransition::any()
+            });
+        });
+
+        // When it's an internal call
+        // +1 Write TxAccessListAccount
+        // +3 Write Account (callee) Balance, Nonce, CodeHash if created in this tx
+        let reversible_write_counter_increase =
+            1.expr() + is_created.expr() * 3.expr() + transfer.reversible_w_delta();
+        let restore_context = cb.condition(not::expr(cb.curr.state.is_root.expr()), |cb| {
+            RestoreContextGadget::construct2(
+                cb,
+                true.expr(),
+                gas_cost,
+                0.expr(),
+                0.expr(),
+                0.expr(),
+                0.expr(),
+                reversible_write_counter_increase,
+            )
+        });
+
+        Self {
+            opcode,
+            beneficiary_word,
+            tx_id,
+            is_static,
+            reversion_info,
+            callee_address,
+            is_warm,
+            beneficiary_code_hash,
+            beneficiary_not_exists,
+            is_self_beneficiary,
+            balance,
+            is_created,
+            transfer,
+            code_hash_previous,
+            #[cfg(feature = "scroll")]
+            keccak_code_hash_previous,
+            nonce,
+            code_hash,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode.unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+
+        let mut rws = StepRws::new(block, step);
+
+        let beneficiary = rws.next().stack_value();
+        self.beneficiary_word
+            .assign(region, offset, Some(beneficiary.to_le_bytes()))?;
+
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id as u64)))?;
+        self.is_static
+            .assign(region, offset, Value::known(F::from(call.is_static as u64)))?;
+        self.reversion_info.assign(
+            region,
+            offset,
+            call.rw_counter_end_of_reversion,
+            call.is_persistent,
+        )?;
+        let callee_address = call
+            .callee_address
+            .to_scalar()
+            .expect("unexpected Address -> Scalar conversion failure");
+        self.callee_address
+            .assign(region, offset, Value::known(callee_address))?;
+        // skip TxId, IsStatic, RwCounterEndOfReversion, IsPersistent and CalleeAddress
+        rws.offset_add(5);
+
+        let (_, is_warm) = rws.next().tx_access_list_value_pair();
+        self.is_warm
+            .assign(region, offset, Value::known(F::from(is_warm)))?;
+
+        let beneficiary_code_hash = rws.next().account_codehash_pair().0;
+        self.beneficiary_code_hash.assign(
+            region,
+            offset,
+            region.code_hash(beneficiary_code_hash),
+        )?;
+        self.beneficiary_not_exists.assign_value(
+            region,
+            offset,
+            region.code_hash(beneficiary_code_hash),
+        )?;
+        let beneficiary_exists = !beneficiary_code_hash.is_zero();
+        self.is_self_beneficiary.assign(
+            region,
+            offset,
+            beneficiary
+                .to_scalar()
+                .expect("unexpected Address -> Scalar conversion failure"),
+            callee_address,
+        )?;
+
+        let (balance, _) = rws.next().account_balance_pair();
+        self.balance
+            .assign(region, offset, Some(balance.to_le_bytes()))?;
+
+        let (is_created, _) = rws.next().tx_created_account_value_pair();
+        self.is_created
+            .assign(region, offset, Value::known(F::from(is_created)))?;
+
+        let transfer_assign_result = self.transfer.assign_from_rws(
+            region,
+            offset,
+            beneficiary_exists,
+            false,
+            balance,
+            &mut rws,
+        )?;
+        if let Some(account_code_hash) = transfer_assign_result.account_code_hash {
+            self.code_hash_previous
+                .assign(region, offset, region.code_hash(account_code_hash))?;
+        }
+        #[cfg(feature = "scroll")]
+        if let Some(account_keccak_code_hash) = transfer_assign_result.account_keccak_code_hash {
+            self.keccak_code_hash_previous.assign(
+                region,
+                offset,
+                region.word_rlc(account_keccak_code_hash),
+            )?;
+        }
+
+        if is_created {
+            rws.next(); // callee balance reset
+            let (_, nonce) = rws.next().account_nonce_pair();
+            self.nonce.assign(
+                region,
+                offset,
+                Value::known(
+                    nonce
+                        .to_scalar()
+                        .expect("unexpected U256 -> Scalar conversion failure"),
+                ),
+            )?;
+            let (_, code_hash) = rws.next().account_codehash_pair();
+            self.code_hash
+                .assign(region, offset, region.code_hash(code_hash))?;
+        }
+
+        rws.next(); // IsSuccess
+        if !call.is_root {
+            self.restore_context
+                .assign(region, offset, block, call, step, rws.offset())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{address, bytecode, Address, Bytecode, ToWord, Word};
+    use ethers_core::utils::get_contract_address;
+    use mock::{
+        generate_mock_call_bytecode, test_ctx::TestContext, MockCallBytecodeParams, MOCK_ACCOUNTS,
+    };
+
+    fn selfdestruct_code(beneficiary: Address) -> Bytecode {
+        bytecode! {
+            PUSH20(beneficiary.to_word())
+            SELFDESTRUCT
+        }
+    }
+
+    #[test]
+    fn selfdestruct_gadget_existing_beneficiary() {
+        test_root_ok(MOCK_ACCOUNTS[2], true, Word::from(10u64.pow(18)));
+        test_root_ok(MOCK_ACCOUNTS[2], true, Word::zero());
+    }
+
+    #[test]
+    fn selfdestruct_gadget_non_existing_beneficiary() {
+        test_root_ok(MOCK_ACCOUNTS[3], false, Word::from(10u64.pow(18)));
+        test_root_ok(MOCK_ACCOUNTS[3], false, Word::zero());
+    }
+
+    #[test]
+    fn selfdestruct_gadget_self_beneficiary() {
+        test_root_ok(MOCK_ACCOUNTS[0], true, Word::from(10u64.pow(18)));
+    }
+
+    #[test]
+    fn selfdestruct_gadget_internal_call() {
+        test_internal_ok(MOCK_ACCOUNTS[3], false);
+        test_internal_ok(MOCK_ACCOUNTS[1], false);
+    }
+
+    #[test]
+    fn selfdestruct_gadget_internal_call_reverted() {
+        test_internal_ok(MOCK_ACCOUNTS[3], true);
+    }
+
+    #[test]
+    fn selfdestruct_gadget_created_in_creation_tx() {
+        test_creation_tx_ok(Some(MOCK_ACCOUNTS[2]), true);
+        test_creation_tx_ok(Some(MOCK_ACCOUNTS[3]), false);
+        test_creation_tx_ok(None, true);
+    }
+
+    #[test]
+    fn selfdestruct_gadget_created_by_create() {
+        test_create_ok(Some(MOCK_ACCOUNTS[3]), false);
+        test_create_ok(None, false);
+    }
+
+    #[test]
+    fn selfdestruct_gadget_created_by_create_reverted() {
+        test_create_ok(Some(MOCK_ACCOUNTS[3]), true);
+    }
+
+    fn test_root_ok(beneficiary: Address, beneficiary_exists: bool, balance: Word) {
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(balance)
+                    .code(selfdestruct_code(beneficiary));
+                accs[1]
+                    .address(MOCK_ACCOUNTS[1])
+                    .balance(Word::from(10u64.pow(19)));
+                if beneficiary_exists && beneficiary != MOCK_ACCOUNTS[0] {
+                    accs[2].address(beneficiary).balance(Word::from(1u64));
+                } else {
+                    accs[2]
+                        .address(address!("0x0000000000000000000000000000000000000020"))
+                        .balance(Word::from(1u64));
+                }
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[1].address).to(accs[0].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    fn test_internal_ok(beneficiary: Address, is_reverted: bool) {
+        let (addr_a, addr_b) = (MOCK_ACCOUNTS[0], MOCK_ACCOUNTS[2]);
+
+        // code A calls code B, which self destructs in an internal call.
+        let instructions_after_call = if is_reverted {
+            bytecode! {
+                PUSH1(0)
+                PUSH1(0)
+                REVERT
+            }
+        } else {
+            Bytecode::default()
+        };
+        let code_a = generate_mock_call_bytecode(MockCallBytecodeParams {
+            address: addr_b,
+            instructions_after_call,
+            ..MockCallBytecodeParams::default()
+        });
+
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(addr_a).code(code_a);
+                accs[1]
+                    .address(MOCK_ACCOUNTS[1])
+                    .balance(Word::from(10u64.pow(19)));
+                accs[2]
+                    .address(addr_b)
+                    .balance(Word::from(10u64.pow(18)))
+                    .code(selfdestruct_code(beneficiary));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[1].address).to(accs[0].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    /// The contract self destructs in its own creation tx. `beneficiary` is the
+    /// contract itself when it's `None`.
+    fn test_creation_tx_ok(beneficiary: Option<Address>, beneficiary_exists: bool) {
+        let contract = get_contract_address(MOCK_ACCOUNTS[1], 0);
+        let beneficiary = beneficiary.unwrap_or(contract);
+
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[1])
+                    .balance(Word::from(10u64.pow(19)));
+                if beneficiary_exists && beneficiary != contract {
+                    accs[1].address(beneficiary).balance(Word::from(1u64));
+                } else {
+                    accs[1]
+                        .address(address!("0x0000000000000000000000000000000000000020"))
+                        .balance(Word::from(1u64));
+                }
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .value(Word::from(10u64.pow(18)))
+                    .input(selfdestruct_code(beneficiary).into());
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    /// Code A creates a contract by CREATE whose init code self destructs.
+    /// `beneficiary` is the created contract itself when it's `None`.
+    fn test_create_ok(beneficiary: Option<Address>, is_reverted: bool) {
+        let addr_a = MOCK_ACCOUNTS[0];
+        let contract = get_contract_address(addr_a, 1);
+        let beneficiary = beneficiary.unwrap_or(contract);
+
+        let init_code = selfdestruct_code(beneficiary).code();
+        let mut code_a = bytecode! {
+            PUSH32(Word::from_big_endian(&init_code))
+            PUSH1(0)
+            MSTORE
+            PUSH1(init_code.len()) // length
+            PUSH1(32 - init_code.len()) // offset
+            PUSH2(1000) // value
+            CREATE
+        };
+        code_a.append(&if is_reverted {
+            bytecode! {
+                PUSH1(0)
+                PUSH1(0)
+                REVERT
+            }
+        } else {
+            bytecode! {
+                STOP
+            }
+        });
+
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(addr_a)
+                    .nonce(Word::one())
+                    .balance(Word::from(10u64.pow(18)))
+                    .code(code_a);
+                accs[1]
+                    .address(MOCK_ACCOUNTS[1])
+                    .balance(Word::from(10u64.pow(19)));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[1].address).to(accs[0].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+}
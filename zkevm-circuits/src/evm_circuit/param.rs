@@ -41,6 +41,9 @@ pub(crate) const EVM_LOOKUP_COLS: usize = FIXED_TABLE_LOOKUPS
     + SIG_TABLE_LOOKUPS
     + MODEXP_TABLE_LOOKUPS
     + ECC_TABLE_LOOKUPS
+    + SHA256_TABLE_LOOKUPS
+    + RIPEMD160_TABLE_LOOKUPS
+    + BLAKE2F_TABLE_LOOKUPS
     + POW_OF_RAND_TABLE_LOOKUPS;
 
 /// Lookups done per row.
@@ -56,6 +59,9 @@ pub(crate) const LOOKUP_CONFIG: &[(Table, usize)] = &[
     (Table::Sig, SIG_TABLE_LOOKUPS),
     (Table::ModExp, MODEXP_TABLE_LOOKUPS),
     (Table::Ecc, ECC_TABLE_LOOKUPS),
+    (Table::Sha256, SHA256_TABLE_LOOKUPS),
+    (Table::Ripemd160, RIPEMD160_TABLE_LOOKUPS),
+    (Table::Blake2f, BLAKE2F_TABLE_LOOKUPS),
     (Table::PowOfRand, POW_OF_RAND_TABLE_LOOKUPS),
 ];
 
@@ -91,6 +97,15 @@ pub const MODEXP_TABLE_LOOKUPS: usize = 1;
 /// Ecc Table lookups done in EVMCircuit
 pub const ECC_TABLE_LOOKUPS: usize = 1;
 
+/// Sha256 Table lookups done in EVMCircuit
+pub const SHA256_TABLE_LOOKUPS: usize = 1;
+
+/// Ripemd160 Table lookups done in EVMCircuit
+pub const RIPEMD160_TABLE_LOOKUPS: usize = 1;
+
+/// Blake2f Table lookups done in EVMCircuit
+pub const BLAKE2F_TABLE_LOOKUPS: usize = 1;
+
 /// Power of Randomness lookups done from EVM Circuit.
 pub const POW_OF_RAND_TABLE_LOOKUPS: usize = 1;
 
@@ -133,6 +148,14 @@ pub(crate) const N_BYTES_GAS: usize = N_BYTES_U64;
 // Number of bytes that will be used for call data's size.
 pub(crate) const N_BYTES_CALLDATASIZE: usize = N_BYTES_U64;
 
+// For EIP-7702, every authorization whose authority is recovered takes the rws
+// of the authority's access list, code hash, nonce and the tx refund, plus the
+// keccak code hash and the code size for Scroll.
+#[cfg(feature = "scroll")]
+pub(crate) const SET_CODE_RW_DELTA: usize = 6;
+#[cfg(not(feature = "scroll"))]
+pub(crate) const SET_CODE_RW_DELTA: usize = 4;
+
 lazy_static::lazy_static! {
     // Step slot height in evm circuit
     pub(crate) static ref EXECUTION_STATE_HEIGHT_MAP : HashMap<ExecutionState, usize> = get_step_height_map();
@@ -9,12 +9,13 @@ use crate::{
     witness::Transaction,
 };
 use bus_mapping::{evm::OpcodeId, precompile::PrecompileCalls};
-use eth_types::evm_types::GasCost;
+use eth_types::evm_types::{GasCost, Hardfork};
 use halo2_proofs::{
     arithmetic::FieldExt,
     circuit::Value,
     plonk::{Advice, Column, ConstraintSystem, Error, Expression},
 };
+use serde::{Deserialize, Serialize};
 use std::{fmt::Display, iter};
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
@@ -36,7 +37,7 @@ impl From<PrecompileCalls> for ExecutionState {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize)]
 pub enum ExecutionState {
     // Internal state
     BeginTx,
@@ -83,10 +84,15 @@ pub enum ExecutionState {
     BLOCKCTXU256, // DIFFICULTY, BASEFEE
     CHAINID,
     SELFBALANCE,
+    BLOBHASH,
+    BLOBBASEFEE,
     POP,
     MEMORY, // MLOAD, MSTORE, MSTORE8
     SLOAD,
     SSTORE,
+    TLOAD,
+    TSTORE,
+    MCOPY,
     JUMP,
     JUMPI,
     PC,
@@ -229,6 +235,17 @@ impl ExecutionState {
                 })
                 .collect();
         }
+        if matches!(self, Self::ErrorInvalidOpcode) {
+            return OpcodeId::invalid_opcodes()
+                .into_iter()
+                .map(ResponsibleOp::Op)
+                .chain(
+                    OpcodeId::hardfork_opcodes()
+                        .into_iter()
+                        .map(|op| ResponsibleOp::BeforeHardfork(op, op.activation_fork())),
+                )
+                .collect();
+        }
 
         match self {
             Self::STOP => vec![OpcodeId::STOP],
@@ -276,12 +293,17 @@ impl ExecutionState {
             }
             Self::CHAINID => vec![OpcodeId::CHAINID],
             Self::SELFBALANCE => vec![OpcodeId::SELFBALANCE],
+            Self::BLOBHASH => vec![OpcodeId::BLOBHASH],
+            Self::BLOBBASEFEE => vec![OpcodeId::BLOBBASEFEE],
             Self::POP => vec![OpcodeId::POP],
             Self::MEMORY => {
                 vec![OpcodeId::MLOAD, OpcodeId::MSTORE, OpcodeId::MSTORE8]
             }
             Self::SLOAD => vec![OpcodeId::SLOAD],
             Self::SSTORE => vec![OpcodeId::SSTORE],
+            Self::TLOAD => vec![OpcodeId::TLOAD],
+            Self::TSTORE => vec![OpcodeId::TSTORE],
+            Self::MCOPY => vec![OpcodeId::MCOPY],
             Self::JUMP => vec![OpcodeId::JUMP],
             Self::JUMPI => vec![OpcodeId::JUMPI],
             Self::PC => vec![OpcodeId::PC],
@@ -376,7 +398,6 @@ impl ExecutionState {
             ],
             Self::RETURN_REVERT => vec![OpcodeId::RETURN, OpcodeId::REVERT],
             Self::SELFDESTRUCT => vec![OpcodeId::SELFDESTRUCT],
-            Self::ErrorInvalidOpcode => OpcodeId::invalid_opcodes(),
             _ => vec![],
         }
         .into_iter()
@@ -401,6 +422,9 @@ pub(crate) enum ResponsibleOp {
     Op(OpcodeId),
     /// Corresponding to ExecutionState::ErrorStack
     InvalidStackPtr(OpcodeId, u32),
+    /// Corresponding to ExecutionState::ErrorInvalidOpcode, for an opcode
+    /// executed before the hard fork activating it
+    BeforeHardfork(OpcodeId, Hardfork),
 }
 
 /// Helper for easy transform from a raw OpcodeId to ResponsibleOp.
@@ -415,6 +439,7 @@ impl ResponsibleOp {
         *match self {
             ResponsibleOp::Op(opcode) => opcode,
             ResponsibleOp::InvalidStackPtr(opcode, _) => opcode,
+            ResponsibleOp::BeforeHardfork(opcode, _) => opcode,
         }
     }
 }
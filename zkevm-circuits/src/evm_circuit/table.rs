@@ -95,6 +95,9 @@ impl FixedTableTag {
                                 ResponsibleOp::InvalidStackPtr(op, stack_ptr) => {
                                     (op, F::from(u64::from(stack_ptr)))
                                 }
+                                ResponsibleOp::BeforeHardfork(op, hardfork) => {
+                                    (op, F::from(hardfork as u64))
+                                }
                             };
                             [
                                 tag,
@@ -154,6 +157,9 @@ pub(crate) enum Table {
     Sig,
     ModExp,
     Ecc,
+    Sha256,
+    Ripemd160,
+    Blake2f,
     PowOfRand,
 }
 
@@ -311,11 +317,18 @@ pub(crate) enum Lookup<F> {
         sig_s_rlc: Expression<F>,
         recovered_addr: Expression<F>,
     },
+    /// Lookup to the modexp table, at the last row of an event.
     ModExpTable {
-        base_limbs: [Expression<F>; 3],
-        exp_limbs: [Expression<F>; 3],
-        modulus_limbs: [Expression<F>; 3],
-        result_limbs: [Expression<F>; 3],
+        /// RLC of the big-endian bytes of the base.
+        base_rlc: Expression<F>,
+        /// RLC of the big-endian bytes of the exponent.
+        exp_rlc: Expression<F>,
+        /// RLC of the big-endian bytes of the modulus.
+        modulus_rlc: Expression<F>,
+        /// RLC of the big-endian bytes of the result.
+        result_rlc: Expression<F>,
+        /// Bit length of the exponent.
+        exp_bit_len: Expression<F>,
     },
     EccTable {
         op_type: Expression<F>,
@@ -327,6 +340,33 @@ pub(crate) enum Lookup<F> {
         output1_rlc: Expression<F>,
         output2_rlc: Expression<F>,
     },
+    /// Lookup to sha256 table.
+    Sha256Table {
+        /// Accumulator to the input.
+        input_rlc: Expression<F>,
+        /// Length of input that is being hashed.
+        input_len: Expression<F>,
+        /// RLC of the digest bytes of the input.
+        output_rlc: Expression<F>,
+    },
+    /// Lookup to ripemd160 table.
+    Ripemd160Table {
+        /// Accumulator to the input.
+        input_rlc: Expression<F>,
+        /// Length of input that is being hashed.
+        input_len: Expression<F>,
+        /// RLC of the digest bytes of the input.
+        output_rlc: Expression<F>,
+    },
+    /// Lookup to blake2f table.
+    Blake2fTable {
+        /// Accumulator to the input.
+        input_rlc: Expression<F>,
+        /// Number of rounds of the compression.
+        rounds: Expression<F>,
+        /// RLC of the output bytes.
+        output_rlc: Expression<F>,
+    },
     PowOfRandTable {
         exponent: Expression<F>,
         pow_of_rand: Expression<F>,
@@ -353,6 +393,9 @@ impl<F: Field> Lookup<F> {
             Self::SigTable { .. } => Table::Sig,
             Self::ModExpTable { .. } => Table::ModExp,
             Self::EccTable { .. } => Table::Ecc,
+            Self::Sha256Table { .. } => Table::Sha256,
+            Self::Ripemd160Table { .. } => Table::Ripemd160,
+            Self::Blake2fTable { .. } => Table::Blake2f,
             Self::PowOfRandTable { .. } => Table::PowOfRand,
             Self::Conditional(_, lookup) => lookup.table(),
         }
@@ -491,24 +534,18 @@ impl<F: Field> Lookup<F> {
                 recovered_addr.clone(),
             ],
             Self::ModExpTable {
-                base_limbs,
-                exp_limbs,
-                modulus_limbs,
-                result_limbs,
+                base_rlc,
+                exp_rlc,
+                modulus_rlc,
+                result_rlc,
+                exp_bit_len,
             } => vec![
-                1.expr(), // q_head
-                base_limbs[0].clone(),
-                exp_limbs[0].clone(),
-                modulus_limbs[0].clone(),
-                result_limbs[0].clone(),
-                base_limbs[1].clone(),
-                exp_limbs[1].clone(),
-                modulus_limbs[1].clone(),
-                result_limbs[1].clone(),
-                base_limbs[2].clone(),
-                exp_limbs[2].clone(),
-                modulus_limbs[2].clone(),
-                result_limbs[2].clone(),
+                1.expr(), // q_tail
+                base_rlc.clone(),
+                exp_rlc.clone(),
+                modulus_rlc.clone(),
+                result_rlc.clone(),
+                exp_bit_len.clone(),
             ],
             Self::EccTable {
                 op_type,
@@ -529,6 +566,33 @@ impl<F: Field> Lookup<F> {
                 output1_rlc.expr(),
                 output2_rlc.expr(),
             ],
+            Self::Sha256Table {
+                input_rlc,
+                input_len,
+                output_rlc,
+            }
+            | Self::Ripemd160Table {
+                input_rlc,
+                input_len,
+                output_rlc,
+            } => vec![
+                1.expr(), // q_enable
+                1.expr(), // is_final
+                input_rlc.clone(),
+                input_len.clone(),
+                output_rlc.clone(),
+            ],
+            Self::Blake2fTable {
+                input_rlc,
+                rounds,
+                output_rlc,
+            } => vec![
+                1.expr(), // q_enable
+                1.expr(), // is_final
+                input_rlc.clone(),
+                rounds.clone(),
+                output_rlc.clone(),
+            ],
             Self::PowOfRandTable {
                 exponent,
                 pow_of_rand,
@@ -729,6 +729,10 @@ impl<'a> StepRws<'a> {
     pub(crate) fn offset_set(&mut self, offset: usize) {
         self.offset = offset
     }
+    /// Return the current step rw operation offset.
+    pub(crate) fn offset(&self) -> usize {
+        self.offset
+    }
     /// Return the next rw operation from the step.
     pub(crate) fn next(&mut self) -> Rw {
         let rw = self.rws[self.rw_indices[self.offset]];
@@ -31,9 +31,13 @@ use halo2_proofs::{
     plonk::{Error, Expression},
 };
 
+mod delegation;
+mod hardfork;
 mod tx_l1_fee;
 mod tx_l1_msg;
 
+pub(crate) use delegation::{DelegateAccess, DelegationGadget};
+pub(crate) use hardfork::HardforkGadget;
 pub(crate) use tx_l1_fee::TxL1FeeGadget;
 pub(crate) use tx_l1_msg::TxL1MsgGadget;
 
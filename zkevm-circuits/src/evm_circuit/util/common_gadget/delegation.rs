@@ -0,0 +1,316 @@
+use super::{CachedRegion, Cell};
+use crate::{
+    evm_circuit::{
+        param::N_BYTES_ACCOUNT_ADDRESS,
+        util::{
+            and,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo},
+            from_bytes,
+            math_gadget::{IsEqualGadget, IsZeroGadget},
+            not, or, select, StepRws,
+        },
+    },
+    table::AccountFieldTag,
+    util::Expr,
+    witness::Block,
+};
+use eth_types::{
+    evm_types::{parse_delegation, GasCost, DELEGATION_CODE_SIZE, DELEGATION_PREFIX},
+    Field, U256,
+};
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
+
+/// Access of the delegate in the access list of the tx with the given id.
+pub(crate) enum DelegateAccess<'a, F> {
+    /// Warm up the delegate, with the reversion info if the warm-up is
+    /// reversible.
+    WarmUp(Expression<F>, Option<&'a mut ReversionInfo<F>>),
+    /// Read whether the delegate is warm, when the call fails before it's
+    /// warmed up.
+    Read(Expression<F>),
+}
+
+/// EIP-7702 delegation gadget, which follows the delegation designator
+/// `0xef0100 || address` in the code of an account, so a code of 23 bytes
+/// starting with the 3 bytes of the prefix.
+/// The code hash of the delegate is read if the code is delegated, after the
+/// access of the delegate in the access list if given.
+#[derive(Clone, Debug)]
+pub(crate) struct DelegationGadget<F> {
+    code_hash_is_zero: IsZeroGadget<F>,
+    code_hash_is_empty: IsEqualGadget<F>,
+    code_length: Cell<F>,
+    is_designator_length: IsEqualGadget<F>,
+    prefix_bytes: [Cell<F>; DELEGATION_PREFIX.len()],
+    is_designator_prefix: IsEqualGadget<F>,
+    is_delegated: Cell<F>,
+    /// Address bytes of the delegate in little endian order
+    address_bytes: [Cell<F>; N_BYTES_ACCOUNT_ADDRESS],
+    address_is_code: [Cell<F>; N_BYTES_ACCOUNT_ADDRESS],
+    is_warm_prev: Cell<F>,
+    delegate_code_hash: Cell<F>,
+    delegate_code_hash_is_zero: IsZeroGadget<F>,
+    delegate_code_hash_is_empty: IsEqualGadget<F>,
+    has_access: bool,
+}
+
+impl<F: Field> DelegationGadget<F> {
+    pub(crate) fn construct(
+        cb: &mut EVMConstraintBuilder<F>,
+        code_hash: Expression<F>,
+        access: Option<DelegateAccess<'_, F>>,
+    ) -> Self {
+        let code_hash_is_zero = IsZeroGadget::construct(cb, code_hash.expr());
+        let code_hash_is_empty =
+            IsEqualGadget::construct(cb, code_hash.expr(), cb.empty_code_hash_rlc());
+
+        let code_length = cb.query_cell();
+        cb.condition(
+            or::expr([code_hash_is_zero.expr(), code_hash_is_empty.expr()]),
+            |cb| {
+                cb.require_zero("no code has length 0", code_length.expr());
+            },
+        );
+        cb.condition(
+            and::expr([
+                not::expr(code_hash_is_zero.expr()),
+                not::expr(code_hash_is_empty.expr()),
+            ]),
+            |cb| {
+                cb.bytecode_length(code_hash.expr(), code_length.expr());
+            },
+        );
+        let is_designator_length =
+            IsEqualGadget::construct(cb, code_length.expr(), DELEGATION_CODE_SIZE.expr());
+
+        // None of the prefix bytes is a push, so they are all code.
+        let prefix_bytes = [(); DELEGATION_PREFIX.len()].map(|_| cb.query_cell());
+        cb.condition(is_designator_length.expr(), |cb| {
+            for (i, byte) in prefix_bytes.iter().enumerate() {
+                cb.bytecode_lookup(code_hash.expr(), i.expr(), 1.expr(), byte.expr());
+            }
+        });
+        // The bytes are looked up in the bytecode table, so the prefix is
+        // their big endian value.
+        let is_designator_prefix = IsEqualGadget::construct(
+            cb,
+            prefix_bytes
+                .iter()
+                .fold(0.expr(), |value, byte| value * 256.expr() + byte.expr()),
+            prefix_value().expr(),
+        );
+
+        let is_delegated = cb.query_bool();
+        cb.require_equal(
+            "is_delegated == is_designator_length && is_designator_prefix",
+            is_delegated.expr(),
+            and::expr([is_designator_length.expr(), is_designator_prefix.expr()]),
+        );
+
+        let address_bytes = cb.query_bytes();
+        let address_is_code = [(); N_BYTES_ACCOUNT_ADDRESS].map(|_| cb.query_cell());
+        let is_warm_prev = cb.query_bool();
+        let delegate_code_hash = cb.query_cell_phase2();
+        let has_access = access.is_some();
+        cb.condition(is_delegated.expr(), |cb| {
+            for (i, (byte, is_code)) in address_bytes
+                .iter()
+                .rev()
+                .zip(address_is_code.iter().rev())
+                .enumerate()
+            {
+                cb.bytecode_lookup(
+                    code_hash.expr(),
+                    (DELEGATION_PREFIX.len() + i).expr(),
+                    is_code.expr(),
+                    byte.expr(),
+                );
+            }
+            let address = from_bytes::expr(&address_bytes);
+            match access {
+                Some(DelegateAccess::WarmUp(tx_id, reversion_info)) => {
+                    cb.account_access_list_write(
+                        tx_id,
+                        address.expr(),
+                        1.expr(),
+                        is_warm_prev.expr(),
+                        reversion_info,
+                    );
+                }
+                Some(DelegateAccess::Read(tx_id)) => {
+                    cb.account_access_list_read(tx_id, address.expr(), is_warm_prev.expr());
+                }
+                None => {}
+            }
+            // For non-existing accounts the code_hash must be 0 in the rw_table.
+            cb.account_read(
+                address,
+                AccountFieldTag::CodeHash,
+                delegate_code_hash.expr(),
+            );
+        });
+        let delegate_code_hash_is_zero = IsZeroGadget::construct(cb, delegate_code_hash.expr());
+        let delegate_code_hash_is_empty =
+            IsEqualGadget::construct(cb, delegate_code_hash.expr(), cb.empty_code_hash_rlc());
+
+        Self {
+            code_hash_is_zero,
+            code_hash_is_empty,
+            code_length,
+            is_designator_length,
+            prefix_bytes,
+            is_designator_prefix,
+            is_delegated,
+            address_bytes,
+            address_is_code,
+            is_warm_prev,
+            delegate_code_hash,
+            delegate_code_hash_is_zero,
+            delegate_code_hash_is_empty,
+            has_access,
+        }
+    }
+
+    pub(crate) fn is_delegated(&self) -> Expression<F> {
+        self.is_delegated.expr()
+    }
+
+    /// Hash of the code executed when calling the account, given the code hash
+    /// of the account itself.
+    pub(crate) fn executed_code_hash(&self, code_hash: Expression<F>) -> Expression<F> {
+        select::expr(
+            self.is_delegated.expr(),
+            self.delegate_code_hash.expr(),
+            code_hash,
+        )
+    }
+
+    /// Whether the executed code is empty, given whether the account itself has
+    /// no code.
+    pub(crate) fn executed_no_code(&self, no_code: Expression<F>) -> Expression<F> {
+        no_code
+            + self.is_delegated.expr()
+                * (self.delegate_code_hash_is_empty.expr() + self.delegate_code_hash_is_zero.expr())
+    }
+
+    /// Gas cost of accessing the delegate
+    pub(crate) fn gas_cost(&self) -> Expression<F> {
+        self.is_delegated.expr()
+            * select::expr(
+                self.is_warm_prev.expr(),
+                GasCost::WARM_ACCESS.expr(),
+                GasCost::COLD_ACCOUNT_ACCESS.expr(),
+            )
+    }
+
+    pub(crate) fn rw_delta(&self) -> Expression<F> {
+        self.is_delegated.expr() * if self.has_access { 2 } else { 1 }.expr()
+    }
+
+    /// Assign the gadget from the code hash of the account, reading the rws of
+    /// the delegate if the code is delegated. Return the gas cost of accessing
+    /// the delegate.
+    pub(crate) fn assign_from_rws(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        code_hash: U256,
+        rws: &mut StepRws,
+    ) -> Result<u64, Error> {
+        let code_hash_rlc = region.code_hash(code_hash);
+        self.code_hash_is_zero
+            .assign_value(region, offset, code_hash_rlc)?;
+        self.code_hash_is_empty.assign_value(
+            region,
+            offset,
+            code_hash_rlc,
+            region.empty_code_hash_rlc(),
+        )?;
+
+        let bytecode = block.bytecodes.get(&code_hash);
+        let code_length = bytecode.map_or(0, |bytecode| bytecode.bytes.len());
+        self.code_length
+            .assign(region, offset, Value::known(F::from(code_length as u64)))?;
+        self.is_designator_length.assign(
+            region,
+            offset,
+            F::from(code_length as u64),
+            F::from(DELEGATION_CODE_SIZE as u64),
+        )?;
+        let mut prefix = 0;
+        for (i, cell) in self.prefix_bytes.iter().enumerate() {
+            let byte = bytecode
+                .filter(|_| code_length == DELEGATION_CODE_SIZE)
+                .map_or(0, |bytecode| bytecode.bytes[i]);
+            cell.assign(region, offset, Value::known(F::from(byte as u64)))?;
+            prefix = (prefix << 8) + byte as u64;
+        }
+        self.is_designator_prefix.assign(
+            region,
+            offset,
+            F::from(prefix),
+            F::from(prefix_value()),
+        )?;
+
+        let delegate = bytecode.and_then(|bytecode| parse_delegation(&bytecode.bytes));
+        self.is_delegated.assign(
+            region,
+            offset,
+            Value::known(F::from(delegate.is_some() as u64)),
+        )?;
+        for (i, (byte, is_code)) in self
+            .address_bytes
+            .iter()
+            .rev()
+            .zip(self.address_is_code.iter().rev())
+            .enumerate()
+        {
+            let [value, is_code_value] = match (delegate, bytecode) {
+                (Some(_), Some(bytecode)) => bytecode.get(DELEGATION_PREFIX.len() + i),
+                _ => [0, 0],
+            };
+            byte.assign(region, offset, Value::known(F::from(value as u64)))?;
+            is_code.assign(region, offset, Value::known(F::from(is_code_value as u64)))?;
+        }
+        let is_warm_prev = if delegate.is_some() && self.has_access {
+            rws.next().tx_access_list_value_pair().1
+        } else {
+            false
+        };
+        let delegate_code_hash = if delegate.is_some() {
+            rws.next().account_codehash_pair().1
+        } else {
+            U256::zero()
+        };
+        self.is_warm_prev
+            .assign(region, offset, Value::known(F::from(is_warm_prev as u64)))?;
+        let delegate_code_hash = region.code_hash(delegate_code_hash);
+        self.delegate_code_hash
+            .assign(region, offset, delegate_code_hash)?;
+        self.delegate_code_hash_is_zero
+            .assign_value(region, offset, delegate_code_hash)?;
+        self.delegate_code_hash_is_empty.assign_value(
+            region,
+            offset,
+            delegate_code_hash,
+            region.empty_code_hash_rlc(),
+        )?;
+
+        Ok(match (delegate, is_warm_prev) {
+            (None, _) => 0,
+            (Some(_), true) => GasCost::WARM_ACCESS.as_u64(),
+            (Some(_), false) => GasCost::COLD_ACCOUNT_ACCESS.as_u64(),
+        })
+    }
+}
+
+// Big endian value of the delegation prefix
+fn prefix_value() -> u64 {
+    DELEGATION_PREFIX
+        .iter()
+        .fold(0, |value, byte| (value << 8) + *byte as u64)
+}
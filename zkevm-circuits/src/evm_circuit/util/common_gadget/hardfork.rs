@@ -0,0 +1,117 @@
+use super::{CachedRegion, Cell};
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+        select,
+    },
+    table::BlockContextFieldTag,
+    util::Expr,
+};
+use eth_types::{
+    evm_types::{
+        Hardfork, OpcodeId, INIT_CODE_WORD_GAS, MAX_EXPANDED_MEMORY_ADDRESS, MAX_INIT_CODE_SIZE,
+    },
+    Field,
+};
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
+
+/// Gadget for the hard fork active in the block of the current step, which is
+/// looked up in the block table (and so bound to the chain spec by the PI
+/// circuit). The fork is decomposed into a flag for each fork activated after
+/// London, so that the rules of a fork are selected by a degree 1 expression.
+#[derive(Clone, Debug)]
+pub(crate) struct HardforkGadget<F> {
+    hardfork: Cell<F>,
+    is_shanghai: Cell<F>,
+    is_cancun: Cell<F>,
+}
+
+impl<F: Field> HardforkGadget<F> {
+    pub(crate) fn construct(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let hardfork = cb.query_cell();
+        cb.block_lookup(
+            BlockContextFieldTag::Hardfork.expr(),
+            cb.curr.state.block_number.expr(),
+            hardfork.expr(),
+        );
+
+        let [is_shanghai, is_cancun] = [(); 2].map(|_| cb.query_bool());
+        cb.require_equal(
+            "hardfork == is_shanghai + is_cancun",
+            hardfork.expr(),
+            is_shanghai.expr() + is_cancun.expr(),
+        );
+        cb.require_zero(
+            "Cancun is activated after Shanghai",
+            is_cancun.expr() * (1.expr() - is_shanghai.expr()),
+        );
+
+        Self {
+            hardfork,
+            is_shanghai,
+            is_cancun,
+        }
+    }
+
+    /// Hard fork of the block, as assigned in the block table.
+    pub(crate) fn hardfork(&self) -> Expression<F> {
+        self.hardfork.expr()
+    }
+
+    /// Whether `fork` is active in the block.
+    pub(crate) fn is_active(&self, fork: Hardfork) -> Expression<F> {
+        match fork {
+            Hardfork::London => 1.expr(),
+            Hardfork::Shanghai => self.is_shanghai.expr(),
+            Hardfork::Cancun => self.is_cancun.expr(),
+        }
+    }
+
+    /// Maximum init code size in the block, see [`Hardfork::max_init_code_size`].
+    pub(crate) fn max_init_code_size(&self) -> Expression<F> {
+        select::expr(
+            self.is_shanghai.expr(),
+            MAX_INIT_CODE_SIZE.expr(),
+            MAX_EXPANDED_MEMORY_ADDRESS.expr(),
+        )
+    }
+
+    /// Gas per word of init code in the block, see
+    /// [`Hardfork::init_code_word_gas`].
+    pub(crate) fn init_code_word_gas(&self) -> Expression<F> {
+        self.is_shanghai.expr() * INIT_CODE_WORD_GAS.expr()
+    }
+
+    /// Constrain `opcode` to be activated by the hard fork of the block.
+    pub(crate) fn require_enabled(&self, cb: &mut EVMConstraintBuilder<F>, opcode: OpcodeId) {
+        cb.require_equal(
+            "opcode is activated by the hard fork of the block",
+            self.is_active(opcode.activation_fork()),
+            1.expr(),
+        );
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        hardfork: Hardfork,
+    ) -> Result<(), Error> {
+        self.hardfork
+            .assign(region, offset, Value::known(F::from(hardfork as u64)))?;
+        for (cell, fork) in [
+            (&self.is_shanghai, Hardfork::Shanghai),
+            (&self.is_cancun, Hardfork::Cancun),
+        ] {
+            cell.assign(
+                region,
+                offset,
+                Value::known(F::from((hardfork >= fork) as u64)),
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -112,6 +112,15 @@ impl Instrument {
                     CellType::Lookup(Table::Ecc) => {
                         report.ecc_table = data_entry;
                     }
+                    CellType::Lookup(Table::Sha256) => {
+                        report.sha256_table = data_entry;
+                    }
+                    CellType::Lookup(Table::Ripemd160) => {
+                        report.ripemd160_table = data_entry;
+                    }
+                    CellType::Lookup(Table::Blake2f) => {
+                        report.blake2f_table = data_entry;
+                    }
                     CellType::Lookup(Table::PowOfRand) => {
                         report.pow_of_rand_table = data_entry;
                     }
@@ -144,6 +153,9 @@ pub(crate) struct ExecStateReport {
     pub(crate) sig_table: StateReportRow,
     pub(crate) modexp_table: StateReportRow,
     pub(crate) ecc_table: StateReportRow,
+    pub(crate) sha256_table: StateReportRow,
+    pub(crate) ripemd160_table: StateReportRow,
+    pub(crate) blake2f_table: StateReportRow,
     pub(crate) pow_of_rand_table: StateReportRow,
 }
 
@@ -34,6 +34,8 @@ impl<F: Field> PrecompileGadget<F> {
         _rd_offset: Expression<F>,
         _rd_length: Expression<F>,
         precompile_return_length: Expression<F>,
+        // number of input bytes copied to the precompile call.
+        precompile_input_len: Expression<F>,
         // input bytes to precompile call.
         input_bytes_rlc: Expression<F>,
         // output result from precompile call.
@@ -53,15 +55,10 @@ impl<F: Field> PrecompileGadget<F> {
                 address.value_equals(PrecompileCalls::Bn128Add),
             ]);
             let len_96 = address.value_equals(PrecompileCalls::Bn128Mul);
-            let len_192 = address.value_equals(PrecompileCalls::Modexp);
             select::expr(
                 len_128,
                 128.expr(),
-                select::expr(
-                    len_96,
-                    96.expr(),
-                    select::expr(len_192, 192.expr(), cd_length.expr()),
-                ),
+                select::expr(len_96, 96.expr(), cd_length.expr()),
             )
         };
         let pad_right = LtGadget::construct(cb, cd_length.expr(), input_len.expr());
@@ -148,11 +145,37 @@ impl<F: Field> PrecompileGadget<F> {
             }),
             Box::new(|cb| {
                 /* Sha256 */
-                cb.query_cell()
+                let (input_bytes_rlc_copied, output_bytes_rlc_copied) =
+                    (cb.query_cell_phase2(), cb.query_cell_phase2());
+                let gas_cost = cb.query_cell();
+                cb.require_equal(
+                    "copy input bytes",
+                    padding_gadget.padded_rlc(),
+                    input_bytes_rlc_copied.expr(),
+                );
+                cb.require_equal(
+                    "copy output bytes",
+                    output_bytes_rlc.clone(),
+                    output_bytes_rlc_copied.expr(),
+                );
+                gas_cost
             }),
             Box::new(|cb| {
                 /* Ripemd160 */
-                cb.query_cell()
+                let (input_bytes_rlc_copied, output_bytes_rlc_copied) =
+                    (cb.query_cell_phase2(), cb.query_cell_phase2());
+                let gas_cost = cb.query_cell();
+                cb.require_equal(
+                    "copy input bytes",
+                    padding_gadget.padded_rlc(),
+                    input_bytes_rlc_copied.expr(),
+                );
+                cb.require_equal(
+                    "copy output bytes",
+                    output_bytes_rlc.clone(),
+                    output_bytes_rlc_copied.expr(),
+                );
+                gas_cost
             }),
             Box::new(|cb| {
                 /* Identity */
@@ -174,6 +197,14 @@ impl<F: Field> PrecompileGadget<F> {
                 let (input_bytes_acc_copied, output_bytes_acc_copied) =
                     (cb.query_cell_phase2(), cb.query_cell_phase2());
                 let gas_cost = cb.query_cell();
+                // the input length is given by the header of the input, which is only known to
+                // the precompile step.
+                let input_len = cb.query_cell();
+                cb.require_equal(
+                    "copy input length",
+                    precompile_input_len.expr(),
+                    input_len.expr(),
+                );
                 cb.require_equal(
                     "copy padded input bytes",
                     padding_gadget.padded_rlc(),
@@ -272,7 +303,20 @@ impl<F: Field> PrecompileGadget<F> {
             }),
             Box::new(|cb| {
                 /* Blake2F */
-                cb.query_cell()
+                let (input_bytes_rlc_copied, output_bytes_rlc_copied) =
+                    (cb.query_cell_phase2(), cb.query_cell_phase2());
+                let gas_cost = cb.query_cell();
+                cb.require_equal(
+                    "copy input bytes",
+                    padding_gadget.padded_rlc(),
+                    input_bytes_rlc_copied.expr(),
+                );
+                cb.require_equal(
+                    "copy output bytes",
+                    output_bytes_rlc.clone(),
+                    output_bytes_rlc_copied.expr(),
+                );
+                gas_cost
             }),
         ];
         let precompile_call_gas_cost =
@@ -524,6 +524,13 @@ impl<F: Field> ExpCircuit<F> {
             _marker: PhantomData::default(),
         }
     }
+
+    /// Return the minimum number of steps with which the circuit fits the exp
+    /// events, as used by `max_exp_steps` of the circuits params.
+    pub fn min_num_steps(exp_events: &[ExpEvent]) -> usize {
+        let num_rows = ExpCircuitConfig::<F>::min_num_rows(exp_events);
+        (num_rows + OFFSET_INCREMENT - 1) / OFFSET_INCREMENT
+    }
 }
 
 impl<F: Field> SubCircuit<F> for ExpCircuit<F> {
@@ -565,7 +572,7 @@ impl<F: Field> SubCircuit<F> for ExpCircuit<F> {
     fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
         (
             Self::Config::min_num_rows(&block.exp_events),
-            block.circuits_params.max_exp_steps,
+            block.circuits_params.max_exp_steps * OFFSET_INCREMENT,
         )
     }
 
@@ -1085,6 +1085,14 @@ impl<F: Field> KeccakCircuit<F> {
         }
     }
 
+    /// The minimum number of rows with which the circuit has the capacity to
+    /// hash the inputs, the inverse of [`Self::capacity_for_row`].
+    pub fn min_num_rows_for_inputs(inputs: &[Vec<u8>]) -> usize {
+        let num_keccak_f: usize = inputs.iter().map(|bytes| bytes.len() / RATE + 1).sum();
+        // Add two for unusable rows
+        (num_keccak_f + 2) * (NUM_ROUNDS + 1) * get_num_rows_per_round()
+    }
+
     /// Sets the witness using the data to be hashed
     pub(crate) fn generate_witness(&self, challenges: Challenges<Value<F>>) -> Vec<KeccakRow<F>> {
         multi_keccak(self.inputs.as_slice(), challenges, self.capacity())
@@ -1,208 +1,1336 @@
-//! The Modexp circuit is responsible for modexp operations on big integer from precompiled contract
-//! calls ModExp, current the size of supported integer is up to 32 bytes (U256)
-
-use halo2_proofs::{
-    circuit::{Layouter, Region, Value},
-    plonk::{Advice, Column, ConstraintSystem, Error},
-};
+//! The Modexp circuit is responsible for modexp operations on big integers from precompiled
+//! contract calls ModExp, the size of the supported integers is up to [`MODEXP_SIZE_LIMIT`] bytes.
+//!
+//! The events are laid out in the [`ModExpTable`] from the first row, one byte of each integer per
+//! row. The exponentiation of each event is then proven by a chain of blocks of [`BLOCK_ROWS`]
+//! rows after the table, each block proving a modular multiplication `a * b = q * m + r` with
+//! `r < m`, on [`N_LIMBS`] limbs of 64 bits laid out one per row from the least significant:
+//! - a REDUCE block reduces the base, with `b = 1`,
+//! - then for each bit of the exponent, from the most significant byte with a set bit, a SQUARE
+//!   block squares the accumulator, followed by a MUL block multiplying it by the reduced base when
+//!   the bit is set,
+//! - and a FINAL block reduces the accumulator, with `b = 1`, into the result of the event.
+//!
+//! The multiplication is checked on the polynomials of the limbs evaluated at a challenge `x`:
+//! `a(x) * b(x) - q(x) * m(x) - r(x) = (x - 2^64) * c(x)`, where `c` are the signed carries. As
+//! the limbs and the carries are range checked, the coefficients of the identity don't wrap around
+//! the field, so that it holds on the integers.
 
 use crate::{
-    table::ModExpTable,
+    evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    table::{ModExpTable, U16Table},
     util::{Challenges, SubCircuit, SubCircuitConfig},
     witness,
 };
-use bus_mapping::circuit_input_builder::BigModExp;
-use eth_types::{Field, Word};
-
-//use misc_precompiled_circuit::value_for_assign;
-use misc_precompiled_circuit::circuits::{
-    modexp::{ModExpChip, Number},
-    range::{RangeCheckChip, RangeCheckConfig},
-    CommonGateConfig as ModExpConfig, Limb,
+use bus_mapping::{circuit_input_builder::BigModExp, precompile::MODEXP_SIZE_LIMIT};
+use eth_types::Field;
+use gadgets::util::{not, sum, Expr};
+use halo2_proofs::{
+    circuit::{Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, TableColumn,
+        VirtualCells,
+    },
+    poly::Rotation,
 };
+use num::{BigInt, BigUint, Integer, One, ToPrimitive, Zero};
+use std::marker::PhantomData;
+
+/// Number of bytes of a limb.
+const N_BYTES_LIMB: usize = 8;
+/// Number of limbs of the integers.
+pub(crate) const N_LIMBS: usize = MODEXP_SIZE_LIMIT / N_BYTES_LIMB;
+/// Number of rows of a block, one per coefficient of the product of two
+/// integers.
+pub(crate) const BLOCK_ROWS: usize = 2 * N_LIMBS - 1;
+/// Number of u16 chunks of a limb.
+const N_CHUNKS_LIMB: usize = 4;
+/// Number of u16 chunks of a carry.
+const N_CHUNKS_CARRY: usize = 5;
+/// Offset of the carries, so that their chunks are positive.
+const CARRY_OFFSET: u128 = 1 << 79;
+/// Rows budget of an event, which bounds the number of events of the
+/// circuit.
+const MODEXP_ROWS_PER_EVENT: usize = 24576;
+
+/// Kind of the modular multiplication of a block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BlockKind {
+    Reduce,
+    Square,
+    Mul,
+    Final,
+    Padding,
+}
+
+impl BlockKind {
+    /// Kinds with a column, padding blocks are none of them.
+    const COLUMNS: [Self; 4] = [Self::Reduce, Self::Square, Self::Mul, Self::Final];
+}
+
+/// Position of a block in the walk over the bits of the exponent, from its
+/// most significant bit.
+#[derive(Clone, Copy, Debug, Default)]
+struct ExpBit {
+    /// index of the byte in the exponent
+    index: usize,
+    /// power of two of the bit in the byte
+    pow: u64,
+    /// byte of the exponent
+    byte: u8,
+    /// bits of the byte from the bit
+    rem: u64,
+    /// the bit
+    bit: bool,
+}
+
+/// Modular multiplication `a * b = q * modulus + r` of a block.
+#[derive(Clone, Debug)]
+struct Block {
+    kind: BlockKind,
+    event_id: usize,
+    exp_bit_len: u64,
+    exp_bit: ExpBit,
+    a: BigUint,
+    b: BigUint,
+    /// reduced base of the exponentiation
+    base: BigUint,
+    modulus: BigUint,
+}
+
+impl Block {
+    fn padding() -> Self {
+        Self {
+            kind: BlockKind::Padding,
+            event_id: 0,
+            exp_bit_len: 0,
+            exp_bit: ExpBit::default(),
+            a: BigUint::zero(),
+            b: BigUint::zero(),
+            base: BigUint::zero(),
+            modulus: BigUint::zero(),
+        }
+    }
+}
+
+// Bit length of the exponent of the event.
+fn exp_bit_len(event: &BigModExp) -> u64 {
+    ModExpTable::exp_bit_lens(&event.exponent)[MODEXP_SIZE_LIMIT - 1]
+}
+
+// Index of the exponent byte where the walk over its bits starts, for a
+// non-zero bit length.
+fn exp_walk_start(exp_bit_len: u64) -> usize {
+    MODEXP_SIZE_LIMIT - 1 - (exp_bit_len as usize - 1) / 8
+}
+
+// Number of blocks of the chain of the event.
+fn num_blocks(event: &BigModExp) -> usize {
+    if event.modulus.iter().all(|byte| *byte == 0) {
+        return 0;
+    }
+    let exp_bit_len = exp_bit_len(event);
+    if exp_bit_len == 0 {
+        return 2;
+    }
+    2 + event.exponent[exp_walk_start(exp_bit_len)..]
+        .iter()
+        .map(|byte| 8 + byte.count_ones() as usize)
+        .sum::<usize>()
+}
+
+// Blocks of the chain of the event, none when its modulus is zero.
+fn event_blocks(event_id: usize, event: &BigModExp) -> Vec<Block> {
+    let modulus = BigUint::from_bytes_be(&event.modulus);
+    if modulus.is_zero() {
+        return vec![];
+    }
+    let exp_bit_len = exp_bit_len(event);
+    let base = BigUint::from_bytes_be(&event.base);
+    let reduced_base = &base % &modulus;
+    let block = |kind, exp_bit, a: &BigUint, b: &BigUint| Block {
+        kind,
+        event_id,
+        exp_bit_len,
+        exp_bit,
+        a: a.clone(),
+        b: b.clone(),
+        base: reduced_base.clone(),
+        modulus: modulus.clone(),
+    };
+
+    let one = BigUint::one();
+    let mut blocks = vec![block(BlockKind::Reduce, ExpBit::default(), &base, &one)];
+    let mut acc = one.clone();
+    if exp_bit_len > 0 {
+        for index in exp_walk_start(exp_bit_len)..MODEXP_SIZE_LIMIT {
+            let byte = event.exponent[index];
+            let mut rem = byte as u64;
+            for shift in (0..8).rev() {
+                let pow = 1 << shift;
+                let exp_bit = ExpBit {
+                    index,
+                    pow,
+                    byte,
+                    rem,
+                    bit: rem >= pow,
+                };
+                blocks.push(block(BlockKind::Square, exp_bit, &acc, &acc));
+                acc = &acc * &acc % &modulus;
+                if exp_bit.bit {
+                    blocks.push(block(BlockKind::Mul, exp_bit, &acc, &reduced_base));
+                    acc = &acc * &reduced_base % &modulus;
+                    rem -= pow;
+                }
+            }
+        }
+    }
+    blocks.push(block(BlockKind::Final, ExpBit::default(), &acc, &one));
+    blocks
+}
+
+// Limbs of the integer, from the least significant.
+fn limbs(value: &BigUint) -> [u64; N_LIMBS] {
+    let digits = value.to_u64_digits();
+    assert!(digits.len() <= N_LIMBS, "integer out of the modexp limbs");
+    let mut limbs = [0; N_LIMBS];
+    limbs[..digits.len()].copy_from_slice(&digits);
+    limbs
+}
+
+// Signed carries `c` of `a * b - q * m - r`, whose coefficient `k` is
+// `c[k - 1] - 2^64 * c[k]`.
+fn carries(
+    a: &[u64; N_LIMBS],
+    b: &[u64; N_LIMBS],
+    q: &[u64; N_LIMBS],
+    m: &[u64; N_LIMBS],
+    r: &[u64; N_LIMBS],
+) -> Vec<i128> {
+    let two_64 = BigInt::one() << 64;
+    let mut carry = BigInt::zero();
+    (0..BLOCK_ROWS)
+        .map(|k| {
+            let mut coeff = BigInt::zero();
+            for i in k.saturating_sub(N_LIMBS - 1)..=k.min(N_LIMBS - 1) {
+                coeff += a[i] as u128 * b[k - i] as u128;
+                coeff -= q[i] as u128 * m[k - i] as u128;
+            }
+            if k < N_LIMBS {
+                coeff -= r[k];
+            }
+            let (next, rem) = (&carry - coeff).div_rem(&two_64);
+            debug_assert!(rem.is_zero(), "inexact carry of modexp product");
+            carry = next;
+            carry
+                .to_i128()
+                .expect("carry of modexp product fits in 80 bits")
+        })
+        .collect()
+}
+
+// Evaluations at the challenge of the coefficients from each of them, by
+// Horner's rule from the last one.
+fn horner_evals<F: Field>(coeffs: &[F], x: Value<F>) -> Vec<Value<F>> {
+    let mut acc = Value::known(F::zero());
+    let mut evals = coeffs
+        .iter()
+        .rev()
+        .map(|coeff| {
+            acc = acc * x + Value::known(*coeff);
+            acc
+        })
+        .collect::<Vec<_>>();
+    evals.reverse();
+    evals
+}
+
+fn u16_chunks<const N: usize>(value: u128) -> [u64; N] {
+    array_init::array_init(|j| ((value >> (16 * j)) & 0xffff) as u64)
+}
 
 /// ModExp circuit config
 #[derive(Clone, Debug)]
 pub struct ModExpCircuitConfig {
-    modexp_config: ModExpConfig,
-    rangecheck_config: RangeCheckConfig,
     modexp_table: ModExpTable,
+    u16_table: U16Table,
+    /// Fixed table of the bytes and of their bit length
+    byte_table: [TableColumn; 2],
+    /// Identifier of the event of the table row, from 1
+    event_id: Column<Fixed>,
+    /// Index of the byte of the table row in its event
+    byte_index: Column<Fixed>,
+    /// Whether the table row holds the last byte of a limb
+    q_limb_end: Column<Fixed>,
+    /// Bit length of the exponent byte of the table row
+    exp_byte_bit_len: Column<Advice>,
+    /// Inverse of the bit length of the exponent bytes before the table row
+    exp_bit_len_prev_inv: Column<Advice>,
+    /// Inverse of the evaluation of the modulus, at the tail of an event
+    modulus_eval_inv: Column<Advice>,
+    /// Whether the modulus is zero, at the tail of an event
+    is_modulus_zero: Column<Advice>,
+    /// Whether the row belongs to a block
+    q_block: Column<Fixed>,
+    /// Whether the row is the first row of a block, holding its head cells
+    q_block_head: Column<Fixed>,
+    /// Whether the row is the last row of a block
+    q_block_last: Column<Fixed>,
+    /// Whether the row of the block holds limbs of the integers
+    q_limb: Column<Fixed>,
+    /// Whether the row is the head of the first block
+    q_first_block: Column<Fixed>,
+    /// Whether the row is the head of the last block
+    q_last_block: Column<Fixed>,
+    /// Whether the block is a REDUCE, SQUARE, MUL or FINAL block, padding
+    /// blocks are none of them
+    kinds: [Column<Advice>; 4],
+    /// Event of the block
+    block_event_id: Column<Advice>,
+    /// Bit length of the exponent of the event of the block
+    block_exp_bit_len: Column<Advice>,
+    /// Position of the block in the walk over the bits of the exponent
+    exp_index: Column<Advice>,
+    exp_pow: Column<Advice>,
+    exp_byte: Column<Advice>,
+    exp_rem: Column<Advice>,
+    exp_bit: Column<Advice>,
+    /// Inverse of `pow - 1`, and whether `pow == 1`
+    exp_pow_inv: Column<Advice>,
+    is_byte_end: Column<Advice>,
+    /// Inverse of `index - (MODEXP_SIZE_LIMIT - 1)`, and whether it is zero
+    exp_index_inv: Column<Advice>,
+    is_index_end: Column<Advice>,
+    /// Limb of the first multiplicand
+    a: Column<Advice>,
+    /// Limb of the reduced base, carried along the chain
+    base: Column<Advice>,
+    /// Limb of the modulus
+    modulus: Column<Advice>,
+    /// u16 chunks of the limbs of the quotient, of the remainder and of
+    /// `modulus - remainder - 1`
+    quotient: [Column<Advice>; N_CHUNKS_LIMB],
+    remainder: [Column<Advice>; N_CHUNKS_LIMB],
+    diff: [Column<Advice>; N_CHUNKS_LIMB],
+    /// Borrow of the limb of `modulus - remainder - 1`
+    borrow: Column<Advice>,
+    /// u16 chunks of the carry, offset by 2^79
+    carry: [Column<Advice>; N_CHUNKS_CARRY],
+    /// Evaluations of the polynomials of the block from the row. In the table
+    /// rows, the evaluations of `a`, modulus and remainder hold the ones of
+    /// the limbs of the base, modulus and result so far
+    a_eval: Column<Advice>,
+    b_eval: Column<Advice>,
+    modulus_eval: Column<Advice>,
+    quotient_eval: Column<Advice>,
+    remainder_eval: Column<Advice>,
+    carry_eval: Column<Advice>,
+}
+
+/// Arguments to configure ModExp circuit
+pub struct ModExpCircuitConfigArgs<F> {
+    /// Modexp table
+    pub modexp_table: ModExpTable,
+    /// u16 lookup table
+    pub u16_table: U16Table,
+    /// Challenges
+    pub challenges: Challenges<Expression<F>>,
 }
 
 impl<F: Field> SubCircuitConfig<F> for ModExpCircuitConfig {
-    type ConfigArgs = ModExpTable;
+    type ConfigArgs = ModExpCircuitConfigArgs<F>;
 
     /// Return a new ModExpCircuitConfig
-    fn new(meta: &mut ConstraintSystem<F>, modexp_table: Self::ConfigArgs) -> Self {
-        let rangecheck_config = RangeCheckChip::configure(meta);
-        let modexp_config = ModExpChip::configure(meta, &rangecheck_config);
-        Self {
-            rangecheck_config,
-            modexp_config,
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        ModExpCircuitConfigArgs {
             modexp_table,
-        }
+            u16_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let config = Self {
+            modexp_table,
+            u16_table,
+            byte_table: [meta.lookup_table_column(), meta.lookup_table_column()],
+            event_id: meta.fixed_column(),
+            byte_index: meta.fixed_column(),
+            q_limb_end: meta.fixed_column(),
+            exp_byte_bit_len: meta.advice_column(),
+            exp_bit_len_prev_inv: meta.advice_column(),
+            modulus_eval_inv: meta.advice_column_in(SecondPhase),
+            is_modulus_zero: meta.advice_column(),
+            q_block: meta.fixed_column(),
+            q_block_head: meta.fixed_column(),
+            q_block_last: meta.fixed_column(),
+            q_limb: meta.fixed_column(),
+            q_first_block: meta.fixed_column(),
+            q_last_block: meta.fixed_column(),
+            kinds: array_init::array_init(|_| meta.advice_column()),
+            block_event_id: meta.advice_column(),
+            block_exp_bit_len: meta.advice_column(),
+            exp_index: meta.advice_column(),
+            exp_pow: meta.advice_column(),
+            exp_byte: meta.advice_column(),
+            exp_rem: meta.advice_column(),
+            exp_bit: meta.advice_column(),
+            exp_pow_inv: meta.advice_column(),
+            is_byte_end: meta.advice_column(),
+            exp_index_inv: meta.advice_column(),
+            is_index_end: meta.advice_column(),
+            a: meta.advice_column(),
+            base: meta.advice_column(),
+            modulus: meta.advice_column(),
+            quotient: array_init::array_init(|_| meta.advice_column()),
+            remainder: array_init::array_init(|_| meta.advice_column()),
+            diff: array_init::array_init(|_| meta.advice_column()),
+            borrow: meta.advice_column(),
+            carry: array_init::array_init(|_| meta.advice_column()),
+            a_eval: meta.advice_column_in(SecondPhase),
+            b_eval: meta.advice_column_in(SecondPhase),
+            modulus_eval: meta.advice_column_in(SecondPhase),
+            quotient_eval: meta.advice_column_in(SecondPhase),
+            remainder_eval: meta.advice_column_in(SecondPhase),
+            carry_eval: meta.advice_column_in(SecondPhase),
+        };
+        let x = challenges.evm_word();
+        config.configure_table(meta, x.expr());
+        config.configure_blocks(meta, x);
+        config
     }
 }
 
 impl ModExpCircuitConfig {
-    pub(crate) fn assign_group<F: Field>(
+    fn configure_table<F: Field>(&self, meta: &mut ConstraintSystem<F>, x: Expression<F>) {
+        let table = self.modexp_table;
+
+        for column in [table.base, table.modulus, table.result] {
+            meta.lookup("modexp table: byte range", |meta| {
+                let q_enable = meta.query_fixed(table.q_enable, Rotation::cur());
+                vec![(
+                    q_enable * meta.query_advice(column, Rotation::cur()),
+                    self.byte_table[0],
+                )]
+            });
+        }
+        meta.lookup("modexp table: bit length of the exponent byte", |meta| {
+            let q_enable = meta.query_fixed(table.q_enable, Rotation::cur());
+            vec![
+                (
+                    q_enable.expr() * meta.query_advice(table.exp, Rotation::cur()),
+                    self.byte_table[0],
+                ),
+                (
+                    q_enable * meta.query_advice(self.exp_byte_bit_len, Rotation::cur()),
+                    self.byte_table[1],
+                ),
+            ]
+        });
+
+        meta.create_gate("modexp table: rows", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+            let q_head = meta.query_fixed(table.q_head, Rotation::cur());
+            let q_limb_end = meta.query_fixed(self.q_limb_end, Rotation::cur());
+
+            // the bit length of the exponent starts at its first non-zero byte
+            let exp_bit_len_prev =
+                not::expr(q_head.expr()) * meta.query_advice(table.exp_bit_len, Rotation::prev());
+            let is_prev_zero = 1.expr()
+                - exp_bit_len_prev.expr()
+                    * meta.query_advice(self.exp_bit_len_prev_inv, Rotation::cur());
+            cb.require_zero(
+                "is_prev_zero == (exp_bit_len::prev == 0)",
+                exp_bit_len_prev.expr() * is_prev_zero.expr(),
+            );
+            cb.require_equal(
+                "exp_bit_len == exp_bit_len::prev == 0 ? bit_len(exp) : exp_bit_len::prev + 8",
+                meta.query_advice(table.exp_bit_len, Rotation::cur()),
+                is_prev_zero.expr() * meta.query_advice(self.exp_byte_bit_len, Rotation::cur())
+                    + not::expr(is_prev_zero) * (exp_bit_len_prev + 8.expr()),
+            );
+
+            // evaluations of the limbs so far, from the most significant
+            for (byte, eval) in [
+                (table.base, self.a_eval),
+                (table.modulus, self.modulus_eval),
+                (table.result, self.remainder_eval),
+            ] {
+                let limb = (0..N_BYTES_LIMB).fold(0.expr(), |acc, i| {
+                    acc * 256.expr()
+                        + meta.query_advice(byte, Rotation((i + 1) as i32 - N_BYTES_LIMB as i32))
+                });
+                let eval_prev =
+                    not::expr(q_head.expr()) * meta.query_advice(eval, Rotation::prev());
+                cb.require_equal(
+                    "eval == q_limb_end ? eval::prev * x + limb : eval::prev",
+                    meta.query_advice(eval, Rotation::cur()),
+                    q_limb_end.expr() * (eval_prev.expr() * x.expr() + limb)
+                        + not::expr(q_limb_end.expr()) * eval_prev,
+                );
+            }
+
+            cb.gate(meta.query_fixed(table.q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("modexp table: tail", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+            let modulus_eval = meta.query_advice(self.modulus_eval, Rotation::cur());
+            let is_modulus_zero = meta.query_advice(self.is_modulus_zero, Rotation::cur());
+            cb.require_equal(
+                "is_modulus_zero == (modulus == 0)",
+                is_modulus_zero.expr(),
+                1.expr()
+                    - modulus_eval.expr()
+                        * meta.query_advice(self.modulus_eval_inv, Rotation::cur()),
+            );
+            cb.require_zero(
+                "is_modulus_zero == (modulus == 0)",
+                modulus_eval * is_modulus_zero.expr(),
+            );
+            cb.condition(is_modulus_zero, |cb| {
+                cb.require_zero(
+                    "result is zero for a zero modulus",
+                    meta.query_advice(self.remainder_eval, Rotation::cur()),
+                );
+            });
+            cb.gate(meta.query_fixed(table.q_tail, Rotation::cur()))
+        });
+
+        // events with a non-zero modulus have the result of the FINAL block of
+        // their chain
+        meta.lookup_any("modexp table: result of the event", |meta| {
+            let cond = meta.query_fixed(table.q_tail, Rotation::cur())
+                * not::expr(meta.query_advice(self.is_modulus_zero, Rotation::cur()));
+            let q_block_head = meta.query_fixed(self.q_block_head, Rotation::cur());
+            let is_final = meta.query_advice(self.kinds[3], Rotation::cur());
+            vec![
+                (
+                    cond.expr() * meta.query_fixed(self.event_id, Rotation::cur()),
+                    q_block_head.expr()
+                        * is_final
+                        * meta.query_advice(self.block_event_id, Rotation::cur()),
+                ),
+                (
+                    cond * meta.query_advice(self.remainder_eval, Rotation::cur()),
+                    q_block_head * meta.query_advice(self.remainder_eval, Rotation::cur()),
+                ),
+            ]
+        });
+    }
+
+    fn configure_blocks<F: Field>(&self, meta: &mut ConstraintSystem<F>, x: Expression<F>) {
+        let table = self.modexp_table;
+        let two_64 = Expression::Constant(F::from_u128(1 << 64));
+        let next_block = Rotation(BLOCK_ROWS as i32);
+
+        for column in self
+            .quotient
+            .iter()
+            .chain(self.remainder.iter())
+            .chain(self.diff.iter())
+            .chain(self.carry.iter())
+        {
+            meta.lookup("modexp block: u16 chunk", |meta| {
+                let q_block = meta.query_fixed(self.q_block, Rotation::cur());
+                vec![(
+                    q_block * meta.query_advice(*column, Rotation::cur()),
+                    self.u16_table.into(),
+                )]
+            });
+        }
+
+        meta.create_gate("modexp block: rows", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+            let q_block_head = meta.query_fixed(self.q_block_head, Rotation::cur());
+            let q_block_last = meta.query_fixed(self.q_block_last, Rotation::cur());
+            let kinds = self.kinds(meta, Rotation::cur());
+            let [is_reduce, is_square, is_mul, is_final] = kinds.clone();
+
+            cb.condition(not::expr(q_block_last.expr()), |cb| {
+                for (kind, kind_next) in kinds.iter().zip(self.kinds(meta, Rotation::next())) {
+                    cb.require_equal("kind::next == kind", kind_next, kind.expr());
+                }
+            });
+
+            let a = meta.query_advice(self.a, Rotation::cur());
+            let base = meta.query_advice(self.base, Rotation::cur());
+            let modulus = meta.query_advice(self.modulus, Rotation::cur());
+            let quotient = Self::limb(meta, &self.quotient, Rotation::cur());
+            let remainder = Self::limb(meta, &self.remainder, Rotation::cur());
+            let diff = Self::limb(meta, &self.diff, Rotation::cur());
+            cb.condition(
+                not::expr(meta.query_fixed(self.q_limb, Rotation::cur())),
+                |cb| {
+                    for limb in [&a, &base, &modulus, &quotient, &remainder, &diff] {
+                        cb.require_zero("no limb beyond the integers", limb.expr());
+                    }
+                },
+            );
+
+            // b is a for SQUARE, the reduced base for MUL, and 1 for REDUCE and FINAL
+            let b = is_square * a.expr()
+                + is_mul * base.expr()
+                + (is_reduce.expr() + is_final) * q_block_head.expr();
+            for (value, eval) in [
+                (a.expr(), self.a_eval),
+                (b, self.b_eval),
+                (modulus.expr(), self.modulus_eval),
+                (quotient, self.quotient_eval),
+                (remainder.expr(), self.remainder_eval),
+                (self.carry_expr(meta), self.carry_eval),
+            ] {
+                cb.require_equal(
+                    "eval == value + eval::next * x",
+                    meta.query_advice(eval, Rotation::cur()),
+                    value
+                        + not::expr(q_block_last.expr())
+                            * meta.query_advice(eval, Rotation::next())
+                            * x.expr(),
+                );
+            }
+
+            // remainder < modulus, as modulus - remainder - 1 doesn't underflow
+            let borrow = meta.query_advice(self.borrow, Rotation::cur());
+            let borrow_prev =
+                not::expr(q_block_head.expr()) * meta.query_advice(self.borrow, Rotation::prev());
+            cb.require_boolean("borrow is boolean", borrow.expr());
+            cb.condition(q_block_last, |cb| {
+                cb.require_zero("no borrow out of the last limb", borrow.expr());
+            });
+            cb.condition(sum::expr(kinds), |cb| {
+                cb.require_equal(
+                    "diff == modulus - remainder - 1 with borrows",
+                    diff + borrow * two_64.expr(),
+                    modulus.expr() - remainder.expr() - q_block_head.expr() - borrow_prev,
+                );
+            });
+
+            // the next block continues the chain
+            let [_, is_square_next, is_mul_next, is_final_next] = self.kinds(meta, next_block);
+            cb.condition(
+                meta.query_fixed(self.q_block, next_block)
+                    * (is_square_next + is_mul_next + is_final_next),
+                |cb| {
+                    cb.require_equal(
+                        "a::next == is_reduce ? 1 : remainder",
+                        meta.query_advice(self.a, next_block),
+                        is_reduce.expr() * q_block_head
+                            + not::expr(is_reduce.expr()) * remainder.expr(),
+                    );
+                    cb.require_equal(
+                        "base::next == is_reduce ? remainder : base",
+                        meta.query_advice(self.base, next_block),
+                        is_reduce.expr() * remainder + not::expr(is_reduce) * base,
+                    );
+                    cb.require_equal(
+                        "modulus::next == modulus",
+                        meta.query_advice(self.modulus, next_block),
+                        modulus,
+                    );
+                },
+            );
+
+            cb.gate(meta.query_fixed(self.q_block, Rotation::cur()))
+        });
+
+        meta.create_gate("modexp block: head", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+            let kinds = self.kinds(meta, Rotation::cur());
+            let [is_reduce, is_square, is_mul, is_final] = kinds.clone();
+            for kind in kinds.iter() {
+                cb.require_boolean("kind is boolean", kind.expr());
+            }
+            cb.require_boolean("at most one kind", sum::expr(kinds.clone()));
+            let is_padding = not::expr(sum::expr(kinds));
+
+            let [a_eval, b_eval, modulus_eval, quotient_eval, remainder_eval, carry_eval] = [
+                self.a_eval,
+                self.b_eval,
+                self.modulus_eval,
+                self.quotient_eval,
+                self.remainder_eval,
+                self.carry_eval,
+            ]
+            .map(|eval| meta.query_advice(eval, Rotation::cur()));
+            cb.require_equal(
+                "a(x) * b(x) == q(x) * m(x) + r(x) + (x - 2^64) * c(x)",
+                a_eval * b_eval,
+                quotient_eval * modulus_eval
+                    + remainder_eval
+                    + (x.expr() - two_64.expr()) * carry_eval,
+            );
+
+            // order of the blocks
+            let q_block_next = meta.query_fixed(self.q_block, next_block);
+            let [_, is_square_next, is_mul_next, is_final_next] = self.kinds(meta, next_block);
+            let is_continued = q_block_next.expr()
+                * (is_square_next.expr() + is_mul_next.expr() + is_final_next.expr());
+            cb.condition(
+                meta.query_fixed(self.q_first_block, Rotation::cur()),
+                |cb| {
+                    cb.require_zero(
+                        "first block is REDUCE or padding",
+                        is_square.expr() + is_mul.expr() + is_final.expr(),
+                    );
+                },
+            );
+            cb.condition(meta.query_fixed(self.q_last_block, Rotation::cur()), |cb| {
+                cb.require_zero(
+                    "last block is FINAL or padding",
+                    is_reduce.expr() + is_square.expr() + is_mul.expr(),
+                );
+            });
+            cb.require_zero(
+                "FINAL and padding are followed by REDUCE or padding",
+                (is_final + is_padding) * is_continued.expr(),
+            );
+            cb.condition(is_reduce.expr(), |cb| {
+                cb.require_equal(
+                    "REDUCE is followed by SQUARE or FINAL",
+                    is_square_next.expr() + is_final_next.expr(),
+                    1.expr(),
+                );
+            });
+
+            let event_id = meta.query_advice(self.block_event_id, Rotation::cur());
+            let exp_bit_len = meta.query_advice(self.block_exp_bit_len, Rotation::cur());
+            cb.condition(is_continued, |cb| {
+                cb.require_equal(
+                    "event_id::next == event_id",
+                    meta.query_advice(self.block_event_id, next_block),
+                    event_id,
+                );
+                cb.require_equal(
+                    "exp_bit_len::next == exp_bit_len",
+                    meta.query_advice(self.block_exp_bit_len, next_block),
+                    exp_bit_len.expr(),
+                );
+            });
+            cb.condition(is_reduce.expr() * is_final_next.expr(), |cb| {
+                cb.require_zero(
+                    "REDUCE is followed by FINAL for a zero exponent",
+                    exp_bit_len,
+                );
+            });
+
+            // walk over the bits of the exponent, from the most significant
+            let walk = [
+                self.exp_index,
+                self.exp_pow,
+                self.exp_byte,
+                self.exp_rem,
+                self.exp_bit,
+            ];
+            let [index, pow, byte, rem, bit] =
+                walk.map(|column| meta.query_advice(column, Rotation::cur()));
+            let [index_next, pow_next, byte_next, rem_next, bit_next] =
+                walk.map(|column| meta.query_advice(column, next_block));
+            cb.require_boolean("bit is boolean", bit.expr());
+
+            let is_byte_end = meta.query_advice(self.is_byte_end, Rotation::cur());
+            cb.require_equal(
+                "is_byte_end == (pow == 1)",
+                is_byte_end.expr(),
+                1.expr()
+                    - (pow.expr() - 1.expr())
+                        * meta.query_advice(self.exp_pow_inv, Rotation::cur()),
+            );
+            cb.require_zero(
+                "is_byte_end == (pow == 1)",
+                (pow.expr() - 1.expr()) * is_byte_end.expr(),
+            );
+            let last_index = (MODEXP_SIZE_LIMIT - 1).expr();
+            let is_index_end = meta.query_advice(self.is_index_end, Rotation::cur());
+            cb.require_equal(
+                "is_index_end == (index == MODEXP_SIZE_LIMIT - 1)",
+                is_index_end.expr(),
+                1.expr()
+                    - (index.expr() - last_index.expr())
+                        * meta.query_advice(self.exp_index_inv, Rotation::cur()),
+            );
+            cb.require_zero(
+                "is_index_end == (index == MODEXP_SIZE_LIMIT - 1)",
+                (index.expr() - last_index) * is_index_end.expr(),
+            );
+
+            cb.condition(is_reduce * is_square_next.expr(), |cb| {
+                cb.require_equal(
+                    "walk starts at the most significant bit of the byte",
+                    pow_next.expr(),
+                    128.expr(),
+                );
+                cb.require_equal(
+                    "walk starts with all the bits of the byte",
+                    rem_next.expr(),
+                    byte_next.expr(),
+                );
+            });
+            cb.condition(
+                not::expr(is_square.expr() * bit.expr()) * q_block_next,
+                |cb| {
+                    cb.require_zero("MUL only follows SQUARE of a set bit", is_mul_next.expr());
+                },
+            );
+            cb.condition(is_square.expr() * bit.expr(), |cb| {
+                cb.require_equal(
+                    "SQUARE of a set bit is followed by MUL",
+                    is_mul_next.expr(),
+                    1.expr(),
+                );
+                for (value_next, value) in [
+                    (index_next.expr(), index.expr()),
+                    (pow_next.expr(), pow.expr()),
+                    (byte_next.expr(), byte.expr()),
+                    (rem_next.expr(), rem.expr()),
+                    (bit_next, bit.expr()),
+                ] {
+                    cb.require_equal("MUL is at the bit of its SQUARE", value_next, value);
+                }
+            });
+
+            // the walk moves to the next bit after SQUARE of an unset bit, and
+            // after MUL
+            let is_moving = is_square * not::expr(bit.expr()) + is_mul;
+            let is_walk_end = is_byte_end.expr() * is_index_end;
+            cb.condition(is_moving.expr() * is_byte_end.expr(), |cb| {
+                cb.require_equal("bit is the last bit of the byte", rem.expr(), bit.expr());
+            });
+            cb.condition(is_moving.expr() * is_walk_end.expr(), |cb| {
+                cb.require_equal("walk ends with FINAL", is_final_next, 1.expr());
+            });
+            cb.condition(is_moving.expr() * not::expr(is_walk_end.expr()), |cb| {
+                cb.require_equal("walk goes on with SQUARE", is_square_next, 1.expr());
+            });
+            cb.condition(
+                is_moving.expr() * not::expr(is_walk_end) * is_byte_end.expr(),
+                |cb| {
+                    cb.require_equal(
+                        "index::next == index + 1",
+                        index_next.expr(),
+                        index.expr() + 1.expr(),
+                    );
+                    cb.require_equal(
+                        "walk goes on at the most significant bit of the next byte",
+                        pow_next.expr(),
+                        128.expr(),
+                    );
+                    cb.require_equal(
+                        "walk goes on with all the bits of the next byte",
+                        rem_next.expr(),
+                        byte_next.expr(),
+                    );
+                },
+            );
+            cb.condition(is_moving * not::expr(is_byte_end), |cb| {
+                cb.require_equal("index::next == index", index_next, index);
+                cb.require_equal("byte::next == byte", byte_next, byte);
+                cb.require_equal("pow == 2 * pow::next", pow.expr(), 2.expr() * pow_next);
+                cb.require_equal("rem::next == rem - bit * pow", rem_next, rem - bit * pow);
+            });
+
+            cb.gate(meta.query_fixed(self.q_block_head, Rotation::cur()))
+        });
+
+        // the walk starts early enough to go over all the bits of the exponent
+        meta.lookup("modexp block: start of the walk", |meta| {
+            let cond = meta.query_fixed(self.q_block_head, Rotation::cur())
+                * meta.query_advice(self.kinds[0], Rotation::cur())
+                * meta.query_advice(self.kinds[1], next_block);
+            let index_next = meta.query_advice(self.exp_index, next_block);
+            let exp_bit_len = meta.query_advice(self.block_exp_bit_len, Rotation::cur());
+            vec![(
+                cond * ((MODEXP_SIZE_LIMIT.expr() - index_next) * 8.expr() - exp_bit_len),
+                self.u16_table.into(),
+            )]
+        });
+
+        // SQUARE blocks walk over the bytes of the exponent of their event
+        meta.lookup_any("modexp block: exponent byte", |meta| {
+            let cond = meta.query_fixed(self.q_block_head, Rotation::cur())
+                * meta.query_advice(self.kinds[1], Rotation::cur());
+            let q_enable = meta.query_fixed(table.q_enable, Rotation::cur());
+            vec![
+                (
+                    cond.expr() * meta.query_advice(self.block_event_id, Rotation::cur()),
+                    q_enable.expr() * meta.query_fixed(self.event_id, Rotation::cur()),
+                ),
+                (
+                    cond.expr() * meta.query_advice(self.exp_index, Rotation::cur()),
+                    q_enable.expr() * meta.query_fixed(self.byte_index, Rotation::cur()),
+                ),
+                (
+                    cond * meta.query_advice(self.exp_byte, Rotation::cur()),
+                    q_enable * meta.query_advice(table.exp, Rotation::cur()),
+                ),
+            ]
+        });
+
+        // REDUCE blocks start the chain of their event from its base and modulus
+        meta.lookup_any("modexp block: REDUCE of the event", |meta| {
+            let cond = meta.query_fixed(self.q_block_head, Rotation::cur())
+                * meta.query_advice(self.kinds[0], Rotation::cur());
+            let q_tail = meta.query_fixed(table.q_tail, Rotation::cur());
+            vec![
+                (
+                    cond.expr() * meta.query_advice(self.block_event_id, Rotation::cur()),
+                    q_tail.expr() * meta.query_fixed(self.event_id, Rotation::cur()),
+                ),
+                (
+                    cond.expr() * meta.query_advice(self.a_eval, Rotation::cur()),
+                    q_tail.expr() * meta.query_advice(self.a_eval, Rotation::cur()),
+                ),
+                (
+                    cond.expr() * meta.query_advice(self.modulus_eval, Rotation::cur()),
+                    q_tail.expr() * meta.query_advice(self.modulus_eval, Rotation::cur()),
+                ),
+                (
+                    cond * meta.query_advice(self.block_exp_bit_len, Rotation::cur()),
+                    q_tail * meta.query_advice(table.exp_bit_len, Rotation::cur()),
+                ),
+            ]
+        });
+    }
+
+    fn kinds<F: Field>(&self, meta: &mut VirtualCells<F>, at: Rotation) -> [Expression<F>; 4] {
+        self.kinds.map(|kind| meta.query_advice(kind, at))
+    }
+
+    fn limb<F: Field>(
+        meta: &mut VirtualCells<F>,
+        chunks: &[Column<Advice>],
+        at: Rotation,
+    ) -> Expression<F> {
+        chunks.iter().rev().fold(0.expr(), |acc, chunk| {
+            acc * 65536.expr() + meta.query_advice(*chunk, at)
+        })
+    }
+
+    fn carry_expr<F: Field>(&self, meta: &mut VirtualCells<F>) -> Expression<F> {
+        Self::limb(meta, &self.carry, Rotation::cur())
+            - Expression::Constant(F::from_u128(CARRY_OFFSET))
+    }
+
+    fn load_byte_table<F: Field>(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "modexp byte table",
+            |mut table| {
+                for byte in 0..=u8::MAX {
+                    table.assign_cell(
+                        || "byte",
+                        self.byte_table[0],
+                        byte as usize,
+                        || Value::known(F::from(byte as u64)),
+                    )?;
+                    table.assign_cell(
+                        || "bit length of byte",
+                        self.byte_table[1],
+                        byte as usize,
+                        || Value::known(F::from(8 - byte.leading_zeros() as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    fn assign_table_event<F: Field>(
         &self,
-        region: &mut Region<F>,
-        table_offset: usize,
-        mut calc_offset: usize,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        event_id: usize,
         event: &BigModExp,
-        modexp_chip: &ModExpChip<F>,
-        range_check_chip: &mut RangeCheckChip<F>,
-    ) -> Result<usize, Error> {
-        let base = self.assign_value(region, table_offset, self.modexp_table.base, &event.base)?;
-        let exp =
-            self.assign_value(region, table_offset, self.modexp_table.exp, &event.exponent)?;
-        let modulus = self.assign_value(
-            region,
-            table_offset,
-            self.modexp_table.modulus,
-            &event.modulus,
-        )?;
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        self.modexp_table
+            .assign_event(region, offset, event, challenges)?;
 
-        let ret = modexp_chip.mod_exp(
-            region,
-            range_check_chip,
-            &mut calc_offset,
-            &base,
-            &exp,
-            &modulus,
-        )?;
-        for i in 0..4 {
-            region.assign_fixed(
-                || format!("modexp table head {}", table_offset + i),
-                self.modexp_table.q_head,
-                table_offset + i,
-                || Value::known(if i == 0 { F::one() } else { F::zero() }),
-            )?;
-
-            ret.limbs[i]
-                .cell
-                .clone()
-                .expect("should has assigned after modexp")
-                .copy_advice(
-                    || "copy to result limbs",
-                    region,
-                    self.modexp_table.result,
-                    table_offset + i,
+        let x = challenges.evm_word();
+        let exp_bit_lens = ModExpTable::exp_bit_lens(&event.exponent);
+        let integers = [&event.base, &event.modulus, &event.result];
+        let mut evals = [Value::known(F::zero()); 3];
+        for i in 0..MODEXP_SIZE_LIMIT {
+            let row = offset + i;
+            let is_limb_end = i % N_BYTES_LIMB == N_BYTES_LIMB - 1;
+            for (column, value) in [
+                (self.event_id, event_id as u64),
+                (self.byte_index, i as u64),
+                (self.q_limb_end, is_limb_end as u64),
+            ] {
+                region.assign_fixed(
+                    || format!("modexp table fixed row {row}"),
+                    column,
+                    row,
+                    || Value::known(F::from(value)),
+                )?;
+            }
+
+            let exp_bit_len_prev = if i == 0 { 0 } else { exp_bit_lens[i - 1] };
+            for (column, value) in [
+                (
+                    self.exp_byte_bit_len,
+                    F::from(8 - event.exponent[i].leading_zeros() as u64),
+                ),
+                (
+                    self.exp_bit_len_prev_inv,
+                    F::from(exp_bit_len_prev).invert().unwrap_or(F::zero()),
+                ),
+            ] {
+                region.assign_advice(
+                    || format!("modexp table row {row}"),
+                    column,
+                    row,
+                    || Value::known(value),
+                )?;
+            }
+
+            if is_limb_end {
+                for (eval, integer) in evals.iter_mut().zip(integers) {
+                    let limb = u64::from_be_bytes(
+                        integer[i + 1 - N_BYTES_LIMB..=i]
+                            .try_into()
+                            .expect("limb of 8 bytes"),
+                    );
+                    *eval = *eval * x + Value::known(F::from(limb));
+                }
+            }
+            for (column, eval) in [self.a_eval, self.modulus_eval, self.remainder_eval]
+                .into_iter()
+                .zip(evals)
+            {
+                region.assign_advice(
+                    || format!("modexp table eval row {row}"),
+                    column,
+                    row,
+                    || eval,
                 )?;
+            }
         }
-        Ok(calc_offset)
+
+        let tail = offset + MODEXP_SIZE_LIMIT - 1;
+        region.assign_advice(
+            || "modexp table modulus_eval_inv",
+            self.modulus_eval_inv,
+            tail,
+            || evals[1].map(|eval| eval.invert().unwrap_or(F::zero())),
+        )?;
+        region.assign_advice(
+            || "modexp table is_modulus_zero",
+            self.is_modulus_zero,
+            tail,
+            || Value::known(F::from(event.modulus.iter().all(|byte| *byte == 0) as u64)),
+        )?;
+        Ok(())
     }
 
-    fn assign_value<F: Field>(
+    fn assign_block<F: Field>(
         &self,
-        region: &mut Region<F>,
+        region: &mut Region<'_, F>,
         offset: usize,
-        col: Column<Advice>,
-        value: &Word,
-    ) -> Result<Number<F>, Error> {
-        let limbs_v = ModExpTable::split_u256_108bit_limbs(value);
-        let native_v = ModExpTable::native_u256(value);
-        let mut limbs = Vec::new();
-
-        for (i, limb) in limbs_v.into_iter().enumerate() {
-            let fv = F::from_u128(limb);
-            let c = region.assign_advice(
-                || "assign modexp limb",
-                col,
-                offset + i,
-                || Value::known(fv),
-            )?;
-            limbs.push(Limb::new(Some(c), fv));
+        block: &Block,
+        (is_first, is_last): (bool, bool),
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let (quotient, remainder) = if block.modulus.is_zero() {
+            (BigUint::zero(), BigUint::zero())
+        } else {
+            (&block.a * &block.b).div_rem(&block.modulus)
+        };
+        let [a, b, base, modulus, quotient, remainder] = [
+            &block.a,
+            &block.b,
+            &block.base,
+            &block.modulus,
+            &quotient,
+            &remainder,
+        ]
+        .map(limbs);
+        let carries = carries(&a, &b, &quotient, &modulus, &remainder);
+
+        // modulus - remainder - 1, with the borrows of the limbs
+        let mut diff = [0; N_LIMBS];
+        let mut borrows = [false; N_LIMBS];
+        if block.kind != BlockKind::Padding {
+            let mut borrow = 1;
+            for i in 0..N_LIMBS {
+                let value = modulus[i] as i128 - remainder[i] as i128 - borrow;
+                borrows[i] = value < 0;
+                diff[i] = (value + ((borrows[i] as i128) << 64)) as u64;
+                borrow = borrows[i] as i128;
+            }
+            debug_assert!(!borrows[N_LIMBS - 1], "remainder not below modulus");
         }
-        let c = region.assign_advice(
-            || "assign modexp native",
-            col,
-            offset + 3,
-            || Value::known(native_v),
-        )?;
-        limbs.push(Limb::new(Some(c), native_v));
-        Ok(Number {
-            limbs: limbs.try_into().expect("just 4 pushes"),
-        })
+
+        let x = challenges.evm_word();
+        let coeffs = |limbs: &[u64; N_LIMBS]| {
+            (0..BLOCK_ROWS)
+                .map(|i| F::from(limbs.get(i).copied().unwrap_or_default()))
+                .collect::<Vec<_>>()
+        };
+        let carry_coeffs = carries
+            .iter()
+            .map(|carry| {
+                F::from_u128((carry + CARRY_OFFSET as i128) as u128) - F::from_u128(CARRY_OFFSET)
+            })
+            .collect::<Vec<_>>();
+        let evals = [
+            (self.a_eval, horner_evals(&coeffs(&a), x)),
+            (self.b_eval, horner_evals(&coeffs(&b), x)),
+            (self.modulus_eval, horner_evals(&coeffs(&modulus), x)),
+            (self.quotient_eval, horner_evals(&coeffs(&quotient), x)),
+            (self.remainder_eval, horner_evals(&coeffs(&remainder), x)),
+            (self.carry_eval, horner_evals(&carry_coeffs, x)),
+        ];
+
+        for i in 0..BLOCK_ROWS {
+            let row = offset + i;
+            for (column, value) in [
+                (self.q_block, true),
+                (self.q_block_head, i == 0),
+                (self.q_block_last, i == BLOCK_ROWS - 1),
+                (self.q_limb, i < N_LIMBS),
+                (self.q_first_block, i == 0 && is_first),
+                (self.q_last_block, i == 0 && is_last),
+            ] {
+                region.assign_fixed(
+                    || format!("modexp block fixed row {row}"),
+                    column,
+                    row,
+                    || Value::known(F::from(value as u64)),
+                )?;
+            }
+
+            let limb = |limbs: &[u64; N_LIMBS]| limbs.get(i).copied().unwrap_or_default();
+            let mut values = self
+                .kinds
+                .iter()
+                .zip(BlockKind::COLUMNS)
+                .map(|(column, kind)| (*column, (block.kind == kind) as u64))
+                .chain([
+                    (self.a, limb(&a)),
+                    (self.base, limb(&base)),
+                    (self.modulus, limb(&modulus)),
+                    (
+                        self.borrow,
+                        borrows.get(i).copied().unwrap_or_default() as u64,
+                    ),
+                ])
+                .map(|(column, value)| (column, F::from(value)))
+                .collect::<Vec<_>>();
+            for (columns, value) in [
+                (&self.quotient, limb(&quotient)),
+                (&self.remainder, limb(&remainder)),
+                (&self.diff, limb(&diff)),
+            ] {
+                values.extend(
+                    columns
+                        .iter()
+                        .zip(u16_chunks::<N_CHUNKS_LIMB>(value as u128))
+                        .map(|(column, chunk)| (*column, F::from(chunk))),
+                );
+            }
+            values.extend(
+                self.carry
+                    .iter()
+                    .zip(u16_chunks::<N_CHUNKS_CARRY>(
+                        (carries[i] + CARRY_OFFSET as i128) as u128,
+                    ))
+                    .map(|(column, chunk)| (*column, F::from(chunk))),
+            );
+            if i == 0 {
+                let exp_bit = block.exp_bit;
+                let pow_minus_one = F::from(exp_bit.pow) - F::one();
+                let index_diff =
+                    F::from(exp_bit.index as u64) - F::from((MODEXP_SIZE_LIMIT - 1) as u64);
+                values.extend([
+                    (self.block_event_id, F::from(block.event_id as u64)),
+                    (self.block_exp_bit_len, F::from(block.exp_bit_len)),
+                    (self.exp_index, F::from(exp_bit.index as u64)),
+                    (self.exp_pow, F::from(exp_bit.pow)),
+                    (self.exp_byte, F::from(exp_bit.byte as u64)),
+                    (self.exp_rem, F::from(exp_bit.rem)),
+                    (self.exp_bit, F::from(exp_bit.bit as u64)),
+                    (
+                        self.exp_pow_inv,
+                        pow_minus_one.invert().unwrap_or(F::zero()),
+                    ),
+                    (self.is_byte_end, F::from((exp_bit.pow == 1) as u64)),
+                    (self.exp_index_inv, index_diff.invert().unwrap_or(F::zero())),
+                    (
+                        self.is_index_end,
+                        F::from((exp_bit.index == MODEXP_SIZE_LIMIT - 1) as u64),
+                    ),
+                ]);
+            }
+            for (column, value) in values {
+                region.assign_advice(
+                    || format!("modexp block row {row}"),
+                    column,
+                    row,
+                    || Value::known(value),
+                )?;
+            }
+            for (column, evals) in evals.iter() {
+                region.assign_advice(
+                    || format!("modexp block eval row {row}"),
+                    *column,
+                    row,
+                    || evals[i],
+                )?;
+            }
+        }
+        Ok(())
     }
 }
 
-const MODEXPCONFIG_EACH_CHIP_ROWS: usize = 24576;
-
 /// ModExp circuit for precompile modexp
 #[derive(Clone, Debug, Default)]
-pub struct ModExpCircuit<F: Field>(Vec<BigModExp>, std::marker::PhantomData<F>);
+pub struct ModExpCircuit<F: Field> {
+    /// Modexp events, padded to the capacity of the circuit
+    events: Vec<BigModExp>,
+    /// Number of rows of the circuit
+    max_rows: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> ModExpCircuit<F> {
+    /// Return a new ModExpCircuit of the rows, padding the events to its
+    /// capacity.
+    pub fn new(mut events: Vec<BigModExp>, max_rows: usize) -> Self {
+        let event_limit = max_rows / MODEXP_ROWS_PER_EVENT;
+        assert!(
+            events.len() <= event_limit,
+            "no enough rows for modexp circuit, expected {}, limit {}",
+            events.len(),
+            event_limit,
+        );
+        events.resize(event_limit, Default::default());
+        log::info!("modexp circuit work with maxium {} entries", event_limit);
+        Self {
+            events,
+            max_rows,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Modexp events of the circuit, padded to its capacity.
+    pub fn events(&self) -> &[BigModExp] {
+        &self.events
+    }
+
+    // First row of the blocks, after the table and its blank row.
+    fn blocks_offset(&self) -> usize {
+        self.events.len() * MODEXP_SIZE_LIMIT + 1
+    }
+
+    // Blocks of the events, padded to the rows of the circuit.
+    fn blocks(&self) -> Result<Vec<Block>, Error> {
+        let mut blocks = self
+            .events
+            .iter()
+            .enumerate()
+            .flat_map(|(n, event)| event_blocks(n + 1, event))
+            .collect::<Vec<_>>();
+        let block_limit = self.max_rows.saturating_sub(self.blocks_offset()) / BLOCK_ROWS;
+        if blocks.len() > block_limit {
+            log::error!(
+                "no enough rows for modexp circuit blocks, expected {}, limit {}",
+                blocks.len(),
+                block_limit,
+            );
+            return Err(Error::Synthesis);
+        }
+        blocks.resize(block_limit, Block::padding());
+        Ok(blocks)
+    }
+
+    /// Minimum rows of the layout of [`ModExpCircuit::new`] for the events:
+    /// the events are padded to one per [`MODEXP_ROWS_PER_EVENT`] rows, and
+    /// the table of the padded events followed by the blocks of the events
+    /// must fit in these rows.
+    fn min_rows(events: &[BigModExp]) -> usize {
+        if events.is_empty() {
+            return 0;
+        }
+        // With `n` padded events, the table and its blank row take
+        // `n * MODEXP_SIZE_LIMIT + 1` of the `n * MODEXP_ROWS_PER_EVENT` rows.
+        let block_rows = events.iter().map(num_blocks).sum::<usize>() * BLOCK_ROWS;
+        let free_rows_per_event = MODEXP_ROWS_PER_EVENT - MODEXP_SIZE_LIMIT;
+        let event_limit = events
+            .len()
+            .max((block_rows + 1 + free_rows_per_event - 1) / free_rows_per_event);
+        event_limit * MODEXP_ROWS_PER_EVENT
+    }
+}
 
 impl<F: Field> SubCircuit<F> for ModExpCircuit<F> {
     type Config = ModExpCircuitConfig;
 
     fn unusable_rows() -> usize {
-        // No column queried at more than 4 distinct rotations, so returns 8 as
-        // minimum unusable rows.
-        8
+        // Columns base, modulus and result of the modexp table are queried at
+        // 8 distinct rotations, so returns 11 unusable rows.
+        N_BYTES_LIMB + 3
     }
 
+    /// The modexp circuit shares the row budget of the keccak circuit, see
+    /// `block.circuits_params.max_keccak_rows`.
     fn new_from_block(block: &witness::Block<F>) -> Self {
-        let event_limit = block.circuits_params.max_keccak_rows / MODEXPCONFIG_EACH_CHIP_ROWS;
-        let mut exp_events = block.get_big_modexp();
-        assert!(
-            exp_events.len() <= event_limit,
-            "no enough rows for modexp circuit, expected {}, limit {}",
-            exp_events.len(),
-            event_limit,
-        );
-
-        exp_events.resize(event_limit, Default::default());
-        log::info!("modexp circuit work with maxium {} entries", event_limit);
-        Self(exp_events, Default::default())
+        Self::new(
+            block.get_big_modexp(),
+            block.circuits_params.max_keccak_rows,
+        )
     }
 
     fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
-        let exp_events = block.get_big_modexp();
-        (
-            exp_events.len() * MODEXPCONFIG_EACH_CHIP_ROWS,
-            (exp_events.len() * MODEXPCONFIG_EACH_CHIP_ROWS)
-                .max(block.circuits_params.max_keccak_rows),
-        )
+        let rows = Self::min_rows(&block.get_big_modexp());
+        (rows, rows.max(block.circuits_params.max_keccak_rows))
     }
 
     fn synthesize_sub(
         &self,
         config: &Self::Config,
-        _challenges: &Challenges<Value<F>>,
+        challenges: &Challenges<Value<F>>,
         layouter: &mut impl Layouter<F>,
     ) -> Result<(), Error> {
-        let modexp_chip = ModExpChip::new(config.modexp_config.clone());
-        let mut range_chip = RangeCheckChip::new(config.rangecheck_config.clone());
+        config.load_byte_table(layouter)?;
 
+        let blocks = self.blocks()?;
         layouter.assign_region(
             || "modexp circuit",
             |mut region| {
-                range_chip.initialize(&mut region)?;
-                let mut calc_offset = 0;
-                for (n, event) in self.0.iter().enumerate() {
-                    calc_offset = config.assign_group(
+                for (n, event) in self.events.iter().enumerate() {
+                    config.assign_table_event(
                         &mut region,
-                        n * 4,
-                        calc_offset,
+                        n * MODEXP_SIZE_LIMIT,
+                        n + 1,
                         event,
-                        &modexp_chip,
-                        &mut range_chip,
+                        challenges,
+                    )?;
+                }
+                config
+                    .modexp_table
+                    .assign_blank_row(&mut region, self.events.len() * MODEXP_SIZE_LIMIT)?;
+
+                let offset = self.blocks_offset();
+                for (i, block) in blocks.iter().enumerate() {
+                    config.assign_block(
+                        &mut region,
+                        offset + i * BLOCK_ROWS,
+                        block,
+                        (i == 0, i + 1 == blocks.len()),
+                        challenges,
                     )?;
                 }
                 Ok(())
             },
-        )?;
-
-        config.modexp_table.fill_blank(layouter)
+        )
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::util::MockChallenges;
+    use crate::util::{log2_ceil, MockChallenges};
+    use bus_mapping::circuit_input_builder::PrecompileEvent;
     use halo2_proofs::{
         circuit::SimpleFloorPlanner,
         dev::MockProver,
@@ -219,10 +1347,19 @@ mod test {
         }
 
         fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
-            let modexp_table = ModExpTable::construct(meta);
             let challenge = MockChallenges::construct(meta);
+            let challenges = challenge.exprs(meta);
+            let modexp_table = ModExpTable::construct(meta, &challenges);
+            let u16_table = U16Table::construct(meta);
             (
-                <ModExpCircuitConfig as SubCircuitConfig<Fr>>::new(meta, modexp_table),
+                <ModExpCircuitConfig as SubCircuitConfig<Fr>>::new(
+                    meta,
+                    ModExpCircuitConfigArgs {
+                        modexp_table,
+                        u16_table,
+                        challenges,
+                    },
+                ),
                 challenge,
             )
         }
@@ -233,57 +1370,93 @@ mod test {
             mut layouter: impl Layouter<Fr>,
         ) -> Result<(), Error> {
             let challenges = challenge.values(&layouter);
+            config.u16_table.load(&mut layouter)?;
             <Self as SubCircuit<Fr>>::synthesize_sub(self, &config, &challenges, &mut layouter)
         }
     }
 
+    fn modexp_event(base: &[u8], exponent: &[u8], modulus: &[u8]) -> BigModExp {
+        let [base_int, exponent_int, modulus_int] =
+            [base, exponent, modulus].map(BigUint::from_bytes_be);
+        let result = if modulus_int.is_zero() {
+            BigUint::zero()
+        } else {
+            base_int.modpow(&exponent_int, &modulus_int)
+        };
+        BigModExp::new(base, exponent, modulus, &result.to_bytes_be())
+    }
+
+    fn run(events: Vec<BigModExp>) -> Result<(), Vec<halo2_proofs::dev::VerifyFailure>> {
+        let test_circuit = ModExpCircuit::<Fr>::new(events, 1 << 16);
+        let prover = MockProver::run(17, &test_circuit, vec![]).unwrap();
+        prover.verify()
+    }
+
     #[test]
     fn test_modexp_circuit_00() {
-        let base = Word::from(1u128);
-        let exp = Word::from(2u128);
-        let modulus = Word::from(7u128);
-        let (_, result) = base.pow(exp).div_mod(modulus);
-        let event1 = BigModExp {
-            base,
-            exponent: exp,
-            modulus,
-            result,
-        };
-        let test_circuit = ModExpCircuit(vec![event1], Default::default());
-        let prover = MockProver::run(16, &test_circuit, vec![]).unwrap();
-        assert_eq!(prover.verify(), Ok(()));
+        let event = modexp_event(&[1], &[2], &[7]);
+        assert_eq!(run(vec![event]), Ok(()));
     }
 
     #[test]
     fn test_modexp_circuit_01() {
-        let base = Word::from(1u128);
-        let exp = Word::from(2u128);
-        let modulus = Word::from(7u128);
-        let (_, result) = base.pow(exp).div_mod(modulus);
-        let event1 = BigModExp {
-            base,
-            exponent: exp,
-            modulus,
-            result,
-        };
-        let test_circuit = ModExpCircuit(vec![event1], Default::default());
-        let prover = MockProver::run(16, &test_circuit, vec![]).unwrap();
-        assert_eq!(prover.verify(), Ok(()));
+        let events = vec![modexp_event(&[2], &[], &[7]), modexp_event(&[3], &[5], &[])];
+        assert_eq!(run(events), Ok(()));
     }
+
     #[test]
     fn test_modexp_circuit_02() {
-        let base = Word::from(2u128);
-        let exp = Word::from(2u128);
-        let modulus = Word::from(7u128);
-        let (_, result) = base.pow(exp).div_mod(modulus);
-        let event1 = BigModExp {
-            base,
-            exponent: exp,
-            modulus,
-            result,
-        };
-        let test_circuit = ModExpCircuit(vec![event1], Default::default());
-        let prover = MockProver::run(16, &test_circuit, vec![]).unwrap();
+        let base = [0xfe; 64];
+        let modulus = [0xab; 64];
+        let event = modexp_event(&base, &[0x01, 0x00, 0x01], &modulus);
+        assert_eq!(run(vec![event]), Ok(()));
+    }
+
+    #[test]
+    fn test_modexp_circuit_max_size() {
+        let base = [0x5a; MODEXP_SIZE_LIMIT];
+        let mut exponent = [0u8; MODEXP_SIZE_LIMIT];
+        exponent[MODEXP_SIZE_LIMIT - 2..].copy_from_slice(&[0x80, 0x03]);
+        let mut modulus = [0xff; MODEXP_SIZE_LIMIT];
+        modulus[MODEXP_SIZE_LIMIT - 1] = 0xfd;
+        let event = modexp_event(&base, &exponent, &modulus);
+        assert_eq!(run(vec![event]), Ok(()));
+    }
+
+    #[test]
+    fn test_modexp_circuit_min_num_rows_block() {
+        // The blocks of the long exponent need more rows than the budget of
+        // the two events.
+        let mut exponent = [0u8; MODEXP_SIZE_LIMIT];
+        exponent[MODEXP_SIZE_LIMIT - 24..].fill(0xff);
+        let events = vec![
+            modexp_event(&[0xfe; 64], &exponent, &[0xab; MODEXP_SIZE_LIMIT]),
+            modexp_event(&[2], &[], &[7]),
+        ];
+        let mut block = witness::Block::<Fr>::default();
+        block.precompile_events.events = events.into_iter().map(PrecompileEvent::ModExp).collect();
+
+        let (min_rows, max_rows) = ModExpCircuit::<Fr>::min_num_rows_block(&block);
+        assert_eq!(min_rows, max_rows);
+        block.circuits_params.max_keccak_rows = max_rows;
+        let circuit = ModExpCircuit::<Fr>::new_from_block(&block);
+        assert_eq!(circuit.events().len(), 3);
+
+        // The u16 table takes 2^16 rows.
+        let k = log2_ceil(max_rows.max(1 << 16) + ModExpCircuit::<Fr>::unusable_rows());
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
         assert_eq!(prover.verify(), Ok(()));
+
+        // The blocks don't fit in the rows of one event less.
+        let circuit =
+            ModExpCircuit::<Fr>::new(block.get_big_modexp(), max_rows - MODEXP_ROWS_PER_EVENT);
+        assert!(circuit.blocks().is_err());
+    }
+
+    #[test]
+    fn test_modexp_circuit_bad_result() {
+        let mut event = modexp_event(&[0x12, 0x34], &[0x03], &[0x56, 0x78]);
+        event.result[MODEXP_SIZE_LIMIT - 1] ^= 1;
+        assert!(run(vec![event]).is_err());
     }
 }
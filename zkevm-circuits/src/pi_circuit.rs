@@ -46,12 +46,15 @@ use once_cell::sync::Lazy;
 
 use crate::{
     evm_circuit::param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_U64, N_BYTES_WORD},
-    pi_circuit::param::{COINBASE_OFFSET, DIFFICULTY_OFFSET, NUM_ALL_TXS_OFFSET},
+    pi_circuit::param::{
+        BLOB_BASE_FEE_OFFSET, COINBASE_OFFSET, DIFFICULTY_OFFSET, EXCESS_BLOB_GAS_OFFSET,
+        HARDFORK_OFFSET, NUM_ALL_TXS_OFFSET,
+    },
     table::{
         BlockContextFieldTag,
         BlockContextFieldTag::{
-            BaseFee, ChainId, Coinbase, CumNumTxs, Difficulty, GasLimit, NumAllTxs, NumTxs, Number,
-            Timestamp,
+            BaseFee, BlobBaseFee, ChainId, Coinbase, CumNumTxs, Difficulty, ExcessBlobGas,
+            GasLimit, Hardfork, NumAllTxs, NumTxs, Number, Timestamp,
         },
     },
     util::rlc_be_bytes,
@@ -155,6 +158,9 @@ impl PublicData {
                     .chain(block.base_fee.to_be_bytes())
                     .chain(block.gas_limit.to_be_bytes())
                     .chain(num_all_txs.to_be_bytes())
+                    .chain(block.excess_blob_gas.to_be_bytes())
+                    .chain(block.blob_base_fee.to_be_bytes())
+                    .chain([block.hardfork as u8])
             }))
             // Tx Hashes
             .chain(
@@ -220,6 +226,9 @@ impl BlockContext {
             number: Default::default(),
             timestamp: Default::default(),
             base_fee: Default::default(),
+            excess_blob_gas: 0,
+            blob_base_fee: Default::default(),
+            hardfork: Default::default(),
             history_hashes: vec![],
             eth_block: Default::default(),
         }
@@ -718,6 +727,15 @@ impl<F: Field> PiCircuitConfig<F> {
                 (block.base_fee.to_be_bytes().to_vec(), BASE_FEE_OFFSET), // base_fee
                 (block.gas_limit.to_be_bytes().to_vec(), GAS_LIMIT_OFFSET), // gas_limit
                 (num_all_txs.to_be_bytes().to_vec(), NUM_ALL_TXS_OFFSET), // num_all_txs
+                (
+                    block.excess_blob_gas.to_be_bytes().to_vec(),
+                    EXCESS_BLOB_GAS_OFFSET,
+                ), // excess_blob_gas
+                (
+                    block.blob_base_fee.to_be_bytes().to_vec(),
+                    BLOB_BASE_FEE_OFFSET,
+                ), // blob_base_fee
+                (vec![block.hardfork as u8], HARDFORK_OFFSET),            // hardfork
             ];
             for (bytes, block_offset) in fields {
                 let cells = self.assign_field_in_pi(
@@ -1376,8 +1394,19 @@ impl<F: Field> PiCircuitConfig<F> {
                 .cloned()
                 .unwrap_or(0);
             let tag = [
-                Coinbase, Timestamp, Number, Difficulty, GasLimit, BaseFee, ChainId, NumTxs,
-                CumNumTxs, NumAllTxs,
+                Coinbase,
+                Timestamp,
+                Number,
+                Difficulty,
+                GasLimit,
+                BaseFee,
+                ChainId,
+                NumTxs,
+                CumNumTxs,
+                NumAllTxs,
+                ExcessBlobGas,
+                BlobBaseFee,
+                Hardfork,
             ];
 
             // index_cells of same block are equal to block_number.
@@ -0,0 +1,775 @@
+//! The RIPEMD-160 circuit implementation.
+//!
+//! Every 64 bytes block of the padded input takes `NUM_ROWS_PER_BLOCK` rows:
+//! - 5 rows loading the state, as the results of 5 steps before the first one
+//! - 80 rows doing one step of both lines each, where the first 16 rows absorb the input
+//! - 5 rows adding the results of both lines back to the state
+//!
+//! All words are decomposed into bits, so no lookup tables are needed.
+mod param;
+/// RIPEMD-160 witness generation
+pub mod ripemd160_bit;
+mod util;
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+mod dev;
+#[cfg(any(feature = "test", test))]
+mod test;
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+pub use dev::Ripemd160Circuit as TestRipemd160Circuit;
+
+use std::marker::PhantomData;
+
+use self::{
+    param::*,
+    ripemd160_bit::{get_num_blocks, multi_ripemd160, start_values, Ripemd160Row},
+    util::*,
+};
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        rlc,
+    },
+    table::{LookupTable, Ripemd160Table},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use eth_types::Field;
+use gadgets::util::{not, select, sum, Expr};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, VirtualCells},
+    poly::Rotation,
+};
+
+#[cfg(feature = "onephase")]
+use halo2_proofs::plonk::FirstPhase as SecondPhase;
+#[cfg(not(feature = "onephase"))]
+use halo2_proofs::plonk::SecondPhase;
+
+/// Ripemd160CircuitConfig
+#[derive(Clone, Debug)]
+pub struct Ripemd160CircuitConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    q_start: Column<Fixed>,
+    q_step: Column<Fixed>,
+    q_input: Column<Fixed>,
+    q_input_last: Column<Fixed>,
+    q_end: Column<Fixed>,
+    q_rounds: [Column<Fixed>; NUM_ROUNDS],
+    q_shifts_left: [Column<Fixed>; NUM_SHIFTS],
+    q_shifts_right: [Column<Fixed>; NUM_SHIFTS],
+    q_words_left: [Column<Fixed>; NUM_WORDS_TO_ABSORB],
+    q_words_right: [Column<Fixed>; NUM_WORDS_TO_ABSORB],
+    word_w: [Column<Advice>; NUM_BITS_PER_WORD],
+    sum_left: [Column<Advice>; NUM_BITS_PER_WORD_EXT],
+    sum_right: [Column<Advice>; NUM_BITS_PER_WORD_EXT],
+    word_left: [Column<Advice>; NUM_BITS_PER_WORD_EXT],
+    word_right: [Column<Advice>; NUM_BITS_PER_WORD_EXT],
+    message: [Column<Advice>; NUM_WORDS_TO_ABSORB],
+    is_paddings: [Column<Advice>; NUM_BYTES_PER_WORD],
+    data_rlcs: [Column<Advice>; NUM_BYTES_PER_WORD - 1],
+    /// The columns for other circuits to lookup RIPEMD-160 hash results
+    pub ripemd160_table: Ripemd160Table,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+#[derive(Debug, Clone)]
+pub struct Ripemd160CircuitConfigArgs<F: Field> {
+    /// Ripemd160Table
+    pub ripemd160_table: Ripemd160Table,
+    /// Challenges randomness
+    pub challenges: Challenges<Expression<F>>,
+}
+
+fn query_bits<F: Field>(
+    meta: &mut VirtualCells<'_, F>,
+    columns: &[Column<Advice>],
+    rot: i32,
+) -> Vec<Expression<F>> {
+    columns
+        .iter()
+        .map(|column| meta.query_advice(*column, Rotation(rot)))
+        .collect()
+}
+
+fn query_word<F: Field>(
+    meta: &mut VirtualCells<'_, F>,
+    columns: &[Column<Advice>],
+    rot: i32,
+) -> Vec<Expression<F>> {
+    query_bits(meta, &columns[..NUM_BITS_PER_WORD], rot)
+}
+
+/// Queries the state word `var` of a line whose last step result is at
+/// `rot_last`, see `ripemd160_bit::state_word`.
+fn query_state_word<F: Field>(
+    meta: &mut VirtualCells<'_, F>,
+    columns: &[Column<Advice>],
+    rot_last: i32,
+    var: usize,
+) -> Vec<Expression<F>> {
+    let idx = (var + NUM_STATE_WORDS - 1) % NUM_STATE_WORDS;
+    let word = query_word(meta, columns, rot_last - idx as i32);
+    if idx < NUM_STATE_WORDS - NUM_ROTATED_START_ROWS {
+        word
+    } else {
+        rotate::expr(&word, NUM_BITS_PER_WORD - ROTATE_STATE)
+    }
+}
+
+impl<F: Field> SubCircuitConfig<F> for Ripemd160CircuitConfig<F> {
+    type ConfigArgs = Ripemd160CircuitConfigArgs<F>;
+
+    /// Return a new Ripemd160CircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            ripemd160_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = ripemd160_table.q_enable;
+        let q_first = meta.fixed_column();
+        let q_start = meta.fixed_column();
+        let q_step = meta.fixed_column();
+        let q_input = meta.fixed_column();
+        let q_input_last = meta.fixed_column();
+        let q_end = meta.fixed_column();
+        let q_rounds = array_init::array_init(|_| meta.fixed_column());
+        let q_shifts_left = array_init::array_init(|_| meta.fixed_column());
+        let q_shifts_right = array_init::array_init(|_| meta.fixed_column());
+        let q_words_left = array_init::array_init(|_| meta.fixed_column());
+        let q_words_right = array_init::array_init(|_| meta.fixed_column());
+
+        let is_final = ripemd160_table.is_final;
+        let length = ripemd160_table.input_len;
+        let data_rlc = ripemd160_table.input_rlc;
+        let hash_rlc = ripemd160_table.output_rlc;
+
+        let word_w = array_init::array_init(|_| meta.advice_column());
+        let sum_left = array_init::array_init(|_| meta.advice_column());
+        let sum_right = array_init::array_init(|_| meta.advice_column());
+        let word_left = array_init::array_init(|_| meta.advice_column());
+        let word_right = array_init::array_init(|_| meta.advice_column());
+        let message = array_init::array_init(|_| meta.advice_column());
+        let is_paddings = array_init::array_init(|_| meta.advice_column());
+        let data_rlcs = array_init::array_init(|_| meta.advice_column_in(SecondPhase));
+
+        // The end gate is enabled on the first end row, the table values are
+        // on the last one.
+        let rot_end_last = NUM_END_ROWS as i32 - 1;
+
+        meta.create_gate("boolean checks", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in word_w
+                .iter()
+                .chain(sum_left.iter())
+                .chain(sum_right.iter())
+                .chain(word_left.iter())
+                .chain(word_right.iter())
+                .chain(is_paddings.iter())
+            {
+                cb.require_boolean(
+                    "bit is boolean",
+                    meta.query_advice(*column, Rotation::cur()),
+                );
+            }
+            let is_final = meta.query_advice(is_final, Rotation::cur());
+            cb.require_boolean("is_final is boolean", is_final.expr());
+            cb.condition(
+                not::expr(meta.query_fixed(q_end, Rotation(-rot_end_last))),
+                |cb| {
+                    cb.require_zero(
+                        "is_final can only be set on the last row of a block",
+                        is_final,
+                    );
+                },
+            );
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("message", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in message {
+                cb.require_equal(
+                    "message words are the same on all rows of a block",
+                    meta.query_advice(column, Rotation::cur()),
+                    meta.query_advice(column, Rotation::prev()),
+                );
+            }
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    - meta.query_fixed(q_start, Rotation::cur()),
+            )
+        });
+
+        meta.create_gate("step", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_rounds = q_rounds.map(|column| meta.query_fixed(column, Rotation::cur()));
+            let message = message.map(|column| meta.query_advice(column, Rotation::cur()));
+            for (sum_columns, word_columns, q_shifts, q_words, k, is_left) in [
+                (
+                    &sum_left,
+                    &word_left,
+                    &q_shifts_left,
+                    &q_words_left,
+                    K_LEFT,
+                    true,
+                ),
+                (
+                    &sum_right,
+                    &word_right,
+                    &q_shifts_right,
+                    &q_words_right,
+                    K_RIGHT,
+                    false,
+                ),
+            ] {
+                let [a, b, c, d, e] =
+                    [0, 1, 2, 3, 4].map(|var| query_state_word(meta, word_columns, -1, var));
+                let sum_bits = query_bits(meta, sum_columns, 0);
+                let new_word = query_bits(meta, word_columns, 0);
+
+                // The right line applies the round functions in reverse order
+                let f = sum::expr(q_rounds.iter().enumerate().map(|(round, q_round)| {
+                    let function = if is_left {
+                        round
+                    } else {
+                        NUM_ROUNDS - 1 - round
+                    };
+                    q_round.expr() * decode::expr(&round_function::expr(function, &b, &c, &d))
+                }));
+                let x = sum::expr(q_words.iter().zip(message.iter()).map(|(q_word, word)| {
+                    meta.query_fixed(*q_word, Rotation::cur()) * word.expr()
+                }));
+                let k = sum::expr(
+                    q_rounds
+                        .iter()
+                        .zip(k)
+                        .map(|(q_round, k)| q_round.expr() * k.expr()),
+                );
+                cb.require_equal(
+                    "sum = a + f(b, c, d) + x + k",
+                    decode::expr(&sum_bits),
+                    decode::expr(&a) + f + x + k,
+                );
+
+                let rotated_sum = sum::expr(q_shifts.iter().enumerate().map(|(idx, q_shift)| {
+                    meta.query_fixed(*q_shift, Rotation::cur())
+                        * decode::expr(&rotate::expr(
+                            &sum_bits,
+                            NUM_BITS_PER_WORD - (MIN_SHIFT + idx),
+                        ))
+                }));
+                cb.require_equal(
+                    "t = rol(sum, s) + e",
+                    decode::expr(&new_word),
+                    rotated_sum + decode::expr(&e),
+                );
+            }
+            cb.gate(meta.query_fixed(q_step, Rotation::cur()))
+        });
+
+        meta.create_gate("start", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_first = meta.query_fixed(q_first, Rotation::cur());
+            // A new hash starts on the first row or after a final block
+            let start_new_hash = select::expr(
+                q_first,
+                1.expr(),
+                meta.query_advice(is_final, Rotation::prev()),
+            );
+            let initial_state = start_values(&H);
+            for idx in 0..NUM_START_ROWS {
+                // The end rows hold the new state in the same order as the start
+                // rows, the first words are loaded rotated back.
+                let prev_state = query_word(meta, &word_left, idx as i32 - NUM_END_ROWS as i32);
+                let prev_state = if idx < NUM_ROTATED_START_ROWS {
+                    rotate::expr(&prev_state, ROTATE_STATE)
+                } else {
+                    prev_state
+                };
+                for columns in [&word_left, &word_right] {
+                    let state = query_bits(meta, columns, idx as i32);
+                    cb.require_equal(
+                        "state is initialized or carried over from the previous block",
+                        decode::expr(&state),
+                        select::expr(
+                            start_new_hash.expr(),
+                            initial_state[idx].expr(),
+                            decode::expr(&prev_state),
+                        ),
+                    );
+                }
+            }
+            for column in [length, data_rlc, is_paddings[NUM_BYTES_PER_WORD - 1]] {
+                cb.require_equal(
+                    "value is reset or carried over from the previous block",
+                    meta.query_advice(column, Rotation::cur()),
+                    select::expr(
+                        start_new_hash.expr(),
+                        0.expr(),
+                        meta.query_advice(column, Rotation::prev()),
+                    ),
+                );
+            }
+            cb.gate(meta.query_fixed(q_start, Rotation::cur()))
+        });
+
+        meta.create_gate("input", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_input_last = meta.query_fixed(q_input_last, Rotation::cur());
+            let w = query_bits(meta, &word_w, 0);
+            cb.require_equal(
+                "input word is the message word of the step",
+                decode::expr(&w),
+                sum::expr(
+                    q_words_left
+                        .iter()
+                        .zip(message.iter())
+                        .map(|(q_word, word)| {
+                            meta.query_fixed(*q_word, Rotation::cur())
+                                * meta.query_advice(*word, Rotation::cur())
+                        }),
+                ),
+            );
+            let bytes = to_le_bytes(&w);
+
+            let mut prev_is_padding =
+                meta.query_advice(is_paddings[NUM_BYTES_PER_WORD - 1], Rotation::prev());
+            let is_paddings = is_paddings.map(|column| meta.query_advice(column, Rotation::cur()));
+            let data_rlcs = data_rlcs
+                .iter()
+                .chain([data_rlc].iter())
+                .map(|column| meta.query_advice(*column, Rotation::cur()))
+                .collect::<Vec<_>>();
+            let mut prev_data_rlc = meta.query_advice(data_rlc, Rotation::prev());
+            for ((byte, is_padding), data_rlc) in
+                bytes.iter().zip(is_paddings.iter()).zip(data_rlcs.iter())
+            {
+                let is_first_padding = is_padding.expr() - prev_is_padding.expr();
+                cb.require_boolean("padding is monotonic", is_first_padding.expr());
+                // The last two words are checked at the end of the block, they
+                // contain the length in a final block.
+                cb.condition(is_padding.expr() * not::expr(q_input_last.expr()), |cb| {
+                    cb.require_equal(
+                        "padding byte is 0x80 followed by zeros",
+                        byte.expr(),
+                        is_first_padding.expr() * 0x80.expr(),
+                    );
+                });
+                cb.require_equal(
+                    "data_rlc is accumulated over the input bytes",
+                    data_rlc.expr(),
+                    select::expr(
+                        is_padding.expr(),
+                        prev_data_rlc.expr(),
+                        prev_data_rlc.expr() * challenges.keccak_input() + byte.expr(),
+                    ),
+                );
+                prev_is_padding = is_padding.expr();
+                prev_data_rlc = data_rlc.expr();
+            }
+            cb.require_equal(
+                "length is increased by the number of input bytes",
+                meta.query_advice(length, Rotation::cur()),
+                meta.query_advice(length, Rotation::prev())
+                    + sum::expr(
+                        is_paddings
+                            .iter()
+                            .map(|is_padding| not::expr(is_padding.expr())),
+                    ),
+            );
+            cb.gate(meta.query_fixed(q_input, Rotation::cur()))
+        });
+
+        meta.create_gate("carry over", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in [length, data_rlc, is_paddings[NUM_BYTES_PER_WORD - 1]] {
+                cb.require_equal(
+                    "value is carried over",
+                    meta.query_advice(column, Rotation::cur()),
+                    meta.query_advice(column, Rotation::prev()),
+                );
+            }
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    - meta.query_fixed(q_start, Rotation::cur())
+                    - meta.query_fixed(q_input, Rotation::cur()),
+            )
+        });
+
+        meta.create_gate("end", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let is_final = meta.query_advice(is_final, Rotation(rot_end_last));
+            let rot_start_last = -((NUM_STEPS + 1) as i32);
+            let rot_step_last = -1;
+            for idx in 0..NUM_END_ROWS {
+                let var = STATE_ROWS[idx];
+                let state = query_bits(meta, &word_left, idx as i32);
+                let h = query_state_word(
+                    meta,
+                    &word_left,
+                    rot_start_last,
+                    (var + 1) % NUM_STATE_WORDS,
+                );
+                let left =
+                    query_state_word(meta, &word_left, rot_step_last, (var + 2) % NUM_STATE_WORDS);
+                let right = query_state_word(
+                    meta,
+                    &word_right,
+                    rot_step_last,
+                    (var + 3) % NUM_STATE_WORDS,
+                );
+                cb.require_equal(
+                    "state is increased by the results of both lines",
+                    decode::expr(&state),
+                    decode::expr(&h) + decode::expr(&left) + decode::expr(&right),
+                );
+            }
+            // The digest is the new state in little-endian
+            let digest = STATE_ROWS
+                .iter()
+                .flat_map(|idx| to_le_bytes(&query_word(meta, &word_left, *idx as i32)))
+                .collect::<Vec<_>>();
+
+            // Padding checks for the last two input words
+            let rot_input_last = -((NUM_STEPS - NUM_WORDS_TO_ABSORB + 1) as i32);
+            let mut prev_is_padding = meta.query_advice(
+                is_paddings[NUM_BYTES_PER_WORD - 1],
+                Rotation(rot_input_last - 2),
+            );
+            cb.require_equal(
+                "is_final is set when the length fits in the last two words",
+                is_final.expr(),
+                prev_is_padding.expr(),
+            );
+            let mut length_words = vec![];
+            for rot in [rot_input_last - 1, rot_input_last] {
+                let w = query_word(meta, &word_w, rot);
+                for (byte, column) in to_le_bytes(&w).iter().zip(is_paddings.iter()) {
+                    let is_padding = meta.query_advice(*column, Rotation(rot));
+                    cb.condition(not::expr(is_final.expr()) * is_padding.expr(), |cb| {
+                        cb.require_equal(
+                            "padding byte is 0x80 followed by zeros",
+                            byte.expr(),
+                            (is_padding.expr() - prev_is_padding.expr()) * 0x80.expr(),
+                        );
+                    });
+                    prev_is_padding = is_padding;
+                }
+                length_words.push(decode::expr(&w));
+            }
+            cb.condition(is_final.expr(), |cb| {
+                cb.require_equal(
+                    "length low word is the length in bits",
+                    length_words[0].expr(),
+                    meta.query_advice(length, Rotation(rot_end_last)) * NUM_BITS_PER_BYTE.expr(),
+                );
+                cb.require_zero("length high word is zero", length_words[1].expr());
+                cb.require_equal(
+                    "hash_rlc is the RLC of the digest",
+                    meta.query_advice(hash_rlc, Rotation(rot_end_last)),
+                    rlc::expr(
+                        &digest.into_iter().rev().collect::<Vec<_>>(),
+                        challenges.keccak_input(),
+                    ),
+                );
+            });
+            cb.gate(meta.query_fixed(q_end, Rotation::cur()))
+        });
+
+        Ripemd160CircuitConfig {
+            q_enable,
+            q_first,
+            q_start,
+            q_step,
+            q_input,
+            q_input_last,
+            q_end,
+            q_rounds,
+            q_shifts_left,
+            q_shifts_right,
+            q_words_left,
+            q_words_right,
+            word_w,
+            sum_left,
+            sum_right,
+            word_left,
+            word_right,
+            message,
+            is_paddings,
+            data_rlcs,
+            ripemd160_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> Ripemd160CircuitConfig<F> {
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        witness: &[Ripemd160Row<F>],
+    ) -> Result<(), Error> {
+        let mut is_first_time = true;
+        layouter.assign_region(
+            || "assign ripemd160 rows",
+            |mut region| {
+                if is_first_time {
+                    is_first_time = false;
+                    if let Some(row) = witness.last() {
+                        self.set_row(&mut region, witness.len() - 1, row)?;
+                    }
+                    return Ok(());
+                }
+                for (offset, ripemd160_row) in witness.iter().enumerate() {
+                    self.set_row(&mut region, offset, ripemd160_row)?;
+                }
+                self.ripemd160_table.annotate_columns_in_region(&mut region);
+                self.annotate_circuit(&mut region);
+                Ok(())
+            },
+        )
+    }
+
+    /// Set the cells for a ripemd160 row; return the cells that are assigned.
+    pub fn set_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &Ripemd160Row<F>,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let idx = offset % NUM_ROWS_PER_BLOCK;
+        let step = idx
+            .checked_sub(NUM_START_ROWS)
+            .filter(|step| *step < NUM_STEPS);
+        let is_input = step.filter(|step| *step < NUM_WORDS_TO_ABSORB).is_some();
+
+        // Fixed selectors
+        for (name, column, value) in &[
+            ("q_enable", self.q_enable, true),
+            ("q_first", self.q_first, offset == 0),
+            ("q_start", self.q_start, idx == 0),
+            ("q_step", self.q_step, step.is_some()),
+            ("q_input", self.q_input, is_input),
+            (
+                "q_input_last",
+                self.q_input_last,
+                is_input && step.unwrap() + 2 >= NUM_WORDS_TO_ABSORB,
+            ),
+            ("q_end", self.q_end, idx == NUM_START_ROWS + NUM_STEPS),
+        ] {
+            region.assign_fixed(
+                || format!("assign {name} {offset}"),
+                *column,
+                offset,
+                || Value::known(F::from(*value)),
+            )?;
+        }
+
+        // One-hot selectors of the round, the rotations and the message words
+        for (name, columns, values) in [
+            ("q_round", self.q_rounds.as_slice(), None),
+            (
+                "q_shift_left",
+                self.q_shifts_left.as_slice(),
+                Some(S_LEFT.map(|shift| shift - MIN_SHIFT)),
+            ),
+            (
+                "q_shift_right",
+                self.q_shifts_right.as_slice(),
+                Some(S_RIGHT.map(|shift| shift - MIN_SHIFT)),
+            ),
+            ("q_word_left", self.q_words_left.as_slice(), Some(R_LEFT)),
+            ("q_word_right", self.q_words_right.as_slice(), Some(R_RIGHT)),
+        ] {
+            let selected = step.map(|step| match values {
+                Some(values) => values[step],
+                None => step / NUM_WORDS_TO_ABSORB,
+            });
+            for (idx, column) in columns.iter().enumerate() {
+                region.assign_fixed(
+                    || format!("assign {name} {idx} {offset}"),
+                    *column,
+                    offset,
+                    || Value::known(F::from(selected == Some(idx))),
+                )?;
+            }
+        }
+
+        // table values
+        let mut res = self.ripemd160_table.assign_row(
+            region,
+            offset,
+            [
+                Value::known(F::from(row.is_final)),
+                row.data_rlcs[NUM_BYTES_PER_WORD - 1],
+                Value::known(F::from(row.length as u64)),
+                row.hash_rlc,
+            ],
+        )?;
+
+        // Bits of the words
+        for (name, columns, bits) in [
+            ("w", self.word_w.as_slice(), row.w.as_slice()),
+            ("s_left", self.sum_left.as_slice(), row.s_left.as_slice()),
+            ("s_right", self.sum_right.as_slice(), row.s_right.as_slice()),
+            ("t_left", self.word_left.as_slice(), row.t_left.as_slice()),
+            (
+                "t_right",
+                self.word_right.as_slice(),
+                row.t_right.as_slice(),
+            ),
+        ] {
+            for (idx, (column, bit)) in columns.iter().zip(bits.iter()).enumerate() {
+                res.push(region.assign_advice(
+                    || format!("assign {name} bit {idx} {offset}"),
+                    *column,
+                    offset,
+                    || Value::known(F::from(*bit as u64)),
+                )?);
+            }
+        }
+
+        // Message words of the block
+        for (idx, (column, word)) in self.message.iter().zip(row.message.iter()).enumerate() {
+            res.push(region.assign_advice(
+                || format!("assign message {idx} {offset}"),
+                *column,
+                offset,
+                || Value::known(F::from(*word)),
+            )?);
+        }
+
+        // Padding flags and intermediate data rlcs
+        for (idx, (column, is_padding)) in self
+            .is_paddings
+            .iter()
+            .zip(row.is_paddings.iter())
+            .enumerate()
+        {
+            res.push(region.assign_advice(
+                || format!("assign is_padding {idx} {offset}"),
+                *column,
+                offset,
+                || Value::known(F::from(*is_padding)),
+            )?);
+        }
+        for (idx, (column, data_rlc)) in self.data_rlcs.iter().zip(row.data_rlcs.iter()).enumerate()
+        {
+            res.push(region.assign_advice(
+                || format!("assign data_rlc {idx} {offset}"),
+                *column,
+                offset,
+                || *data_rlc,
+            )?);
+        }
+
+        Ok(res)
+    }
+
+    /// Annotate the circuit
+    pub fn annotate_circuit(&self, region: &mut Region<F>) {
+        region.name_column(|| "RIPEMD160_q_first", self.q_first);
+        region.name_column(|| "RIPEMD160_q_start", self.q_start);
+        region.name_column(|| "RIPEMD160_q_step", self.q_step);
+        region.name_column(|| "RIPEMD160_q_input", self.q_input);
+        region.name_column(|| "RIPEMD160_q_input_last", self.q_input_last);
+        region.name_column(|| "RIPEMD160_q_end", self.q_end);
+    }
+}
+
+/// Ripemd160Circuit
+#[derive(Default, Clone, Debug)]
+pub struct Ripemd160Circuit<F: Field> {
+    // Each input row is a pre-image of the hash
+    inputs: Vec<Vec<u8>>,
+    // The maximum number of rows, for example, 2^20
+    // This needs to be large enough for the circuit.
+    num_rows: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> SubCircuit<F> for Ripemd160Circuit<F> {
+    type Config = Ripemd160CircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // Columns of word t_left are queried at 15 distinct rotations, so
+        // returns 18 unusable rows.
+        18
+    }
+
+    /// The ripemd160 circuit shares the row budget of the keccak circuit, see
+    /// `block.circuits_params.max_keccak_rows`.
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self::new(
+            block.circuits_params.max_keccak_rows,
+            block
+                .get_ripemd160()
+                .into_iter()
+                .map(|event| event.input)
+                .collect(),
+        )
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        let rows = block
+            .get_ripemd160()
+            .iter()
+            .map(|event| get_num_blocks(event.input.len()) * NUM_ROWS_PER_BLOCK)
+            .sum::<usize>();
+        (rows, rows.max(block.circuits_params.max_keccak_rows))
+    }
+
+    /// Make the assignments to the Ripemd160Circuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let witness = self.generate_witness(*challenges);
+        config.assign(layouter, witness.as_slice())
+    }
+}
+
+impl<F: Field> Ripemd160Circuit<F> {
+    /// Creates a new circuit instance
+    pub fn new(num_rows: usize, inputs: Vec<Vec<u8>>) -> Self {
+        Ripemd160Circuit {
+            inputs,
+            num_rows,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The number of ripemd160 blocks that can be compressed in this circuit
+    pub fn capacity(&self) -> Option<usize> {
+        Self::capacity_for_row(self.num_rows)
+    }
+
+    /// The number of ripemd160 blocks that can be compressed for a particular
+    /// row number
+    pub fn capacity_for_row(num_rows: usize) -> Option<usize> {
+        if num_rows > 0 {
+            Some(num_rows.saturating_sub(Self::unusable_rows()) / NUM_ROWS_PER_BLOCK)
+        } else {
+            None
+        }
+    }
+
+    /// Sets the witness using the data to be hashed
+    pub(crate) fn generate_witness(
+        &self,
+        challenges: Challenges<Value<F>>,
+    ) -> Vec<Ripemd160Row<F>> {
+        multi_ripemd160(self.inputs.as_slice(), challenges, self.capacity())
+            .expect("Too many inputs for given capacity")
+    }
+}
@@ -0,0 +1,70 @@
+pub(crate) const MAX_DEGREE: usize = 5;
+
+pub(crate) const NUM_BITS_PER_BYTE: usize = 8;
+pub(crate) const NUM_BYTES_PER_WORD: usize = 4;
+pub(crate) const NUM_BITS_PER_WORD: usize = NUM_BYTES_PER_WORD * NUM_BITS_PER_BYTE;
+// The step sum and the new state are the sum of at most 4 words, so they need
+// 2 carry bits.
+pub(crate) const NUM_BITS_PER_WORD_EXT: usize = NUM_BITS_PER_WORD + 2;
+pub(crate) const NUM_WORDS_TO_ABSORB: usize = 16;
+pub(crate) const RATE: usize = NUM_WORDS_TO_ABSORB * NUM_BYTES_PER_WORD;
+pub(crate) const NUM_ROUNDS: usize = 5;
+pub(crate) const NUM_STEPS: usize = NUM_ROUNDS * NUM_WORDS_TO_ABSORB;
+// The state has 5 words, each line needs the results of its last 5 steps.
+pub(crate) const NUM_STATE_WORDS: usize = 5;
+// 5 rows to load the state, one row per step and 5 rows to add the state back.
+pub(crate) const NUM_START_ROWS: usize = NUM_STATE_WORDS;
+pub(crate) const NUM_END_ROWS: usize = NUM_STATE_WORDS;
+pub(crate) const NUM_ROWS_PER_BLOCK: usize = NUM_START_ROWS + NUM_STEPS + NUM_END_ROWS;
+// The bit length of the message is encoded in the last 2 words of the final block.
+pub(crate) const NUM_BYTES_LENGTH: usize = 2 * NUM_BYTES_PER_WORD;
+// The distinct rotation amounts used by the steps, from 5 to 15.
+pub(crate) const MIN_SHIFT: usize = 5;
+pub(crate) const NUM_SHIFTS: usize = 11;
+// The rotation applied to a word when it moves from `c` to `d`.
+pub(crate) const ROTATE_STATE: usize = 10;
+// The state word loaded on each start row and computed on each end row, so
+// that the start rows hold the results of the 5 steps before the first one.
+// The words on the first 3 start rows are rotated right by `ROTATE_STATE`.
+pub(crate) const STATE_ROWS: [usize; NUM_STATE_WORDS] = [0, 4, 3, 2, 1];
+pub(crate) const NUM_ROTATED_START_ROWS: usize = 3;
+
+/// Initial hash values
+pub(crate) const H: [u64; NUM_STATE_WORDS] =
+    [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+
+/// Round constants of the left line
+pub(crate) const K_LEFT: [u64; NUM_ROUNDS] =
+    [0x00000000, 0x5a827999, 0x6ed9eba1, 0x8f1bbcdc, 0xa953fd4e];
+
+/// Round constants of the right line
+pub(crate) const K_RIGHT: [u64; NUM_ROUNDS] =
+    [0x50a28be6, 0x5c4dd124, 0x6d703ef3, 0x7a6d76e9, 0x00000000];
+
+/// Message word selected by each step of the left line
+pub(crate) const R_LEFT: [usize; NUM_STEPS] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 7, 4, 13, 1, 10, 6, 15, 3, 12, 0, 9, 5,
+    2, 14, 11, 8, 3, 10, 14, 4, 9, 15, 8, 1, 2, 7, 0, 6, 13, 11, 5, 12, 1, 9, 11, 10, 0, 8, 12, 4,
+    13, 3, 7, 15, 14, 5, 6, 2, 4, 0, 5, 9, 7, 12, 2, 10, 14, 1, 3, 8, 11, 6, 15, 13,
+];
+
+/// Message word selected by each step of the right line
+pub(crate) const R_RIGHT: [usize; NUM_STEPS] = [
+    5, 14, 7, 0, 9, 2, 11, 4, 13, 6, 15, 8, 1, 10, 3, 12, 6, 11, 3, 7, 0, 13, 5, 10, 14, 15, 8, 12,
+    4, 9, 1, 2, 15, 5, 1, 3, 7, 14, 6, 9, 11, 8, 12, 2, 10, 0, 4, 13, 8, 6, 4, 1, 3, 11, 15, 0, 5,
+    12, 2, 13, 9, 7, 10, 14, 12, 15, 10, 4, 1, 5, 8, 7, 6, 2, 13, 14, 0, 3, 9, 11,
+];
+
+/// Left rotation applied by each step of the left line
+pub(crate) const S_LEFT: [usize; NUM_STEPS] = [
+    11, 14, 15, 12, 5, 8, 7, 9, 11, 13, 14, 15, 6, 7, 9, 8, 7, 6, 8, 13, 11, 9, 7, 15, 7, 12, 15,
+    9, 11, 7, 13, 12, 11, 13, 6, 7, 14, 9, 13, 15, 14, 8, 13, 6, 5, 12, 7, 5, 11, 12, 14, 15, 14,
+    15, 9, 8, 9, 14, 5, 6, 8, 6, 5, 12, 9, 15, 5, 11, 6, 8, 13, 12, 5, 12, 13, 14, 11, 8, 5, 6,
+];
+
+/// Left rotation applied by each step of the right line
+pub(crate) const S_RIGHT: [usize; NUM_STEPS] = [
+    8, 9, 9, 11, 13, 15, 15, 5, 7, 7, 8, 11, 14, 14, 12, 6, 9, 13, 15, 7, 12, 8, 9, 11, 7, 7, 12,
+    7, 6, 15, 13, 11, 9, 7, 15, 11, 8, 6, 6, 14, 12, 13, 5, 14, 13, 13, 7, 5, 15, 5, 8, 11, 14, 14,
+    6, 14, 6, 9, 12, 9, 12, 5, 15, 8, 8, 5, 12, 9, 12, 5, 14, 6, 8, 13, 6, 5, 15, 13, 11, 11,
+];
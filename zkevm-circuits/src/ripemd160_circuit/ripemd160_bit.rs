@@ -0,0 +1,238 @@
+use super::{param::*, util::*};
+use crate::{evm_circuit::util::rlc, util::Challenges};
+use eth_types::Field;
+use halo2_proofs::{circuit::Value, plonk::Error};
+use log::debug;
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+
+const WORD_MASK: u64 = (1 << NUM_BITS_PER_WORD) - 1;
+
+/// Ripemd160Row
+#[derive(Clone, Debug)]
+pub struct Ripemd160Row<F> {
+    pub(crate) w: [u8; NUM_BITS_PER_WORD],
+    pub(crate) s_left: [u8; NUM_BITS_PER_WORD_EXT],
+    pub(crate) s_right: [u8; NUM_BITS_PER_WORD_EXT],
+    pub(crate) t_left: [u8; NUM_BITS_PER_WORD_EXT],
+    pub(crate) t_right: [u8; NUM_BITS_PER_WORD_EXT],
+    pub(crate) message: [u64; NUM_WORDS_TO_ABSORB],
+    pub(crate) is_paddings: [bool; NUM_BYTES_PER_WORD],
+    /// The intermediate input RLCs after each byte, the last one is the input
+    /// RLC of the row
+    pub(crate) data_rlcs: [Value<F>; NUM_BYTES_PER_WORD],
+    /// if the row is the last row of the hash
+    pub is_final: bool,
+    /// The input length of the hash function
+    pub length: usize,
+    pub(crate) hash_rlc: Value<F>,
+}
+
+fn rotl(x: u64, n: usize) -> u64 {
+    (x as u32).rotate_left(n as u32) as u64
+}
+
+fn rotr(x: u64, n: usize) -> u64 {
+    (x as u32).rotate_right(n as u32) as u64
+}
+
+/// Pads the input with 0x80, zeros and the little-endian bit length
+fn pad(bytes: &[u8]) -> Vec<u8> {
+    let mut padded = bytes.to_vec();
+    padded.push(0x80);
+    while padded.len() % RATE != RATE - NUM_BYTES_LENGTH {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&((bytes.len() as u64) * NUM_BITS_PER_BYTE as u64).to_le_bytes());
+    padded
+}
+
+/// The number of blocks needed to hash an input of `len` bytes
+pub(crate) fn get_num_blocks(len: usize) -> usize {
+    (len + 1 + NUM_BYTES_LENGTH + RATE - 1) / RATE
+}
+
+/// The state of one line, stored as the results of the last 5 steps so that
+/// `[a, b, c, d, e] = [rotl10(t[-5]), t[-1], t[-2], rotl10(t[-3]), rotl10(t[-4])]`
+fn state_word(results: &[u64], var: usize) -> u64 {
+    let idx = (var + NUM_STATE_WORDS - 1) % NUM_STATE_WORDS;
+    let result = results[results.len() - 1 - idx];
+    if idx < NUM_STATE_WORDS - NUM_ROTATED_START_ROWS {
+        result
+    } else {
+        rotl(result, ROTATE_STATE)
+    }
+}
+
+/// Returns the start row values for the state `hs`
+pub(crate) fn start_values(hs: &[u64; NUM_STATE_WORDS]) -> [u64; NUM_START_ROWS] {
+    array_init::array_init(|idx| {
+        let h = hs[STATE_ROWS[idx]];
+        if idx < NUM_ROTATED_START_ROWS {
+            rotr(h, ROTATE_STATE)
+        } else {
+            h
+        }
+    })
+}
+
+fn ripemd160<F: Field>(
+    rows: &mut Vec<Ripemd160Row<F>>,
+    bytes: &[u8],
+    challenges: Challenges<Value<F>>,
+) {
+    let padded = pad(bytes);
+    let num_blocks = padded.len() / RATE;
+
+    let mut hs = H;
+    let mut length = 0usize;
+    let mut data_rlc = Value::known(F::zero());
+    let mut is_padding = false;
+    for (block_idx, block) in padded.chunks(RATE).enumerate() {
+        let is_final = block_idx == num_blocks - 1;
+        let message: [u64; NUM_WORDS_TO_ABSORB] = array_init::array_init(|idx| {
+            u32::from_le_bytes(
+                block[idx * NUM_BYTES_PER_WORD..(idx + 1) * NUM_BYTES_PER_WORD]
+                    .try_into()
+                    .unwrap(),
+            ) as u64
+        });
+        let non_input_row =
+            |t_left: u64, t_right: u64, length, data_rlc, is_padding| Ripemd160Row {
+                w: [0; NUM_BITS_PER_WORD],
+                s_left: [0; NUM_BITS_PER_WORD_EXT],
+                s_right: [0; NUM_BITS_PER_WORD_EXT],
+                t_left: into_bits(t_left),
+                t_right: into_bits(t_right),
+                message,
+                is_paddings: [is_padding; NUM_BYTES_PER_WORD],
+                data_rlcs: [data_rlc; NUM_BYTES_PER_WORD],
+                is_final: false,
+                length,
+                hash_rlc: Value::known(F::zero()),
+            };
+
+        // Load the state as the results of the previous 5 steps of both lines
+        let mut results_left = start_values(&hs).to_vec();
+        let mut results_right = results_left.clone();
+        for value in results_left.iter() {
+            rows.push(non_input_row(*value, *value, length, data_rlc, is_padding));
+        }
+
+        for step in 0..NUM_STEPS {
+            let round = step / NUM_WORDS_TO_ABSORB;
+            let mut sums = [0; 2];
+            let mut results = [0; 2];
+            // The right line applies the round functions in reverse order
+            for (line, (line_results, function, k, r, s)) in [
+                (&mut results_left, round, K_LEFT[round], R_LEFT, S_LEFT),
+                (
+                    &mut results_right,
+                    NUM_ROUNDS - 1 - round,
+                    K_RIGHT[round],
+                    R_RIGHT,
+                    S_RIGHT,
+                ),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                let [a, b, c, d, e] = [0, 1, 2, 3, 4].map(|var| state_word(line_results, var));
+                sums[line] = a + round_function::value(function, b, c, d) + message[r[step]] + k;
+                results[line] = rotl(sums[line] & WORD_MASK, s[step]) + e;
+                line_results.push(results[line] & WORD_MASK);
+            }
+
+            let mut row = non_input_row(results[0], results[1], length, data_rlc, is_padding);
+            row.s_left = into_bits(sums[0]);
+            row.s_right = into_bits(sums[1]);
+            if step < NUM_WORDS_TO_ABSORB {
+                row.w = into_bits(message[step]);
+                for idx in 0..NUM_BYTES_PER_WORD {
+                    let byte_idx = block_idx * RATE + step * NUM_BYTES_PER_WORD + idx;
+                    is_padding = byte_idx >= bytes.len();
+                    if !is_padding {
+                        length += 1;
+                        data_rlc = data_rlc
+                            .zip(challenges.keccak_input())
+                            .map(|(data_rlc, r)| data_rlc * r + F::from(bytes[byte_idx] as u64));
+                    }
+                    row.is_paddings[idx] = is_padding;
+                    row.data_rlcs[idx] = data_rlc;
+                }
+                row.length = length;
+            }
+            rows.push(row);
+        }
+
+        // Add the compressed block to the state, in the order of the start rows
+        let hs_ext: [u64; NUM_STATE_WORDS] = array_init::array_init(|idx| {
+            hs[(idx + 1) % NUM_STATE_WORDS]
+                + state_word(&results_left, (idx + 2) % NUM_STATE_WORDS)
+                + state_word(&results_right, (idx + 3) % NUM_STATE_WORDS)
+        });
+        for idx in 0..NUM_END_ROWS {
+            rows.push(non_input_row(
+                hs_ext[STATE_ROWS[idx]],
+                0,
+                length,
+                data_rlc,
+                is_padding,
+            ));
+        }
+        hs = hs_ext.map(|h| h & WORD_MASK);
+
+        if is_final {
+            let digest = hs
+                .iter()
+                .flat_map(|h| (*h as u32).to_le_bytes())
+                .collect::<Vec<_>>();
+            let last_row = rows.last_mut().unwrap();
+            last_row.is_final = true;
+            last_row.hash_rlc = challenges
+                .keccak_input()
+                .map(|r| rlc::value(digest.iter().rev(), r));
+            debug!("ripemd160 digest: {:x?}", digest);
+        }
+    }
+}
+
+/// Witness generation for multiple ripemd160 hashes
+pub fn multi_ripemd160<F: Field>(
+    bytes: &[Vec<u8>],
+    challenges: Challenges<Value<F>>,
+    capacity: Option<usize>,
+) -> Result<Vec<Ripemd160Row<F>>, Error> {
+    log::info!("multi_ripemd160 assign with capacity: {:?}", capacity);
+    let mut rows: Vec<Ripemd160Row<F>> = Vec::new();
+    if let Some(capacity) = capacity {
+        rows.reserve(capacity * NUM_ROWS_PER_BLOCK);
+    }
+
+    let real_rows: Vec<_> = bytes
+        .par_iter()
+        .flat_map_iter(|bytes| {
+            let mut rows = Vec::new();
+            ripemd160(&mut rows, bytes, challenges);
+            rows
+        })
+        .collect();
+    rows.extend(real_rows.into_iter());
+    debug!("ripemd160 rows len without padding: {}", rows.len());
+    if let Some(capacity) = capacity {
+        let padding_rows = {
+            let mut rows = Vec::new();
+            ripemd160(&mut rows, &[], challenges);
+            rows
+        };
+        // Pad with no data hashes to the expected capacity
+        while rows.len() < capacity * NUM_ROWS_PER_BLOCK {
+            rows.extend(padding_rows.clone());
+        }
+        // Check that we are not over capacity
+        if rows.len() > capacity * NUM_ROWS_PER_BLOCK {
+            return Err(Error::BoundsFailure);
+        }
+    }
+    debug!("ripemd160 witgen done");
+    Ok(rows)
+}
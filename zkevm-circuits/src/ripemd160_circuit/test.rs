@@ -0,0 +1,85 @@
+use super::*;
+use crate::util::unusable_rows;
+use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
+use log::error;
+use ripemd::{Digest, Ripemd160};
+
+const K: u32 = 12;
+
+#[test]
+fn ripemd160_circuit_unusable_rows() {
+    assert_eq!(
+        Ripemd160Circuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, Ripemd160Circuit::<Fr>>(),
+    )
+}
+
+fn verify<F: Field>(k: u32, inputs: Vec<Vec<u8>>, success: bool) {
+    let circuit = Ripemd160Circuit::new(2usize.pow(k), inputs);
+
+    let prover = MockProver::<F>::run(k, &circuit, vec![]).unwrap();
+    let verify_result = prover.verify();
+    if verify_result.is_ok() != success {
+        if let Some(errors) = verify_result.err() {
+            for error in errors.iter() {
+                error!("{}", error);
+            }
+        }
+        panic!();
+    }
+}
+
+#[test]
+fn ripemd160_circuit_simple() {
+    let inputs = vec![
+        vec![],
+        (0u8..1).collect::<Vec<_>>(),
+        (0u8..55).collect::<Vec<_>>(),
+        (0u8..56).collect::<Vec<_>>(),
+        (0u8..64).collect::<Vec<_>>(),
+        (0u8..200).collect::<Vec<_>>(),
+    ];
+    verify::<Fr>(K, inputs, true);
+}
+
+fn mock_challenges() -> Challenges<Value<Fr>> {
+    Challenges::mock(
+        Value::known(Fr::from(0x100)),
+        Value::known(Fr::from(0x100)),
+        Value::known(Fr::from(0x100)),
+    )
+}
+
+#[test]
+fn ripemd160_circuit_capacity() {
+    let challenges = mock_challenges();
+    // 120 bytes need 3 blocks once padded
+    let inputs = vec![(0u8..120).collect::<Vec<_>>()];
+    assert!(multi_ripemd160::<Fr>(&inputs, challenges, Some(2)).is_err());
+    assert_eq!(
+        multi_ripemd160::<Fr>(&inputs, challenges, Some(4))
+            .unwrap()
+            .len(),
+        4 * NUM_ROWS_PER_BLOCK
+    );
+}
+
+#[test]
+fn ripemd160_witness_digest() {
+    let challenges = mock_challenges();
+    for len in [0, 1, 55, 56, 63, 64, 65, 119, 120, 200] {
+        let input = (0..len).map(|idx| idx as u8).collect::<Vec<_>>();
+        let rows = multi_ripemd160::<Fr>(&[input.clone()], challenges, None).unwrap();
+        assert_eq!(rows.len(), get_num_blocks(len) * NUM_ROWS_PER_BLOCK);
+
+        let last_row = rows.last().unwrap();
+        assert!(last_row.is_final);
+        assert_eq!(last_row.length, len);
+
+        let digest = Ripemd160::digest(&input);
+        last_row
+            .hash_rlc
+            .zip(challenges.keccak_input())
+            .assert_if_known(|(hash_rlc, r)| *hash_rlc == rlc::value(digest.iter().rev(), *r));
+    }
+}
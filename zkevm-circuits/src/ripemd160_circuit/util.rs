@@ -0,0 +1,97 @@
+use super::param::*;
+use eth_types::Field;
+use halo2_proofs::plonk::Expression;
+
+/// Decodes little-endian bits into a value
+pub(crate) mod decode {
+    use crate::util::Expr;
+    use eth_types::Field;
+    use halo2_proofs::plonk::Expression;
+
+    pub(crate) fn expr<F: Field>(bits: &[Expression<F>]) -> Expression<F> {
+        let mut value = 0.expr();
+        let mut multiplier = F::one();
+        for bit in bits.iter() {
+            value = value + bit.expr() * multiplier;
+            multiplier *= F::from(2);
+        }
+        value
+    }
+}
+
+/// Rotates the bits of a word to the right
+pub(crate) mod rotate {
+    use super::NUM_BITS_PER_WORD;
+    use eth_types::Field;
+    use halo2_proofs::plonk::Expression;
+
+    pub(crate) fn expr<F: Field>(bits: &[Expression<F>], count: usize) -> Vec<Expression<F>> {
+        (0..NUM_BITS_PER_WORD)
+            .map(|idx| bits[(idx + count) % NUM_BITS_PER_WORD].clone())
+            .collect()
+    }
+}
+
+/// The boolean function of each round, applied bitwise to `x`, `y` and `z`:
+/// - `x ^ y ^ z`
+/// - `(x & y) | (!x & z)`
+/// - `(x | !y) ^ z`
+/// - `(x & z) | (y & !z)`
+/// - `x ^ (y | !z)`
+pub(crate) mod round_function {
+    use super::{NUM_BITS_PER_WORD, NUM_ROUNDS};
+    use crate::util::Expr;
+    use eth_types::Field;
+    use halo2_proofs::plonk::Expression;
+
+    fn xor<F: Field>(a: Expression<F>, b: Expression<F>) -> Expression<F> {
+        a.clone() + b.clone() - 2.expr() * a * b
+    }
+
+    pub(crate) fn expr<F: Field>(
+        round: usize,
+        x: &[Expression<F>],
+        y: &[Expression<F>],
+        z: &[Expression<F>],
+    ) -> Vec<Expression<F>> {
+        assert!(round < NUM_ROUNDS);
+        x.iter()
+            .zip(y.iter())
+            .zip(z.iter())
+            .map(|((x, y), z)| {
+                let (x, y, z) = (x.clone(), y.clone(), z.clone());
+                match round {
+                    0 => xor(xor(x, y), z),
+                    1 => x.clone() * y + (1.expr() - x) * z,
+                    2 => xor(1.expr() - y.clone() + x * y, z),
+                    3 => x * z.clone() + y * (1.expr() - z),
+                    _ => xor(x, 1.expr() - z.clone() + y * z),
+                }
+            })
+            .collect()
+    }
+
+    pub(crate) fn value(round: usize, x: u64, y: u64, z: u64) -> u64 {
+        let mask = (1 << NUM_BITS_PER_WORD) - 1;
+        match round {
+            0 => x ^ y ^ z,
+            1 => (x & y) | (!x & z & mask),
+            2 => ((x | !y) & mask) ^ z,
+            3 => (x & z) | (y & !z & mask),
+            _ => x ^ ((y | !z) & mask),
+        }
+    }
+}
+
+/// Returns the little-endian bytes of a word given its little-endian bits
+pub(crate) fn to_le_bytes<F: Field>(bits: &[Expression<F>]) -> Vec<Expression<F>> {
+    bits[..NUM_BITS_PER_WORD]
+        .chunks(NUM_BITS_PER_BYTE)
+        .map(decode::expr)
+        .collect()
+}
+
+/// Returns the little-endian bits of a value
+pub(crate) fn into_bits<const N: usize>(value: u64) -> [u8; N] {
+    array_init::array_init(|idx| ((value >> idx) & 1) as u8)
+}
@@ -197,7 +197,7 @@ pub struct RlpCircuitConfig<F> {
     /// The tag, i.e. what field is being decoded at the current row.
     tag: Column<Advice>,
     /// A utility gadget to compare/query what tag we are at.
-    tag_bits: BinaryNumberConfig<Tag, 5>,
+    tag_bits: BinaryNumberConfig<Tag, 6>,
     /// The tag that will be decoded next after the current tag is done decoding.
     tag_next: Column<Advice>,
     /// Boolean check whether or not the current tag represents a list or not.
@@ -237,7 +237,7 @@ pub struct RlpCircuitConfig<F> {
     /// Check equality between format' and format in sm.
     format_check_in_sm: IsEqualConfig<F>,
 
-    /// Booleans to reduce the circuit's degree as tag_bits's degree is 5.
+    /// Booleans to reduce the circuit's degree as tag_bits's degree is 6.
     is_tag_end: Column<Advice>,
     is_tag_begin: Column<Advice>,
     /// Boolean to reduce the circuit's degree
@@ -248,6 +248,12 @@ pub struct RlpCircuitConfig<F> {
     transit_to_new_rlp_instance: Column<Advice>,
     /// Boolean to reduce the circuit's degree
     is_same_rlp_instance: Column<Advice>,
+    /// Booleans to count the access list entries, the blob versioned hashes and
+    /// the authorizations as tag_bits's degree is 6.
+    is_tag_access_list_address: Column<Advice>,
+    is_tag_access_list_storage_key: Column<Advice>,
+    is_tag_blob_versioned_hash: Column<Advice>,
+    is_tag_auth_chain_id: Column<Advice>,
 
     /// Check for byte_value <= 0x80
     byte_value_lte_0x80: ComparatorConfig<F, 1>,
@@ -319,6 +325,10 @@ impl<F: Field> RlpCircuitConfig<F> {
             is_case3,
             transit_to_new_rlp_instance,
             is_same_rlp_instance,
+            is_tag_access_list_address,
+            is_tag_access_list_storage_key,
+            is_tag_blob_versioned_hash,
+            is_tag_auth_chain_id,
         ) = (
             meta.fixed_column(),
             meta.fixed_column(),
@@ -338,6 +348,10 @@ impl<F: Field> RlpCircuitConfig<F> {
             meta.advice_column(),
             meta.advice_column(),
             meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
         );
 
         let tag_value_acc = meta.advice_column_in(SecondPhase);
@@ -385,6 +399,10 @@ impl<F: Field> RlpCircuitConfig<F> {
         is_tag!(is_tag_begin_vector, BeginVector);
         is_tag!(is_tag_end_list, EndList);
         is_tag!(is_tag_end_vector, EndVector);
+        is_tag!(is_tag_access_list_address_expr, AccessListAddress);
+        is_tag!(is_tag_access_list_storage_key_expr, AccessListStorageKey);
+        is_tag!(is_tag_blob_versioned_hash_expr, BlobVersionedHash);
+        is_tag!(is_tag_auth_chain_id_expr, AuthChainId);
 
         //////////////////////////////////////////////////////////
         //////////// data table checks. //////////////////////////
@@ -802,6 +820,26 @@ impl<F: Field> RlpCircuitConfig<F> {
                 meta.query_advice(is_tag_begin, Rotation::cur()),
                 sum::expr([is_tag_begin_list(meta), is_tag_begin_vector(meta)]),
             );
+            cb.require_equal(
+                "is_tag_access_list_address = (tag == AccessListAddress)",
+                meta.query_advice(is_tag_access_list_address, Rotation::cur()),
+                is_tag_access_list_address_expr(meta),
+            );
+            cb.require_equal(
+                "is_tag_access_list_storage_key = (tag == AccessListStorageKey)",
+                meta.query_advice(is_tag_access_list_storage_key, Rotation::cur()),
+                is_tag_access_list_storage_key_expr(meta),
+            );
+            cb.require_equal(
+                "is_tag_blob_versioned_hash = (tag == BlobVersionedHash)",
+                meta.query_advice(is_tag_blob_versioned_hash, Rotation::cur()),
+                is_tag_blob_versioned_hash_expr(meta),
+            );
+            cb.require_equal(
+                "is_tag_auth_chain_id = (tag == AuthChainId)",
+                meta.query_advice(is_tag_auth_chain_id, Rotation::cur()),
+                is_tag_auth_chain_id_expr(meta),
+            );
             cb.require_equal(
                 "is_case3 = (0xc0 <= byte_value < 0xf8) && (is_tag_end == false)",
                 meta.query_advice(is_case3, Rotation::cur()),
@@ -899,6 +937,67 @@ impl<F: Field> RlpCircuitConfig<F> {
         });
         debug_assert!(meta.degree() <= 9);
 
+        // The access list entries, the blob versioned hashes and the authorizations are
+        // counted at the rows where they are emitted, so that the tx circuit can look up
+        // the i-th address (storage key, versioned hash, authorization) of a tx. An
+        // authorization is counted at its chain id, the first field of the tuple.
+        meta.create_gate("state transition: access list indices", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let is_output_next = meta.query_advice(rlp_table.is_output, Rotation::next());
+            update_state!(
+                meta,
+                cb,
+                rlp_table.access_list_idx,
+                meta.query_advice(rlp_table.access_list_idx, Rotation::cur())
+                    + is_output_next.expr()
+                        * meta.query_advice(is_tag_access_list_address, Rotation::next())
+            );
+            update_state!(
+                meta,
+                cb,
+                rlp_table.storage_key_idx,
+                meta.query_advice(rlp_table.storage_key_idx, Rotation::cur())
+                    + is_output_next.expr()
+                        * meta.query_advice(is_tag_access_list_storage_key, Rotation::next())
+            );
+            update_state!(
+                meta,
+                cb,
+                rlp_table.blob_idx,
+                meta.query_advice(rlp_table.blob_idx, Rotation::cur())
+                    + is_output_next.expr()
+                        * meta.query_advice(is_tag_blob_versioned_hash, Rotation::next())
+            );
+            update_state!(
+                meta,
+                cb,
+                rlp_table.authorization_idx,
+                meta.query_advice(rlp_table.authorization_idx, Rotation::cur())
+                    + is_output_next * meta.query_advice(is_tag_auth_chain_id, Rotation::next())
+            );
+
+            cb.gate(and::expr([
+                meta.query_fixed(q_enabled, Rotation::cur()),
+                not::expr(is_end(meta)),
+                meta.query_advice(is_same_rlp_instance, Rotation::cur()),
+            ]))
+        });
+        meta.create_gate("access list indices of a new rlp instance", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            update_state!(meta, cb, rlp_table.access_list_idx, 0.expr());
+            update_state!(meta, cb, rlp_table.storage_key_idx, 0.expr());
+            update_state!(meta, cb, rlp_table.blob_idx, 0.expr());
+            update_state!(meta, cb, rlp_table.authorization_idx, 0.expr());
+
+            cb.gate(and::expr([
+                meta.query_fixed(q_enabled, Rotation::cur()),
+                meta.query_advice(transit_to_new_rlp_instance, Rotation::cur()),
+            ]))
+        });
+        debug_assert!(meta.degree() <= 9);
+
         meta.create_gate("sm init", |meta| {
             let mut cb = BaseConstraintBuilder::default();
             let tag = tag_expr(meta);
@@ -906,6 +1005,10 @@ impl<F: Field> RlpCircuitConfig<F> {
             constrain_eq!(meta, cb, state, DecodeTagStart.expr());
             constrain_eq!(meta, cb, tx_id, 1.expr());
             constrain_eq!(meta, cb, byte_idx, 1.expr());
+            constrain_eq!(meta, cb, rlp_table.access_list_idx, 0.expr());
+            constrain_eq!(meta, cb, rlp_table.storage_key_idx, 0.expr());
+            constrain_eq!(meta, cb, rlp_table.blob_idx, 0.expr());
+            constrain_eq!(meta, cb, rlp_table.authorization_idx, 0.expr());
             cb.require_zero(
                 "tag == TxType or tag == BeginList",
                 (tag.expr() - TxType.expr()) * (tag - BeginList.expr()),
@@ -1438,6 +1541,10 @@ impl<F: Field> RlpCircuitConfig<F> {
             is_case3,
             transit_to_new_rlp_instance,
             is_same_rlp_instance,
+            is_tag_access_list_address,
+            is_tag_access_list_storage_key,
+            is_tag_blob_versioned_hash,
+            is_tag_auth_chain_id,
 
             // comparators
             byte_value_lte_0x80,
@@ -1529,6 +1636,30 @@ impl<F: Field> RlpCircuitConfig<F> {
             row,
             || Value::known(F::from(witness.rlp_table.is_none as u64)),
         )?;
+        region.assign_advice(
+            || "rlp_table.access_list_idx",
+            self.rlp_table.access_list_idx,
+            row,
+            || Value::known(F::from(witness.rlp_table.access_list_idx as u64)),
+        )?;
+        region.assign_advice(
+            || "rlp_table.storage_key_idx",
+            self.rlp_table.storage_key_idx,
+            row,
+            || Value::known(F::from(witness.rlp_table.storage_key_idx as u64)),
+        )?;
+        region.assign_advice(
+            || "rlp_table.blob_idx",
+            self.rlp_table.blob_idx,
+            row,
+            || Value::known(F::from(witness.rlp_table.blob_idx as u64)),
+        )?;
+        region.assign_advice(
+            || "rlp_table.authorization_idx",
+            self.rlp_table.authorization_idx,
+            row,
+            || Value::known(F::from(witness.rlp_table.authorization_idx as u64)),
+        )?;
 
         // assign to sm
         region.assign_advice(
@@ -1634,6 +1765,46 @@ impl<F: Field> RlpCircuitConfig<F> {
             row,
             || Value::known(F::from(is_case3 as u64)),
         )?;
+        region.assign_advice(
+            || "is_tag_access_list_address",
+            self.is_tag_access_list_address,
+            row,
+            || {
+                Value::known(F::from(
+                    (witness.state_machine.tag == Tag::AccessListAddress) as u64,
+                ))
+            },
+        )?;
+        region.assign_advice(
+            || "is_tag_access_list_storage_key",
+            self.is_tag_access_list_storage_key,
+            row,
+            || {
+                Value::known(F::from(
+                    (witness.state_machine.tag == Tag::AccessListStorageKey) as u64,
+                ))
+            },
+        )?;
+        region.assign_advice(
+            || "is_tag_blob_versioned_hash",
+            self.is_tag_blob_versioned_hash,
+            row,
+            || {
+                Value::known(F::from(
+                    (witness.state_machine.tag == Tag::BlobVersionedHash) as u64,
+                ))
+            },
+        )?;
+        region.assign_advice(
+            || "is_tag_auth_chain_id",
+            self.is_tag_auth_chain_id,
+            row,
+            || {
+                Value::known(F::from(
+                    (witness.state_machine.tag == Tag::AuthChainId) as u64,
+                ))
+            },
+        )?;
         region.assign_advice(
             || "transit_to_new_rlp_instance",
             self.transit_to_new_rlp_instance,
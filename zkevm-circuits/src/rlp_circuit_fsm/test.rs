@@ -1,16 +1,25 @@
 #![allow(unused_imports)]
 use crate::{rlp_circuit_fsm::RlpCircuit, witness::Transaction};
-use eth_types::{geth_types::TxType, word, Address};
+use eth_types::{
+    geth_types::{
+        get_rlp_signed, get_rlp_unsigned, SetCodeAuthorization, TxType, EIP2930_TX_TYPE,
+        EIP4844_TX_TYPE, EIP7702_TX_TYPE,
+    },
+    word, Address, H256, U64,
+};
 use ethers_core::{
     types::{
-        transaction::eip2718::TypedTransaction, Eip1559TransactionRequest,
-        Transaction as EthTransaction, TransactionRequest,
+        transaction::{
+            eip2718::TypedTransaction,
+            eip2930::{AccessList, AccessListItem},
+        },
+        Eip1559TransactionRequest, Transaction as EthTransaction, TransactionRequest,
     },
     utils::rlp::{Decodable, Rlp},
 };
 use ethers_signers::Wallet;
 use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
-use mock::{eth, MOCK_CHAIN_ID};
+use mock::{eth, MockTransaction, MOCK_ACCOUNTS, MOCK_CHAIN_ID, MOCK_WALLETS};
 use rand::rngs::OsRng;
 
 #[cfg(test)]
@@ -115,10 +124,152 @@ fn test_eip1559_tx() {
     let eth_tx = EthTransaction::decode(&Rlp::new(&raw_tx_rlp_bytes))
         .expect("decode tx's rlp bytes shall not fail");
 
-    let eth_tx_req: Eip1559TransactionRequest = (&eth_tx).into();
-    let rlp_unsigned = eth_tx_req.rlp().to_vec();
+    let tx = Transaction::new_from_rlp_bytes(
+        TxType::Eip1559,
+        raw_tx_rlp_bytes,
+        get_rlp_unsigned(&eth_tx),
+    );
+    let rlp_circuit = RlpCircuit::<Fr, Transaction> {
+        txs: vec![tx],
+        max_txs: 10,
+        size: 1000,
+        _marker: Default::default(),
+    };
+
+    let mock_prover = MockProver::run(14, &rlp_circuit, vec![]);
+    assert!(mock_prover.is_ok());
+    let mock_prover = mock_prover.unwrap();
+    if let Err(errors) = mock_prover.verify_par() {
+        log::debug!("errors.len() = {}", errors.len());
+    }
+
+    mock_prover.assert_satisfied_par();
+}
+
+#[test]
+fn test_eip2930_tx() {
+    let mock_tx = MockTransaction::default()
+        .transaction_type(EIP2930_TX_TYPE as u64)
+        .from(MOCK_WALLETS[0].clone())
+        .to(MOCK_ACCOUNTS[0])
+        .nonce(word!("0x7f"))
+        .value(eth(10))
+        .gas(word!("0x77320"))
+        .gas_price(word!("0x4321"))
+        .access_list(AccessList(vec![
+            AccessListItem {
+                address: MOCK_ACCOUNTS[1],
+                storage_keys: vec![H256::from_low_u64_be(1), H256::from_low_u64_be(2)],
+            },
+            AccessListItem {
+                address: MOCK_ACCOUNTS[2],
+                storage_keys: vec![],
+            },
+        ]))
+        .build();
+    let eth_tx = EthTransaction::from(mock_tx);
+
+    let tx = Transaction::new_from_rlp_bytes(
+        TxType::Eip2930,
+        get_rlp_signed(&eth_tx),
+        get_rlp_unsigned(&eth_tx),
+    );
+    let rlp_circuit = RlpCircuit::<Fr, Transaction> {
+        txs: vec![tx],
+        max_txs: 10,
+        size: 1000,
+        _marker: Default::default(),
+    };
+
+    let mock_prover = MockProver::run(14, &rlp_circuit, vec![]);
+    assert!(mock_prover.is_ok());
+    let mock_prover = mock_prover.unwrap();
+    if let Err(errors) = mock_prover.verify_par() {
+        log::debug!("errors.len() = {}", errors.len());
+    }
+
+    mock_prover.assert_satisfied_par();
+}
+
+#[test]
+fn test_eip4844_tx() {
+    let mock_tx = MockTransaction::default()
+        .transaction_type(EIP4844_TX_TYPE as u64)
+        .from(MOCK_WALLETS[0].clone())
+        .to(MOCK_ACCOUNTS[0])
+        .nonce(word!("0x7f"))
+        .value(eth(10))
+        .gas(word!("0x77320"))
+        .max_priority_fee_per_gas(word!("0x1234"))
+        .max_fee_per_gas(word!("0x4321"))
+        .max_fee_per_blob_gas(word!("0x10"))
+        .access_list(AccessList(vec![AccessListItem {
+            address: MOCK_ACCOUNTS[1],
+            storage_keys: vec![H256::from_low_u64_be(1)],
+        }]))
+        .blob_versioned_hashes(vec![
+            H256::from_low_u64_be(0x11),
+            H256::from_low_u64_be(0x22),
+        ])
+        .build();
+    let eth_tx = EthTransaction::from(mock_tx);
+
+    let tx = Transaction::new_from_rlp_bytes(
+        TxType::Eip4844,
+        get_rlp_signed(&eth_tx),
+        get_rlp_unsigned(&eth_tx),
+    );
+    let rlp_circuit = RlpCircuit::<Fr, Transaction> {
+        txs: vec![tx],
+        max_txs: 10,
+        size: 1000,
+        _marker: Default::default(),
+    };
+
+    let mock_prover = MockProver::run(14, &rlp_circuit, vec![]);
+    assert!(mock_prover.is_ok());
+    let mock_prover = mock_prover.unwrap();
+    if let Err(errors) = mock_prover.verify_par() {
+        log::debug!("errors.len() = {}", errors.len());
+    }
+
+    mock_prover.assert_satisfied_par();
+}
+
+#[test]
+fn test_eip7702_tx() {
+    let authorization_list = vec![
+        SetCodeAuthorization::new_signed(
+            (*MOCK_CHAIN_ID).into(),
+            MOCK_ACCOUNTS[2],
+            U64::from(3),
+            &MOCK_WALLETS[1],
+        ),
+        // authorizations with chain id 0 are valid on any chain
+        SetCodeAuthorization::new_signed(0.into(), Address::zero(), U64::zero(), &MOCK_WALLETS[2]),
+    ];
+    let mock_tx = MockTransaction::default()
+        .transaction_type(EIP7702_TX_TYPE as u64)
+        .from(MOCK_WALLETS[0].clone())
+        .to(MOCK_ACCOUNTS[0])
+        .nonce(word!("0x7f"))
+        .value(eth(10))
+        .gas(word!("0x77320"))
+        .max_priority_fee_per_gas(word!("0x1234"))
+        .max_fee_per_gas(word!("0x4321"))
+        .access_list(AccessList(vec![AccessListItem {
+            address: MOCK_ACCOUNTS[1],
+            storage_keys: vec![H256::from_low_u64_be(1)],
+        }]))
+        .authorization_list(authorization_list)
+        .build();
+    let eth_tx = EthTransaction::from(mock_tx);
 
-    let tx = Transaction::new_from_rlp_bytes(TxType::Eip1559, raw_tx_rlp_bytes, rlp_unsigned);
+    let tx = Transaction::new_from_rlp_bytes(
+        TxType::Eip7702,
+        get_rlp_signed(&eth_tx),
+        get_rlp_unsigned(&eth_tx),
+    );
     let rlp_circuit = RlpCircuit::<Fr, Transaction> {
         txs: vec![tx],
         max_txs: 10,
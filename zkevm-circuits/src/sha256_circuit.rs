@@ -0,0 +1,630 @@
+//! The SHA256 circuit implementation.
+//!
+//! Every 64 bytes block of the padded input takes `NUM_ROWS_PER_BLOCK` rows:
+//! - 4 rows loading the state, `[d, c, b, a]` and `[h, g, f, e]`
+//! - 64 rows doing one round each, where the first 16 rows absorb the input
+//! - 4 rows adding the compressed block back to the state
+//!
+//! All words are decomposed into bits, so no lookup tables are needed.
+mod param;
+/// SHA256 witness generation
+pub mod sha256_bit;
+mod util;
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+mod dev;
+#[cfg(any(feature = "test", test))]
+mod test;
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+pub use dev::Sha256Circuit as TestSha256Circuit;
+
+use std::marker::PhantomData;
+
+use self::{
+    param::*,
+    sha256_bit::{get_num_blocks, multi_sha256, Sha256Row},
+    util::*,
+};
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        rlc,
+    },
+    table::{LookupTable, Sha256Table},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use eth_types::Field;
+use gadgets::util::{not, select, sum, Expr};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, VirtualCells},
+    poly::Rotation,
+};
+
+#[cfg(feature = "onephase")]
+use halo2_proofs::plonk::FirstPhase as SecondPhase;
+#[cfg(not(feature = "onephase"))]
+use halo2_proofs::plonk::SecondPhase;
+
+/// Sha256CircuitConfig
+#[derive(Clone, Debug)]
+pub struct Sha256CircuitConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    q_start: Column<Fixed>,
+    q_round: Column<Fixed>,
+    q_input: Column<Fixed>,
+    q_input_last: Column<Fixed>,
+    q_extend: Column<Fixed>,
+    q_end: Column<Fixed>,
+    round_cst: Column<Fixed>,
+    word_w: [Column<Advice>; NUM_BITS_PER_WORD_W],
+    word_a: [Column<Advice>; NUM_BITS_PER_WORD_EXT],
+    word_e: [Column<Advice>; NUM_BITS_PER_WORD_EXT],
+    is_paddings: [Column<Advice>; NUM_BYTES_PER_WORD],
+    data_rlcs: [Column<Advice>; NUM_BYTES_PER_WORD - 1],
+    /// The columns for other circuits to lookup SHA256 hash results
+    pub sha256_table: Sha256Table,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+#[derive(Debug, Clone)]
+pub struct Sha256CircuitConfigArgs<F: Field> {
+    /// Sha256Table
+    pub sha256_table: Sha256Table,
+    /// Challenges randomness
+    pub challenges: Challenges<Expression<F>>,
+}
+
+fn query_bits<F: Field>(
+    meta: &mut VirtualCells<'_, F>,
+    columns: &[Column<Advice>],
+    rot: i32,
+) -> Vec<Expression<F>> {
+    columns
+        .iter()
+        .map(|column| meta.query_advice(*column, Rotation(rot)))
+        .collect()
+}
+
+fn query_word<F: Field>(
+    meta: &mut VirtualCells<'_, F>,
+    columns: &[Column<Advice>],
+    rot: i32,
+) -> Vec<Expression<F>> {
+    query_bits(meta, &columns[..NUM_BITS_PER_WORD], rot)
+}
+
+impl<F: Field> SubCircuitConfig<F> for Sha256CircuitConfig<F> {
+    type ConfigArgs = Sha256CircuitConfigArgs<F>;
+
+    /// Return a new Sha256CircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            sha256_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = sha256_table.q_enable;
+        let q_first = meta.fixed_column();
+        let q_start = meta.fixed_column();
+        let q_round = meta.fixed_column();
+        let q_input = meta.fixed_column();
+        let q_input_last = meta.fixed_column();
+        let q_extend = meta.fixed_column();
+        let q_end = meta.fixed_column();
+        let round_cst = meta.fixed_column();
+
+        let is_final = sha256_table.is_final;
+        let length = sha256_table.input_len;
+        let data_rlc = sha256_table.input_rlc;
+        let hash_rlc = sha256_table.output_rlc;
+
+        let word_w = array_init::array_init(|_| meta.advice_column());
+        let word_a = array_init::array_init(|_| meta.advice_column());
+        let word_e = array_init::array_init(|_| meta.advice_column());
+        let is_paddings = array_init::array_init(|_| meta.advice_column());
+        let data_rlcs = array_init::array_init(|_| meta.advice_column_in(SecondPhase));
+
+        meta.create_gate("boolean checks", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in word_w
+                .iter()
+                .chain(word_a.iter())
+                .chain(word_e.iter())
+                .chain(is_paddings.iter())
+            {
+                cb.require_boolean(
+                    "bit is boolean",
+                    meta.query_advice(*column, Rotation::cur()),
+                );
+            }
+            let is_final = meta.query_advice(is_final, Rotation::cur());
+            cb.require_boolean("is_final is boolean", is_final.expr());
+            cb.condition(not::expr(meta.query_fixed(q_end, Rotation::cur())), |cb| {
+                cb.require_zero(
+                    "is_final can only be set on the last row of a block",
+                    is_final,
+                );
+            });
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("message schedule", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let w = query_bits(meta, &word_w, 0);
+            let w_2 = query_word(meta, &word_w, -2);
+            let w_7 = query_word(meta, &word_w, -7);
+            let w_15 = query_word(meta, &word_w, -15);
+            let w_16 = query_word(meta, &word_w, -16);
+            cb.require_equal(
+                "w = σ1(w[-2]) + w[-7] + σ0(w[-15]) + w[-16]",
+                decode::expr(&w),
+                decode::expr(&sigma_lower_1(&w_2))
+                    + decode::expr(&w_7)
+                    + decode::expr(&sigma_lower_0(&w_15))
+                    + decode::expr(&w_16),
+            );
+            cb.gate(meta.query_fixed(q_extend, Rotation::cur()))
+        });
+
+        meta.create_gate("round", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let [a, b, c, d] = [-1, -2, -3, -4].map(|rot| query_word(meta, &word_a, rot));
+            let [e, f, g, h] = [-1, -2, -3, -4].map(|rot| query_word(meta, &word_e, rot));
+            let new_a = query_bits(meta, &word_a, 0);
+            let new_e = query_bits(meta, &word_e, 0);
+            let w = query_word(meta, &word_w, 0);
+            let round_cst = meta.query_fixed(round_cst, Rotation::cur());
+
+            let t1 = decode::expr(&h)
+                + decode::expr(&sigma_upper_1(&e))
+                + decode::expr(&ch::expr(&e, &f, &g))
+                + round_cst
+                + decode::expr(&w);
+            let t2 = decode::expr(&sigma_upper_0(&a)) + decode::expr(&maj::expr(&a, &b, &c));
+            cb.require_equal(
+                "e = d + t1",
+                decode::expr(&new_e),
+                decode::expr(&d) + t1.expr(),
+            );
+            cb.require_equal("a = t1 + t2", decode::expr(&new_a), t1 + t2);
+            cb.gate(meta.query_fixed(q_round, Rotation::cur()))
+        });
+
+        meta.create_gate("start", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_first = meta.query_fixed(q_first, Rotation::cur());
+            // A new hash starts on the first row or after a final block
+            let start_new_hash = select::expr(
+                q_first,
+                1.expr(),
+                meta.query_advice(is_final, Rotation::prev()),
+            );
+            for idx in 0..NUM_START_ROWS {
+                for (columns, initial_state) in [(&word_a, H[3 - idx]), (&word_e, H[7 - idx])] {
+                    let state = query_word(meta, columns, idx as i32);
+                    let prev_state = query_word(meta, columns, idx as i32 - NUM_END_ROWS as i32);
+                    cb.require_equal(
+                        "state is initialized or carried over from the previous block",
+                        decode::expr(&state),
+                        select::expr(
+                            start_new_hash.expr(),
+                            initial_state.expr(),
+                            decode::expr(&prev_state),
+                        ),
+                    );
+                }
+            }
+            for column in [length, data_rlc, is_paddings[NUM_BYTES_PER_WORD - 1]] {
+                cb.require_equal(
+                    "value is reset or carried over from the previous block",
+                    meta.query_advice(column, Rotation::cur()),
+                    select::expr(
+                        start_new_hash.expr(),
+                        0.expr(),
+                        meta.query_advice(column, Rotation::prev()),
+                    ),
+                );
+            }
+            cb.gate(meta.query_fixed(q_start, Rotation::cur()))
+        });
+
+        meta.create_gate("input", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_input_last = meta.query_fixed(q_input_last, Rotation::cur());
+            let w = query_bits(meta, &word_w, 0);
+            cb.require_zero(
+                "input words have no carry",
+                decode::expr(&w[NUM_BITS_PER_WORD..]),
+            );
+            let bytes = to_be_bytes(&w);
+
+            let mut prev_is_padding =
+                meta.query_advice(is_paddings[NUM_BYTES_PER_WORD - 1], Rotation::prev());
+            let is_paddings = is_paddings.map(|column| meta.query_advice(column, Rotation::cur()));
+            let data_rlcs = data_rlcs
+                .iter()
+                .chain([data_rlc].iter())
+                .map(|column| meta.query_advice(*column, Rotation::cur()))
+                .collect::<Vec<_>>();
+            let mut prev_data_rlc = meta.query_advice(data_rlc, Rotation::prev());
+            for ((byte, is_padding), data_rlc) in
+                bytes.iter().zip(is_paddings.iter()).zip(data_rlcs.iter())
+            {
+                let is_first_padding = is_padding.expr() - prev_is_padding.expr();
+                cb.require_boolean("padding is monotonic", is_first_padding.expr());
+                // The last two words are checked at the end of the block, they
+                // contain the length in a final block.
+                cb.condition(is_padding.expr() * not::expr(q_input_last.expr()), |cb| {
+                    cb.require_equal(
+                        "padding byte is 0x80 followed by zeros",
+                        byte.expr(),
+                        is_first_padding.expr() * 0x80.expr(),
+                    );
+                });
+                cb.require_equal(
+                    "data_rlc is accumulated over the input bytes",
+                    data_rlc.expr(),
+                    select::expr(
+                        is_padding.expr(),
+                        prev_data_rlc.expr(),
+                        prev_data_rlc.expr() * challenges.keccak_input() + byte.expr(),
+                    ),
+                );
+                prev_is_padding = is_padding.expr();
+                prev_data_rlc = data_rlc.expr();
+            }
+            cb.require_equal(
+                "length is increased by the number of input bytes",
+                meta.query_advice(length, Rotation::cur()),
+                meta.query_advice(length, Rotation::prev())
+                    + sum::expr(
+                        is_paddings
+                            .iter()
+                            .map(|is_padding| not::expr(is_padding.expr())),
+                    ),
+            );
+            cb.gate(meta.query_fixed(q_input, Rotation::cur()))
+        });
+
+        meta.create_gate("carry over", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for column in [length, data_rlc, is_paddings[NUM_BYTES_PER_WORD - 1]] {
+                cb.require_equal(
+                    "value is carried over",
+                    meta.query_advice(column, Rotation::cur()),
+                    meta.query_advice(column, Rotation::prev()),
+                );
+            }
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    - meta.query_fixed(q_start, Rotation::cur())
+                    - meta.query_fixed(q_input, Rotation::cur()),
+            )
+        });
+
+        meta.create_gate("end", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let is_final = meta.query_advice(is_final, Rotation::cur());
+            let rot_start = -((NUM_ROWS_PER_BLOCK - NUM_END_ROWS) as i32);
+            let rot_round_last = -(NUM_END_ROWS as i32);
+            let mut digest = vec![];
+            for columns in [&word_a, &word_e] {
+                for idx in 0..NUM_END_ROWS {
+                    let rot = idx as i32 - (NUM_END_ROWS as i32 - 1);
+                    let state = query_bits(meta, columns, rot);
+                    cb.require_equal(
+                        "state is increased by the compressed block",
+                        decode::expr(&state),
+                        decode::expr(&query_word(meta, columns, rot + rot_start))
+                            + decode::expr(&query_word(meta, columns, rot + rot_round_last)),
+                    );
+                }
+                // The digest is the final state, starting from `a`/`e` on this row
+                for rot in 0..NUM_END_ROWS as i32 {
+                    digest.extend(to_be_bytes(&query_word(meta, columns, -rot)));
+                }
+            }
+
+            // Padding checks for the last two input words
+            let rot_input_last =
+                -((NUM_ROWS_PER_BLOCK - NUM_START_ROWS - NUM_WORDS_TO_ABSORB) as i32);
+            let mut prev_is_padding = meta.query_advice(
+                is_paddings[NUM_BYTES_PER_WORD - 1],
+                Rotation(rot_input_last - 2),
+            );
+            cb.require_equal(
+                "is_final is set when the length fits in the last two words",
+                is_final.expr(),
+                prev_is_padding.expr(),
+            );
+            let mut length_words = vec![];
+            for rot in [rot_input_last - 1, rot_input_last] {
+                let w = query_word(meta, &word_w, rot);
+                for (byte, column) in to_be_bytes(&w).iter().zip(is_paddings.iter()) {
+                    let is_padding = meta.query_advice(*column, Rotation(rot));
+                    cb.condition(not::expr(is_final.expr()) * is_padding.expr(), |cb| {
+                        cb.require_equal(
+                            "padding byte is 0x80 followed by zeros",
+                            byte.expr(),
+                            (is_padding.expr() - prev_is_padding.expr()) * 0x80.expr(),
+                        );
+                    });
+                    prev_is_padding = is_padding;
+                }
+                length_words.push(decode::expr(&w));
+            }
+            cb.condition(is_final.expr(), |cb| {
+                cb.require_zero("length high word is zero", length_words[0].expr());
+                cb.require_equal(
+                    "length low word is the length in bits",
+                    length_words[1].expr(),
+                    meta.query_advice(length, Rotation::cur()) * NUM_BITS_PER_BYTE.expr(),
+                );
+                cb.require_equal(
+                    "hash_rlc is the RLC of the digest",
+                    meta.query_advice(hash_rlc, Rotation::cur()),
+                    rlc::expr(
+                        &digest.into_iter().rev().collect::<Vec<_>>(),
+                        challenges.keccak_input(),
+                    ),
+                );
+            });
+            cb.gate(meta.query_fixed(q_end, Rotation::cur()))
+        });
+
+        Sha256CircuitConfig {
+            q_enable,
+            q_first,
+            q_start,
+            q_round,
+            q_input,
+            q_input_last,
+            q_extend,
+            q_end,
+            round_cst,
+            word_w,
+            word_a,
+            word_e,
+            is_paddings,
+            data_rlcs,
+            sha256_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> Sha256CircuitConfig<F> {
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        witness: &[Sha256Row<F>],
+    ) -> Result<(), Error> {
+        let mut is_first_time = true;
+        layouter.assign_region(
+            || "assign sha256 rows",
+            |mut region| {
+                if is_first_time {
+                    is_first_time = false;
+                    if let Some(row) = witness.last() {
+                        self.set_row(&mut region, witness.len() - 1, row)?;
+                    }
+                    return Ok(());
+                }
+                for (offset, sha256_row) in witness.iter().enumerate() {
+                    self.set_row(&mut region, offset, sha256_row)?;
+                }
+                self.sha256_table.annotate_columns_in_region(&mut region);
+                self.annotate_circuit(&mut region);
+                Ok(())
+            },
+        )
+    }
+
+    /// Set the cells for a sha256 row; return the cells that are assigned.
+    pub fn set_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &Sha256Row<F>,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let idx = offset % NUM_ROWS_PER_BLOCK;
+        let round = idx
+            .checked_sub(NUM_START_ROWS)
+            .filter(|round| *round < NUM_ROUNDS);
+        let is_input = round.filter(|round| *round < NUM_WORDS_TO_ABSORB).is_some();
+
+        // Fixed selectors
+        for (name, column, value) in &[
+            ("q_enable", self.q_enable, true),
+            ("q_first", self.q_first, offset == 0),
+            ("q_start", self.q_start, idx == 0),
+            ("q_round", self.q_round, round.is_some()),
+            ("q_input", self.q_input, is_input),
+            (
+                "q_input_last",
+                self.q_input_last,
+                is_input && round.unwrap() + 2 >= NUM_WORDS_TO_ABSORB,
+            ),
+            ("q_extend", self.q_extend, round.is_some() && !is_input),
+            ("q_end", self.q_end, idx == NUM_ROWS_PER_BLOCK - 1),
+        ] {
+            region.assign_fixed(
+                || format!("assign {name} {offset}"),
+                *column,
+                offset,
+                || Value::known(F::from(*value)),
+            )?;
+        }
+
+        // Round constant
+        region.assign_fixed(
+            || format!("assign round cst {offset}"),
+            self.round_cst,
+            offset,
+            || {
+                Value::known(F::from(
+                    round.map(|round| ROUND_CST[round]).unwrap_or_default(),
+                ))
+            },
+        )?;
+
+        // table values
+        let mut res = self.sha256_table.assign_row(
+            region,
+            offset,
+            [
+                Value::known(F::from(row.is_final)),
+                row.data_rlcs[NUM_BYTES_PER_WORD - 1],
+                Value::known(F::from(row.length as u64)),
+                row.hash_rlc,
+            ],
+        )?;
+
+        // Bits of the words
+        for (name, columns, bits) in [
+            ("w", self.word_w.as_slice(), row.w.as_slice()),
+            ("a", self.word_a.as_slice(), row.a.as_slice()),
+            ("e", self.word_e.as_slice(), row.e.as_slice()),
+        ] {
+            for (idx, (column, bit)) in columns.iter().zip(bits.iter()).enumerate() {
+                res.push(region.assign_advice(
+                    || format!("assign {name} bit {idx} {offset}"),
+                    *column,
+                    offset,
+                    || Value::known(F::from(*bit as u64)),
+                )?);
+            }
+        }
+
+        // Padding flags and intermediate data rlcs
+        for (idx, (column, is_padding)) in self
+            .is_paddings
+            .iter()
+            .zip(row.is_paddings.iter())
+            .enumerate()
+        {
+            res.push(region.assign_advice(
+                || format!("assign is_padding {idx} {offset}"),
+                *column,
+                offset,
+                || Value::known(F::from(*is_padding)),
+            )?);
+        }
+        for (idx, (column, data_rlc)) in self.data_rlcs.iter().zip(row.data_rlcs.iter()).enumerate()
+        {
+            res.push(region.assign_advice(
+                || format!("assign data_rlc {idx} {offset}"),
+                *column,
+                offset,
+                || *data_rlc,
+            )?);
+        }
+
+        Ok(res)
+    }
+
+    /// Annotate the circuit
+    pub fn annotate_circuit(&self, region: &mut Region<F>) {
+        region.name_column(|| "SHA256_q_first", self.q_first);
+        region.name_column(|| "SHA256_q_start", self.q_start);
+        region.name_column(|| "SHA256_q_round", self.q_round);
+        region.name_column(|| "SHA256_q_input", self.q_input);
+        region.name_column(|| "SHA256_q_input_last", self.q_input_last);
+        region.name_column(|| "SHA256_q_extend", self.q_extend);
+        region.name_column(|| "SHA256_q_end", self.q_end);
+        region.name_column(|| "SHA256_round_cst", self.round_cst);
+    }
+}
+
+/// Sha256Circuit
+#[derive(Default, Clone, Debug)]
+pub struct Sha256Circuit<F: Field> {
+    // Each input row is a pre-image of the hash
+    inputs: Vec<Vec<u8>>,
+    // The maximum number of rows, for example, 2^20
+    // This needs to be large enough for the circuit.
+    num_rows: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> SubCircuit<F> for Sha256Circuit<F> {
+    type Config = Sha256CircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // Columns of word a and e are queried at 15 distinct rotations, so
+        // returns 18 unusable rows.
+        18
+    }
+
+    /// The sha256 circuit shares the row budget of the keccak circuit, see
+    /// `block.circuits_params.max_keccak_rows`.
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self::new(
+            block.circuits_params.max_keccak_rows,
+            block
+                .get_sha256()
+                .into_iter()
+                .map(|event| event.input)
+                .collect(),
+        )
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        let rows = block
+            .get_sha256()
+            .iter()
+            .map(|event| get_num_blocks(event.input.len()) * NUM_ROWS_PER_BLOCK)
+            .sum::<usize>();
+        (rows, rows.max(block.circuits_params.max_keccak_rows))
+    }
+
+    /// Make the assignments to the Sha256Circuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let witness = self.generate_witness(*challenges);
+        config.assign(layouter, witness.as_slice())
+    }
+}
+
+impl<F: Field> Sha256Circuit<F> {
+    /// Creates a new circuit instance
+    pub fn new(num_rows: usize, inputs: Vec<Vec<u8>>) -> Self {
+        Sha256Circuit {
+            inputs,
+            num_rows,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The number of sha256 blocks that can be compressed in this circuit
+    pub fn capacity(&self) -> Option<usize> {
+        Self::capacity_for_row(self.num_rows)
+    }
+
+    /// The number of sha256 blocks that can be compressed for a particular
+    /// row number
+    pub fn capacity_for_row(num_rows: usize) -> Option<usize> {
+        if num_rows > 0 {
+            Some(num_rows.saturating_sub(Self::unusable_rows()) / NUM_ROWS_PER_BLOCK)
+        } else {
+            None
+        }
+    }
+
+    /// Sets the witness using the data to be hashed
+    pub(crate) fn generate_witness(&self, challenges: Challenges<Value<F>>) -> Vec<Sha256Row<F>> {
+        multi_sha256(self.inputs.as_slice(), challenges, self.capacity())
+            .expect("Too many inputs for given capacity")
+    }
+}
@@ -0,0 +1,220 @@
+use super::{param::*, util::*};
+use crate::{evm_circuit::util::rlc, util::Challenges};
+use eth_types::Field;
+use halo2_proofs::{circuit::Value, plonk::Error};
+use log::debug;
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+
+const WORD_MASK: u64 = (1 << NUM_BITS_PER_WORD) - 1;
+
+/// Sha256Row
+#[derive(Clone, Debug)]
+pub struct Sha256Row<F> {
+    pub(crate) w: [u8; NUM_BITS_PER_WORD_W],
+    pub(crate) a: [u8; NUM_BITS_PER_WORD_EXT],
+    pub(crate) e: [u8; NUM_BITS_PER_WORD_EXT],
+    pub(crate) is_paddings: [bool; NUM_BYTES_PER_WORD],
+    /// The intermediate input RLCs after each byte, the last one is the input
+    /// RLC of the row
+    pub(crate) data_rlcs: [Value<F>; NUM_BYTES_PER_WORD],
+    /// if the row is the last row of the hash
+    pub is_final: bool,
+    /// The input length of the hash function
+    pub length: usize,
+    pub(crate) hash_rlc: Value<F>,
+}
+
+fn rotr(x: u64, n: u32) -> u64 {
+    (x as u32).rotate_right(n) as u64
+}
+
+fn sigma_upper_0(x: u64) -> u64 {
+    rotr(x, 2) ^ rotr(x, 13) ^ rotr(x, 22)
+}
+
+fn sigma_upper_1(x: u64) -> u64 {
+    rotr(x, 6) ^ rotr(x, 11) ^ rotr(x, 25)
+}
+
+fn sigma_lower_0(x: u64) -> u64 {
+    rotr(x, 7) ^ rotr(x, 18) ^ (x >> 3)
+}
+
+fn sigma_lower_1(x: u64) -> u64 {
+    rotr(x, 17) ^ rotr(x, 19) ^ (x >> 10)
+}
+
+/// Pads the input as described in FIPS 180-4 section 5.1.1
+fn pad(bytes: &[u8]) -> Vec<u8> {
+    let mut padded = bytes.to_vec();
+    padded.push(0x80);
+    while padded.len() % RATE != RATE - NUM_BYTES_LENGTH {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&((bytes.len() as u64) * NUM_BITS_PER_BYTE as u64).to_be_bytes());
+    padded
+}
+
+/// The number of blocks needed to hash an input of `len` bytes
+pub(crate) fn get_num_blocks(len: usize) -> usize {
+    (len + 1 + NUM_BYTES_LENGTH + RATE - 1) / RATE
+}
+
+fn sha256<F: Field>(rows: &mut Vec<Sha256Row<F>>, bytes: &[u8], challenges: Challenges<Value<F>>) {
+    let padded = pad(bytes);
+    let num_blocks = padded.len() / RATE;
+
+    let mut hs = H;
+    let mut length = 0usize;
+    let mut data_rlc = Value::known(F::zero());
+    let mut is_padding = false;
+    for (block_idx, block) in padded.chunks(RATE).enumerate() {
+        let is_final = block_idx == num_blocks - 1;
+        let non_input_row = |w: u64, a: u64, e: u64, length, data_rlc, is_padding| Sha256Row {
+            w: into_bits(w),
+            a: into_bits(a),
+            e: into_bits(e),
+            is_paddings: [is_padding; NUM_BYTES_PER_WORD],
+            data_rlcs: [data_rlc; NUM_BYTES_PER_WORD],
+            is_final: false,
+            length,
+            hash_rlc: Value::known(F::zero()),
+        };
+
+        // Load the state, reversed so that the rounds find `a` at the previous
+        // row.
+        for idx in 0..NUM_START_ROWS {
+            rows.push(non_input_row(
+                0,
+                hs[3 - idx],
+                hs[7 - idx],
+                length,
+                data_rlc,
+                is_padding,
+            ));
+        }
+
+        let mut ws = Vec::with_capacity(NUM_ROUNDS);
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = hs;
+        for round in 0..NUM_ROUNDS {
+            let w_ext = if round < NUM_WORDS_TO_ABSORB {
+                u32::from_be_bytes(
+                    block[round * NUM_BYTES_PER_WORD..(round + 1) * NUM_BYTES_PER_WORD]
+                        .try_into()
+                        .unwrap(),
+                ) as u64
+            } else {
+                sigma_lower_1(ws[round - 2])
+                    + ws[round - 7]
+                    + sigma_lower_0(ws[round - 15])
+                    + ws[round - 16]
+            };
+            ws.push(w_ext & WORD_MASK);
+
+            let t1 = h
+                + sigma_upper_1(e)
+                + ((e & f) ^ (!e & g & WORD_MASK))
+                + ROUND_CST[round]
+                + ws[round];
+            let t2 = sigma_upper_0(a) + ((a & b) ^ (a & c) ^ (b & c));
+            let a_ext = t1 + t2;
+            let e_ext = d + t1;
+
+            let mut row = non_input_row(w_ext, a_ext, e_ext, length, data_rlc, is_padding);
+            if round < NUM_WORDS_TO_ABSORB {
+                for idx in 0..NUM_BYTES_PER_WORD {
+                    let byte_idx = block_idx * RATE + round * NUM_BYTES_PER_WORD + idx;
+                    is_padding = byte_idx >= bytes.len();
+                    if !is_padding {
+                        length += 1;
+                        data_rlc = data_rlc
+                            .zip(challenges.keccak_input())
+                            .map(|(data_rlc, r)| data_rlc * r + F::from(bytes[byte_idx] as u64));
+                    }
+                    row.is_paddings[idx] = is_padding;
+                    row.data_rlcs[idx] = data_rlc;
+                }
+                row.length = length;
+            }
+            rows.push(row);
+
+            h = g;
+            g = f;
+            f = e;
+            e = e_ext & WORD_MASK;
+            d = c;
+            c = b;
+            b = a;
+            a = a_ext & WORD_MASK;
+        }
+
+        // Add the compressed chunk to the current hash value
+        let vars = [a, b, c, d, e, f, g, h];
+        let hs_ext: [u64; 8] = array_init::array_init(|idx| hs[idx] + vars[idx]);
+        hs = hs_ext.map(|h| h & WORD_MASK);
+        for idx in 0..NUM_END_ROWS {
+            rows.push(non_input_row(
+                0,
+                hs_ext[3 - idx],
+                hs_ext[7 - idx],
+                length,
+                data_rlc,
+                is_padding,
+            ));
+        }
+
+        if is_final {
+            let digest = hs
+                .iter()
+                .flat_map(|h| (*h as u32).to_be_bytes())
+                .collect::<Vec<_>>();
+            let last_row = rows.last_mut().unwrap();
+            last_row.is_final = true;
+            last_row.hash_rlc = challenges
+                .keccak_input()
+                .map(|r| rlc::value(digest.iter().rev(), r));
+            debug!("sha256 digest: {:x?}", digest);
+        }
+    }
+}
+
+/// Witness generation for multiple sha256 hashes
+pub fn multi_sha256<F: Field>(
+    bytes: &[Vec<u8>],
+    challenges: Challenges<Value<F>>,
+    capacity: Option<usize>,
+) -> Result<Vec<Sha256Row<F>>, Error> {
+    log::info!("multi_sha256 assign with capacity: {:?}", capacity);
+    let mut rows: Vec<Sha256Row<F>> = Vec::new();
+    if let Some(capacity) = capacity {
+        rows.reserve(capacity * NUM_ROWS_PER_BLOCK);
+    }
+
+    let real_rows: Vec<_> = bytes
+        .par_iter()
+        .flat_map_iter(|bytes| {
+            let mut rows = Vec::new();
+            sha256(&mut rows, bytes, challenges);
+            rows
+        })
+        .collect();
+    rows.extend(real_rows.into_iter());
+    debug!("sha256 rows len without padding: {}", rows.len());
+    if let Some(capacity) = capacity {
+        let padding_rows = {
+            let mut rows = Vec::new();
+            sha256(&mut rows, &[], challenges);
+            rows
+        };
+        // Pad with no data hashes to the expected capacity
+        while rows.len() < capacity * NUM_ROWS_PER_BLOCK {
+            rows.extend(padding_rows.clone());
+        }
+        // Check that we are not over capacity
+        if rows.len() > capacity * NUM_ROWS_PER_BLOCK {
+            return Err(Error::BoundsFailure);
+        }
+    }
+    debug!("sha256 witgen done");
+    Ok(rows)
+}
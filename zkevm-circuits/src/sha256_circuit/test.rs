@@ -0,0 +1,161 @@
+use super::*;
+use crate::util::unusable_rows;
+use halo2_proofs::{
+    circuit::SimpleFloorPlanner,
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{Circuit, ConstraintSystem},
+};
+use log::error;
+use sha2::{Digest, Sha256};
+
+const K: u32 = 12;
+
+#[test]
+fn sha256_circuit_unusable_rows() {
+    assert_eq!(
+        Sha256Circuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, Sha256Circuit::<Fr>>(),
+    )
+}
+
+fn verify<F: Field>(k: u32, inputs: Vec<Vec<u8>>, success: bool) {
+    let circuit = Sha256Circuit::new(2usize.pow(k), inputs);
+
+    let prover = MockProver::<F>::run(k, &circuit, vec![]).unwrap();
+    let verify_result = prover.verify();
+    if verify_result.is_ok() != success {
+        if let Some(errors) = verify_result.err() {
+            for error in errors.iter() {
+                error!("{}", error);
+            }
+        }
+        panic!();
+    }
+}
+
+#[test]
+fn sha256_circuit_simple() {
+    let inputs = vec![
+        vec![],
+        (0u8..1).collect::<Vec<_>>(),
+        (0u8..55).collect::<Vec<_>>(),
+        (0u8..56).collect::<Vec<_>>(),
+        (0u8..64).collect::<Vec<_>>(),
+        (0u8..200).collect::<Vec<_>>(),
+    ];
+    verify::<Fr>(K, inputs, true);
+}
+
+// Sha256 circuit whose witness is tampered with before being assigned.
+#[derive(Clone)]
+struct TamperedSha256Circuit {
+    circuit: Sha256Circuit<Fr>,
+    tamper: fn(&mut Vec<Sha256Row<Fr>>),
+}
+
+impl Circuit<Fr> for TamperedSha256Circuit {
+    type Config = <Sha256Circuit<Fr> as Circuit<Fr>>::Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        Sha256Circuit::<Fr>::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        (config, challenges): Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let challenges = challenges.values(&layouter);
+        let mut witness = self.circuit.generate_witness(challenges);
+        (self.tamper)(&mut witness);
+        config.assign(&mut layouter, witness.as_slice())
+    }
+}
+
+fn verify_tampered(input: Vec<u8>, tamper: fn(&mut Vec<Sha256Row<Fr>>)) {
+    let circuit = TamperedSha256Circuit {
+        circuit: Sha256Circuit::new(2usize.pow(K), vec![input]),
+        tamper,
+    };
+    let prover = MockProver::<Fr>::run(K, &circuit, vec![]).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+#[test]
+fn sha256_circuit_tampered_digest() {
+    verify_tampered((0u8..55).collect(), |rows| {
+        let last_row = rows.last_mut().unwrap();
+        last_row.hash_rlc = last_row.hash_rlc + Value::known(Fr::one());
+    });
+}
+
+#[test]
+fn sha256_circuit_wrong_length() {
+    verify_tampered((0u8..55).collect(), |rows| {
+        rows.last_mut().unwrap().length += 1;
+    });
+}
+
+#[test]
+fn sha256_circuit_wrong_padding() {
+    // The 0x80 padding byte of a 55 bytes input is the last byte of the 14th
+    // input word, flag it as an input byte.
+    verify_tampered((0u8..55).collect(), |rows| {
+        rows[NUM_START_ROWS + 13].is_paddings[NUM_BYTES_PER_WORD - 1] = false;
+    });
+}
+
+#[test]
+fn sha256_circuit_corrupted_round() {
+    verify_tampered((0u8..55).collect(), |rows| {
+        rows[NUM_START_ROWS + 20].a[0] ^= 1;
+    });
+}
+
+fn mock_challenges() -> Challenges<Value<Fr>> {
+    Challenges::mock(
+        Value::known(Fr::from(0x100)),
+        Value::known(Fr::from(0x100)),
+        Value::known(Fr::from(0x100)),
+    )
+}
+
+#[test]
+fn sha256_circuit_capacity() {
+    let challenges = mock_challenges();
+    // 120 bytes need 3 blocks once padded
+    let inputs = vec![(0u8..120).collect::<Vec<_>>()];
+    assert!(multi_sha256::<Fr>(&inputs, challenges, Some(2)).is_err());
+    assert_eq!(
+        multi_sha256::<Fr>(&inputs, challenges, Some(4))
+            .unwrap()
+            .len(),
+        4 * NUM_ROWS_PER_BLOCK
+    );
+}
+
+#[test]
+fn sha256_witness_digest() {
+    let challenges = mock_challenges();
+    for len in [0, 1, 55, 56, 63, 64, 65, 119, 120, 200] {
+        let input = (0..len).map(|idx| idx as u8).collect::<Vec<_>>();
+        let rows = multi_sha256::<Fr>(&[input.clone()], challenges, None).unwrap();
+        assert_eq!(rows.len(), get_num_blocks(len) * NUM_ROWS_PER_BLOCK);
+
+        let last_row = rows.last().unwrap();
+        assert!(last_row.is_final);
+        assert_eq!(last_row.length, len);
+
+        let digest = Sha256::digest(&input);
+        last_row
+            .hash_rlc
+            .zip(challenges.keccak_input())
+            .assert_if_known(|(hash_rlc, r)| *hash_rlc == rlc::value(digest.iter().rev(), *r));
+    }
+}
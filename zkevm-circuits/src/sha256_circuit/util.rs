@@ -0,0 +1,168 @@
+use super::param::*;
+use eth_types::Field;
+use halo2_proofs::plonk::Expression;
+
+/// Decodes little-endian bits into a value
+pub(crate) mod decode {
+    use crate::util::Expr;
+    use eth_types::Field;
+    use halo2_proofs::plonk::Expression;
+
+    pub(crate) fn expr<F: Field>(bits: &[Expression<F>]) -> Expression<F> {
+        let mut value = 0.expr();
+        let mut multiplier = F::one();
+        for bit in bits.iter() {
+            value = value + bit.expr() * multiplier;
+            multiplier *= F::from(2);
+        }
+        value
+    }
+}
+
+/// Rotates the bits of a word to the right
+pub(crate) mod rotate {
+    use super::NUM_BITS_PER_WORD;
+    use eth_types::Field;
+    use halo2_proofs::plonk::Expression;
+
+    pub(crate) fn expr<F: Field>(bits: &[Expression<F>], count: usize) -> Vec<Expression<F>> {
+        (0..NUM_BITS_PER_WORD)
+            .map(|idx| bits[(idx + count) % NUM_BITS_PER_WORD].clone())
+            .collect()
+    }
+}
+
+/// Shifts the bits of a word to the right
+pub(crate) mod shift {
+    use super::NUM_BITS_PER_WORD;
+    use crate::util::Expr;
+    use eth_types::Field;
+    use halo2_proofs::plonk::Expression;
+
+    pub(crate) fn expr<F: Field>(bits: &[Expression<F>], count: usize) -> Vec<Expression<F>> {
+        (0..NUM_BITS_PER_WORD)
+            .map(|idx| {
+                if idx + count < NUM_BITS_PER_WORD {
+                    bits[idx + count].clone()
+                } else {
+                    0.expr()
+                }
+            })
+            .collect()
+    }
+}
+
+/// Bitwise xor of three words
+pub(crate) mod xor3 {
+    use crate::util::Expr;
+    use eth_types::Field;
+    use halo2_proofs::plonk::Expression;
+
+    pub(crate) fn expr<F: Field>(
+        a: &[Expression<F>],
+        b: &[Expression<F>],
+        c: &[Expression<F>],
+    ) -> Vec<Expression<F>> {
+        a.iter()
+            .zip(b.iter())
+            .zip(c.iter())
+            .map(|((a, b), c)| {
+                a.clone() + b.clone() + c.clone()
+                    - 2.expr()
+                        * (a.clone() * b.clone() + a.clone() * c.clone() + b.clone() * c.clone())
+                    + 4.expr() * a.clone() * b.clone() * c.clone()
+            })
+            .collect()
+    }
+}
+
+/// `Ch(e, f, g) = (e & f) ^ (!e & g)`
+pub(crate) mod ch {
+    use crate::util::Expr;
+    use eth_types::Field;
+    use halo2_proofs::plonk::Expression;
+
+    pub(crate) fn expr<F: Field>(
+        e: &[Expression<F>],
+        f: &[Expression<F>],
+        g: &[Expression<F>],
+    ) -> Vec<Expression<F>> {
+        e.iter()
+            .zip(f.iter())
+            .zip(g.iter())
+            .map(|((e, f), g)| e.clone() * f.clone() + (1.expr() - e.clone()) * g.clone())
+            .collect()
+    }
+}
+
+/// `Maj(a, b, c) = (a & b) ^ (a & c) ^ (b & c)`
+pub(crate) mod maj {
+    use crate::util::Expr;
+    use eth_types::Field;
+    use halo2_proofs::plonk::Expression;
+
+    pub(crate) fn expr<F: Field>(
+        a: &[Expression<F>],
+        b: &[Expression<F>],
+        c: &[Expression<F>],
+    ) -> Vec<Expression<F>> {
+        a.iter()
+            .zip(b.iter())
+            .zip(c.iter())
+            .map(|((a, b), c)| {
+                a.clone() * b.clone() + a.clone() * c.clone() + b.clone() * c.clone()
+                    - 2.expr() * a.clone() * b.clone() * c.clone()
+            })
+            .collect()
+    }
+}
+
+/// `Σ0(a) = ROTR2(a) ^ ROTR13(a) ^ ROTR22(a)`
+pub(crate) fn sigma_upper_0<F: Field>(bits: &[Expression<F>]) -> Vec<Expression<F>> {
+    xor3::expr(
+        &rotate::expr(bits, 2),
+        &rotate::expr(bits, 13),
+        &rotate::expr(bits, 22),
+    )
+}
+
+/// `Σ1(e) = ROTR6(e) ^ ROTR11(e) ^ ROTR25(e)`
+pub(crate) fn sigma_upper_1<F: Field>(bits: &[Expression<F>]) -> Vec<Expression<F>> {
+    xor3::expr(
+        &rotate::expr(bits, 6),
+        &rotate::expr(bits, 11),
+        &rotate::expr(bits, 25),
+    )
+}
+
+/// `σ0(w) = ROTR7(w) ^ ROTR18(w) ^ SHR3(w)`
+pub(crate) fn sigma_lower_0<F: Field>(bits: &[Expression<F>]) -> Vec<Expression<F>> {
+    xor3::expr(
+        &rotate::expr(bits, 7),
+        &rotate::expr(bits, 18),
+        &shift::expr(bits, 3),
+    )
+}
+
+/// `σ1(w) = ROTR17(w) ^ ROTR19(w) ^ SHR10(w)`
+pub(crate) fn sigma_lower_1<F: Field>(bits: &[Expression<F>]) -> Vec<Expression<F>> {
+    xor3::expr(
+        &rotate::expr(bits, 17),
+        &rotate::expr(bits, 19),
+        &shift::expr(bits, 10),
+    )
+}
+
+/// Returns the big-endian bytes of a word given its little-endian bits
+pub(crate) fn to_be_bytes<F: Field>(bits: &[Expression<F>]) -> Vec<Expression<F>> {
+    bits[..NUM_BITS_PER_WORD]
+        .chunks(NUM_BITS_PER_BYTE)
+        .rev()
+        .map(decode::expr)
+        .collect()
+}
+
+/// Returns the little-endian bits of a value
+pub(crate) fn into_bits<const N: usize>(value: u64) -> [u8; N] {
+    array_init::array_init(|idx| ((value >> idx) & 1) as u8)
+}
@@ -9,6 +9,9 @@
 // - *_be: Big-Endian bytes
 // - *_le: Little-Endian bytes
 
+#[cfg(all(feature = "onephase", feature = "batch-ecdsa"))]
+compile_error!("the batched ECDSA verification needs a second phase for its challenge");
+
 #[cfg(any(feature = "test", test, feature = "test-circuits"))]
 mod dev;
 #[cfg(any(feature = "test", test, feature = "test-circuits"))]
@@ -55,6 +58,20 @@ use halo2_proofs::{
     poly::Rotation,
 };
 
+#[cfg(feature = "batch-ecdsa")]
+use crate::sig_circuit::ecdsa::{ecdsa_batch_verify, ecdsa_verify_deferred};
+#[cfg(feature = "batch-ecdsa")]
+use halo2_base::utils::{biguint_to_fe, fe_to_biguint};
+#[cfg(feature = "batch-ecdsa")]
+use halo2_ecc::bigint::OverflowInteger;
+#[cfg(feature = "batch-ecdsa")]
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    halo2curves::group::ff::{Field as GroupField, PrimeField},
+};
+#[cfg(feature = "batch-ecdsa")]
+use num_bigint::{BigInt, BigUint};
+
 use ethers_core::utils::keccak256;
 use itertools::Itertools;
 use log::error;
@@ -79,6 +96,9 @@ pub struct SigCircuitConfig<F: Field> {
     rlc_column: Column<Advice>,
     /// selector for keccak lookup table
     q_keccak: Selector,
+    /// selector for the challenge of the batched verification
+    #[cfg(feature = "batch-ecdsa")]
+    q_challenge: Selector,
     /// Used to lookup pk->pk_hash(addr)
     keccak_table: KeccakTable,
     /// The exposed table to be used by tx circuit and ecrecover
@@ -94,15 +114,26 @@ impl<F: Field> SubCircuitConfig<F> for SigCircuitConfig<F> {
         Self::ConfigArgs {
             keccak_table,
             sig_table,
-            challenges: _,
+            challenges,
         }: Self::ConfigArgs,
     ) -> Self {
         #[cfg(feature = "onephase")]
-        let num_advice = [calc_required_advices(MAX_NUM_SIG)];
-        #[cfg(not(feature = "onephase"))]
+        let num_advice = [calc_required_advices(MAX_NUM_SIG, CELLS_PER_SIG)];
+        #[cfg(all(not(feature = "onephase"), not(feature = "batch-ecdsa")))]
         // need an additional phase 2 column/basic gate to hold the witnesses during RLC
         // computations
-        let num_advice = [calc_required_advices(MAX_NUM_SIG), 1];
+        let num_advice = [calc_required_advices(MAX_NUM_SIG, CELLS_PER_SIG), 1];
+        #[cfg(feature = "batch-ecdsa")]
+        // the batched verification is done in phase 2 with the RLC computations, after the
+        // points are fixed in phase 1
+        let num_advice = [
+            calc_required_advices(MAX_NUM_SIG, CELLS_PER_SIG_PHASE1),
+            calc_required_advices(MAX_NUM_SIG, CELLS_PER_SIG_PHASE2),
+        ];
+        #[cfg(not(feature = "batch-ecdsa"))]
+        let num_lookup_advice = [8];
+        #[cfg(feature = "batch-ecdsa")]
+        let num_lookup_advice = [8, 8];
 
         #[cfg(feature = "onephase")]
         log::info!("configuring ECDSA chip with single phase");
@@ -126,7 +157,7 @@ impl<F: Field> SubCircuitConfig<F> for SigCircuitConfig<F> {
             meta,
             FpStrategy::Simple,
             &num_advice,
-            &[8],
+            &num_lookup_advice,
             1,
             LOG_TOTAL_NUM_ROWS - 1,
             88,
@@ -188,11 +219,30 @@ impl<F: Field> SubCircuitConfig<F> for SigCircuitConfig<F> {
             input.into_iter().zip(table).collect()
         });
 
+        // The coefficient of the batched verification is derived from the evm word
+        // challenge, which is copied to the RLC column.
+        // Layout:
+        // | q_challenge |    rlc    |
+        // | ----------- | --------- |
+        // |      1      | challenge |
+        #[cfg(feature = "batch-ecdsa")]
+        let q_challenge = meta.selector();
+        #[cfg(feature = "batch-ecdsa")]
+        meta.create_gate("challenge of the batched verification", |meta| {
+            let q_challenge = meta.query_selector(q_challenge);
+            let challenge = meta.query_advice(rlc_column, Rotation::cur());
+            vec![q_challenge * (challenge - challenges.evm_word())]
+        });
+        #[cfg(not(feature = "batch-ecdsa"))]
+        let _ = challenges;
+
         Self {
             ecdsa_config,
             keccak_table,
             sig_table,
             q_keccak,
+            #[cfg(feature = "batch-ecdsa")]
+            q_challenge,
             rlc_column,
         }
     }
@@ -220,10 +270,8 @@ impl<F: Field> SubCircuit<F> for SigCircuit<F> {
     type Config = SigCircuitConfig<F>;
 
     fn new_from_block(block: &crate::witness::Block<F>) -> Self {
-        assert!(block.circuits_params.max_txs <= MAX_NUM_SIG);
-
         SigCircuit {
-            max_verif: MAX_NUM_SIG,
+            max_verif: block.circuits_params.max_num_sig,
             signatures: block.get_sign_data(true),
             _marker: Default::default(),
         }
@@ -258,17 +306,12 @@ impl<F: Field> SubCircuit<F> for SigCircuit<F> {
     fn min_num_rows_block(block: &crate::witness::Block<F>) -> (usize, usize) {
         let row_num = Self::min_num_rows();
 
-        let ecdsa_verif_count = block
-            .txs
-            .iter()
-            .filter(|tx| !tx.tx_type.is_l1_msg())
-            .count()
-            + block.precompile_events.get_ecrecover_events().len();
+        let ecdsa_verif_count = Self::num_signatures(block);
         // Reserve one ecdsa verification for padding tx such that the bad case in which some tx
-        // calls MAX_NUM_SIG - 1 ecrecover precompile won't happen. If that case happens, the sig
+        // calls max_num_sig - 1 ecrecover precompile won't happen. If that case happens, the sig
         // circuit won't have more space for the padding tx's ECDSA verification. Then the
         // prover won't be able to produce any valid proof.
-        let max_num_verif = MAX_NUM_SIG - 1;
+        let max_num_verif = block.circuits_params.max_num_sig.saturating_sub(1).max(1);
 
         // Instead of showing actual minimum row usage,
         // halo2-lib based circuits use min_row_num to represent a percentage of total-used capacity
@@ -289,6 +332,12 @@ impl<F: Field> SigCircuit<F> {
         }
     }
 
+    /// Return the number of signatures of the block without padding: the tx
+    /// signatures, the EIP-7702 authorizations and the ecrecover calls.
+    pub fn num_signatures(block: &crate::witness::Block<F>) -> usize {
+        block.get_sign_data(false).len()
+    }
+
     /// Return the minimum number of rows required to prove an input of a
     /// particular size.
     pub fn min_num_rows() -> usize {
@@ -370,10 +419,221 @@ impl<F: Field> SigCircuit<F> {
         );
         log::trace!("ECDSA res : {:?}", sig_is_valid);
 
-        // =======================================
-        // constrains v == y.is_oddness()
-        // =======================================
-        assert!(*v == 0 || *v == 1, "v is not boolean");
+        let assigned_y_is_odd = self.assign_y_parity(ctx, ecdsa_chip, &y_coord, *v);
+
+        Ok(AssignedECDSA {
+            pk: pk_assigned,
+            msg_hash,
+            integer_r,
+            integer_s,
+            v: assigned_y_is_odd,
+            sig_is_valid,
+        })
+    }
+
+    /// Same as `assign_ecdsa`, but instead of computing u1 * G + u2 * pk in
+    /// the circuit, loads the point R of x coordinate r and y parity v, and
+    /// returns the witnesses to check the group equation u1 * G + u2 * pk = R
+    /// in the batched verification.
+    ///
+    /// Unlike `assign_ecdsa`, the signature is enforced to be valid: an
+    /// invalid one fails the constraints of the batched verification.
+    #[cfg(feature = "batch-ecdsa")]
+    fn assign_ecdsa_deferred(
+        &self,
+        ctx: &mut Context<F>,
+        ecdsa_chip: &FpChip<F>,
+        sign_data: &SignData,
+    ) -> Result<(AssignedECDSA<F, FpChip<F>>, DeferredECDSA<F, FpChip<F>>), Error> {
+        log::trace!("start deferred ecdsa assignment");
+        let SignData {
+            signature,
+            pk,
+            msg: _,
+            msg_hash,
+        } = sign_data;
+        let (sig_r, sig_s, v) = signature;
+
+        // R is the point recovered from r and v, as in ecrecover
+        let r_point = Option::<Fp>::from(Fp::from_bytes(&sig_r.to_bytes())).and_then(|x| {
+            Option::<Fp>::from((x.square() * x + Secp256k1Affine::b()).sqrt()).map(|y| {
+                if bool::from(y.is_odd()) == (*v == 1) {
+                    (x, y)
+                } else {
+                    (x, -y)
+                }
+            })
+        });
+        let (r_x, r_y) = match r_point {
+            Some(point) => point,
+            None => {
+                error!(
+                    "invalid signature {:?} in the batched verification, r is not on the curve",
+                    signature
+                );
+                return Err(Error::Synthesis);
+            }
+        };
+
+        let ecc_chip = EccChip::<F, FpChip<F>>::construct(ecdsa_chip.clone());
+        let pk_assigned = ecc_chip.load_private(ctx, (Value::known(pk.x), Value::known(pk.y)));
+        ecc_chip.assert_is_on_curve::<Secp256k1Affine>(ctx, &pk_assigned);
+        let r_point = ecc_chip.load_private(ctx, (Value::known(r_x), Value::known(r_y)));
+        ecc_chip.assert_is_on_curve::<Secp256k1Affine>(ctx, &r_point);
+
+        let fq_chip = FqChip::construct(ecdsa_chip.range.clone(), 88, 3, modulus::<Fq>());
+        let integer_r =
+            fq_chip.load_private(ctx, FqChip::<F>::fe_to_witness(&Value::known(*sig_r)));
+        let integer_s =
+            fq_chip.load_private(ctx, FqChip::<F>::fe_to_witness(&Value::known(*sig_s)));
+        let msg_hash =
+            fq_chip.load_private(ctx, FqChip::<F>::fe_to_witness(&Value::known(*msg_hash)));
+
+        let (u1, u2) = ecdsa_verify_deferred::<F, Fp, Fq>(
+            &ecc_chip.field_chip,
+            ctx,
+            &r_point,
+            &integer_r,
+            &integer_s,
+            &msg_hash,
+        );
+
+        let assigned_y_is_odd = self.assign_y_parity(ctx, ecdsa_chip, &r_point.y, *v);
+        let sig_is_valid = ecdsa_chip.gate().load_constant(ctx, F::one());
+
+        Ok((
+            AssignedECDSA {
+                pk: pk_assigned,
+                msg_hash,
+                integer_r,
+                integer_s,
+                v: assigned_y_is_odd,
+                sig_is_valid,
+            },
+            DeferredECDSA { r_point, u1, u2 },
+        ))
+    }
+
+    /// Loads the coefficient of the batched verification from the evm word
+    /// challenge, copied to the RLC column at `offset`. The low 176 bits of the
+    /// challenge are used, as a scalar with limbs of [88, 88, 0] bits.
+    #[cfg(feature = "batch-ecdsa")]
+    fn load_batch_coefficient(
+        &self,
+        config: &SigCircuitConfig<F>,
+        ctx: &mut Context<F>,
+        ecdsa_chip: &FpChip<F>,
+        offset: usize,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<CRTInteger<F>, Error> {
+        let gate = ecdsa_chip.gate();
+        let challenge = challenges.evm_word();
+        let assigned_challenge = gate.load_witness(ctx, challenge);
+
+        config.q_challenge.enable(&mut ctx.region, offset)?;
+        let tmp_cell =
+            ctx.region
+                .assign_advice(|| "challenge", config.rlc_column, offset, || challenge)?;
+        ctx.region
+            .constrain_equal(assigned_challenge.cell, tmp_cell.cell())?;
+
+        // challenge = lo + 2^88 * mid + 2^176 * hi, where the decomposition is unique up
+        // to a wrap around the modulus of F
+        let shifts = [0, 88, 176];
+        let mask = (BigUint::from(1u64) << 88) - 1u64;
+        let [lo, mid, hi] = shifts.map(|shift| {
+            let limb =
+                challenge.map(|c| biguint_to_fe::<F>(&((fe_to_biguint(&c) >> shift) & &mask)));
+            gate.load_witness(ctx, limb)
+        });
+        ecdsa_chip.range.range_check(ctx, &lo, 88);
+        ecdsa_chip.range.range_check(ctx, &mid, 88);
+        ecdsa_chip
+            .range
+            .range_check(ctx, &hi, F::NUM_BITS as usize - 176);
+        let powers = shifts
+            .map(|shift| QuantumCell::Constant(biguint_to_fe(&(BigUint::from(1u64) << shift))))
+            .to_vec();
+        let recomposed = gate.inner_product(
+            ctx,
+            vec![
+                QuantumCell::Existing(lo),
+                QuantumCell::Existing(mid),
+                QuantumCell::Existing(hi),
+            ],
+            powers.clone(),
+        );
+        gate.assert_equal(
+            ctx,
+            QuantumCell::Existing(recomposed),
+            QuantumCell::Existing(assigned_challenge),
+        );
+
+        let native = gate.inner_product(
+            ctx,
+            vec![QuantumCell::Existing(lo), QuantumCell::Existing(mid)],
+            powers[..2].to_vec(),
+        );
+        let zero = gate.load_zero(ctx);
+        let value = challenge
+            .map(|c| BigInt::from(fe_to_biguint(&c) & ((BigUint::from(1u64) << 176) - 1u64)));
+        Ok(CRTInteger::construct(
+            OverflowInteger::construct(vec![lo, mid, zero], 88),
+            native,
+            value,
+        ))
+    }
+
+    /// Checks the group equations of the deferred signatures at once, with the
+    /// coefficient from the challenge at `offset` of the RLC column.
+    #[cfg(feature = "batch-ecdsa")]
+    fn assign_batch_verify(
+        &self,
+        config: &SigCircuitConfig<F>,
+        ctx: &mut Context<F>,
+        ecdsa_chip: &FpChip<F>,
+        offset: usize,
+        assigned_ecdsas: &[AssignedECDSA<F, FpChip<F>>],
+        deferred_ecdsas: &[DeferredECDSA<F, FpChip<F>>],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        if deferred_ecdsas.is_empty() {
+            return Ok(());
+        }
+        let coefficient =
+            self.load_batch_coefficient(config, ctx, ecdsa_chip, offset, challenges)?;
+
+        let pubkeys = assigned_ecdsas.iter().map(|a| a.pk.clone()).collect_vec();
+        let r_points = deferred_ecdsas
+            .iter()
+            .map(|d| d.r_point.clone())
+            .collect_vec();
+        let u1s = deferred_ecdsas.iter().map(|d| d.u1.clone()).collect_vec();
+        let u2s = deferred_ecdsas.iter().map(|d| d.u2.clone()).collect_vec();
+        ecdsa_batch_verify::<F, Fp, Fq, Secp256k1Affine>(
+            ecdsa_chip,
+            ctx,
+            &coefficient,
+            &pubkeys,
+            &r_points,
+            &u1s,
+            &u2s,
+            4,
+            4,
+        );
+        log::trace!("finished batched verification");
+        Ok(())
+    }
+
+    /// Constrains v == y.is_oddness(), returning the assigned v.
+    fn assign_y_parity(
+        &self,
+        ctx: &mut Context<F>,
+        ecdsa_chip: &FpChip<F>,
+        y_coord: &CRTInteger<F>,
+        v: u8,
+    ) -> AssignedValue<F> {
+        assert!(v == 0 || v == 1, "v is not boolean");
 
         // we constrain:
         // - v + 2*tmp = y where y is already range checked (88 bits)
@@ -383,7 +643,7 @@ impl<F: Field> SigCircuit<F> {
 
         let gate = ecdsa_chip.gate();
 
-        let assigned_y_is_odd = gate.load_witness(ctx, Value::known(F::from(*v as u64)));
+        let assigned_y_is_odd = gate.load_witness(ctx, Value::known(F::from(v as u64)));
         gate.assert_bit(ctx, assigned_y_is_odd);
 
         // the last 88 bits of y
@@ -392,7 +652,7 @@ impl<F: Field> SigCircuit<F> {
         assigned_y_limb.value().map(|&x| y_value = x);
 
         // y_tmp = (y_value - y_last_bit)/2
-        let y_tmp = (y_value - F::from(*v as u64)) * F::TWO_INV;
+        let y_tmp = (y_value - F::from(v as u64)) * F::TWO_INV;
         let assigned_y_tmp = gate.load_witness(ctx, Value::known(y_tmp));
 
         // y_tmp_double = (y_value - y_last_bit)
@@ -414,19 +674,9 @@ impl<F: Field> SigCircuit<F> {
         );
 
         // last step we want to constrain assigned_y_tmp is 87 bits
-        ecc_chip
-            .field_chip
-            .range
-            .range_check(ctx, &assigned_y_tmp, 88);
+        ecdsa_chip.range.range_check(ctx, &assigned_y_tmp, 88);
 
-        Ok(AssignedECDSA {
-            pk: pk_assigned,
-            msg_hash,
-            integer_r,
-            integer_s,
-            v: assigned_y_is_odd,
-            sig_is_valid,
-        })
+        assigned_y_is_odd
     }
 
     fn enable_keccak_lookup(
@@ -739,6 +989,13 @@ impl<F: Field> SigCircuit<F> {
         signatures: &[SignData],
         challenges: &Challenges<Value<F>>,
     ) -> Result<Vec<AssignedSignatureVerify<F>>, Error> {
+        if self.max_verif > MAX_NUM_SIG {
+            error!(
+                "max_verif = {} > MAX_NUM_SIG = {}",
+                self.max_verif, MAX_NUM_SIG
+            );
+            return Err(Error::Synthesis);
+        }
         if signatures.len() > self.max_verif {
             error!(
                 "signatures.len() = {} > max_verif = {}",
@@ -764,6 +1021,7 @@ impl<F: Field> SigCircuit<F> {
                 // step 1: assert the signature is valid in circuit
                 // ================================================
 
+                #[cfg(not(feature = "batch-ecdsa"))]
                 let assigned_ecdsas = signatures
                     .iter()
                     .chain(
@@ -772,6 +1030,18 @@ impl<F: Field> SigCircuit<F> {
                     )
                     .map(|sign_data| self.assign_ecdsa(&mut ctx, ecdsa_chip, sign_data))
                     .collect::<Result<Vec<AssignedECDSA<F, FpChip<F>>>, Error>>()?;
+                // the group equations are checked in step 3 once the points are fixed
+                #[cfg(feature = "batch-ecdsa")]
+                let (assigned_ecdsas, deferred_ecdsas): (Vec<_>, Vec<_>) = signatures
+                    .iter()
+                    .chain(
+                        std::iter::repeat(&SignData::default())
+                            .take(self.max_verif - signatures.len()),
+                    )
+                    .map(|sign_data| self.assign_ecdsa_deferred(&mut ctx, ecdsa_chip, sign_data))
+                    .collect::<Result<Vec<_>, Error>>()?
+                    .into_iter()
+                    .unzip();
 
                 // ================================================
                 // step 2: decompose the keys and messages
@@ -831,6 +1101,21 @@ impl<F: Field> SigCircuit<F> {
                                 Error,
                             >>()?;
 
+                // ================================================
+                // step 3b: batched verification of the group equations
+                // ================================================
+                #[cfg(feature = "batch-ecdsa")]
+                self.assign_batch_verify(
+                    config,
+                    &mut ctx,
+                    ecdsa_chip,
+                    // the RLC column is used by the keccak lookups up to this offset
+                    3 * self.max_verif,
+                    &assigned_ecdsas,
+                    &deferred_ecdsas,
+                    challenges,
+                )?;
+
                 // ================================================
                 // step 4: deferred keccak checks
                 // ================================================
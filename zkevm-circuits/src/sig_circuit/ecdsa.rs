@@ -7,6 +7,8 @@ use halo2_base::{
     AssignedValue, Context,
     QuantumCell::Existing,
 };
+#[cfg(feature = "batch-ecdsa")]
+use halo2_ecc::ecc::EccChip;
 use halo2_ecc::{
     bigint::{big_less_than, CRTInteger},
     ecc::{ec_add_unequal, fixed_base, scalar_multiply, EcPoint},
@@ -128,3 +130,140 @@ where
         .and(ctx, Existing(res4), Existing(equal_check));
     (res5, sum.y)
 }
+
+// Checks the part of the verification which is local to a signature, given the
+// point R = u1 * G + u2 * pubkey supplied by the prover:
+// - r, s are in [1, n - 1]
+// - R.x == r as integers, with the same caveat on p and n as above
+// returns
+// - u1 = m s^{-1} mod n and u2 = r s^{-1} mod n, to check the group equation in
+//   `ecdsa_batch_verify`
+//
+// Unlike `ecdsa_verify_no_pubkey_check`, the signature is enforced to be valid.
+#[cfg(feature = "batch-ecdsa")]
+pub(crate) fn ecdsa_verify_deferred<F: PrimeField, CF: PrimeField, SF: PrimeField>(
+    base_chip: &FpConfig<F, CF>,
+    ctx: &mut Context<F>,
+    r_point: &EcPoint<F, <FpConfig<F, CF> as FieldChip<F>>::FieldPoint>,
+    r: &CRTInteger<F>,
+    s: &CRTInteger<F>,
+    msghash: &CRTInteger<F>,
+) -> (CRTInteger<F>, CRTInteger<F>) {
+    let scalar_chip = FpConfig::<F, SF>::construct(
+        base_chip.range.clone(),
+        base_chip.limb_bits,
+        base_chip.num_limbs,
+        modulus::<SF>(),
+    );
+    let gate = base_chip.range.gate();
+
+    // check r,s are in [1, n - 1]
+    let r_valid = scalar_chip.is_soft_nonzero(ctx, r);
+    let s_valid = scalar_chip.is_soft_nonzero(ctx, s);
+    gate.assert_is_const(ctx, &r_valid, F::one());
+    gate.assert_is_const(ctx, &s_valid, F::one());
+
+    // compute u1 = m s^{-1} mod n and u2 = r s^{-1} mod n
+    let u1 = scalar_chip.divide(ctx, msghash, s);
+    let u2 = scalar_chip.divide(ctx, r, s);
+
+    // check (r mod n) == R.x as integers
+    base_chip.enforce_less_than_p(ctx, r_point.x());
+    let equal_check = base_chip.is_equal(ctx, r_point.x(), r);
+    gate.assert_is_const(ctx, &equal_check, F::one());
+
+    (u1, u2)
+}
+
+// Checks the group equations u1_i * G + u2_i * pubkey_i = R_i of all the
+// signatures at once with the random linear combination
+//     sum(c_i * u1_i) * G + sum(c_i * u2_i * pubkey_i) - sum(c_i * R_i) = 0
+// where c_i = coefficient^(i + 1), with a single fixed base multiplication and a
+// single multi-scalar multiplication.
+//
+// The coefficient must be derived from a challenge of the previous phase, so
+// that the points are fixed before it is known.
+//
+// The padding signatures repeat the same pubkey and R in the multi-scalar
+// multiplication. Its strict unequal additions still hold for them, as every
+// accumulator and cached point is offset by a random point of the prover.
+#[allow(clippy::too_many_arguments)]
+#[cfg(feature = "batch-ecdsa")]
+pub(crate) fn ecdsa_batch_verify<F: PrimeField, CF: PrimeField, SF: PrimeField, GA>(
+    base_chip: &FpConfig<F, CF>,
+    ctx: &mut Context<F>,
+    coefficient: &CRTInteger<F>,
+    pubkeys: &[EcPoint<F, <FpConfig<F, CF> as FieldChip<F>>::FieldPoint>],
+    r_points: &[EcPoint<F, <FpConfig<F, CF> as FieldChip<F>>::FieldPoint>],
+    u1s: &[CRTInteger<F>],
+    u2s: &[CRTInteger<F>],
+    var_window_bits: usize,
+    fixed_window_bits: usize,
+) where
+    GA: CurveAffineExt<Base = CF, ScalarExt = SF>,
+{
+    assert!(!pubkeys.is_empty(), "nothing to verify");
+    assert_eq!(pubkeys.len(), r_points.len());
+    assert_eq!(pubkeys.len(), u1s.len());
+    assert_eq!(pubkeys.len(), u2s.len());
+
+    let scalar_chip = FpConfig::<F, SF>::construct(
+        base_chip.range.clone(),
+        base_chip.limb_bits,
+        base_chip.num_limbs,
+        modulus::<SF>(),
+    );
+    let ecc_chip = EccChip::<F, FpConfig<F, CF>>::construct(base_chip.clone());
+
+    let mut c = coefficient.clone();
+    let mut u1_sum: Option<CRTInteger<F>> = None;
+    let mut points = Vec::with_capacity(2 * pubkeys.len());
+    let mut scalars = Vec::with_capacity(2 * pubkeys.len());
+    let mut neg_r_points = Vec::with_capacity(r_points.len());
+    let mut r_scalars = Vec::with_capacity(r_points.len());
+    for (i, (((pubkey, r_point), u1), u2)) in
+        pubkeys.iter().zip(r_points).zip(u1s).zip(u2s).enumerate()
+    {
+        if i > 0 {
+            c = scalar_chip.mul(ctx, &c, coefficient);
+        }
+
+        // accumulate c_i * u1_i for the fixed base multiplication
+        let c_u1 = scalar_chip.mul(ctx, &c, u1);
+        u1_sum = Some(match u1_sum {
+            Some(sum) => {
+                let sum = scalar_chip.add_no_carry(ctx, &sum, &c_u1);
+                scalar_chip.carry_mod(ctx, &sum)
+            }
+            None => c_u1,
+        });
+
+        let c_u2 = scalar_chip.mul(ctx, &c, u2);
+        points.push(pubkey.clone());
+        scalars.push(c_u2.truncation.limbs);
+        neg_r_points.push(ecc_chip.negate(ctx, r_point));
+        r_scalars.push(c.truncation.limbs.clone());
+    }
+    points.extend(neg_r_points);
+    scalars.extend(r_scalars);
+
+    // sum(c_i * u2_i * pubkey_i) - sum(c_i * R_i) == -sum(c_i * u1_i) * G
+    let msm = ecc_chip.variable_base_msm::<GA>(
+        ctx,
+        &points,
+        &scalars,
+        base_chip.limb_bits,
+        var_window_bits,
+    );
+    let u1_sum = u1_sum.expect("at least one signature");
+    let u1_mul = fixed_base::scalar_multiply::<F, _, _>(
+        base_chip,
+        ctx,
+        &GA::generator(),
+        &u1_sum.truncation.limbs,
+        base_chip.limb_bits,
+        fixed_window_bits,
+    );
+    let neg_u1_mul = ecc_chip.negate(ctx, &u1_mul);
+    ecc_chip.assert_equal(ctx, &msm, &neg_u1_mul);
+}
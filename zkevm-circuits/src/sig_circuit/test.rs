@@ -52,6 +52,98 @@ fn sign_verify() {
     }
 }
 
+#[test]
+fn sign_verify_over_capacity() {
+    use super::utils::LOG_TOTAL_NUM_ROWS;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+    let mut rng = XorShiftRng::seed_from_u64(1);
+    let signatures = (0..2)
+        .map(|_| {
+            let (sk, pk) = gen_key_pair(&mut rng);
+            let msg_hash = gen_msg_hash(&mut rng);
+            let (r, s, v) = sign_with_rng(&mut rng, sk, msg_hash);
+            SignData {
+                signature: (r, s, v),
+                pk,
+                msg: Default::default(),
+                msg_hash,
+            }
+        })
+        .collect();
+
+    let circuit = SigCircuit::<Fr> {
+        max_verif: 1,
+        signatures,
+        _marker: PhantomData,
+    };
+    let k = LOG_TOTAL_NUM_ROWS as u32;
+    assert!(MockProver::run(k, &circuit, vec![]).is_err());
+}
+
+#[test]
+fn sign_verify_with_padding() {
+    use super::utils::LOG_TOTAL_NUM_ROWS;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+    let mut rng = XorShiftRng::seed_from_u64(1);
+    // the padding signatures repeat the same pk and R in the batched verification
+    for num_sig in [0, 1, 4] {
+        let signatures = (0..num_sig)
+            .map(|_| {
+                let (sk, pk) = gen_key_pair(&mut rng);
+                let msg_hash = gen_msg_hash(&mut rng);
+                let (r, s, v) = sign_with_rng(&mut rng, sk, msg_hash);
+                SignData {
+                    signature: (r, s, v),
+                    pk,
+                    msg: Default::default(),
+                    msg_hash,
+                }
+            })
+            .collect();
+
+        let k = LOG_TOTAL_NUM_ROWS as u32;
+        run::<Fr>(k, 8, signatures);
+    }
+}
+
+#[cfg(feature = "batch-ecdsa")]
+#[test]
+fn sign_verify_tampered_r() {
+    use super::utils::LOG_TOTAL_NUM_ROWS;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+    let mut rng = XorShiftRng::seed_from_u64(1);
+    let signatures = (0..2)
+        .map(|i| {
+            let (sk, pk) = gen_key_pair(&mut rng);
+            let msg_hash = gen_msg_hash(&mut rng);
+            let (r, s, v) = sign_with_rng(&mut rng, sk, msg_hash);
+            // flipping v of the second signature replaces R by -R, which still has
+            // R.x == r but fails the group equation
+            SignData {
+                signature: (r, s, if i == 1 { v ^ 1 } else { v }),
+                pk,
+                msg: Default::default(),
+                msg_hash,
+            }
+        })
+        .collect();
+
+    let circuit = SigCircuit::<Fr> {
+        max_verif: 2,
+        signatures,
+        _marker: PhantomData,
+    };
+    let k = LOG_TOTAL_NUM_ROWS as u32;
+    let prover = match MockProver::run(k, &circuit, vec![]) {
+        Ok(prover) => prover,
+        Err(e) => panic!("{e:#?}"),
+    };
+    assert!(prover.verify().is_err());
+}
+
 // Generate a test key pair
 fn gen_key_pair(rng: impl RngCore) -> (secp256k1::Fq, Secp256k1Affine) {
     // generate a valid signature
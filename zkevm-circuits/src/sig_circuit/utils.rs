@@ -10,20 +10,36 @@ use halo2_proofs::{
     halo2curves::secp256k1::{Fp, Fq},
 };
 
-// Hard coded parameters.
-// FIXME: allow for a configurable param.
+// Max number of signatures the ecdsa chip is configured for. The capacity of a
+// block is `CircuitsParams::max_num_sig`, which must not exceed it.
 pub(super) const MAX_NUM_SIG: usize = 128;
 // Each ecdsa signature requires 456786 cells
 // We set CELLS_PER_SIG = 457000 to allows for a few buffer
 pub(super) const CELLS_PER_SIG: usize = 457000;
+// With batched verification, each signature requires the cells in the first
+// phase to load pk and R, check them on the curve and check R against r and v,
+// and the cells in the second phase for the RLC and its share of the batched
+// multi-scalar multiplication.
+// These are not measured but derived from CELLS_PER_SIG above, of which the two
+// scalar multiplications are the bulk. The first phase does none of them and
+// takes at most a tenth of it. The second phase takes about a third of it: the
+// window additions of two points, while the doublings are shared by all the
+// points of the multi-scalar multiplication. Both are rounded up; if they are
+// too small, `sign_verify` runs out of rows at MAX_NUM_SIG with `batch-ecdsa`,
+// which CI runs. The cells used per phase are printed by the ECDSA context
+// stats with `RUST_LOG=info`.
+#[cfg(feature = "batch-ecdsa")]
+pub(super) const CELLS_PER_SIG_PHASE1: usize = 46000;
+#[cfg(feature = "batch-ecdsa")]
+pub(super) const CELLS_PER_SIG_PHASE2: usize = 160000;
 // Total number of rows allocated for ecdsa chip
 pub(super) const LOG_TOTAL_NUM_ROWS: usize = 20;
 // Max number of columns allowed
 pub(super) const COLUMN_NUM_LIMIT: usize = 150;
 
-pub(super) fn calc_required_advices(num_verif: usize) -> usize {
+pub(super) fn calc_required_advices(num_verif: usize, cells_per_sig: usize) -> usize {
     let mut num_adv = 1;
-    let total_cells = num_verif * CELLS_PER_SIG;
+    let total_cells = num_verif * cells_per_sig;
     let row_num = 1 << LOG_TOTAL_NUM_ROWS;
     while num_adv < COLUMN_NUM_LIMIT {
         if num_adv * row_num > total_cells {
@@ -53,6 +69,15 @@ pub(crate) struct AssignedECDSA<F: Field, FC: FieldChip<F>> {
     pub(super) sig_is_valid: AssignedValue<F>,
 }
 
+/// Witnesses of a signature whose group equation `u1 * G + u2 * pk = R` is
+/// deferred to the batched verification.
+#[cfg(feature = "batch-ecdsa")]
+pub(crate) struct DeferredECDSA<F: Field, FC: FieldChip<F>> {
+    pub(super) r_point: EcPoint<F, FC::FieldPoint>,
+    pub(super) u1: CRTInteger<F>,
+    pub(super) u2: CRTInteger<F>,
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct AssignedSignatureVerify<F: Field> {
     pub(crate) address: AssignedValue<F>,
@@ -943,6 +943,119 @@ fn bad_initial_tx_refund_value() {
     );
 }
 
+#[test]
+fn transient_storage_ok() {
+    let account_address = address!("0x0000000000000000000000000000000000005c5d");
+    let rows = vec![
+        Rw::TransientStorage {
+            rw_counter: 1,
+            is_write: true,
+            tx_id: 1,
+            account_address,
+            storage_key: U256::from(7),
+            value: U256::from(42),
+            value_prev: U256::zero(),
+        },
+        Rw::TransientStorage {
+            rw_counter: 2,
+            is_write: false,
+            tx_id: 1,
+            account_address,
+            storage_key: U256::from(7),
+            value: U256::from(42),
+            value_prev: U256::from(42),
+        },
+        // The value is reset in the next tx.
+        Rw::TransientStorage {
+            rw_counter: 3,
+            is_write: false,
+            tx_id: 2,
+            account_address,
+            storage_key: U256::from(7),
+            value: U256::zero(),
+            value_prev: U256::zero(),
+        },
+    ];
+
+    assert_eq!(verify(rows), Ok(()));
+}
+
+#[test]
+fn bad_initial_transient_storage_value() {
+    let rows = vec![Rw::TransientStorage {
+        rw_counter: 1,
+        is_write: true,
+        tx_id: 2,
+        account_address: address!("0x0000000000000000000000000000000000005c5d"),
+        storage_key: U256::from(7),
+        value: U256::from(42),
+        value_prev: U256::zero(),
+    }];
+
+    let overrides = HashMap::from([
+        ((AdviceColumn::InitialValue, 0), Fr::from(10)),
+        ((AdviceColumn::ValuePrev, 0), Fr::from(10)),
+    ]);
+
+    assert_error_matches(
+        verify_with_overrides(rows, overrides),
+        "initial TransientStorage value is 0",
+    );
+}
+
+#[test]
+fn tx_created_account_ok() {
+    let account_address = address!("0x0000000000000000000000000000000000c4ea7e");
+    let rows = vec![
+        Rw::TxCreatedAccount {
+            rw_counter: 1,
+            is_write: true,
+            tx_id: 1,
+            account_address,
+            is_created: true,
+            is_created_prev: false,
+        },
+        Rw::TxCreatedAccount {
+            rw_counter: 2,
+            is_write: false,
+            tx_id: 1,
+            account_address,
+            is_created: true,
+            is_created_prev: true,
+        },
+        // The account is no longer created in the next tx.
+        Rw::TxCreatedAccount {
+            rw_counter: 3,
+            is_write: false,
+            tx_id: 2,
+            account_address,
+            is_created: false,
+            is_created_prev: false,
+        },
+    ];
+
+    assert_eq!(verify(rows), Ok(()));
+}
+
+#[test]
+fn bad_initial_tx_created_account_value() {
+    let rows = vec![Rw::TxCreatedAccount {
+        rw_counter: 1,
+        is_write: false,
+        tx_id: 2,
+        account_address: address!("0x0000000000000000000000000000000000c4ea7e"),
+        is_created: true,
+        is_created_prev: true,
+    }];
+
+    let overrides = HashMap::from([((AdviceColumn::InitialValue, 0), Fr::from(1))]);
+
+    assert_error_matches(
+        verify_with_overrides(rows, overrides),
+        "initial TxCreatedAccount value is false",
+    );
+}
+
 #[test]
 fn bad_initial_tx_log_value() {
     let rows = vec![Rw::TxLog {
@@ -14,6 +14,9 @@
 //! - [x] Copy Circuit
 //! - [x] Exponentiation Circuit
 //! - [ ] Keccak Circuit
+//! - [x] SHA256 Circuit
+//! - [x] RIPEMD160 Circuit
+//! - [x] BLAKE2F Circuit
 //! - [ ] MPT Circuit
 //! - [x] PublicInputs Circuit
 //!
@@ -49,9 +52,26 @@
 //!   - [x] Bytecode Circuit
 //!   - [x] Tx Circuit
 //!   - [ ] MPT Circuit
+//! - [x] SHA256 Table
+//!   - [x] SHA256 Circuit
+//!   - [x] EVM Circuit
+//! - [x] RIPEMD160 Table
+//!   - [x] RIPEMD160 Circuit
+//!   - [x] EVM Circuit
+//! - [x] BLAKE2F Table
+//!   - [x] BLAKE2F Circuit
+//!   - [x] EVM Circuit
 
+mod capacity_checker;
+mod fingerprint;
+mod params;
+mod split;
 #[cfg(any(feature = "test", test))]
 pub(crate) mod test;
+pub use capacity_checker::{CircuitCapacityChecker, RowUsage};
+pub use fingerprint::{TableFingerprintConfig, NUM_FINGERPRINT_CHALLENGES};
+pub use params::{CircuitsParamsError, CircuitsParamsReport};
+pub use split::{DetachedCircuit, DetachedSubCircuit, SplitSuperCircuit, DETACHED_CIRCUITS};
 
 #[cfg(feature = "poseidon-codehash")]
 use crate::bytecode_circuit::circuit::to_poseidon_hash::{
@@ -60,25 +80,31 @@ use crate::bytecode_circuit::circuit::to_poseidon_hash::{
 #[cfg(not(feature = "poseidon-codehash"))]
 use crate::bytecode_circuit::circuit::BytecodeCircuitConfig;
 use crate::{
+    blake2f_circuit::{Blake2fCircuit, Blake2fCircuitConfig, Blake2fCircuitConfigArgs},
     bytecode_circuit::circuit::{BytecodeCircuit, BytecodeCircuitConfigArgs},
     copy_circuit::{CopyCircuit, CopyCircuitConfig, CopyCircuitConfigArgs},
     ecc_circuit::{EccCircuit, EccCircuitConfig, EccCircuitConfigArgs},
     evm_circuit::{EvmCircuit, EvmCircuitConfig, EvmCircuitConfigArgs},
     exp_circuit::{ExpCircuit, ExpCircuitArgs, ExpCircuitConfig},
     keccak_circuit::{KeccakCircuit, KeccakCircuitConfig, KeccakCircuitConfigArgs},
-    modexp_circuit::{ModExpCircuit, ModExpCircuitConfig},
+    modexp_circuit::{ModExpCircuit, ModExpCircuitConfig, ModExpCircuitConfigArgs},
     pi_circuit::{PiCircuit, PiCircuitConfig, PiCircuitConfigArgs},
     poseidon_circuit::{PoseidonCircuit, PoseidonCircuitConfig, PoseidonCircuitConfigArgs},
+    ripemd160_circuit::{Ripemd160Circuit, Ripemd160CircuitConfig, Ripemd160CircuitConfigArgs},
     rlp_circuit_fsm::{RlpCircuit, RlpCircuitConfig, RlpCircuitConfigArgs},
+    sha256_circuit::{Sha256Circuit, Sha256CircuitConfig, Sha256CircuitConfigArgs},
     sig_circuit::{SigCircuit, SigCircuitConfig, SigCircuitConfigArgs},
     state_circuit::{StateCircuit, StateCircuitConfig, StateCircuitConfigArgs},
     table::{
-        BlockTable, BytecodeTable, CopyTable, EccTable, ExpTable, KeccakTable, ModExpTable,
-        MptTable, PoseidonTable, PowOfRandTable, RlpFsmRlpTable as RlpTable, RwTable, SigTable,
-        TxTable, U16Table, U8Table,
+        Blake2fTable, BlockTable, BytecodeTable, CopyTable, EccTable, ExpTable, KeccakTable,
+        ModExpBytesTable, ModExpTable, MptTable, PoseidonTable, PowOfRandTable, Ripemd160Table,
+        RlpFsmRlpTable as RlpTable, RwTable, Sha256Table, SigTable, TxTable, U16Table, U8Table,
     },
     tx_circuit::{TxCircuit, TxCircuitConfig, TxCircuitConfigArgs},
-    util::{circuit_stats, log2_ceil, Challenges, SubCircuit, SubCircuitConfig},
+    util::{
+        circuit_stats, key_store::CircuitDigest, log2_ceil, Challenges, SubCircuit,
+        SubCircuitConfig,
+    },
     witness::{block_convert, Block, Transaction},
 };
 
@@ -112,7 +138,8 @@ pub struct SuperCircuitConfig<F: Field> {
     state_circuit: StateCircuitConfig<F>,
     tx_circuit: TxCircuitConfig<F>,
     sig_circuit: SigCircuitConfig<F>,
-    modexp_circuit: ModExpCircuitConfig,
+    modexp_table: ModExpTable,
+    modexp_circuit: Option<ModExpCircuitConfig>,
     ecc_circuit: EccCircuitConfig<F>,
     #[cfg(not(feature = "poseidon-codehash"))]
     bytecode_circuit: BytecodeCircuitConfig<F>,
@@ -120,10 +147,15 @@ pub struct SuperCircuitConfig<F: Field> {
     bytecode_circuit: ToHashBlockCircuitConfig<F, HASHBLOCK_BYTES_IN_FIELD>,
     copy_circuit: CopyCircuitConfig<F>,
     keccak_circuit: KeccakCircuitConfig<F>,
+    sha256_circuit: Sha256CircuitConfig<F>,
+    ripemd160_circuit: Ripemd160CircuitConfig<F>,
+    blake2f_circuit: Blake2fCircuitConfig<F>,
     poseidon_circuit: PoseidonCircuitConfig<F>,
     pi_circuit: PiCircuitConfig<F>,
-    exp_circuit: ExpCircuitConfig<F>,
+    exp_table: ExpTable,
+    exp_circuit: Option<ExpCircuitConfig<F>>,
     rlp_circuit: RlpCircuitConfig<F>,
+    table_fingerprint: Option<TableFingerprintConfig<F>>,
     /// Mpt Circuit
     #[cfg(feature = "zktrie")]
     mpt_circuit: MptCircuitConfig<F>,
@@ -139,6 +171,10 @@ pub struct SuperCircuitConfigArgs {
     pub max_inner_blocks: usize,
     /// Mock randomness
     pub mock_randomness: u64,
+    /// Whether to leave out the exp and modexp circuits, which are proven
+    /// apart as [`DetachedCircuit`]s. Their tables are then loaded from the
+    /// witness and fingerprinted.
+    pub detach_circuits: bool,
     /// Challenges
     pub challenges: crate::util::Challenges,
 }
@@ -154,6 +190,7 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
             max_calldata,
             max_inner_blocks,
             mock_randomness: _mock_randomness,
+            detach_circuits,
             challenges,
         }: Self::ConfigArgs,
     ) -> Self {
@@ -188,10 +225,16 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
         log_circuit_info(meta, "keccak table");
         let sig_table = SigTable::construct(meta);
         log_circuit_info(meta, "sig table");
-        let modexp_table = ModExpTable::construct(meta);
+        let modexp_table = ModExpTable::construct(meta, &challenges_expr);
         log_circuit_info(meta, "modexp table");
         let ecc_table = EccTable::construct(meta);
         log_circuit_info(meta, "ecc table");
+        let sha256_table = Sha256Table::construct(meta);
+        log_circuit_info(meta, "sha256 table");
+        let ripemd160_table = Ripemd160Table::construct(meta);
+        log_circuit_info(meta, "ripemd160 table");
+        let blake2f_table = Blake2fTable::construct(meta);
+        log_circuit_info(meta, "blake2f table");
         let pow_of_rand_table = PowOfRandTable::construct(meta, &challenges_expr);
         log_circuit_info(meta, "power of randomness table");
 
@@ -209,6 +252,33 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
         );
         log_circuit_info(meta, "keccak circuit");
 
+        let sha256_circuit = Sha256CircuitConfig::new(
+            meta,
+            Sha256CircuitConfigArgs {
+                sha256_table: sha256_table.clone(),
+                challenges: challenges_expr.clone(),
+            },
+        );
+        log_circuit_info(meta, "sha256 circuit");
+
+        let ripemd160_circuit = Ripemd160CircuitConfig::new(
+            meta,
+            Ripemd160CircuitConfigArgs {
+                ripemd160_table: ripemd160_table.clone(),
+                challenges: challenges_expr.clone(),
+            },
+        );
+        log_circuit_info(meta, "ripemd160 circuit");
+
+        let blake2f_circuit = Blake2fCircuitConfig::new(
+            meta,
+            Blake2fCircuitConfigArgs {
+                blake2f_table: blake2f_table.clone(),
+                challenges: challenges_expr.clone(),
+            },
+        );
+        log_circuit_info(meta, "blake2f circuit");
+
         let poseidon_circuit =
             PoseidonCircuitConfig::new(meta, PoseidonCircuitConfigArgs { poseidon_table });
         log_circuit_info(meta, "poseidon circuit");
@@ -245,6 +315,10 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
                 keccak_table: keccak_table.clone(),
                 rlp_table,
                 sig_table,
+                rw_table,
+                pow_of_rand_table,
+                poseidon_table,
+                bytecode_table: bytecode_table.clone(),
                 u8_table,
                 u16_table,
                 challenges: challenges_expr.clone(),
@@ -301,7 +375,16 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
         #[cfg(feature = "zktrie")]
         log_circuit_info(meta, "zktrie circuit");
 
-        let modexp_circuit = ModExpCircuitConfig::new(meta, modexp_table);
+        let modexp_circuit = (!detach_circuits).then(|| {
+            ModExpCircuitConfig::new(
+                meta,
+                ModExpCircuitConfigArgs {
+                    modexp_table,
+                    u16_table,
+                    challenges: challenges_expr.clone(),
+                },
+            )
+        });
         log_circuit_info(meta, "modexp circuit");
         let state_circuit = StateCircuitConfig::new(
             meta,
@@ -313,13 +396,15 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
         );
         log_circuit_info(meta, "state circuit");
 
-        let exp_circuit = ExpCircuitConfig::new(
-            meta,
-            ExpCircuitArgs {
-                exp_table,
-                u16_table,
-            },
-        );
+        let exp_circuit = (!detach_circuits).then(|| {
+            ExpCircuitConfig::new(
+                meta,
+                ExpCircuitArgs {
+                    exp_table,
+                    u16_table,
+                },
+            )
+        });
         log_circuit_info(meta, "exp circuit");
 
         let evm_circuit = EvmCircuitConfig::new(
@@ -336,11 +421,21 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
                 sig_table,
                 modexp_table,
                 ecc_table,
+                sha256_table,
+                ripemd160_table,
+                blake2f_table,
                 pow_of_rand_table,
             },
         );
         log_circuit_info(meta, "evm circuit");
 
+        // The fingerprints are in the same order as the tables loaded by
+        // `SuperCircuit::synthesize_detached_tables`.
+        let table_fingerprint = detach_circuits.then(|| {
+            TableFingerprintConfig::configure(meta, &[&exp_table, &ModExpBytesTable(modexp_table)])
+        });
+        log_circuit_info(meta, "table fingerprint");
+
         // Sig Circuit and ECC Circuit use halo2-lib's vertifcal assignments gates
         // and need to be configured after Circuits with higher counts of unique rotation queries
         // (ex. Keccak, EVM) to avoid assigning advice values into blinding area.
@@ -381,14 +476,20 @@ impl SubCircuitConfig<Fr> for SuperCircuitConfig<Fr> {
             copy_circuit,
             bytecode_circuit,
             keccak_circuit,
+            sha256_circuit,
+            ripemd160_circuit,
+            blake2f_circuit,
             poseidon_circuit,
             pi_circuit,
             rlp_circuit,
             tx_circuit,
+            exp_table,
             exp_circuit,
             sig_circuit,
+            modexp_table,
             modexp_circuit,
             ecc_circuit,
+            table_fingerprint,
             #[cfg(feature = "zktrie")]
             mpt_circuit,
         }
@@ -432,6 +533,12 @@ pub struct SuperCircuit<
     pub exp_circuit: ExpCircuit<F>,
     /// Keccak Circuit
     pub keccak_circuit: KeccakCircuit<F>,
+    /// SHA256 Circuit
+    pub sha256_circuit: Sha256Circuit<F>,
+    /// RIPEMD160 Circuit
+    pub ripemd160_circuit: Ripemd160Circuit<F>,
+    /// BLAKE2F Circuit
+    pub blake2f_circuit: Blake2fCircuit<F>,
     /// Poseidon hash Circuit
     pub poseidon_circuit: PoseidonCircuit<F>,
     /// Sig Circuit
@@ -472,6 +579,9 @@ impl<
         let bytecode = BytecodeCircuit::min_num_rows_block(block);
         let copy = CopyCircuit::min_num_rows_block(block);
         let keccak = KeccakCircuit::min_num_rows_block(block);
+        let sha256 = Sha256Circuit::min_num_rows_block(block);
+        let ripemd160 = Ripemd160Circuit::min_num_rows_block(block);
+        let blake2f = Blake2fCircuit::min_num_rows_block(block);
         let tx = TxCircuit::min_num_rows_block(block);
         let rlp = RlpCircuit::min_num_rows_block(block);
         let exp = ExpCircuit::min_num_rows_block(block);
@@ -489,6 +599,9 @@ impl<
             bytecode,
             copy,
             keccak,
+            sha256,
+            ripemd160,
+            blake2f,
             tx,
             rlp,
             exp,
@@ -506,6 +619,9 @@ impl<
             "bytecode",
             "copy",
             "keccak",
+            "sha256",
+            "ripemd160",
+            "blake2f",
             "tx",
             "rlp",
             "exp",
@@ -559,6 +675,9 @@ impl<
             CopyCircuit::<Fr>::unusable_rows(),
             ExpCircuit::<Fr>::unusable_rows(),
             KeccakCircuit::<Fr>::unusable_rows(),
+            Sha256Circuit::<Fr>::unusable_rows(),
+            Ripemd160Circuit::<Fr>::unusable_rows(),
+            Blake2fCircuit::<Fr>::unusable_rows(),
         ])
         .unwrap()
     }
@@ -573,6 +692,9 @@ impl<
         let exp_circuit = ExpCircuit::new_from_block(block);
         let modexp_circuit = ModExpCircuit::new_from_block(block);
         let keccak_circuit = KeccakCircuit::new_from_block(block);
+        let sha256_circuit = Sha256Circuit::new_from_block(block);
+        let ripemd160_circuit = Ripemd160Circuit::new_from_block(block);
+        let blake2f_circuit = Blake2fCircuit::new_from_block(block);
         let poseidon_circuit = PoseidonCircuit::new_from_block(block);
         let rlp_circuit = RlpCircuit::new_from_block(block);
         let sig_circuit = SigCircuit::new_from_block(block);
@@ -588,6 +710,9 @@ impl<
             copy_circuit,
             exp_circuit,
             keccak_circuit,
+            sha256_circuit,
+            ripemd160_circuit,
+            blake2f_circuit,
             poseidon_circuit,
             rlp_circuit,
             sig_circuit,
@@ -631,6 +756,12 @@ impl<
     ) -> Result<(), Error> {
         self.keccak_circuit
             .synthesize_sub(&config.keccak_circuit, challenges, layouter)?;
+        self.sha256_circuit
+            .synthesize_sub(&config.sha256_circuit, challenges, layouter)?;
+        self.ripemd160_circuit
+            .synthesize_sub(&config.ripemd160_circuit, challenges, layouter)?;
+        self.blake2f_circuit
+            .synthesize_sub(&config.blake2f_circuit, challenges, layouter)?;
         self.poseidon_circuit
             .synthesize_sub(&config.poseidon_circuit, challenges, layouter)?;
         self.bytecode_circuit
@@ -641,14 +772,18 @@ impl<
             .synthesize_sub(&config.sig_circuit, challenges, layouter)?;
         self.ecc_circuit
             .synthesize_sub(&config.ecc_circuit, challenges, layouter)?;
-        self.modexp_circuit
-            .synthesize_sub(&config.modexp_circuit, challenges, layouter)?;
+        if let Some(modexp_circuit) = &config.modexp_circuit {
+            self.modexp_circuit
+                .synthesize_sub(modexp_circuit, challenges, layouter)?;
+        }
         self.state_circuit
             .synthesize_sub(&config.state_circuit, challenges, layouter)?;
         self.copy_circuit
             .synthesize_sub(&config.copy_circuit, challenges, layouter)?;
-        self.exp_circuit
-            .synthesize_sub(&config.exp_circuit, challenges, layouter)?;
+        if let Some(exp_circuit) = &config.exp_circuit {
+            self.exp_circuit
+                .synthesize_sub(exp_circuit, challenges, layouter)?;
+        }
         self.evm_circuit
             .synthesize_sub(&config.evm_circuit, challenges, layouter)?;
 
@@ -696,6 +831,7 @@ impl<
                     max_calldata: MAX_CALLDATA,
                     max_inner_blocks: MAX_INNER_BLOCKS,
                     mock_randomness: MOCK_RANDOMNESS,
+                    detach_circuits: false,
                     challenges,
                 },
             ),
@@ -745,6 +881,32 @@ impl<
     }
 }
 
+impl<
+        const MAX_TXS: usize,
+        const MAX_CALLDATA: usize,
+        const MAX_INNER_BLOCKS: usize,
+        const MOCK_RANDOMNESS: u64,
+    > CircuitDigest for SuperCircuit<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS>
+{
+    fn config_bytes(&self) -> Vec<u8> {
+        let block = self
+            .evm_circuit
+            .block
+            .as_ref()
+            .expect("the circuits params are read from the block");
+        [
+            MAX_TXS as u64,
+            MAX_CALLDATA as u64,
+            MAX_INNER_BLOCKS as u64,
+            MOCK_RANDOMNESS,
+        ]
+        .iter()
+        .flat_map(|param| param.to_be_bytes())
+        .chain(serde_json::to_vec(&block.circuits_params).unwrap())
+        .collect()
+    }
+}
+
 impl<
         const MAX_TXS: usize,
         const MAX_CALLDATA: usize,
@@ -0,0 +1,263 @@
+//! Capacity checker which packs transactions one at a time, tracking the row
+//! usage of every sub-circuit of the Super Circuit.
+
+use super::{SubcircuitRowUsage, SuperCircuit};
+use crate::{util::SubCircuit, witness::block_convert};
+use bus_mapping::{
+    circuit_input_builder::{Block, BlockHead, CircuitInputBuilder},
+    state_db::{CodeDB, StateDB},
+    Error,
+};
+use eth_types::GethExecTrace;
+use halo2_proofs::halo2curves::bn256::Fr;
+
+type EthBlock = eth_types::Block<eth_types::Transaction>;
+
+// The row usage of the sub-circuits doesn't depend on the const parameters of
+// the Super Circuit, they are all read from the `CircuitsParams` of the block.
+type RowUsageCircuit = SuperCircuit<Fr, 0, 0, 0, 0>;
+
+/// Row usage of the sub-circuits for the transactions packed so far.
+#[derive(Clone, Debug, Default)]
+pub struct RowUsage {
+    /// Whether every sub-circuit fits its capacity.
+    pub is_ok: bool,
+    /// Largest number of rows used by a sub-circuit.
+    pub row_number: usize,
+    /// Sub-circuit with the largest share of its capacity in use.
+    pub bottleneck: String,
+    /// Row usage of each sub-circuit.
+    pub row_usage_details: Vec<SubcircuitRowUsage>,
+}
+
+impl RowUsage {
+    /// Create the row usage from the row usage of each sub-circuit, where a
+    /// sub-circuit has the capacity of its total rows, up to `usable_rows`.
+    pub fn from_row_usage_details(
+        row_usage_details: Vec<SubcircuitRowUsage>,
+        usable_rows: usize,
+    ) -> Self {
+        let capacity = |usage: &SubcircuitRowUsage| usage.row_num_total.min(usable_rows).max(1);
+        let is_ok = row_usage_details
+            .iter()
+            .all(|usage| usage.row_num_real <= capacity(usage));
+        let row_number = itertools::max(row_usage_details.iter().map(|usage| usage.row_num_real))
+            .unwrap_or_default();
+        // Compare the ratios row_num_real / capacity without dividing.
+        let bottleneck = row_usage_details
+            .iter()
+            .max_by(|a, b| (a.row_num_real * capacity(b)).cmp(&(b.row_num_real * capacity(a))))
+            .map(|usage| usage.name.clone())
+            .unwrap_or_default();
+
+        Self {
+            is_ok,
+            row_number,
+            bottleneck,
+            row_usage_details,
+        }
+    }
+}
+
+/// Packs transactions into a chunk one at a time, checking after each of them
+/// whether every sub-circuit still fits its capacity, so that a sequencer can
+/// roll back the transaction which overflows the chunk.
+///
+/// The capacity of a sub-circuit is its total number of rows with the
+/// `CircuitsParams` of the builder, up to the usable rows of the Super
+/// Circuit with degree `max_k`. The number of txs is bounded by `max_txs`.
+///
+/// The row usage is kept incrementally: the usage of an empty chunk plus, for
+/// every packed tx, the rows it adds to a chunk holding only that tx. So a tx
+/// is converted on its own instead of re-converting the whole chunk. The rows
+/// shared between txs, like the bytecode of a contract called by several of
+/// them, are counted once per tx, which over-estimates the usage.
+#[derive(Debug)]
+pub struct CircuitCapacityChecker {
+    state: PackedState,
+    usable_rows: usize,
+    row_usage: RowUsage,
+    // State before the last tx, to roll it back.
+    last_snapshot: Option<(PackedState, RowUsage)>,
+}
+
+// State of the chunk after the packed txs.
+#[derive(Clone, Debug)]
+struct PackedState {
+    sdb: StateDB,
+    code_db: CodeDB,
+    // Block with the headers and params of the chunk, without txs.
+    block: Block,
+    num_txs: usize,
+    // Row usage of an empty chunk with the headers of `block`.
+    base_usage: Vec<SubcircuitRowUsage>,
+    // Rows added by the packed txs, by sub-circuit.
+    tx_rows: Vec<usize>,
+}
+
+impl CircuitCapacityChecker {
+    /// Create a capacity checker which packs the transactions on top of the
+    /// builder of an empty chunk, for a Super Circuit with degree `max_k`.
+    pub fn new(builder: CircuitInputBuilder, max_k: u32) -> Result<Self, Error> {
+        let CircuitInputBuilder {
+            sdb,
+            code_db,
+            block,
+            ..
+        } = builder;
+        let base_usage = block_row_usage(CircuitInputBuilder::new(
+            sdb.clone(),
+            code_db.clone(),
+            &block,
+        ))?;
+        let usable_rows = (1usize << max_k).saturating_sub(RowUsageCircuit::unusable_rows());
+        let state = PackedState {
+            sdb,
+            code_db,
+            block,
+            num_txs: 0,
+            tx_rows: vec![0; base_usage.len()],
+            base_usage,
+        };
+
+        Ok(Self {
+            row_usage: state.row_usage(usable_rows),
+            state,
+            usable_rows,
+            last_snapshot: None,
+        })
+    }
+
+    /// Pack the transaction of the block with its trace, and return the row
+    /// usage of all the packed transactions. The transaction stays packed
+    /// even if it overflows a sub-circuit, until it's rolled back with
+    /// [`Self::rollback_last_tx`].
+    ///
+    /// Only the header of `eth_block` is used, its transactions are ignored.
+    pub fn apply_tx(
+        &mut self,
+        eth_block: &EthBlock,
+        eth_tx: &eth_types::Transaction,
+        geth_trace: &GethExecTrace,
+    ) -> Result<&RowUsage, Error> {
+        if self.state.num_txs >= self.state.block.circuits_params.max_txs {
+            return Err(Error::InternalError("txs not enough"));
+        }
+
+        // The state is only replaced once the tx is handled, so a failing tx
+        // leaves the packed txs as they were.
+        let state = self.state.apply_tx(eth_block, eth_tx, geth_trace)?;
+        let snapshot = (
+            std::mem::replace(&mut self.state, state),
+            self.row_usage.clone(),
+        );
+        self.last_snapshot = Some(snapshot);
+        self.row_usage = self.state.row_usage(self.usable_rows);
+        log::debug!(
+            "packed {} txs, row usage {:?}",
+            self.state.num_txs,
+            self.row_usage
+        );
+        Ok(&self.row_usage)
+    }
+
+    /// Roll back the last packed transaction. Only the last one can be
+    /// rolled back, so return false if there is none.
+    pub fn rollback_last_tx(&mut self) -> bool {
+        match self.last_snapshot.take() {
+            Some((state, row_usage)) => {
+                self.state = state;
+                self.row_usage = row_usage;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Row usage of the packed transactions.
+    pub fn row_usage(&self) -> &RowUsage {
+        &self.row_usage
+    }
+
+    /// Number of packed transactions.
+    pub fn num_txs(&self) -> usize {
+        self.state.num_txs
+    }
+}
+
+impl PackedState {
+    fn apply_tx(
+        &self,
+        eth_block: &EthBlock,
+        eth_tx: &eth_types::Transaction,
+        geth_trace: &GethExecTrace,
+    ) -> Result<Self, Error> {
+        let mut block = self.block.clone();
+        let mut base_usage = self.base_usage.clone();
+        let block_num = eth_block
+            .number
+            .ok_or(Error::EthTypeError(eth_types::Error::IncompleteBlock))?
+            .as_u64();
+        if !block.headers.contains_key(&block_num) {
+            let header = BlockHead::new(block.chain_id, Default::default(), eth_block)?;
+            block.headers.insert(block_num, header);
+            // The rows of the new header go to the base usage, not to the tx.
+            base_usage = block_row_usage(CircuitInputBuilder::new(
+                self.sdb.clone(),
+                self.code_db.clone(),
+                &block,
+            ))?;
+        }
+
+        let mut builder = CircuitInputBuilder::new(self.sdb.clone(), self.code_db.clone(), &block);
+        let eth_block = EthBlock {
+            transactions: vec![eth_tx.clone()],
+            ..eth_block.clone()
+        };
+        builder.handle_block_inner(&eth_block, std::slice::from_ref(geth_trace), false, false)?;
+        let sdb = builder.sdb.clone();
+        let code_db = builder.code_db.clone();
+        let tx_usage = block_row_usage(builder)?;
+
+        let tx_rows = self
+            .tx_rows
+            .iter()
+            .zip(tx_usage.iter().zip(base_usage.iter()))
+            .map(|(rows, (tx, base))| rows + tx.row_num_real.saturating_sub(base.row_num_real))
+            .collect();
+
+        Ok(Self {
+            sdb,
+            code_db,
+            block,
+            num_txs: self.num_txs + 1,
+            base_usage,
+            tx_rows,
+        })
+    }
+
+    fn row_usage(&self, usable_rows: usize) -> RowUsage {
+        let row_usage_details = self
+            .base_usage
+            .iter()
+            .zip(self.tx_rows.iter())
+            .map(|(base, rows)| SubcircuitRowUsage {
+                row_num_real: base.row_num_real + rows,
+                ..base.clone()
+            })
+            .collect();
+        RowUsage::from_row_usage_details(row_usage_details, usable_rows)
+    }
+}
+
+// Seal the block of the builder and return the row usage of its sub-circuits.
+fn block_row_usage(mut builder: CircuitInputBuilder) -> Result<Vec<SubcircuitRowUsage>, Error> {
+    // Pad the rws to the ones in use, their capacity is checked by the state
+    // circuit.
+    builder.block.circuits_params.max_rws = 0;
+    builder.set_value_ops_call_context_rwc_eor();
+    builder.set_end_block()?;
+    let block = block_convert::<Fr>(&builder.block, &builder.code_db)?;
+
+    Ok(RowUsageCircuit::min_num_rows_block_subcircuits(&block))
+}
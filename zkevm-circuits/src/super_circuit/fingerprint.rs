@@ -0,0 +1,267 @@
+//! Fingerprints of the lookup tables shared between circuits which are proven
+//! apart, so that the aggregation of their proofs can check that they loaded
+//! the same tables.
+//!
+//! The fingerprint of a table is the product over its rows of
+//! `1 + t_0 * (c_0 * t_0 + c_1 * t_1 + ...)`, where `t_i` are the expressions
+//! of the lookup table at the row and `c_i` are challenges shared by all the
+//! proofs, given in their instance. The first expression of the tables is the
+//! fixed column which enables the row for lookups, so that the rows which
+//! can't be looked up, like the unused rows of a circuit with a larger degree,
+//! don't change the fingerprint.
+
+use crate::{table::LookupTable, util::query_expression};
+use eth_types::Field;
+use gadgets::util::Expr;
+use halo2_proofs::{
+    circuit::{Layouter, Value},
+    plonk::{Advice, Any, Column, ConstraintSystem, Error, Expression, Fixed, Instance},
+    poly::Rotation,
+};
+use itertools::Itertools;
+
+/// Number of challenges of the fingerprints, which bounds the number of
+/// expressions of a fingerprinted table.
+pub const NUM_FINGERPRINT_CHALLENGES: usize = 13;
+
+/// Expressions of a lookup table, to compute its fingerprint from the values
+/// of its columns.
+#[derive(Clone, Debug)]
+pub(crate) struct TableExprs<F> {
+    columns: Vec<Column<Any>>,
+    exprs: Vec<Expression<F>>,
+}
+
+impl<F: Field> TableExprs<F> {
+    pub(crate) fn new(meta: &mut ConstraintSystem<F>, table: &dyn LookupTable<F>) -> Self {
+        let exprs = query_expression(meta, |meta| table.table_exprs(meta));
+        assert!(
+            exprs.len() <= NUM_FINGERPRINT_CHALLENGES,
+            "table has more expressions than fingerprint challenges"
+        );
+        Self {
+            columns: table.columns(),
+            exprs,
+        }
+    }
+
+    // Position in the table of the fixed or advice column with the index.
+    fn position(&self, is_fixed: bool, column_index: usize) -> usize {
+        self.columns
+            .iter()
+            .position(|column| {
+                column.index() == column_index
+                    && matches!(column.column_type(), Any::Fixed) == is_fixed
+            })
+            .expect("expression queries a column out of the table")
+    }
+
+    // Value of the column at the rotated row, where `rows` are the table rows
+    // in the order of its columns. Rows out of the table are empty.
+    fn value(&self, rows: &[Vec<F>], position: usize, row: usize, rotation: Rotation) -> F {
+        usize::try_from(row as i64 + rotation.0 as i64)
+            .ok()
+            .and_then(|row| rows.get(row))
+            .map_or(F::zero(), |values| values[position])
+    }
+
+    /// Factor of the row in the fingerprint.
+    pub(crate) fn factor(&self, rows: &[Vec<F>], row: usize, challenges: &[F]) -> F {
+        let values = self
+            .exprs
+            .iter()
+            .map(|expr| {
+                expr.evaluate(
+                    &|scalar| scalar,
+                    &|_| unimplemented!("selector column"),
+                    &|query| {
+                        let position = self.position(true, query.column_index());
+                        self.value(rows, position, row, query.rotation())
+                    },
+                    &|query| {
+                        let position = self.position(false, query.column_index());
+                        self.value(rows, position, row, query.rotation())
+                    },
+                    &|_| unimplemented!("instance column"),
+                    &|_| unimplemented!("challenge"),
+                    &|a| -a,
+                    &|a, b| a + b,
+                    &|a, b| a * b,
+                    &|a, scalar| a * scalar,
+                )
+            })
+            .collect_vec();
+        let rlc = values
+            .iter()
+            .zip(challenges)
+            .fold(F::zero(), |acc, (value, challenge)| {
+                acc + *value * challenge
+            });
+        F::one() + values[0] * rlc
+    }
+
+    /// Fingerprint of the table rows, in the order of its columns.
+    pub(crate) fn fingerprint(&self, rows: &[Vec<F>], challenges: &[F]) -> F {
+        (0..rows.len()).fold(F::one(), |acc, row| {
+            acc * self.factor(rows, row, challenges)
+        })
+    }
+}
+
+/// Config computing the fingerprints of lookup tables, exposed in an instance
+/// column after the challenges.
+///
+/// The tables must be assigned from the first row, as done by the floor
+/// planner when no other region uses their columns.
+#[derive(Clone, Debug)]
+pub struct TableFingerprintConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    challenges: [Column<Advice>; NUM_FINGERPRINT_CHALLENGES],
+    fingerprints: Vec<Column<Advice>>,
+    instance: Column<Instance>,
+    tables: Vec<TableExprs<F>>,
+}
+
+impl<F: Field> TableFingerprintConfig<F> {
+    /// Configure the fingerprints of the tables.
+    pub fn configure(meta: &mut ConstraintSystem<F>, tables: &[&dyn LookupTable<F>]) -> Self {
+        let q_enable = meta.fixed_column();
+        let q_first = meta.fixed_column();
+        let challenges = array_init::array_init(|_| meta.advice_column());
+        let fingerprints = tables.iter().map(|_| meta.advice_column()).collect_vec();
+        let instance = meta.instance_column();
+        for column in challenges.iter().chain(fingerprints.iter()) {
+            meta.enable_equality(*column);
+        }
+        meta.enable_equality(instance);
+
+        meta.create_gate("fingerprint challenges are the same in all rows", |meta| {
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            challenges
+                .iter()
+                .map(|challenge| {
+                    q_enable.clone()
+                        * (meta.query_advice(*challenge, Rotation::next())
+                            - meta.query_advice(*challenge, Rotation::cur()))
+                })
+                .collect_vec()
+        });
+
+        for (table, fingerprint) in tables.iter().zip(fingerprints.iter()) {
+            meta.create_gate("table fingerprint", |meta| {
+                let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+                let q_first = meta.query_fixed(q_first, Rotation::cur());
+                let challenges =
+                    challenges.map(|challenge| meta.query_advice(challenge, Rotation::cur()));
+                let exprs = table.table_exprs(meta);
+                let rlc = exprs
+                    .iter()
+                    .zip(challenges)
+                    .fold(0.expr(), |acc, (expr, challenge)| {
+                        acc + expr.clone() * challenge
+                    });
+                let factor = 1.expr() + exprs[0].clone() * rlc;
+
+                let acc = meta.query_advice(*fingerprint, Rotation::cur());
+                let acc_next = meta.query_advice(*fingerprint, Rotation::next());
+                vec![
+                    q_first * (acc.clone() - 1.expr()),
+                    q_enable * (acc_next - acc * factor),
+                ]
+            });
+        }
+
+        let tables = tables
+            .iter()
+            .map(|table| TableExprs::new(meta, *table))
+            .collect();
+
+        Self {
+            q_enable,
+            q_first,
+            challenges,
+            fingerprints,
+            instance,
+            tables,
+        }
+    }
+
+    /// Assign the fingerprints of the table rows, given in the order of the
+    /// columns of each table, and expose them with the challenges.
+    pub fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        challenges: &[F; NUM_FINGERPRINT_CHALLENGES],
+        tables_rows: &[&[Vec<F>]],
+    ) -> Result<(), Error> {
+        debug_assert_eq!(tables_rows.len(), self.tables.len());
+        let num_rows =
+            itertools::max(tables_rows.iter().map(|rows| rows.len())).unwrap_or_default();
+
+        let (challenge_cells, fingerprint_cells) = layouter.assign_region(
+            || "table fingerprints",
+            |mut region| {
+                let mut challenge_cells = vec![];
+                let mut fingerprint_cells = vec![];
+                let mut accs = vec![F::one(); self.tables.len()];
+                for offset in 0..=num_rows {
+                    if offset < num_rows {
+                        region.assign_fixed(
+                            || "fingerprint q_enable",
+                            self.q_enable,
+                            offset,
+                            || Value::known(F::one()),
+                        )?;
+                    }
+                    region.assign_fixed(
+                        || "fingerprint q_first",
+                        self.q_first,
+                        offset,
+                        || Value::known(if offset == 0 { F::one() } else { F::zero() }),
+                    )?;
+                    for (column, challenge) in self.challenges.iter().zip(challenges) {
+                        let cell = region.assign_advice(
+                            || "fingerprint challenge",
+                            *column,
+                            offset,
+                            || Value::known(*challenge),
+                        )?;
+                        if offset == 0 {
+                            challenge_cells.push(cell);
+                        }
+                    }
+                    for (((column, table), rows), acc) in self
+                        .fingerprints
+                        .iter()
+                        .zip(self.tables.iter())
+                        .zip(tables_rows)
+                        .zip(accs.iter_mut())
+                    {
+                        let cell = region.assign_advice(
+                            || "table fingerprint",
+                            *column,
+                            offset,
+                            || Value::known(*acc),
+                        )?;
+                        if offset == num_rows {
+                            fingerprint_cells.push(cell);
+                        } else {
+                            *acc *= table.factor(rows, offset, challenges);
+                        }
+                    }
+                }
+                Ok((challenge_cells, fingerprint_cells))
+            },
+        )?;
+
+        for (row, cell) in challenge_cells
+            .iter()
+            .chain(fingerprint_cells.iter())
+            .enumerate()
+        {
+            layouter.constrain_instance(cell.cell(), self.instance, row)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,213 @@
+//! Sizing of the [`CircuitsParams`] and of the degree of the Super Circuit
+//! from the witness of a block.
+
+use super::{SubcircuitRowUsage, SuperCircuit};
+#[cfg(feature = "zktrie")]
+use crate::mpt_circuit::MptCircuit;
+use crate::{
+    bytecode_circuit::circuit::BytecodeCircuit,
+    copy_circuit::CopyCircuit,
+    evm_circuit::EvmCircuit,
+    exp_circuit::ExpCircuit,
+    keccak_circuit::KeccakCircuit,
+    rlp_circuit_fsm::RlpCircuit,
+    sig_circuit::SigCircuit,
+    table::RwTableTag,
+    util::{log2_ceil, SubCircuit},
+    witness::{block_convert, Block},
+};
+use bus_mapping::circuit_input_builder::{CircuitInputBuilder, CircuitsParams, PrecompileEcParams};
+use halo2_proofs::halo2curves::bn256::Fr;
+use std::fmt::{self, Display, Formatter};
+
+/// Minimal [`CircuitsParams`] and degree with which a block can be proven.
+#[derive(Clone, Debug)]
+pub struct CircuitsParamsReport {
+    /// Minimal params with which every sub-circuit fits the block.
+    pub params: CircuitsParams,
+    /// Smallest degree with which the Super Circuit fits the block.
+    pub k: u32,
+    /// Row usage of each sub-circuit with the minimal params.
+    pub row_usage: Vec<SubcircuitRowUsage>,
+}
+
+/// Error while sizing the circuits for a block.
+#[derive(Debug)]
+pub enum CircuitsParamsError {
+    /// The block needs more rows than the fixed capacity of the sub-circuit.
+    CapacityExceeded {
+        /// Name of the sub-circuit
+        name: String,
+        /// Number of rows needed by the block
+        row_num_real: usize,
+        /// Number of rows available in the sub-circuit
+        row_num_total: usize,
+    },
+    /// The sub-circuit needs more rows than available with the maximum
+    /// degree.
+    DegreeExceeded {
+        /// Name of the sub-circuit
+        name: String,
+        /// Number of rows of the sub-circuit
+        row_num: usize,
+        /// Maximum degree of the circuit
+        max_k: u32,
+    },
+    /// Error while converting the circuit input builder to a witness block.
+    BlockConvert(bus_mapping::Error),
+}
+
+impl Display for CircuitsParamsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CapacityExceeded {
+                name,
+                row_num_real,
+                row_num_total,
+            } => write!(
+                f,
+                "{name} circuit needs {row_num_real} rows but has a capacity of {row_num_total} rows"
+            ),
+            Self::DegreeExceeded {
+                name,
+                row_num,
+                max_k,
+            } => write!(
+                f,
+                "{name} circuit needs {row_num} rows which exceeds the max degree {max_k}"
+            ),
+            Self::BlockConvert(err) => write!(f, "block convert error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for CircuitsParamsError {}
+
+impl<
+        const MAX_TXS: usize,
+        const MAX_CALLDATA: usize,
+        const MAX_INNER_BLOCKS: usize,
+        const MOCK_RANDOMNESS: u64,
+    > SuperCircuit<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS>
+{
+    /// Return the minimal [`CircuitsParams`] with which every sub-circuit fits
+    /// the block.
+    ///
+    /// The witness block depends on `max_rws`, so the returned params are
+    /// meant to configure the [`CircuitInputBuilder`] which builds the block
+    /// again for proving.
+    pub fn min_circuits_params(block: &Block<Fr>) -> CircuitsParams {
+        // The Start rows used for padding are not counted, and there must be
+        // at least one of them.
+        let max_rws = block
+            .rws
+            .0
+            .iter()
+            .filter(|(tag, _)| **tag != RwTableTag::Start)
+            .map(|(_, rws)| rws.len())
+            .sum::<usize>()
+            + 1;
+        let max_txs = block.txs.len().max(1);
+        let max_calldata = block.txs.iter().map(|tx| tx.call_data.len()).sum();
+        // Reserve one row for the end of the state machine.
+        let max_rlp_rows = RlpCircuit::min_num_rows_block(block).0 + 1;
+        let max_copy_rows = CopyCircuit::min_num_rows_block(block).0;
+        let max_inner_blocks = block.context.ctxs.len().max(1);
+        let max_exp_steps = ExpCircuit::<Fr>::min_num_steps(&block.exp_events);
+        let max_bytecode =
+            BytecodeCircuit::min_num_rows_block(block).0 + BytecodeCircuit::<Fr>::unusable_rows();
+        let max_evm_rows = EvmCircuit::<Fr>::get_num_rows_required_no_padding(block);
+        #[cfg(feature = "zktrie")]
+        let max_mpt_rows = MptCircuit::min_num_rows_block(block).0;
+        #[cfg(not(feature = "zktrie"))]
+        let max_mpt_rows = block.circuits_params.max_mpt_rows;
+        let max_keccak_rows = KeccakCircuit::<Fr>::min_num_rows_for_inputs(&block.keccak_inputs);
+        // The ECC circuit divides its rows among the ops, so keep at least
+        // one of each.
+        let max_ec_ops = PrecompileEcParams {
+            ec_add: block.get_ec_add_ops().len().max(1),
+            ec_mul: block.get_ec_mul_ops().len().max(1),
+            ec_pairing: block.get_ec_pairing_ops().len().max(1),
+            ec_pairing_chunks: block
+                .get_ec_pairing_ops()
+                .iter()
+                .map(|op| op.n_chunks())
+                .sum::<usize>()
+                .max(1),
+        };
+        // Reserve one signature for the padding tx.
+        let max_num_sig = SigCircuit::<Fr>::num_signatures(block) + 1;
+
+        CircuitsParams {
+            max_rws,
+            max_txs,
+            max_calldata,
+            max_rlp_rows,
+            max_copy_rows,
+            max_inner_blocks,
+            max_exp_steps,
+            max_bytecode,
+            max_evm_rows,
+            max_mpt_rows,
+            max_keccak_rows,
+            max_ec_ops,
+            max_num_sig,
+            chain_spec: block.circuits_params.chain_spec,
+        }
+    }
+
+    /// Return the minimal [`CircuitsParams`] of the block, the smallest
+    /// degree of the Super Circuit with them, and the row usage of each
+    /// sub-circuit. Fails naming the first sub-circuit which can't fit the
+    /// block, either because of its fixed capacity or because it needs a
+    /// degree above `max_k`.
+    pub fn circuits_params_report(
+        block: &Block<Fr>,
+        max_k: u32,
+    ) -> Result<CircuitsParamsReport, CircuitsParamsError> {
+        let params = Self::min_circuits_params(block);
+        let mut sized_block = block.clone();
+        sized_block.circuits_params = params;
+        let row_usage = Self::min_num_rows_block_subcircuits(&sized_block);
+
+        let usable_rows = (1usize << max_k).saturating_sub(Self::unusable_rows());
+        for usage in &row_usage {
+            if usage.row_num_real > usage.row_num_total {
+                return Err(CircuitsParamsError::CapacityExceeded {
+                    name: usage.name.clone(),
+                    row_num_real: usage.row_num_real,
+                    row_num_total: usage.row_num_total,
+                });
+            }
+            if usage.row_num_total > usable_rows {
+                return Err(CircuitsParamsError::DegreeExceeded {
+                    name: usage.name.clone(),
+                    row_num: usage.row_num_total,
+                    max_k,
+                });
+            }
+        }
+
+        let rows_needed =
+            itertools::max(row_usage.iter().map(|usage| usage.row_num_total)).unwrap_or_default();
+        let k = log2_ceil(Self::unusable_rows() + rows_needed);
+        log::debug!("min circuits params {params:?} with k = {k}");
+
+        Ok(CircuitsParamsReport {
+            params,
+            k,
+            row_usage,
+        })
+    }
+
+    /// Same as [`Self::circuits_params_report`], for the block of the
+    /// [`CircuitInputBuilder`].
+    pub fn circuits_params_report_from_builder(
+        builder: &CircuitInputBuilder,
+        max_k: u32,
+    ) -> Result<CircuitsParamsReport, CircuitsParamsError> {
+        let block = block_convert(&builder.block, &builder.code_db)
+            .map_err(CircuitsParamsError::BlockConvert)?;
+        Self::circuits_params_report(&block, max_k)
+    }
+}
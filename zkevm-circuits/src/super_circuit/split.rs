@@ -0,0 +1,594 @@
+//! Split proving of the Super Circuit, where the exponentiation and modexp
+//! circuits are proven apart as [`DetachedCircuit`]s with their own degree,
+//! instead of sharing the degree of the largest sub-circuit.
+//!
+//! The [`SplitSuperCircuit`] loads the exponentiation and modexp tables from
+//! the witness without constraining them, and both the Split Super Circuit and
+//! the detached circuits expose the [fingerprints](super::fingerprint) of
+//! these tables. The aggregation of their proofs must check that the
+//! fingerprints and their challenges are the same in all the proofs.
+//!
+//! The challenges are squeezed from the commitments of the tables in the
+//! proofs, at the indices given by `table_commitment_indices`, and the
+//! aggregation must constrain them to be the challenges of the fingerprints.
+//! The tables are committed in the first phase before the challenges are
+//! known, so the prover derives the challenges from proofs with placeholder
+//! challenges, and proves again with the same randomness: the commitments of
+//! the tables don't change, only those of the fingerprints do.
+
+use super::{
+    fingerprint::{TableExprs, TableFingerprintConfig, NUM_FINGERPRINT_CHALLENGES},
+    SubcircuitRowUsage, SuperCircuit, SuperCircuitConfig, SuperCircuitConfigArgs,
+};
+use crate::{
+    exp_circuit::{
+        param::{OFFSET_INCREMENT, UNUSABLE_EXP_ROWS},
+        ExpCircuit, ExpCircuitArgs, ExpCircuitConfig,
+    },
+    modexp_circuit::{ModExpCircuit, ModExpCircuitConfig, ModExpCircuitConfigArgs},
+    table::{ExpTable, LookupTable, ModExpBytesTable, ModExpTable, U16Table},
+    util::{log2_ceil, Challenges, SubCircuit, SubCircuitConfig},
+    witness::Block,
+};
+use bus_mapping::{circuit_input_builder::ExpEvent, precompile::MODEXP_SIZE_LIMIT};
+use eth_types::Field;
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    halo2curves::bn256::Fr,
+    plonk::{Advice, Any, Circuit, Column, ConstraintSystem, Error, Expression, Fixed},
+};
+use itertools::Itertools;
+use snark_verifier_sdk::CircuitExt;
+use std::iter;
+
+/// Names of the sub-circuits which are proven apart from the Split Super
+/// Circuit.
+pub const DETACHED_CIRCUITS: [&str; 2] = ["exp", "modexp"];
+
+/// Sub-circuit which is proven apart from the Super Circuit, sharing its
+/// lookup table through a fingerprint.
+pub trait DetachedSubCircuit: SubCircuit<Fr> + Clone + Default {
+    /// Configure the sub-circuit with its own tables, and return the table
+    /// shared with the Super Circuit.
+    fn configure_detached(
+        meta: &mut ConstraintSystem<Fr>,
+        challenges: &Challenges<Expression<Fr>>,
+    ) -> (Self::Config, Box<dyn LookupTable<Fr>>);
+
+    /// Load the fixed tables which the sub-circuit looks up.
+    fn load_fixed_tables(
+        _config: &Self::Config,
+        _layouter: &mut impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Rows of the shared table as assigned by the sub-circuit, in the order
+    /// of the table columns.
+    fn table_rows(&self) -> Vec<Vec<Fr>>;
+
+    /// Return the minimum number of usable rows of the detached circuit for
+    /// the block.
+    fn min_num_rows_detached(block: &Block<Fr>) -> usize {
+        Self::min_num_rows_block(block).1
+    }
+}
+
+impl DetachedSubCircuit for ExpCircuit<Fr> {
+    fn configure_detached(
+        meta: &mut ConstraintSystem<Fr>,
+        _challenges: &Challenges<Expression<Fr>>,
+    ) -> (Self::Config, Box<dyn LookupTable<Fr>>) {
+        let exp_table = ExpTable::construct(meta);
+        let u16_table = U16Table::construct(meta);
+        let config = ExpCircuitConfig::new(
+            meta,
+            ExpCircuitArgs {
+                exp_table,
+                u16_table,
+            },
+        );
+        (config, Box::new(exp_table))
+    }
+
+    fn load_fixed_tables(
+        config: &Self::Config,
+        layouter: &mut impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        config.u16_table.load(layouter)
+    }
+
+    fn table_rows(&self) -> Vec<Vec<Fr>> {
+        // `max_exp_rows` holds the number of steps of the circuit.
+        let max_exp_rows = self.max_exp_rows * OFFSET_INCREMENT;
+        let pad_exp_event = ExpEvent::default();
+        let mut exp_events = self.exp_events.iter().collect_vec();
+        let mut offset = exp_events
+            .iter()
+            .map(|exp_event| exp_event.steps.len() * OFFSET_INCREMENT)
+            .sum::<usize>();
+        // Same padding as `ExpCircuitConfig::assign_exp_events`.
+        while offset + OFFSET_INCREMENT <= max_exp_rows - UNUSABLE_EXP_ROWS {
+            exp_events.push(&pad_exp_event);
+            offset += pad_exp_event.steps.len() * OFFSET_INCREMENT;
+        }
+
+        exp_events
+            .into_iter()
+            .flat_map(ExpTable::assignments::<Fr>)
+            .enumerate()
+            .map(|(offset, row)| {
+                let is_step = if offset % OFFSET_INCREMENT == 0 {
+                    Fr::one()
+                } else {
+                    Fr::zero()
+                };
+                [Fr::one(), is_step].into_iter().chain(row).collect()
+            })
+            .collect()
+    }
+
+    fn min_num_rows_detached(block: &Block<Fr>) -> usize {
+        // The u16 table takes 2^16 rows.
+        Self::min_num_rows_block(block).1.max(1 << 16)
+    }
+}
+
+impl DetachedSubCircuit for ModExpCircuit<Fr> {
+    fn configure_detached(
+        meta: &mut ConstraintSystem<Fr>,
+        challenges: &Challenges<Expression<Fr>>,
+    ) -> (Self::Config, Box<dyn LookupTable<Fr>>) {
+        let modexp_table = ModExpTable::construct(meta, challenges);
+        let u16_table = U16Table::construct(meta);
+        let config = ModExpCircuitConfig::new(
+            meta,
+            ModExpCircuitConfigArgs {
+                modexp_table,
+                u16_table,
+                challenges: challenges.clone(),
+            },
+        );
+        (config, Box::new(ModExpBytesTable(modexp_table)))
+    }
+
+    fn load_fixed_tables(
+        config: &Self::Config,
+        layouter: &mut impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        config.u16_table.load(layouter)
+    }
+
+    fn table_rows(&self) -> Vec<Vec<Fr>> {
+        self.events()
+            .iter()
+            .flat_map(ModExpTable::assignments::<Fr>)
+            .enumerate()
+            .map(|(offset, row)| {
+                let is_head = if offset % MODEXP_SIZE_LIMIT == 0 {
+                    Fr::one()
+                } else {
+                    Fr::zero()
+                };
+                [Fr::one(), is_head].into_iter().chain(row).collect()
+            })
+            .collect()
+    }
+
+    fn min_num_rows_detached(block: &Block<Fr>) -> usize {
+        // The u16 table takes 2^16 rows.
+        Self::min_num_rows_block(block).1.max(1 << 16)
+    }
+}
+
+// Indices of the commitments of the advice columns of the tables among the
+// witness commitments of a proof, where the advice columns of each phase are
+// committed in the order of their indices.
+fn table_commitment_indices(
+    meta: &ConstraintSystem<Fr>,
+    tables: &[&dyn LookupTable<Fr>],
+) -> Vec<usize> {
+    let phases = meta.advice_column_phase();
+    tables
+        .iter()
+        .flat_map(|table| table.columns())
+        .filter_map(|column| match column.column_type() {
+            Any::Advice(advice) => {
+                // The tables must be committed before the challenges are
+                // squeezed from them.
+                assert_eq!(advice.phase(), 0, "table column after the first phase");
+                Some(
+                    phases[..column.index()]
+                        .iter()
+                        .filter(|phase| **phase == 0)
+                        .count(),
+                )
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+// Expressions of the shared table of the sub-circuit, in a fresh constraint
+// system so that they match the order of the rows of the table.
+fn shared_table_exprs<C: DetachedSubCircuit>() -> TableExprs<Fr> {
+    let mut meta = ConstraintSystem::default();
+    let challenges = Challenges::construct(&mut meta).exprs(&mut meta);
+    let (_, table) = C::configure_detached(&mut meta, &challenges);
+    TableExprs::new(&mut meta, table.as_ref())
+}
+
+// Instance column of the fingerprints: the challenges followed by the
+// fingerprint of each table.
+fn fingerprint_instance(
+    challenges: &[Fr; NUM_FINGERPRINT_CHALLENGES],
+    tables: &[(TableExprs<Fr>, &[Vec<Fr>])],
+) -> Vec<Fr> {
+    challenges
+        .iter()
+        .copied()
+        .chain(
+            tables
+                .iter()
+                .map(|(table, rows)| table.fingerprint(rows, challenges)),
+        )
+        .collect()
+}
+
+// Assign the rows to the table in a single region, followed by a blank row for
+// the disabled lookups.
+fn load_table_rows<F: Field>(
+    layouter: &mut impl Layouter<F>,
+    name: &str,
+    table: &dyn LookupTable<F>,
+    rows: &[Vec<F>],
+) -> Result<(), Error> {
+    let columns = table.columns();
+    let blank_row = vec![F::zero(); columns.len()];
+    layouter.assign_region(
+        || name,
+        |mut region| {
+            for (offset, row) in rows.iter().chain(iter::once(&blank_row)).enumerate() {
+                for (column, value) in columns.iter().zip_eq(row) {
+                    match column.column_type() {
+                        Any::Fixed => {
+                            region.assign_fixed(
+                                || format!("{name} row {offset}"),
+                                Column::<Fixed>::try_from(*column).unwrap(),
+                                offset,
+                                || Value::known(*value),
+                            )?;
+                        }
+                        _ => {
+                            region.assign_advice(
+                                || format!("{name} row {offset}"),
+                                Column::<Advice>::try_from(*column).unwrap(),
+                                offset,
+                                || Value::known(*value),
+                            )?;
+                        }
+                    }
+                }
+            }
+            Ok(())
+        },
+    )
+}
+
+impl<
+        const MAX_TXS: usize,
+        const MAX_CALLDATA: usize,
+        const MAX_INNER_BLOCKS: usize,
+        const MOCK_RANDOMNESS: u64,
+    > SuperCircuit<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS>
+{
+    fn detached_tables_rows(&self) -> (Vec<Vec<Fr>>, Vec<Vec<Fr>>) {
+        (
+            self.exp_circuit.table_rows(),
+            self.modexp_circuit.table_rows(),
+        )
+    }
+
+    /// Load the tables of the detached circuits from the witness, and assign
+    /// their fingerprints.
+    pub(super) fn synthesize_detached_tables(
+        &self,
+        config: &SuperCircuitConfig<Fr>,
+        table_fingerprint: &TableFingerprintConfig<Fr>,
+        challenges: &Challenges<Value<Fr>>,
+        fingerprint_challenges: &[Fr; NUM_FINGERPRINT_CHALLENGES],
+        layouter: &mut impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let (exp_rows, modexp_rows) = self.detached_tables_rows();
+        load_table_rows(
+            layouter,
+            "exponentiation table",
+            &config.exp_table,
+            &exp_rows,
+        )?;
+        // The RLC columns of the modexp table are constrained from its bytes.
+        config
+            .modexp_table
+            .dev_load(layouter, self.modexp_circuit.events(), challenges)?;
+
+        table_fingerprint.assign(
+            layouter,
+            fingerprint_challenges,
+            &[exp_rows.as_slice(), modexp_rows.as_slice()],
+        )
+    }
+}
+
+/// Super Circuit without the exponentiation and modexp circuits, which are
+/// proven apart as [`DetachedCircuit`]s. Its last instance column holds the
+/// fingerprint challenges, followed by the fingerprints of the exponentiation
+/// and modexp tables.
+#[derive(Clone, Default, Debug)]
+pub struct SplitSuperCircuit<
+    const MAX_TXS: usize,
+    const MAX_CALLDATA: usize,
+    const MAX_INNER_BLOCKS: usize,
+    const MOCK_RANDOMNESS: u64,
+> {
+    /// Super Circuit, of which the exp and modexp circuits are detached
+    pub circuit: SuperCircuit<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS>,
+    /// Challenges of the fingerprints, squeezed from the commitments of the
+    /// tables
+    pub challenges: [Fr; NUM_FINGERPRINT_CHALLENGES],
+}
+
+impl<
+        const MAX_TXS: usize,
+        const MAX_CALLDATA: usize,
+        const MAX_INNER_BLOCKS: usize,
+        const MOCK_RANDOMNESS: u64,
+    > SplitSuperCircuit<MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS>
+{
+    /// Return the row usage of the sub-circuits which aren't detached.
+    pub fn min_num_rows_block_subcircuits(block: &Block<Fr>) -> Vec<SubcircuitRowUsage> {
+        SuperCircuit::<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS>::min_num_rows_block_subcircuits(block)
+            .into_iter()
+            .filter(|usage| !DETACHED_CIRCUITS.contains(&usage.name.as_str()))
+            .collect()
+    }
+
+    /// Indices of the commitments of the exponentiation and modexp tables
+    /// among the witness commitments of a proof of the circuit.
+    pub fn table_commitment_indices() -> Vec<usize> {
+        let mut meta = ConstraintSystem::default();
+        let (config, _) = Self::configure(&mut meta);
+        table_commitment_indices(
+            &meta,
+            &[&config.exp_table, &ModExpBytesTable(config.modexp_table)],
+        )
+    }
+
+    /// Returns suitable inputs for the Split Super Circuit.
+    pub fn instance(&self) -> Vec<Vec<Fr>> {
+        let (exp_rows, modexp_rows) = self.circuit.detached_tables_rows();
+
+        let mut instance = self.circuit.instance();
+        instance.push(fingerprint_instance(
+            &self.challenges,
+            &[
+                (shared_table_exprs::<ExpCircuit<Fr>>(), exp_rows.as_slice()),
+                (
+                    shared_table_exprs::<ModExpCircuit<Fr>>(),
+                    modexp_rows.as_slice(),
+                ),
+            ],
+        ));
+        instance
+    }
+
+    /// From the witness data, generate a Split Super Circuit with the
+    /// minimum required SRS degree and the Public Inputs needed. The degree
+    /// only accounts for the sub-circuits which aren't detached, and for the
+    /// rows of the tables of the detached ones.
+    ///
+    /// The fingerprint challenges are left to zero, until they are squeezed
+    /// from the commitments of the tables.
+    pub fn build_from_witness_block(
+        block: Block<Fr>,
+    ) -> Result<(u32, Self, Vec<Vec<Fr>>), bus_mapping::Error> {
+        let row_usage = Self::min_num_rows_block_subcircuits(&block);
+        let circuit = Self {
+            circuit: SuperCircuit::new_from_block(&block),
+            challenges: Default::default(),
+        };
+
+        let (exp_rows, modexp_rows) = circuit.circuit.detached_tables_rows();
+        let rows_needed = itertools::max(
+            row_usage
+                .iter()
+                .map(|usage| usage.row_num_total)
+                .chain([exp_rows.len() + 1, modexp_rows.len() + 1]),
+        )
+        .unwrap();
+        let k = log2_ceil(
+            SuperCircuit::<Fr, MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS>::unusable_rows()
+                + rows_needed,
+        );
+        log::debug!("split super circuit needs k = {}", k);
+
+        let instance = circuit.instance();
+        Ok((k, circuit, instance))
+    }
+}
+
+impl<
+        const MAX_TXS: usize,
+        const MAX_CALLDATA: usize,
+        const MAX_INNER_BLOCKS: usize,
+        const MOCK_RANDOMNESS: u64,
+    > Circuit<Fr> for SplitSuperCircuit<MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS>
+{
+    type Config = (SuperCircuitConfig<Fr>, Challenges);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let challenges = Challenges::construct(meta);
+        (
+            SuperCircuitConfig::new(
+                meta,
+                SuperCircuitConfigArgs {
+                    max_txs: MAX_TXS,
+                    max_calldata: MAX_CALLDATA,
+                    max_inner_blocks: MAX_INNER_BLOCKS,
+                    mock_randomness: MOCK_RANDOMNESS,
+                    detach_circuits: true,
+                    challenges,
+                },
+            ),
+            challenges,
+        )
+    }
+
+    fn synthesize(
+        &self,
+        (config, challenges): Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let table_fingerprint = config
+            .table_fingerprint
+            .clone()
+            .expect("split super circuit fingerprints the detached tables");
+        self.circuit.synthesize(
+            (config.clone(), challenges),
+            layouter.namespace(|| "super circuit"),
+        )?;
+        self.circuit.synthesize_detached_tables(
+            &config,
+            &table_fingerprint,
+            &challenges.values(&layouter),
+            &self.challenges,
+            &mut layouter,
+        )
+    }
+}
+
+impl<
+        const MAX_TXS: usize,
+        const MAX_CALLDATA: usize,
+        const MAX_INNER_BLOCKS: usize,
+        const MOCK_RANDOMNESS: u64,
+    > CircuitExt<Fr>
+    for SplitSuperCircuit<MAX_TXS, MAX_CALLDATA, MAX_INNER_BLOCKS, MOCK_RANDOMNESS>
+{
+    fn num_instance(&self) -> Vec<usize> {
+        self.instances().iter().map(|l| l.len()).collect_vec()
+    }
+
+    fn instances(&self) -> Vec<Vec<Fr>> {
+        self.instance()
+    }
+}
+
+/// Sub-circuit proven apart from the [`SplitSuperCircuit`], with its own
+/// degree. Its last instance column holds the fingerprint challenges,
+/// followed by the fingerprint of its shared table.
+#[derive(Clone, Default, Debug)]
+pub struct DetachedCircuit<C> {
+    /// Detached sub-circuit
+    pub circuit: C,
+    /// Challenges of the fingerprint, shared with the Split Super Circuit
+    pub challenges: [Fr; NUM_FINGERPRINT_CHALLENGES],
+}
+
+impl<C: DetachedSubCircuit> DetachedCircuit<C> {
+    /// Create the detached sub-circuit of the block. The fingerprint
+    /// challenges are left to zero, until they are squeezed from the
+    /// commitments of the tables.
+    pub fn new_from_block(block: &Block<Fr>) -> Self {
+        Self {
+            circuit: C::new_from_block(block),
+            challenges: Default::default(),
+        }
+    }
+
+    /// Indices of the commitments of the shared table among the witness
+    /// commitments of a proof of the circuit.
+    pub fn table_commitment_indices() -> Vec<usize> {
+        let mut meta = ConstraintSystem::default();
+        let challenges = Challenges::construct(&mut meta).exprs(&mut meta);
+        let (_, table) = C::configure_detached(&mut meta, &challenges);
+        table_commitment_indices(&meta, &[table.as_ref()])
+    }
+
+    /// Returns suitable inputs for the detached circuit.
+    pub fn instance(&self) -> Vec<Vec<Fr>> {
+        let rows = self.circuit.table_rows();
+        let mut instance = self.circuit.instance();
+        instance.push(fingerprint_instance(
+            &self.challenges,
+            &[(shared_table_exprs::<C>(), rows.as_slice())],
+        ));
+        instance
+    }
+
+    /// From the witness data, generate the detached circuit with its minimum
+    /// required SRS degree and the Public Inputs needed.
+    pub fn build_from_witness_block(block: &Block<Fr>) -> (u32, Self, Vec<Vec<Fr>>) {
+        let circuit = Self::new_from_block(block);
+        let rows_needed =
+            C::min_num_rows_detached(block).max(circuit.circuit.table_rows().len() + 1);
+        let k = log2_ceil(C::unusable_rows() + rows_needed);
+        log::debug!("detached circuit needs k = {}", k);
+
+        let instance = circuit.instance();
+        (k, circuit, instance)
+    }
+}
+
+impl<C: DetachedSubCircuit> Circuit<Fr> for DetachedCircuit<C>
+where
+    C::Config: Clone,
+{
+    type Config = (C::Config, TableFingerprintConfig<Fr>, Challenges);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let challenges = Challenges::construct(meta);
+        let challenges_expr = challenges.exprs(meta);
+        let (config, table) = C::configure_detached(meta, &challenges_expr);
+        let table_fingerprint = TableFingerprintConfig::configure(meta, &[table.as_ref()]);
+        (config, table_fingerprint, challenges)
+    }
+
+    fn synthesize(
+        &self,
+        (config, table_fingerprint, challenges): Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let challenges = challenges.values(&layouter);
+        C::load_fixed_tables(&config, &mut layouter)?;
+        self.circuit
+            .synthesize_sub(&config, &challenges, &mut layouter)?;
+        table_fingerprint.assign(
+            &mut layouter,
+            &self.challenges,
+            &[self.circuit.table_rows().as_slice()],
+        )
+    }
+}
+
+impl<C: DetachedSubCircuit> CircuitExt<Fr> for DetachedCircuit<C>
+where
+    C::Config: Clone,
+{
+    fn num_instance(&self) -> Vec<usize> {
+        self.instances().iter().map(|l| l.len()).collect_vec()
+    }
+
+    fn instances(&self) -> Vec<Vec<Fr>> {
+        self.instance()
+    }
+}
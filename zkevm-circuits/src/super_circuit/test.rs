@@ -562,3 +562,275 @@ fn test_super_circuit_ec_ops_txs() {
         circuits_params,
     );
 }
+
+#[cfg(feature = "scroll")]
+#[test]
+fn test_super_circuit_params_report() {
+    type TestSuperCircuit = SuperCircuit<Fr, 1, 256, 1, TEST_MOCK_RANDOMNESS>;
+
+    let circuits_params = CircuitsParams {
+        max_txs: 1,
+        max_calldata: 256,
+        max_rws: 256,
+        max_copy_rows: 256,
+        max_exp_steps: 256,
+        max_bytecode: 512,
+        max_mpt_rows: 512,
+        max_inner_blocks: 1,
+        max_rlp_rows: 500,
+        ..Default::default()
+    };
+    let block_data = BlockData::new_from_geth_data_with_params(block_1tx(), circuits_params);
+    let mut builder = block_data.new_circuit_input_builder();
+    builder
+        .handle_block(&block_data.eth_block, &block_data.geth_traces)
+        .expect("could not handle block tx");
+
+    let report = TestSuperCircuit::circuits_params_report_from_builder(&builder, 20).unwrap();
+    assert!(report.params.max_rws <= circuits_params.max_rws);
+    assert!(report.params.max_copy_rows <= circuits_params.max_copy_rows);
+    assert!(report
+        .row_usage
+        .iter()
+        .all(|usage| usage.row_num_real <= usage.row_num_total));
+
+    match TestSuperCircuit::circuits_params_report_from_builder(&builder, report.k - 1) {
+        Err(CircuitsParamsError::DegreeExceeded { max_k, .. }) => assert_eq!(max_k, report.k - 1),
+        res => panic!("unexpected result {res:?}"),
+    }
+}
+
+#[cfg(feature = "scroll")]
+#[test]
+fn test_circuit_capacity_checker() {
+    let circuits_params = CircuitsParams {
+        max_txs: 2,
+        max_calldata: 256,
+        max_rws: 1024,
+        max_copy_rows: 256,
+        max_exp_steps: 256,
+        max_bytecode: 512,
+        max_mpt_rows: 1024,
+        max_inner_blocks: 1,
+        max_rlp_rows: 800,
+        ..Default::default()
+    };
+    let block_data = BlockData::new_from_geth_data_with_params(block_2tx(), circuits_params);
+    let eth_block = &block_data.eth_block;
+
+    let mut checker =
+        CircuitCapacityChecker::new(block_data.new_circuit_input_builder(), 20).unwrap();
+    let usage_1tx = checker
+        .apply_tx(
+            eth_block,
+            &eth_block.transactions[0],
+            &block_data.geth_traces[0],
+        )
+        .unwrap()
+        .clone();
+    assert!(usage_1tx.is_ok);
+    let usage_2tx = checker
+        .apply_tx(
+            eth_block,
+            &eth_block.transactions[1],
+            &block_data.geth_traces[1],
+        )
+        .unwrap()
+        .clone();
+    assert!(usage_2tx.row_number >= usage_1tx.row_number);
+
+    // The per-tx deltas never under-estimate the usage of the whole chunk.
+    let mut builder = block_data.new_circuit_input_builder();
+    builder
+        .handle_block_inner(eth_block, &block_data.geth_traces, false, false)
+        .unwrap();
+    builder.block.circuits_params.max_rws = 0;
+    builder.set_value_ops_call_context_rwc_eor();
+    builder.set_end_block().unwrap();
+    let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+    let chunk_usage = SuperCircuit::<Fr, 0, 0, 0, 0>::min_num_rows_block_subcircuits(&block);
+    for (usage, chunk) in usage_2tx.row_usage_details.iter().zip(chunk_usage.iter()) {
+        assert_eq!(usage.name, chunk.name);
+        assert!(usage.row_num_real >= chunk.row_num_real, "{}", usage.name);
+    }
+
+    // Only the last tx can be rolled back.
+    assert!(checker.rollback_last_tx());
+    assert!(!checker.rollback_last_tx());
+    assert_eq!(checker.num_txs(), 1);
+    assert_eq!(checker.row_usage().row_number, usage_1tx.row_number);
+
+    // Packing the rolled back tx again gives the same usage.
+    let usage = checker
+        .apply_tx(
+            eth_block,
+            &eth_block.transactions[1],
+            &block_data.geth_traces[1],
+        )
+        .unwrap();
+    assert_eq!(usage.row_number, usage_2tx.row_number);
+    assert_eq!(checker.num_txs(), 2);
+
+    // No sub-circuit fits in a Super Circuit with a tiny degree.
+    let mut checker =
+        CircuitCapacityChecker::new(block_data.new_circuit_input_builder(), 4).unwrap();
+    let usage = checker
+        .apply_tx(
+            eth_block,
+            &eth_block.transactions[0],
+            &block_data.geth_traces[0],
+        )
+        .unwrap();
+    assert!(!usage.is_ok);
+    assert!(!usage.bottleneck.is_empty());
+}
+
+#[test]
+fn split_super_circuit_degree() {
+    let mut cs = ConstraintSystem::<Fr>::default();
+    SplitSuperCircuit::<1, 32, 64, 0x100>::configure(&mut cs);
+    assert!(cs.degree() <= 9);
+}
+
+fn witness_block_exp(exponent: u64) -> Block<Fr> {
+    let code = bytecode! {
+        PUSH1(exponent)
+        PUSH1(0x03)
+        EXP
+        STOP
+    };
+    let geth_data: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+        .unwrap()
+        .into();
+    let circuits_params = CircuitsParams {
+        max_txs: 1,
+        max_calldata: 256,
+        max_rws: 256,
+        max_copy_rows: 256,
+        max_exp_steps: 256,
+        max_bytecode: 512,
+        max_mpt_rows: 512,
+        max_inner_blocks: 1,
+        max_rlp_rows: 500,
+        ..Default::default()
+    };
+    let block_data = BlockData::new_from_geth_data_with_params(geth_data, circuits_params);
+    let mut builder = block_data.new_circuit_input_builder();
+    builder
+        .handle_block(&block_data.eth_block, &block_data.geth_traces)
+        .expect("could not handle block tx");
+    let mut block = block_convert(&builder.block, &builder.code_db).unwrap();
+    block.randomness = Fr::from(TEST_MOCK_RANDOMNESS);
+    block
+}
+
+// Challenges of the fingerprints in the tests, instead of the ones squeezed
+// from the commitments of the tables.
+fn test_fingerprint_challenges() -> [Fr; NUM_FINGERPRINT_CHALLENGES] {
+    array_init::array_init(|i| Fr::from(i as u64 + 2))
+}
+
+#[cfg(feature = "scroll")]
+#[test]
+fn test_split_super_circuit_fingerprints() {
+    let block = witness_block_exp(5);
+    assert!(!block.exp_events.is_empty());
+
+    let (_, mut circuit, _) =
+        SplitSuperCircuit::<1, 256, 1, TEST_MOCK_RANDOMNESS>::build_from_witness_block(
+            block.clone(),
+        )
+        .unwrap();
+    circuit.challenges = test_fingerprint_challenges();
+    let mut exp_circuit = DetachedCircuit::<ExpCircuit<Fr>>::new_from_block(&block);
+    exp_circuit.challenges = test_fingerprint_challenges();
+    let mut modexp_circuit = DetachedCircuit::<ModExpCircuit<Fr>>::new_from_block(&block);
+    modexp_circuit.challenges = test_fingerprint_challenges();
+
+    let super_instance = circuit.instance();
+    let exp_instance = exp_circuit.instance();
+    let modexp_instance = modexp_circuit.instance();
+    let fingerprints = super_instance.last().unwrap();
+    let exp_fingerprint = exp_instance.last().unwrap();
+    let modexp_fingerprint = modexp_instance.last().unwrap();
+    assert_eq!(fingerprints.len(), NUM_FINGERPRINT_CHALLENGES + 2);
+    assert_eq!(
+        fingerprints[..NUM_FINGERPRINT_CHALLENGES],
+        test_fingerprint_challenges()
+    );
+    assert_eq!(
+        fingerprints[..NUM_FINGERPRINT_CHALLENGES],
+        exp_fingerprint[..NUM_FINGERPRINT_CHALLENGES]
+    );
+    assert_eq!(
+        fingerprints[..NUM_FINGERPRINT_CHALLENGES],
+        modexp_fingerprint[..NUM_FINGERPRINT_CHALLENGES]
+    );
+    assert_eq!(
+        fingerprints[NUM_FINGERPRINT_CHALLENGES],
+        exp_fingerprint[NUM_FINGERPRINT_CHALLENGES]
+    );
+    assert_eq!(
+        fingerprints[NUM_FINGERPRINT_CHALLENGES + 1],
+        modexp_fingerprint[NUM_FINGERPRINT_CHALLENGES]
+    );
+    // The exp events are in the fingerprint.
+    assert_ne!(exp_fingerprint[NUM_FINGERPRINT_CHALLENGES], Fr::from(1));
+}
+
+#[cfg(feature = "scroll")]
+#[test]
+fn test_split_super_circuit_fingerprints_differ() {
+    let block = witness_block_exp(5);
+    let (_, mut circuit, _) =
+        SplitSuperCircuit::<1, 256, 1, TEST_MOCK_RANDOMNESS>::build_from_witness_block(
+            block.clone(),
+        )
+        .unwrap();
+    circuit.challenges = test_fingerprint_challenges();
+
+    // The detached exp circuit of another block is satisfied, but its table
+    // differs from the one loaded by the split super circuit.
+    let other_block = witness_block_exp(6);
+    let (k, mut exp_circuit, _) =
+        DetachedCircuit::<ExpCircuit<Fr>>::build_from_witness_block(&other_block);
+    exp_circuit.challenges = test_fingerprint_challenges();
+    let exp_instance = exp_circuit.instance();
+    let prover = MockProver::run(k, &exp_circuit, exp_instance.clone()).unwrap();
+    prover.assert_satisfied_par();
+
+    let fingerprints = circuit.instance().pop().unwrap();
+    let exp_fingerprint = exp_instance.last().unwrap();
+    assert_eq!(
+        fingerprints[..NUM_FINGERPRINT_CHALLENGES],
+        exp_fingerprint[..NUM_FINGERPRINT_CHALLENGES]
+    );
+    assert_ne!(
+        fingerprints[NUM_FINGERPRINT_CHALLENGES],
+        exp_fingerprint[NUM_FINGERPRINT_CHALLENGES]
+    );
+}
+
+#[test]
+fn test_detached_table_commitment_indices() {
+    // The advice columns of the exp table are the first ones of the detached
+    // exp circuit.
+    assert_eq!(
+        DetachedCircuit::<ExpCircuit<Fr>>::table_commitment_indices(),
+        (0..5).collect::<Vec<_>>()
+    );
+    // The tables of the split super circuit are committed in the first phase.
+    let indices = SplitSuperCircuit::<1, 32, 64, 0x100>::table_commitment_indices();
+    assert_eq!(indices.len(), 5 + 5);
+    assert!(indices.iter().all_unique());
+}
+
+#[test]
+fn test_detached_exp_circuit() {
+    let block = witness_block_exp(5);
+    let (k, mut circuit, _) = DetachedCircuit::<ExpCircuit<Fr>>::build_from_witness_block(&block);
+    circuit.challenges = test_fingerprint_challenges();
+    let instance = circuit.instance();
+    let prover = MockProver::run(k, &circuit, instance).unwrap();
+    prover.assert_satisfied_par();
+}
@@ -1,6 +1,7 @@
 //! Table definitions used cross-circuits
 
 use crate::{
+    blake2f_circuit::blake2f_bit,
     copy_circuit::util::number_or_hash_to_field,
     evm_circuit::util::{
         constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
@@ -17,9 +18,9 @@ use crate::{
 use bus_mapping::{
     circuit_input_builder::{
         BigModExp, CopyDataType, CopyEvent, CopyStep, EcAddOp, EcMulOp, EcPairingOp, ExpEvent,
-        PrecompileEcParams, N_BYTES_PER_PAIR, N_PAIRING_PER_OP,
+        PrecompileEcParams,
     },
-    precompile::PrecompileCalls,
+    precompile::{PrecompileCalls, MODEXP_INPUT_LIMIT, MODEXP_SIZE_LIMIT},
 };
 use core::iter::once;
 use eth_types::{sign_types::SignData, Field, ToLittleEndian, ToScalar, ToWord, Word, U256};
@@ -48,6 +49,9 @@ use halo2_proofs::plonk::SecondPhase;
 use halo2_proofs::plonk::TableColumn;
 use itertools::Itertools;
 use keccak256::plain::Keccak;
+use ripemd::Ripemd160;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::array;
 use strum_macros::{EnumCount, EnumIter};
 
@@ -179,6 +183,28 @@ pub enum TxFieldTag {
     TxType,
     /// The block number in which this tx is included.
     BlockNumber,
+    /// BlobVersionedHash: Versioned hash of the blob at `index` (EIP-4844)
+    BlobVersionedHash,
+    /// MaxPriorityFeePerGas (EIP-1559)
+    MaxPriorityFeePerGas,
+    /// MaxFeePerGas (EIP-1559)
+    MaxFeePerGas,
+    /// MaxFeePerBlobGas (EIP-4844)
+    MaxFeePerBlobGas,
+    /// AccessListAddressesLen: Number of addresses in the access list (EIP-2930)
+    AccessListAddressesLen,
+    /// AccessListStorageKeysLen: Number of storage keys in the access list
+    /// (EIP-2930)
+    AccessListStorageKeysLen,
+    /// AccessListRwCounter: The rw counter at which BeginTx starts to warm up
+    /// the access list
+    AccessListRwCounter,
+    /// AuthorizationListLen: Number of tuples in the authorization list
+    /// (EIP-7702)
+    AuthorizationListLen,
+    /// AuthorizationListAuthoritiesLen: Number of tuples in the authorization
+    /// list whose authority is recovered, which are applied by BeginTx
+    AuthorizationListAuthoritiesLen,
 }
 impl_expr!(TxFieldTag);
 
@@ -377,7 +403,7 @@ impl<F: Field> LookupTable<F> for TxTable {
 }
 
 /// Tag to identify the operation type in a RwTable row
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize)]
 pub enum RwTableTag {
     /// Start (used for padding)
     Start = 1,
@@ -401,6 +427,10 @@ pub enum RwTableTag {
     TxLog,
     /// Tx Receipt operation
     TxReceipt,
+    /// Transient Storage operation
+    TransientStorage,
+    /// Tx Created Account operation
+    TxCreatedAccount,
 }
 impl_expr!(RwTableTag);
 
@@ -414,6 +444,8 @@ impl RwTableTag {
                 | RwTableTag::TxRefund
                 | RwTableTag::Account
                 | RwTableTag::AccountStorage
+                | RwTableTag::TransientStorage
+                | RwTableTag::TxCreatedAccount
         )
     }
 }
@@ -425,7 +457,9 @@ impl From<RwTableTag> for usize {
 }
 
 /// Tag for an AccountField in RwTable
-#[derive(Clone, Copy, Debug, EnumIter, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(
+    Clone, Copy, Debug, EnumIter, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
 pub enum AccountFieldTag {
     /// Nonce field
     Nonce,
@@ -443,7 +477,7 @@ pub enum AccountFieldTag {
 impl_expr!(AccountFieldTag);
 
 /// Tag for a TxLogField in RwTable
-#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
 pub enum TxLogFieldTag {
     /// Address field
     Address = 1,
@@ -455,7 +489,7 @@ pub enum TxLogFieldTag {
 impl_expr!(TxLogFieldTag);
 
 /// Tag for a TxReceiptField in RwTable
-#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, EnumCount)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, EnumCount, Serialize, Deserialize)]
 pub enum TxReceiptFieldTag {
     /// Tx result
     PostStateOrStatus = 1,
@@ -467,7 +501,7 @@ pub enum TxReceiptFieldTag {
 impl_expr!(TxReceiptFieldTag);
 
 /// Tag for a CallContextField in RwTable
-#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
 pub enum CallContextFieldTag {
     /// RwCounterEndOfReversion
     RwCounterEndOfReversion = 1,
@@ -1201,6 +1235,14 @@ pub enum BlockContextFieldTag {
     /// included in this block which also taking skipped l1 msgs into account.
     /// This could possibly be larger than NumTxs.
     NumAllTxs,
+    /// Excess Blob Gas field (EIP-4844)
+    ExcessBlobGas,
+    /// Blob Base Fee, derived from the excess blob gas. Although this is not a
+    /// field in the block header, we add it here for the BLOBBASEFEE opcode.
+    BlobBaseFee,
+    /// Hard fork active in the block, derived from the chain spec. It selects
+    /// the EVM rules of the block.
+    Hardfork,
 }
 impl_expr!(BlockContextFieldTag);
 
@@ -1469,6 +1511,458 @@ impl KeccakTable {
     }
 }
 
+/// SHA256 Table, used to verify SHA256 hashing from RLC'ed input.
+#[derive(Clone, Debug)]
+pub struct Sha256Table {
+    /// True when the row is enabled
+    pub q_enable: Column<Fixed>,
+    /// True when the row is final
+    pub is_final: Column<Advice>,
+    /// Byte array input as `RLC(reversed(input))`
+    pub input_rlc: Column<Advice>, // RLC of input bytes
+    /// Byte array input length
+    pub input_len: Column<Advice>,
+    /// Digest as `RLC(reversed(digest))`
+    pub output_rlc: Column<Advice>, // RLC of digest bytes
+}
+
+impl<F: Field> LookupTable<F> for Sha256Table {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.q_enable.into(),
+            self.is_final.into(),
+            self.input_rlc.into(),
+            self.input_len.into(),
+            self.output_rlc.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("q_enable"),
+            String::from("is_final"),
+            String::from("input_rlc"),
+            String::from("input_len"),
+            String::from("output_rlc"),
+        ]
+    }
+}
+
+impl Sha256Table {
+    /// Construct a new Sha256Table
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            q_enable: meta.fixed_column(),
+            is_final: meta.advice_column(),
+            input_rlc: meta.advice_column_in(SecondPhase),
+            input_len: meta.advice_column(),
+            output_rlc: meta.advice_column_in(SecondPhase),
+        }
+    }
+
+    /// Generate the sha256 table assignments from a byte array input.
+    /// Used only for dev_load
+    pub fn assignments<F: Field>(
+        input: &[u8],
+        challenges: &Challenges<Value<F>>,
+    ) -> Vec<[Value<F>; 4]> {
+        let input_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(input.iter().rev(), challenge));
+        let input_len = F::from(input.len() as u64);
+        let output = Sha256::digest(input);
+        let output_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(output.iter().rev(), challenge));
+
+        vec![[
+            Value::known(F::one()),
+            input_rlc,
+            Value::known(input_len),
+            output_rlc,
+        ]]
+    }
+
+    /// Assign a table row for sha256 table
+    /// Used inside sha256 circuit
+    /// q_enable assigned inside sha256 circuit
+    pub fn assign_row<F: Field>(
+        &self,
+        region: &mut Region<F>,
+        offset: usize,
+        values: [Value<F>; 4],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let mut res = vec![];
+        for (&column, value) in <Sha256Table as LookupTable<F>>::advice_columns(self)
+            .iter()
+            .zip(values.iter())
+        {
+            res.push(region.assign_advice(
+                || format!("assign {offset}"),
+                column,
+                offset,
+                || *value,
+            )?);
+        }
+        Ok(res)
+    }
+
+    /// Provide this function for the case that we want to consume a sha256
+    /// table but without running the full sha256 circuit
+    pub fn dev_load<'a, F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        inputs: impl IntoIterator<Item = &'a Vec<u8>> + Clone,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "sha256 table",
+            |mut region| {
+                let mut offset = 0;
+                for column in <Sha256Table as LookupTable<F>>::advice_columns(self) {
+                    region.assign_fixed(
+                        || "sha256 table all-zero row",
+                        self.q_enable,
+                        offset,
+                        || Value::known(F::one()),
+                    )?;
+                    region.assign_advice(
+                        || "sha256 table all-zero row",
+                        column,
+                        offset,
+                        || Value::known(F::zero()),
+                    )?;
+                }
+                offset += 1;
+
+                let sha256_table_columns = <Sha256Table as LookupTable<F>>::advice_columns(self);
+                for input in inputs.clone() {
+                    for row in Self::assignments(input, challenges) {
+                        region.assign_fixed(
+                            || format!("sha256 table row {offset}"),
+                            self.q_enable,
+                            offset,
+                            || Value::known(F::one()),
+                        )?;
+                        for (&column, value) in sha256_table_columns.iter().zip_eq(row) {
+                            region.assign_advice(
+                                || format!("sha256 table row {offset}"),
+                                column,
+                                offset,
+                                || value,
+                            )?;
+                        }
+                        offset += 1;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// RIPEMD-160 Table, used to verify RIPEMD-160 hashing from RLC'ed input.
+#[derive(Clone, Debug)]
+pub struct Ripemd160Table {
+    /// True when the row is enabled
+    pub q_enable: Column<Fixed>,
+    /// True when the row is final
+    pub is_final: Column<Advice>,
+    /// Byte array input as `RLC(reversed(input))`
+    pub input_rlc: Column<Advice>, // RLC of input bytes
+    /// Byte array input length
+    pub input_len: Column<Advice>,
+    /// Digest as `RLC(reversed(digest))`
+    pub output_rlc: Column<Advice>, // RLC of digest bytes
+}
+
+impl<F: Field> LookupTable<F> for Ripemd160Table {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.q_enable.into(),
+            self.is_final.into(),
+            self.input_rlc.into(),
+            self.input_len.into(),
+            self.output_rlc.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("q_enable"),
+            String::from("is_final"),
+            String::from("input_rlc"),
+            String::from("input_len"),
+            String::from("output_rlc"),
+        ]
+    }
+}
+
+impl Ripemd160Table {
+    /// Construct a new Ripemd160Table
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            q_enable: meta.fixed_column(),
+            is_final: meta.advice_column(),
+            input_rlc: meta.advice_column_in(SecondPhase),
+            input_len: meta.advice_column(),
+            output_rlc: meta.advice_column_in(SecondPhase),
+        }
+    }
+
+    /// Generate the ripemd160 table assignments from a byte array input.
+    /// Used only for dev_load
+    pub fn assignments<F: Field>(
+        input: &[u8],
+        challenges: &Challenges<Value<F>>,
+    ) -> Vec<[Value<F>; 4]> {
+        let input_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(input.iter().rev(), challenge));
+        let input_len = F::from(input.len() as u64);
+        let output = Ripemd160::digest(input);
+        let output_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(output.iter().rev(), challenge));
+
+        vec![[
+            Value::known(F::one()),
+            input_rlc,
+            Value::known(input_len),
+            output_rlc,
+        ]]
+    }
+
+    /// Assign a table row for ripemd160 table
+    /// Used inside ripemd160 circuit
+    /// q_enable assigned inside ripemd160 circuit
+    pub fn assign_row<F: Field>(
+        &self,
+        region: &mut Region<F>,
+        offset: usize,
+        values: [Value<F>; 4],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let mut res = vec![];
+        for (&column, value) in <Ripemd160Table as LookupTable<F>>::advice_columns(self)
+            .iter()
+            .zip(values.iter())
+        {
+            res.push(region.assign_advice(
+                || format!("assign {offset}"),
+                column,
+                offset,
+                || *value,
+            )?);
+        }
+        Ok(res)
+    }
+
+    /// Provide this function for the case that we want to consume a ripemd160
+    /// table but without running the full ripemd160 circuit
+    pub fn dev_load<'a, F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        inputs: impl IntoIterator<Item = &'a Vec<u8>> + Clone,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "ripemd160 table",
+            |mut region| {
+                let mut offset = 0;
+                for column in <Ripemd160Table as LookupTable<F>>::advice_columns(self) {
+                    region.assign_fixed(
+                        || "ripemd160 table all-zero row",
+                        self.q_enable,
+                        offset,
+                        || Value::known(F::one()),
+                    )?;
+                    region.assign_advice(
+                        || "ripemd160 table all-zero row",
+                        column,
+                        offset,
+                        || Value::known(F::zero()),
+                    )?;
+                }
+                offset += 1;
+
+                let ripemd160_table_columns =
+                    <Ripemd160Table as LookupTable<F>>::advice_columns(self);
+                for input in inputs.clone() {
+                    for row in Self::assignments(input, challenges) {
+                        region.assign_fixed(
+                            || format!("ripemd160 table row {offset}"),
+                            self.q_enable,
+                            offset,
+                            || Value::known(F::one()),
+                        )?;
+                        for (&column, value) in ripemd160_table_columns.iter().zip_eq(row) {
+                            region.assign_advice(
+                                || format!("ripemd160 table row {offset}"),
+                                column,
+                                offset,
+                                || value,
+                            )?;
+                        }
+                        offset += 1;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// BLAKE2F Table, used to verify the compression function F from RLC'ed
+/// input.
+#[derive(Clone, Debug)]
+pub struct Blake2fTable {
+    /// True when the row is enabled
+    pub q_enable: Column<Fixed>,
+    /// True when the row is final
+    pub is_final: Column<Advice>,
+    /// Byte array input as `RLC(reversed(input))`
+    pub input_rlc: Column<Advice>, // RLC of input bytes
+    /// Number of rounds, which is also the gas cost of the call
+    pub rounds: Column<Advice>,
+    /// Output as `RLC(reversed(output))`
+    pub output_rlc: Column<Advice>, // RLC of output bytes
+}
+
+impl<F: Field> LookupTable<F> for Blake2fTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.q_enable.into(),
+            self.is_final.into(),
+            self.input_rlc.into(),
+            self.rounds.into(),
+            self.output_rlc.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("q_enable"),
+            String::from("is_final"),
+            String::from("input_rlc"),
+            String::from("rounds"),
+            String::from("output_rlc"),
+        ]
+    }
+}
+
+impl Blake2fTable {
+    /// Construct a new Blake2fTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            q_enable: meta.fixed_column(),
+            is_final: meta.advice_column(),
+            input_rlc: meta.advice_column_in(SecondPhase),
+            rounds: meta.advice_column(),
+            output_rlc: meta.advice_column_in(SecondPhase),
+        }
+    }
+
+    /// Generate the blake2f table assignments from a 213 bytes input.
+    /// Used only for dev_load
+    pub fn assignments<F: Field>(
+        input: &[u8],
+        challenges: &Challenges<Value<F>>,
+    ) -> Vec<[Value<F>; 4]> {
+        let input_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(input.iter().rev(), challenge));
+        let rounds = u32::from_be_bytes(input[..4].try_into().unwrap());
+        let output = blake2f_bit::compress(input);
+        let output_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(output.iter().rev(), challenge));
+
+        vec![[
+            Value::known(F::one()),
+            input_rlc,
+            Value::known(F::from(rounds as u64)),
+            output_rlc,
+        ]]
+    }
+
+    /// Assign a table row for blake2f table
+    /// Used inside blake2f circuit
+    /// q_enable assigned inside blake2f circuit
+    pub fn assign_row<F: Field>(
+        &self,
+        region: &mut Region<F>,
+        offset: usize,
+        values: [Value<F>; 4],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let mut res = vec![];
+        for (&column, value) in <Blake2fTable as LookupTable<F>>::advice_columns(self)
+            .iter()
+            .zip(values.iter())
+        {
+            res.push(region.assign_advice(
+                || format!("assign {offset}"),
+                column,
+                offset,
+                || *value,
+            )?);
+        }
+        Ok(res)
+    }
+
+    /// Provide this function for the case that we want to consume a blake2f
+    /// table but without running the full blake2f circuit
+    pub fn dev_load<'a, F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        inputs: impl IntoIterator<Item = &'a Vec<u8>> + Clone,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "blake2f table",
+            |mut region| {
+                let mut offset = 0;
+                for column in <Blake2fTable as LookupTable<F>>::advice_columns(self) {
+                    region.assign_fixed(
+                        || "blake2f table all-zero row",
+                        self.q_enable,
+                        offset,
+                        || Value::known(F::one()),
+                    )?;
+                    region.assign_advice(
+                        || "blake2f table all-zero row",
+                        column,
+                        offset,
+                        || Value::known(F::zero()),
+                    )?;
+                }
+                offset += 1;
+
+                let blake2f_table_columns = <Blake2fTable as LookupTable<F>>::advice_columns(self);
+                for input in inputs.clone() {
+                    for row in Self::assignments(input, challenges) {
+                        region.assign_fixed(
+                            || format!("blake2f table row {offset}"),
+                            self.q_enable,
+                            offset,
+                            || Value::known(F::one()),
+                        )?;
+                        for (&column, value) in blake2f_table_columns.iter().zip_eq(row) {
+                            region.assign_advice(
+                                || format!("blake2f table row {offset}"),
+                                column,
+                                offset,
+                                || value,
+                            )?;
+                        }
+                        offset += 1;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
 /// Copy Table, used to verify copies of byte chunks between Memory, Bytecode,
 /// TxLogs and TxCallData.
 #[derive(Clone, Copy, Debug)]
@@ -1583,6 +2077,7 @@ impl CopyTable {
 
         let mut rw_counter = copy_event.rw_counter_start();
         let mut rwc_inc_left = copy_event.rw_counter_delta();
+        let is_memory_copy = copy_event.is_memory_copy();
 
         let mut reader = CopyThread {
             tag: copy_event.src_type,
@@ -1683,6 +2178,16 @@ impl CopyTable {
             // is_code
             let is_code = Value::known(copy_step.is_code.map_or(F::zero(), |v| F::from(v)));
 
+            // A memory copy reads all the source words before writing the destination words. The
+            // reader counts the RWs of both threads and the writer those of the writes only.
+            if is_memory_copy {
+                let num_words = copy_event.rw_counter_delta() / 2;
+                let word_idx = step_idx as u64 / 64;
+                let reads_before = if is_read_step { 0 } else { num_words };
+                rw_counter = copy_event.rw_counter_start() + reads_before + word_idx;
+                rwc_inc_left = copy_event.rw_counter_delta() - reads_before - word_idx;
+            }
+
             // For LOG, format the address including the log_id.
             let addr = if thread.tag == CopyDataType::TxLog {
                 build_tx_log_address(thread.addr, TxLogFieldTag::Data, copy_event.log_id.unwrap())
@@ -2089,6 +2594,16 @@ pub struct RlpFsmRlpTable {
     pub is_output: Column<Advice>,
     /// Whether or not the current tag's value was nil.
     pub is_none: Column<Advice>,
+    /// The number of access list addresses decoded up to the current row.
+    /// It's not part of `columns` and only looked up by the access list rows
+    /// of the tx circuit.
+    pub access_list_idx: Column<Advice>,
+    /// The number of access list storage keys decoded up to the current row.
+    pub storage_key_idx: Column<Advice>,
+    /// The number of blob versioned hashes decoded up to the current row.
+    pub blob_idx: Column<Advice>,
+    /// The number of authorizations decoded up to the current row.
+    pub authorization_idx: Column<Advice>,
 }
 
 impl<F: Field> LookupTable<F> for RlpFsmRlpTable {
@@ -2134,6 +2649,10 @@ impl RlpFsmRlpTable {
             tag_length: meta.advice_column(),
             is_output: meta.advice_column(),
             is_none: meta.advice_column(),
+            access_list_idx: meta.advice_column(),
+            storage_key_idx: meta.advice_column(),
+            blob_idx: meta.advice_column(),
+            authorization_idx: meta.advice_column(),
         }
     }
 
@@ -2201,6 +2720,26 @@ impl RlpFsmRlpTable {
                             self.is_none.into(),
                             Value::known(F::from(row.is_none as u64)),
                         ),
+                        (
+                            "access_list_idx",
+                            self.access_list_idx.into(),
+                            Value::known(F::from(row.access_list_idx as u64)),
+                        ),
+                        (
+                            "storage_key_idx",
+                            self.storage_key_idx.into(),
+                            Value::known(F::from(row.storage_key_idx as u64)),
+                        ),
+                        (
+                            "blob_idx",
+                            self.blob_idx.into(),
+                            Value::known(F::from(row.blob_idx as u64)),
+                        ),
+                        (
+                            "authorization_idx",
+                            self.authorization_idx.into(),
+                            Value::known(F::from(row.authorization_idx as u64)),
+                        ),
                     ];
 
                     for cell in cells.into_iter() {
@@ -2586,80 +3125,201 @@ impl EccTable {
 }
 
 /// Lookup table embedded in the modexp circuit for precompile.
+///
+/// Each event takes [`MODEXP_SIZE_LIMIT`] rows, holding one byte of each
+/// integer per row in big-endian order, as the integers are left-padded with
+/// zeroes. The RLC columns accumulate the bytes of the event so far, so that
+/// the last row of the event holds the RLC of the integers, which equals the
+/// RLC of their bytes without the leading zeroes, and the bit length of the
+/// exponent, as looked up by the EVM circuit.
 #[derive(Clone, Copy, Debug)]
 pub struct ModExpTable {
-    /// Use for indicate beginning of a limbs group
+    /// Whether the row belongs to an event
+    pub q_enable: Column<Fixed>,
+    /// Whether the row is the first row of an event
     pub q_head: Column<Fixed>,
-    /// base represented by limbs
+    /// Whether the row is the last row of an event
+    pub q_tail: Column<Fixed>,
+    /// byte of base
     pub base: Column<Advice>,
-    /// exp represented by limbs
+    /// byte of exponent
     pub exp: Column<Advice>,
-    /// modulus represented by limbs
+    /// byte of modulus
     pub modulus: Column<Advice>,
-    /// result represented by limbs
+    /// byte of result
     pub result: Column<Advice>,
+    /// bit length of the exponent bytes so far
+    pub exp_bit_len: Column<Advice>,
+    /// RLC of the base bytes so far
+    pub base_rlc: Column<Advice>,
+    /// RLC of the exponent bytes so far
+    pub exp_rlc: Column<Advice>,
+    /// RLC of the modulus bytes so far
+    pub modulus_rlc: Column<Advice>,
+    /// RLC of the result bytes so far
+    pub result_rlc: Column<Advice>,
 }
 
 impl ModExpTable {
-    /// Construct the modexp table.
-    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
-        let ret = Self {
+    /// Construct the modexp table, with the gates of its RLC columns.
+    pub fn construct<F: Field>(
+        meta: &mut ConstraintSystem<F>,
+        challenges: &Challenges<Expression<F>>,
+    ) -> Self {
+        let table = Self {
+            q_enable: meta.fixed_column(),
             q_head: meta.fixed_column(),
+            q_tail: meta.fixed_column(),
             base: meta.advice_column(),
             exp: meta.advice_column(),
             modulus: meta.advice_column(),
             result: meta.advice_column(),
+            exp_bit_len: meta.advice_column(),
+            base_rlc: meta.advice_column_in(SecondPhase),
+            exp_rlc: meta.advice_column_in(SecondPhase),
+            modulus_rlc: meta.advice_column_in(SecondPhase),
+            result_rlc: meta.advice_column_in(SecondPhase),
         };
-        meta.enable_equality(ret.base);
-        meta.enable_equality(ret.exp);
-        meta.enable_equality(ret.modulus);
-        meta.enable_equality(ret.result);
-        ret
+
+        meta.create_gate("modexp_table: rlc of the bytes", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+            let is_head = meta.query_fixed(table.q_head, Rotation::cur());
+            for (byte, rlc) in table.byte_rlc_columns() {
+                let rlc_prev = not::expr(is_head.expr()) * meta.query_advice(rlc, Rotation::prev());
+                cb.require_equal(
+                    "rlc::cur == rlc::prev * rand + byte::cur",
+                    meta.query_advice(rlc, Rotation::cur()),
+                    rlc_prev * challenges.keccak_input() + meta.query_advice(byte, Rotation::cur()),
+                );
+            }
+            cb.gate(meta.query_fixed(table.q_enable, Rotation::cur()))
+        });
+
+        table
     }
 
-    /// helper for devide a U256 into 3 108bit limbs
-    pub fn split_u256_108bit_limbs(word: &Word) -> [u128; 3] {
-        let bit108 = 1u128 << 108;
-        let (next, limb0) = word.div_mod(U256::from(bit108));
-        let (limb2, limb1) = next.div_mod(U256::from(bit108));
-        [limb0.as_u128(), limb1.as_u128(), limb2.as_u128()]
+    fn byte_rlc_columns(&self) -> [(Column<Advice>, Column<Advice>); 4] {
+        [
+            (self.base, self.base_rlc),
+            (self.exp, self.exp_rlc),
+            (self.modulus, self.modulus_rlc),
+            (self.result, self.result_rlc),
+        ]
     }
 
-    /// helper for obtain the modulus of a U256 in Fr
-    pub fn native_u256<F: Field>(word: &Word) -> F {
-        let minus1 = -F::one();
-        let (div, _) = word.div_mod(Word::from_little_endian(minus1.to_repr().as_ref()));
-        let div = div.checked_add(Word::from(1u64)).unwrap();
-        let mut bytes = [0u8; 64];
-        div.to_little_endian(&mut bytes[..32]);
-        F::from_bytes_wide(&bytes)
+    /// Bit length of the exponent bytes up to each byte.
+    pub fn exp_bit_lens(exponent: &[u8; MODEXP_SIZE_LIMIT]) -> Vec<u64> {
+        exponent
+            .iter()
+            .scan(0u64, |bit_len, &byte| {
+                *bit_len = if *bit_len == 0 {
+                    8 - byte.leading_zeros() as u64
+                } else {
+                    *bit_len + 8
+                };
+                Some(*bit_len)
+            })
+            .collect()
     }
 
-    /// fill a blank 4-row region start from offset for empty lookup
-    pub fn fill_blank<F: Field>(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
-        layouter.assign_region(
-            || "modexp table blank region",
-            |mut region| {
-                for i in 0..4 {
-                    // fill last totally 0 row
-                    region.assign_fixed(
-                        || "modexp table blank head row",
-                        self.q_head,
-                        i,
-                        || Value::known(F::zero()),
-                    )?;
-                    for &col in [&self.base, &self.exp, &self.modulus, &self.result] {
-                        region.assign_advice(
-                            || "modexp table blank limb row",
-                            col,
-                            i,
-                            || Value::known(F::zero()),
-                        )?;
-                    }
-                }
-                Ok(())
-            },
-        )
+    /// Rows of the bytes of the event, as `[base, exp, modulus, result,
+    /// exp_bit_len]`.
+    pub fn assignments<F: Field>(event: &BigModExp) -> Vec<[F; 5]> {
+        Self::exp_bit_lens(&event.exponent)
+            .into_iter()
+            .enumerate()
+            .map(|(i, exp_bit_len)| {
+                [
+                    F::from(event.base[i] as u64),
+                    F::from(event.exponent[i] as u64),
+                    F::from(event.modulus[i] as u64),
+                    F::from(event.result[i] as u64),
+                    F::from(exp_bit_len),
+                ]
+            })
+            .collect()
+    }
+
+    /// Assign the rows of the event from the offset.
+    pub fn assign_event<F: Field>(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        event: &BigModExp,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let mut rlcs = [Value::known(F::zero()); 4];
+        for (i, row) in Self::assignments::<F>(event).into_iter().enumerate() {
+            let offset = offset + i;
+            for (column, value) in [
+                (self.q_enable, true),
+                (self.q_head, i == 0),
+                (self.q_tail, i == MODEXP_SIZE_LIMIT - 1),
+            ] {
+                region.assign_fixed(
+                    || format!("modexp table fixed row {offset}"),
+                    column,
+                    offset,
+                    || Value::known(F::from(value as u64)),
+                )?;
+            }
+            for (column, value) in [
+                self.base,
+                self.exp,
+                self.modulus,
+                self.result,
+                self.exp_bit_len,
+            ]
+            .into_iter()
+            .zip(row)
+            {
+                region.assign_advice(
+                    || format!("modexp table row {offset}"),
+                    column,
+                    offset,
+                    || Value::known(value),
+                )?;
+            }
+            for ((_, column), (rlc, byte)) in self
+                .byte_rlc_columns()
+                .into_iter()
+                .zip(rlcs.iter_mut().zip(row))
+            {
+                *rlc = *rlc * challenges.keccak_input() + Value::known(byte);
+                region.assign_advice(
+                    || format!("modexp table rlc row {offset}"),
+                    column,
+                    offset,
+                    || *rlc,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Assign a blank row at the offset, for the disabled lookups.
+    pub fn assign_blank_row<F: Field>(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+    ) -> Result<(), Error> {
+        for column in [self.q_enable, self.q_head, self.q_tail] {
+            region.assign_fixed(
+                || "modexp table blank row",
+                column,
+                offset,
+                || Value::known(F::zero()),
+            )?;
+        }
+        for column in <Self as LookupTable<F>>::advice_columns(self) {
+            region.assign_advice(
+                || "modexp table blank row",
+                column,
+                offset,
+                || Value::known(F::zero()),
+            )?;
+        }
+        Ok(())
     }
 
     /// Get assignments to the modexp table. Meant to be used for dev purposes.
@@ -2667,118 +3327,113 @@ impl ModExpTable {
         &self,
         layouter: &mut impl Layouter<F>,
         events: &[BigModExp],
+        challenges: &Challenges<Value<F>>,
     ) -> Result<(), Error> {
         layouter.assign_region(
             || "modexp table",
             |mut region| {
-                let mut offset = 0usize;
-
-                for event in events {
-                    for i in 0..4 {
-                        region.assign_fixed(
-                            || format!("modexp table head {}", offset + i),
-                            self.q_head,
-                            offset + i,
-                            || Value::known(if i == 0 { F::one() } else { F::zero() }),
-                        )?;
-                    }
-
-                    let base_limbs = Self::split_u256_108bit_limbs(&event.base);
-                    let exp_limbs = Self::split_u256_108bit_limbs(&event.exponent);
-                    let modulus_limbs = Self::split_u256_108bit_limbs(&event.modulus);
-                    let result_limbs = Self::split_u256_108bit_limbs(&event.result);
-
-                    for i in 0..3 {
-                        for (limbs, &col) in [base_limbs, exp_limbs, modulus_limbs, result_limbs]
-                            .zip([&self.base, &self.exp, &self.modulus, &self.result])
-                        {
-                            region.assign_advice(
-                                || format!("modexp table limb row {}", offset + i),
-                                col,
-                                offset + i,
-                                || Value::known(F::from_u128(limbs[i])),
-                            )?;
-                        }
-                    }
-
-                    // native is not used by lookup (and in fact it can be omitted in dev)
-                    for (word, &col) in [
-                        &event.base,
-                        &event.exponent,
-                        &event.modulus,
-                        &event.result,
-                    ]
-                    .zip([&self.base, &self.exp, &self.modulus, &self.result])
-                    {
-                        region.assign_advice(
-                            || format!("modexp table native row {}", offset + 3),
-                            col,
-                            offset + 3,
-                            || Value::<F>::known(Self::native_u256(word)),
-                        )?;
-                    }
-
-                    offset += 4;
+                for (n, event) in events.iter().enumerate() {
+                    self.assign_event(&mut region, n * MODEXP_SIZE_LIMIT, event, challenges)?;
                 }
-
-                Ok(())
+                self.assign_blank_row(&mut region, events.len() * MODEXP_SIZE_LIMIT)
             },
-        )?;
-        self.fill_blank(layouter)
+        )
     }
 }
 
 impl<F: Field> LookupTable<F> for ModExpTable {
     fn columns(&self) -> Vec<Column<Any>> {
         vec![
+            self.q_enable.into(),
             self.q_head.into(),
+            self.q_tail.into(),
             self.base.into(),
             self.exp.into(),
             self.modulus.into(),
             self.result.into(),
+            self.exp_bit_len.into(),
+            self.base_rlc.into(),
+            self.exp_rlc.into(),
+            self.modulus_rlc.into(),
+            self.result_rlc.into(),
         ]
     }
 
     fn annotations(&self) -> Vec<String> {
         vec![
+            String::from("q_enable"),
             String::from("is_head"),
+            String::from("is_tail"),
             String::from("base"),
             String::from("exp"),
             String::from("modulus"),
             String::from("result"),
+            String::from("exp_bit_len"),
+            String::from("base_rlc"),
+            String::from("exp_rlc"),
+            String::from("modulus_rlc"),
+            String::from("result_rlc"),
         ]
     }
 
     fn table_exprs(&self, meta: &mut VirtualCells<F>) -> Vec<Expression<F>> {
         vec![
-            // ignore the is_valid field as the EVM circuit's use-case (Ecrecover precompile) does
-            // not care whether the signature is valid or not. It only cares about the recovered
-            // address.
-            meta.query_fixed(self.q_head, Rotation::cur()),
-            meta.query_advice(self.base, Rotation::cur()),
-            meta.query_advice(self.exp, Rotation::cur()),
-            meta.query_advice(self.modulus, Rotation::cur()),
-            meta.query_advice(self.result, Rotation::cur()),
-            meta.query_advice(self.base, Rotation::next()),
-            meta.query_advice(self.exp, Rotation::next()),
-            meta.query_advice(self.modulus, Rotation::next()),
-            meta.query_advice(self.result, Rotation::next()),
-            meta.query_advice(self.base, Rotation(2)),
-            meta.query_advice(self.exp, Rotation(2)),
-            meta.query_advice(self.modulus, Rotation(2)),
-            meta.query_advice(self.result, Rotation(2)),
+            meta.query_fixed(self.q_tail, Rotation::cur()),
+            meta.query_advice(self.base_rlc, Rotation::cur()),
+            meta.query_advice(self.exp_rlc, Rotation::cur()),
+            meta.query_advice(self.modulus_rlc, Rotation::cur()),
+            meta.query_advice(self.result_rlc, Rotation::cur()),
+            meta.query_advice(self.exp_bit_len, Rotation::cur()),
         ]
     }
 }
 
-/// Lookup table for powers of keccak randomness up to exponent in [0, 128)
+/// View of the [`ModExpTable`] on its bytes, without the RLC columns which
+/// depend on the challenges of the circuit. It is the table shared by circuits
+/// which are proven apart, as the RLC columns are derived from the bytes by
+/// the gates of each circuit.
+#[derive(Clone, Copy, Debug)]
+pub struct ModExpBytesTable(pub ModExpTable);
+
+impl<F: Field> LookupTable<F> for ModExpBytesTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.0.q_enable.into(),
+            self.0.q_head.into(),
+            self.0.base.into(),
+            self.0.exp.into(),
+            self.0.modulus.into(),
+            self.0.result.into(),
+            self.0.exp_bit_len.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("q_enable"),
+            String::from("is_head"),
+            String::from("base"),
+            String::from("exp"),
+            String::from("modulus"),
+            String::from("result"),
+            String::from("exp_bit_len"),
+        ]
+    }
+}
+
+/// Number of powers of keccak randomness in the [`PowOfRandTable`], enough
+/// for the input of the modexp precompile call.
+pub const POW_OF_RAND_TABLE_SIZE: usize = MODEXP_INPUT_LIMIT + 1;
+
+/// Lookup table for powers of keccak randomness up to exponent in
+/// [0, POW_OF_RAND_TABLE_SIZE)
 #[derive(Clone, Copy, Debug)]
 pub struct PowOfRandTable {
     /// Whether the row is enabled.
     pub q_enable: Column<Fixed>,
     /// Whether the row is the first enabled row.
     pub is_first: Column<Fixed>,
-    /// exponent = [0, 1, 2, ..., 126, 127] for enabled rows.
+    /// exponent = [0, 1, 2, ..., POW_OF_RAND_TABLE_SIZE - 1] for enabled rows.
     /// exponent = 0 for all other rows (disabled).
     pub exponent: Column<Fixed>,
     /// power of keccak randomness.
@@ -2839,7 +3494,7 @@ impl PowOfRandTable {
             |mut region| {
                 let pows_of_rand =
                     std::iter::successors(Some(Value::known(F::one())), |&v| Some(v * r))
-                        .take(N_PAIRING_PER_OP * N_BYTES_PER_PAIR);
+                        .take(POW_OF_RAND_TABLE_SIZE);
 
                 for (idx, pow_of_rand) in pows_of_rand.enumerate() {
                     region.assign_fixed(
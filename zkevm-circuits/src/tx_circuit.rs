@@ -13,15 +13,26 @@ mod test;
 pub use dev::TxCircuitTester as TestTxCircuit;
 
 use crate::{
-    evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    evm_circuit::{
+        param::{N_BYTES_ACCOUNT_ADDRESS, SET_CODE_RW_DELTA},
+        util::{
+            constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+            rlc as rlc_expr,
+        },
+    },
     sig_circuit::SigCircuit,
     table::{
+        AccountFieldTag,
         BlockContextFieldTag::{CumNumTxs, NumAllTxs, NumTxs},
-        BlockTable, KeccakTable, LookupTable, RlpFsmRlpTable as RlpTable, SigTable, TxFieldTag,
+        BlockTable, BytecodeFieldTag, BytecodeTable, KeccakTable, LookupTable, PoseidonTable,
+        PowOfRandTable, RlpFsmRlpTable as RlpTable, RwTable, RwTableTag, SigTable, TxFieldTag,
         TxFieldTag::{
-            BlockNumber, CallData, CallDataGasCost, CallDataLength, CallDataRLC, CalleeAddress,
-            CallerAddress, ChainID, Gas, GasPrice, IsCreate, Nonce, SigR, SigS, SigV,
-            TxDataGasCost, TxHashLength, TxHashRLC, TxSignHash, TxSignLength, TxSignRLC,
+            AccessListAddressesLen, AccessListRwCounter, AccessListStorageKeysLen,
+            AuthorizationListAuthoritiesLen, AuthorizationListLen, BlobVersionedHash, BlockNumber,
+            CallData, CallDataGasCost, CallDataLength, CallDataRLC, CalleeAddress, CallerAddress,
+            ChainID, Gas, GasPrice, IsCreate, MaxFeePerBlobGas, MaxFeePerGas, MaxPriorityFeePerGas,
+            Nonce, SigR, SigS, SigV, TxDataGasCost, TxHashLength, TxHashRLC, TxSignHash,
+            TxSignLength, TxSignRLC,
         },
         TxTable, U16Table, U8Table,
     },
@@ -32,26 +43,42 @@ use crate::{
     witness,
     witness::{
         rlp_fsm::{Tag, ValueTagLength},
-        Format::{L1MsgHash, TxHashEip155, TxHashPreEip155, TxSignEip155, TxSignPreEip155},
+        Format::{
+            L1MsgHash, TxHashEip155, TxHashEip1559, TxHashEip2930, TxHashEip4844, TxHashEip7702,
+            TxHashPreEip155, TxSignEip155, TxSignEip1559, TxSignEip2930, TxSignEip4844,
+            TxSignEip7702, TxSignPreEip155,
+        },
         RlpTag,
         RlpTag::{GasCost, Len, Null, RLC},
         Tag::TxType as RLPTxType,
         Transaction,
     },
 };
-use bus_mapping::circuit_input_builder::keccak_inputs_sign_verify;
+use bus_mapping::{
+    circuit_input_builder::{keccak_inputs_sign_verify, AppliedAuthorization},
+    state_db::CodeDB,
+    util::KECCAK_CODE_HASH_ZERO,
+};
 use eth_types::{
+    evm_types::{
+        block_utils::MAX_BLOBS_PER_BLOCK, delegation_code, GasCost, DELEGATION_CODE_SIZE,
+        DELEGATION_PREFIX,
+    },
     geth_types::{
-        TxType,
-        TxType::{Eip155, L1Msg, PreEip155},
+        SetCodeAuthorization, TxType,
+        TxType::{Eip155, Eip1559, Eip2930, Eip4844, Eip7702, L1Msg, PreEip155},
+        EIP1559_TX_TYPE, EIP2930_TX_TYPE, EIP4844_TX_TYPE, EIP7702_AUTH_MAGIC, EIP7702_TX_TYPE,
     },
     sign_types::SignData,
-    Address, Field, ToAddress, ToBigEndian, ToLittleEndian, ToScalar,
+    Address, Field, ToAddress, ToBigEndian, ToLittleEndian, ToScalar, ToWord, Word,
 };
 use gadgets::{
     binary_number::{BinaryNumberChip, BinaryNumberConfig},
     comparator::{ComparatorChip, ComparatorConfig, ComparatorInstruction},
     is_equal::{IsEqualChip, IsEqualConfig, IsEqualInstruction},
+    is_zero::{
+        IsZeroChip as IsZeroGadgetChip, IsZeroConfig as IsZeroGadgetConfig, IsZeroInstruction,
+    },
     less_than::{LtChip, LtConfig, LtInstruction},
     util::{and, not, select, sum, Expr},
 };
@@ -76,7 +103,7 @@ use halo2_proofs::plonk::SecondPhase;
 use itertools::Itertools;
 
 /// Number of rows of one tx occupies in the fixed part of tx table
-pub const TX_LEN: usize = 23;
+pub const TX_LEN: usize = 31 + MAX_BLOBS_PER_BLOCK;
 /// Offset of TxHash tag in the tx table
 pub const TX_HASH_OFFSET: usize = 21;
 /// Offset of ChainID tag in the tx table
@@ -90,6 +117,12 @@ enum LookupCondition {
     L1MsgHash,
     RlpSignTag,
     RlpHashTag,
+    // lookup into the access list rows
+    AccessList,
+    // lookup into the authorization rows
+    Authorization,
+    // lookup the number of blobs into rlp table
+    LastBlob,
     // lookup into keccak table
     Keccak,
 }
@@ -105,7 +138,7 @@ pub struct TxCircuitConfig<F: Field> {
     // A selector which is enabled at 1st row
     q_first: Column<Fixed>,
     tx_table: TxTable,
-    tx_tag_bits: BinaryNumberConfig<TxFieldTag, 5>,
+    tx_tag_bits: BinaryNumberConfig<TxFieldTag, 6>,
 
     tx_type: Column<Advice>,
     tx_type_bits: BinaryNumberConfig<TxType, 3>,
@@ -133,7 +166,13 @@ pub struct TxCircuitConfig<F: Field> {
     is_calldata: Column<Advice>,
     is_caller_address: Column<Advice>,
     is_l1_msg: Column<Advice>,
+    is_eip1559: Column<Advice>,
+    is_eip2930: Column<Advice>,
+    is_eip4844: Column<Advice>,
+    is_eip7702: Column<Advice>,
     is_chain_id: Column<Advice>,
+    is_blob_hash: Column<Advice>,
+    is_access_list_len: Column<Advice>,
     lookup_conditions: HashMap<LookupCondition, Column<Advice>>,
 
     /// Columns for computing num_all_txs
@@ -170,12 +209,106 @@ pub struct TxCircuitConfig<F: Field> {
     /// Address recovered by SignVerifyChip
     sv_address: Column<Advice>,
 
+    /// Columns for the access list rows, one row per address or storage key
+    /// in the order they are encoded in the tx and warmed up by BeginTx.
+    q_access_list: Column<Fixed>,
+    access_list_tx_id: Column<Advice>,
+    /// Index of the address (starting from 1)
+    access_list_idx: Column<Advice>,
+    /// Index of the storage key across the whole access list (starting from 1)
+    access_list_storage_key_idx: Column<Advice>,
+    access_list_is_storage_key: Column<Advice>,
+    access_list_address: Column<Advice>,
+    access_list_storage_key: Column<Advice>,
+    access_list_rw_counter: Column<Advice>,
+    access_list_is_warm_prev: Column<Advice>,
+    /// Hash format of the tx, which selects its RLP encoding
+    access_list_format: Column<Advice>,
+    access_list_tx_id_is_zero: IsZeroConfig<F>,
+    access_list_tx_id_unchanged: IsEqualConfig<F>,
+
+    /// Whether the tag is AuthorizationListLen
+    is_authorization_list_len: Column<Advice>,
+    /// Columns for the authorization rows, one row per authorization of an
+    /// EIP-7702 tx in the order they are encoded in the tx and applied by BeginTx.
+    q_authorization: Column<Fixed>,
+    auth_tx_id: Column<Advice>,
+    /// Index of the authorization (starting from 1)
+    auth_idx: Column<Advice>,
+    /// RLC and length of the big-endian bytes of chain_id
+    auth_chain_id_rlc: Column<Advice>,
+    auth_chain_id_len: Column<Advice>,
+    /// RLC of the big-endian bytes of the chain_id of the tx
+    auth_tx_chain_id_rlc: Column<Advice>,
+    auth_address: Column<Advice>,
+    auth_address_rlc: Column<Advice>,
+    auth_nonce: Column<Advice>,
+    auth_nonce_rlc: Column<Advice>,
+    auth_nonce_len: Column<Advice>,
+    auth_y_parity: Column<Advice>,
+    auth_r: Column<Advice>,
+    auth_s: Column<Advice>,
+    /// Columns for the signed message 0x05 || rlp([chain_id, address, nonce])
+    auth_chain_id_is_short: Column<Advice>,
+    auth_nonce_is_short: Column<Advice>,
+    auth_is_long_list: Column<Advice>,
+    auth_pow_chain_id_len: Column<Advice>,
+    auth_pow_nonce_len: Column<Advice>,
+    auth_pow_payload_len: Column<Advice>,
+    auth_msg_rlc: Column<Advice>,
+    auth_msg_len: Column<Advice>,
+    auth_msg_hash_rlc: Column<Advice>,
+    /// Whether the authority is recovered from the signature, in which case
+    /// BeginTx applies the authorization.
+    auth_is_recovered: Column<Advice>,
+    auth_authority: Column<Advice>,
+    auth_rw_counter: Column<Advice>,
+    /// Number of recovered authorities of the tx up to the current row
+    auth_num_recovered: Column<Advice>,
+    auth_is_low_s: Column<Advice>,
+    auth_is_valid_sig: Column<Advice>,
+    /// Whether the code hash of the authority is neither zero nor empty
+    auth_has_code_prev: Column<Advice>,
+    auth_code_length_prev: Column<Advice>,
+    /// First bytes of a code of the size of a delegation designator
+    auth_prefix_prev: [Column<Advice>; DELEGATION_PREFIX.len()],
+    /// Whether the authority has no code or a delegation designator
+    auth_is_code_ok: Column<Advice>,
+    auth_is_valid: Column<Advice>,
+    /// Values of the account of the authority before and after the authorization
+    auth_is_warm_prev: Column<Advice>,
+    auth_nonce_prev: Column<Advice>,
+    auth_code_hash_prev: Column<Advice>,
+    auth_code_hash: Column<Advice>,
+    auth_keccak_code_hash_prev: Column<Advice>,
+    auth_keccak_code_hash: Column<Advice>,
+    auth_code_size_prev: Column<Advice>,
+    auth_code_size: Column<Advice>,
+    auth_refund_prev: Column<Advice>,
+    auth_tx_id_is_zero: IsZeroConfig<F>,
+    auth_tx_id_unchanged: IsEqualConfig<F>,
+    auth_chain_id_len_is_one: IsEqualConfig<F>,
+    auth_nonce_len_is_one: IsEqualConfig<F>,
+    auth_chain_id_is_zero: IsZeroConfig<F>,
+    auth_chain_id_is_tx_chain_id: IsZeroGadgetConfig<F>,
+    auth_nonce_is_max: IsEqualConfig<F>,
+    auth_nonce_is_nonce_prev: IsEqualConfig<F>,
+    auth_address_is_zero: IsZeroConfig<F>,
+    auth_code_hash_prev_is_zero: IsZeroConfig<F>,
+    auth_code_hash_prev_is_empty: IsZeroGadgetConfig<F>,
+    auth_code_length_prev_is_designator: IsEqualConfig<F>,
+    auth_prefix_prev_is_designator: IsEqualConfig<F>,
+
     sig_table: SigTable,
 
     // External tables
     block_table: BlockTable,
     rlp_table: RlpTable,
     keccak_table: KeccakTable,
+    rw_table: RwTable,
+    pow_of_rand_table: PowOfRandTable,
+    poseidon_table: PoseidonTable,
+    bytecode_table: BytecodeTable,
 
     _marker: PhantomData<F>,
 }
@@ -192,6 +325,14 @@ pub struct TxCircuitConfigArgs<F: Field> {
     pub rlp_table: RlpTable,
     /// SigTable
     pub sig_table: SigTable,
+    /// RwTable
+    pub rw_table: RwTable,
+    /// PowOfRandTable
+    pub pow_of_rand_table: PowOfRandTable,
+    /// PoseidonTable
+    pub poseidon_table: PoseidonTable,
+    /// BytecodeTable
+    pub bytecode_table: BytecodeTable,
     /// Reusable u8 lookup table,
     pub u8_table: U8Table,
     /// Reusable u16 lookup table,
@@ -212,6 +353,10 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             keccak_table,
             rlp_table,
             sig_table,
+            rw_table,
+            pow_of_rand_table,
+            poseidon_table,
+            bytecode_table,
             u8_table,
             u16_table,
             challenges,
@@ -267,15 +412,25 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
 
         // booleans to reduce degree
         let is_l1_msg = meta.advice_column();
+        let is_eip1559 = meta.advice_column();
+        let is_eip2930 = meta.advice_column();
+        let is_eip4844 = meta.advice_column();
+        let is_eip7702 = meta.advice_column();
         let is_calldata = meta.advice_column();
         let is_caller_address = meta.advice_column();
         let is_chain_id = meta.advice_column();
+        let is_blob_hash_col = meta.advice_column();
         let is_tag_block_num = meta.advice_column();
+        let is_access_list_len = meta.advice_column();
+        let is_authorization_list_len_col = meta.advice_column();
         let lookup_conditions = [
             LookupCondition::TxCalldata,
             LookupCondition::L1MsgHash,
             LookupCondition::RlpSignTag,
             LookupCondition::RlpHashTag,
+            LookupCondition::AccessList,
+            LookupCondition::Authorization,
+            LookupCondition::LastBlob,
             LookupCondition::Keccak,
         ]
         .into_iter()
@@ -334,6 +489,18 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
         is_tx_tag!(is_hash, TxHash);
         is_tx_tag!(is_block_num, BlockNumber);
         is_tx_tag!(is_tx_type, TxType);
+        is_tx_tag!(is_blob_hash, BlobVersionedHash);
+        is_tx_tag!(is_max_priority_fee, MaxPriorityFeePerGas);
+        is_tx_tag!(is_max_fee, MaxFeePerGas);
+        is_tx_tag!(is_max_fee_per_blob_gas, MaxFeePerBlobGas);
+        is_tx_tag!(is_access_list_addresses_len, AccessListAddressesLen);
+        is_tx_tag!(is_access_list_storage_keys_len, AccessListStorageKeysLen);
+        is_tx_tag!(is_access_list_rw_counter, AccessListRwCounter);
+        is_tx_tag!(is_authorization_list_len, AuthorizationListLen);
+        is_tx_tag!(
+            is_authorization_list_authorities_len,
+            AuthorizationListAuthoritiesLen
+        );
 
         let tx_id_unchanged = IsEqualChip::configure(
             meta,
@@ -343,21 +510,16 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
         );
 
         // testing if value is zero for tags
+        // - if caller_address is zero, then skip the sig verify.
+        // - if call_data_length is zero, then skip lookup to tx table for call data
+        // - if call data byte is zero, then gas_cost = 4 (16 otherwise)
+        // - if blob versioned hash is zero, then the blob slot is unused
+        // - if the access list is empty, then skip the lookups into its rows
+        // The inverse of value is assigned at every row, so the chip is enabled at all rows
+        // of the tx table instead of the rows of these tags to keep the degree low.
         let value_is_zero = IsZeroChip::configure(
             meta,
-            |meta| {
-                and::expr(vec![
-                    meta.query_fixed(q_enable, Rotation::cur()),
-                    sum::expr(vec![
-                        // if caller_address is zero, then skip the sig verify.
-                        is_caller_addr(meta),
-                        // if call_data_length is zero, then skip lookup to tx table for call data
-                        is_data_length(meta),
-                        // if call data byte is zero, then gas_cost = 4 (16 otherwise)
-                        is_data(meta),
-                    ]),
-                ])
-            },
+            |meta| meta.query_fixed(q_enable, Rotation::cur()),
             tx_table.value,
             |meta| meta.advice_column_in(SecondPhase), // value is at 2nd phase
         );
@@ -460,6 +622,15 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                 (is_block_num(meta), Null),
                 (is_chain_id_expr(meta), Tag::ChainId.into()),
                 (is_tx_type(meta), Null),
+                (is_blob_hash(meta), Tag::BlobVersionedHash.into()),
+                (is_max_priority_fee(meta), Tag::MaxPriorityFeePerGas.into()),
+                (is_max_fee(meta), Tag::MaxFeePerGas.into()),
+                (is_max_fee_per_blob_gas(meta), Tag::MaxFeePerBlobGas.into()),
+                (is_access_list_addresses_len(meta), Null),
+                (is_access_list_storage_keys_len(meta), Null),
+                (is_access_list_rw_counter(meta), Null),
+                (is_authorization_list_len(meta), Null),
+                (is_authorization_list_authorities_len(meta), Null),
             ];
 
             cb.require_boolean(
@@ -474,6 +645,10 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                     usize::from(PreEip155).expr(),
                     usize::from(Eip155).expr(),
                     usize::from(L1Msg).expr(),
+                    usize::from(Eip1559).expr(),
+                    usize::from(Eip2930).expr(),
+                    usize::from(Eip4844).expr(),
+                    usize::from(Eip7702).expr(),
                 ],
             );
 
@@ -493,6 +668,23 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                 }),
             );
 
+            // blob txs and set-code txs can not create contracts
+            cb.condition(
+                and::expr([
+                    is_create(meta),
+                    sum::expr([
+                        meta.query_advice(is_eip4844, Rotation::cur()),
+                        meta.query_advice(is_eip7702, Rotation::cur()),
+                    ]),
+                ]),
+                |cb| {
+                    cb.require_zero(
+                        "is_create == false",
+                        meta.query_advice(tx_table.value, Rotation::cur()),
+                    );
+                },
+            );
+
             cb.condition(is_to(meta), |cb| {
                 cb.require_equal(
                     "is_create == is_none",
@@ -535,9 +727,15 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             cb.condition(
                 and::expr([is_data_rlc(meta), not::expr(is_none_expr)]),
                 |cb| {
-                    cb.require_zero(
+                    // the calldata lookup condition at the CallDataLength row is
+                    // (CallDataLength != 0)
+                    cb.require_equal(
                         "CallDataLength != 0",
-                        value_is_zero.expr(Rotation::next())(meta),
+                        meta.query_advice(
+                            lookup_conditions[&LookupCondition::TxCalldata],
+                            Rotation::next(),
+                        ),
+                        1.expr(),
                     );
                 },
             );
@@ -584,6 +782,42 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
         });
 
+        meta.create_gate("is_blob_hash", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_equal(
+                "is_blob_hash = (tag == BlobVersionedHash)",
+                is_blob_hash(meta),
+                meta.query_advice(is_blob_hash_col, Rotation::cur()),
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("is_access_list_len", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_equal(
+                "is_access_list_len = (tag == AccessListAddressesLen)",
+                is_access_list_addresses_len(meta),
+                meta.query_advice(is_access_list_len, Rotation::cur()),
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("is_authorization_list_len", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_equal(
+                "is_authorization_list_len = (tag == AuthorizationListLen)",
+                is_authorization_list_len(meta),
+                meta.query_advice(is_authorization_list_len_col, Rotation::cur()),
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
         meta.create_gate("is_tag_block_num", |meta| {
             let mut cb = BaseConstraintBuilder::default();
 
@@ -608,6 +842,54 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
         });
 
+        meta.create_gate("is_eip1559", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_equal(
+                "is_eip1559 = (tx_type == Eip1559)",
+                meta.query_advice(is_eip1559, Rotation::cur()),
+                tx_type_bits.value_equals(Eip1559, Rotation::cur())(meta),
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("is_eip2930", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_equal(
+                "is_eip2930 = (tx_type == Eip2930)",
+                meta.query_advice(is_eip2930, Rotation::cur()),
+                tx_type_bits.value_equals(Eip2930, Rotation::cur())(meta),
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("is_eip4844", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_equal(
+                "is_eip4844 = (tx_type == Eip4844)",
+                meta.query_advice(is_eip4844, Rotation::cur()),
+                tx_type_bits.value_equals(Eip4844, Rotation::cur())(meta),
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("is_eip7702", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_equal(
+                "is_eip7702 = (tx_type == Eip7702)",
+                meta.query_advice(is_eip7702, Rotation::cur()),
+                tx_type_bits.value_equals(Eip7702, Rotation::cur())(meta),
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
         meta.create_gate("calldata lookup into tx table condition", |meta| {
             let mut cb = BaseConstraintBuilder::default();
 
@@ -629,19 +911,42 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
         meta.create_gate("sign tag lookup into RLP table condition", |meta| {
             let mut cb = BaseConstraintBuilder::default();
 
+            let is_eip4844 = meta.query_advice(is_eip4844, Rotation::cur());
+            // eip4844 and eip7702 txs share the eip1559 fee fields
+            let is_eip1559_fee = sum::expr([
+                meta.query_advice(is_eip1559, Rotation::cur()),
+                is_eip4844.expr(),
+                meta.query_advice(is_eip7702, Rotation::cur()),
+            ]);
             let is_tag_in_tx_sign = sum::expr([
                 is_nonce(meta),
-                is_gas_price(meta),
+                // gas price of the eip1559 (eip4844, eip7702) tx is the effective gas price
+                and::expr([is_gas_price(meta), not::expr(is_eip1559_fee.expr())]),
                 is_gas(meta),
                 is_to(meta),
                 is_value(meta),
                 is_data_rlc(meta),
                 and::expr([
                     meta.query_advice(is_chain_id, Rotation::cur()),
-                    tx_type_bits.value_equals(Eip155, Rotation::cur())(meta),
+                    sum::expr([
+                        tx_type_bits.value_equals(Eip155, Rotation::cur())(meta),
+                        meta.query_advice(is_eip2930, Rotation::cur()),
+                        is_eip1559_fee.expr(),
+                    ]),
                 ]),
                 is_sign_length(meta),
                 is_sign_rlc(meta),
+                and::expr([
+                    sum::expr([is_max_priority_fee(meta), is_max_fee(meta)]),
+                    is_eip1559_fee.expr(),
+                ]),
+                and::expr([is_max_fee_per_blob_gas(meta), is_eip4844.expr()]),
+                // the unused blob slots are zero
+                and::expr([
+                    meta.query_advice(is_blob_hash_col, Rotation::cur()),
+                    is_eip4844.expr(),
+                    not::expr(value_is_zero.expr(Rotation::cur())(meta)),
+                ]),
             ]);
 
             cb.require_equal(
@@ -653,130 +958,1715 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                 ),
             );
 
-            cb.gate(and::expr([
-                meta.query_fixed(q_enable, Rotation::cur()),
-                not::expr(meta.query_advice(is_l1_msg, Rotation::cur())),
-            ]))
+            cb.gate(and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                not::expr(meta.query_advice(is_l1_msg, Rotation::cur())),
+            ]))
+        });
+
+        meta.create_gate("hash tag lookup into RLP table condition", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let is_eip4844 = meta.query_advice(is_eip4844, Rotation::cur());
+            let is_eip1559_fee = sum::expr([
+                meta.query_advice(is_eip1559, Rotation::cur()),
+                is_eip4844.expr(),
+                meta.query_advice(is_eip7702, Rotation::cur()),
+            ]);
+            let is_tag_in_tx_hash = sum::expr([
+                is_nonce(meta),
+                and::expr([is_gas_price(meta), not::expr(is_eip1559_fee.expr())]),
+                is_gas(meta),
+                is_to(meta),
+                is_value(meta),
+                is_tx_gas_cost(meta),
+                is_data_rlc(meta),
+                is_sig_v(meta),
+                is_sig_r(meta),
+                is_sig_s(meta),
+                is_hash_length(meta),
+                is_hash_rlc(meta),
+                and::expr([
+                    sum::expr([
+                        meta.query_advice(is_chain_id, Rotation::cur()),
+                        is_max_priority_fee(meta),
+                        is_max_fee(meta),
+                    ]),
+                    is_eip1559_fee,
+                ]),
+                and::expr([
+                    meta.query_advice(is_chain_id, Rotation::cur()),
+                    meta.query_advice(is_eip2930, Rotation::cur()),
+                ]),
+                and::expr([is_max_fee_per_blob_gas(meta), is_eip4844.expr()]),
+                and::expr([
+                    meta.query_advice(is_blob_hash_col, Rotation::cur()),
+                    is_eip4844.expr(),
+                    not::expr(value_is_zero.expr(Rotation::cur())(meta)),
+                ]),
+            ]);
+
+            cb.require_equal(
+                "condition",
+                is_tag_in_tx_hash,
+                meta.query_advice(
+                    lookup_conditions[&LookupCondition::RlpHashTag],
+                    Rotation::cur(),
+                ),
+            );
+
+            cb.gate(and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                not::expr(meta.query_advice(is_l1_msg, Rotation::cur())),
+            ]))
+        });
+
+        meta.create_gate("l1 msg lookup into RLP table condition", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+            let is_tag_in_l1_msg_hash = sum::expr([
+                is_nonce(meta),
+                is_gas(meta),
+                is_to(meta),
+                is_value(meta),
+                is_data_rlc(meta),
+                is_caller_addr(meta),
+                is_hash_length(meta),
+                is_hash_rlc(meta),
+            ]);
+
+            cb.require_equal(
+                "lookup into RLP table iff tag in l1 msg hash",
+                is_tag_in_l1_msg_hash,
+                meta.query_advice(
+                    lookup_conditions[&LookupCondition::L1MsgHash],
+                    Rotation::cur(),
+                ),
+            );
+
+            cb.gate(and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                meta.query_advice(is_l1_msg, Rotation::cur()),
+            ]))
+        });
+
+        meta.create_gate("access list lookup condition", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_equal(
+                "condition",
+                and::expr([
+                    meta.query_advice(is_access_list_len, Rotation::cur()),
+                    not::expr(value_is_zero.expr(Rotation::cur())(meta)),
+                ]),
+                meta.query_advice(
+                    lookup_conditions[&LookupCondition::AccessList],
+                    Rotation::cur(),
+                ),
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("authorization lookup condition", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.require_equal(
+                "condition",
+                and::expr([
+                    meta.query_advice(is_authorization_list_len_col, Rotation::cur()),
+                    not::expr(value_is_zero.expr(Rotation::cur())(meta)),
+                ]),
+                meta.query_advice(
+                    lookup_conditions[&LookupCondition::Authorization],
+                    Rotation::cur(),
+                ),
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("lookup into Keccak table condition", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let is_tag_sign_or_hash = sum::expr([
+                and::expr([
+                    is_sign_length(meta),
+                    not::expr(meta.query_advice(is_l1_msg, Rotation::cur())),
+                ]),
+                is_hash_length(meta),
+            ]);
+            cb.require_equal(
+                "condition",
+                is_tag_sign_or_hash,
+                meta.query_advice(lookup_conditions[&LookupCondition::Keccak], Rotation::cur()),
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        // lookups to RLP table, Tx table, Keccak table
+        Self::configure_lookups(
+            meta,
+            q_enable,
+            rlp_tag,
+            tx_value_rlc,
+            tx_value_length,
+            tx_type_bits,
+            tx_id_is_zero.clone(),
+            is_none,
+            &lookup_conditions,
+            is_final,
+            is_calldata,
+            is_chain_id,
+            is_l1_msg,
+            sv_address,
+            calldata_gas_cost_acc,
+            calldata_rlc,
+            tx_table.clone(),
+            keccak_table.clone(),
+            rlp_table,
+            sig_table,
+        );
+
+        meta.create_gate("tx_gas_cost == 0 for L1 msg", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            cb.condition(is_tx_gas_cost(meta), |cb| {
+                cb.require_zero(
+                    "tx_gas_cost == 0",
+                    meta.query_advice(tx_table.value, Rotation::cur()),
+                );
+            });
+
+            cb.gate(and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                meta.query_advice(is_l1_msg, Rotation::cur()),
+            ]))
+        });
+
+        meta.create_gate("blob versioned hashes", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            // the blob rows are right after the MaxFeePerBlobGas row
+            let is_first_blob = not::expr(meta.query_advice(is_blob_hash_col, Rotation::prev()));
+            cb.condition(is_first_blob.expr(), |cb| {
+                cb.require_zero(
+                    "index == 0 for the first blob",
+                    meta.query_advice(tx_table.index, Rotation::cur()),
+                );
+            });
+            cb.condition(not::expr(is_first_blob.expr()), |cb| {
+                cb.require_equal(
+                    "index == index::prev + 1",
+                    meta.query_advice(tx_table.index, Rotation::cur()),
+                    meta.query_advice(tx_table.index, Rotation::prev()) + 1.expr(),
+                );
+            });
+            let is_eip4844 = meta.query_advice(is_eip4844, Rotation::cur());
+            // eip4844 tx carries at least one blob
+            cb.condition(and::expr([is_first_blob, is_eip4844.expr()]), |cb| {
+                cb.require_zero(
+                    "first versioned hash != 0",
+                    value_is_zero.expr(Rotation::cur())(meta),
+                );
+            });
+            // only eip4844 tx carries blobs
+            cb.condition(not::expr(is_eip4844.expr()), |cb| {
+                cb.require_zero(
+                    "versioned hash == 0",
+                    meta.query_advice(tx_table.value, Rotation::cur()),
+                );
+            });
+            // the used blob slots are at the front
+            cb.condition(
+                and::expr([
+                    is_eip4844,
+                    value_is_zero.expr(Rotation::cur())(meta),
+                    meta.query_advice(is_blob_hash_col, Rotation::next()),
+                ]),
+                |cb| {
+                    cb.require_zero(
+                        "versioned hash' == 0",
+                        meta.query_advice(tx_table.value, Rotation::next()),
+                    );
+                },
+            );
+
+            cb.gate(and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                meta.query_advice(is_blob_hash_col, Rotation::cur()),
+            ]))
+        });
+
+        // The last used blob slot of an eip4844 tx, whose index + 1 is the number of blobs.
+        meta.create_gate("last blob lookup condition", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let is_used_blob_next = and::expr([
+                meta.query_advice(is_blob_hash_col, Rotation::next()),
+                not::expr(value_is_zero.expr(Rotation::next())(meta)),
+            ]);
+            cb.require_equal(
+                "condition",
+                and::expr([
+                    meta.query_advice(is_blob_hash_col, Rotation::cur()),
+                    meta.query_advice(is_eip4844, Rotation::cur()),
+                    not::expr(value_is_zero.expr(Rotation::cur())(meta)),
+                    not::expr(is_used_blob_next),
+                ]),
+                meta.query_advice(
+                    lookup_conditions[&LookupCondition::LastBlob],
+                    Rotation::cur(),
+                ),
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        // The i-th used blob slot holds the i-th versioned hash of the RLP encoding.
+        meta.lookup_any("blob versioned hash in RLP table", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                meta.query_advice(is_blob_hash_col, Rotation::cur()),
+                meta.query_advice(is_eip4844, Rotation::cur()),
+                not::expr(value_is_zero.expr(Rotation::cur())(meta)),
+            ]);
+
+            let input_exprs = vec![
+                1.expr(), // q_enable = true
+                meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                TxHashEip4844.expr(),
+                Tag::BlobVersionedHash.expr(),
+                meta.query_advice(tx_table.value, Rotation::cur()),
+                1.expr(), // is_output = true
+                meta.query_advice(tx_table.index, Rotation::cur()) + 1.expr(), // blob_idx
+            ];
+            let table_exprs = vec![
+                meta.query_fixed(rlp_table.q_enable, Rotation::cur()),
+                meta.query_advice(rlp_table.tx_id, Rotation::cur()),
+                meta.query_advice(rlp_table.format, Rotation::cur()),
+                meta.query_advice(rlp_table.rlp_tag, Rotation::cur()),
+                meta.query_advice(rlp_table.tag_value, Rotation::cur()),
+                meta.query_advice(rlp_table.is_output, Rotation::cur()),
+                meta.query_advice(rlp_table.blob_idx, Rotation::cur()),
+            ];
+
+            input_exprs
+                .into_iter()
+                .zip_eq(table_exprs.into_iter())
+                .map(|(input, table)| (enable.expr() * input, table))
+                .collect()
+        });
+
+        // The blob index at the SigV row, which comes after blob_versioned_hashes in the
+        // RLP encoding, is the number of blobs.
+        meta.lookup_any("number of blobs in RLP table", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                meta.query_advice(
+                    lookup_conditions[&LookupCondition::LastBlob],
+                    Rotation::cur(),
+                ),
+            ]);
+
+            let input_exprs = vec![
+                1.expr(), // q_enable = true
+                meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                TxHashEip4844.expr(),
+                Tag::SigV.expr(),
+                1.expr(), // is_output = true
+                meta.query_advice(tx_table.index, Rotation::cur()) + 1.expr(), // num of blobs
+            ];
+            let table_exprs = vec![
+                meta.query_fixed(rlp_table.q_enable, Rotation::cur()),
+                meta.query_advice(rlp_table.tx_id, Rotation::cur()),
+                meta.query_advice(rlp_table.format, Rotation::cur()),
+                meta.query_advice(rlp_table.rlp_tag, Rotation::cur()),
+                meta.query_advice(rlp_table.is_output, Rotation::cur()),
+                meta.query_advice(rlp_table.blob_idx, Rotation::cur()),
+            ];
+
+            input_exprs
+                .into_iter()
+                .zip_eq(table_exprs.into_iter())
+                .map(|(input, table)| (enable.expr() * input, table))
+                .collect()
+        });
+
+        ///////////////////////////////////////////////////////////////////////
+        ///////////////  constraints on access list  //////////////////////////
+        ///////////////////////////////////////////////////////////////////////
+        // Among the supported tx types only the eip2930, eip1559, eip4844 and eip7702 txs
+        // carry an access list, which is encoded in the RLP of their hash format.
+        let has_access_list = |meta: &mut VirtualCells<F>| {
+            sum::expr(
+                [is_eip2930, is_eip1559, is_eip4844, is_eip7702]
+                    .map(|col| meta.query_advice(col, Rotation::cur())),
+            )
+        };
+        let access_list_format = |meta: &mut VirtualCells<F>| {
+            sum::expr(
+                [
+                    (is_eip2930, TxHashEip2930),
+                    (is_eip1559, TxHashEip1559),
+                    (is_eip4844, TxHashEip4844),
+                    (is_eip7702, TxHashEip7702),
+                ]
+                .map(|(col, format)| meta.query_advice(col, Rotation::cur()) * format.expr()),
+            )
+        };
+        meta.create_gate("access list of other txs is empty", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            // AccessListStorageKeysLen is next to AccessListAddressesLen
+            cb.require_zero(
+                "num of addresses == 0",
+                meta.query_advice(tx_table.value, Rotation::cur()),
+            );
+            cb.require_zero(
+                "num of storage keys == 0",
+                meta.query_advice(tx_table.value, Rotation::next()),
+            );
+
+            cb.gate(and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                meta.query_advice(is_access_list_len, Rotation::cur()),
+                not::expr(has_access_list(meta)),
+            ]))
+        });
+
+        // The access list indices at the SigV row, which comes after the access list
+        // in the RLP encoding, are the number of addresses and storage keys.
+        meta.lookup_any("access list lengths in RLP table", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                meta.query_advice(is_access_list_len, Rotation::cur()),
+                has_access_list(meta),
+            ]);
+
+            let input_exprs = vec![
+                1.expr(), // q_enable = true
+                meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                access_list_format(meta),
+                Tag::SigV.expr(),
+                1.expr(),                                            // is_output = true
+                meta.query_advice(tx_table.value, Rotation::cur()),  // num of addresses
+                meta.query_advice(tx_table.value, Rotation::next()), // num of storage keys
+            ];
+            let table_exprs = vec![
+                meta.query_fixed(rlp_table.q_enable, Rotation::cur()),
+                meta.query_advice(rlp_table.tx_id, Rotation::cur()),
+                meta.query_advice(rlp_table.format, Rotation::cur()),
+                meta.query_advice(rlp_table.rlp_tag, Rotation::cur()),
+                meta.query_advice(rlp_table.is_output, Rotation::cur()),
+                meta.query_advice(rlp_table.access_list_idx, Rotation::cur()),
+                meta.query_advice(rlp_table.storage_key_idx, Rotation::cur()),
+            ];
+
+            input_exprs
+                .into_iter()
+                .zip_eq(table_exprs.into_iter())
+                .map(|(input, table)| (enable.expr() * input, table))
+                .collect()
+        });
+
+        // The access list rows start with an all-zero row, followed by the entries of
+        // each tx and the padding rows with tx_id = 0. The entries of a tx are
+        // numbered consecutively and warmed up at consecutive rw counters.
+        let q_access_list = meta.fixed_column();
+        let access_list_tx_id = meta.advice_column();
+        let access_list_idx = meta.advice_column();
+        let access_list_storage_key_idx = meta.advice_column();
+        let access_list_is_storage_key = meta.advice_column();
+        let access_list_address = meta.advice_column();
+        let access_list_storage_key = meta.advice_column_in(SecondPhase);
+        let access_list_rw_counter = meta.advice_column();
+        let access_list_is_warm_prev = meta.advice_column();
+        let access_list_format_col = meta.advice_column();
+
+        let access_list_tx_id_is_zero = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_fixed(q_access_list, Rotation::cur()),
+            access_list_tx_id,
+            |meta| meta.advice_column(),
+        );
+        let access_list_tx_id_unchanged = IsEqualChip::configure(
+            meta,
+            |meta| meta.query_fixed(q_access_list, Rotation::cur()),
+            |meta| meta.query_advice(access_list_tx_id, Rotation::cur()),
+            |meta| meta.query_advice(access_list_tx_id, Rotation::next()),
+        );
+
+        meta.create_gate("access list entries", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let is_storage_key_next =
+                meta.query_advice(access_list_is_storage_key, Rotation::next());
+            cb.require_boolean("is_storage_key' is boolean", is_storage_key_next.expr());
+            cb.require_boolean(
+                "is_warm_prev' is boolean",
+                meta.query_advice(access_list_is_warm_prev, Rotation::next()),
+            );
+
+            // the first entry of a tx is its first address
+            cb.condition(not::expr(access_list_tx_id_unchanged.expr()), |cb| {
+                cb.require_equal(
+                    "access_list_idx' == 1",
+                    meta.query_advice(access_list_idx, Rotation::next()),
+                    1.expr(),
+                );
+                cb.require_zero(
+                    "storage_key_idx' == 0",
+                    meta.query_advice(access_list_storage_key_idx, Rotation::next()),
+                );
+                cb.require_zero("is_storage_key' == false", is_storage_key_next.expr());
+            });
+            // an address is followed by its storage keys and then the next address
+            cb.condition(access_list_tx_id_unchanged.expr(), |cb| {
+                cb.require_equal(
+                    "access_list_idx' == access_list_idx + !is_storage_key'",
+                    meta.query_advice(access_list_idx, Rotation::next()),
+                    meta.query_advice(access_list_idx, Rotation::cur())
+                        + not::expr(is_storage_key_next.expr()),
+                );
+                cb.require_equal(
+                    "storage_key_idx' == storage_key_idx + is_storage_key'",
+                    meta.query_advice(access_list_storage_key_idx, Rotation::next()),
+                    meta.query_advice(access_list_storage_key_idx, Rotation::cur())
+                        + is_storage_key_next.expr(),
+                );
+                cb.require_equal(
+                    "rw_counter' == rw_counter + 1",
+                    meta.query_advice(access_list_rw_counter, Rotation::next()),
+                    meta.query_advice(access_list_rw_counter, Rotation::cur()) + 1.expr(),
+                );
+                cb.require_equal(
+                    "format' == format",
+                    meta.query_advice(access_list_format_col, Rotation::next()),
+                    meta.query_advice(access_list_format_col, Rotation::cur()),
+                );
+                cb.condition(is_storage_key_next.expr(), |cb| {
+                    cb.require_equal(
+                        "address' == address",
+                        meta.query_advice(access_list_address, Rotation::next()),
+                        meta.query_advice(access_list_address, Rotation::cur()),
+                    );
+                });
+            });
+
+            cb.gate(and::expr([
+                meta.query_fixed(q_access_list, Rotation::cur()),
+                not::expr(access_list_tx_id_is_zero.expr(Rotation::next())(meta)),
+            ]))
+        });
+
+        meta.lookup_any("first access list entry of a tx", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                meta.query_advice(
+                    lookup_conditions[&LookupCondition::AccessList],
+                    Rotation::cur(),
+                ),
+            ]);
+
+            let input_exprs = vec![
+                meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                1.expr(), // access_list_idx
+                0.expr(), // storage_key_idx
+                0.expr(), // is_storage_key = false
+                // AccessListRwCounter is two rows after AccessListAddressesLen
+                meta.query_advice(tx_table.value, Rotation(2)),
+                access_list_format(meta),
+            ];
+            let table_exprs = vec![
+                meta.query_advice(access_list_tx_id, Rotation::cur()),
+                meta.query_advice(access_list_idx, Rotation::cur()),
+                meta.query_advice(access_list_storage_key_idx, Rotation::cur()),
+                meta.query_advice(access_list_is_storage_key, Rotation::cur()),
+                meta.query_advice(access_list_rw_counter, Rotation::cur()),
+                meta.query_advice(access_list_format_col, Rotation::cur()),
+            ];
+
+            input_exprs
+                .into_iter()
+                .zip_eq(table_exprs.into_iter())
+                .map(|(input, table)| (enable.expr() * input, table))
+                .collect()
+        });
+
+        meta.lookup_any("last access list entry of a tx", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                meta.query_advice(
+                    lookup_conditions[&LookupCondition::AccessList],
+                    Rotation::cur(),
+                ),
+            ]);
+
+            let input_exprs = vec![
+                meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                meta.query_advice(tx_table.value, Rotation::cur()), // num of addresses
+                meta.query_advice(tx_table.value, Rotation::next()), // num of storage keys
+                1.expr(),                                           // is_last = true
+            ];
+            let table_exprs = vec![
+                meta.query_advice(access_list_tx_id, Rotation::cur()),
+                meta.query_advice(access_list_idx, Rotation::cur()),
+                meta.query_advice(access_list_storage_key_idx, Rotation::cur()),
+                meta.query_fixed(q_access_list, Rotation::cur())
+                    * not::expr(access_list_tx_id_unchanged.expr()),
+            ];
+
+            input_exprs
+                .into_iter()
+                .zip_eq(table_exprs.into_iter())
+                .map(|(input, table)| (enable.expr() * input, table))
+                .collect()
+        });
+
+        meta.lookup_any("access list entry in RLP table", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_access_list, Rotation::cur()),
+                not::expr(access_list_tx_id_is_zero.expr(Rotation::cur())(meta)),
+            ]);
+            let is_storage_key = meta.query_advice(access_list_is_storage_key, Rotation::cur());
+
+            let input_exprs = vec![
+                1.expr(), // q_enable = true
+                meta.query_advice(access_list_tx_id, Rotation::cur()),
+                meta.query_advice(access_list_format_col, Rotation::cur()),
+                select::expr(
+                    is_storage_key.expr(),
+                    Tag::AccessListStorageKey.expr(),
+                    Tag::AccessListAddress.expr(),
+                ),
+                select::expr(
+                    is_storage_key,
+                    meta.query_advice(access_list_storage_key, Rotation::cur()),
+                    meta.query_advice(access_list_address, Rotation::cur()),
+                ),
+                1.expr(), // is_output = true
+                meta.query_advice(access_list_idx, Rotation::cur()),
+                meta.query_advice(access_list_storage_key_idx, Rotation::cur()),
+            ];
+            let table_exprs = vec![
+                meta.query_fixed(rlp_table.q_enable, Rotation::cur()),
+                meta.query_advice(rlp_table.tx_id, Rotation::cur()),
+                meta.query_advice(rlp_table.format, Rotation::cur()),
+                meta.query_advice(rlp_table.rlp_tag, Rotation::cur()),
+                meta.query_advice(rlp_table.tag_value, Rotation::cur()),
+                meta.query_advice(rlp_table.is_output, Rotation::cur()),
+                meta.query_advice(rlp_table.access_list_idx, Rotation::cur()),
+                meta.query_advice(rlp_table.storage_key_idx, Rotation::cur()),
+            ];
+
+            input_exprs
+                .into_iter()
+                .zip_eq(table_exprs.into_iter())
+                .map(|(input, table)| (enable.expr() * input, table))
+                .collect()
+        });
+
+        // BeginTx warms up the access list entries at these rw counters.
+        meta.lookup_any("access list entry in RW table", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_access_list, Rotation::cur()),
+                not::expr(access_list_tx_id_is_zero.expr(Rotation::cur())(meta)),
+            ]);
+            let is_storage_key = meta.query_advice(access_list_is_storage_key, Rotation::cur());
+
+            let input_exprs = vec![
+                1.expr(), // q_enable = true
+                meta.query_advice(access_list_rw_counter, Rotation::cur()),
+                1.expr(), // is_write = true
+                select::expr(
+                    is_storage_key.expr(),
+                    RwTableTag::TxAccessListAccountStorage.expr(),
+                    RwTableTag::TxAccessListAccount.expr(),
+                ),
+                meta.query_advice(access_list_tx_id, Rotation::cur()),
+                meta.query_advice(access_list_address, Rotation::cur()),
+                0.expr(), // field_tag
+                is_storage_key * meta.query_advice(access_list_storage_key, Rotation::cur()),
+                1.expr(), // is_warm = true
+                meta.query_advice(access_list_is_warm_prev, Rotation::cur()),
+                0.expr(), // aux1
+                0.expr(), // aux2
+            ];
+
+            input_exprs
+                .into_iter()
+                .zip_eq(rw_table.table_exprs(meta).into_iter())
+                .map(|(input, table)| (enable.expr() * input, table))
+                .collect()
+        });
+
+        ///////////////////////////////////////////////////////////////////////
+        ///////////////  constraints on authorization list  ///////////////////
+        ///////////////////////////////////////////////////////////////////////
+        meta.create_gate("authorization list lengths", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            // only eip7702 txs carry an authorization list
+            cb.condition(
+                not::expr(meta.query_advice(is_eip7702, Rotation::cur())),
+                |cb| {
+                    cb.require_zero(
+                        "num of authorizations == 0",
+                        meta.query_advice(tx_table.value, Rotation::cur()),
+                    );
+                },
+            );
+            // AuthorizationListAuthoritiesLen is next to AuthorizationListLen
+            cb.condition(value_is_zero.expr(Rotation::cur())(meta), |cb| {
+                cb.require_zero(
+                    "num of recovered authorities == 0",
+                    meta.query_advice(tx_table.value, Rotation::next()),
+                );
+            });
+
+            cb.gate(and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                meta.query_advice(is_authorization_list_len_col, Rotation::cur()),
+            ]))
+        });
+
+        // The authorization index at the SigV row, which comes after the authorization
+        // list in the RLP encoding, is the number of authorizations.
+        meta.lookup_any("number of authorizations in RLP table", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                meta.query_advice(is_authorization_list_len_col, Rotation::cur()),
+                meta.query_advice(is_eip7702, Rotation::cur()),
+            ]);
+
+            let input_exprs = vec![
+                1.expr(), // q_enable = true
+                meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                TxHashEip7702.expr(),
+                Tag::SigV.expr(),
+                1.expr(),                                           // is_output = true
+                meta.query_advice(tx_table.value, Rotation::cur()), // num of authorizations
+            ];
+            let table_exprs = vec![
+                meta.query_fixed(rlp_table.q_enable, Rotation::cur()),
+                meta.query_advice(rlp_table.tx_id, Rotation::cur()),
+                meta.query_advice(rlp_table.format, Rotation::cur()),
+                meta.query_advice(rlp_table.rlp_tag, Rotation::cur()),
+                meta.query_advice(rlp_table.is_output, Rotation::cur()),
+                meta.query_advice(rlp_table.authorization_idx, Rotation::cur()),
+            ];
+
+            input_exprs
+                .into_iter()
+                .zip_eq(table_exprs.into_iter())
+                .map(|(input, table)| (enable.expr() * input, table))
+                .collect()
+        });
+
+        // The authorization rows start with an all-zero row, followed by the
+        // authorizations of each tx and the padding rows with tx_id = 0. The
+        // authorizations of a tx are numbered consecutively and the ones whose authority
+        // is recovered are applied by BeginTx at consecutive rw counters.
+        let q_authorization = meta.fixed_column();
+        let auth_tx_id = meta.advice_column();
+        let auth_idx = meta.advice_column();
+        let auth_chain_id_rlc = meta.advice_column_in(SecondPhase);
+        let auth_chain_id_len = meta.advice_column();
+        let auth_tx_chain_id_rlc = meta.advice_column_in(SecondPhase);
+        let auth_address = meta.advice_column();
+        let auth_address_rlc = meta.advice_column_in(SecondPhase);
+        let auth_nonce = meta.advice_column();
+        let auth_nonce_rlc = meta.advice_column_in(SecondPhase);
+        let auth_nonce_len = meta.advice_column();
+        let auth_y_parity = meta.advice_column();
+        let auth_r = meta.advice_column_in(SecondPhase);
+        let auth_s = meta.advice_column_in(SecondPhase);
+        let auth_chain_id_is_short = meta.advice_column();
+        let auth_nonce_is_short = meta.advice_column();
+        let auth_is_long_list = meta.advice_column();
+        let auth_pow_chain_id_len = meta.advice_column_in(SecondPhase);
+        let auth_pow_nonce_len = meta.advice_column_in(SecondPhase);
+        let auth_pow_payload_len = meta.advice_column_in(SecondPhase);
+        let auth_msg_rlc = meta.advice_column_in(SecondPhase);
+        let auth_msg_len = meta.advice_column();
+        let auth_msg_hash_rlc = meta.advice_column_in(SecondPhase);
+        let auth_is_recovered = meta.advice_column();
+        let auth_authority = meta.advice_column();
+        let auth_rw_counter = meta.advice_column();
+        let auth_num_recovered = meta.advice_column();
+        let auth_is_low_s = meta.advice_column();
+        let auth_is_valid_sig = meta.advice_column();
+        let auth_has_code_prev = meta.advice_column();
+        let auth_code_length_prev = meta.advice_column();
+        let auth_prefix_prev = [(); DELEGATION_PREFIX.len()].map(|_| meta.advice_column());
+        let auth_is_code_ok = meta.advice_column();
+        let auth_is_valid = meta.advice_column();
+        let auth_is_warm_prev = meta.advice_column();
+        let auth_nonce_prev = meta.advice_column();
+        let auth_code_hash_prev = meta.advice_column_in(SecondPhase);
+        let auth_code_hash = meta.advice_column_in(SecondPhase);
+        let auth_keccak_code_hash_prev = meta.advice_column_in(SecondPhase);
+        let auth_keccak_code_hash = meta.advice_column_in(SecondPhase);
+        let auth_code_size_prev = meta.advice_column();
+        let auth_code_size = meta.advice_column();
+        let auth_refund_prev = meta.advice_column();
+        // inverses of the differences of second phase values
+        let auth_chain_id_diff_inv = meta.advice_column_in(SecondPhase);
+        let auth_code_hash_prev_diff_inv = meta.advice_column_in(SecondPhase);
+
+        let q_auth =
+            |meta: &mut VirtualCells<F>| meta.query_fixed(q_authorization, Rotation::cur());
+        let auth_tx_id_is_zero =
+            IsZeroChip::configure(meta, q_auth, auth_tx_id, |meta| meta.advice_column());
+        let auth_tx_id_unchanged = IsEqualChip::configure(
+            meta,
+            q_auth,
+            |meta| meta.query_advice(auth_tx_id, Rotation::cur()),
+            |meta| meta.query_advice(auth_tx_id, Rotation::next()),
+        );
+        let auth_chain_id_len_is_one = IsEqualChip::configure(
+            meta,
+            q_auth,
+            |meta| meta.query_advice(auth_chain_id_len, Rotation::cur()),
+            |_| 1.expr(),
+        );
+        let auth_nonce_len_is_one = IsEqualChip::configure(
+            meta,
+            q_auth,
+            |meta| meta.query_advice(auth_nonce_len, Rotation::cur()),
+            |_| 1.expr(),
+        );
+        let auth_chain_id_is_zero =
+            IsZeroChip::configure(meta, q_auth, auth_chain_id_rlc, |meta| {
+                meta.advice_column_in(SecondPhase)
+            });
+        let auth_chain_id_is_tx_chain_id = IsZeroGadgetChip::configure(
+            meta,
+            q_auth,
+            |meta| {
+                meta.query_advice(auth_chain_id_rlc, Rotation::cur())
+                    - meta.query_advice(auth_tx_chain_id_rlc, Rotation::cur())
+            },
+            auth_chain_id_diff_inv,
+        );
+        let auth_nonce_is_max = IsEqualChip::configure(
+            meta,
+            q_auth,
+            |meta| meta.query_advice(auth_nonce, Rotation::cur()),
+            |_| Expression::Constant(F::from(u64::MAX)),
+        );
+        let auth_nonce_is_nonce_prev = IsEqualChip::configure(
+            meta,
+            q_auth,
+            |meta| meta.query_advice(auth_nonce, Rotation::cur()),
+            |meta| meta.query_advice(auth_nonce_prev, Rotation::cur()),
+        );
+        let auth_address_is_zero =
+            IsZeroChip::configure(meta, q_auth, auth_address, |meta| meta.advice_column());
+        let auth_code_hash_prev_is_zero =
+            IsZeroChip::configure(meta, q_auth, auth_code_hash_prev, |meta| {
+                meta.advice_column_in(SecondPhase)
+            });
+        let empty_code_hash = if cfg!(feature = "poseidon-codehash") {
+            Expression::Constant(CodeDB::empty_code_hash().to_word().to_scalar().unwrap())
+        } else {
+            rlc_expr::expr(
+                &CodeDB::empty_code_hash()
+                    .to_word()
+                    .to_le_bytes()
+                    .map(|byte| byte.expr()),
+                challenges.evm_word(),
+            )
+        };
+        let empty_keccak_hash = rlc_expr::expr(
+            &KECCAK_CODE_HASH_ZERO
+                .to_word()
+                .to_le_bytes()
+                .map(|byte| byte.expr()),
+            challenges.evm_word(),
+        );
+        let auth_code_hash_prev_is_empty = IsZeroGadgetChip::configure(
+            meta,
+            q_auth,
+            |meta| meta.query_advice(auth_code_hash_prev, Rotation::cur()) - empty_code_hash.expr(),
+            auth_code_hash_prev_diff_inv,
+        );
+        let auth_code_length_prev_is_designator = IsEqualChip::configure(
+            meta,
+            q_auth,
+            |meta| meta.query_advice(auth_code_length_prev, Rotation::cur()),
+            |_| DELEGATION_CODE_SIZE.expr(),
+        );
+        // The bytes are looked up in the bytecode table, so the prefix is their big
+        // endian value.
+        let auth_prefix_prev_is_designator = IsEqualChip::configure(
+            meta,
+            q_auth,
+            |meta| {
+                auth_prefix_prev.iter().fold(0.expr(), |value, column| {
+                    value * 256.expr() + meta.query_advice(*column, Rotation::cur())
+                })
+            },
+            |_| prefix_value(&DELEGATION_PREFIX).expr(),
+        );
+
+        meta.create_gate("authorization entries", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let is_recovered_next = meta.query_advice(auth_is_recovered, Rotation::next());
+            // the first authorization of a tx
+            cb.condition(not::expr(auth_tx_id_unchanged.expr()), |cb| {
+                cb.require_equal(
+                    "auth_idx' == 1",
+                    meta.query_advice(auth_idx, Rotation::next()),
+                    1.expr(),
+                );
+                cb.require_equal(
+                    "num_recovered' == is_recovered'",
+                    meta.query_advice(auth_num_recovered, Rotation::next()),
+                    is_recovered_next.expr(),
+                );
+            });
+            // the next authorization of the same tx
+            cb.condition(auth_tx_id_unchanged.expr(), |cb| {
+                cb.require_equal(
+                    "auth_idx' == auth_idx + 1",
+                    meta.query_advice(auth_idx, Rotation::next()),
+                    meta.query_advice(auth_idx, Rotation::cur()) + 1.expr(),
+                );
+                cb.require_equal(
+                    "num_recovered' == num_recovered + is_recovered'",
+                    meta.query_advice(auth_num_recovered, Rotation::next()),
+                    meta.query_advice(auth_num_recovered, Rotation::cur())
+                        + is_recovered_next.expr(),
+                );
+                cb.require_equal(
+                    "rw_counter' == rw_counter + is_recovered * SET_CODE_RW_DELTA",
+                    meta.query_advice(auth_rw_counter, Rotation::next()),
+                    meta.query_advice(auth_rw_counter, Rotation::cur())
+                        + meta.query_advice(auth_is_recovered, Rotation::cur())
+                            * SET_CODE_RW_DELTA.expr(),
+                );
+            });
+
+            cb.gate(and::expr([
+                meta.query_fixed(q_authorization, Rotation::cur()),
+                not::expr(auth_tx_id_is_zero.expr(Rotation::next())(meta)),
+            ]))
+        });
+
+        meta.create_gate("authorization booleans", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            for (name, col) in [
+                ("chain_id_is_short", auth_chain_id_is_short),
+                ("nonce_is_short", auth_nonce_is_short),
+                ("is_long_list", auth_is_long_list),
+                ("is_recovered", auth_is_recovered),
+                ("is_low_s", auth_is_low_s),
+                ("is_warm_prev", auth_is_warm_prev),
+            ] {
+                cb.require_boolean(name, meta.query_advice(col, Rotation::cur()));
+            }
+            // the padding rows are not applied
+            cb.condition(auth_tx_id_is_zero.expr(Rotation::cur())(meta), |cb| {
+                cb.require_zero(
+                    "is_recovered == false",
+                    meta.query_advice(auth_is_recovered, Rotation::cur()),
+                );
+            });
+            // a single byte below 0x80 is its own RLP encoding
+            cb.condition(
+                meta.query_advice(auth_chain_id_is_short, Rotation::cur()),
+                |cb| {
+                    cb.require_equal(
+                        "chain_id_is_short => chain_id_len == 1",
+                        auth_chain_id_len_is_one.expr(),
+                        1.expr(),
+                    );
+                },
+            );
+            cb.condition(
+                meta.query_advice(auth_nonce_is_short, Rotation::cur()),
+                |cb| {
+                    cb.require_equal(
+                        "nonce_is_short => nonce_len == 1",
+                        auth_nonce_len_is_one.expr(),
+                        1.expr(),
+                    );
+                },
+            );
+
+            cb.gate(meta.query_fixed(q_authorization, Rotation::cur()))
+        });
+
+        // The signed message is 0x05 || rlp([chain_id, address, nonce]), whose RLC is
+        // built from the RLC of the big-endian bytes of its fields.
+        meta.create_gate("authorization message", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let r = challenges.keccak_input();
+            let r_pow = |n: usize| (0..n).fold(1.expr(), |acc, _| acc * r.expr());
+
+            let chain_id_is_short = meta.query_advice(auth_chain_id_is_short, Rotation::cur());
+            let chain_id_len = meta.query_advice(auth_chain_id_len, Rotation::cur());
+            let chain_id_enc = meta.query_advice(auth_chain_id_rlc, Rotation::cur())
+                + not::expr(chain_id_is_short.expr())
+                    * (0x80.expr() + chain_id_len.expr())
+                    * meta.query_advice(auth_pow_chain_id_len, Rotation::cur());
+            let chain_id_enc_len = 1.expr() + not::expr(chain_id_is_short.expr()) * chain_id_len;
+
+            let nonce_is_short = meta.query_advice(auth_nonce_is_short, Rotation::cur());
+            let nonce_len = meta.query_advice(auth_nonce_len, Rotation::cur());
+            let pow_nonce_len = meta.query_advice(auth_pow_nonce_len, Rotation::cur());
+            let nonce_enc = meta.query_advice(auth_nonce_rlc, Rotation::cur())
+                + not::expr(nonce_is_short.expr())
+                    * (0x80.expr() + nonce_len.expr())
+                    * pow_nonce_len.expr();
+            let nonce_enc_len = 1.expr() + not::expr(nonce_is_short.expr()) * nonce_len;
+            let pow_nonce_enc_len =
+                select::expr(nonce_is_short, r.expr(), r.expr() * pow_nonce_len);
+
+            // the address is always encoded as 0x94 || address
+            let address_enc = (0x80 + N_BYTES_ACCOUNT_ADDRESS).expr()
+                * r_pow(N_BYTES_ACCOUNT_ADDRESS)
+                + meta.query_advice(auth_address_rlc, Rotation::cur());
+
+            let payload =
+                chain_id_enc * r_pow(N_BYTES_ACCOUNT_ADDRESS + 1) * pow_nonce_enc_len.expr()
+                    + address_enc * pow_nonce_enc_len
+                    + nonce_enc;
+            let payload_len =
+                chain_id_enc_len + (N_BYTES_ACCOUNT_ADDRESS + 1).expr() + nonce_enc_len;
+
+            // the list header is 0xf8 || payload_len for a payload longer than 55 bytes
+            let is_long_list = meta.query_advice(auth_is_long_list, Rotation::cur());
+            let header = select::expr(
+                is_long_list.expr(),
+                0xf8.expr() * r.expr() + payload_len.expr(),
+                0xc0.expr() + payload_len.expr(),
+            );
+            let pow_payload_len = meta.query_advice(auth_pow_payload_len, Rotation::cur());
+            cb.require_equal(
+                "msg_rlc == rlc(0x05 || header || payload)",
+                meta.query_advice(auth_msg_rlc, Rotation::cur()),
+                (EIP7702_AUTH_MAGIC as u64).expr()
+                    * select::expr(is_long_list.expr(), r_pow(2), r.expr())
+                    * pow_payload_len.expr()
+                    + header * pow_payload_len
+                    + payload,
+            );
+            cb.require_equal(
+                "msg_len == 2 + is_long_list + payload_len",
+                meta.query_advice(auth_msg_len, Rotation::cur()),
+                2.expr() + is_long_list + payload_len,
+            );
+
+            cb.gate(and::expr([
+                meta.query_fixed(q_authorization, Rotation::cur()),
+                not::expr(auth_tx_id_is_zero.expr(Rotation::cur())(meta)),
+            ]))
+        });
+
+        // a chain_id (nonce) byte is below 0x80 iff it is its own encoding
+        for (name, is_short, rlc, len_is_one) in [
+            (
+                "authorization chain_id byte range",
+                auth_chain_id_is_short,
+                auth_chain_id_rlc,
+                auth_chain_id_len_is_one.clone(),
+            ),
+            (
+                "authorization nonce byte range",
+                auth_nonce_is_short,
+                auth_nonce_rlc,
+                auth_nonce_len_is_one.clone(),
+            ),
+        ] {
+            meta.lookup(name, |meta| {
+                let is_short = meta.query_advice(is_short, Rotation::cur());
+                let byte = meta.query_advice(rlc, Rotation::cur());
+                let diff = is_short.expr() * (0x7f.expr() - byte.expr())
+                    + not::expr(is_short) * len_is_one.expr() * (byte - 0x80.expr());
+
+                vec![(
+                    meta.query_fixed(q_authorization, Rotation::cur()) * diff,
+                    u8_table.into(),
+                )]
+            });
+        }
+
+        meta.lookup("authorization list header range", |meta| {
+            let is_long_list = meta.query_advice(auth_is_long_list, Rotation::cur());
+            let payload_len =
+                meta.query_advice(auth_msg_len, Rotation::cur()) - 2.expr() - is_long_list.expr();
+            // is_long_list iff payload_len > 55
+            let diff = is_long_list.expr() * (payload_len.expr() - 56.expr())
+                + not::expr(is_long_list) * (55.expr() - payload_len);
+
+            vec![(
+                meta.query_fixed(q_authorization, Rotation::cur()) * diff,
+                u8_table.into(),
+            )]
+        });
+
+        meta.create_gate("authorization validity", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            // chain_id is zero or the chain id of the tx, which the pi circuit binds to the
+            // chain id of the block
+            let chain_id_is_zero = auth_chain_id_is_zero.expr(Rotation::cur())(meta);
+            let is_chain_id_valid = chain_id_is_zero.expr()
+                + not::expr(chain_id_is_zero) * auth_chain_id_is_tx_chain_id.expr();
+            cb.require_equal(
+                "is_valid_sig == is_chain_id_valid && nonce < 2^64 - 1 && is_low_s",
+                meta.query_advice(auth_is_valid_sig, Rotation::cur()),
+                and::expr([
+                    is_chain_id_valid,
+                    not::expr(auth_nonce_is_max.expr()),
+                    meta.query_advice(auth_is_low_s, Rotation::cur()),
+                ]),
+            );
+
+            // The authority has no code or is delegated already.
+            let has_code_prev = meta.query_advice(auth_has_code_prev, Rotation::cur());
+            cb.require_equal(
+                "has_code_prev == code_hash_prev is neither zero nor empty",
+                has_code_prev.expr(),
+                and::expr([
+                    not::expr(auth_code_hash_prev_is_zero.expr(Rotation::cur())(meta)),
+                    not::expr(auth_code_hash_prev_is_empty.expr()),
+                ]),
+            );
+            cb.require_equal(
+                "is_code_ok == !has_code_prev || code_prev is a delegation designator",
+                meta.query_advice(auth_is_code_ok, Rotation::cur()),
+                not::expr(has_code_prev.expr())
+                    + has_code_prev
+                        * auth_code_length_prev_is_designator.expr()
+                        * auth_prefix_prev_is_designator.expr(),
+            );
+            let is_valid = meta.query_advice(auth_is_valid, Rotation::cur());
+            cb.require_equal(
+                "is_valid == is_valid_sig && is_code_ok && nonce == nonce_prev",
+                is_valid.expr(),
+                and::expr([
+                    meta.query_advice(auth_is_valid_sig, Rotation::cur()),
+                    meta.query_advice(auth_is_code_ok, Rotation::cur()),
+                    auth_nonce_is_nonce_prev.expr(),
+                ]),
+            );
+
+            // an invalid authorization leaves the code of the authority unchanged
+            cb.condition(not::expr(is_valid.expr()), |cb| {
+                for (name, value, value_prev) in [
+                    ("code_hash", auth_code_hash, auth_code_hash_prev),
+                    (
+                        "keccak_code_hash",
+                        auth_keccak_code_hash,
+                        auth_keccak_code_hash_prev,
+                    ),
+                    ("code_size", auth_code_size, auth_code_size_prev),
+                ] {
+                    cb.require_equal(
+                        name,
+                        meta.query_advice(value, Rotation::cur()),
+                        meta.query_advice(value_prev, Rotation::cur()),
+                    );
+                }
+            });
+            // a valid authorization to the zero address clears the delegation
+            let address_is_zero = auth_address_is_zero.expr(Rotation::cur())(meta);
+            cb.condition(and::expr([is_valid.expr(), address_is_zero.expr()]), |cb| {
+                cb.require_equal(
+                    "code_hash == empty code hash",
+                    meta.query_advice(auth_code_hash, Rotation::cur()),
+                    empty_code_hash.expr(),
+                );
+                cb.require_equal(
+                    "keccak_code_hash == empty keccak hash",
+                    meta.query_advice(auth_keccak_code_hash, Rotation::cur()),
+                    empty_keccak_hash.expr(),
+                );
+                cb.require_zero(
+                    "code_size == 0",
+                    meta.query_advice(auth_code_size, Rotation::cur()),
+                );
+            });
+            // otherwise the code is the delegation designator, whose hash is looked up below
+            cb.condition(and::expr([is_valid, not::expr(address_is_zero)]), |cb| {
+                cb.require_equal(
+                    "code_size == DELEGATION_CODE_SIZE",
+                    meta.query_advice(auth_code_size, Rotation::cur()),
+                    DELEGATION_CODE_SIZE.expr(),
+                );
+            });
+
+            cb.gate(and::expr([
+                meta.query_fixed(q_authorization, Rotation::cur()),
+                meta.query_advice(auth_is_recovered, Rotation::cur()),
+            ]))
+        });
+
+        // The fields of the i-th authorization of a tx are the ones decoded by the RLP
+        // circuit.
+        for (tag, columns) in [
+            (
+                Tag::AuthChainId,
+                vec![
+                    (auth_chain_id_rlc, rlp_table.tag_bytes_rlc),
+                    (auth_chain_id_len, rlp_table.tag_length),
+                ],
+            ),
+            (
+                Tag::AuthAddress,
+                vec![
+                    (auth_address, rlp_table.tag_value),
+                    (auth_address_rlc, rlp_table.tag_bytes_rlc),
+                ],
+            ),
+            (
+                Tag::AuthNonce,
+                vec![
+                    (auth_nonce, rlp_table.tag_value),
+                    (auth_nonce_rlc, rlp_table.tag_bytes_rlc),
+                    (auth_nonce_len, rlp_table.tag_length),
+                ],
+            ),
+            (Tag::AuthYParity, vec![(auth_y_parity, rlp_table.tag_value)]),
+            (Tag::AuthR, vec![(auth_r, rlp_table.tag_value)]),
+            (Tag::AuthS, vec![(auth_s, rlp_table.tag_value)]),
+        ] {
+            meta.lookup_any("authorization field in RLP table", |meta| {
+                let enable = and::expr([
+                    meta.query_fixed(q_authorization, Rotation::cur()),
+                    not::expr(auth_tx_id_is_zero.expr(Rotation::cur())(meta)),
+                ]);
+
+                let mut lookup = vec![
+                    (
+                        1.expr(),
+                        meta.query_fixed(rlp_table.q_enable, Rotation::cur()),
+                    ),
+                    (
+                        meta.query_advice(auth_tx_id, Rotation::cur()),
+                        meta.query_advice(rlp_table.tx_id, Rotation::cur()),
+                    ),
+                    (
+                        TxHashEip7702.expr(),
+                        meta.query_advice(rlp_table.format, Rotation::cur()),
+                    ),
+                    (
+                        tag.expr(),
+                        meta.query_advice(rlp_table.rlp_tag, Rotation::cur()),
+                    ),
+                    (
+                        1.expr(),
+                        meta.query_advice(rlp_table.is_output, Rotation::cur()),
+                    ),
+                    (
+                        meta.query_advice(auth_idx, Rotation::cur()),
+                        meta.query_advice(rlp_table.authorization_idx, Rotation::cur()),
+                    ),
+                ];
+                lookup.extend(columns.iter().map(|&(input, table)| {
+                    (
+                        meta.query_advice(input, Rotation::cur()),
+                        meta.query_advice(table, Rotation::cur()),
+                    )
+                }));
+                // the address is encoded with all of its 20 bytes
+                if tag == Tag::AuthAddress {
+                    lookup.push((
+                        N_BYTES_ACCOUNT_ADDRESS.expr(),
+                        meta.query_advice(rlp_table.tag_length, Rotation::cur()),
+                    ));
+                }
+
+                lookup
+                    .into_iter()
+                    .map(|(input, table)| (enable.expr() * input, table))
+                    .collect()
+            });
+        }
+
+        meta.lookup_any("chain id of authorization tx in RLP table", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_authorization, Rotation::cur()),
+                not::expr(auth_tx_id_is_zero.expr(Rotation::cur())(meta)),
+            ]);
+
+            let input_exprs = vec![
+                1.expr(), // q_enable = true
+                meta.query_advice(auth_tx_id, Rotation::cur()),
+                TxHashEip7702.expr(),
+                Tag::ChainId.expr(),
+                1.expr(), // is_output = true
+                meta.query_advice(auth_tx_chain_id_rlc, Rotation::cur()),
+            ];
+            let table_exprs = vec![
+                meta.query_fixed(rlp_table.q_enable, Rotation::cur()),
+                meta.query_advice(rlp_table.tx_id, Rotation::cur()),
+                meta.query_advice(rlp_table.format, Rotation::cur()),
+                meta.query_advice(rlp_table.rlp_tag, Rotation::cur()),
+                meta.query_advice(rlp_table.is_output, Rotation::cur()),
+                meta.query_advice(rlp_table.tag_bytes_rlc, Rotation::cur()),
+            ];
+
+            input_exprs
+                .into_iter()
+                .zip_eq(table_exprs.into_iter())
+                .map(|(input, table)| (enable.expr() * input, table))
+                .collect()
+        });
+
+        // powers of randomness for the lengths of chain_id, nonce and the payload
+        for (name, len, pow) in [
+            (
+                "authorization chain_id length in PowOfRand table",
+                auth_chain_id_len,
+                auth_pow_chain_id_len,
+            ),
+            (
+                "authorization nonce length in PowOfRand table",
+                auth_nonce_len,
+                auth_pow_nonce_len,
+            ),
+        ] {
+            meta.lookup_any(name, |meta| {
+                let enable = and::expr([
+                    meta.query_fixed(q_authorization, Rotation::cur()),
+                    not::expr(auth_tx_id_is_zero.expr(Rotation::cur())(meta)),
+                ]);
+
+                let input_exprs = vec![
+                    1.expr(), // q_enable = true
+                    meta.query_advice(len, Rotation::cur()),
+                    meta.query_advice(pow, Rotation::cur()),
+                ];
+
+                input_exprs
+                    .into_iter()
+                    .zip_eq(pow_of_rand_table.table_exprs(meta).into_iter())
+                    .map(|(input, table)| (enable.expr() * input, table))
+                    .collect()
+            });
+        }
+        meta.lookup_any("authorization payload length in PowOfRand table", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_authorization, Rotation::cur()),
+                not::expr(auth_tx_id_is_zero.expr(Rotation::cur())(meta)),
+            ]);
+
+            let input_exprs = vec![
+                1.expr(), // q_enable = true
+                meta.query_advice(auth_msg_len, Rotation::cur())
+                    - 2.expr()
+                    - meta.query_advice(auth_is_long_list, Rotation::cur()),
+                meta.query_advice(auth_pow_payload_len, Rotation::cur()),
+            ];
+
+            input_exprs
+                .into_iter()
+                .zip_eq(pow_of_rand_table.table_exprs(meta).into_iter())
+                .map(|(input, table)| (enable.expr() * input, table))
+                .collect()
+        });
+
+        meta.lookup_any("authorization message in Keccak table", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_authorization, Rotation::cur()),
+                meta.query_advice(auth_is_recovered, Rotation::cur()),
+            ]);
+
+            vec![
+                1.expr(), // q_enable
+                1.expr(), // is_final
+                meta.query_advice(auth_msg_rlc, Rotation::cur()),
+                meta.query_advice(auth_msg_len, Rotation::cur()),
+                meta.query_advice(auth_msg_hash_rlc, Rotation::cur()),
+            ]
+            .into_iter()
+            .zip(keccak_table.table_exprs(meta).into_iter())
+            .map(|(arg, table)| (enable.clone() * arg, table))
+            .collect()
+        });
+
+        meta.lookup_any("authority in Sig table", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_authorization, Rotation::cur()),
+                meta.query_advice(auth_is_recovered, Rotation::cur()),
+            ]);
+
+            let input_exprs = vec![
+                1.expr(), // q_enable = true
+                meta.query_advice(auth_msg_hash_rlc, Rotation::cur()),
+                meta.query_advice(auth_y_parity, Rotation::cur()),
+                meta.query_advice(auth_r, Rotation::cur()),
+                meta.query_advice(auth_s, Rotation::cur()),
+                meta.query_advice(auth_authority, Rotation::cur()),
+                1.expr(), // is_valid
+            ];
+            let table_exprs = vec![
+                meta.query_fixed(sig_table.q_enable, Rotation::cur()),
+                meta.query_advice(sig_table.msg_hash_rlc, Rotation::cur()),
+                meta.query_advice(sig_table.sig_v, Rotation::cur()),
+                meta.query_advice(sig_table.sig_r_rlc, Rotation::cur()),
+                meta.query_advice(sig_table.sig_s_rlc, Rotation::cur()),
+                meta.query_advice(sig_table.recovered_addr, Rotation::cur()),
+                meta.query_advice(sig_table.is_valid, Rotation::cur()),
+            ];
+
+            input_exprs
+                .into_iter()
+                .zip_eq(table_exprs.into_iter())
+                .map(|(input, table)| (enable.expr() * input, table))
+                .collect()
+        });
+
+        // The code set by a valid authorization to a non-zero address is the
+        // delegation designator 0xef0100 || address.
+        let is_delegating = |meta: &mut VirtualCells<F>| {
+            and::expr([
+                meta.query_fixed(q_authorization, Rotation::cur()),
+                meta.query_advice(auth_is_recovered, Rotation::cur()),
+                meta.query_advice(auth_is_valid, Rotation::cur()),
+                not::expr(auth_address_is_zero.expr(Rotation::cur())(meta)),
+            ])
+        };
+        meta.lookup_any("delegation designator in Keccak table", |meta| {
+            let enable = is_delegating(meta);
+            let r = challenges.keccak_input();
+            let prefix_rlc = DELEGATION_PREFIX
+                .iter()
+                .fold(0.expr(), |acc, byte| acc * r.expr() + byte.expr());
+            let code_rlc = (0..N_BYTES_ACCOUNT_ADDRESS).fold(prefix_rlc, |acc, _| acc * r.expr())
+                + meta.query_advice(auth_address_rlc, Rotation::cur());
+            // the keccak code hash is the code hash unless the code hash is poseidon
+            let keccak_code_hash = if cfg!(feature = "poseidon-codehash") {
+                auth_keccak_code_hash
+            } else {
+                auth_code_hash
+            };
+
+            vec![
+                1.expr(), // q_enable
+                1.expr(), // is_final
+                code_rlc,
+                DELEGATION_CODE_SIZE.expr(),
+                meta.query_advice(keccak_code_hash, Rotation::cur()),
+            ]
+            .into_iter()
+            .zip(keccak_table.table_exprs(meta).into_iter())
+            .map(|(arg, table)| (enable.clone() * arg, table))
+            .collect()
         });
 
-        meta.create_gate("hash tag lookup into RLP table condition", |meta| {
-            let mut cb = BaseConstraintBuilder::default();
+        // The poseidon code hash of the delegation designator, which fits in one
+        // field element of the hash input.
+        #[cfg(all(feature = "poseidon-codehash", feature = "scroll-trace"))]
+        {
+            use hash_circuit::hash::HASHABLE_DOMAIN_SPEC;
+
+            meta.lookup_any("delegation designator in Poseidon table", |meta| {
+                let enable = is_delegating(meta);
+                let prefix = DELEGATION_PREFIX
+                    .iter()
+                    .fold(0.expr(), |acc, byte| acc * 256.expr() + byte.expr());
+                let padding = (DELEGATION_CODE_SIZE
+                    ..bus_mapping::util::POSEIDON_HASH_BYTES_IN_FIELD)
+                    .fold(1.expr(), |acc, _| acc * 256.expr());
+                let address_shift =
+                    (0..N_BYTES_ACCOUNT_ADDRESS).fold(1.expr(), |acc, _| acc * 256.expr());
+                let input0 = (prefix * address_shift
+                    + meta.query_advice(auth_address, Rotation::cur()))
+                    * padding;
+
+                let input_exprs = vec![
+                    1.expr(), // q_enable = true
+                    meta.query_advice(auth_code_hash, Rotation::cur()),
+                    input0,
+                    0.expr(), // input1
+                    Expression::Constant(F::from_u128(
+                        HASHABLE_DOMAIN_SPEC * DELEGATION_CODE_SIZE as u128,
+                    )),
+                    0.expr(), // domain_spec
+                    1.expr(), // heading_mark
+                ];
 
-            let is_tag_in_tx_hash = sum::expr([
-                is_nonce(meta),
-                is_gas_price(meta),
-                is_gas(meta),
-                is_to(meta),
-                is_value(meta),
-                is_tx_gas_cost(meta),
-                is_data_rlc(meta),
-                is_sig_v(meta),
-                is_sig_r(meta),
-                is_sig_s(meta),
-                is_hash_length(meta),
-                is_hash_rlc(meta),
+                input_exprs
+                    .into_iter()
+                    .zip_eq(poseidon_table.table_exprs(meta).into_iter())
+                    .map(|(input, table)| (enable.expr() * input, table))
+                    .collect()
+            });
+        }
+
+        // The length and the prefix of the code of the authority, as the
+        // DelegationGadget checks them.
+        meta.lookup_any("authority code length in Bytecode table", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_authorization, Rotation::cur()),
+                meta.query_advice(auth_is_recovered, Rotation::cur()),
+                meta.query_advice(auth_has_code_prev, Rotation::cur()),
             ]);
 
-            cb.require_equal(
-                "condition",
-                is_tag_in_tx_hash,
-                meta.query_advice(
-                    lookup_conditions[&LookupCondition::RlpHashTag],
-                    Rotation::cur(),
-                ),
-            );
+            let input_exprs = vec![
+                1.expr(), // q_enable = true
+                meta.query_advice(auth_code_hash_prev, Rotation::cur()),
+                BytecodeFieldTag::Header.expr(),
+                0.expr(), // index
+                0.expr(), // is_code
+                meta.query_advice(auth_code_length_prev, Rotation::cur()),
+            ];
 
-            cb.gate(and::expr([
-                meta.query_fixed(q_enable, Rotation::cur()),
-                not::expr(meta.query_advice(is_l1_msg, Rotation::cur())),
-            ]))
+            input_exprs
+                .into_iter()
+                .zip_eq(bytecode_table.table_exprs(meta).into_iter())
+                .map(|(input, table)| (enable.expr() * input, table))
+                .collect()
         });
+        // None of the prefix bytes is a push, so they are all code.
+        for (index, byte) in auth_prefix_prev.into_iter().enumerate() {
+            meta.lookup_any("authority code prefix in Bytecode table", |meta| {
+                let enable = and::expr([
+                    meta.query_fixed(q_authorization, Rotation::cur()),
+                    meta.query_advice(auth_is_recovered, Rotation::cur()),
+                    meta.query_advice(auth_has_code_prev, Rotation::cur()),
+                    auth_code_length_prev_is_designator.expr(),
+                ]);
+
+                let input_exprs = vec![
+                    1.expr(), // q_enable = true
+                    meta.query_advice(auth_code_hash_prev, Rotation::cur()),
+                    BytecodeFieldTag::Byte.expr(),
+                    index.expr(),
+                    1.expr(), // is_code
+                    meta.query_advice(byte, Rotation::cur()),
+                ];
 
-        meta.create_gate("l1 msg lookup into RLP table condition", |meta| {
-            let mut cb = BaseConstraintBuilder::default();
-            let is_tag_in_l1_msg_hash = sum::expr([
-                is_nonce(meta),
-                is_gas(meta),
-                is_to(meta),
-                is_value(meta),
-                is_data_rlc(meta),
-                is_caller_addr(meta),
-                is_hash_length(meta),
-                is_hash_rlc(meta),
+                input_exprs
+                    .into_iter()
+                    .zip_eq(bytecode_table.table_exprs(meta).into_iter())
+                    .map(|(input, table)| (enable.expr() * input, table))
+                    .collect()
+            });
+        }
+
+        // BeginTx applies a recovered authorization with these writes at consecutive rw
+        // counters: the access list, the code (hashes and size), the nonce and the refund.
+        let mut auth_account_writes = vec![(
+            AccountFieldTag::CodeHash,
+            auth_code_hash,
+            auth_code_hash_prev,
+        )];
+        #[cfg(feature = "scroll")]
+        auth_account_writes.extend([
+            (
+                AccountFieldTag::KeccakCodeHash,
+                auth_keccak_code_hash,
+                auth_keccak_code_hash_prev,
+            ),
+            (
+                AccountFieldTag::CodeSize,
+                auth_code_size,
+                auth_code_size_prev,
+            ),
+        ]);
+        meta.lookup_any("authority access list in RW table", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_authorization, Rotation::cur()),
+                meta.query_advice(auth_is_recovered, Rotation::cur()),
             ]);
+            let is_warm_prev = meta.query_advice(auth_is_warm_prev, Rotation::cur());
+            let is_valid_sig = meta.query_advice(auth_is_valid_sig, Rotation::cur());
 
-            cb.require_equal(
-                "lookup into RLP table iff tag in l1 msg hash",
-                is_tag_in_l1_msg_hash,
-                meta.query_advice(
-                    lookup_conditions[&LookupCondition::L1MsgHash],
-                    Rotation::cur(),
-                ),
-            );
+            let input_exprs = vec![
+                1.expr(), // q_enable = true
+                meta.query_advice(auth_rw_counter, Rotation::cur()),
+                1.expr(), // is_write = true
+                RwTableTag::TxAccessListAccount.expr(),
+                meta.query_advice(auth_tx_id, Rotation::cur()),
+                meta.query_advice(auth_authority, Rotation::cur()),
+                0.expr(), // field_tag
+                0.expr(), // storage_key
+                // is_warm = is_warm_prev || is_valid_sig
+                is_warm_prev.expr() + is_valid_sig.expr() - is_warm_prev.expr() * is_valid_sig,
+                is_warm_prev,
+                0.expr(), // aux1
+                0.expr(), // aux2
+            ];
 
-            cb.gate(and::expr([
-                meta.query_fixed(q_enable, Rotation::cur()),
-                meta.query_advice(is_l1_msg, Rotation::cur()),
-            ]))
+            input_exprs
+                .into_iter()
+                .zip_eq(rw_table.table_exprs(meta).into_iter())
+                .map(|(input, table)| (enable.expr() * input, table))
+                .collect()
         });
+        for (i, (field_tag, value, value_prev)) in auth_account_writes.into_iter().enumerate() {
+            meta.lookup_any("authority account in RW table", |meta| {
+                let enable = and::expr([
+                    meta.query_fixed(q_authorization, Rotation::cur()),
+                    meta.query_advice(auth_is_recovered, Rotation::cur()),
+                ]);
+
+                let input_exprs = vec![
+                    1.expr(), // q_enable = true
+                    meta.query_advice(auth_rw_counter, Rotation::cur()) + (i + 1).expr(),
+                    1.expr(), // is_write = true
+                    RwTableTag::Account.expr(),
+                    0.expr(), // id
+                    meta.query_advice(auth_authority, Rotation::cur()),
+                    field_tag.expr(),
+                    0.expr(), // storage_key
+                    meta.query_advice(value, Rotation::cur()),
+                    meta.query_advice(value_prev, Rotation::cur()),
+                    0.expr(), // aux1
+                    0.expr(), // aux2
+                ];
 
-        meta.create_gate("lookup into Keccak table condition", |meta| {
-            let mut cb = BaseConstraintBuilder::default();
+                input_exprs
+                    .into_iter()
+                    .zip_eq(rw_table.table_exprs(meta).into_iter())
+                    .map(|(input, table)| (enable.expr() * input, table))
+                    .collect()
+            });
+        }
+        meta.lookup_any("authority nonce in RW table", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_authorization, Rotation::cur()),
+                meta.query_advice(auth_is_recovered, Rotation::cur()),
+            ]);
+            let nonce_prev = meta.query_advice(auth_nonce_prev, Rotation::cur());
 
-            let is_tag_sign_or_hash = sum::expr([
-                and::expr([
-                    is_sign_length(meta),
-                    not::expr(meta.query_advice(is_l1_msg, Rotation::cur())),
-                ]),
-                is_hash_length(meta),
+            let input_exprs = vec![
+                1.expr(), // q_enable = true
+                meta.query_advice(auth_rw_counter, Rotation::cur())
+                    + (SET_CODE_RW_DELTA - 2).expr(),
+                1.expr(), // is_write = true
+                RwTableTag::Account.expr(),
+                0.expr(), // id
+                meta.query_advice(auth_authority, Rotation::cur()),
+                AccountFieldTag::Nonce.expr(),
+                0.expr(), // storage_key
+                nonce_prev.expr() + meta.query_advice(auth_is_valid, Rotation::cur()),
+                nonce_prev,
+                0.expr(), // aux1
+                0.expr(), // aux2
+            ];
+
+            input_exprs
+                .into_iter()
+                .zip_eq(rw_table.table_exprs(meta).into_iter())
+                .map(|(input, table)| (enable.expr() * input, table))
+                .collect()
+        });
+        meta.lookup_any("authority refund in RW table", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_authorization, Rotation::cur()),
+                meta.query_advice(auth_is_recovered, Rotation::cur()),
             ]);
-            cb.require_equal(
-                "condition",
-                is_tag_sign_or_hash,
-                meta.query_advice(lookup_conditions[&LookupCondition::Keccak], Rotation::cur()),
-            );
+            let refund_prev = meta.query_advice(auth_refund_prev, Rotation::cur());
+            // the intrinsic gas of an authorization to an existing authority is refunded
+            // in part
+            let refund = refund_prev.expr()
+                + meta.query_advice(auth_is_valid, Rotation::cur())
+                    * not::expr(auth_code_hash_prev_is_zero.expr(Rotation::cur())(meta))
+                    * (GasCost::PER_EMPTY_ACCOUNT.as_u64() - GasCost::PER_AUTH_BASE.as_u64())
+                        .expr();
 
-            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+            let input_exprs = vec![
+                1.expr(), // q_enable = true
+                meta.query_advice(auth_rw_counter, Rotation::cur())
+                    + (SET_CODE_RW_DELTA - 1).expr(),
+                1.expr(), // is_write = true
+                RwTableTag::TxRefund.expr(),
+                meta.query_advice(auth_tx_id, Rotation::cur()),
+                0.expr(), // address
+                0.expr(), // field_tag
+                0.expr(), // storage_key
+                refund,
+                refund_prev,
+                0.expr(), // aux1
+                0.expr(), // aux2
+            ];
+
+            input_exprs
+                .into_iter()
+                .zip_eq(rw_table.table_exprs(meta).into_iter())
+                .map(|(input, table)| (enable.expr() * input, table))
+                .collect()
         });
 
-        // lookups to RLP table, Tx table, Keccak table
-        Self::configure_lookups(
-            meta,
-            q_enable,
-            rlp_tag,
-            tx_value_rlc,
-            tx_value_length,
-            tx_type_bits,
-            tx_id_is_zero.clone(),
-            is_none,
-            &lookup_conditions,
-            is_final,
-            is_calldata,
-            is_chain_id,
-            is_l1_msg,
-            sv_address,
-            calldata_gas_cost_acc,
-            calldata_rlc,
-            tx_table.clone(),
-            keccak_table.clone(),
-            rlp_table,
-            sig_table,
-        );
+        meta.lookup_any("first authorization of a tx", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                meta.query_advice(
+                    lookup_conditions[&LookupCondition::Authorization],
+                    Rotation::cur(),
+                ),
+            ]);
 
-        meta.create_gate("tx_gas_cost == 0 for L1 msg", |meta| {
-            let mut cb = BaseConstraintBuilder::default();
+            let input_exprs = vec![
+                meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                1.expr(), // auth_idx
+                // BeginTx applies the authorizations after the access list, whose rw
+                // counter and lengths are the three rows before AuthorizationListLen
+                meta.query_advice(tx_table.value, Rotation::prev())
+                    + meta.query_advice(tx_table.value, Rotation(-3))
+                    + meta.query_advice(tx_table.value, Rotation(-2)),
+            ];
+            let table_exprs = vec![
+                meta.query_advice(auth_tx_id, Rotation::cur()),
+                meta.query_advice(auth_idx, Rotation::cur()),
+                meta.query_advice(auth_rw_counter, Rotation::cur()),
+            ];
 
-            cb.condition(is_tx_gas_cost(meta), |cb| {
-                cb.require_zero(
-                    "tx_gas_cost == 0",
-                    meta.query_advice(tx_table.value, Rotation::cur()),
-                );
-            });
+            input_exprs
+                .into_iter()
+                .zip_eq(table_exprs.into_iter())
+                .map(|(input, table)| (enable.expr() * input, table))
+                .collect()
+        });
 
-            cb.gate(and::expr([
+        meta.lookup_any("last authorization of a tx", |meta| {
+            let enable = and::expr([
                 meta.query_fixed(q_enable, Rotation::cur()),
-                meta.query_advice(is_l1_msg, Rotation::cur()),
-            ]))
+                meta.query_advice(
+                    lookup_conditions[&LookupCondition::Authorization],
+                    Rotation::cur(),
+                ),
+            ]);
+            // AuthorizationListAuthoritiesLen is next to AuthorizationListLen
+            let num_recovered = meta.query_advice(tx_table.value, Rotation::next());
+
+            let input_exprs = vec![
+                meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                meta.query_advice(tx_table.value, Rotation::cur()), // num of authorizations
+                num_recovered.expr(),
+                meta.query_advice(tx_table.value, Rotation::prev())
+                    + meta.query_advice(tx_table.value, Rotation(-3))
+                    + meta.query_advice(tx_table.value, Rotation(-2))
+                    + num_recovered * SET_CODE_RW_DELTA.expr(),
+                1.expr(), // is_last = true
+            ];
+            let table_exprs = vec![
+                meta.query_advice(auth_tx_id, Rotation::cur()),
+                meta.query_advice(auth_idx, Rotation::cur()),
+                meta.query_advice(auth_num_recovered, Rotation::cur()),
+                meta.query_advice(auth_rw_counter, Rotation::cur())
+                    + meta.query_advice(auth_is_recovered, Rotation::cur())
+                        * SET_CODE_RW_DELTA.expr(),
+                meta.query_fixed(q_authorization, Rotation::cur())
+                    * not::expr(auth_tx_id_unchanged.expr()),
+            ];
+
+            input_exprs
+                .into_iter()
+                .zip_eq(table_exprs.into_iter())
+                .map(|(input, table)| (enable.expr() * input, table))
+                .collect()
         });
 
         ///////////////////////////////////////////////////////////////////////
@@ -1040,7 +2930,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             let mut cb = BaseConstraintBuilder::default();
 
             let (lt_expr, eq_expr) = tx_id_cmp_cum_num_txs.expr(meta, None);
-            cb.condition(is_block_num(meta), |cb| {
+            cb.condition(meta.query_advice(is_tag_block_num, Rotation::cur()), |cb| {
                 cb.require_equal("lt or eq", sum::expr([lt_expr, eq_expr]), true.expr());
             });
 
@@ -1281,9 +3171,22 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
                 },
             );
 
-            // TODO:
-            //  4. eip1559 tx: v Є {0, 1}
-            //  5. eip2930 tx: v Є {0, 1}
+            //  4. eip2930, eip1559, eip4844 and eip7702 tx: v Є {0, 1}
+            cb.condition(
+                and::expr([
+                    is_chain_id.expr(),
+                    sum::expr([
+                        meta.query_advice(is_eip2930, Rotation::cur()),
+                        meta.query_advice(is_eip1559, Rotation::cur()),
+                        meta.query_advice(is_eip4844, Rotation::cur()),
+                        meta.query_advice(is_eip7702, Rotation::cur()),
+                    ]),
+                ]),
+                |cb| {
+                    let v = meta.query_advice(tx_table.value, Rotation::next());
+                    cb.require_boolean("V Є {0, 1}", v);
+                },
+            );
 
             cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
         });
@@ -1341,17 +3244,96 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             num_all_txs_acc,
             total_l1_popped_before,
             is_l1_msg,
+            is_eip1559,
+            is_eip2930,
+            is_eip4844,
+            is_eip7702,
             is_chain_id,
+            is_blob_hash: is_blob_hash_col,
+            is_access_list_len,
             is_final,
             calldata_gas_cost_acc,
             calldata_rlc,
             calldata_byte,
             sv_address,
+            q_access_list,
+            access_list_tx_id,
+            access_list_idx,
+            access_list_storage_key_idx,
+            access_list_is_storage_key,
+            access_list_address,
+            access_list_storage_key,
+            access_list_rw_counter,
+            access_list_is_warm_prev,
+            access_list_format: access_list_format_col,
+            access_list_tx_id_is_zero,
+            access_list_tx_id_unchanged,
+            is_authorization_list_len: is_authorization_list_len_col,
+            q_authorization,
+            auth_tx_id,
+            auth_idx,
+            auth_chain_id_rlc,
+            auth_chain_id_len,
+            auth_tx_chain_id_rlc,
+            auth_address,
+            auth_address_rlc,
+            auth_nonce,
+            auth_nonce_rlc,
+            auth_nonce_len,
+            auth_y_parity,
+            auth_r,
+            auth_s,
+            auth_chain_id_is_short,
+            auth_nonce_is_short,
+            auth_is_long_list,
+            auth_pow_chain_id_len,
+            auth_pow_nonce_len,
+            auth_pow_payload_len,
+            auth_msg_rlc,
+            auth_msg_len,
+            auth_msg_hash_rlc,
+            auth_is_recovered,
+            auth_authority,
+            auth_rw_counter,
+            auth_num_recovered,
+            auth_is_low_s,
+            auth_is_valid_sig,
+            auth_has_code_prev,
+            auth_code_length_prev,
+            auth_prefix_prev,
+            auth_is_code_ok,
+            auth_is_valid,
+            auth_is_warm_prev,
+            auth_nonce_prev,
+            auth_code_hash_prev,
+            auth_code_hash,
+            auth_keccak_code_hash_prev,
+            auth_keccak_code_hash,
+            auth_code_size_prev,
+            auth_code_size,
+            auth_refund_prev,
+            auth_tx_id_is_zero,
+            auth_tx_id_unchanged,
+            auth_chain_id_len_is_one,
+            auth_nonce_len_is_one,
+            auth_chain_id_is_zero,
+            auth_chain_id_is_tx_chain_id,
+            auth_nonce_is_max,
+            auth_nonce_is_nonce_prev,
+            auth_address_is_zero,
+            auth_code_hash_prev_is_zero,
+            auth_code_hash_prev_is_empty,
+            auth_code_length_prev_is_designator,
+            auth_prefix_prev_is_designator,
             sig_table,
             block_table,
             tx_table,
             keccak_table,
             rlp_table,
+            rw_table,
+            pow_of_rand_table,
+            poseidon_table,
+            bytecode_table,
             is_tag_block_num,
             _marker: PhantomData,
             num_txs,
@@ -1494,13 +3476,34 @@ impl<F: Field> TxCircuitConfig<F> {
         is_tx_type!(is_pre_eip155, PreEip155);
         is_tx_type!(is_eip155, Eip155);
         is_tx_type!(is_l1_msg, L1Msg);
+        is_tx_type!(is_eip2930, Eip2930);
+        is_tx_type!(is_eip1559, Eip1559);
+        is_tx_type!(is_eip4844, Eip4844);
+        is_tx_type!(is_eip7702, Eip7702);
 
-        // lookup tx type in RLP table for L1Msg only
+        // lookup tx type in RLP table for L1Msg and the typed txs only
         meta.lookup_any("lookup tx type in RLP table", |meta| {
-            let enable = and::expr([meta.query_fixed(q_enable, Rotation::cur()), is_l1_msg(meta)]);
-            let hash_format = L1MsgHash.expr();
-            let tag_value = 0x7E.expr();
-            let tag_bytes_rlc = 0x7E.expr();
+            let enable = and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                sum::expr([
+                    is_l1_msg(meta),
+                    is_eip2930(meta),
+                    is_eip1559(meta),
+                    is_eip4844(meta),
+                    is_eip7702(meta),
+                ]),
+            ]);
+            let hash_format = is_l1_msg(meta) * L1MsgHash.expr()
+                + is_eip2930(meta) * TxHashEip2930.expr()
+                + is_eip1559(meta) * TxHashEip1559.expr()
+                + is_eip4844(meta) * TxHashEip4844.expr()
+                + is_eip7702(meta) * TxHashEip7702.expr();
+            let tag_value = is_l1_msg(meta) * 0x7E.expr()
+                + is_eip2930(meta) * EIP2930_TX_TYPE.expr()
+                + is_eip1559(meta) * EIP1559_TX_TYPE.expr()
+                + is_eip4844(meta) * EIP4844_TX_TYPE.expr()
+                + is_eip7702(meta) * EIP7702_TX_TYPE.expr();
+            let tag_bytes_rlc = tag_value.expr();
             let tag_length = 1.expr();
 
             let input_exprs = vec![
@@ -1523,6 +3526,46 @@ impl<F: Field> TxCircuitConfig<F> {
                 .collect()
         });
 
+        // lookup tx type in RLP table for signing the typed txs
+        meta.lookup_any("lookup tx type in RLP table for signing", |meta| {
+            let enable = and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                sum::expr([
+                    is_eip2930(meta),
+                    is_eip1559(meta),
+                    is_eip4844(meta),
+                    is_eip7702(meta),
+                ]),
+            ]);
+            let sign_format = is_eip2930(meta) * TxSignEip2930.expr()
+                + is_eip1559(meta) * TxSignEip1559.expr()
+                + is_eip4844(meta) * TxSignEip4844.expr()
+                + is_eip7702(meta) * TxSignEip7702.expr();
+            let tag_value = is_eip2930(meta) * EIP2930_TX_TYPE.expr()
+                + is_eip1559(meta) * EIP1559_TX_TYPE.expr()
+                + is_eip4844(meta) * EIP4844_TX_TYPE.expr()
+                + is_eip7702(meta) * EIP7702_TX_TYPE.expr();
+
+            let input_exprs = vec![
+                1.expr(), // q_enable = true
+                meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                sign_format,
+                RLPTxType.expr(),
+                tag_value.expr(), // tag_value
+                tag_value,        // tag_bytes_rlc
+                1.expr(),         // tag_length
+                1.expr(),         // is_output = true
+                0.expr(),         // is_none = false
+            ];
+            assert_eq!(input_exprs.len(), rlp_table.table_exprs(meta).len());
+
+            input_exprs
+                .into_iter()
+                .zip(rlp_table.table_exprs(meta).into_iter())
+                .map(|(input, table)| (enable.expr() * input, table))
+                .collect()
+        });
+
         // lookup tx tag in RLP table for signing.
         meta.lookup_any("lookup tx tag in RLP Table for signing", |meta| {
             let enable = and::expr([
@@ -1535,7 +3578,11 @@ impl<F: Field> TxCircuitConfig<F> {
             let rlp_tag = meta.query_advice(rlp_tag, Rotation::cur());
             let is_none = meta.query_advice(is_none, Rotation::cur());
             let sign_format = is_pre_eip155(meta) * TxSignPreEip155.expr()
-                + is_eip155(meta) * TxSignEip155.expr();
+                + is_eip155(meta) * TxSignEip155.expr()
+                + is_eip2930(meta) * TxSignEip2930.expr()
+                + is_eip1559(meta) * TxSignEip1559.expr()
+                + is_eip4844(meta) * TxSignEip4844.expr()
+                + is_eip7702(meta) * TxSignEip7702.expr();
 
             // q_enable, tx_id, format, rlp_tag, tag_value, is_output, is_none
             vec![
@@ -1574,7 +3621,11 @@ impl<F: Field> TxCircuitConfig<F> {
             let is_none = meta.query_advice(is_none, Rotation::cur());
             let hash_format = is_pre_eip155(meta) * TxHashPreEip155.expr()
                 + is_eip155(meta) * TxHashEip155.expr()
-                + is_l1_msg(meta) * L1MsgHash.expr();
+                + is_l1_msg(meta) * L1MsgHash.expr()
+                + is_eip2930(meta) * TxHashEip2930.expr()
+                + is_eip1559(meta) * TxHashEip1559.expr()
+                + is_eip4844(meta) * TxHashEip4844.expr()
+                + is_eip7702(meta) * TxHashEip7702.expr();
 
             vec![
                 1.expr(), // q_enable = true
@@ -1614,7 +3665,9 @@ impl<F: Field> TxCircuitConfig<F> {
             let sv_address = meta.query_advice(sv_address, Rotation::cur());
 
             let v = is_eip155(meta) * (sig_v.expr() - 2.expr() * chain_id - 35.expr())
-                + is_pre_eip155(meta) * (sig_v.expr() - 27.expr());
+                + is_pre_eip155(meta) * (sig_v.expr() - 27.expr())
+                + (is_eip2930(meta) + is_eip1559(meta) + is_eip4844(meta) + is_eip7702(meta))
+                    * sig_v.expr();
 
             let input_exprs = vec![
                 1.expr(),     // q_enable = true
@@ -1771,6 +3824,23 @@ impl<F: Field> TxCircuitConfig<F> {
 
         // assign to lookup condition columns
         let is_l1_msg = tx.map(|tx| tx.tx_type.is_l1_msg()).unwrap_or(false);
+        let is_eip2930 = tx.map(|tx| tx.tx_type.is_eip2930_tx()).unwrap_or(false);
+        let is_eip1559 = tx.map(|tx| tx.tx_type.is_eip1559_tx()).unwrap_or(false);
+        let is_eip4844 = tx.map(|tx| tx.tx_type.is_eip4844_tx()).unwrap_or(false);
+        let is_eip7702 = tx.map(|tx| tx.tx_type.is_eip7702_tx()).unwrap_or(false);
+        // fields of eip1559 tx besides the ones shared with legacy tx
+        let eip1559_set = [ChainID, MaxPriorityFeePerGas, MaxFeePerGas];
+        // fields of eip4844 tx besides the ones shared with legacy tx
+        let eip4844_set = [
+            ChainID,
+            MaxPriorityFeePerGas,
+            MaxFeePerGas,
+            MaxFeePerBlobGas,
+        ];
+        // fields of eip7702 tx besides the ones shared with legacy tx
+        let eip7702_set = [ChainID, MaxPriorityFeePerGas, MaxFeePerGas];
+        let is_eip1559_fee = is_eip1559 || is_eip4844 || is_eip7702;
+        let used_blob_slot = value.map(|value| !value.is_zero_vartime());
         let mut conditions = HashMap::<LookupCondition, Value<F>>::new();
         if tag == CallData {
             conditions = vec![
@@ -1778,6 +3848,9 @@ impl<F: Field> TxCircuitConfig<F> {
                 (LookupCondition::L1MsgHash, Value::known(F::zero())),
                 (LookupCondition::RlpSignTag, Value::known(F::zero())),
                 (LookupCondition::RlpHashTag, Value::known(F::zero())),
+                (LookupCondition::AccessList, Value::known(F::zero())),
+                (LookupCondition::Authorization, Value::known(F::zero())),
+                (LookupCondition::LastBlob, Value::known(F::zero())),
                 (LookupCondition::Keccak, Value::known(F::zero())),
             ]
             .into_iter()
@@ -1805,9 +3878,19 @@ impl<F: Field> TxCircuitConfig<F> {
                     TxSignRLC,
                 ];
                 let is_tag_in_set = sign_set.into_iter().filter(|_tag| tag == *_tag).count() == 1;
-                let case1 = is_tag_in_set && !is_l1_msg;
-                let case2 = (tag == ChainID) && tx.map_or(false, |tx| tx.tx_type.is_eip155_tx());
-                Value::known(F::from((case1 || case2) as u64))
+                // gas price of eip4844 (eip7702) tx is the effective gas price which is not in
+                // RLP
+                let case1 = is_tag_in_set && !is_l1_msg && !(is_eip1559_fee && tag == GasPrice);
+                let case2 = (tag == ChainID)
+                    && (is_eip2930 || tx.map_or(false, |tx| tx.tx_type.is_eip155_tx()));
+                let case3 = is_eip4844 && eip4844_set.contains(&tag);
+                let case4 = is_eip7702 && eip7702_set.contains(&tag);
+                let case5 = is_eip1559 && eip1559_set.contains(&tag);
+                if is_eip4844 && tag == BlobVersionedHash {
+                    used_blob_slot.map(|used| F::from(used as u64))
+                } else {
+                    Value::known(F::from((case1 || case2 || case3 || case4 || case5) as u64))
+                }
             });
             // lookup to RLP table for hashing (non L1 msg)
             conditions.insert(LookupCondition::RlpHashTag, {
@@ -1826,7 +3909,16 @@ impl<F: Field> TxCircuitConfig<F> {
                     TxHashRLC,
                 ];
                 let is_tag_in_set = hash_set.into_iter().filter(|_tag| tag == *_tag).count() == 1;
-                Value::known(F::from((!is_l1_msg && is_tag_in_set) as u64))
+                let case1 = is_tag_in_set && !is_l1_msg && !(is_eip1559_fee && tag == GasPrice);
+                let case2 = is_eip4844 && eip4844_set.contains(&tag);
+                let case3 = is_eip7702 && eip7702_set.contains(&tag);
+                let case4 = is_eip1559 && eip1559_set.contains(&tag);
+                let case5 = is_eip2930 && tag == ChainID;
+                if is_eip4844 && tag == BlobVersionedHash {
+                    used_blob_slot.map(|used| F::from(used as u64))
+                } else {
+                    Value::known(F::from((case1 || case2 || case3 || case4 || case5) as u64))
+                }
             });
             // lookup to RLP table for hashing (L1 msg)
             conditions.insert(LookupCondition::L1MsgHash, {
@@ -1844,6 +3936,27 @@ impl<F: Field> TxCircuitConfig<F> {
                 let is_tag_in_set = hash_set.into_iter().filter(|_tag| tag == *_tag).count() == 1;
                 Value::known(F::from((is_l1_msg && is_tag_in_set) as u64))
             });
+            // lookup to the access list rows for non-empty access list
+            conditions.insert(LookupCondition::AccessList, {
+                if tag == AccessListAddressesLen {
+                    value.map(|value| F::from(!value.is_zero_vartime() as u64))
+                } else {
+                    Value::known(F::zero())
+                }
+            });
+            // lookup to the authorization rows for non-empty authorization list
+            conditions.insert(LookupCondition::Authorization, {
+                if tag == AuthorizationListLen {
+                    value.map(|value| F::from(!value.is_zero_vartime() as u64))
+                } else {
+                    Value::known(F::zero())
+                }
+            });
+            // the last used blob slot is assigned by the caller, which knows the index of
+            // the blob rows
+            if tag != BlobVersionedHash {
+                conditions.insert(LookupCondition::LastBlob, Value::known(F::zero()));
+            }
             // lookup to Keccak table for tx_sign_hash and tx_hash
             conditions.insert(LookupCondition::Keccak, {
                 let case1 = (tag == TxSignLength) && !is_l1_msg;
@@ -1862,10 +3975,52 @@ impl<F: Field> TxCircuitConfig<F> {
 
         // assign to columns which are used to reduce degree
         region.assign_advice(
-            || "is_l1_msg",
-            self.is_l1_msg,
+            || "is_l1_msg",
+            self.is_l1_msg,
+            *offset,
+            || Value::known(F::from(is_l1_msg as u64)),
+        )?;
+        region.assign_advice(
+            || "is_eip2930",
+            self.is_eip2930,
+            *offset,
+            || Value::known(F::from(is_eip2930 as u64)),
+        )?;
+        region.assign_advice(
+            || "is_eip1559",
+            self.is_eip1559,
+            *offset,
+            || Value::known(F::from(is_eip1559 as u64)),
+        )?;
+        region.assign_advice(
+            || "is_eip4844",
+            self.is_eip4844,
             *offset,
-            || Value::known(F::from(is_l1_msg as u64)),
+            || Value::known(F::from(is_eip4844 as u64)),
+        )?;
+        region.assign_advice(
+            || "is_eip7702",
+            self.is_eip7702,
+            *offset,
+            || Value::known(F::from(is_eip7702 as u64)),
+        )?;
+        region.assign_advice(
+            || "is_blob_hash",
+            self.is_blob_hash,
+            *offset,
+            || Value::known(F::from((tag == BlobVersionedHash) as u64)),
+        )?;
+        region.assign_advice(
+            || "is_access_list_len",
+            self.is_access_list_len,
+            *offset,
+            || Value::known(F::from((tag == AccessListAddressesLen) as u64)),
+        )?;
+        region.assign_advice(
+            || "is_authorization_list_len",
+            self.is_authorization_list_len,
+            *offset,
+            || Value::known(F::from((tag == AuthorizationListLen) as u64)),
         )?;
         region.assign_advice(
             || "is_tag_block_num",
@@ -2136,6 +4291,23 @@ impl<F: Field> TxCircuit<F> {
         let sign_verify_inputs = keccak_inputs_sign_verify(&sign_datas);
         inputs.extend_from_slice(&sign_verify_inputs);
 
+        // Keccak inputs of the authorizations of EIP-7702 txs and the delegation codes
+        // that they may set
+        let authorization_sign_datas: Vec<SignData> = self
+            .txs
+            .iter()
+            .flat_map(|tx| tx.authorization_list.iter())
+            .filter_map(|auth| auth.sign_data().ok())
+            .collect();
+        inputs.extend_from_slice(&keccak_inputs_sign_verify(&authorization_sign_datas));
+        inputs.extend(
+            self.txs
+                .iter()
+                .flat_map(|tx| tx.authorization_list.iter())
+                .filter(|auth| !auth.address.is_zero())
+                .map(|auth| delegation_code(auth.address)),
+        );
+
         Ok(inputs)
     }
 
@@ -2353,6 +4525,27 @@ impl<F: Field> TxCircuit<F> {
                         })
                     };
                     log::debug!("calldata len: {}", tx.call_data.len());
+                    let (access_list_addresses_len, access_list_storage_keys_len) =
+                        tx.access_list_len();
+                    let blob_hashes = tx
+                        .blob_versioned_hash_values(challenges)
+                        .into_iter()
+                        .enumerate()
+                        .map(|(index, value)| {
+                            let hash_be_bytes = tx
+                                .blob_versioned_hashes
+                                .get(index)
+                                .map_or(vec![], |hash| hash.to_fixed_bytes().to_vec());
+                            (
+                                BlobVersionedHash,
+                                Some(Tag::BlobVersionedHash.into()),
+                                Some(false),
+                                rlc_be_bytes(&hash_be_bytes, challenges.keccak_input()),
+                                Some(hash_be_bytes.tag_length()),
+                                value,
+                            )
+                        });
+                    let mut blob_index = 0;
                     for (tag, rlp_tag, is_none, be_bytes_rlc, be_bytes_length, value) in [
                         // need to be in same order as that tx table load function uses
                         (
@@ -2560,14 +4753,95 @@ impl<F: Field> TxCircuit<F> {
                             Value::known(F::from(tx.tx_type as u64)),
                         ),
                         (
-                            BlockNumber,
+                            AccessListAddressesLen,
                             None,
                             None,
                             zero_rlc,
                             None,
-                            Value::known(F::from(tx.block_number)),
+                            Value::known(F::from(access_list_addresses_len as u64)),
                         ),
-                    ] {
+                        (
+                            AccessListStorageKeysLen,
+                            None,
+                            None,
+                            zero_rlc,
+                            None,
+                            Value::known(F::from(access_list_storage_keys_len as u64)),
+                        ),
+                        (
+                            AccessListRwCounter,
+                            None,
+                            None,
+                            zero_rlc,
+                            None,
+                            Value::known(F::from(tx.access_list_rw_counter as u64)),
+                        ),
+                        (
+                            AuthorizationListLen,
+                            None,
+                            None,
+                            zero_rlc,
+                            None,
+                            Value::known(F::from(tx.authorization_list.len() as u64)),
+                        ),
+                        (
+                            AuthorizationListAuthoritiesLen,
+                            None,
+                            None,
+                            zero_rlc,
+                            None,
+                            Value::known(F::from(tx.applied_authorizations.len() as u64)),
+                        ),
+                        (
+                            MaxPriorityFeePerGas,
+                            Some(Tag::MaxPriorityFeePerGas.into()),
+                            Some(tx.max_priority_fee_per_gas.is_zero()),
+                            rlc_be_bytes(
+                                &tx.max_priority_fee_per_gas.to_be_bytes(),
+                                challenges.keccak_input(),
+                            ),
+                            Some(tx.max_priority_fee_per_gas.tag_length()),
+                            challenges.evm_word().map(|challenge| {
+                                rlc(tx.max_priority_fee_per_gas.to_le_bytes(), challenge)
+                            }),
+                        ),
+                        (
+                            MaxFeePerGas,
+                            Some(Tag::MaxFeePerGas.into()),
+                            Some(tx.max_fee_per_gas.is_zero()),
+                            rlc_be_bytes(
+                                &tx.max_fee_per_gas.to_be_bytes(),
+                                challenges.keccak_input(),
+                            ),
+                            Some(tx.max_fee_per_gas.tag_length()),
+                            challenges
+                                .evm_word()
+                                .map(|challenge| rlc(tx.max_fee_per_gas.to_le_bytes(), challenge)),
+                        ),
+                        (
+                            MaxFeePerBlobGas,
+                            Some(Tag::MaxFeePerBlobGas.into()),
+                            Some(tx.max_fee_per_blob_gas.is_zero()),
+                            rlc_be_bytes(
+                                &tx.max_fee_per_blob_gas.to_be_bytes(),
+                                challenges.keccak_input(),
+                            ),
+                            Some(tx.max_fee_per_blob_gas.tag_length()),
+                            challenges.evm_word().map(|challenge| {
+                                rlc(tx.max_fee_per_blob_gas.to_le_bytes(), challenge)
+                            }),
+                        ),
+                    ]
+                    .into_iter()
+                    .chain(blob_hashes)
+                    .chain(iter::once((
+                        BlockNumber,
+                        None,
+                        None,
+                        zero_rlc,
+                        None,
+                        Value::known(F::from(tx.block_number)),
+                    ))) {
                         let (tx_id_next, cur_block_num, next_block_num) = match tag {
                             BlockNumber => {
                                 log::debug!(
@@ -2629,6 +4903,17 @@ impl<F: Field> TxCircuit<F> {
                             offset - 1,
                             || Value::known(sv_address),
                         )?;
+                        if tag == BlobVersionedHash {
+                            let is_last_blob = tx.tx_type.is_eip4844_tx()
+                                && blob_index + 1 == tx.blob_versioned_hashes.len();
+                            region.assign_advice(
+                                || "lookup condition LastBlob",
+                                config.lookup_conditions[&LookupCondition::LastBlob],
+                                offset - 1,
+                                || Value::known(F::from(is_last_blob as u64)),
+                            )?;
+                            blob_index += 1;
+                        }
                     }
                     // set next tx's total_l1_popped_before
                     total_l1_popped_before = total_l1_popped_after;
@@ -2764,6 +5049,583 @@ impl<F: Field> TxCircuit<F> {
     }
 }
 
+impl<F: Field> TxCircuit<F> {
+    fn assign_access_list(
+        &self,
+        config: &TxCircuitConfig<F>,
+        challenges: &crate::util::Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        // (tx_id, access_list_idx, storage_key_idx, address, storage key, rw_counter,
+        // is_warm_prev, format)
+        let mut entries = vec![(0, 0, 0, Address::zero(), None, 0, false, 0)];
+        for (i, tx) in self.txs.iter().enumerate() {
+            let tx_entries = tx.access_list_entries();
+            assert_eq!(tx_entries.len(), tx.access_list_is_warm_prev.len());
+            let (mut access_list_idx, mut storage_key_idx) = (0, 0);
+            for (j, ((address, storage_key), is_warm_prev)) in tx_entries
+                .into_iter()
+                .zip(tx.access_list_is_warm_prev.iter())
+                .enumerate()
+            {
+                if storage_key.is_some() {
+                    storage_key_idx += 1;
+                } else {
+                    access_list_idx += 1;
+                }
+                entries.push((
+                    i + 1,
+                    access_list_idx,
+                    storage_key_idx,
+                    address,
+                    storage_key,
+                    tx.access_list_rw_counter + j,
+                    *is_warm_prev,
+                    usize::from(tx.hash_format()),
+                ));
+            }
+        }
+        // the row after the last enabled one is queried by Rotation::next()
+        let num_rows = self.size - config.minimum_rows;
+        assert!(
+            entries.len() < num_rows,
+            "access list entries {} exceed the capacity {}",
+            entries.len() - 1,
+            num_rows - 2
+        );
+
+        layouter.assign_region(
+            || "tx access list",
+            |mut region| {
+                let tx_id_is_zero_chip =
+                    IsZeroChip::construct(config.access_list_tx_id_is_zero.clone());
+                let tx_id_unchanged_chip =
+                    IsEqualChip::construct(config.access_list_tx_id_unchanged.clone());
+                let padding = (0, 0, 0, Address::zero(), None, 0, false, 0);
+
+                for offset in 0..num_rows {
+                    let (
+                        tx_id,
+                        access_list_idx,
+                        storage_key_idx,
+                        address,
+                        storage_key,
+                        rw_counter,
+                        is_warm_prev,
+                        format,
+                    ) = entries.get(offset).copied().unwrap_or(padding);
+                    let tx_id_next = entries.get(offset + 1).map_or(0, |entry| entry.0);
+
+                    if offset < num_rows - 1 {
+                        region.assign_fixed(
+                            || "q_access_list",
+                            config.q_access_list,
+                            offset,
+                            || Value::known(F::one()),
+                        )?;
+                    }
+                    let storage_key_rlc = challenges.evm_word().map(|challenge| {
+                        rlc(
+                            storage_key
+                                .map_or(Word::zero(), |key| key.to_word())
+                                .to_le_bytes(),
+                            challenge,
+                        )
+                    });
+                    for (name, col, value) in [
+                        ("tx_id", config.access_list_tx_id, F::from(tx_id as u64)),
+                        (
+                            "access_list_idx",
+                            config.access_list_idx,
+                            F::from(access_list_idx as u64),
+                        ),
+                        (
+                            "storage_key_idx",
+                            config.access_list_storage_key_idx,
+                            F::from(storage_key_idx as u64),
+                        ),
+                        (
+                            "is_storage_key",
+                            config.access_list_is_storage_key,
+                            F::from(storage_key.is_some() as u64),
+                        ),
+                        (
+                            "address",
+                            config.access_list_address,
+                            address.to_scalar().unwrap(),
+                        ),
+                        (
+                            "rw_counter",
+                            config.access_list_rw_counter,
+                            F::from(rw_counter as u64),
+                        ),
+                        (
+                            "is_warm_prev",
+                            config.access_list_is_warm_prev,
+                            F::from(is_warm_prev as u64),
+                        ),
+                        ("format", config.access_list_format, F::from(format as u64)),
+                    ] {
+                        region.assign_advice(
+                            || format!("access list {name}"),
+                            col,
+                            offset,
+                            || Value::known(value),
+                        )?;
+                    }
+                    region.assign_advice(
+                        || "access list storage_key",
+                        config.access_list_storage_key,
+                        offset,
+                        || storage_key_rlc,
+                    )?;
+
+                    tx_id_is_zero_chip.assign(
+                        &mut region,
+                        offset,
+                        Value::known(F::from(tx_id as u64)),
+                    )?;
+                    tx_id_unchanged_chip.assign(
+                        &mut region,
+                        offset,
+                        Value::known(F::from(tx_id as u64)),
+                        Value::known(F::from(tx_id_next as u64)),
+                    )?;
+                }
+
+                Ok(())
+            },
+        )
+    }
+
+    fn assign_authorizations(
+        &self,
+        config: &TxCircuitConfig<F>,
+        challenges: &crate::util::Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        // (tx_id, auth_idx, authorization, applied authorization, rw_counter,
+        // num_recovered, chain_id of the tx)
+        let mut entries = vec![None];
+        for (i, tx) in self.txs.iter().enumerate() {
+            // BeginTx applies the recovered authorizations in order after the access list
+            let mut applied_authorizations = tx.applied_authorizations.iter();
+            let mut rw_counter = tx.access_list_rw_counter + tx.access_list_entries().len();
+            let mut num_recovered = 0;
+            for (j, auth) in tx.authorization_list.iter().enumerate() {
+                let applied = if auth.authority().is_ok() {
+                    applied_authorizations.next()
+                } else {
+                    None
+                };
+                num_recovered += applied.is_some() as usize;
+                entries.push(Some((
+                    i + 1,
+                    j + 1,
+                    auth,
+                    applied,
+                    rw_counter,
+                    num_recovered,
+                    tx.chain_id,
+                )));
+                if applied.is_some() {
+                    rw_counter += SET_CODE_RW_DELTA;
+                }
+            }
+            assert!(
+                applied_authorizations.next().is_none(),
+                "tx {} has more applied authorizations than recoverable ones",
+                i + 1
+            );
+        }
+        // the row after the last enabled one is queried by Rotation::next()
+        let num_rows = self.size - config.minimum_rows;
+        assert!(
+            entries.len() < num_rows,
+            "authorizations {} exceed the capacity {}",
+            entries.len() - 1,
+            num_rows - 2
+        );
+
+        let code_hash_value = |code_hash: Word| {
+            if cfg!(feature = "poseidon-codehash") {
+                Value::known(code_hash.to_scalar().unwrap())
+            } else {
+                challenges
+                    .evm_word()
+                    .map(|challenge| rlc(code_hash.to_le_bytes(), challenge))
+            }
+        };
+        let word_rlc = |word: Word| {
+            challenges
+                .evm_word()
+                .map(|challenge| rlc(word.to_le_bytes(), challenge))
+        };
+        let pow_of_rand = |n: usize| {
+            challenges
+                .keccak_input()
+                .map(|r| r.pow(&[n as u64, 0, 0, 0]))
+        };
+        let empty_code_hash = code_hash_value(CodeDB::empty_code_hash().to_word());
+
+        layouter.assign_region(
+            || "tx authorizations",
+            |mut region| {
+                let tx_id_is_zero_chip = IsZeroChip::construct(config.auth_tx_id_is_zero.clone());
+                let chain_id_is_zero_chip =
+                    IsZeroChip::construct(config.auth_chain_id_is_zero.clone());
+                let address_is_zero_chip =
+                    IsZeroChip::construct(config.auth_address_is_zero.clone());
+                let code_hash_prev_is_zero_chip =
+                    IsZeroChip::construct(config.auth_code_hash_prev_is_zero.clone());
+                let chain_id_is_tx_chain_id_chip =
+                    IsZeroGadgetChip::construct(config.auth_chain_id_is_tx_chain_id.clone());
+                let code_hash_prev_is_empty_chip =
+                    IsZeroGadgetChip::construct(config.auth_code_hash_prev_is_empty.clone());
+
+                for offset in 0..num_rows {
+                    let entry = entries.get(offset).copied().flatten();
+                    let tx_id = entry.map_or(0, |entry| entry.0);
+                    let tx_id_next = entries
+                        .get(offset + 1)
+                        .copied()
+                        .flatten()
+                        .map_or(0, |entry| entry.0);
+
+                    if offset < num_rows - 1 {
+                        region.assign_fixed(
+                            || "q_authorization",
+                            config.q_authorization,
+                            offset,
+                            || Value::known(F::one()),
+                        )?;
+                    }
+
+                    let default_auth = SetCodeAuthorization::default();
+                    let default_applied = AppliedAuthorization::default();
+                    let (auth_idx, auth, applied, rw_counter, num_recovered, tx_chain_id) = entry
+                        .map_or(
+                            (0, &default_auth, None, 0, 0, 0),
+                            |(_, auth_idx, auth, applied, rw_counter, num_recovered, chain_id)| {
+                                (auth_idx, auth, applied, rw_counter, num_recovered, chain_id)
+                            },
+                        );
+                    let is_recovered = applied.is_some();
+                    let applied = applied.unwrap_or(&default_applied);
+
+                    // the big-endian bytes of the RLP encoded integers
+                    let chain_id_len = auth.chain_id.tag_length() as usize;
+                    let chain_id_bytes = &auth.chain_id.to_be_bytes()[32 - chain_id_len..];
+                    let tx_chain_id_bytes =
+                        &tx_chain_id.to_be_bytes()[8 - tx_chain_id.tag_length() as usize..];
+                    let nonce = auth.nonce.as_u64();
+                    let nonce_len = nonce.tag_length() as usize;
+                    let nonce_bytes = &nonce.to_be_bytes()[8 - nonce_len..];
+                    let is_short = |bytes: &[u8]| bytes.len() == 1 && bytes[0] < 0x80;
+                    let chain_id_is_short = is_short(chain_id_bytes);
+                    let nonce_is_short = is_short(nonce_bytes);
+                    let enc_len = |len: usize, is_short: bool| 1 + (!is_short as usize) * len;
+                    let (msg, payload_len) = if entry.is_some() {
+                        (
+                            auth.sig_msg(),
+                            enc_len(chain_id_len, chain_id_is_short)
+                                + N_BYTES_ACCOUNT_ADDRESS
+                                + 1
+                                + enc_len(nonce_len, nonce_is_short),
+                        )
+                    } else {
+                        (vec![], 0)
+                    };
+                    let msg_hash_rlc = if entry.is_some() {
+                        word_rlc(keccak(&msg))
+                    } else {
+                        Value::known(F::zero())
+                    };
+                    let is_valid_sig = (auth.chain_id.is_zero()
+                        || auth.chain_id == tx_chain_id.into())
+                        && nonce < u64::MAX
+                        && auth.has_low_s();
+
+                    let code_length_prev = applied.code_size.0.as_u64();
+                    let (code_hash_prev, code_hash) = applied.code_hash;
+                    let has_code_prev = !code_hash_prev.is_zero()
+                        && code_hash_prev != CodeDB::empty_code_hash().to_word();
+                    let is_code_ok = !has_code_prev
+                        || (code_length_prev == DELEGATION_CODE_SIZE as u64
+                            && applied.code_prefix_prev == DELEGATION_PREFIX);
+
+                    for (name, col, value) in [
+                        ("tx_id", config.auth_tx_id, F::from(tx_id as u64)),
+                        ("auth_idx", config.auth_idx, F::from(auth_idx as u64)),
+                        (
+                            "chain_id_len",
+                            config.auth_chain_id_len,
+                            F::from(chain_id_len as u64),
+                        ),
+                        (
+                            "address",
+                            config.auth_address,
+                            auth.address.to_scalar().unwrap(),
+                        ),
+                        ("nonce", config.auth_nonce, F::from(nonce)),
+                        (
+                            "nonce_len",
+                            config.auth_nonce_len,
+                            F::from(nonce_len as u64),
+                        ),
+                        (
+                            "y_parity",
+                            config.auth_y_parity,
+                            F::from(auth.y_parity.as_u64()),
+                        ),
+                        (
+                            "chain_id_is_short",
+                            config.auth_chain_id_is_short,
+                            F::from(chain_id_is_short as u64),
+                        ),
+                        (
+                            "nonce_is_short",
+                            config.auth_nonce_is_short,
+                            F::from(nonce_is_short as u64),
+                        ),
+                        (
+                            "is_long_list",
+                            config.auth_is_long_list,
+                            F::from((payload_len > 55) as u64),
+                        ),
+                        ("msg_len", config.auth_msg_len, F::from(msg.len() as u64)),
+                        (
+                            "is_recovered",
+                            config.auth_is_recovered,
+                            F::from(is_recovered as u64),
+                        ),
+                        (
+                            "authority",
+                            config.auth_authority,
+                            applied.authority.to_scalar().unwrap(),
+                        ),
+                        (
+                            "rw_counter",
+                            config.auth_rw_counter,
+                            F::from(rw_counter as u64),
+                        ),
+                        (
+                            "num_recovered",
+                            config.auth_num_recovered,
+                            F::from(num_recovered as u64),
+                        ),
+                        (
+                            "is_low_s",
+                            config.auth_is_low_s,
+                            F::from((entry.is_some() && auth.has_low_s()) as u64),
+                        ),
+                        (
+                            "is_valid_sig",
+                            config.auth_is_valid_sig,
+                            F::from((entry.is_some() && is_valid_sig) as u64),
+                        ),
+                        (
+                            "has_code_prev",
+                            config.auth_has_code_prev,
+                            F::from(has_code_prev as u64),
+                        ),
+                        (
+                            "code_length_prev",
+                            config.auth_code_length_prev,
+                            F::from(code_length_prev),
+                        ),
+                        (
+                            "is_code_ok",
+                            config.auth_is_code_ok,
+                            F::from(is_code_ok as u64),
+                        ),
+                        (
+                            "is_valid",
+                            config.auth_is_valid,
+                            F::from(applied.is_valid as u64),
+                        ),
+                        (
+                            "is_warm_prev",
+                            config.auth_is_warm_prev,
+                            F::from(applied.is_warm_prev as u64),
+                        ),
+                        (
+                            "nonce_prev",
+                            config.auth_nonce_prev,
+                            F::from(applied.nonce_prev),
+                        ),
+                        (
+                            "code_size_prev",
+                            config.auth_code_size_prev,
+                            F::from(code_length_prev),
+                        ),
+                        (
+                            "code_size",
+                            config.auth_code_size,
+                            F::from(applied.code_size.1.as_u64()),
+                        ),
+                        (
+                            "refund_prev",
+                            config.auth_refund_prev,
+                            F::from(applied.refund.0),
+                        ),
+                    ] {
+                        region.assign_advice(
+                            || format!("authorization {name}"),
+                            col,
+                            offset,
+                            || Value::known(value),
+                        )?;
+                    }
+                    for (col, byte) in config
+                        .auth_prefix_prev
+                        .iter()
+                        .zip(applied.code_prefix_prev.iter())
+                    {
+                        region.assign_advice(
+                            || "authorization prefix_prev",
+                            *col,
+                            offset,
+                            || Value::known(F::from(*byte as u64)),
+                        )?;
+                    }
+
+                    let chain_id_rlc = rlc_be_bytes(chain_id_bytes, challenges.keccak_input());
+                    let tx_chain_id_rlc =
+                        rlc_be_bytes(tx_chain_id_bytes, challenges.keccak_input());
+                    let code_hash_prev_value = code_hash_value(code_hash_prev);
+                    for (name, col, value) in [
+                        ("chain_id_rlc", config.auth_chain_id_rlc, chain_id_rlc),
+                        (
+                            "tx_chain_id_rlc",
+                            config.auth_tx_chain_id_rlc,
+                            tx_chain_id_rlc,
+                        ),
+                        (
+                            "address_rlc",
+                            config.auth_address_rlc,
+                            rlc_be_bytes(auth.address.as_bytes(), challenges.keccak_input()),
+                        ),
+                        (
+                            "nonce_rlc",
+                            config.auth_nonce_rlc,
+                            rlc_be_bytes(nonce_bytes, challenges.keccak_input()),
+                        ),
+                        ("r", config.auth_r, word_rlc(auth.r)),
+                        ("s", config.auth_s, word_rlc(auth.s)),
+                        (
+                            "pow_chain_id_len",
+                            config.auth_pow_chain_id_len,
+                            pow_of_rand(chain_id_len),
+                        ),
+                        (
+                            "pow_nonce_len",
+                            config.auth_pow_nonce_len,
+                            pow_of_rand(nonce_len),
+                        ),
+                        (
+                            "pow_payload_len",
+                            config.auth_pow_payload_len,
+                            pow_of_rand(payload_len),
+                        ),
+                        (
+                            "msg_rlc",
+                            config.auth_msg_rlc,
+                            rlc_be_bytes(&msg, challenges.keccak_input()),
+                        ),
+                        ("msg_hash_rlc", config.auth_msg_hash_rlc, msg_hash_rlc),
+                        (
+                            "code_hash_prev",
+                            config.auth_code_hash_prev,
+                            code_hash_prev_value,
+                        ),
+                        (
+                            "code_hash",
+                            config.auth_code_hash,
+                            code_hash_value(code_hash),
+                        ),
+                        (
+                            "keccak_code_hash_prev",
+                            config.auth_keccak_code_hash_prev,
+                            word_rlc(applied.keccak_code_hash.0),
+                        ),
+                        (
+                            "keccak_code_hash",
+                            config.auth_keccak_code_hash,
+                            word_rlc(applied.keccak_code_hash.1),
+                        ),
+                    ] {
+                        region.assign_advice(
+                            || format!("authorization {name}"),
+                            col,
+                            offset,
+                            || value,
+                        )?;
+                    }
+
+                    tx_id_is_zero_chip.assign(
+                        &mut region,
+                        offset,
+                        Value::known(F::from(tx_id as u64)),
+                    )?;
+                    IsEqualChip::construct(config.auth_tx_id_unchanged.clone()).assign(
+                        &mut region,
+                        offset,
+                        Value::known(F::from(tx_id as u64)),
+                        Value::known(F::from(tx_id_next as u64)),
+                    )?;
+                    for (chip_config, lhs, rhs) in [
+                        (&config.auth_chain_id_len_is_one, chain_id_len as u64, 1),
+                        (&config.auth_nonce_len_is_one, nonce_len as u64, 1),
+                        (&config.auth_nonce_is_max, nonce, u64::MAX),
+                        (&config.auth_nonce_is_nonce_prev, nonce, applied.nonce_prev),
+                        (
+                            &config.auth_code_length_prev_is_designator,
+                            code_length_prev,
+                            DELEGATION_CODE_SIZE as u64,
+                        ),
+                        (
+                            &config.auth_prefix_prev_is_designator,
+                            prefix_value(&applied.code_prefix_prev),
+                            prefix_value(&DELEGATION_PREFIX),
+                        ),
+                    ] {
+                        IsEqualChip::construct(chip_config.clone()).assign(
+                            &mut region,
+                            offset,
+                            Value::known(F::from(lhs)),
+                            Value::known(F::from(rhs)),
+                        )?;
+                    }
+                    chain_id_is_zero_chip.assign(&mut region, offset, chain_id_rlc)?;
+                    chain_id_is_tx_chain_id_chip.assign(
+                        &mut region,
+                        offset,
+                        chain_id_rlc - tx_chain_id_rlc,
+                    )?;
+                    address_is_zero_chip.assign(
+                        &mut region,
+                        offset,
+                        Value::known(auth.address.to_scalar().unwrap()),
+                    )?;
+                    code_hash_prev_is_zero_chip.assign(
+                        &mut region,
+                        offset,
+                        code_hash_prev_value,
+                    )?;
+                    code_hash_prev_is_empty_chip.assign(
+                        &mut region,
+                        offset,
+                        code_hash_prev_value - empty_code_hash,
+                    )?;
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
 impl<F: Field> SubCircuit<F> for TxCircuit<F> {
     type Config = TxCircuitConfig<F>;
 
@@ -2869,6 +5731,8 @@ impl<F: Field> SubCircuit<F> for TxCircuit<F> {
             sign_datas,
             &padding_txs,
         )?;
+        self.assign_access_list(config, challenges, layouter)?;
+        self.assign_authorizations(config, challenges, layouter)?;
 
         Ok(())
     }
@@ -2903,5 +5767,21 @@ pub(crate) fn get_sign_data(
             }
         })
         .collect::<Result<Vec<SignData>, halo2_proofs::plonk::Error>>()?;
+    // the authorities of EIP-7702 txs are recovered from their authorization tuples
+    let signatures = signatures
+        .into_iter()
+        .chain(
+            txs.iter()
+                .flat_map(|tx| tx.authorization_list.iter())
+                .filter_map(|auth| auth.sign_data().ok()),
+        )
+        .collect();
     Ok(signatures)
 }
+
+// Big endian value of the prefix of a code
+fn prefix_value(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0, |value, byte| (value << 8) + *byte as u64)
+}
@@ -5,15 +5,18 @@ use super::get_sign_data;
 pub use super::TxCircuit;
 
 use crate::{
+    evm_circuit::param::SET_CODE_RW_DELTA,
     sig_circuit::{SigCircuit, SigCircuitConfig, SigCircuitConfigArgs},
     table::{
-        BlockTable, KeccakTable, RlpFsmRlpTable as RlpTable, SigTable, TxTable, U16Table, U8Table,
+        AccountFieldTag, BlockTable, BytecodeTable, KeccakTable, PoseidonTable, PowOfRandTable,
+        RlpFsmRlpTable as RlpTable, RwTable, SigTable, TxTable, U16Table, U8Table,
     },
     tx_circuit::{TxCircuitConfig, TxCircuitConfigArgs},
     util::{Challenges, SubCircuit, SubCircuitConfig},
-    witness::Transaction,
+    witness::{Bytecode, Rw, Transaction},
 };
-use eth_types::Field;
+use bus_mapping::state_db::CodeDB;
+use eth_types::{evm_types::delegation_code, Field, ToWord};
 use halo2_proofs::{
     circuit::{Layouter, SimpleFloorPlanner},
     plonk::{Circuit, ConstraintSystem, Error, Expression},
@@ -31,6 +34,14 @@ pub struct TxCircuitTesterConfigArgs<F: Field> {
     pub keccak_table: KeccakTable,
     /// SigTable
     pub sig_table: SigTable,
+    /// RwTable
+    pub rw_table: RwTable,
+    /// PowOfRandTable
+    pub pow_of_rand_table: PowOfRandTable,
+    /// PoseidonTable
+    pub poseidon_table: PoseidonTable,
+    /// BytecodeTable
+    pub bytecode_table: BytecodeTable,
     /// u8 lookup table,
     pub u8_table: U8Table,
     /// u16 lookup table,
@@ -45,6 +56,7 @@ pub struct TxCircuitTesterConfig<F: Field> {
     tx_config: TxCircuitConfig<F>,
     // SigTable is assigned inside SigCircuit
     sig_config: SigCircuitConfig<F>,
+    rw_table: RwTable,
     /// u16 lookup table,
     pub u8_table: U8Table,
     /// u16 lookup table,
@@ -62,6 +74,10 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitTesterConfig<F> {
             keccak_table,
             rlp_table,
             sig_table,
+            rw_table,
+            pow_of_rand_table,
+            poseidon_table,
+            bytecode_table,
             u8_table,
             u16_table,
             challenges,
@@ -83,6 +99,10 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitTesterConfig<F> {
                 tx_table,
                 keccak_table,
                 rlp_table,
+                rw_table,
+                pow_of_rand_table,
+                poseidon_table,
+                bytecode_table,
                 u8_table,
                 u16_table,
                 challenges,
@@ -91,6 +111,7 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitTesterConfig<F> {
         TxCircuitTesterConfig {
             tx_config,
             sig_config,
+            rw_table,
             u8_table,
             u16_table,
         }
@@ -113,10 +134,12 @@ impl<F: Field> TxCircuitTester<F> {
         start_l1_queue_index: u64,
         txs: Vec<Transaction>,
     ) -> Self {
+        // the sig circuit also recovers the authorities of EIP-7702 txs
+        let signatures = get_sign_data(&txs, max_txs, chain_id as usize).unwrap();
         TxCircuitTester::<F> {
             sig_circuit: SigCircuit {
-                max_verif: max_txs,
-                signatures: get_sign_data(&txs, max_txs, chain_id as usize).unwrap(),
+                max_verif: signatures.len().max(max_txs),
+                signatures,
                 _marker: PhantomData,
             },
             tx_circuit: TxCircuit::new(max_txs, max_calldata, chain_id, start_l1_queue_index, txs),
@@ -166,12 +189,16 @@ impl<F: Field> Circuit<F> for TxCircuitTester<F> {
         let keccak_table = KeccakTable::construct(meta);
         let rlp_table = RlpTable::construct(meta);
         let sig_table = SigTable::construct(meta);
+        let rw_table = RwTable::construct(meta);
+        let poseidon_table = PoseidonTable::construct(meta);
+        let bytecode_table = BytecodeTable::construct(meta);
         let u8_table = U8Table::construct(meta);
         let u16_table = U16Table::construct(meta);
         let challenges = Challenges::construct(meta);
 
         let config = {
             let challenges = challenges.exprs(meta);
+            let pow_of_rand_table = PowOfRandTable::construct(meta, &challenges);
             let sig_config = SigCircuitConfig::new(
                 meta,
                 SigCircuitConfigArgs {
@@ -188,6 +215,10 @@ impl<F: Field> Circuit<F> for TxCircuitTester<F> {
                     tx_table,
                     keccak_table,
                     rlp_table,
+                    rw_table,
+                    pow_of_rand_table,
+                    poseidon_table,
+                    bytecode_table,
                     u8_table,
                     u16_table,
                     challenges,
@@ -196,6 +227,7 @@ impl<F: Field> Circuit<F> for TxCircuitTester<F> {
             TxCircuitTesterConfig {
                 tx_config,
                 sig_config,
+                rw_table,
                 u8_table,
                 u16_table,
             }
@@ -236,6 +268,33 @@ impl<F: Field> Circuit<F> for TxCircuitTester<F> {
             self.tx_circuit.chain_id,
             &challenges,
         )?;
+        config
+            .tx_config
+            .pow_of_rand_table
+            .assign(&mut layouter, &challenges)?;
+        // the delegation codes that the authorizations may set or replace
+        let delegation_codes = self
+            .tx_circuit
+            .txs
+            .iter()
+            .flat_map(|tx| tx.authorization_list.iter())
+            .map(|auth| delegation_code(auth.address))
+            .collect::<Vec<_>>();
+        config
+            .tx_config
+            .poseidon_table
+            .dev_load(&mut layouter, &delegation_codes)?;
+        let bytecodes = delegation_codes
+            .iter()
+            .map(|code| Bytecode {
+                hash: CodeDB::hash(code).to_word(),
+                bytes: code.clone(),
+            })
+            .collect::<Vec<_>>();
+        config
+            .tx_config
+            .bytecode_table
+            .dev_load(&mut layouter, &bytecodes, &challenges)?;
         config.tx_config.rlp_table.dev_load(
             &mut layouter,
             self.tx_circuit
@@ -246,6 +305,98 @@ impl<F: Field> Circuit<F> for TxCircuitTester<F> {
                 .collect(),
             &challenges,
         )?;
+        // the access list writes that BeginTx would do
+        let rws = self
+            .tx_circuit
+            .txs
+            .iter()
+            .flat_map(|tx| {
+                tx.access_list_entries()
+                    .into_iter()
+                    .zip(tx.access_list_is_warm_prev.iter())
+                    .enumerate()
+                    .map(|(i, ((address, storage_key), is_warm_prev))| {
+                        let rw_counter = tx.access_list_rw_counter + i;
+                        match storage_key {
+                            Some(key) => Rw::TxAccessListAccountStorage {
+                                rw_counter,
+                                is_write: true,
+                                tx_id: tx.id,
+                                account_address: address,
+                                storage_key: key.to_word(),
+                                is_warm: true,
+                                is_warm_prev: *is_warm_prev,
+                            },
+                            None => Rw::TxAccessListAccount {
+                                rw_counter,
+                                is_write: true,
+                                tx_id: tx.id,
+                                account_address: address,
+                                is_warm: true,
+                                is_warm_prev: *is_warm_prev,
+                            },
+                        }
+                    })
+            })
+            .collect::<Vec<_>>();
+        // the authorization writes that BeginTx would do after the access list
+        let mut rws = rws;
+        for tx in self.tx_circuit.txs.iter() {
+            let mut rw_counter = tx.access_list_rw_counter + tx.access_list_entries().len();
+            let recovered = tx
+                .authorization_list
+                .iter()
+                .filter(|auth| auth.authority().is_ok());
+            for (auth, applied) in recovered.zip(tx.applied_authorizations.iter()) {
+                let is_valid_sig = (auth.chain_id.is_zero() || auth.chain_id == tx.chain_id.into())
+                    && auth.nonce.as_u64() < u64::MAX
+                    && auth.has_low_s();
+                rws.push(Rw::TxAccessListAccount {
+                    rw_counter,
+                    is_write: true,
+                    tx_id: tx.id,
+                    account_address: applied.authority,
+                    is_warm: applied.is_warm_prev || is_valid_sig,
+                    is_warm_prev: applied.is_warm_prev,
+                });
+                #[allow(unused_mut)]
+                let mut account_writes = vec![(AccountFieldTag::CodeHash, applied.code_hash)];
+                #[cfg(feature = "scroll")]
+                account_writes.extend([
+                    (AccountFieldTag::KeccakCodeHash, applied.keccak_code_hash),
+                    (AccountFieldTag::CodeSize, applied.code_size),
+                ]);
+                account_writes.push((
+                    AccountFieldTag::Nonce,
+                    (
+                        applied.nonce_prev.into(),
+                        (applied.nonce_prev + applied.is_valid as u64).into(),
+                    ),
+                ));
+                for (i, (field_tag, (value_prev, value))) in account_writes.into_iter().enumerate()
+                {
+                    rws.push(Rw::Account {
+                        rw_counter: rw_counter + i + 1,
+                        is_write: true,
+                        account_address: applied.authority,
+                        field_tag,
+                        value,
+                        value_prev,
+                    });
+                }
+                rws.push(Rw::TxRefund {
+                    rw_counter: rw_counter + SET_CODE_RW_DELTA - 1,
+                    is_write: true,
+                    tx_id: tx.id,
+                    value: applied.refund.1,
+                    value_prev: applied.refund.0,
+                });
+                rw_counter += SET_CODE_RW_DELTA;
+            }
+        }
+        config
+            .rw_table
+            .load(&mut layouter, &rws, rws.len() + 1, challenges.evm_word())?;
 
         self.tx_circuit
             .assign_dev_block_table(config.tx_config.clone(), &mut layouter)?;
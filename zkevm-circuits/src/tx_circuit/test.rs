@@ -1,17 +1,25 @@
 #![allow(unused_imports)]
 
 use ethers_core::{
-    types::{NameOrAddress, Signature, Transaction as EthTransaction, TransactionRequest},
+    types::{
+        transaction::eip2930::{AccessList, AccessListItem},
+        NameOrAddress, Signature, Transaction as EthTransaction, TransactionRequest,
+    },
     utils::{keccak256, rlp, rlp::Decodable},
 };
 use std::cmp::max;
 
 use super::*;
 use crate::{
-    tx_circuit::{dev::TxCircuitTester, get_sign_data},
+    tx_circuit::dev::TxCircuitTester,
     util::{log2_ceil, unusable_rows},
 };
-use eth_types::{address, evm_types::gas_utils::tx_data_gas_cost, word, H256, U256, U64};
+use eth_types::{
+    address,
+    evm_types::gas_utils::tx_data_gas_cost,
+    geth_types::{get_rlp_signed, get_rlp_unsigned},
+    word, H256, U256, U64,
+};
 use halo2_proofs::{
     dev::{MockProver, VerifyFailure},
     halo2curves::bn256::Fr,
@@ -108,6 +116,219 @@ fn build_l1_msg_tx() -> Transaction {
     tx
 }
 
+#[cfg(test)]
+fn build_eip2930_tx(access_list: AccessList) -> Transaction {
+    let mock_tx = MockTransaction::default()
+        .transaction_type(EIP2930_TX_TYPE as u64)
+        .from(mock::MOCK_WALLETS[0].clone())
+        .to(mock::MOCK_ACCOUNTS[0])
+        .nonce(word!("0x1"))
+        .value(word!("0x3e8"))
+        .input(vec![1, 2, 3].into())
+        .gas_price(word!("0x4d2"))
+        .access_list(access_list)
+        .build();
+    let eth_tx = eth_types::Transaction::from(mock_tx);
+
+    let mut tx = Transaction::new_from_rlp_bytes(
+        Eip2930,
+        get_rlp_signed(&eth_tx),
+        get_rlp_unsigned(&eth_tx),
+    );
+
+    tx.hash = eth_tx.hash;
+    tx.block_number = 1;
+    tx.id = 1;
+    tx.chain_id = eth_tx.chain_id.unwrap().as_u64();
+    tx.nonce = eth_tx.nonce.as_u64();
+    tx.gas_price = eth_tx.gas_price.unwrap();
+    tx.gas = eth_tx.gas.as_u64();
+    tx.call_data = eth_tx.input.to_vec();
+    tx.callee_address = eth_tx.to;
+    tx.caller_address = eth_tx.from;
+    tx.is_create = false;
+    tx.call_data_length = tx.call_data.len();
+    tx.call_data_gas_cost = tx_data_gas_cost(&tx.call_data);
+    tx.tx_data_gas_cost = tx_data_gas_cost(&tx.rlp_signed);
+    tx.v = eth_tx.v.as_u64();
+    tx.r = eth_tx.r;
+    tx.s = eth_tx.s;
+    tx.access_list = eth_tx.access_list;
+    tx.access_list_rw_counter = 20;
+    tx.access_list_is_warm_prev = vec![false; tx.access_list_entries().len()];
+
+    tx
+}
+
+#[cfg(test)]
+fn build_eip1559_tx(access_list: AccessList) -> Transaction {
+    let mock_tx = MockTransaction::default()
+        .transaction_type(EIP1559_TX_TYPE as u64)
+        .from(mock::MOCK_WALLETS[0].clone())
+        .to(mock::MOCK_ACCOUNTS[0])
+        .nonce(word!("0x1"))
+        .value(word!("0x3e8"))
+        .input(vec![1, 2, 3].into())
+        .max_priority_fee_per_gas(word!("0x2"))
+        .max_fee_per_gas(word!("0x4d2"))
+        .access_list(access_list)
+        .build();
+    let eth_tx = eth_types::Transaction::from(mock_tx);
+
+    let mut tx = Transaction::new_from_rlp_bytes(
+        Eip1559,
+        get_rlp_signed(&eth_tx),
+        get_rlp_unsigned(&eth_tx),
+    );
+
+    tx.hash = eth_tx.hash;
+    tx.block_number = 1;
+    tx.id = 1;
+    tx.chain_id = eth_tx.chain_id.unwrap().as_u64();
+    tx.nonce = eth_tx.nonce.as_u64();
+    tx.gas_price = eth_tx.max_fee_per_gas.unwrap();
+    tx.max_priority_fee_per_gas = eth_tx.max_priority_fee_per_gas.unwrap();
+    tx.max_fee_per_gas = eth_tx.max_fee_per_gas.unwrap();
+    tx.gas = eth_tx.gas.as_u64();
+    tx.call_data = eth_tx.input.to_vec();
+    tx.callee_address = eth_tx.to;
+    tx.caller_address = eth_tx.from;
+    tx.is_create = false;
+    tx.call_data_length = tx.call_data.len();
+    tx.call_data_gas_cost = tx_data_gas_cost(&tx.call_data);
+    tx.tx_data_gas_cost = tx_data_gas_cost(&tx.rlp_signed);
+    tx.v = eth_tx.v.as_u64();
+    tx.r = eth_tx.r;
+    tx.s = eth_tx.s;
+    tx.access_list = eth_tx.access_list;
+    tx.access_list_rw_counter = 20;
+    tx.access_list_is_warm_prev = vec![false; tx.access_list_entries().len()];
+
+    tx
+}
+
+#[cfg(test)]
+fn build_eip4844_tx(access_list: AccessList) -> Transaction {
+    let mock_tx = MockTransaction::default()
+        .transaction_type(EIP4844_TX_TYPE as u64)
+        .from(mock::MOCK_WALLETS[0].clone())
+        .to(mock::MOCK_ACCOUNTS[0])
+        .nonce(word!("0x1"))
+        .value(word!("0x3e8"))
+        .input(vec![1, 2, 3].into())
+        .max_priority_fee_per_gas(word!("0x2"))
+        .max_fee_per_gas(word!("0x4d2"))
+        .max_fee_per_blob_gas(word!("0x3"))
+        .blob_versioned_hashes(vec![
+            H256::from_low_u64_be(0x0111),
+            H256::from_low_u64_be(0x0122),
+        ])
+        .access_list(access_list)
+        .build();
+    let eth_tx = eth_types::Transaction::from(mock_tx);
+
+    let mut tx = Transaction::new_from_rlp_bytes(
+        Eip4844,
+        get_rlp_signed(&eth_tx),
+        get_rlp_unsigned(&eth_tx),
+    );
+
+    tx.hash = eth_tx.hash;
+    tx.block_number = 1;
+    tx.id = 1;
+    tx.chain_id = eth_tx.chain_id.unwrap().as_u64();
+    tx.nonce = eth_tx.nonce.as_u64();
+    tx.gas_price = eth_tx.max_fee_per_gas.unwrap();
+    tx.max_priority_fee_per_gas = eth_tx.max_priority_fee_per_gas.unwrap();
+    tx.max_fee_per_gas = eth_tx.max_fee_per_gas.unwrap();
+    tx.max_fee_per_blob_gas = word!("0x3");
+    tx.blob_versioned_hashes = vec![H256::from_low_u64_be(0x0111), H256::from_low_u64_be(0x0122)];
+    tx.gas = eth_tx.gas.as_u64();
+    tx.call_data = eth_tx.input.to_vec();
+    tx.callee_address = eth_tx.to;
+    tx.caller_address = eth_tx.from;
+    tx.is_create = false;
+    tx.call_data_length = tx.call_data.len();
+    tx.call_data_gas_cost = tx_data_gas_cost(&tx.call_data);
+    tx.tx_data_gas_cost = tx_data_gas_cost(&tx.rlp_signed);
+    tx.v = eth_tx.v.as_u64();
+    tx.r = eth_tx.r;
+    tx.s = eth_tx.s;
+    tx.access_list = eth_tx.access_list;
+    tx.access_list_rw_counter = 20;
+    tx.access_list_is_warm_prev = vec![false; tx.access_list_entries().len()];
+
+    tx
+}
+
+#[cfg(test)]
+fn build_eip7702_tx(authorization_list: Vec<SetCodeAuthorization>) -> Transaction {
+    let mock_tx = MockTransaction::default()
+        .transaction_type(EIP7702_TX_TYPE as u64)
+        .from(mock::MOCK_WALLETS[0].clone())
+        .to(mock::MOCK_ACCOUNTS[0])
+        .nonce(word!("0x1"))
+        .value(word!("0x3e8"))
+        .input(vec![1, 2, 3].into())
+        .max_priority_fee_per_gas(word!("0x2"))
+        .max_fee_per_gas(word!("0x4d2"))
+        .authorization_list(authorization_list)
+        .build();
+    let eth_tx = eth_types::Transaction::from(mock_tx.clone());
+
+    let mut tx = Transaction::new_from_rlp_bytes(
+        Eip7702,
+        get_rlp_signed(&eth_tx),
+        get_rlp_unsigned(&eth_tx),
+    );
+
+    tx.hash = eth_tx.hash;
+    tx.block_number = 1;
+    tx.id = 1;
+    tx.chain_id = eth_tx.chain_id.unwrap().as_u64();
+    tx.nonce = eth_tx.nonce.as_u64();
+    tx.gas_price = eth_tx.max_fee_per_gas.unwrap();
+    tx.max_priority_fee_per_gas = eth_tx.max_priority_fee_per_gas.unwrap();
+    tx.max_fee_per_gas = eth_tx.max_fee_per_gas.unwrap();
+    tx.gas = eth_tx.gas.as_u64();
+    tx.call_data = eth_tx.input.to_vec();
+    tx.callee_address = eth_tx.to;
+    tx.caller_address = eth_tx.from;
+    tx.is_create = false;
+    tx.call_data_length = tx.call_data.len();
+    tx.call_data_gas_cost = tx_data_gas_cost(&tx.call_data);
+    tx.tx_data_gas_cost = tx_data_gas_cost(&tx.rlp_signed);
+    tx.v = eth_tx.v.as_u64();
+    tx.r = eth_tx.r;
+    tx.s = eth_tx.s;
+    tx.access_list_rw_counter = 20;
+    // The authorities don't exist before the tx, and are delegated by their
+    // authorization of nonce 0.
+    tx.applied_authorizations = mock_tx
+        .authorization_list
+        .iter()
+        .filter_map(|auth| Some((auth.authority().ok()?, auth)))
+        .map(|(authority, auth)| {
+            assert!(auth.nonce.is_zero());
+            let code = delegation_code(auth.address);
+            AppliedAuthorization {
+                authority,
+                is_warm_prev: false,
+                is_valid: true,
+                nonce_prev: 0,
+                code_hash: (Word::zero(), CodeDB::hash(&code).to_word()),
+                keccak_code_hash: (Word::zero(), Word::from_big_endian(&keccak256(&code))),
+                code_size: (Word::zero(), code.len().into()),
+                code_prefix_prev: [0; DELEGATION_PREFIX.len()],
+                refund: (0, 0),
+            }
+        })
+        .collect();
+    tx.authorization_list = mock_tx.authorization_list;
+
+    tx
+}
+
 fn run<F: Field>(
     txs: Vec<Transaction>,
     chain_id: u64,
@@ -118,14 +339,8 @@ fn run<F: Field>(
     let active_row_num = TxCircuit::<F>::min_num_rows(max_txs, max_calldata);
 
     let k = max(20, log2_ceil(active_row_num));
-    let circuit = TxCircuitTester::<F> {
-        sig_circuit: SigCircuit {
-            max_verif: max_txs,
-            signatures: get_sign_data(&txs, max_txs, chain_id as usize).unwrap(),
-            _marker: PhantomData,
-        },
-        tx_circuit: TxCircuit::new(max_txs, max_calldata, chain_id, start_l1_queue_index, txs),
-    };
+    let circuit =
+        TxCircuitTester::<F>::new(max_txs, max_calldata, chain_id, start_l1_queue_index, txs);
     let prover = match MockProver::run(k, &circuit, vec![]) {
         Ok(prover) => prover,
         Err(e) => panic!("{e:#?}"),
@@ -218,6 +433,123 @@ fn tx_circuit_l1_msg_tx() {
     );
 }
 
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_eip4844_tx() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let tx = build_eip4844_tx(AccessList::default());
+
+    assert_eq!(
+        run::<Fr>(vec![tx], *mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0),
+        Ok(())
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_eip4844_tx_with_access_list() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let tx = build_eip4844_tx(AccessList(vec![
+        AccessListItem {
+            address: mock::MOCK_ACCOUNTS[1],
+            storage_keys: vec![H256::from_low_u64_be(1), H256::from_low_u64_be(2)],
+        },
+        AccessListItem {
+            address: mock::MOCK_ACCOUNTS[2],
+            storage_keys: vec![],
+        },
+    ]));
+
+    assert_eq!(
+        run::<Fr>(vec![tx], *mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0),
+        Ok(())
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_eip2930_tx_with_access_list() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let tx = build_eip2930_tx(AccessList(vec![
+        AccessListItem {
+            address: mock::MOCK_ACCOUNTS[1],
+            storage_keys: vec![H256::from_low_u64_be(1), H256::from_low_u64_be(2)],
+        },
+        AccessListItem {
+            address: mock::MOCK_ACCOUNTS[2],
+            storage_keys: vec![],
+        },
+    ]));
+
+    assert_eq!(
+        run::<Fr>(vec![tx], *mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0),
+        Ok(())
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_eip1559_tx() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let tx = build_eip1559_tx(AccessList::default());
+
+    assert_eq!(
+        run::<Fr>(vec![tx], *mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0),
+        Ok(())
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_eip1559_tx_with_access_list() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    // the same address and storage key are listed twice, so they are warm the
+    // second time
+    let mut tx = build_eip1559_tx(AccessList(vec![
+        AccessListItem {
+            address: mock::MOCK_ACCOUNTS[1],
+            storage_keys: vec![H256::from_low_u64_be(1)],
+        },
+        AccessListItem {
+            address: mock::MOCK_ACCOUNTS[1],
+            storage_keys: vec![H256::from_low_u64_be(1), H256::from_low_u64_be(3)],
+        },
+    ]));
+    tx.access_list_is_warm_prev = vec![false, false, true, true, false];
+
+    assert_eq!(
+        run::<Fr>(vec![tx], *mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0),
+        Ok(())
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_eip4844_tx_bad_num_of_blobs() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    // The tx table drops a blob of the RLP encoding.
+    let mut tx = build_eip4844_tx(AccessList::default());
+    tx.blob_versioned_hashes.truncate(1);
+    assert!(run::<Fr>(vec![tx], *mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0).is_err());
+
+    // The tx table adds a blob which is not in the RLP encoding.
+    let mut tx = build_eip4844_tx(AccessList::default());
+    tx.blob_versioned_hashes.push(H256::from_low_u64_be(0x0133));
+    assert!(run::<Fr>(vec![tx], *mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0).is_err());
+}
+
 #[test]
 #[cfg(feature = "scroll")]
 fn tx_circuit_bad_address() {
@@ -258,3 +590,74 @@ fn tx_circuit_to_is_zero() {
         Ok(())
     );
 }
+
+#[cfg(test)]
+fn build_authorization_list() -> Vec<SetCodeAuthorization> {
+    mock::MOCK_WALLETS[1..3]
+        .iter()
+        .zip([mock::MOCK_ACCOUNTS[1], mock::MOCK_ACCOUNTS[2]])
+        .map(|(wallet, address)| {
+            SetCodeAuthorization::new_signed(
+                (*mock::MOCK_CHAIN_ID).into(),
+                address,
+                U64::zero(),
+                wallet,
+            )
+        })
+        .collect()
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_eip7702_tx() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let tx = build_eip7702_tx(build_authorization_list());
+
+    assert_eq!(
+        run::<Fr>(vec![tx], *mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0),
+        Ok(())
+    );
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_eip7702_tx_bad_authority() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    // The authority is not the signer of the authorization.
+    let mut tx = build_eip7702_tx(build_authorization_list());
+    tx.applied_authorizations[0].authority = mock::MOCK_ACCOUNTS[3];
+
+    assert!(run::<Fr>(vec![tx], *mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0).is_err());
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_eip7702_tx_bad_nonce() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    // The authorization is applied although the nonce of the authority
+    // doesn't match the one of the authorization.
+    let mut tx = build_eip7702_tx(build_authorization_list());
+    tx.applied_authorizations[0].nonce_prev = 1;
+
+    assert!(run::<Fr>(vec![tx], *mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0).is_err());
+}
+
+#[test]
+#[cfg(feature = "scroll")]
+fn tx_circuit_eip7702_tx_bad_num_of_authorizations() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    // The tx table drops an authorization of the RLP encoding.
+    let mut tx = build_eip7702_tx(build_authorization_list());
+    tx.authorization_list.truncate(1);
+    tx.applied_authorizations.truncate(1);
+
+    assert!(run::<Fr>(vec![tx], *mock::MOCK_CHAIN_ID, MAX_TXS, MAX_CALLDATA, 0).is_err());
+}
@@ -21,6 +21,8 @@ pub use gadgets::util::Expr;
 
 /// A wrapper of is_zero in gadgets which gives is_zero at any rotation
 pub mod is_zero;
+/// Persistence of the proving and verifying keys of the circuits
+pub mod key_store;
 
 pub(crate) fn query_expression<F: FieldExt, T>(
     meta: &mut ConstraintSystem<F>,
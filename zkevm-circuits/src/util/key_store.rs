@@ -0,0 +1,213 @@
+//! Persistence of the proving and verifying keys of a circuit, so that the
+//! keygen only runs once for a circuit configuration.
+//!
+//! A key file starts with the 32 bytes digest of the configuration of the
+//! circuit the key was generated for, followed by the key in
+//! [`SerdeFormat::RawBytes`]. A key is only loaded if its digest matches the
+//! digest of the current circuit: a key of another configuration either fails
+//! to deserialize or, worse, produces proofs that don't verify.
+
+use eth_types::H256;
+use ethers_core::utils::keccak256;
+use halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{self, keygen_pk, keygen_vk, Circuit, ConstraintSystem, ProvingKey, VerifyingKey},
+    poly::{commitment::Params, kzg::commitment::ParamsKZG},
+    SerdeFormat,
+};
+use std::{
+    fmt::{self, Display, Formatter},
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::PathBuf,
+};
+
+/// A circuit whose keys can be persisted in a [`KeyStore`].
+pub trait CircuitDigest: Circuit<Fr> {
+    /// Parameters of the circuit which aren't captured by its constraint
+    /// system but change its keys, such as the capacities of the
+    /// sub-circuits, which size the fixed tables.
+    fn config_bytes(&self) -> Vec<u8>;
+
+    /// Digest of the configuration of the circuit with degree `k`: its
+    /// parameters and its constraint system, which pins the column layout,
+    /// the gates, the lookups and the permutation.
+    fn circuit_digest(&self, k: u32) -> H256 {
+        let mut cs = ConstraintSystem::default();
+        Self::configure(&mut cs);
+
+        let mut bytes = k.to_be_bytes().to_vec();
+        bytes.extend(self.config_bytes());
+        bytes.extend(format!("{:?}", cs.pinned()).as_bytes());
+        H256(keccak256(bytes))
+    }
+}
+
+/// Error while writing or reading a key.
+#[derive(Debug)]
+pub enum KeyStoreError {
+    /// IO error
+    Io(io::Error),
+    /// Error while generating the keys of the circuit.
+    Keygen(plonk::Error),
+    /// The key was generated for another configuration of the circuit.
+    DigestMismatch {
+        /// Digest of the current circuit
+        expected: H256,
+        /// Digest in the key file
+        found: H256,
+    },
+}
+
+impl Display for KeyStoreError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "io error: {err}"),
+            Self::Keygen(err) => write!(f, "keygen error: {err:?}"),
+            Self::DigestMismatch { expected, found } => write!(
+                f,
+                "key was generated for circuit digest {found:?}, but the circuit digest is \
+                 {expected:?}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for KeyStoreError {}
+
+impl From<io::Error> for KeyStoreError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<plonk::Error> for KeyStoreError {
+    fn from(err: plonk::Error) -> Self {
+        Self::Keygen(err)
+    }
+}
+
+/// Directory of key files. The keys of a circuit named `name` are stored in
+/// `{name}.pk` and `{name}.vk`.
+#[derive(Clone, Debug)]
+pub struct KeyStore {
+    dir: PathBuf,
+}
+
+impl KeyStore {
+    /// Create a key store in the directory `dir`, which is created when the
+    /// first key is written.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, name: &str, extension: &str) -> PathBuf {
+        self.dir.join(format!("{name}.{extension}"))
+    }
+
+    /// Write the proving key of `circuit` with degree `k`.
+    pub fn write_pk<C: CircuitDigest>(
+        &self,
+        name: &str,
+        circuit: &C,
+        k: u32,
+        pk: &ProvingKey<G1Affine>,
+    ) -> Result<(), KeyStoreError> {
+        self.write_key(name, "pk", circuit.circuit_digest(k), |writer| {
+            pk.write(writer, SerdeFormat::RawBytes)
+        })
+    }
+
+    /// Read the proving key of `circuit` with degree `k`, which must have been
+    /// written for the same configuration of the circuit.
+    pub fn read_pk<C: CircuitDigest>(
+        &self,
+        name: &str,
+        circuit: &C,
+        k: u32,
+    ) -> Result<ProvingKey<G1Affine>, KeyStoreError> {
+        self.read_key(name, "pk", circuit.circuit_digest(k), |reader| {
+            ProvingKey::read::<_, C>(reader, SerdeFormat::RawBytes)
+        })
+    }
+
+    /// Write the verifying key of `circuit` with degree `k`.
+    pub fn write_vk<C: CircuitDigest>(
+        &self,
+        name: &str,
+        circuit: &C,
+        k: u32,
+        vk: &VerifyingKey<G1Affine>,
+    ) -> Result<(), KeyStoreError> {
+        self.write_key(name, "vk", circuit.circuit_digest(k), |writer| {
+            vk.write(writer, SerdeFormat::RawBytes)
+        })
+    }
+
+    /// Read the verifying key of `circuit` with degree `k`, which must have
+    /// been written for the same configuration of the circuit.
+    pub fn read_vk<C: CircuitDigest>(
+        &self,
+        name: &str,
+        circuit: &C,
+        k: u32,
+    ) -> Result<VerifyingKey<G1Affine>, KeyStoreError> {
+        self.read_key(name, "vk", circuit.circuit_digest(k), |reader| {
+            VerifyingKey::read::<_, C>(reader, SerdeFormat::RawBytes)
+        })
+    }
+
+    /// Read the proving key of `circuit` if it was written before, otherwise
+    /// generate the keys of the circuit and write them. A key written for
+    /// another configuration of the circuit is an error rather than being
+    /// overwritten.
+    pub fn read_or_gen_pk<C: CircuitDigest>(
+        &self,
+        name: &str,
+        params: &ParamsKZG<Bn256>,
+        circuit: &C,
+    ) -> Result<ProvingKey<G1Affine>, KeyStoreError> {
+        let k = params.k();
+        if self.path(name, "pk").exists() {
+            return self.read_pk(name, circuit, k);
+        }
+
+        log::info!("generating keys of circuit {name} with k = {k}");
+        let vk = keygen_vk(params, circuit)?;
+        self.write_vk(name, circuit, k, &vk)?;
+        let pk = keygen_pk(params, vk, circuit)?;
+        self.write_pk(name, circuit, k, &pk)?;
+        Ok(pk)
+    }
+
+    fn write_key(
+        &self,
+        name: &str,
+        extension: &str,
+        digest: H256,
+        write: impl FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+    ) -> Result<(), KeyStoreError> {
+        fs::create_dir_all(&self.dir)?;
+        let mut writer = BufWriter::new(File::create(self.path(name, extension))?);
+        writer.write_all(digest.as_bytes())?;
+        write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn read_key<T>(
+        &self,
+        name: &str,
+        extension: &str,
+        expected: H256,
+        read: impl FnOnce(&mut BufReader<File>) -> io::Result<T>,
+    ) -> Result<T, KeyStoreError> {
+        let mut reader = BufReader::new(File::open(self.path(name, extension))?);
+        let mut found = H256::zero();
+        reader.read_exact(found.as_bytes_mut())?;
+        if found != expected {
+            return Err(KeyStoreError::DigestMismatch { expected, found });
+        }
+        Ok(read(&mut reader)?)
+    }
+}
@@ -11,6 +11,9 @@ pub use block::{
 mod bytecode;
 pub use bytecode::Bytecode;
 
+mod format;
+pub use format::{BlockFormatError, BLOCK_FORMAT_VERSION};
+
 mod call;
 pub use call::Call;
 
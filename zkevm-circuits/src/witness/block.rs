@@ -8,12 +8,15 @@ use crate::{evm_circuit::util::rlc, table::BlockContextFieldTag, util::SubCircui
 use bus_mapping::{
     circuit_input_builder::{
         self, BigModExp, CircuitsParams, CopyEvent, EcAddOp, EcMulOp, EcPairingOp, ExpEvent,
-        PrecompileEvents,
+        PrecompileEvents, BLAKE2F, RIPEMD160, SHA256,
     },
     Error,
 };
-use eth_types::{sign_types::SignData, Address, Field, ToLittleEndian, ToScalar, Word, U256};
+use eth_types::{
+    evm_types::Hardfork, sign_types::SignData, Address, Field, ToLittleEndian, ToScalar, Word, U256,
+};
 use halo2_proofs::circuit::Value;
+use serde::{Deserialize, Serialize};
 
 use super::{
     mpt::ZktrieState as MptState, step::step_convert, tx::tx_convert, Bytecode, ExecStep,
@@ -24,9 +27,11 @@ use crate::util::{Challenges, DEFAULT_RAND};
 // TODO: Remove fields that are duplicated in`eth_block`
 /// Block is the struct used by all circuits, which contains all the needed
 /// data for witness generation.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(bound = "F: Field")]
 pub struct Block<F> {
     /// The randomness for random linear combination
+    #[serde(with = "eth_types::serde_utils::field")]
     pub randomness: F,
     /// Transactions in the block
     pub txs: Vec<Transaction>,
@@ -72,7 +77,7 @@ pub struct Block<F> {
 }
 
 /// ...
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct BlockContexts {
     /// Hashmap that maps block number to its block context.
     pub ctxs: BTreeMap<u64, BlockContext>,
@@ -93,7 +98,8 @@ impl<F: Field> Block<F> {
         }
     }
 
-    /// Get signature (witness) from the block for tx signatures and ecRecover calls.
+    /// Get signature (witness) from the block for tx signatures, EIP-7702
+    /// authorizations and ecRecover calls.
     pub(crate) fn get_sign_data(&self, padding: bool) -> Vec<SignData> {
         let mut signatures: Vec<SignData> = self
             .txs
@@ -103,6 +109,13 @@ impl<F: Field> Block<F> {
             .map(|tx| tx.sign_data())
             .filter_map(|res| res.ok())
             .collect::<Vec<SignData>>();
+        // the authorities of EIP-7702 txs are recovered from their authorization tuples
+        signatures.extend(
+            self.txs
+                .iter()
+                .flat_map(|tx| tx.authorization_list.iter())
+                .filter_map(|auth| auth.sign_data().ok()),
+        );
         signatures.extend_from_slice(&self.precompile_events.get_ecrecover_events());
         if padding && self.txs.len() < self.circuits_params.max_txs {
             // padding tx's sign data
@@ -130,6 +143,22 @@ impl<F: Field> Block<F> {
     pub(crate) fn get_big_modexp(&self) -> Vec<BigModExp> {
         self.precompile_events.get_modexp_events()
     }
+
+    /// Get SHA256 operations from all precompiled contract calls in this block.
+    pub(crate) fn get_sha256(&self) -> Vec<SHA256> {
+        self.precompile_events.get_sha256_events()
+    }
+
+    /// Get RIPEMD160 operations from all precompiled contract calls in this
+    /// block.
+    pub(crate) fn get_ripemd160(&self) -> Vec<RIPEMD160> {
+        self.precompile_events.get_ripemd160_events()
+    }
+
+    /// Get BLAKE2F operations from all precompiled contract calls in this block.
+    pub(crate) fn get_blake2f(&self) -> Vec<BLAKE2F> {
+        self.precompile_events.get_blake2f_events()
+    }
 }
 
 #[cfg(feature = "test")]
@@ -200,7 +229,7 @@ impl<F: Field> Block<F> {
 }
 
 /// Block context for execution
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockContext {
     /// The address of the miner for the block
     pub coinbase: Address,
@@ -214,6 +243,12 @@ pub struct BlockContext {
     pub difficulty: Word,
     /// The base fee, the minimum amount of gas fee for a transaction
     pub base_fee: Word,
+    /// The excess blob gas of the block
+    pub excess_blob_gas: u64,
+    /// The blob base fee, derived from the excess blob gas
+    pub blob_base_fee: Word,
+    /// The hard fork active in the block
+    pub hardfork: Hardfork,
     /// The hash of previous blocks
     pub history_hashes: Vec<Word>,
     /// The chain id
@@ -286,6 +321,23 @@ impl BlockContext {
                     Value::known(current_block_number),
                     Value::known(F::from(num_all_txs)),
                 ],
+                [
+                    Value::known(F::from(BlockContextFieldTag::ExcessBlobGas as u64)),
+                    Value::known(current_block_number),
+                    Value::known(F::from(self.excess_blob_gas)),
+                ],
+                [
+                    Value::known(F::from(BlockContextFieldTag::BlobBaseFee as u64)),
+                    Value::known(current_block_number),
+                    randomness.map(|randomness| {
+                        rlc::value(&self.blob_base_fee.to_le_bytes(), randomness)
+                    }),
+                ],
+                [
+                    Value::known(F::from(BlockContextFieldTag::Hardfork as u64)),
+                    Value::known(current_block_number),
+                    Value::known(F::from(self.hardfork as u64)),
+                ],
             ],
             self.block_hash_assignments(randomness),
         ]
@@ -325,9 +377,9 @@ impl BlockContext {
 }
 
 impl From<&circuit_input_builder::Block> for BlockContexts {
-    fn from(block: &circuit_input_builder::Block) -> Self {
+    fn from(builder_block: &circuit_input_builder::Block) -> Self {
         Self {
-            ctxs: block
+            ctxs: builder_block
                 .headers
                 .values()
                 .map(|block| {
@@ -340,6 +392,9 @@ impl From<&circuit_input_builder::Block> for BlockContexts {
                             timestamp: block.timestamp,
                             difficulty: block.difficulty,
                             base_fee: block.base_fee,
+                            excess_blob_gas: block.excess_blob_gas,
+                            blob_base_fee: block.blob_base_fee,
+                            hardfork: builder_block.hardfork(block.number.as_u64()),
                             history_hashes: block.history_hashes.clone(),
                             chain_id: block.chain_id,
                             eth_block: block.eth_block.clone(),
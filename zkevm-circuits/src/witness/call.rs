@@ -1,7 +1,8 @@
 use eth_types::{Address, Word};
+use serde::{Deserialize, Serialize};
 
 /// Call in transactions.
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Call {
     /// The unique identifier of call in the whole proof, using the
     /// `rw_counter` at the call step.
@@ -0,0 +1,215 @@
+//! Serialization format of the witness [`Block`], so that the witness
+//! generation and the proving can run on different machines.
+//!
+//! A serialized block starts with a header made of magic bytes and the format
+//! version, followed by the block in CBOR.
+
+use super::Block;
+use eth_types::Field;
+use std::{
+    fmt::{self, Display, Formatter},
+    io::{self, Read, Write},
+};
+
+/// Magic bytes at the start of a serialized witness block.
+const BLOCK_FORMAT_MAGIC: [u8; 4] = *b"ZKWB";
+
+/// Version of the serialization format of the witness block. It must be bumped
+/// whenever a serialized witness type changes.
+pub const BLOCK_FORMAT_VERSION: u32 = 10;
+
+/// Error while writing or reading a serialized witness block.
+#[derive(Debug)]
+pub enum BlockFormatError {
+    /// IO error
+    Io(io::Error),
+    /// The data doesn't start with the magic bytes of a witness block.
+    InvalidMagic,
+    /// The witness block was written with another version of the format.
+    UnsupportedVersion(u32),
+    /// Error while encoding or decoding the witness block.
+    Encoding(serde_cbor::Error),
+}
+
+impl Display for BlockFormatError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "io error: {err}"),
+            Self::InvalidMagic => write!(f, "not a witness block"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "witness block format version {version} is not supported, expected \
+                 {BLOCK_FORMAT_VERSION}"
+            ),
+            Self::Encoding(err) => write!(f, "witness block encoding error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for BlockFormatError {}
+
+impl From<io::Error> for BlockFormatError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<serde_cbor::Error> for BlockFormatError {
+    fn from(err: serde_cbor::Error) -> Self {
+        Self::Encoding(err)
+    }
+}
+
+impl<F: Field> Block<F> {
+    /// Write the block with the header of the current format version.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<(), BlockFormatError> {
+        writer.write_all(&BLOCK_FORMAT_MAGIC)?;
+        writer.write_all(&BLOCK_FORMAT_VERSION.to_be_bytes())?;
+        serde_cbor::to_writer(writer, self)?;
+        Ok(())
+    }
+
+    /// Read a block written by [`Self::write_to`] with the current format
+    /// version.
+    pub fn read_from<R: Read>(mut reader: R) -> Result<Self, BlockFormatError> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != BLOCK_FORMAT_MAGIC {
+            return Err(BlockFormatError::InvalidMagic);
+        }
+        let mut version = [0u8; 4];
+        reader.read_exact(&mut version)?;
+        let version = u32::from_be_bytes(version);
+        if version != BLOCK_FORMAT_VERSION {
+            return Err(BlockFormatError::UnsupportedVersion(version));
+        }
+        Ok(serde_cbor::from_reader(reader)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{table::RwTableTag, witness::block_convert};
+    use bus_mapping::mock::BlockData;
+    use eth_types::{bytecode, geth_types::GethData};
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use mock::TestContext;
+    use strum::IntoEnumIterator;
+
+    fn witness_block() -> Block<Fr> {
+        let code = bytecode! {
+            // SHA3 and copy events
+            PUSH1(0x40)
+            PUSH1(0x00)
+            SHA3
+            PUSH1(0x20)
+            PUSH1(0x00)
+            PUSH1(0x20)
+            CODECOPY
+            // Precompile event with curve points
+            PUSH1(0x40)
+            PUSH1(0x00)
+            PUSH1(0x80)
+            PUSH1(0x00)
+            PUSH1(0x06)
+            GAS
+            STATICCALL
+            // Exponentiation event
+            PUSH1(0x05)
+            PUSH1(0x03)
+            EXP
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let mut block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+        block.randomness = Fr::from(0x100);
+        block
+    }
+
+    fn assert_blocks_eq(a: &Block<Fr>, b: &Block<Fr>) {
+        assert_eq!(a.randomness, b.randomness);
+        assert_eq!(a.txs, b.txs);
+        assert_eq!(a.sigs, b.sigs);
+        assert_eq!(a.end_block_not_last, b.end_block_not_last);
+        assert_eq!(a.end_block_last, b.end_block_last);
+        for tag in RwTableTag::iter() {
+            assert_eq!(
+                format!("{:?}", a.rws.0.get(&tag)),
+                format!("{:?}", b.rws.0.get(&tag))
+            );
+        }
+        assert_eq!(format!("{:?}", a.bytecodes), format!("{:?}", b.bytecodes));
+        assert_eq!(format!("{:?}", a.context), format!("{:?}", b.context));
+        assert_eq!(
+            format!("{:?}", a.copy_events),
+            format!("{:?}", b.copy_events)
+        );
+        assert_eq!(format!("{:?}", a.exp_events), format!("{:?}", b.exp_events));
+        assert_eq!(
+            format!("{:?}", a.circuits_params),
+            format!("{:?}", b.circuits_params)
+        );
+        assert_eq!(a.keccak_inputs, b.keccak_inputs);
+        assert_eq!(
+            format!("{:?}", a.mpt_updates),
+            format!("{:?}", b.mpt_updates)
+        );
+        assert_eq!(
+            format!("{:?}", a.precompile_events),
+            format!("{:?}", b.precompile_events)
+        );
+        assert_eq!(a.prev_state_root, b.prev_state_root);
+        assert_eq!(a.withdraw_root, b.withdraw_root);
+        assert_eq!(a.chain_id, b.chain_id);
+    }
+
+    #[test]
+    fn block_format_round_trip() {
+        let block = witness_block();
+        assert!(!block.copy_events.is_empty());
+        assert!(!block.exp_events.is_empty());
+        assert!(!block.precompile_events.events.is_empty());
+
+        let mut bytes = Vec::new();
+        block.write_to(&mut bytes).unwrap();
+        let read_block = Block::<Fr>::read_from(bytes.as_slice()).unwrap();
+        assert_blocks_eq(&block, &read_block);
+
+        let mut read_bytes = Vec::new();
+        read_block.write_to(&mut read_bytes).unwrap();
+        let block = Block::<Fr>::read_from(read_bytes.as_slice()).unwrap();
+        assert_blocks_eq(&block, &read_block);
+    }
+
+    #[test]
+    fn block_format_header() {
+        let mut bytes = Vec::new();
+        Block::<Fr>::default().write_to(&mut bytes).unwrap();
+
+        let mut wrong_version = bytes.clone();
+        wrong_version[4..8].copy_from_slice(&(BLOCK_FORMAT_VERSION + 1).to_be_bytes());
+        assert!(matches!(
+            Block::<Fr>::read_from(wrong_version.as_slice()),
+            Err(BlockFormatError::UnsupportedVersion(version)) if version == BLOCK_FORMAT_VERSION + 1
+        ));
+
+        let mut wrong_magic = bytes;
+        wrong_magic[0] = 0;
+        assert!(matches!(
+            Block::<Fr>::read_from(wrong_magic.as_slice()),
+            Err(BlockFormatError::InvalidMagic)
+        ));
+
+        assert!(matches!(
+            Block::<Fr>::read_from(&[][..]),
+            Err(BlockFormatError::Io(_))
+        ));
+    }
+}
@@ -10,7 +10,7 @@ use mpt_zktrie::{
     state,
     state::witness::WitnessGenerator,
 };
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::BTreeMap;
 
 pub use state::ZktrieState;
@@ -27,7 +27,7 @@ pub struct WithdrawProof {
 }
 
 /// An MPT update whose validity is proved by the MptCircuit
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MptUpdate {
     key: Key,
     old_value: Word,
@@ -63,7 +63,7 @@ impl MptUpdate {
 }
 
 /// All the MPT updates in the MptCircuit, accessible by their key
-#[derive(Default, Clone, Debug)]
+#[derive(Default, Clone, Debug, Serialize)]
 pub struct MptUpdates {
     old_root: Word,
     new_root: Word,
@@ -72,9 +72,40 @@ pub struct MptUpdates {
     /// Withdraw proof after this block
     pub withdraw_proof: WithdrawProof,
     pub(crate) smt_traces: Vec<SMTTrace>,
+    #[serde(skip)]
     pub(crate) proof_types: Vec<MPTProofType>,
 }
 
+impl<'de> Deserialize<'de> for MptUpdates {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Fields {
+            old_root: Word,
+            new_root: Word,
+            updates: BTreeMap<Key, MptUpdate>,
+            withdraw_proof: WithdrawProof,
+            smt_traces: Vec<SMTTrace>,
+        }
+
+        let fields = Fields::deserialize(deserializer)?;
+        // The proof types are not serialized, each SMT trace has the proof
+        // type of its update.
+        let proof_types = if fields.smt_traces.is_empty() {
+            vec![]
+        } else {
+            fields.updates.values().map(MptUpdate::proof_type).collect()
+        };
+        Ok(Self {
+            old_root: fields.old_root,
+            new_root: fields.new_root,
+            updates: fields.updates,
+            withdraw_proof: fields.withdraw_proof,
+            smt_traces: fields.smt_traces,
+            proof_types,
+        })
+    }
+}
+
 /// The field element encoding of an MPT update, which is used by the MptTable
 #[derive(Debug, Clone, Copy)]
 pub struct MptUpdateRow<F>(pub(crate) [F; 7]);
@@ -324,7 +355,7 @@ impl MptUpdate {
     }
 }
 
-#[derive(Eq, PartialEq, Hash, Clone, Debug, Copy, PartialOrd, Ord)]
+#[derive(Eq, PartialEq, Hash, Clone, Debug, Copy, PartialOrd, Ord, Serialize, Deserialize)]
 enum Key {
     Account {
         address: Address,
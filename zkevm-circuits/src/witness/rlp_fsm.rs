@@ -116,6 +116,26 @@ pub enum Tag {
     // L1MsgHash
     /// Sender
     Sender,
+
+    // EIP-4844
+    /// Max fee per blob gas
+    MaxFeePerBlobGas,
+    /// Versioned hash in blob_versioned_hashes
+    BlobVersionedHash,
+
+    // EIP-7702
+    /// Chain ID of an authorization
+    AuthChainId,
+    /// Delegate address of an authorization
+    AuthAddress,
+    /// Nonce of an authorization
+    AuthNonce,
+    /// Signature y_parity of an authorization
+    AuthYParity,
+    /// Signature r of an authorization
+    AuthR,
+    /// Signature s of an authorization
+    AuthS,
 }
 
 impl From<Tag> for usize {
@@ -189,13 +209,15 @@ use crate::{
     witness::{
         l1_msg,
         Format::{
-            TxHashEip155, TxHashEip1559, TxHashPreEip155, TxSignEip155, TxSignEip1559,
-            TxSignPreEip155,
+            TxHashEip155, TxHashEip1559, TxHashEip2930, TxHashEip4844, TxHashEip7702,
+            TxHashPreEip155, TxSignEip155, TxSignEip1559, TxSignEip2930, TxSignEip4844,
+            TxSignEip7702, TxSignPreEip155,
         },
         Tag::{
-            AccessListAddress, AccessListStorageKey, BeginList, BeginVector, ChainId, Data,
-            EndList, EndVector, Gas, GasPrice, MaxFeePerGas, MaxPriorityFeePerGas, Nonce, SigR,
-            SigS, SigV, To, TxType, Value as TxValue, Zero1, Zero2,
+            AccessListAddress, AccessListStorageKey, AuthAddress, AuthChainId, AuthNonce, AuthR,
+            AuthS, AuthYParity, BeginList, BeginVector, BlobVersionedHash, ChainId, Data, EndList,
+            EndVector, Gas, GasPrice, MaxFeePerBlobGas, MaxFeePerGas, MaxPriorityFeePerGas, Nonce,
+            SigR, SigS, SigV, To, TxType, Value as TxValue, Zero1, Zero2,
         },
     },
 };
@@ -351,11 +373,67 @@ pub fn eip1559_tx_hash_rom_table_rows() -> Vec<RomTableRow> {
 
 pub fn eip1559_tx_sign_rom_table_rows() -> Vec<RomTableRow> {
     let rows = vec![
-        (BeginList, ChainId, MAX_TAG_LENGTH_OF_LIST, vec![1]),
-        (ChainId, Nonce, N_BYTES_U64, vec![2]),
-        (Nonce, MaxPriorityFeePerGas, N_BYTES_U64, vec![3]),
-        (MaxPriorityFeePerGas, MaxFeePerGas, N_BYTES_WORD, vec![4]),
-        (MaxFeePerGas, Gas, N_BYTES_WORD, vec![5]),
+        (TxType, BeginList, 1, vec![1]),
+        (BeginList, ChainId, MAX_TAG_LENGTH_OF_LIST, vec![2]),
+        (ChainId, Nonce, N_BYTES_U64, vec![3]),
+        (Nonce, MaxPriorityFeePerGas, N_BYTES_U64, vec![4]),
+        (MaxPriorityFeePerGas, MaxFeePerGas, N_BYTES_WORD, vec![5]),
+        (MaxFeePerGas, Gas, N_BYTES_WORD, vec![6]),
+        (Gas, To, N_BYTES_U64, vec![7]),
+        (To, TxValue, N_BYTES_ACCOUNT_ADDRESS, vec![8]),
+        (TxValue, Data, N_BYTES_WORD, vec![9]),
+        (Data, BeginVector, N_BYTES_CALLDATA, vec![10, 11]),
+        (BeginVector, EndVector, MAX_TAG_LENGTH_OF_LIST, vec![21]), // access_list is none
+        (BeginVector, BeginList, MAX_TAG_LENGTH_OF_LIST, vec![12]),
+        (
+            BeginList,
+            AccessListAddress,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![13],
+        ),
+        (
+            AccessListAddress,
+            BeginVector,
+            N_BYTES_ACCOUNT_ADDRESS,
+            vec![14, 15],
+        ),
+        (BeginVector, EndVector, MAX_TAG_LENGTH_OF_LIST, vec![18]), /* access_list.storage_keys
+                                                                     * is
+                                                                     * none */
+        (
+            BeginVector,
+            AccessListStorageKey,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![16, 17],
+        ),
+        (AccessListStorageKey, EndVector, N_BYTES_WORD, vec![18]), // finished parsing storage keys
+        (
+            AccessListStorageKey,
+            AccessListStorageKey,
+            N_BYTES_WORD,
+            vec![16, 17],
+        ), // keep parsing storage_keys
+        (EndVector, EndList, 0, vec![19, 20]),
+        (EndList, EndVector, 0, vec![21]), // finished parsing access_list
+        (EndList, BeginList, 0, vec![12]), // parse another access_list entry
+        (EndVector, EndList, 0, vec![22]),
+        (EndList, EndList, 0, vec![23]),
+        // used to emit TxGasCostInL1
+        (EndList, BeginList, 0, vec![]),
+    ];
+
+    rows.into_iter()
+        .map(|row| (row.0, row.1, row.2, TxSignEip1559, row.3).into())
+        .collect()
+}
+
+pub fn eip2930_tx_hash_rom_table_rows() -> Vec<RomTableRow> {
+    let rows = vec![
+        (TxType, BeginList, 1, vec![1]),
+        (BeginList, ChainId, MAX_TAG_LENGTH_OF_LIST, vec![2]),
+        (ChainId, Nonce, N_BYTES_U64, vec![3]),
+        (Nonce, GasPrice, N_BYTES_U64, vec![4]),
+        (GasPrice, Gas, N_BYTES_WORD, vec![5]),
         (Gas, To, N_BYTES_U64, vec![6]),
         (To, TxValue, N_BYTES_ACCOUNT_ADDRESS, vec![7]),
         (TxValue, Data, N_BYTES_WORD, vec![8]),
@@ -375,8 +453,64 @@ pub fn eip1559_tx_sign_rom_table_rows() -> Vec<RomTableRow> {
             vec![13, 14],
         ),
         (BeginVector, EndVector, MAX_TAG_LENGTH_OF_LIST, vec![17]), /* access_list.storage_keys
-                                                                     * is
-                                                                     * none */
+                                                                     * is none */
+        (
+            BeginVector,
+            AccessListStorageKey,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![15, 16],
+        ),
+        (AccessListStorageKey, EndVector, N_BYTES_WORD, vec![17]), // finished parsing storage keys
+        (
+            AccessListStorageKey,
+            AccessListStorageKey,
+            N_BYTES_WORD,
+            vec![15, 16],
+        ), // keep parsing storage_keys
+        (EndVector, EndList, 0, vec![18, 19]),
+        (EndList, EndVector, 0, vec![20]), // finished parsing access_list
+        (EndList, BeginList, 0, vec![11]), // parse another access_list entry
+        (EndVector, SigV, 0, vec![21]),
+        (SigV, SigR, N_BYTES_U64, vec![22]),
+        (SigR, SigS, N_BYTES_WORD, vec![23]),
+        (SigS, EndList, N_BYTES_WORD, vec![24]),
+        (EndList, EndList, 0, vec![25]),
+        // used to emit TxGasCostInL1
+        (EndList, BeginList, 0, vec![]),
+    ];
+
+    rows.into_iter()
+        .map(|row| (row.0, row.1, row.2, TxHashEip2930, row.3).into())
+        .collect()
+}
+
+pub fn eip2930_tx_sign_rom_table_rows() -> Vec<RomTableRow> {
+    let rows = vec![
+        (TxType, BeginList, 1, vec![1]),
+        (BeginList, ChainId, MAX_TAG_LENGTH_OF_LIST, vec![2]),
+        (ChainId, Nonce, N_BYTES_U64, vec![3]),
+        (Nonce, GasPrice, N_BYTES_U64, vec![4]),
+        (GasPrice, Gas, N_BYTES_WORD, vec![5]),
+        (Gas, To, N_BYTES_U64, vec![6]),
+        (To, TxValue, N_BYTES_ACCOUNT_ADDRESS, vec![7]),
+        (TxValue, Data, N_BYTES_WORD, vec![8]),
+        (Data, BeginVector, N_BYTES_CALLDATA, vec![9, 10]),
+        (BeginVector, EndVector, MAX_TAG_LENGTH_OF_LIST, vec![20]), // access_list is none
+        (BeginVector, BeginList, MAX_TAG_LENGTH_OF_LIST, vec![11]),
+        (
+            BeginList,
+            AccessListAddress,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![12],
+        ),
+        (
+            AccessListAddress,
+            BeginVector,
+            N_BYTES_ACCOUNT_ADDRESS,
+            vec![13, 14],
+        ),
+        (BeginVector, EndVector, MAX_TAG_LENGTH_OF_LIST, vec![17]), /* access_list.storage_keys
+                                                                     * is none */
         (
             BeginVector,
             AccessListStorageKey,
@@ -400,7 +534,289 @@ pub fn eip1559_tx_sign_rom_table_rows() -> Vec<RomTableRow> {
     ];
 
     rows.into_iter()
-        .map(|row| (row.0, row.1, row.2, TxSignEip1559, row.3).into())
+        .map(|row| (row.0, row.1, row.2, TxSignEip2930, row.3).into())
+        .collect()
+}
+
+pub fn eip4844_tx_sign_rom_table_rows() -> Vec<RomTableRow> {
+    let rows = vec![
+        (TxType, BeginList, 1, vec![1]),
+        (BeginList, ChainId, MAX_TAG_LENGTH_OF_LIST, vec![2]),
+        (ChainId, Nonce, N_BYTES_U64, vec![3]),
+        (Nonce, MaxPriorityFeePerGas, N_BYTES_U64, vec![4]),
+        (MaxPriorityFeePerGas, MaxFeePerGas, N_BYTES_WORD, vec![5]),
+        (MaxFeePerGas, Gas, N_BYTES_WORD, vec![6]),
+        (Gas, To, N_BYTES_U64, vec![7]),
+        (To, TxValue, N_BYTES_ACCOUNT_ADDRESS, vec![8]),
+        (TxValue, Data, N_BYTES_WORD, vec![9]),
+        (Data, BeginVector, N_BYTES_CALLDATA, vec![10, 11]),
+        (BeginVector, EndVector, MAX_TAG_LENGTH_OF_LIST, vec![21]), // access_list is none
+        (BeginVector, BeginList, MAX_TAG_LENGTH_OF_LIST, vec![12]),
+        (
+            BeginList,
+            AccessListAddress,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![13],
+        ),
+        (
+            AccessListAddress,
+            BeginVector,
+            N_BYTES_ACCOUNT_ADDRESS,
+            vec![14, 15],
+        ),
+        (BeginVector, EndVector, MAX_TAG_LENGTH_OF_LIST, vec![18]), /* access_list.storage_keys
+                                                                     * is none */
+        (
+            BeginVector,
+            AccessListStorageKey,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![16, 17],
+        ),
+        (AccessListStorageKey, EndVector, N_BYTES_WORD, vec![18]), // finished parsing storage keys
+        (
+            AccessListStorageKey,
+            AccessListStorageKey,
+            N_BYTES_WORD,
+            vec![16, 17],
+        ), // keep parsing storage_keys
+        (EndVector, EndList, 0, vec![19, 20]),
+        (EndList, EndVector, 0, vec![21]), // finished parsing access_list
+        (EndList, BeginList, 0, vec![12]), // parse another access_list entry
+        (EndVector, MaxFeePerBlobGas, 0, vec![22]),
+        (MaxFeePerBlobGas, BeginVector, N_BYTES_WORD, vec![23]),
+        // blob_versioned_hashes can not be empty
+        (
+            BeginVector,
+            BlobVersionedHash,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![24, 25],
+        ),
+        (BlobVersionedHash, EndVector, N_BYTES_WORD, vec![26]), // finished parsing blob hashes
+        (
+            BlobVersionedHash,
+            BlobVersionedHash,
+            N_BYTES_WORD,
+            vec![24, 25],
+        ), // keep parsing
+        (EndVector, EndList, 0, vec![27]),
+        (EndList, EndList, 0, vec![28]),
+        // used to emit TxGasCostInL1
+        (EndList, BeginList, 0, vec![]),
+    ];
+
+    rows.into_iter()
+        .map(|row| (row.0, row.1, row.2, TxSignEip4844, row.3).into())
+        .collect()
+}
+
+pub fn eip4844_tx_hash_rom_table_rows() -> Vec<RomTableRow> {
+    let rows = vec![
+        (TxType, BeginList, 1, vec![1]),
+        (BeginList, ChainId, MAX_TAG_LENGTH_OF_LIST, vec![2]),
+        (ChainId, Nonce, N_BYTES_U64, vec![3]),
+        (Nonce, MaxPriorityFeePerGas, N_BYTES_U64, vec![4]),
+        (MaxPriorityFeePerGas, MaxFeePerGas, N_BYTES_WORD, vec![5]),
+        (MaxFeePerGas, Gas, N_BYTES_WORD, vec![6]),
+        (Gas, To, N_BYTES_U64, vec![7]),
+        (To, TxValue, N_BYTES_ACCOUNT_ADDRESS, vec![8]),
+        (TxValue, Data, N_BYTES_WORD, vec![9]),
+        (Data, BeginVector, N_BYTES_CALLDATA, vec![10, 11]),
+        (BeginVector, EndVector, MAX_TAG_LENGTH_OF_LIST, vec![21]), // access_list is none
+        (BeginVector, BeginList, MAX_TAG_LENGTH_OF_LIST, vec![12]),
+        (
+            BeginList,
+            AccessListAddress,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![13],
+        ),
+        (
+            AccessListAddress,
+            BeginVector,
+            N_BYTES_ACCOUNT_ADDRESS,
+            vec![14, 15],
+        ),
+        (BeginVector, EndVector, MAX_TAG_LENGTH_OF_LIST, vec![18]), /* access_list.storage_keys
+                                                                     * is none */
+        (
+            BeginVector,
+            AccessListStorageKey,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![16, 17],
+        ),
+        (AccessListStorageKey, EndVector, N_BYTES_WORD, vec![18]), // finished parsing storage keys
+        (
+            AccessListStorageKey,
+            AccessListStorageKey,
+            N_BYTES_WORD,
+            vec![16, 17],
+        ), // keep parsing storage_keys
+        (EndVector, EndList, 0, vec![19, 20]),
+        (EndList, EndVector, 0, vec![21]), // finished parsing access_list
+        (EndList, BeginList, 0, vec![12]), // parse another access_list entry
+        (EndVector, MaxFeePerBlobGas, 0, vec![22]),
+        (MaxFeePerBlobGas, BeginVector, N_BYTES_WORD, vec![23]),
+        // blob_versioned_hashes can not be empty
+        (
+            BeginVector,
+            BlobVersionedHash,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![24, 25],
+        ),
+        (BlobVersionedHash, EndVector, N_BYTES_WORD, vec![26]), // finished parsing blob hashes
+        (
+            BlobVersionedHash,
+            BlobVersionedHash,
+            N_BYTES_WORD,
+            vec![24, 25],
+        ), // keep parsing
+        (EndVector, SigV, 0, vec![27]),
+        (SigV, SigR, N_BYTES_U64, vec![28]),
+        (SigR, SigS, N_BYTES_WORD, vec![29]),
+        (SigS, EndList, N_BYTES_WORD, vec![30]),
+        (EndList, EndList, 0, vec![31]),
+        // used to emit TxGasCostInL1
+        (EndList, BeginList, 0, vec![]),
+    ];
+
+    rows.into_iter()
+        .map(|row| (row.0, row.1, row.2, TxHashEip4844, row.3).into())
+        .collect()
+}
+
+pub fn eip7702_tx_sign_rom_table_rows() -> Vec<RomTableRow> {
+    let rows = vec![
+        (TxType, BeginList, 1, vec![1]),
+        (BeginList, ChainId, MAX_TAG_LENGTH_OF_LIST, vec![2]),
+        (ChainId, Nonce, N_BYTES_U64, vec![3]),
+        (Nonce, MaxPriorityFeePerGas, N_BYTES_U64, vec![4]),
+        (MaxPriorityFeePerGas, MaxFeePerGas, N_BYTES_WORD, vec![5]),
+        (MaxFeePerGas, Gas, N_BYTES_WORD, vec![6]),
+        (Gas, To, N_BYTES_U64, vec![7]),
+        (To, TxValue, N_BYTES_ACCOUNT_ADDRESS, vec![8]),
+        (TxValue, Data, N_BYTES_WORD, vec![9]),
+        (Data, BeginVector, N_BYTES_CALLDATA, vec![10, 11]),
+        (BeginVector, EndVector, MAX_TAG_LENGTH_OF_LIST, vec![21]), // access_list is none
+        (BeginVector, BeginList, MAX_TAG_LENGTH_OF_LIST, vec![12]),
+        (
+            BeginList,
+            AccessListAddress,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![13],
+        ),
+        (
+            AccessListAddress,
+            BeginVector,
+            N_BYTES_ACCOUNT_ADDRESS,
+            vec![14, 15],
+        ),
+        (BeginVector, EndVector, MAX_TAG_LENGTH_OF_LIST, vec![18]), /* access_list.storage_keys
+                                                                     * is none */
+        (
+            BeginVector,
+            AccessListStorageKey,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![16, 17],
+        ),
+        (AccessListStorageKey, EndVector, N_BYTES_WORD, vec![18]), // finished parsing storage keys
+        (
+            AccessListStorageKey,
+            AccessListStorageKey,
+            N_BYTES_WORD,
+            vec![16, 17],
+        ), // keep parsing storage_keys
+        (EndVector, EndList, 0, vec![19, 20]),
+        (EndList, EndVector, 0, vec![21]), // finished parsing access_list
+        (EndList, BeginList, 0, vec![12]), // parse another access_list entry
+        // authorization_list can not be empty
+        (EndVector, BeginVector, 0, vec![22]),
+        (BeginVector, BeginList, MAX_TAG_LENGTH_OF_LIST, vec![23]),
+        (BeginList, AuthChainId, MAX_TAG_LENGTH_OF_LIST, vec![24]),
+        (AuthChainId, AuthAddress, N_BYTES_WORD, vec![25]),
+        (AuthAddress, AuthNonce, N_BYTES_ACCOUNT_ADDRESS, vec![26]),
+        (AuthNonce, AuthYParity, N_BYTES_U64, vec![27]),
+        (AuthYParity, AuthR, N_BYTES_U64, vec![28]),
+        (AuthR, AuthS, N_BYTES_WORD, vec![29]),
+        (AuthS, EndList, N_BYTES_WORD, vec![30, 31]),
+        (EndList, EndVector, 0, vec![32]), // finished parsing authorization_list
+        (EndList, BeginList, 0, vec![23]), // parse another authorization
+        (EndVector, EndList, 0, vec![33]),
+        (EndList, EndList, 0, vec![34]),
+        // used to emit TxGasCostInL1
+        (EndList, BeginList, 0, vec![]),
+    ];
+
+    rows.into_iter()
+        .map(|row| (row.0, row.1, row.2, TxSignEip7702, row.3).into())
+        .collect()
+}
+
+pub fn eip7702_tx_hash_rom_table_rows() -> Vec<RomTableRow> {
+    let rows = vec![
+        (TxType, BeginList, 1, vec![1]),
+        (BeginList, ChainId, MAX_TAG_LENGTH_OF_LIST, vec![2]),
+        (ChainId, Nonce, N_BYTES_U64, vec![3]),
+        (Nonce, MaxPriorityFeePerGas, N_BYTES_U64, vec![4]),
+        (MaxPriorityFeePerGas, MaxFeePerGas, N_BYTES_WORD, vec![5]),
+        (MaxFeePerGas, Gas, N_BYTES_WORD, vec![6]),
+        (Gas, To, N_BYTES_U64, vec![7]),
+        (To, TxValue, N_BYTES_ACCOUNT_ADDRESS, vec![8]),
+        (TxValue, Data, N_BYTES_WORD, vec![9]),
+        (Data, BeginVector, N_BYTES_CALLDATA, vec![10, 11]),
+        (BeginVector, EndVector, MAX_TAG_LENGTH_OF_LIST, vec![21]), // access_list is none
+        (BeginVector, BeginList, MAX_TAG_LENGTH_OF_LIST, vec![12]),
+        (
+            BeginList,
+            AccessListAddress,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![13],
+        ),
+        (
+            AccessListAddress,
+            BeginVector,
+            N_BYTES_ACCOUNT_ADDRESS,
+            vec![14, 15],
+        ),
+        (BeginVector, EndVector, MAX_TAG_LENGTH_OF_LIST, vec![18]), /* access_list.storage_keys
+                                                                     * is none */
+        (
+            BeginVector,
+            AccessListStorageKey,
+            MAX_TAG_LENGTH_OF_LIST,
+            vec![16, 17],
+        ),
+        (AccessListStorageKey, EndVector, N_BYTES_WORD, vec![18]), // finished parsing storage keys
+        (
+            AccessListStorageKey,
+            AccessListStorageKey,
+            N_BYTES_WORD,
+            vec![16, 17],
+        ), // keep parsing storage_keys
+        (EndVector, EndList, 0, vec![19, 20]),
+        (EndList, EndVector, 0, vec![21]), // finished parsing access_list
+        (EndList, BeginList, 0, vec![12]), // parse another access_list entry
+        // authorization_list can not be empty
+        (EndVector, BeginVector, 0, vec![22]),
+        (BeginVector, BeginList, MAX_TAG_LENGTH_OF_LIST, vec![23]),
+        (BeginList, AuthChainId, MAX_TAG_LENGTH_OF_LIST, vec![24]),
+        (AuthChainId, AuthAddress, N_BYTES_WORD, vec![25]),
+        (AuthAddress, AuthNonce, N_BYTES_ACCOUNT_ADDRESS, vec![26]),
+        (AuthNonce, AuthYParity, N_BYTES_U64, vec![27]),
+        (AuthYParity, AuthR, N_BYTES_U64, vec![28]),
+        (AuthR, AuthS, N_BYTES_WORD, vec![29]),
+        (AuthS, EndList, N_BYTES_WORD, vec![30, 31]),
+        (EndList, EndVector, 0, vec![32]), // finished parsing authorization_list
+        (EndList, BeginList, 0, vec![23]), // parse another authorization
+        (EndVector, SigV, 0, vec![33]),
+        (SigV, SigR, N_BYTES_U64, vec![34]),
+        (SigR, SigS, N_BYTES_WORD, vec![35]),
+        (SigS, EndList, N_BYTES_WORD, vec![36]),
+        (EndList, EndList, 0, vec![37]),
+        // used to emit TxGasCostInL1
+        (EndList, BeginList, 0, vec![]),
+    ];
+
+    rows.into_iter()
+        .map(|row| (row.0, row.1, row.2, TxHashEip7702, row.3).into())
         .collect()
 }
 
@@ -458,6 +874,18 @@ pub enum Format {
     TxHashEip1559,
     /// L1 Msg
     L1MsgHash,
+    /// Sign for EIP4844 tx
+    TxSignEip4844,
+    /// Hash for EIP4844 tx
+    TxHashEip4844,
+    /// Sign for EIP7702 tx
+    TxSignEip7702,
+    /// Hash for EIP7702 tx
+    TxHashEip7702,
+    /// Sign for EIP2930 tx
+    TxSignEip2930,
+    /// Hash for EIP2930 tx
+    TxHashEip2930,
 }
 
 impl From<Format> for usize {
@@ -477,6 +905,12 @@ impl Format {
             TxSignEip1559 => eip1559_tx_sign_rom_table_rows(),
             TxHashEip1559 => eip1559_tx_hash_rom_table_rows(),
             Self::L1MsgHash => l1_msg::rom_table_rows(),
+            TxSignEip4844 => eip4844_tx_sign_rom_table_rows(),
+            TxHashEip4844 => eip4844_tx_hash_rom_table_rows(),
+            TxSignEip7702 => eip7702_tx_sign_rom_table_rows(),
+            TxHashEip7702 => eip7702_tx_hash_rom_table_rows(),
+            TxSignEip2930 => eip2930_tx_sign_rom_table_rows(),
+            TxHashEip2930 => eip2930_tx_hash_rom_table_rows(),
         }
     }
 }
@@ -570,6 +1004,14 @@ pub struct RlpTable<F: FieldExt> {
     pub is_output: bool,
     /// If current tag's value is None.
     pub is_none: bool,
+    /// The number of access list addresses decoded up to the current row
+    pub access_list_idx: usize,
+    /// The number of access list storage keys decoded up to the current row
+    pub storage_key_idx: usize,
+    /// The number of blob versioned hashes decoded up to the current row
+    pub blob_idx: usize,
+    /// The number of authorizations decoded up to the current row
+    pub authorization_idx: usize,
 }
 
 /// State Machine
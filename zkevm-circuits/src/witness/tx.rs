@@ -6,21 +6,28 @@ use crate::{
         rlp_fsm::SmState,
         DataTable, Format,
         Format::{
-            L1MsgHash, TxHashEip155, TxHashEip1559, TxHashPreEip155, TxSignEip155, TxSignEip1559,
-            TxSignPreEip155,
+            L1MsgHash, TxHashEip155, TxHashEip1559, TxHashEip2930, TxHashEip4844, TxHashEip7702,
+            TxHashPreEip155, TxSignEip155, TxSignEip1559, TxSignEip2930, TxSignEip4844,
+            TxSignEip7702, TxSignPreEip155,
         },
         RlpFsmWitnessGen, RlpFsmWitnessRow, RlpTable, RlpTag, State,
         State::DecodeTagStart,
         StateMachine,
-        Tag::{EndList, EndVector},
+        Tag::{
+            AccessListAddress, AccessListStorageKey, AuthChainId, BlobVersionedHash, EndList,
+            EndVector,
+        },
     },
 };
-use bus_mapping::circuit_input_builder::{self, get_dummy_tx, get_dummy_tx_hash, TxL1Fee};
+use bus_mapping::circuit_input_builder::{
+    self, get_dummy_tx, get_dummy_tx_hash, AppliedAuthorization, TxL1Fee,
+};
 use eth_types::{
-    evm_types::gas_utils::tx_data_gas_cost,
-    geth_types::{TxType, TxType::PreEip155},
+    evm_types::{block_utils::MAX_BLOBS_PER_BLOCK, gas_utils::tx_data_gas_cost},
+    geth_types::{SetCodeAuthorization, TxType, TxType::PreEip155},
     sign_types::{biguint_to_32bytes_le, ct_option_ok_or, recover_pk, SignData, SECP256K1_Q},
-    Address, Error, Field, Signature, ToBigEndian, ToLittleEndian, ToScalar, ToWord, Word, H256,
+    AccessList, Address, Error, Field, Signature, ToBigEndian, ToLittleEndian, ToScalar, ToWord,
+    Word, H256,
 };
 use ethers_core::{types::TransactionRequest, utils::keccak256};
 use halo2_proofs::{
@@ -30,12 +37,13 @@ use halo2_proofs::{
 use mock::MockTransaction;
 use num::Integer;
 use num_bigint::BigUint;
-use std::{cmp::Ordering, collections::BTreeMap};
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, collections::BTreeMap, iter};
 
 use super::{step::step_convert, Call, ExecStep};
 
 /// Transaction in a witness block
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Transaction {
     /// The block number in which this tx is included in
     pub block_number: u64,
@@ -83,6 +91,25 @@ pub struct Transaction {
     pub l1_fee: TxL1Fee,
     /// Committed values of L1 fee
     pub l1_fee_committed: TxL1Fee,
+    /// The max priority fee per gas (EIP-1559)
+    pub max_priority_fee_per_gas: Word,
+    /// The max fee per gas (EIP-1559)
+    pub max_fee_per_gas: Word,
+    /// The max fee per blob gas (EIP-4844)
+    pub max_fee_per_blob_gas: Word,
+    /// Versioned hashes of the blobs carried by the transaction (EIP-4844)
+    pub blob_versioned_hashes: Vec<H256>,
+    /// The access list (EIP-2930)
+    pub access_list: Option<AccessList>,
+    /// The rw counter at which BeginTx starts to warm up the access list
+    pub access_list_rw_counter: usize,
+    /// Whether each access list entry was warm before BeginTx warms it up
+    pub access_list_is_warm_prev: Vec<bool>,
+    /// The authorization list (EIP-7702)
+    pub authorization_list: Vec<SetCodeAuthorization>,
+    /// The authorizations applied by BeginTx, one for each authorization
+    /// whose authority is recovered
+    pub applied_authorizations: Vec<AppliedAuthorization>,
     /// The calls made in the transaction
     pub calls: Vec<Call>,
     /// The steps executioned in the transaction
@@ -152,6 +179,54 @@ impl Transaction {
         })
     }
 
+    /// Values of the BlobVersionedHash rows in the tx table, one per blob slot
+    /// with zero for the slots the tx doesn't use.
+    pub fn blob_versioned_hash_values<F: Field>(
+        &self,
+        challenges: &Challenges<Value<F>>,
+    ) -> Vec<Value<F>> {
+        assert!(self.blob_versioned_hashes.len() <= MAX_BLOBS_PER_BLOCK);
+        (0..MAX_BLOBS_PER_BLOCK)
+            .map(|index| {
+                self.blob_versioned_hashes
+                    .get(index)
+                    .map_or(Value::known(F::zero()), |hash| {
+                        rlc_be_bytes(&hash.to_fixed_bytes(), challenges.evm_word())
+                    })
+            })
+            .collect()
+    }
+
+    /// Number of addresses and storage keys in the access list
+    pub fn access_list_len(&self) -> (usize, usize) {
+        self.access_list.as_ref().map_or((0, 0), |access_list| {
+            (
+                access_list.0.len(),
+                access_list
+                    .0
+                    .iter()
+                    .map(|item| item.storage_keys.len())
+                    .sum(),
+            )
+        })
+    }
+
+    /// Entries of the access list in the order they are encoded and warmed up,
+    /// i.e. each address followed by its storage keys.
+    pub fn access_list_entries(&self) -> Vec<(Address, Option<H256>)> {
+        self.access_list
+            .iter()
+            .flat_map(|access_list| access_list.0.iter())
+            .flat_map(|item| {
+                iter::once((item.address, None)).chain(
+                    item.storage_keys
+                        .iter()
+                        .map(|key| (item.address, Some(*key))),
+                )
+            })
+            .collect()
+    }
+
     /// Assignments for tx table, split into tx_data (all fields except
     /// calldata) and tx_calldata
 
@@ -162,6 +237,7 @@ impl Transaction {
     ) -> Vec<[Value<F>; 4]> {
         let tx_hash_be_bytes = keccak256(&self.rlp_signed);
         let tx_sign_hash_be_bytes = keccak256(&self.rlp_unsigned);
+        let (access_list_addresses_len, access_list_storage_keys_len) = self.access_list_len();
 
         let ret = vec![
             [
@@ -307,13 +383,85 @@ impl Transaction {
             ],
             [
                 Value::known(F::from(self.id as u64)),
-                Value::known(F::from(TxContextFieldTag::BlockNumber as u64)),
+                Value::known(F::from(TxContextFieldTag::AccessListAddressesLen as u64)),
                 Value::known(F::zero()),
-                Value::known(F::from(self.block_number)),
+                Value::known(F::from(access_list_addresses_len as u64)),
             ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::AccessListStorageKeysLen as u64)),
+                Value::known(F::zero()),
+                Value::known(F::from(access_list_storage_keys_len as u64)),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::AccessListRwCounter as u64)),
+                Value::known(F::zero()),
+                Value::known(F::from(self.access_list_rw_counter as u64)),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::AuthorizationListLen as u64)),
+                Value::known(F::zero()),
+                Value::known(F::from(self.authorization_list.len() as u64)),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(
+                    TxContextFieldTag::AuthorizationListAuthoritiesLen as u64,
+                )),
+                Value::known(F::zero()),
+                Value::known(F::from(self.applied_authorizations.len() as u64)),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::MaxPriorityFeePerGas as u64)),
+                Value::known(F::zero()),
+                challenges.evm_word().map(|challenge| {
+                    rlc::value(&self.max_priority_fee_per_gas.to_le_bytes(), challenge)
+                }),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::MaxFeePerGas as u64)),
+                Value::known(F::zero()),
+                challenges
+                    .evm_word()
+                    .map(|challenge| rlc::value(&self.max_fee_per_gas.to_le_bytes(), challenge)),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::MaxFeePerBlobGas as u64)),
+                Value::known(F::zero()),
+                challenges.evm_word().map(|challenge| {
+                    rlc::value(&self.max_fee_per_blob_gas.to_le_bytes(), challenge)
+                }),
+            ],
+        ];
+        let blob_hashes = self
+            .blob_versioned_hash_values(&challenges)
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| {
+                [
+                    Value::known(F::from(self.id as u64)),
+                    Value::known(F::from(TxContextFieldTag::BlobVersionedHash as u64)),
+                    Value::known(F::from(index as u64)),
+                    value,
+                ]
+            });
+        // BlockNumber must be the last row of each tx
+        let block_number = [
+            Value::known(F::from(self.id as u64)),
+            Value::known(F::from(TxContextFieldTag::BlockNumber as u64)),
+            Value::known(F::zero()),
+            Value::known(F::from(self.block_number)),
         ];
 
-        ret
+        ret.into_iter()
+            .chain(blob_hashes)
+            .chain(std::iter::once(block_number))
+            .collect()
     }
 
     /// Assignments for tx table
@@ -335,22 +483,26 @@ impl Transaction {
             .collect()
     }
 
+    /// Format of the RLP encoding of the signed tx
+    pub(crate) fn hash_format(&self) -> Format {
+        match self.tx_type {
+            TxType::Eip155 => TxHashEip155,
+            TxType::PreEip155 => TxHashPreEip155,
+            TxType::Eip1559 => TxHashEip1559,
+            TxType::Eip2930 => TxHashEip2930,
+            TxType::Eip4844 => TxHashEip4844,
+            TxType::Eip7702 => TxHashEip7702,
+            TxType::L1Msg => L1MsgHash,
+        }
+    }
+
     pub(crate) fn gen_rlp_witness<F: Field>(
         &self,
         is_hash: bool,
         challenges: &Challenges<Value<F>>,
     ) -> Vec<RlpFsmWitnessRow<F>> {
         let (rlp_bytes, format) = if is_hash {
-            (
-                self.rlp_signed.clone(),
-                match self.tx_type {
-                    TxType::Eip155 => TxHashEip155,
-                    TxType::PreEip155 => TxHashPreEip155,
-                    TxType::Eip1559 => TxHashEip1559,
-                    TxType::L1Msg => L1MsgHash,
-                    _ => unreachable!("tx type {:?} not supported", self.tx_type),
-                },
-            )
+            (self.rlp_signed.clone(), self.hash_format())
         } else {
             (
                 self.rlp_unsigned.clone(),
@@ -358,6 +510,9 @@ impl Transaction {
                     TxType::Eip155 => TxSignEip155,
                     TxType::PreEip155 => TxSignPreEip155,
                     TxType::Eip1559 => TxSignEip1559,
+                    TxType::Eip2930 => TxSignEip2930,
+                    TxType::Eip4844 => TxSignEip4844,
+                    TxType::Eip7702 => TxSignEip7702,
                     _ => unreachable!("tx type {:?} not supported", self.tx_type),
                 },
             )
@@ -412,6 +567,13 @@ impl Transaction {
         let mut is_none;
         let mut rlp_tag;
         let mut lb_len = 0;
+        // The access list entries, the blob versioned hashes and the
+        // authorizations are numbered from 1 in the order they are decoded, so
+        // that the tx circuit can look them up one by one.
+        let mut access_list_idx = 0;
+        let mut storage_key_idx = 0;
+        let mut blob_idx = 0;
+        let mut authorization_idx = 0;
 
         loop {
             // default behavior
@@ -672,6 +834,16 @@ impl Transaction {
                 RlpTag::Null => unreachable!("Null is not used"),
             };
 
+            if is_output {
+                match rlp_tag {
+                    RlpTag::Tag(AccessListAddress) => access_list_idx += 1,
+                    RlpTag::Tag(AccessListStorageKey) => storage_key_idx += 1,
+                    RlpTag::Tag(BlobVersionedHash) => blob_idx += 1,
+                    RlpTag::Tag(AuthChainId) => authorization_idx += 1,
+                    _ => (),
+                }
+            }
+
             witness.push(RlpFsmWitnessRow {
                 rlp_table: RlpTable {
                     tx_id,
@@ -682,6 +854,10 @@ impl Transaction {
                     tag_length,
                     is_output,
                     is_none,
+                    access_list_idx,
+                    storage_key_idx,
+                    blob_idx,
+                    authorization_idx,
                 },
                 state_machine: StateMachine {
                     state: cur.state,
@@ -784,10 +960,10 @@ impl<F: Field> RlpFsmWitnessGen<F> for Transaction {
             TxType::Eip155 => (TxHashEip155, Some(TxSignEip155)),
             TxType::PreEip155 => (TxHashPreEip155, Some(TxSignPreEip155)),
             TxType::Eip1559 => (TxHashEip1559, Some(TxSignEip1559)),
-            TxType::Eip2930 => {
-                unimplemented!("eip2930 not supported now")
-            }
+            TxType::Eip2930 => (TxHashEip2930, Some(TxSignEip2930)),
             TxType::L1Msg => (L1MsgHash, None),
+            TxType::Eip4844 => (TxHashEip4844, Some(TxSignEip4844)),
+            TxType::Eip7702 => (TxHashEip7702, Some(TxSignEip7702)),
         };
 
         let get_table = |rlp_bytes: &Vec<u8>, format: Format| {
@@ -875,6 +1051,15 @@ impl From<MockTransaction> for Transaction {
             s: sig.s,
             l1_fee: Default::default(),
             l1_fee_committed: Default::default(),
+            max_priority_fee_per_gas: mock_tx.max_priority_fee_per_gas,
+            max_fee_per_gas: mock_tx.max_fee_per_gas,
+            max_fee_per_blob_gas: mock_tx.max_fee_per_blob_gas,
+            blob_versioned_hashes: mock_tx.blob_versioned_hashes,
+            access_list: None,
+            access_list_rw_counter: 0,
+            access_list_is_warm_prev: vec![],
+            authorization_list: mock_tx.authorization_list,
+            applied_authorizations: vec![],
             calls: vec![],
             steps: vec![],
         }
@@ -926,6 +1111,15 @@ pub(super) fn tx_convert(
         s: tx.signature.s,
         l1_fee: tx.l1_fee,
         l1_fee_committed: tx.l1_fee_committed,
+        max_priority_fee_per_gas: tx.gas_tip_cap,
+        max_fee_per_gas: tx.gas_fee_cap,
+        max_fee_per_blob_gas: tx.max_fee_per_blob_gas,
+        blob_versioned_hashes: tx.blob_versioned_hashes.clone(),
+        access_list: tx.access_list.clone(),
+        access_list_rw_counter: tx.access_list_rw_counter,
+        access_list_is_warm_prev: tx.access_list_is_warm_prev.clone(),
+        authorization_list: tx.authorization_list.clone(),
+        applied_authorizations: tx.applied_authorizations.clone(),
         calls: tx
             .calls()
             .iter()